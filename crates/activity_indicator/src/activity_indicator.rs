@@ -218,6 +218,43 @@ impl ActivityIndicator {
             };
         }
 
+        // Show the progress of any large files that are still being read.
+        let project = self.project.read(cx);
+        let mut large_files = project.large_file_load_progress();
+        if let Some(progress) = large_files.next() {
+            let file_name = progress
+                .path
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut message = format!(
+                "Loading {} ({}%)",
+                file_name,
+                progress.read_len * 100 / progress.len.max(1)
+            );
+            let additional_file_count = large_files.count();
+            if additional_file_count > 0 {
+                write!(&mut message, " + {} more", additional_file_count).unwrap();
+            }
+            message.push_str("...");
+
+            return Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            };
+        }
+
         // Show any language server installation info.
         let mut downloading = SmallVec::<[_; 3]>::new();
         let mut checking_for_update = SmallVec::<[_; 3]>::new();
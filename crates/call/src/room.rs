@@ -535,6 +535,7 @@ impl Room {
                                 }
                             })
                             .collect(),
+                        replica_id: None,
                     });
                 }
                 return true;
@@ -657,6 +658,21 @@ impl Room {
             .map_or(&[], |v| v.as_slice())
     }
 
+    /// Returns the leader and the follower of every follow within the given project.
+    pub fn follows_in_project(
+        &self,
+        project_id: u64,
+    ) -> impl Iterator<Item = (PeerId, PeerId)> + '_ {
+        self.follows_by_leader_id_project_id
+            .iter()
+            .filter(move |((_, follow_project_id), _)| *follow_project_id == project_id)
+            .flat_map(|((leader_id, _), follower_ids)| {
+                follower_ids
+                    .iter()
+                    .map(move |follower_id| (*leader_id, *follower_id))
+            })
+    }
+
     /// Returns the most 'active' projects, defined as most people in the project
     pub fn most_active_project(&self, cx: &AppContext) -> Option<(u64, u64)> {
         let mut project_hosts_and_guest_counts = HashMap::<u64, (Option<u64>, u32)>::default();
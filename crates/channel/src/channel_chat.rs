@@ -52,6 +52,21 @@ pub struct ChannelMessage {
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
     pub edited_at: Option<OffsetDateTime>,
+    pub reactions: Vec<ChannelMessageReaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageReaction {
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
+}
+
+/// A page of the messages that match a search, most recent first.
+#[derive(Clone, Debug)]
+pub struct MessageSearchResults {
+    pub messages: Vec<ChannelMessage>,
+    /// Whether older messages match the search too.
+    pub has_more: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -99,6 +114,7 @@ pub fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelChat::handle_message_sent);
     client.add_model_message_handler(ChannelChat::handle_message_removed);
     client.add_model_message_handler(ChannelChat::handle_message_updated);
+    client.add_model_message_handler(ChannelChat::handle_message_reactions_updated);
 }
 
 impl ChannelChat {
@@ -196,6 +212,7 @@ impl ChannelChat {
                     nonce,
                     reply_to_message_id: message.reply_to_message_id,
                     edited_at: None,
+                    reactions: Vec::new(),
                 },
                 &(),
             ),
@@ -273,6 +290,121 @@ impl ChannelChat {
         }))
     }
 
+    /// Adds the current user's reaction to a message, or removes it if they already reacted
+    /// with the same emoji. The message is updated once the server broadcasts its reactions.
+    pub fn toggle_reaction(
+        &mut self,
+        id: u64,
+        emoji: &str,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<Task<Result<()>>> {
+        let current_user_id = self
+            .user_store
+            .read(cx)
+            .current_user()
+            .ok_or_else(|| anyhow!("current_user is not present"))?
+            .id;
+        let message = self
+            .find_loaded_message(id)
+            .ok_or_else(|| anyhow!("no such message"))?;
+        let has_reacted = message.reactions.iter().any(|reaction| {
+            reaction.emoji == emoji && reaction.user_ids.contains(&current_user_id)
+        });
+
+        let channel_id = self.channel_id.0;
+        let emoji = emoji.to_string();
+        let rpc = self.rpc.clone();
+        Ok(cx.spawn(move |_, _| async move {
+            if has_reacted {
+                rpc.request(proto::RemoveChannelMessageReaction {
+                    channel_id,
+                    message_id: id,
+                    emoji,
+                })
+                .await?;
+            } else {
+                rpc.request(proto::AddChannelMessageReaction {
+                    channel_id,
+                    message_id: id,
+                    emoji,
+                })
+                .await?;
+            }
+            Ok(())
+        }))
+    }
+
+    /// Searches the channel's entire history for messages matching the query, returning the
+    /// most recent matches first. Pass the id of the oldest match found so far to load the
+    /// matches that precede it.
+    pub fn search_messages(
+        &self,
+        query: String,
+        before_message_id: Option<u64>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<MessageSearchResults>> {
+        let request = self.rpc.request(proto::SearchChannelMessages {
+            channel_id: self.channel_id.0,
+            query,
+            before_message_id,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(move |_, mut cx| async move {
+            let response = request.await?;
+            let mut messages =
+                ChannelMessage::from_proto_vec(response.messages, &user_store, &mut cx).await?;
+            messages.reverse();
+            Ok(MessageSearchResults {
+                messages,
+                has_more: !response.done,
+            })
+        })
+    }
+
+    /// Loads every message in the thread started by the given message, so that the thread can
+    /// be shown even if parts of it are older than the loaded history.
+    pub fn load_thread(&mut self, root_id: u64, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let request = self.rpc.request(proto::GetChannelMessageThread {
+            channel_id: self.channel_id.0,
+            root_message_id: root_id,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let messages =
+                ChannelMessage::from_proto_vec(response.messages, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                // The thread's messages aren't contiguous, so they're inserted one by one to
+                // avoid replacing the loaded messages in between them.
+                for message in messages {
+                    this.insert_messages(SumTree::from_item(message, &()), cx);
+                }
+            })
+        })
+    }
+
+    /// Returns the loaded messages in the thread started by the given message: the message
+    /// itself, followed by every message that replies to it directly or through other replies.
+    pub fn thread_messages(&self, root_id: u64) -> Vec<&ChannelMessage> {
+        let mut message_ids = HashSet::default();
+        let mut messages = Vec::new();
+        for message in self.messages.iter() {
+            let is_in_thread = match message.id {
+                ChannelMessageId::Saved(id) if id == root_id => true,
+                _ => message
+                    .reply_to_message_id
+                    .map_or(false, |id| message_ids.contains(&id)),
+            };
+            if is_in_thread {
+                if let ChannelMessageId::Saved(id) = message.id {
+                    message_ids.insert(id);
+                }
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
     pub fn load_more_messages(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<Option<()>>> {
         if self.loaded_all_messages {
             return None;
@@ -585,6 +717,21 @@ impl ChannelChat {
         Ok(())
     }
 
+    async fn handle_message_reactions_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ChannelMessageReactionsUpdated>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            this.message_reactions_updated(
+                ChannelMessageId::Saved(message.payload.message_id),
+                reactions_from_proto(message.payload.reactions),
+                cx,
+            )
+        })?;
+        Ok(())
+    }
+
     fn insert_messages(&mut self, messages: SumTree<ChannelMessage>, cx: &mut ModelContext<Self>) {
         if let Some((first_message, last_message)) = messages.first().zip(messages.last()) {
             let nonces = messages
@@ -707,6 +854,38 @@ impl ChannelChat {
 
         cx.notify();
     }
+
+    fn message_reactions_updated(
+        &mut self,
+        id: ChannelMessageId,
+        reactions: Vec<ChannelMessageReaction>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut cursor = self.messages.cursor::<ChannelMessageId>();
+        let mut messages = cursor.slice(&id, Bias::Left, &());
+        let ix = messages.summary().count;
+
+        match cursor.item() {
+            Some(message) if message.id == id => {
+                let mut message = message.clone();
+                message.reactions = reactions;
+                messages.push(message, &());
+                cursor.next(&());
+            }
+            _ => return,
+        }
+
+        messages.append(cursor.suffix(&()), &());
+        drop(cursor);
+        self.messages = messages;
+
+        cx.emit(ChannelChatEvent::UpdateMessage {
+            message_ix: ix,
+            message_id: id,
+        });
+
+        cx.notify();
+    }
 }
 
 async fn messages_from_proto(
@@ -759,6 +938,7 @@ impl ChannelMessage {
                 .into(),
             reply_to_message_id: message.reply_to_message_id,
             edited_at,
+            reactions: reactions_from_proto(message.reactions),
         })
     }
 
@@ -804,6 +984,18 @@ pub fn mentions_to_proto(mentions: &[(Range<usize>, UserId)]) -> Vec<proto::Chat
         .collect()
 }
 
+fn reactions_from_proto(
+    reactions: Vec<proto::ChannelMessageReaction>,
+) -> Vec<ChannelMessageReaction> {
+    reactions
+        .into_iter()
+        .map(|reaction| ChannelMessageReaction {
+            emoji: reaction.emoji,
+            user_ids: reaction.user_ids,
+        })
+        .collect()
+}
+
 impl sum_tree::Item for ChannelMessage {
     type Summary = ChannelMessageSummary;
 
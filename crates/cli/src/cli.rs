@@ -14,6 +14,7 @@ pub enum CliRequest {
         wait: bool,
         open_new_workspace: Option<bool>,
         dev_server_token: Option<String>,
+        session: Option<String>,
     },
 }
 
@@ -33,6 +33,9 @@ struct Args {
     /// Create a new workspace
     #[arg(short, long, overrides_with = "add")]
     new: bool,
+    /// Restore the session saved under this name in the opened workspace
+    #[arg(long)]
+    session: Option<String>,
     /// A sequence of space-separated paths that you want to open.
     ///
     /// Use `path:line:row` syntax to open a file at a specific location.
@@ -137,6 +140,7 @@ fn main() -> Result<()> {
                 wait: args.wait,
                 open_new_workspace,
                 dev_server_token: args.dev_server_token,
+                session: args.session,
             })?;
 
             while let Ok(response) = rx.recv() {
@@ -93,11 +93,24 @@ actions!(client, [SignIn, SignOut, Reconnect]);
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ClientSettingsContent {
     server_url: Option<String>,
+    collaboration_server_url: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct ClientSettings {
     pub server_url: String,
+    /// A self-hosted collab server to sign in to and collaborate through, instead of the one
+    /// that `server_url` points to.
+    pub collaboration_server_url: Option<String>,
+}
+
+impl ClientSettings {
+    /// The URL of the server that issues the user's credentials.
+    pub fn credentials_url(&self) -> &str {
+        self.collaboration_server_url
+            .as_deref()
+            .unwrap_or(&self.server_url)
+    }
 }
 
 impl Settings for ClientSettings {
@@ -188,6 +201,9 @@ struct GlobalClient(Arc<Client>);
 
 impl Global for GlobalClient {}
 
+type ConnectTunnel =
+    Arc<dyn 'static + Send + Sync + Fn(&AsyncAppContext) -> Task<Result<Connection>>>;
+
 pub struct Client {
     id: AtomicU64,
     peer: Arc<Peer>,
@@ -195,6 +211,7 @@ pub struct Client {
     telemetry: Arc<Telemetry>,
     credentials_provider: Arc<dyn CredentialsProvider + Send + Sync + 'static>,
     state: RwLock<ClientState>,
+    tunnel: RwLock<Option<ConnectTunnel>>,
 
     #[allow(clippy::type_complexity)]
     #[cfg(any(test, feature = "test-support"))]
@@ -522,6 +539,7 @@ impl Client {
             http,
             credentials_provider,
             state: Default::default(),
+            tunnel: Default::default(),
 
             #[cfg(any(test, feature = "test-support"))]
             authenticate: Default::default(),
@@ -584,6 +602,23 @@ impl Client {
         self
     }
 
+    /// Connects this client through the given tunnel instead of the collaboration server.
+    ///
+    /// Tunneled connections skip authentication entirely: the other end of the tunnel is
+    /// expected to greet the client with a `Hello` message, just like the server does, and
+    /// the tunnel is opened again whenever the connection is lost.
+    pub fn set_tunnel<F>(&self, connect: F) -> &Self
+    where
+        F: 'static + Send + Sync + Fn(&AsyncAppContext) -> Task<Result<Connection>>,
+    {
+        *self.tunnel.write() = Some(Arc::new(connect));
+        self
+    }
+
+    pub fn is_tunneled(&self) -> bool {
+        self.tunnel.read().is_some()
+    }
+
     pub fn global(cx: &AppContext) -> Arc<Self> {
         cx.global::<GlobalClient>().0.clone()
     }
@@ -875,6 +910,13 @@ impl Client {
             }
             Status::UpgradeRequired => return Err(EstablishConnectionError::UpgradeRequired)?,
         };
+        let tunnel = self.tunnel.read().clone();
+        if let Some(tunnel) = tunnel {
+            return self
+                .connect_through_tunnel(tunnel, was_disconnected, cx)
+                .await;
+        }
+
         if was_disconnected {
             self.set_status(Status::Authenticating, cx);
         } else {
@@ -966,6 +1008,45 @@ impl Client {
         }
     }
 
+    async fn connect_through_tunnel(
+        self: &Arc<Self>,
+        tunnel: ConnectTunnel,
+        was_disconnected: bool,
+        cx: &AsyncAppContext,
+    ) -> Result<()> {
+        if was_disconnected {
+            self.set_status(Status::Connecting, cx);
+        } else {
+            self.set_status(Status::Reconnecting, cx);
+        }
+
+        let mut timeout =
+            futures::FutureExt::fuse(cx.background_executor().timer(CONNECTION_TIMEOUT));
+        futures::select_biased! {
+            connection = tunnel(cx).fuse() => {
+                match connection {
+                    Ok(conn) => {
+                        futures::select_biased! {
+                            result = self.set_connection(conn, cx).fuse() => result,
+                            _ = timeout => {
+                                self.set_status(Status::ConnectionError, cx);
+                                Err(anyhow!("timed out waiting on hello message from tunnel"))
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        self.set_status(Status::ConnectionError, cx);
+                        Err(error)
+                    }
+                }
+            }
+            _ = &mut timeout => {
+                self.set_status(Status::ConnectionError, cx);
+                Err(anyhow!("timed out trying to open tunnel"))
+            }
+        }
+    }
+
     async fn set_connection(
         self: &Arc<Self>,
         conn: Connection,
@@ -1088,12 +1169,20 @@ impl Client {
 
     async fn get_rpc_url(
         http: Arc<HttpClientWithUrl>,
+        collaboration_server_url: Option<String>,
         release_channel: Option<ReleaseChannel>,
     ) -> Result<Url> {
         if let Some(url) = &*ZED_RPC_URL {
             return Url::parse(url).context("invalid rpc url");
         }
 
+        // A self-hosted server accepts connections itself, rather than redirecting to a
+        // collab server.
+        if let Some(url) = collaboration_server_url {
+            return Url::parse(&format!("{}/rpc", url.trim_end_matches('/')))
+                .context("invalid rpc url");
+        }
+
         let mut url = http.build_url("/rpc");
         if let Some(preview_param) =
             release_channel.and_then(|channel| channel.release_query_param())
@@ -1133,6 +1222,14 @@ impl Client {
             .update(|cx| AppVersion::global(cx).to_string())
             .ok()
             .unwrap_or_default();
+        let collaboration_server_url = cx
+            .update(|cx| {
+                ClientSettings::get_global(cx)
+                    .collaboration_server_url
+                    .clone()
+            })
+            .ok()
+            .flatten();
 
         let request = Request::builder()
             .header("Authorization", credentials.authorization_header())
@@ -1145,7 +1242,8 @@ impl Client {
 
         let http = self.http.clone();
         cx.background_executor().spawn(async move {
-            let mut rpc_url = Self::get_rpc_url(http, release_channel).await?;
+            let mut rpc_url =
+                Self::get_rpc_url(http, collaboration_server_url, release_channel).await?;
             let rpc_host = rpc_url
                 .host_str()
                 .zip(rpc_url.port_or_known_default())
@@ -1188,6 +1286,14 @@ impl Client {
         let http = self.http.clone();
         cx.spawn(|cx| async move {
             let background = cx.background_executor().clone();
+            let signin_base_url = cx
+                .update(|cx| {
+                    ClientSettings::get_global(cx)
+                        .collaboration_server_url
+                        .clone()
+                })?
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| http.base_url());
 
             let (open_url_tx, open_url_rx) = oneshot::channel::<String>();
             cx.update(|cx| {
@@ -1226,10 +1332,10 @@ impl Client {
 
                     // Open the Zed sign-in page in the user's browser, with query parameters that indicate
                     // that the user is signing in from a Zed app running on the same device.
-                    let mut url = http.build_url(&format!(
-                        "/native_app_signin?native_app_port={}&native_app_public_key={}",
-                        port, public_key_string
-                    ));
+                    let mut url = format!(
+                        "{}/native_app_signin?native_app_port={}&native_app_public_key={}",
+                        signin_base_url, port, public_key_string
+                    );
 
                     if let Some(impersonate_login) = IMPERSONATE_LOGIN.as_ref() {
                         log::info!("impersonating user @{}", impersonate_login);
@@ -1261,7 +1367,7 @@ impl Client {
                                     }
 
                                     let post_auth_url =
-                                        http.build_url("/native_app_signin_succeeded");
+                                        format!("{}/native_app_signin_succeeded", signin_base_url);
                                     req.respond(
                                         tiny_http::Response::empty(302).with_header(
                                             tiny_http::Header::from_bytes(
@@ -1319,7 +1425,7 @@ impl Client {
 
         // Use the collab server's admin API to retrieve the id
         // of the impersonated user.
-        let mut url = Self::get_rpc_url(http.clone(), None).await?;
+        let mut url = Self::get_rpc_url(http.clone(), None, None).await?;
         url.set_path("/user");
         url.set_query(Some(&format!("github_login={login}")));
         let request = Request::get(url.as_str())
@@ -1659,7 +1765,7 @@ impl CredentialsProvider for KeychainCredentialsProvider {
             }
 
             let (user_id, access_token) = cx
-                .update(|cx| cx.read_credentials(&ClientSettings::get_global(cx).server_url))
+                .update(|cx| cx.read_credentials(ClientSettings::get_global(cx).credentials_url()))
                 .log_err()?
                 .await
                 .log_err()??;
@@ -1681,7 +1787,7 @@ impl CredentialsProvider for KeychainCredentialsProvider {
         async move {
             cx.update(move |cx| {
                 cx.write_credentials(
-                    &ClientSettings::get_global(cx).server_url,
+                    ClientSettings::get_global(cx).credentials_url(),
                     &user_id.to_string(),
                     access_token.as_bytes(),
                 )
@@ -1696,8 +1802,10 @@ impl CredentialsProvider for KeychainCredentialsProvider {
         cx: &'a AsyncAppContext,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
         async move {
-            cx.update(move |cx| cx.delete_credentials(&ClientSettings::get_global(cx).server_url))?
-                .await
+            cx.update(move |cx| {
+                cx.delete_credentials(ClientSettings::get_global(cx).credentials_url())
+            })?
+            .await
         }
         .boxed_local()
     }
@@ -1857,6 +1965,60 @@ mod tests {
         ));
     }
 
+    #[gpui::test(iterations = 10)]
+    async fn test_tunneled_connection(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        client.override_authenticate(|_| panic!("tunneled clients should not authenticate"));
+
+        let peer = Peer::new(0);
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        client.set_tunnel({
+            let peer = peer.clone();
+            let connections = connections.clone();
+            move |cx| {
+                let (client_conn, server_conn, _) =
+                    Connection::in_memory(cx.background_executor().clone());
+                let (connection_id, io, incoming) =
+                    peer.add_test_connection(server_conn, cx.background_executor().clone());
+                cx.background_executor().spawn(io).detach();
+                peer.send(
+                    connection_id,
+                    proto::Hello {
+                        peer_id: Some(connection_id.into()),
+                    },
+                )
+                .unwrap();
+                connections.lock().push((connection_id, incoming));
+                Task::ready(Ok(client_conn))
+            }
+        });
+
+        let mut status = client.status();
+        client
+            .authenticate_and_connect(false, &cx.to_async())
+            .await
+            .unwrap();
+        assert!(matches!(
+            status.next().await,
+            Some(Status::Connected { .. })
+        ));
+        assert!(client.user_id().is_none());
+
+        // Losing the connection opens the tunnel again.
+        let connection_id = connections.lock()[0].0;
+        peer.disconnect(connection_id);
+        cx.executor().advance_clock(Duration::from_secs(10));
+        while !matches!(status.next().await, Some(Status::Connected { .. })) {}
+        assert_eq!(connections.lock().len(), 2);
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_authenticating_more_than_once(
         cx: &mut TestAppContext,
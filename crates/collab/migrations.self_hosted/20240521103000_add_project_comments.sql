@@ -0,0 +1,23 @@
+-- Comment threads are ephemeral: their ranges are anchors into the histories of the project's
+-- open buffers, so they're deleted along with the project when it's unshared.
+CREATE TABLE "project_comment_threads" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    "worktree_id" INTEGER NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "start_anchor" BLOB NOT NULL,
+    "end_anchor" BLOB NOT NULL,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "resolved_by_id" INTEGER REFERENCES users (id) ON DELETE SET NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX "index_project_comment_threads_on_project_id" ON "project_comment_threads" ("project_id");
+
+CREATE TABLE "project_comments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "thread_id" INTEGER NOT NULL REFERENCES project_comment_threads (id) ON DELETE CASCADE,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX "index_project_comments_on_thread_id" ON "project_comments" ("thread_id");
@@ -0,0 +1,16 @@
+-- SQLite can't change a table's primary key, so the table is rebuilt with the new one.
+CREATE TABLE "worktree_settings_files_with_kind" (
+    "project_id" INTEGER NOT NULL,
+    "worktree_id" INTEGER NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "kind" VARCHAR NOT NULL DEFAULT 'settings',
+    "content" TEXT,
+    PRIMARY KEY(project_id, worktree_id, path, kind),
+    FOREIGN KEY(project_id, worktree_id) REFERENCES worktrees (project_id, id) ON DELETE CASCADE
+);
+INSERT INTO "worktree_settings_files_with_kind" ("project_id", "worktree_id", "path", "content")
+SELECT "project_id", "worktree_id", "path", "content" FROM "worktree_settings_files";
+DROP TABLE "worktree_settings_files";
+ALTER TABLE "worktree_settings_files_with_kind" RENAME TO "worktree_settings_files";
+CREATE INDEX "index_worktree_settings_files_on_project_id" ON "worktree_settings_files" ("project_id");
+CREATE INDEX "index_worktree_settings_files_on_project_id_and_worktree_id" ON "worktree_settings_files" ("project_id", "worktree_id");
@@ -108,8 +108,9 @@ CREATE TABLE "worktree_settings_files" (
     "project_id" INTEGER NOT NULL,
     "worktree_id" INTEGER NOT NULL,
     "path" VARCHAR NOT NULL,
+    "kind" VARCHAR NOT NULL DEFAULT 'settings',
     "content" TEXT,
-    PRIMARY KEY(project_id, worktree_id, path),
+    PRIMARY KEY(project_id, worktree_id, path, kind),
     FOREIGN KEY(project_id, worktree_id) REFERENCES worktrees (project_id, id) ON DELETE CASCADE
 );
 CREATE INDEX "index_worktree_settings_files_on_project_id" ON "worktree_settings_files" ("project_id");
@@ -151,6 +152,28 @@ CREATE INDEX "index_project_collaborators_on_connection_server_id" ON "project_c
 CREATE INDEX "index_project_collaborators_on_connection_id" ON "project_collaborators" ("connection_id");
 CREATE UNIQUE INDEX "index_project_collaborators_on_project_id_connection_id_and_server_id" ON "project_collaborators" ("project_id", "connection_id", "connection_server_id");
 
+CREATE TABLE "project_comment_threads" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    "worktree_id" INTEGER NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "start_anchor" BLOB NOT NULL,
+    "end_anchor" BLOB NOT NULL,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "resolved_by_id" INTEGER REFERENCES users (id) ON DELETE SET NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX "index_project_comment_threads_on_project_id" ON "project_comment_threads" ("project_id");
+
+CREATE TABLE "project_comments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "thread_id" INTEGER NOT NULL REFERENCES project_comment_threads (id) ON DELETE CASCADE,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX "index_project_comments_on_thread_id" ON "project_comments" ("thread_id");
+
 CREATE TABLE "room_participants" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "room_id" INTEGER NOT NULL REFERENCES rooms (id),
@@ -226,6 +249,7 @@ CREATE TABLE IF NOT EXISTS "channel_messages" (
 );
 CREATE INDEX "index_channel_messages_on_channel_id" ON "channel_messages" ("channel_id");
 CREATE UNIQUE INDEX "index_channel_messages_on_sender_id_nonce" ON "channel_messages" ("sender_id", "nonce");
+CREATE INDEX "index_channel_messages_on_reply_to_message_id" ON "channel_messages" ("reply_to_message_id");
 
 CREATE TABLE "channel_message_mentions" (
     "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
@@ -235,6 +259,14 @@ CREATE TABLE "channel_message_mentions" (
     PRIMARY KEY(message_id, start_offset)
 );
 
+CREATE TABLE "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(message_id, user_id, emoji)
+);
+
 CREATE TABLE "channel_members" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
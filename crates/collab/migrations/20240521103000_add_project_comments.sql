@@ -0,0 +1,23 @@
+-- Comment threads are ephemeral: their ranges are anchors into the histories of the project's
+-- open buffers, so they're deleted along with the project when it's unshared.
+CREATE TABLE "project_comment_threads" (
+    "id" SERIAL PRIMARY KEY,
+    "project_id" INTEGER NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    "worktree_id" INT8 NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "start_anchor" BYTEA NOT NULL,
+    "end_anchor" BYTEA NOT NULL,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "resolved_by_id" INTEGER REFERENCES users (id) ON DELETE SET NULL,
+    "created_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX "index_project_comment_threads_on_project_id" ON "project_comment_threads" ("project_id");
+
+CREATE TABLE "project_comments" (
+    "id" SERIAL PRIMARY KEY,
+    "thread_id" INTEGER NOT NULL REFERENCES project_comment_threads (id) ON DELETE CASCADE,
+    "author_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "created_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX "index_project_comments_on_thread_id" ON "project_comments" ("thread_id");
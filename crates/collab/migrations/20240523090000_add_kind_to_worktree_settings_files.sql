@@ -0,0 +1,3 @@
+ALTER TABLE "worktree_settings_files" ADD COLUMN "kind" VARCHAR NOT NULL DEFAULT 'settings';
+ALTER TABLE "worktree_settings_files" DROP CONSTRAINT "worktree_settings_files_pkey";
+ALTER TABLE "worktree_settings_files" ADD PRIMARY KEY ("project_id", "worktree_id", "path", "kind");
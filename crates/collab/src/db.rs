@@ -100,7 +100,25 @@ impl Database {
         let migrations = MigrationSource::resolve(migrations_path)
             .await
             .map_err(|err| anyhow!("failed to load migrations: {err:?}"))?;
+        self.apply_migrations(migrations, ignore_checksum_mismatch)
+            .await
+    }
+
+    /// Runs the SQLite migrations embedded in the binary, so that a self-hosted server
+    /// can be deployed without the source tree. Unlike the schema in `migrations.sqlite`,
+    /// which tests start from, these are only ever appended to.
+    #[cfg(any(test, feature = "sqlite"))]
+    pub async fn migrate_embedded_sqlite(&self) -> anyhow::Result<Vec<(Migration, Duration)>> {
+        let migrator = sqlx::migrate!("./migrations.self_hosted");
+        self.apply_migrations(migrator.migrations.to_vec(), false)
+            .await
+    }
 
+    async fn apply_migrations(
+        &self,
+        migrations: Vec<Migration>,
+        ignore_checksum_mismatch: bool,
+    ) -> anyhow::Result<Vec<(Migration, Duration)>> {
         let mut connection = sqlx::AnyConnection::connect(self.options.get_url()).await?;
 
         connection.ensure_migrations_table().await?;
@@ -513,6 +531,18 @@ pub struct CreatedChannelMessage {
     pub notifications: NotificationBatch,
 }
 
+pub struct UpdatedChannelMessageReactions {
+    pub participant_connection_ids: Vec<ConnectionId>,
+    pub reactions: Vec<proto::ChannelMessageReaction>,
+}
+
+pub struct UpdatedProjectCommentThread {
+    pub thread: proto::ProjectCommentThread,
+    /// The connections of the other collaborators in the project.
+    pub connection_ids: Vec<ConnectionId>,
+    pub notifications: NotificationBatch,
+}
+
 pub struct UpdatedChannelMessage {
     pub message_id: MessageId,
     pub participant_connection_ids: Vec<ConnectionId>,
@@ -805,6 +835,7 @@ pub struct Worktree {
 #[derive(Debug)]
 pub struct WorktreeSettingsFile {
     pub path: String,
+    pub kind: LocalSettingsKind,
     pub content: String,
 }
 
@@ -83,6 +83,8 @@ id_type!(MessageId);
 id_type!(NotificationId);
 id_type!(NotificationKindId);
 id_type!(ProjectCollaboratorId);
+id_type!(ProjectCommentId);
+id_type!(ProjectCommentThreadId);
 id_type!(ProjectId);
 id_type!(DevServerProjectId);
 id_type!(ReplicaId);
@@ -272,6 +274,35 @@ impl Into<i32> for ChannelVisibility {
     }
 }
 
+/// The kind of a settings file in a project's worktree.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, EnumIter, DeriveActiveEnum, Default, Hash)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+pub enum LocalSettingsKind {
+    /// A `.zed/settings.json` file.
+    #[sea_orm(string_value = "settings")]
+    #[default]
+    Settings,
+    /// An `.editorconfig` file.
+    #[sea_orm(string_value = "editorconfig")]
+    Editorconfig,
+}
+
+impl LocalSettingsKind {
+    pub fn from_proto(proto_kind: proto::LocalSettingsKind) -> Self {
+        match proto_kind {
+            proto::LocalSettingsKind::Settings => Self::Settings,
+            proto::LocalSettingsKind::Editorconfig => Self::Editorconfig,
+        }
+    }
+
+    pub fn to_proto(&self) -> proto::LocalSettingsKind {
+        match self {
+            Self::Settings => proto::LocalSettingsKind::Settings,
+            Self::Editorconfig => proto::LocalSettingsKind::Editorconfig,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, PartialEq)]
 pub enum PrincipalId {
     UserId(UserId),
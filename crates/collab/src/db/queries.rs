@@ -12,6 +12,7 @@ pub mod extensions;
 pub mod hosted_projects;
 pub mod messages;
 pub mod notifications;
+pub mod project_comments;
 pub mod projects;
 pub mod rate_buckets;
 pub mod rooms;
@@ -73,4 +73,21 @@ impl Database {
         })
         .await
     }
+
+    /// Deletes every access token that lets someone sign in as the given user, including
+    /// the ones used to impersonate them. Returns the number of deleted tokens.
+    pub async fn delete_access_tokens_for_user(&self, user_id: UserId) -> Result<u64> {
+        self.transaction(|tx| async move {
+            let result = access_token::Entity::delete_many()
+                .filter(
+                    Condition::any()
+                        .add(access_token::Column::UserId.eq(user_id))
+                        .add(access_token::Column::ImpersonatedUserId.eq(user_id)),
+                )
+                .exec(&*tx)
+                .await?;
+            Ok(result.rows_affected)
+        })
+        .await
+    }
 }
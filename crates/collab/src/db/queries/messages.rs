@@ -1,6 +1,6 @@
 use super::*;
 use rpc::Notification;
-use sea_orm::{SelectColumns, TryInsertResult};
+use sea_orm::{DbBackend, SelectColumns, TryInsertResult};
 use time::OffsetDateTime;
 use util::ResultExt;
 
@@ -166,6 +166,7 @@ impl Database {
                     edited_at: row
                         .edited_at
                         .map(|t| t.assume_utc().unix_timestamp() as u64),
+                    reactions: vec![],
                 }
             })
             .collect::<Vec<_>>();
@@ -199,10 +200,259 @@ impl Database {
                 }
             }
         }
+        drop(mentions);
+
+        let mut reactions = self
+            .load_channel_message_reactions(messages.iter().map(|m| m.id), tx)
+            .await?;
+        for message in &mut messages {
+            message.reactions = reactions.remove(&message.id).unwrap_or_default();
+        }
 
         Ok(messages)
     }
 
+    /// Returns the reactions to the given messages, grouped by emoji in the order in which each
+    /// emoji was first used.
+    async fn load_channel_message_reactions(
+        &self,
+        message_ids: impl IntoIterator<Item = u64>,
+        tx: &DatabaseTransaction,
+    ) -> Result<HashMap<u64, Vec<proto::ChannelMessageReaction>>> {
+        let mut rows = channel_message_reaction::Entity::find()
+            .filter(channel_message_reaction::Column::MessageId.is_in(message_ids))
+            .order_by_asc(channel_message_reaction::Column::MessageId)
+            .order_by_asc(channel_message_reaction::Column::CreatedAt)
+            .order_by_asc(channel_message_reaction::Column::UserId)
+            .stream(tx)
+            .await?;
+
+        let mut reactions_by_message_id =
+            HashMap::<u64, Vec<proto::ChannelMessageReaction>>::default();
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            let reactions = reactions_by_message_id
+                .entry(row.message_id.to_proto())
+                .or_default();
+            let user_id = row.user_id.to_proto();
+            if let Some(reaction) = reactions
+                .iter_mut()
+                .find(|reaction| reaction.emoji == row.emoji)
+            {
+                reaction.user_ids.push(user_id);
+            } else {
+                reactions.push(proto::ChannelMessageReaction {
+                    emoji: row.emoji,
+                    user_ids: vec![user_id],
+                });
+            }
+        }
+
+        Ok(reactions_by_message_id)
+    }
+
+    /// Returns the root message with the given ID and every message that replies to it, directly
+    /// or through other replies.
+    pub async fn get_channel_message_thread(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        root_message_id: MessageId,
+    ) -> Result<Vec<proto::ChannelMessage>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            let root = channel_message::Entity::find_by_id(root_message_id)
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such message"))?;
+
+            let mut parent_ids = vec![root.id];
+            let mut rows = vec![root];
+            while !parent_ids.is_empty() {
+                let replies = channel_message::Entity::find()
+                    .filter(channel_message::Column::ChannelId.eq(channel_id))
+                    .filter(channel_message::Column::ReplyToMessageId.is_in(parent_ids))
+                    .all(&*tx)
+                    .await?;
+                parent_ids = replies.iter().map(|reply| reply.id).collect();
+                rows.extend(replies);
+            }
+            rows.sort_unstable_by_key(|row| std::cmp::Reverse(row.id));
+
+            self.load_channel_messages(rows, &tx).await
+        })
+        .await
+    }
+
+    /// Returns the messages in the channel that match the given query, most recent first.
+    ///
+    /// Use `before_message_id` to paginate through the results.
+    pub async fn search_channel_messages(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        query: &str,
+        count: usize,
+        before_message_id: Option<MessageId>,
+    ) -> Result<Vec<proto::ChannelMessage>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            if query.trim().is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let mut condition =
+                Condition::all().add(channel_message::Column::ChannelId.eq(channel_id));
+            if let Some(before_message_id) = before_message_id {
+                condition = condition.add(channel_message::Column::Id.lt(before_message_id));
+            }
+
+            if cfg!(any(test, feature = "sqlite"))
+                && self.pool.get_database_backend() == DbBackend::Sqlite
+            {
+                // SQLite has no built-in full-text search on ordinary tables, so every word of
+                // the query has to appear somewhere in the message instead.
+                for word in query.split_whitespace() {
+                    let word = word
+                        .to_lowercase()
+                        .replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_");
+                    condition = condition.add(Expr::cust_with_values(
+                        "LOWER(body) LIKE ? ESCAPE '\\'",
+                        [format!("%{word}%")],
+                    ));
+                }
+            } else {
+                condition = condition.add(Expr::cust_with_values(
+                    "to_tsvector('english', body) @@ plainto_tsquery('english', $1)",
+                    [query],
+                ));
+            }
+
+            let rows = channel_message::Entity::find()
+                .filter(condition)
+                .order_by_desc(channel_message::Column::Id)
+                .limit(count as u64)
+                .all(&*tx)
+                .await?;
+
+            self.load_channel_messages(rows, &tx).await
+        })
+        .await
+    }
+
+    /// Adds a reaction to the channel message with the given ID.
+    pub async fn add_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<UpdatedChannelMessageReactions> {
+        self.transaction(|tx| async move {
+            self.check_user_can_react_to_message(channel_id, message_id, user_id, &tx)
+                .await?;
+
+            channel_message_reaction::Entity::insert(channel_message_reaction::ActiveModel {
+                message_id: ActiveValue::Set(message_id),
+                user_id: ActiveValue::Set(user_id),
+                emoji: ActiveValue::Set(emoji.to_string()),
+                created_at: ActiveValue::NotSet,
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    channel_message_reaction::Column::MessageId,
+                    channel_message_reaction::Column::UserId,
+                    channel_message_reaction::Column::Emoji,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&*tx)
+            .await?;
+
+            self.channel_message_reactions_updated(channel_id, message_id, &tx)
+                .await
+        })
+        .await
+    }
+
+    /// Removes a reaction from the channel message with the given ID.
+    pub async fn remove_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<UpdatedChannelMessageReactions> {
+        self.transaction(|tx| async move {
+            self.check_user_can_react_to_message(channel_id, message_id, user_id, &tx)
+                .await?;
+
+            channel_message_reaction::Entity::delete_many()
+                .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                .filter(channel_message_reaction::Column::UserId.eq(user_id))
+                .filter(channel_message_reaction::Column::Emoji.eq(emoji))
+                .exec(&*tx)
+                .await?;
+
+            self.channel_message_reactions_updated(channel_id, message_id, &tx)
+                .await
+        })
+        .await
+    }
+
+    async fn check_user_can_react_to_message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        let channel = self.get_channel_internal(channel_id, tx).await?;
+        self.check_user_is_channel_participant(&channel, user_id, tx)
+            .await?;
+        channel_message::Entity::find_by_id(message_id)
+            .filter(channel_message::Column::ChannelId.eq(channel_id))
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such message"))?;
+        Ok(())
+    }
+
+    async fn channel_message_reactions_updated(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        tx: &DatabaseTransaction,
+    ) -> Result<UpdatedChannelMessageReactions> {
+        let participant_connection_ids = channel_chat_participant::Entity::find()
+            .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|participant| participant.connection())
+            .collect();
+        let reactions = self
+            .load_channel_message_reactions([message_id.to_proto()], tx)
+            .await?
+            .remove(&message_id.to_proto())
+            .unwrap_or_default();
+
+        Ok(UpdatedChannelMessageReactions {
+            participant_connection_ids,
+            reactions,
+        })
+    }
+
     fn format_mentions_to_entities(
         &self,
         message_id: MessageId,
@@ -0,0 +1,297 @@
+use super::*;
+use prost::Message;
+use rpc::Notification;
+use time::OffsetDateTime;
+
+impl Database {
+    /// Returns the comment threads on the buffers of the given project.
+    pub async fn get_project_comment_threads(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+    ) -> Result<TransactionGuard<Vec<proto::ProjectCommentThread>>> {
+        self.project_transaction(project_id, |tx| async move {
+            self.project_comment_participant(project_id, connection_id, &tx)
+                .await?;
+            let threads = project_comment_thread::Entity::find()
+                .filter(project_comment_thread::Column::ProjectId.eq(project_id))
+                .order_by_asc(project_comment_thread::Column::Id)
+                .all(&*tx)
+                .await?;
+            self.load_project_comment_threads(threads, &tx).await
+        })
+        .await
+    }
+
+    /// Starts a new comment thread on a range of a buffer in the given project.
+    pub async fn create_project_comment_thread(
+        &self,
+        request: &proto::CreateProjectCommentThread,
+        body: &str,
+        connection_id: ConnectionId,
+    ) -> Result<TransactionGuard<UpdatedProjectCommentThread>> {
+        let project_id = ProjectId::from_proto(request.project_id);
+        self.project_transaction(project_id, |tx| async move {
+            let (user_id, connection_ids) = self
+                .project_comment_participant(project_id, connection_id, &tx)
+                .await?;
+            let start = request
+                .start
+                .as_ref()
+                .ok_or_else(|| anyhow!("invalid start anchor"))?;
+            let end = request
+                .end
+                .as_ref()
+                .ok_or_else(|| anyhow!("invalid end anchor"))?;
+            let now = OffsetDateTime::now_utc();
+            let now = PrimitiveDateTime::new(now.date(), now.time());
+
+            let thread = project_comment_thread::ActiveModel {
+                project_id: ActiveValue::Set(project_id),
+                worktree_id: ActiveValue::Set(request.worktree_id as i64),
+                path: ActiveValue::Set(request.path.clone()),
+                start_anchor: ActiveValue::Set(start.encode_to_vec()),
+                end_anchor: ActiveValue::Set(end.encode_to_vec()),
+                author_id: ActiveValue::Set(user_id),
+                resolved_by_id: ActiveValue::Set(None),
+                created_at: ActiveValue::Set(now),
+                ..Default::default()
+            }
+            .insert(&*tx)
+            .await?;
+            project_comment::ActiveModel {
+                thread_id: ActiveValue::Set(thread.id),
+                author_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(body.to_string()),
+                created_at: ActiveValue::Set(now),
+                ..Default::default()
+            }
+            .insert(&*tx)
+            .await?;
+
+            let thread = self
+                .load_project_comment_threads(vec![thread], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such comment thread"))?;
+            Ok(UpdatedProjectCommentThread {
+                thread,
+                connection_ids,
+                notifications: Vec::new(),
+            })
+        })
+        .await
+    }
+
+    /// Adds a comment to an existing thread, notifying everyone else who commented on it.
+    pub async fn reply_to_project_comment_thread(
+        &self,
+        project_id: ProjectId,
+        thread_id: ProjectCommentThreadId,
+        body: &str,
+        connection_id: ConnectionId,
+    ) -> Result<TransactionGuard<UpdatedProjectCommentThread>> {
+        self.project_transaction(project_id, |tx| async move {
+            let (user_id, connection_ids) = self
+                .project_comment_participant(project_id, connection_id, &tx)
+                .await?;
+            let thread = self
+                .get_project_comment_thread(project_id, thread_id, &tx)
+                .await?;
+            let now = OffsetDateTime::now_utc();
+            let comment = project_comment::ActiveModel {
+                thread_id: ActiveValue::Set(thread.id),
+                author_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(body.to_string()),
+                created_at: ActiveValue::Set(PrimitiveDateTime::new(now.date(), now.time())),
+                ..Default::default()
+            }
+            .insert(&*tx)
+            .await?;
+
+            let mut recipient_ids = project_comment::Entity::find()
+                .filter(project_comment::Column::ThreadId.eq(thread.id))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|comment| comment.author_id)
+                .collect::<HashSet<_>>();
+            recipient_ids.insert(thread.author_id);
+            recipient_ids.remove(&user_id);
+
+            let mut notifications = Vec::new();
+            for recipient_id in recipient_ids {
+                notifications.extend(
+                    self.create_notification(
+                        recipient_id,
+                        Notification::ProjectCommentReply {
+                            comment_id: comment.id.to_proto(),
+                            thread_id: thread.id.to_proto(),
+                            project_id: project_id.to_proto(),
+                            sender_id: user_id.to_proto(),
+                            worktree_id: thread.worktree_id as u64,
+                            path: thread.path.clone(),
+                            body: body.to_string(),
+                        },
+                        false,
+                        &tx,
+                    )
+                    .await?,
+                );
+            }
+
+            let thread = self
+                .load_project_comment_threads(vec![thread], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such comment thread"))?;
+            Ok(UpdatedProjectCommentThread {
+                thread,
+                connection_ids,
+                notifications,
+            })
+        })
+        .await
+    }
+
+    /// Marks a comment thread as resolved or reopens it, notifying the thread's author when
+    /// someone else resolves it.
+    pub async fn resolve_project_comment_thread(
+        &self,
+        project_id: ProjectId,
+        thread_id: ProjectCommentThreadId,
+        resolved: bool,
+        connection_id: ConnectionId,
+    ) -> Result<TransactionGuard<UpdatedProjectCommentThread>> {
+        self.project_transaction(project_id, |tx| async move {
+            let (user_id, connection_ids) = self
+                .project_comment_participant(project_id, connection_id, &tx)
+                .await?;
+            let thread = self
+                .get_project_comment_thread(project_id, thread_id, &tx)
+                .await?;
+            let was_resolved = thread.resolved_by_id.is_some();
+            let thread = project_comment_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                resolved_by_id: ActiveValue::Set(resolved.then_some(user_id)),
+                ..Default::default()
+            }
+            .update(&*tx)
+            .await?;
+
+            let mut notifications = Vec::new();
+            if resolved && !was_resolved && thread.author_id != user_id {
+                notifications.extend(
+                    self.create_notification(
+                        thread.author_id,
+                        Notification::ProjectCommentThreadResolved {
+                            thread_id: thread.id.to_proto(),
+                            project_id: project_id.to_proto(),
+                            resolver_id: user_id.to_proto(),
+                            worktree_id: thread.worktree_id as u64,
+                            path: thread.path.clone(),
+                        },
+                        true,
+                        &tx,
+                    )
+                    .await?,
+                );
+            }
+
+            let thread = self
+                .load_project_comment_threads(vec![thread], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such comment thread"))?;
+            Ok(UpdatedProjectCommentThread {
+                thread,
+                connection_ids,
+                notifications,
+            })
+        })
+        .await
+    }
+
+    /// Returns the id of the user with the given connection to the project, along with the
+    /// connections of everyone else in the project.
+    async fn project_comment_participant(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        tx: &DatabaseTransaction,
+    ) -> Result<(UserId, Vec<ConnectionId>)> {
+        let collaborators = project_collaborator::Entity::find()
+            .filter(project_collaborator::Column::ProjectId.eq(project_id))
+            .all(tx)
+            .await?;
+        let user_id = collaborators
+            .iter()
+            .find(|collaborator| collaborator.connection() == connection_id)
+            .map(|collaborator| collaborator.user_id)
+            .ok_or_else(|| anyhow!("no such project"))?;
+        let connection_ids = collaborators
+            .iter()
+            .map(|collaborator| collaborator.connection())
+            .filter(|connection| *connection != connection_id)
+            .collect();
+        Ok((user_id, connection_ids))
+    }
+
+    async fn get_project_comment_thread(
+        &self,
+        project_id: ProjectId,
+        thread_id: ProjectCommentThreadId,
+        tx: &DatabaseTransaction,
+    ) -> Result<project_comment_thread::Model> {
+        let thread = project_comment_thread::Entity::find_by_id(thread_id)
+            .filter(project_comment_thread::Column::ProjectId.eq(project_id))
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such comment thread"))?;
+        Ok(thread)
+    }
+
+    async fn load_project_comment_threads(
+        &self,
+        threads: Vec<project_comment_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ProjectCommentThread>> {
+        let mut comments = project_comment::Entity::find()
+            .filter(project_comment::Column::ThreadId.is_in(threads.iter().map(|thread| thread.id)))
+            .order_by_asc(project_comment::Column::Id)
+            .stream(tx)
+            .await?;
+        let mut comments_by_thread_id = HashMap::<_, Vec<_>>::default();
+        while let Some(comment) = comments.next().await {
+            let comment = comment?;
+            comments_by_thread_id
+                .entry(comment.thread_id)
+                .or_default()
+                .push(proto::ProjectComment {
+                    id: comment.id.to_proto(),
+                    author_id: comment.author_id.to_proto(),
+                    body: comment.body,
+                    timestamp: comment.created_at.assume_utc().unix_timestamp() as u64,
+                });
+        }
+
+        threads
+            .into_iter()
+            .map(|thread| {
+                Ok(proto::ProjectCommentThread {
+                    id: thread.id.to_proto(),
+                    worktree_id: thread.worktree_id as u64,
+                    path: thread.path,
+                    start: Some(decode_anchor(&thread.start_anchor)?),
+                    end: Some(decode_anchor(&thread.end_anchor)?),
+                    resolved_by_id: thread.resolved_by_id.map(|id| id.to_proto()),
+                    comments: comments_by_thread_id.remove(&thread.id).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn decode_anchor(anchor: &[u8]) -> Result<proto::Anchor> {
+    Ok(proto::Anchor::decode(anchor).map_err(|error| anyhow!("{}", error))?)
+}
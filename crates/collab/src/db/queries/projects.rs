@@ -523,6 +523,7 @@ impl Database {
         connection: ConnectionId,
     ) -> Result<TransactionGuard<Vec<ConnectionId>>> {
         let project_id = ProjectId::from_proto(update.project_id);
+        let kind = LocalSettingsKind::from_proto(update.kind());
         self.project_transaction(project_id, |tx| async move {
             // Ensure the update comes from the host.
             let project = project::Entity::find_by_id(project_id)
@@ -538,6 +539,7 @@ impl Database {
                     project_id: ActiveValue::Set(project_id),
                     worktree_id: ActiveValue::Set(update.worktree_id as i64),
                     path: ActiveValue::Set(update.path.clone()),
+                    kind: ActiveValue::Set(kind),
                     content: ActiveValue::Set(content.clone()),
                 })
                 .on_conflict(
@@ -545,6 +547,7 @@ impl Database {
                         worktree_settings_file::Column::ProjectId,
                         worktree_settings_file::Column::WorktreeId,
                         worktree_settings_file::Column::Path,
+                        worktree_settings_file::Column::Kind,
                     ])
                     .update_column(worktree_settings_file::Column::Content)
                     .to_owned(),
@@ -556,6 +559,7 @@ impl Database {
                     project_id: ActiveValue::Set(project_id),
                     worktree_id: ActiveValue::Set(update.worktree_id as i64),
                     path: ActiveValue::Set(update.path.clone()),
+                    kind: ActiveValue::Set(kind),
                     ..Default::default()
                 })
                 .exec(&*tx)
@@ -789,6 +793,7 @@ impl Database {
                 if let Some(worktree) = worktrees.get_mut(&(db_settings_file.worktree_id as u64)) {
                     worktree.settings_files.push(WorktreeSettingsFile {
                         path: db_settings_file.path,
+                        kind: db_settings_file.kind,
                         content: db_settings_file.content,
                     });
                 }
@@ -728,6 +728,7 @@ impl Database {
                 {
                     worktree.settings_files.push(WorktreeSettingsFile {
                         path: db_settings_file.path,
+                        kind: db_settings_file.kind,
                         content: db_settings_file.content,
                     });
                 }
@@ -40,6 +40,22 @@ impl Database {
         .await
     }
 
+    /// Creates a user who signs in with access tokens issued by a self-hosted server,
+    /// rather than through GitHub.
+    pub async fn create_local_user(&self, login: &str, admin: bool) -> Result<User> {
+        self.transaction(|tx| async move {
+            Ok(user::ActiveModel {
+                github_login: ActiveValue::set(login.into()),
+                admin: ActiveValue::set(admin),
+                metrics_id: ActiveValue::set(Uuid::new_v4()),
+                ..Default::default()
+            }
+            .insert(&*tx)
+            .await?)
+        })
+        .await
+    }
+
     /// Returns a user by ID. There are no access checks here, so this should only be used internally.
     pub async fn get_user_by_id(&self, id: UserId) -> Result<Option<user::Model>> {
         self.transaction(|tx| async move { Ok(user::Entity::find_by_id(id).one(&*tx).await?) })
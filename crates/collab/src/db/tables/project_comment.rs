@@ -0,0 +1,32 @@
+use crate::db::{ProjectCommentId, ProjectCommentThreadId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "project_comments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ProjectCommentId,
+    pub thread_id: ProjectCommentThreadId,
+    pub author_id: UserId,
+    pub body: String,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project_comment_thread::Entity",
+        from = "Column::ThreadId",
+        to = "super::project_comment_thread::Column::Id"
+    )]
+    Thread,
+}
+
+impl Related<super::project_comment_thread::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Thread.def()
+    }
+}
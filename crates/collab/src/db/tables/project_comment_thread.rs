@@ -0,0 +1,47 @@
+use crate::db::{ProjectCommentThreadId, ProjectId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+/// A thread of comments on a range of a buffer in a shared project.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "project_comment_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ProjectCommentThreadId,
+    pub project_id: ProjectId,
+    pub worktree_id: i64,
+    pub path: String,
+    /// The start of the commented range, as an encoded `proto::Anchor`.
+    pub start_anchor: Vec<u8>,
+    /// The end of the commented range, as an encoded `proto::Anchor`.
+    pub end_anchor: Vec<u8>,
+    pub author_id: UserId,
+    pub resolved_by_id: Option<UserId>,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+    #[sea_orm(has_many = "super::project_comment::Entity")]
+    Comments,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl Related<super::project_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comments.def()
+    }
+}
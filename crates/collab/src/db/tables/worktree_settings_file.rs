@@ -1,4 +1,4 @@
-use crate::db::ProjectId;
+use crate::db::{LocalSettingsKind, ProjectId};
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -10,6 +10,8 @@ pub struct Model {
     pub worktree_id: i64,
     #[sea_orm(primary_key)]
     pub path: String,
+    #[sea_orm(primary_key)]
+    pub kind: LocalSettingsKind,
     pub content: String,
 }
 
@@ -1,6 +1,7 @@
 use super::*;
 use crate::test_both_dbs;
 use pretty_assertions::{assert_eq, assert_ne};
+use sqlx::Connection as _;
 use std::sync::Arc;
 
 test_both_dbs!(
@@ -277,6 +278,120 @@ async fn test_create_access_tokens(db: &Arc<Database>) {
     );
 }
 
+test_both_dbs!(
+    test_local_users,
+    test_local_users_postgres,
+    test_local_users_sqlite
+);
+
+async fn test_local_users(db: &Arc<Database>) {
+    let user_1 = db.create_local_user("local-1", true).await.unwrap();
+    let user_2 = db.create_local_user("local-2", false).await.unwrap();
+    assert!(user_1.admin);
+    assert_eq!(user_1.github_user_id, None);
+    assert_eq!(
+        db.get_user_by_github_login("local-2").await.unwrap(),
+        Some(user_2.clone())
+    );
+    assert!(db.create_local_user("local-1", false).await.is_err());
+
+    let token_1 = db
+        .create_access_token(user_1.id, None, "h1", 8)
+        .await
+        .unwrap();
+    let token_2 = db
+        .create_access_token(user_2.id, Some(user_1.id), "h2", 8)
+        .await
+        .unwrap();
+    let token_3 = db
+        .create_access_token(user_2.id, None, "h3", 8)
+        .await
+        .unwrap();
+
+    // Revoking a user's tokens also revokes the tokens used to impersonate them.
+    assert_eq!(
+        db.delete_access_tokens_for_user(user_1.id).await.unwrap(),
+        2
+    );
+    assert!(db.get_access_token(token_1).await.is_err());
+    assert!(db.get_access_token(token_2).await.is_err());
+    assert!(db.get_access_token(token_3).await.is_ok());
+}
+
+#[test]
+fn test_self_hosted_migrations_upgrade_older_databases() {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!(
+        "sqlite://{}?mode=rwc",
+        dir.path().join("collab.db").display()
+    );
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let db = Database::new(ConnectOptions::new(url.clone()), Executor::Production)
+            .await
+            .unwrap();
+        let migrations = sqlx::migrate!("./migrations.self_hosted")
+            .migrations
+            .to_vec();
+
+        // Create a database with the schema of the first self-hosted release, and store a
+        // settings file from before files had a kind.
+        db.apply_migrations(migrations[..1].to_vec(), false)
+            .await
+            .unwrap();
+        let mut connection = sqlx::SqliteConnection::connect(&url).await.unwrap();
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO worktree_settings_files (project_id, worktree_id, path, content)
+            VALUES (1, 2, '.zed/settings.json', '{}')",
+        )
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+        let applied = db.migrate_embedded_sqlite().await.unwrap();
+        assert_eq!(applied.len(), migrations.len() - 1);
+        assert!(db.migrate_embedded_sqlite().await.unwrap().is_empty());
+
+        // Settings files and editorconfigs at the same path are stored separately.
+        sqlx::query(
+            "INSERT INTO worktree_settings_files (project_id, worktree_id, path, kind, content)
+            VALUES (1, 2, '.zed/settings.json', 'editorconfig', 'root = true')",
+        )
+        .execute(&mut connection)
+        .await
+        .unwrap();
+        let files = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT path, kind, content FROM worktree_settings_files ORDER BY kind",
+        )
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+        assert_eq!(
+            files,
+            [
+                (
+                    ".zed/settings.json".to_string(),
+                    "editorconfig".to_string(),
+                    "root = true".to_string()
+                ),
+                (
+                    ".zed/settings.json".to_string(),
+                    "settings".to_string(),
+                    "{}".to_string()
+                ),
+            ]
+        );
+    });
+}
+
 test_both_dbs!(
     test_add_contacts,
     test_add_contacts_postgres,
@@ -4,6 +4,7 @@ use crate::{
     test_both_dbs,
 };
 use channel::mentions_to_proto;
+use rpc::proto;
 use std::sync::Arc;
 use time::OffsetDateTime;
 
@@ -419,3 +420,168 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         ]
     );
 }
+
+test_both_dbs!(
+    test_channel_message_threads_and_reactions,
+    test_channel_message_threads_and_reactions_postgres,
+    test_channel_message_threads_and_reactions_sqlite
+);
+
+async fn test_channel_message_threads_and_reactions(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 1 }, user_b)
+        .await
+        .unwrap();
+
+    let send = move |body: &'static str, nonce: u128, reply_to_message_id| {
+        db.create_channel_message(
+            channel,
+            user_a,
+            body,
+            &[],
+            OffsetDateTime::now_utc(),
+            nonce,
+            reply_to_message_id,
+        )
+    };
+    let root = send("root", 1, None).await.unwrap().message_id;
+    let reply = send("reply", 2, Some(root)).await.unwrap().message_id;
+    send("unrelated", 3, None).await.unwrap();
+    send("nested reply", 4, Some(reply)).await.unwrap();
+
+    let thread = db
+        .get_channel_message_thread(channel, user_b, root)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|message| message.body)
+        .collect::<Vec<_>>();
+    assert_eq!(thread, &["root", "reply", "nested reply"]);
+
+    db.add_channel_message_reaction(channel, root, user_a, "👍")
+        .await
+        .unwrap();
+    let updated = db
+        .add_channel_message_reaction(channel, root, user_b, "👍")
+        .await
+        .unwrap();
+    assert_eq!(updated.participant_connection_ids.len(), 2);
+    assert_eq!(
+        updated.reactions,
+        &[proto::ChannelMessageReaction {
+            emoji: "👍".into(),
+            user_ids: vec![user_a.to_proto(), user_b.to_proto()],
+        }]
+    );
+
+    // Reacting twice with the same emoji has no effect.
+    db.add_channel_message_reaction(channel, root, user_b, "👍")
+        .await
+        .unwrap();
+    let updated = db
+        .remove_channel_message_reaction(channel, root, user_a, "👍")
+        .await
+        .unwrap();
+    assert_eq!(
+        updated.reactions,
+        &[proto::ChannelMessageReaction {
+            emoji: "👍".into(),
+            user_ids: vec![user_b.to_proto()],
+        }]
+    );
+
+    let messages = db
+        .get_channel_messages(channel, user_a, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(messages[0].reactions, updated.reactions);
+    assert!(messages[1..]
+        .iter()
+        .all(|message| message.reactions.is_empty()));
+}
+
+test_both_dbs!(
+    test_channel_message_search,
+    test_channel_message_search_postgres,
+    test_channel_message_search_sqlite
+);
+
+async fn test_channel_message_search(db: &Arc<Database>) {
+    let user = new_test_user(db, "user@example.com").await;
+    let channel = db.create_root_channel("channel", user).await.unwrap();
+    let other_channel = db.create_root_channel("other-channel", user).await.unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user)
+        .await
+        .unwrap();
+    db.join_channel_chat(other_channel, rpc::ConnectionId { owner_id, id: 0 }, user)
+        .await
+        .unwrap();
+
+    let mut message_ids = Vec::new();
+    for (nonce, (channel, body)) in [
+        (channel, "the build is broken"),
+        (channel, "deploy the fix"),
+        (other_channel, "the build is broken"),
+        (channel, "Build fixed"),
+        (channel, "snake_case names"),
+        (channel, "snakescase names"),
+        (channel, "100% done"),
+        (channel, "1000 tests"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        message_ids.push(
+            db.create_channel_message(
+                channel,
+                user,
+                body,
+                &[],
+                OffsetDateTime::now_utc(),
+                nonce as u128,
+                None,
+            )
+            .await
+            .unwrap()
+            .message_id,
+        );
+    }
+
+    let search = move |query: &'static str, before_message_id| async move {
+        db.search_channel_messages(channel, user, query, 10, before_message_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|message| message.body)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        search("build", None).await,
+        &["the build is broken", "Build fixed"]
+    );
+    assert_eq!(search("build broken", None).await, &["the build is broken"]);
+    assert_eq!(
+        search("build", Some(message_ids[3])).await,
+        &["the build is broken"]
+    );
+    assert!(search("  ", None).await.is_empty());
+
+    // Wildcards in the query are matched literally.
+    assert_eq!(search("snake_case", None).await, &["snake_case names"]);
+    assert_eq!(search("100%", None).await, &["100% done"]);
+}
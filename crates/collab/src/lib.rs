@@ -7,6 +7,7 @@ pub mod executor;
 mod rate_limiter;
 pub mod rpc;
 pub mod seed;
+pub mod self_hosted;
 
 #[cfg(test)]
 mod tests;
@@ -139,12 +140,18 @@ pub struct Config {
     pub slack_panics_webhook: Option<String>,
     pub auto_join_channel_id: Option<ChannelId>,
     pub supermaven_admin_api_key: Option<Arc<str>>,
+    pub self_hosted: Option<bool>,
 }
 
 impl Config {
     pub fn is_development(&self) -> bool {
         self.zed_environment == "development".into()
     }
+
+    /// Whether users sign in with tokens issued by `collab users` rather than through zed.dev.
+    pub fn is_self_hosted(&self) -> bool {
+        self.self_hosted.unwrap_or(false)
+    }
 }
 
 pub struct AppState {
@@ -54,6 +54,14 @@ async fn main() -> Result<()> {
 
             collab::seed::seed(&config, &db, true).await?;
         }
+        Some("users") => {
+            let config = envy::from_env::<Config>().expect("error loading config");
+            run_migrations(&config).await?;
+            let db_options = db::ConnectOptions::new(config.database_url.clone());
+            let db = Database::new(db_options, Executor::Production).await?;
+            let args = args.collect::<Vec<_>>();
+            collab::self_hosted::manage_users(&db, &args).await?;
+        }
         Some("serve") => {
             let (is_api, is_collab) = if let Some(next) = args.next() {
                 (next == "api", next == "collab")
@@ -62,7 +70,7 @@ async fn main() -> Result<()> {
             };
             if !is_api && !is_collab {
                 Err(anyhow!(
-                    "usage: collab <version | migrate | seed | serve [api|collab]>"
+                    "usage: collab <version | migrate | seed | users | serve [api|collab]>"
                 ))?;
             }
 
@@ -102,6 +110,9 @@ async fn main() -> Result<()> {
             if let Some(rpc_server) = rpc_server.clone() {
                 app = app.merge(collab::rpc::routes(rpc_server))
             }
+            if state.config.is_self_hosted() {
+                app = app.merge(collab::self_hosted::router().layer(Extension(state.clone())));
+            }
             app = app
                 .merge(
                     Router::new()
@@ -181,7 +192,7 @@ async fn main() -> Result<()> {
         }
         _ => {
             Err(anyhow!(
-                "usage: collab <version | migrate | seed | serve [api|collab]>"
+                "usage: collab <version | migrate | seed | users | serve [api|collab]>"
             ))?;
         }
     }
@@ -192,16 +203,19 @@ async fn run_migrations(config: &Config) -> Result<()> {
     let db_options = db::ConnectOptions::new(config.database_url.clone());
     let mut db = Database::new(db_options, Executor::Production).await?;
 
-    let migrations_path = config.migrations_path.as_deref().unwrap_or_else(|| {
+    let migrations = match config.migrations_path.as_deref() {
+        Some(migrations_path) => db.migrate(migrations_path, false).await?,
         #[cfg(feature = "sqlite")]
-        let default_migrations = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations.sqlite");
+        None if config.database_url.starts_with("sqlite:") => db.migrate_embedded_sqlite().await?,
         #[cfg(not(feature = "sqlite"))]
-        let default_migrations = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
-
-        Path::new(default_migrations)
-    });
-
-    let migrations = db.migrate(&migrations_path, false).await?;
+        None if config.database_url.starts_with("sqlite:") => Err(anyhow!(
+            "collab was built without SQLite support, build it with `--features sqlite`"
+        ))?,
+        None => {
+            let default_migrations = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations");
+            db.migrate(Path::new(default_migrations), false).await?
+        }
+    };
     for (migration, duration) in migrations {
         log::info!(
             "Migrated {} {} {:?}",
@@ -5,9 +5,10 @@ use crate::{
     db::{
         self, dev_server, BufferId, Capability, Channel, ChannelId, ChannelRole, ChannelsForUser,
         CreatedChannelMessage, Database, DevServerId, DevServerProjectId, InviteMemberResult,
-        MembershipUpdated, MessageId, NotificationId, PrincipalId, Project, ProjectId,
-        RejoinedProject, RemoveChannelMemberResult, ReplicaId, RespondToChannelInvite, RoomId,
-        ServerId, UpdatedChannelMessage, User, UserId,
+        MembershipUpdated, MessageId, NotificationId, PrincipalId, Project, ProjectCommentThreadId,
+        ProjectId, RejoinedProject, RemoveChannelMemberResult, ReplicaId, RespondToChannelInvite,
+        RoomId, ServerId, UpdatedChannelMessage, UpdatedChannelMessageReactions,
+        UpdatedProjectCommentThread, User, UserId,
     },
     executor::Executor,
     AppState, Error, RateLimit, RateLimiter, Result,
@@ -84,6 +85,7 @@ pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
 const MAX_MESSAGE_LEN: usize = 1024;
+const MAX_COMMENT_LEN: usize = 8192;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 type MessageHandler =
@@ -446,6 +448,10 @@ impl Server {
             .add_message_handler(update_language_server)
             .add_message_handler(update_diagnostic_summary)
             .add_message_handler(update_worktree_settings)
+            .add_request_handler(user_handler(get_project_comment_threads))
+            .add_request_handler(user_handler(create_project_comment_thread))
+            .add_request_handler(user_handler(reply_to_project_comment_thread))
+            .add_request_handler(user_handler(resolve_project_comment_thread))
             .add_request_handler(user_handler(
                 forward_project_request_for_owner::<proto::TaskContextForLocation>,
             ))
@@ -558,6 +564,12 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::ShareTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UnshareTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateTerminal>)
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::TerminalInput>,
+            ))
             .add_request_handler(get_users)
             .add_request_handler(user_handler(fuzzy_search_users))
             .add_request_handler(user_handler(request_contact))
@@ -585,6 +597,10 @@ impl Server {
             .add_request_handler(user_handler(update_channel_message))
             .add_request_handler(user_handler(get_channel_messages))
             .add_request_handler(user_handler(get_channel_messages_by_id))
+            .add_request_handler(user_handler(get_channel_message_thread))
+            .add_request_handler(user_handler(search_channel_messages))
+            .add_request_handler(user_handler(add_channel_message_reaction))
+            .add_request_handler(user_handler(remove_channel_message_reaction))
             .add_request_handler(user_handler(get_notifications))
             .add_request_handler(user_handler(mark_notification_as_read))
             .add_request_handler(user_handler(move_channel))
@@ -1759,6 +1775,7 @@ fn notify_rejoined_projects(
                         worktree_id: worktree.id,
                         path: settings_file.path,
                         content: Some(settings_file.content),
+                        kind: settings_file.kind.to_proto().into(),
                     },
                 )?;
             }
@@ -2239,6 +2256,7 @@ fn join_project_internal(
                     worktree_id: worktree.id,
                     path: settings_file.path,
                     content: Some(settings_file.content),
+                    kind: settings_file.kind.to_proto().into(),
                 },
             )?;
         }
@@ -2798,6 +2816,131 @@ async fn update_diagnostic_summary(
     Ok(())
 }
 
+/// Returns the comment threads on the buffers of a project
+async fn get_project_comment_threads(
+    request: proto::GetProjectCommentThreads,
+    response: Response<proto::GetProjectCommentThreads>,
+    session: UserSession,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_project_comment_threads(
+            ProjectId::from_proto(request.project_id),
+            session.connection_id,
+        )
+        .await?
+        .into_inner();
+    response.send(proto::GetProjectCommentThreadsResponse { threads })?;
+    Ok(())
+}
+
+/// Starts a comment thread on a range of a buffer in a project
+async fn create_project_comment_thread(
+    request: proto::CreateProjectCommentThread,
+    response: Response<proto::CreateProjectCommentThread>,
+    session: UserSession,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let update = session
+        .db()
+        .await
+        .create_project_comment_thread(&request, &body, session.connection_id)
+        .await?
+        .into_inner();
+    send_project_comment_thread_update(request.project_id, update, response, &session).await
+}
+
+/// Adds a comment to an existing thread in a project
+async fn reply_to_project_comment_thread(
+    request: proto::ReplyToProjectCommentThread,
+    response: Response<proto::ReplyToProjectCommentThread>,
+    session: UserSession,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let update = session
+        .db()
+        .await
+        .reply_to_project_comment_thread(
+            ProjectId::from_proto(request.project_id),
+            ProjectCommentThreadId::from_proto(request.thread_id),
+            &body,
+            session.connection_id,
+        )
+        .await?
+        .into_inner();
+    send_project_comment_thread_update(request.project_id, update, response, &session).await
+}
+
+/// Resolves or reopens a comment thread in a project
+async fn resolve_project_comment_thread(
+    request: proto::ResolveProjectCommentThread,
+    response: Response<proto::ResolveProjectCommentThread>,
+    session: UserSession,
+) -> Result<()> {
+    let update = session
+        .db()
+        .await
+        .resolve_project_comment_thread(
+            ProjectId::from_proto(request.project_id),
+            ProjectCommentThreadId::from_proto(request.thread_id),
+            request.resolved,
+            session.connection_id,
+        )
+        .await?
+        .into_inner();
+    send_project_comment_thread_update(request.project_id, update, response, &session).await
+}
+
+fn validate_comment_body(body: &str) -> Result<String> {
+    let body = body.trim();
+    if body.len() > MAX_COMMENT_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    Ok(body.to_string())
+}
+
+/// Responds with the updated thread, sends it to the project's other collaborators and
+/// notifies the users the update concerns.
+async fn send_project_comment_thread_update<T>(
+    project_id: u64,
+    update: UpdatedProjectCommentThread,
+    response: Response<T>,
+    session: &UserSession,
+) -> Result<()>
+where
+    T: RequestMessage<Response = proto::ProjectCommentThreadResponse>,
+{
+    let UpdatedProjectCommentThread {
+        thread,
+        connection_ids,
+        notifications,
+    } = update;
+    broadcast(
+        Some(session.connection_id),
+        connection_ids,
+        |connection_id| {
+            session.peer.send(
+                connection_id,
+                proto::UpdateProjectCommentThread {
+                    project_id,
+                    thread: Some(thread.clone()),
+                },
+            )
+        },
+    );
+    response.send(proto::ProjectCommentThreadResponse {
+        thread: Some(thread),
+    })?;
+
+    let pool = &*session.connection_pool().await;
+    send_notifications(pool, &session.peer, notifications);
+    Ok(())
+}
+
 /// Updates other participants with changes to the worktree settings
 async fn update_worktree_settings(
     message: proto::UpdateWorktreeSettings,
@@ -4167,6 +4310,7 @@ async fn send_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
         edited_at: None,
+        reactions: Vec::new(),
     };
     broadcast(
         Some(session.connection_id),
@@ -4291,6 +4435,7 @@ async fn update_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: reply_to_message_id.map(|id| id.to_proto()),
         edited_at: Some(updated_at.unix_timestamp() as u64),
+        reactions: Vec::new(),
     };
 
     response.send(proto::Ack {})?;
@@ -4335,6 +4480,73 @@ async fn update_channel_message(
     Ok(())
 }
 
+/// Add a reaction to a channel message
+async fn add_channel_message_reaction(
+    request: proto::AddChannelMessageReaction,
+    response: Response<proto::AddChannelMessageReaction>,
+    session: UserSession,
+) -> Result<()> {
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let message_id = MessageId::from_proto(request.message_id);
+    let emoji = validate_reaction(&request.emoji)?;
+    let updated = session
+        .db()
+        .await
+        .add_channel_message_reaction(channel_id, message_id, session.user_id(), emoji)
+        .await?;
+    broadcast_channel_message_reactions(channel_id, message_id, updated, &session);
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Remove a reaction from a channel message
+async fn remove_channel_message_reaction(
+    request: proto::RemoveChannelMessageReaction,
+    response: Response<proto::RemoveChannelMessageReaction>,
+    session: UserSession,
+) -> Result<()> {
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let message_id = MessageId::from_proto(request.message_id);
+    let emoji = validate_reaction(&request.emoji)?;
+    let updated = session
+        .db()
+        .await
+        .remove_channel_message_reaction(channel_id, message_id, session.user_id(), emoji)
+        .await?;
+    broadcast_channel_message_reactions(channel_id, message_id, updated, &session);
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Reactions are a single emoji, stored in its fully-qualified form so that the same emoji
+/// is always counted together.
+fn validate_reaction(emoji: &str) -> Result<&'static str> {
+    let emoji = emoji.trim();
+    if emoji.is_empty() {
+        return Err(anyhow!("reaction can't be blank"))?;
+    }
+    match emojis::get(emoji) {
+        Some(emoji) => Ok(emoji.as_str()),
+        None => Err(anyhow!("reaction must be an emoji"))?,
+    }
+}
+
+fn broadcast_channel_message_reactions(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    updated: UpdatedChannelMessageReactions,
+    session: &UserSession,
+) {
+    let message = proto::ChannelMessageReactionsUpdated {
+        channel_id: channel_id.to_proto(),
+        message_id: message_id.to_proto(),
+        reactions: updated.reactions,
+    };
+    broadcast(None, updated.participant_connection_ids, |connection| {
+        session.peer.send(connection, message.clone())
+    });
+}
+
 /// Mark a channel message as read
 async fn acknowledge_channel_message(
     request: proto::AckChannelMessage,
@@ -4940,6 +5152,52 @@ async fn get_channel_messages_by_id(
     Ok(())
 }
 
+/// Retrieve a chat message along with all of the replies to it
+async fn get_channel_message_thread(
+    request: proto::GetChannelMessageThread,
+    response: Response<proto::GetChannelMessageThread>,
+    session: UserSession,
+) -> Result<()> {
+    let messages = session
+        .db()
+        .await
+        .get_channel_message_thread(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            MessageId::from_proto(request.root_message_id),
+        )
+        .await?;
+    response.send(proto::GetChannelMessagesResponse {
+        done: true,
+        messages,
+    })?;
+    Ok(())
+}
+
+/// Search the chat history of a channel
+async fn search_channel_messages(
+    request: proto::SearchChannelMessages,
+    response: Response<proto::SearchChannelMessages>,
+    session: UserSession,
+) -> Result<()> {
+    let messages = session
+        .db()
+        .await
+        .search_channel_messages(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id(),
+            &request.query,
+            MESSAGE_COUNT_PER_PAGE,
+            request.before_message_id.map(MessageId::from_proto),
+        )
+        .await?;
+    response.send(proto::GetChannelMessagesResponse {
+        done: messages.len() < MESSAGE_COUNT_PER_PAGE,
+        messages,
+    })?;
+    Ok(())
+}
+
 /// Retrieve the current users notifications
 async fn get_notifications(
     request: proto::GetNotifications,
@@ -0,0 +1,192 @@
+//! Support for running collab on its own, without zed.dev.
+//!
+//! A self-hosted server has no GitHub sign-in. Instead, an administrator creates users and
+//! issues their access tokens with `collab users`, and users paste their token into the
+//! sign-in page that this module serves in place of zed.dev's.
+
+use crate::{
+    auth::{self, encrypt_access_token},
+    db::{Database, UserId},
+    AppState, Result,
+};
+use anyhow::{anyhow, Context as _};
+use axum::{
+    extract::Query,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Extension, Form, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub const USERS_USAGE: &str =
+    "usage: collab users <list | add <login> [--admin] | token <login> | revoke <login>>";
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/native_app_signin",
+            get(get_native_app_signin).post(post_native_app_signin),
+        )
+        .route(
+            "/native_app_signin_succeeded",
+            get(get_native_app_signin_succeeded),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeAppSigninParams {
+    native_app_port: u16,
+    native_app_public_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeAppSigninForm {
+    native_app_port: u16,
+    native_app_public_key: String,
+    login: String,
+    access_token: String,
+}
+
+async fn get_native_app_signin(Query(params): Query<NativeAppSigninParams>) -> Html<String> {
+    signin_page(&params.native_app_public_key, params.native_app_port, None)
+}
+
+async fn post_native_app_signin(
+    Extension(app): Extension<Arc<AppState>>,
+    Form(form): Form<NativeAppSigninForm>,
+) -> Result<Response> {
+    let access_token = form.access_token.trim();
+    let Some(user_id) = verify_local_access_token(&app, form.login.trim(), access_token).await
+    else {
+        return Ok(signin_page(
+            &form.native_app_public_key,
+            form.native_app_port,
+            Some("Invalid login or access token."),
+        )
+        .into_response());
+    };
+
+    let encrypted_access_token =
+        encrypt_access_token(access_token, form.native_app_public_key.clone())?;
+    Ok(Redirect::to(&format!(
+        "http://127.0.0.1:{}/?user_id={}&access_token={}",
+        form.native_app_port, user_id, encrypted_access_token
+    ))
+    .into_response())
+}
+
+async fn get_native_app_signin_succeeded() -> Html<&'static str> {
+    Html(
+        "<!DOCTYPE html><html><head><title>Signed in</title></head>\
+         <body><p>You are signed in. You can close this page and return to Zed.</p></body></html>",
+    )
+}
+
+/// Returns the id of the user with the given login if the token lets them sign in directly,
+/// without impersonating anyone.
+async fn verify_local_access_token(
+    app: &AppState,
+    login: &str,
+    access_token: &str,
+) -> Option<UserId> {
+    let user = app.db.get_user_by_github_login(login).await.ok()??;
+    let result = auth::verify_access_token(access_token, user.id, &app.db)
+        .await
+        .ok()?;
+    (result.is_valid && result.impersonator_id.is_none()).then_some(user.id)
+}
+
+fn signin_page(public_key: &str, port: u16, error: Option<&str>) -> Html<String> {
+    let error = error
+        .map(|error| format!("<p class=\"error\">{}</p>", escape_html(error)))
+        .unwrap_or_default();
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>Sign in to Zed</title></head>
+<body>
+<h1>Sign in to Zed</h1>
+{error}
+<form method="post" action="/native_app_signin">
+<input type="hidden" name="native_app_port" value="{port}">
+<input type="hidden" name="native_app_public_key" value="{public_key}">
+<p><label>Login <input type="text" name="login" autofocus required></label></p>
+<p><label>Access token <textarea name="access_token" rows="4" cols="60" required></textarea></label></p>
+<p><button type="submit">Sign in</button></p>
+</form>
+</body>
+</html>"#,
+        public_key = escape_html(public_key),
+    ))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Runs the `collab users` subcommand, which manages the accounts of a self-hosted server.
+pub async fn manage_users(db: &Database, args: &[String]) -> Result<()> {
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    match args.as_slice() {
+        ["list"] => {
+            let mut page = 0;
+            loop {
+                let users = db.get_all_users(page, 100).await?;
+                if users.is_empty() {
+                    break;
+                }
+                for user in users {
+                    let admin = if user.admin { " (admin)" } else { "" };
+                    println!("{}\t{}{}", user.id, user.github_login, admin);
+                }
+                page += 1;
+            }
+        }
+        ["add", login, flags @ ..] => {
+            let admin = match flags {
+                [] => false,
+                ["--admin"] => true,
+                _ => Err(anyhow!(USERS_USAGE))?,
+            };
+            if db.get_user_by_github_login(login).await?.is_some() {
+                Err(anyhow!("user {login} already exists"))?;
+            }
+            let user = db.create_local_user(login, admin).await?;
+            let access_token = auth::create_access_token(db, user.id, None).await?;
+            println!("created user {login} with id {}", user.id);
+            println!("access token: {access_token}");
+        }
+        ["token", login] => {
+            let user = find_user(db, login).await?;
+            let access_token = auth::create_access_token(db, user, None).await?;
+            println!("{access_token}");
+        }
+        ["revoke", login] => {
+            let user = find_user(db, login).await?;
+            let count = db.delete_access_tokens_for_user(user).await?;
+            println!("revoked {count} access token(s) for {login}");
+        }
+        _ => Err(anyhow!(USERS_USAGE))?,
+    }
+    Ok(())
+}
+
+async fn find_user(db: &Database, login: &str) -> Result<UserId> {
+    Ok(db
+        .get_user_by_github_login(login)
+        .await?
+        .with_context(|| format!("no user with login {login}"))?
+        .id)
+}
@@ -1,5 +1,5 @@
 use crate::{rpc::RECONNECT_TIMEOUT, tests::TestServer};
-use channel::{ChannelChat, ChannelMessageId, MessageParams};
+use channel::{ChannelChat, ChannelMessageId, ChannelMessageReaction, MessageParams};
 use collab_ui::chat_panel::ChatPanel;
 use gpui::{BackgroundExecutor, Model, TestAppContext};
 use rpc::Notification;
@@ -723,3 +723,174 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
         assert_eq!(store.notification_count(), 1);
     });
 }
+
+#[gpui::test]
+async fn test_chat_threads_reactions_and_search(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let root_id = channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.send_message("the deploy is broken".into(), cx).unwrap()
+        })
+        .await
+        .unwrap();
+    let reply_id = channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_message(
+                MessageParams {
+                    text: "looking into the deploy".into(),
+                    reply_to_message_id: Some(root_id),
+                    mentions: Vec::new(),
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.send_message("unrelated".into(), cx).unwrap()
+        })
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_message(
+                MessageParams {
+                    text: "fixed".into(),
+                    reply_to_message_id: Some(reply_id),
+                    mentions: Vec::new(),
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    channel_chat_a
+        .update(cx_a, |c, cx| c.load_thread(root_id, cx))
+        .await
+        .unwrap();
+    channel_chat_a.update(cx_a, |c, _| {
+        assert_eq!(c.message_count(), 4);
+        assert_eq!(
+            c.thread_messages(root_id)
+                .iter()
+                .map(|m| m.body.as_str())
+                .collect::<Vec<_>>(),
+            ["the deploy is broken", "looking into the deploy", "fixed"]
+        );
+    });
+
+    // Reactions are broadcast to every participant, including the one who reacted.
+    channel_chat_a
+        .update(cx_a, |c, cx| c.toggle_reaction(root_id, "👍", cx).unwrap())
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| c.toggle_reaction(root_id, "👍", cx).unwrap())
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    for (chat, cx) in [(&channel_chat_a, &mut *cx_a), (&channel_chat_b, &mut *cx_b)] {
+        chat.update(cx, |c, _| {
+            assert_eq!(
+                c.find_loaded_message(root_id).unwrap().reactions,
+                [ChannelMessageReaction {
+                    emoji: "👍".into(),
+                    user_ids: vec![client_a.id(), client_b.id()],
+                }]
+            );
+        });
+    }
+
+    // Toggling the same reaction again removes it.
+    channel_chat_a
+        .update(cx_a, |c, cx| c.toggle_reaction(root_id, "👍", cx).unwrap())
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    channel_chat_b.update(cx_b, |c, _| {
+        assert_eq!(
+            c.find_loaded_message(root_id).unwrap().reactions,
+            [ChannelMessageReaction {
+                emoji: "👍".into(),
+                user_ids: vec![client_b.id()],
+            }]
+        );
+    });
+
+    // Reactions must be a single emoji.
+    for reaction in ["ok", "👍👍"] {
+        assert!(channel_chat_a
+            .update(cx_a, |c, cx| c
+                .toggle_reaction(root_id, reaction, cx)
+                .unwrap())
+            .await
+            .is_err());
+    }
+
+    let results = channel_chat_b
+        .update(cx_b, |c, cx| c.search_messages("deploy".into(), None, cx))
+        .await
+        .unwrap();
+    assert!(!results.has_more);
+    assert_eq!(
+        results
+            .messages
+            .iter()
+            .map(|m| (m.id, m.body.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (ChannelMessageId::Saved(reply_id), "looking into the deploy"),
+            (ChannelMessageId::Saved(root_id), "the deploy is broken"),
+        ]
+    );
+
+    // Older matches are loaded from the oldest match found so far.
+    let results = channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.search_messages("deploy".into(), Some(reply_id), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        results
+            .messages
+            .iter()
+            .map(|m| m.body.as_str())
+            .collect::<Vec<_>>(),
+        ["the deploy is broken"]
+    );
+}
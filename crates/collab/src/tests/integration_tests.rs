@@ -13,13 +13,13 @@ use fs::{FakeFs, Fs as _, RemoveOptions};
 use futures::{channel::mpsc, StreamExt as _};
 use git::repository::GitFileStatus;
 use gpui::{
-    px, size, AppContext, BackgroundExecutor, Model, Modifiers, MouseButton, MouseDownEvent,
-    TestAppContext, UpdateGlobal,
+    px, size, AppContext, BackgroundExecutor, Context as _, Entity as _, Model, Modifiers,
+    MouseButton, MouseDownEvent, TestAppContext, UpdateGlobal,
 };
 use language::{
     language_settings::{AllLanguageSettings, Formatter, PrettierSettings},
-    tree_sitter_rust, Diagnostic, DiagnosticEntry, FakeLspAdapter, Language, LanguageConfig,
-    LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope,
+    tree_sitter_rust, Diagnostic, DiagnosticEntry, Encoding, FakeLspAdapter, Language,
+    LanguageConfig, LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope, ToPoint,
 };
 use live_kit_client::MacOSDisplay;
 use lsp::LanguageServerId;
@@ -29,6 +29,7 @@ use project::{
     SearchResult,
 };
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
 use settings::SettingsStore;
 use std::{
@@ -42,6 +43,7 @@ use std::{
     },
     time::Duration,
 };
+use terminal::TerminalBuilder;
 use unindent::Unindent as _;
 use workspace::Pane;
 
@@ -3301,6 +3303,7 @@ async fn test_local_settings(
                     ".zed": {
                         "settings.json": r#"{ "tab_size": 8 }"#
                     },
+                    ".editorconfig": "[*]\nindent_style = tab\n",
                     "a.txt": "a-contents",
                 },
                 "b": {
@@ -3332,7 +3335,16 @@ async fn test_local_settings(
                 (Path::new("").into(), r#"{"tab_size":2}"#.to_string()),
                 (Path::new("a").into(), r#"{"tab_size":8}"#.to_string()),
             ]
-        )
+        );
+        assert_eq!(
+            store
+                .local_editorconfigs(worktree_b.read(cx).id().to_usize())
+                .collect::<Vec<_>>(),
+            &[(
+                Path::new("a").into(),
+                "[*]\nindent_style = tab\n".to_string()
+            )]
+        );
     });
 
     // As client A, update a settings file. As Client B, see the changed settings.
@@ -3536,6 +3548,85 @@ async fn test_buffer_reloading(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_buffer_encodings(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a.fs().insert_tree("/dir", json!({})).await;
+    client_a
+        .fs()
+        .insert_file("/dir/a.txt", b"caf\xE9 cr\xE8me".to_vec())
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+
+    let windows_1252 = Encoding::from_name("windows-1252", false).unwrap();
+    let utf8_bom = Encoding::from_name("utf-8", true).unwrap();
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    buffer_b.read_with(cx_b, |buf, _| {
+        assert_eq!(buf.text(), "café crème");
+        assert_eq!(buf.encoding(), windows_1252);
+    });
+
+    // The guest converts the file, which is written by the host.
+    project_b
+        .update(cx_b, |project, cx| {
+            project.save_buffer_with_encoding(buffer_b.clone(), utf8_bom, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        client_a
+            .fs()
+            .load_bytes("/dir/a.txt".as_ref())
+            .await
+            .unwrap(),
+        utf8_bom.encode("café crème").unwrap()
+    );
+    buffer_a.read_with(cx_a, |buf, _| assert_eq!(buf.encoding(), utf8_bom));
+    buffer_b.read_with(cx_b, |buf, _| {
+        assert_eq!(buf.encoding(), utf8_bom);
+        assert!(!buf.is_dirty());
+    });
+
+    // The guest reopens the file with another encoding.
+    project_b
+        .update(cx_b, |project, cx| {
+            project.reload_buffer_with_encoding(buffer_b.clone(), windows_1252, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    for (buffer, cx) in [(&buffer_a, &mut *cx_a), (&buffer_b, &mut *cx_b)] {
+        buffer.read_with(cx, |buf, _| {
+            assert_eq!(buf.encoding(), windows_1252);
+            assert_eq!(buf.text(), "ï»¿cafÃ© crÃ¨me");
+        });
+    }
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_editing_while_guest_opens_buffer(
     executor: BackgroundExecutor,
@@ -3584,6 +3675,191 @@ async fn test_editing_while_guest_opens_buffer(
     buffer_b.read_with(cx_b, |buf, _| assert_eq!(buf.text(), text));
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_editing_while_guest_is_offline(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree\nfour\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+
+    let conflicts = Rc::new(RefCell::new(Vec::new()));
+    project_b.update(cx_b, |_, cx| {
+        let conflicts = conflicts.clone();
+        cx.subscribe(&project_b, move |_, _, event, _| {
+            if let project::Event::OfflineEditsConflicted { ranges, .. } = event {
+                conflicts.borrow_mut().extend(ranges.iter().cloned());
+            }
+        })
+        .detach();
+    });
+
+    // While client B is offline, both clients edit the buffer. Only their edits to the last
+    // line interleave.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    executor.run_until_parked();
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..3, "ONE"), (14..18, "4")], None, cx)
+    });
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.edit([(4..7, "TWO"), (14..18, "FOUR")], None, cx)
+    });
+    executor.run_until_parked();
+    assert!(!project_b.read_with(cx_b, |project, _| project.is_read_only()));
+
+    // Client B reconnects, and the host receives its offline edits.
+    server.allow_connections();
+    client_b
+        .authenticate_and_connect(false, &cx_b.to_async())
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let text = buffer_a.read_with(cx_a, |buffer, _| buffer.text());
+    assert_eq!(buffer_b.read_with(cx_b, |buffer, _| buffer.text()), text);
+    assert!(text.starts_with("ONE\nTWO\nthree\n"), "{text:?}");
+    assert!(text.contains('4') && text.contains("FOUR"), "{text:?}");
+
+    buffer_b.read_with(cx_b, |buffer, _| {
+        let rows = conflicts
+            .borrow()
+            .iter()
+            .map(|range| range.start.to_point(buffer).row)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [3]);
+    });
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_editing_while_guest_is_offline_past_reconnect_timeout(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    // Client C keeps the room alive while client B is gone.
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree\nfour\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+
+    // Client B edits the buffer while offline, and doesn't reconnect before it is dropped
+    // from the call and the project.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    executor.run_until_parked();
+    buffer_b.update(cx_b, |buffer, cx| {
+        buffer.edit([(0..3, "ONE"), (14..18, "4")], None, cx)
+    });
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.edit([(4..7, "TWO"), (14..18, "FOUR")], None, cx)
+    });
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+    assert!(project_b.read_with(cx_b, |project, _| project.is_disconnected()));
+    active_call_b.read_with(cx_b, |call, _| assert!(call.room().is_none()));
+    assert_eq!(
+        buffer_a.read_with(cx_a, |buffer, _| buffer.text()),
+        "one\nTWO\nthree\nFOUR\n"
+    );
+
+    // Client B reconnects and joins the call and the project again, and its offline edits
+    // are replayed.
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    let conflicts = Rc::new(RefCell::new(Vec::new()));
+    project_b.update(cx_b, |_, cx| {
+        let conflicts = conflicts.clone();
+        cx.subscribe(&project_b, move |_, _, event, _| {
+            if let project::Event::OfflineEditsConflicted { ranges, .. } = event {
+                conflicts.borrow_mut().extend(ranges.iter().cloned());
+            }
+        })
+        .detach();
+    });
+    executor.run_until_parked();
+
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let text = buffer_a.read_with(cx_a, |buffer, _| buffer.text());
+    assert_eq!(buffer_b.read_with(cx_b, |buffer, _| buffer.text()), text);
+    assert!(text.starts_with("ONE\nTWO\nthree\n"), "{text:?}");
+    assert!(text.contains('4') && text.contains("FOUR"), "{text:?}");
+
+    buffer_b.read_with(cx_b, |buffer, _| {
+        let rows = conflicts
+            .borrow()
+            .iter()
+            .map(|range| range.start.to_point(buffer).row)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [3]);
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_leaving_worktree_while_opening_buffer(
     executor: BackgroundExecutor,
@@ -6449,3 +6725,268 @@ async fn test_preview_tabs(cx: &mut TestAppContext) {
         assert!(!pane.can_navigate_forward());
     });
 }
+
+#[gpui::test]
+async fn test_project_comment_threads(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/dir", json!({ "a.txt": "one\ntwo\nthree\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+
+    // Client B comments on the second line.
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let range = buffer_b.read_with(cx_b, |buffer, _| {
+        buffer.anchor_before(4)..buffer.anchor_after(7)
+    });
+    let thread_id = project_b
+        .update(cx_b, |project, cx| {
+            project.create_comment_thread(&buffer_b, range, "Why two?".into(), cx)
+        })
+        .await
+        .unwrap();
+
+    // Client A sees the thread, and its range follows their edits.
+    executor.run_until_parked();
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    buffer_a.update(cx_a, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    executor.run_until_parked();
+    for (project, buffer, cx) in [
+        (&project_a, &buffer_a, &*cx_a),
+        (&project_b, &buffer_b, &*cx_b),
+    ] {
+        project.read_with(cx, |project, cx| {
+            let threads = project.comment_threads_for_buffer(buffer, cx);
+            assert_eq!(threads.len(), 1);
+            let (thread, range) = &threads[0];
+            assert_eq!(thread.id, thread_id);
+            assert_eq!(thread.comments.len(), 1);
+            assert_eq!(thread.comments[0].body, "Why two?");
+            assert_eq!(thread.comments[0].author_id, client_b.id());
+            let buffer = buffer.read(cx);
+            assert_eq!(
+                buffer
+                    .text_for_range(range.to_offset(buffer))
+                    .collect::<String>(),
+                "two"
+            );
+        });
+    }
+
+    // Client A replies, and client B is notified.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.reply_to_comment_thread(thread_id, "Because.".into(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        let thread = project.comment_thread(thread_id).unwrap();
+        assert_eq!(
+            thread
+                .comments
+                .iter()
+                .map(|comment| comment.body.as_str())
+                .collect::<Vec<_>>(),
+            ["Why two?", "Because."]
+        );
+    });
+    client_b.notification_store().read_with(cx_b, |store, _| {
+        assert_eq!(store.notification_count(), 1);
+        assert!(matches!(
+            &store.notification_at(0).unwrap().notification,
+            rpc::Notification::ProjectCommentReply { thread_id: id, sender_id, body, .. }
+                if *id == thread_id && *sender_id == client_a.id() && body == "Because."
+        ));
+    });
+    client_a.notification_store().read_with(cx_a, |store, _| {
+        assert_eq!(store.notification_count(), 0);
+    });
+
+    // Client A resolves the thread, and client B is notified.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.resolve_comment_thread(thread_id, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        let thread = project.comment_thread(thread_id).unwrap();
+        assert_eq!(thread.resolved_by, Some(client_a.id()));
+    });
+    client_b.notification_store().read_with(cx_b, |store, _| {
+        assert_eq!(store.notification_count(), 2);
+        assert_eq!(
+            store.notification_at(0).unwrap().notification,
+            rpc::Notification::ProjectCommentThreadResolved {
+                thread_id,
+                project_id,
+                resolver_id: client_a.id(),
+                worktree_id: worktree_id.to_proto(),
+                path: "a.txt".into(),
+            }
+        );
+    });
+
+    // Threads are cleared when the project is unshared, but the notifications about them
+    // are kept.
+    project_a.update(cx_a, |project, cx| project.unshare(cx).unwrap());
+    executor.run_until_parked();
+    project_a.read_with(cx_a, |project, _| {
+        assert_eq!(project.comment_threads().count(), 0);
+    });
+    project_b.read_with(cx_b, |project, _| {
+        assert!(project.comment_thread(thread_id).is_none());
+    });
+    client_b.notification_store().read_with(cx_b, |store, _| {
+        assert_eq!(store.notification_count(), 2);
+    });
+}
+
+#[gpui::test]
+async fn test_shared_terminals(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a.fs().insert_tree("/dir", json!({})).await;
+    let (project_a, _) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+
+    // Client A shares a terminal before client B joins the project.
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let terminal_a = cx_a.new_model(|cx| TerminalBuilder::new_fake(input_tx).subscribe(cx));
+    let terminal_id = terminal_a.entity_id().as_u64();
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ echo one\r\none\r\n$ ", cx)
+    });
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, false, cx)
+        })
+        .unwrap();
+
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    executor.run_until_parked();
+    let terminal_b = project_b.read_with(cx_b, |project, _| {
+        let terminals = project.remote_terminals().cloned().collect::<Vec<_>>();
+        assert_eq!(terminals.len(), 1);
+        terminals[0].clone()
+    });
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert!(terminal.is_remote());
+        assert!(!terminal.is_read_only());
+        assert_eq!(terminal.title(false), "Terminal");
+        assert_eq!(
+            terminal.last_n_non_empty_lines(3),
+            ["$ echo one", "one", "$"]
+        );
+    });
+
+    // Client B sees the output that client A's terminal prints afterwards.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"echo two\r\ntwo\r\n$ ", cx)
+    });
+    executor.advance_clock(Duration::from_millis(100));
+    executor.run_until_parked();
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert_eq!(
+            terminal.last_n_non_empty_lines(5),
+            ["$ echo one", "one", "$ echo two", "two", "$"]
+        );
+    });
+
+    // Client B's input is sent to client A's terminal.
+    terminal_b.update(cx_b, |terminal, _| terminal.input("pwd\r".into()));
+    executor.run_until_parked();
+    assert_eq!(input_rx.try_next().unwrap(), Some(b"pwd\r".to_vec()));
+
+    // Once the terminal is shared read-only, client B's input is rejected.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, true, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.is_read_only()));
+    terminal_b.update(cx_b, |terminal, _| terminal.input("exit\r".into()));
+    let response = client_b
+        .client()
+        .request(proto::TerminalInput {
+            project_id,
+            terminal_id,
+            input: b"exit\r".to_vec(),
+        })
+        .await;
+    assert!(response.is_err());
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+
+    // Once client B closes the terminal, it's shared with them as a new one when client A
+    // shares it again.
+    project_b.update(cx_b, |project, _| {
+        project.forget_remote_terminal(&terminal_b);
+        assert_eq!(project.remote_terminals().count(), 0);
+    });
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, false, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        let terminals = project.remote_terminals().collect::<Vec<_>>();
+        assert_eq!(terminals.len(), 1);
+        assert_ne!(terminals[0], &terminal_b);
+    });
+
+    // Client B's terminal is closed when client A stops sharing it.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.unshare_terminal(&terminal_a, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    project_b.read_with(cx_b, |project, _| {
+        assert_eq!(project.remote_terminals().count(), 0);
+    });
+    project_a.read_with(cx_a, |project, _| {
+        assert_eq!(project.shared_terminal_read_only(&terminal_a), None);
+    });
+}
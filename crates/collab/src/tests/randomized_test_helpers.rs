@@ -4,6 +4,7 @@ use crate::{
     tests::{TestClient, TestServer},
 };
 use async_trait::async_trait;
+use client::MAX_RECONNECTION_DELAY;
 use futures::StreamExt;
 use gpui::{BackgroundExecutor, Task, TestAppContext};
 use parking_lot::Mutex;
@@ -97,6 +98,10 @@ enum ServerOperation {
     BounceConnection {
         user_id: UserId,
     },
+    MutateClientWhileOffline {
+        user_id: UserId,
+        batch_id: usize,
+    },
     RestartServer,
     MutateClients {
         batch_id: usize,
@@ -433,7 +438,14 @@ impl<T: RandomizedTest> TestPlan<T> {
                     self.operation_ix += 1;
                     ServerOperation::BounceConnection { user_id }
                 }
-                40..=44 if self.allow_server_restarts && clients.len() > 1 => {
+                40..=44 if clients.len() > 1 && self.allow_client_reconnection => {
+                    let (client, cx) = &clients[self.rng.gen_range(0..clients.len())];
+                    let user_id = client.current_user_id(cx);
+                    let batch_id = util::post_inc(&mut self.next_batch_id);
+                    self.operation_ix += 1;
+                    ServerOperation::MutateClientWhileOffline { user_id, batch_id }
+                }
+                45..=49 if self.allow_server_restarts && clients.len() > 1 => {
                     self.operation_ix += 1;
                     ServerOperation::RestartServer
                 }
@@ -583,6 +595,39 @@ impl<T: RandomizedTest> TestPlan<T> {
                 deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
             }
 
+            ServerOperation::MutateClientWhileOffline { user_id, batch_id } => {
+                log::info!("simulating changes by user {} while offline", user_id);
+                let client_ix = clients
+                    .iter()
+                    .position(|(client, cx)| client.current_user_id(cx) == user_id);
+                let Some(client_ix) = client_ix else {
+                    return false;
+                };
+                let user_connection_ids = server
+                    .connection_pool
+                    .lock()
+                    .user_connection_ids(user_id)
+                    .collect::<Vec<_>>();
+                if user_connection_ids.is_empty() {
+                    return false;
+                }
+                assert_eq!(user_connection_ids.len(), 1);
+                let peer_id = user_connection_ids[0].into();
+                server.forbid_connections();
+                server.disconnect_client(peer_id);
+                deterministic.run_until_parked();
+
+                if let Err(err) = operation_channels[client_ix].unbounded_send(batch_id) {
+                    log::error!("error signaling user {user_id}: {err}");
+                }
+                deterministic.run_until_parked();
+
+                // Let the client reconnect before its room gives up on rejoining.
+                server.allow_connections();
+                deterministic.advance_clock(MAX_RECONNECTION_DELAY);
+                deterministic.run_until_parked();
+            }
+
             ServerOperation::RestartServer => {
                 log::info!("simulating server restart");
                 server.reset().await;
@@ -0,0 +1,145 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use futures::AsyncWrite;
+use gpui::{BackgroundExecutor, Context as _, TestAppContext};
+use parking_lot::Mutex;
+use serde_json::json;
+use session_replay::{ReplayState, SessionRecorder, SessionRecording};
+use std::{
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+#[gpui::test]
+async fn test_recording_and_replaying_a_session(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+
+    // Clients A and B join the channel's call, and client A shares a project.
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    for (call, cx) in [(&active_call_a, &mut *cx_a), (&active_call_b, &mut *cx_b)] {
+        call.update(cx, |call, cx| call.join_channel(channel_id, cx))
+            .await
+            .unwrap();
+    }
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "hello" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_dev_server_project(project_id, cx_b).await;
+    for (call, project, cx) in [
+        (&active_call_a, &project_a, &mut *cx_a),
+        (&active_call_b, &project_b, &mut *cx_b),
+    ] {
+        call.update(cx, |call, cx| call.set_location(Some(project), cx))
+            .await
+            .unwrap();
+    }
+    let _buffer_a = project_a
+        .update(cx_a, |project, cx| {
+            project.open_buffer((worktree_id, "a.txt"), cx)
+        })
+        .await
+        .unwrap();
+
+    // Client A records the session.
+    let recording = RecordingBuffer::default();
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    let recorder =
+        cx_a.new_model(|cx| SessionRecorder::new(project_a.clone(), room_a, recording.clone(), cx));
+    executor.run_until_parked();
+
+    // Client B edits the buffer, follows client A and sends a message in the chat.
+    let buffer_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_buffer((worktree_id, "a.txt"), cx)
+        })
+        .await
+        .unwrap();
+    buffer_b.update(cx_b, |buffer, cx| buffer.edit([(5..5, " world")], None, cx));
+
+    let (_workspace_a, _) = client_a.build_workspace(&project_a, cx_a);
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+    let leader_id = project_b.update(cx_b, |project, _| {
+        project.collaborators().values().next().unwrap().peer_id
+    });
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(leader_id, cx));
+
+    let chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    chat_b
+        .update(cx_b, |chat, cx| chat.send_message("hi".into(), cx).unwrap())
+        .await
+        .unwrap();
+
+    executor.advance_clock(Duration::from_secs(1));
+    executor.run_until_parked();
+    recorder.update(cx_a, |recorder, cx| recorder.stop(cx));
+    executor.run_until_parked();
+
+    // Replaying the session reconstructs the buffer and the log of what happened.
+    let recording = SessionRecording::parse(&recording.0.lock()).unwrap();
+    let mut replay = ReplayState::default();
+    cx_a.update(|cx| replay.seek(&recording, recording.events.len(), cx));
+    let buffers = replay.buffers();
+    assert_eq!(buffers.len(), 1);
+    assert_eq!(buffers[0].path, "a/a.txt");
+    assert_eq!(
+        buffers[0].buffer.read_with(cx_a, |buffer, _| buffer.text()),
+        "hello world"
+    );
+    assert_eq!(
+        replay
+            .log()
+            .iter()
+            .map(|entry| entry.text.as_str())
+            .collect::<Vec<_>>(),
+        ["user_b started following user_a", "user_b: hi"]
+    );
+}
+
+/// Collects the bytes of a recording in memory.
+#[derive(Clone, Default)]
+struct RecordingBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl AsyncWrite for RecordingBuffer {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.0.lock().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -660,6 +660,7 @@ impl TestServer {
                 migrations_path: None,
                 seed_path: None,
                 supermaven_admin_api_key: None,
+                self_hosted: None,
             },
         })
     }
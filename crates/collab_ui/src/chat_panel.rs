@@ -1,7 +1,10 @@
 use crate::{collab_panel, ChatPanelSettings};
 use anyhow::Result;
 use call::{room, ActiveCall};
-use channel::{ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore};
+use channel::{
+    ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore,
+    MessageSearchResults,
+};
 use client::{ChannelId, Client};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
@@ -35,6 +38,7 @@ mod message_editor;
 
 const MESSAGE_LOADING_THRESHOLD: usize = 50;
 const CHAT_PANEL_KEY: &str = "ChatPanel";
+const REACTIONS: [&str; 6] = ["👍", "👎", "😄", "🎉", "❤️", "👀"];
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
@@ -64,6 +68,29 @@ pub struct ChatPanel {
     open_context_menu: Option<(u64, Subscription)>,
     highlighted_message: Option<(u64, Task<()>)>,
     last_acknowledged_message_id: Option<u64>,
+    search_editor: View<Editor>,
+    message_search: Option<MessageSearch>,
+    open_thread: Option<u64>,
+}
+
+struct MessageSearch {
+    query: String,
+    results: Option<Vec<ChannelMessage>>,
+    has_more_results: bool,
+    is_pending: bool,
+    _search: Task<()>,
+}
+
+impl Default for MessageSearch {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            results: None,
+            has_more_results: false,
+            is_pending: false,
+            _search: Task::ready(()),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -111,6 +138,12 @@ impl ChatPanel {
                 this.is_scrolled_to_bottom = !event.is_scrolled;
             }));
 
+            let search_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Search messages…", cx);
+                editor
+            });
+
             let mut this = Self {
                 fs,
                 client,
@@ -130,6 +163,9 @@ impl ChatPanel {
                 open_context_menu: None,
                 highlighted_message: None,
                 last_acknowledged_message_id: None,
+                search_editor,
+                message_search: None,
+                open_thread: None,
             };
 
             if let Some(channel_id) = ActiveCall::global(cx)
@@ -241,6 +277,8 @@ impl ChatPanel {
             });
             let subscription = cx.subscribe(&chat, Self::channel_did_change);
             self.active_chat = Some((chat, subscription));
+            self.message_search = None;
+            self.open_thread = None;
             self.acknowledge_last_message(cx);
             cx.notify();
         }
@@ -437,6 +475,15 @@ impl ChatPanel {
             .and_then(|id| active_chat.read(cx).find_loaded_message(id))
             .cloned();
 
+        let reply_count = match message_id {
+            Some(id) if message.reply_to_message_id.is_none() => active_chat
+                .read(cx)
+                .thread_messages(id)
+                .len()
+                .saturating_sub(1),
+            _ => 0,
+        };
+
         let replied_to_you =
             reply_to_message.as_ref().map(|m| m.sender.id) == self.client.user_id();
 
@@ -541,6 +588,24 @@ impl ChatPanel {
                         .when(self.has_open_menu(message_id), |el| {
                             el.bg(cx.theme().colors().element_selected)
                         })
+                    })
+                    .children(self.render_reactions(&message, cx))
+                    .when_some(message_id.filter(|_| reply_count > 0), |el, message_id| {
+                        let label = if reply_count == 1 {
+                            "1 reply".to_string()
+                        } else {
+                            format!("{reply_count} replies")
+                        };
+                        el.child(
+                            Button::new(("view-thread", message_id), label)
+                                .label_size(LabelSize::XSmall)
+                                .color(Color::Accent)
+                                .on_click(
+                                    cx.listener(move |this, _, cx| {
+                                        this.open_thread(message_id, cx)
+                                    }),
+                                ),
+                        )
                     }),
             )
             .when(
@@ -674,6 +739,28 @@ impl ChatPanel {
             .when_some(message_id, |el, message_id| {
                 let this = cx.view().clone();
 
+                el.child(
+                    self.render_popover_button(
+                        cx,
+                        div()
+                            .child(
+                                PopoverMenu::new(("reaction-menu", message_id))
+                                    .trigger(IconButton::new(
+                                        ("reaction-trigger", message_id),
+                                        IconName::Plus,
+                                    ))
+                                    .menu(move |cx| {
+                                        Some(Self::render_reaction_menu(&this, message_id, cx))
+                                    }),
+                            )
+                            .id("react")
+                            .tooltip(|cx| Tooltip::text("Add reaction", cx)),
+                    ),
+                )
+            })
+            .when_some(message_id, |el, message_id| {
+                let this = cx.view().clone();
+
                 el.child(
                     self.render_popover_button(
                         cx,
@@ -720,6 +807,11 @@ impl ChatPanel {
                         }
                     }),
                 )
+                .entry(
+                    "View thread",
+                    None,
+                    cx.handler_for(&this, move |this, cx| this.open_thread(message_id, cx)),
+                )
                 .when(can_delete_message, |menu| {
                     menu.entry(
                         "Delete message",
@@ -729,13 +821,376 @@ impl ChatPanel {
                 })
             })
         };
+        Self::track_open_menu(this, message_id, &menu, cx);
+        menu
+    }
+
+    fn render_reaction_menu(
+        this: &View<Self>,
+        message_id: u64,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        let menu = ContextMenu::build(cx, move |menu, cx| {
+            REACTIONS.into_iter().fold(menu, |menu, emoji| {
+                menu.entry(
+                    emoji,
+                    None,
+                    cx.handler_for(&this, move |this, cx| {
+                        this.toggle_reaction(message_id, emoji, cx)
+                    }),
+                )
+            })
+        });
+        Self::track_open_menu(this, message_id, &menu, cx);
+        menu
+    }
+
+    fn track_open_menu(
+        this: &View<Self>,
+        message_id: u64,
+        menu: &View<ContextMenu>,
+        cx: &mut WindowContext,
+    ) {
         this.update(cx, |this, cx| {
-            let subscription = cx.subscribe(&menu, |this: &mut Self, _, _: &DismissEvent, _| {
+            let subscription = cx.subscribe(menu, |this: &mut Self, _, _: &DismissEvent, _| {
                 this.open_context_menu = None;
             });
             this.open_context_menu = Some((message_id, subscription));
         });
-        menu
+    }
+
+    fn render_reactions(
+        &self,
+        message: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<impl IntoElement> {
+        let ChannelMessageId::Saved(message_id) = message.id else {
+            return None;
+        };
+        if message.reactions.is_empty() {
+            return None;
+        }
+
+        let current_user_id = self.client.user_id();
+        Some(
+            h_flex()
+                .flex_wrap()
+                .gap_1()
+                .pt_0p5()
+                .children(message.reactions.iter().map(|reaction| {
+                    let emoji = reaction.emoji.clone();
+                    let has_reacted = current_user_id
+                        .map_or(false, |user_id| reaction.user_ids.contains(&user_id));
+                    Button::new(
+                        ElementId::Name(format!("reaction-{message_id}-{emoji}").into()),
+                        format!("{emoji} {}", reaction.user_ids.len()),
+                    )
+                    .style(ButtonStyle::Filled)
+                    .label_size(LabelSize::XSmall)
+                    .selected(has_reacted)
+                    .on_click(
+                        cx.listener(move |this, _, cx| {
+                            this.toggle_reaction(message_id, &emoji, cx)
+                        }),
+                    )
+                })),
+        )
+    }
+
+    fn toggle_reaction(&mut self, message_id: u64, emoji: &str, cx: &mut ViewContext<Self>) {
+        if let Some((chat, _)) = self.active_chat.as_ref() {
+            if let Some(task) = chat
+                .update(cx, |chat, cx| chat.toggle_reaction(message_id, emoji, cx))
+                .log_err()
+            {
+                task.detach_and_log_err(cx);
+            }
+        }
+    }
+
+    /// Shows the thread that the given message belongs to, starting from its earliest loaded
+    /// ancestor.
+    fn open_thread(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(chat) = self.active_chat() else {
+            return;
+        };
+        let mut root_id = message_id;
+        while let Some(parent_id) = chat
+            .read(cx)
+            .find_loaded_message(root_id)
+            .and_then(|message| message.reply_to_message_id)
+        {
+            root_id = parent_id;
+        }
+
+        self.open_thread = Some(root_id);
+        chat.update(cx, |chat, cx| chat.load_thread(root_id, cx))
+            .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    fn close_thread(&mut self, cx: &mut ViewContext<Self>) {
+        self.open_thread = None;
+        cx.notify();
+    }
+
+    fn render_thread(&mut self, root_id: u64, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let messages = self
+            .active_chat()
+            .map(|chat| {
+                chat.read(cx)
+                    .thread_messages(root_id)
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .py_1()
+                    .gap_1()
+                    .child(
+                        IconButton::new("close-thread", IconName::ArrowLeft)
+                            .shape(ui::IconButtonShape::Square)
+                            .tooltip(|cx| Tooltip::text("Back to channel", cx))
+                            .on_click(cx.listener(|this, _, cx| this.close_thread(cx))),
+                    )
+                    .child(Label::new("Thread").size(LabelSize::Small)),
+            )
+            .child(
+                v_flex()
+                    .id("thread-messages")
+                    .size_full()
+                    .overflow_y_scroll()
+                    .gap_2()
+                    .children(messages.iter().enumerate().map(|(ix, message)| {
+                        div()
+                            .when(ix > 0, |el| el.pl_4())
+                            .child(self.render_thread_message(message, cx))
+                    })),
+            )
+    }
+
+    fn render_thread_message(
+        &mut self,
+        message: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let element_id: ElementId = match message.id {
+            ChannelMessageId::Saved(id) => ("thread-saved-message", id).into(),
+            ChannelMessageId::Pending(id) => ("thread-pending-message", id).into(),
+        };
+        let text = self.markdown_data.entry(message.id).or_insert_with(|| {
+            Self::render_markdown_with_mentions(
+                &self.languages,
+                self.client.id(),
+                message,
+                self.local_timezone,
+                cx,
+            )
+        });
+        let body = text.element("body".into(), cx);
+
+        v_flex()
+            .w_full()
+            .px_1p5()
+            .child(
+                h_flex()
+                    .text_ui_sm(cx)
+                    .gap_1()
+                    .child(Avatar::new(message.sender.avatar_uri.clone()).size(rems(1.)))
+                    .child(div().font_weight(FontWeight::BOLD).child(
+                        Label::new(message.sender.github_login.clone()).size(LabelSize::Small),
+                    ))
+                    .child(
+                        Label::new(time_format::format_localized_timestamp(
+                            message.timestamp,
+                            OffsetDateTime::now_utc(),
+                            self.local_timezone,
+                            time_format::TimestampFormat::EnhancedAbsolute,
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            )
+            .child(v_flex().w_full().text_ui_sm(cx).id(element_id).child(body))
+            .children(self.render_reactions(message, cx))
+    }
+
+    fn toggle_message_search(&mut self, cx: &mut ViewContext<Self>) {
+        if self.message_search.take().is_none() {
+            self.message_search = Some(MessageSearch::default());
+            cx.focus_view(&self.search_editor);
+        }
+        cx.notify();
+    }
+
+    fn search_messages(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let Some(chat) = self.active_chat() else {
+            return;
+        };
+        let Some(search) = self.message_search.as_mut() else {
+            return;
+        };
+
+        let query = self.search_editor.read(cx).text(cx);
+        *search = MessageSearch::default();
+        if query.trim().is_empty() {
+            cx.notify();
+            return;
+        }
+
+        search.query = query.clone();
+        search.is_pending = true;
+        let results = chat.update(cx, |chat, cx| chat.search_messages(query, None, cx));
+        search._search = Self::receive_search_results(results, cx);
+        cx.notify();
+    }
+
+    fn load_more_search_results(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(chat) = self.active_chat() else {
+            return;
+        };
+        let Some(search) = self.message_search.as_mut() else {
+            return;
+        };
+        if search.is_pending || !search.has_more_results {
+            return;
+        }
+        let oldest_result_id: Option<u64> = search
+            .results
+            .as_ref()
+            .and_then(|results| results.last())
+            .and_then(|message| message.id.into());
+        let Some(oldest_result_id) = oldest_result_id else {
+            return;
+        };
+
+        search.is_pending = true;
+        let query = search.query.clone();
+        let results = chat.update(cx, |chat, cx| {
+            chat.search_messages(query, Some(oldest_result_id), cx)
+        });
+        search._search = Self::receive_search_results(results, cx);
+        cx.notify();
+    }
+
+    fn receive_search_results(
+        results: Task<Result<MessageSearchResults>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<()> {
+        cx.spawn(|this, mut cx| async move {
+            let results = results.await.log_err();
+            this.update(&mut cx, |this, cx| {
+                if let Some(search) = this.message_search.as_mut() {
+                    let (messages, has_more) = results.map_or((Vec::new(), false), |results| {
+                        (results.messages, results.has_more)
+                    });
+                    search.results.get_or_insert_with(Vec::new).extend(messages);
+                    search.has_more_results = has_more;
+                    search.is_pending = false;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+    }
+
+    fn select_search_result(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        self.message_search = None;
+        self.open_thread = None;
+        if let Some(channel_id) = self.channel_id(cx) {
+            self.select_channel(channel_id, Some(message_id), cx)
+                .detach_and_log_err(cx);
+        }
+        cx.notify();
+    }
+
+    fn render_search_results(
+        &self,
+        search: &MessageSearch,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let results = search.results.as_deref().unwrap_or_default();
+
+        v_flex()
+            .id("search-results")
+            .size_full()
+            .overflow_y_scroll()
+            .gap_1()
+            .when(search.is_pending, |el| {
+                el.child(
+                    Label::new("Searching…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .when(!search.is_pending && results.is_empty(), |el| {
+                el.child(
+                    Label::new("No messages found.")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .children(results.iter().filter_map(|message| {
+                let ChannelMessageId::Saved(message_id) = message.id else {
+                    return None;
+                };
+                Some(
+                    h_flex()
+                        .id(("search-result", message_id))
+                        .w_full()
+                        .items_start()
+                        .gap_2()
+                        .px_1p5()
+                        .py_0p5()
+                        .rounded_md()
+                        .overflow_hidden()
+                        .cursor(CursorStyle::PointingHand)
+                        .hover(|style| style.bg(cx.theme().colors().element_hover))
+                        .child(Avatar::new(message.sender.avatar_uri.clone()).size(rems(1.)))
+                        .child(
+                            v_flex()
+                                .overflow_hidden()
+                                .child(
+                                    h_flex()
+                                        .gap_1()
+                                        .child(
+                                            Label::new(message.sender.github_login.clone())
+                                                .size(LabelSize::Small),
+                                        )
+                                        .child(
+                                            Label::new(time_format::format_localized_timestamp(
+                                                message.timestamp,
+                                                OffsetDateTime::now_utc(),
+                                                self.local_timezone,
+                                                time_format::TimestampFormat::EnhancedAbsolute,
+                                            ))
+                                            .size(LabelSize::XSmall)
+                                            .color(Color::Muted),
+                                        ),
+                                )
+                                .child(
+                                    Label::new(message.body.replace('\n', " "))
+                                        .size(LabelSize::Small),
+                                ),
+                        )
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.select_search_result(message_id, cx)
+                        })),
+                )
+            }))
+            .when(!search.is_pending && search.has_more_results, |el| {
+                el.child(
+                    Button::new("load-more-search-results", "Load more")
+                        .label_size(LabelSize::Small)
+                        .color(Color::Accent)
+                        .on_click(cx.listener(|this, _, cx| this.load_more_search_results(cx))),
+                )
+            })
     }
 
     fn render_markdown_with_mentions(
@@ -793,7 +1248,7 @@ impl ChatPanel {
 
     fn send(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
-            let message = self
+            let mut message = self
                 .message_editor
                 .update(cx, |editor, cx| editor.take_message(cx));
 
@@ -809,6 +1264,10 @@ impl ChatPanel {
                     task.detach();
                 }
             } else {
+                // Messages sent while a thread is open reply to the thread's root message.
+                if message.reply_to_message_id.is_none() {
+                    message.reply_to_message_id = self.open_thread;
+                }
                 if let Some(task) = chat
                     .update(cx, |chat, cx| chat.send_message(message, cx))
                     .log_err()
@@ -949,6 +1408,7 @@ impl Render for ChatPanel {
                             .w_full()
                             .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
                             .px_2()
+                            .justify_between()
                             .child(Label::new(
                                 self.active_chat
                                     .as_ref()
@@ -956,13 +1416,54 @@ impl Render for ChatPanel {
                                         Some(format!("#{}", c.0.read(cx).channel(cx)?.name))
                                     })
                                     .unwrap_or("Chat".to_string()),
-                            )),
+                            ))
+                            .when(self.active_chat.is_some(), |el| {
+                                el.child(
+                                    IconButton::new("search-messages", IconName::MagnifyingGlass)
+                                        .shape(ui::IconButtonShape::Square)
+                                        .selected(self.message_search.is_some())
+                                        .tooltip(|cx| Tooltip::text("Search messages", cx))
+                                        .on_click(cx.listener(|this, _, cx| {
+                                            this.toggle_message_search(cx)
+                                        })),
+                                )
+                            }),
                     ),
                 ),
             )
+            .when(self.message_search.is_some(), |el| {
+                el.child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .gap_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border)
+                        .on_action(cx.listener(Self::search_messages))
+                        .child(div().flex_1().child(self.search_editor.clone()))
+                        .child(
+                            IconButton::new("close-message-search", IconName::Close)
+                                .shape(ui::IconButtonShape::Square)
+                                .tooltip(|cx| Tooltip::text("Close search", cx))
+                                .on_click(
+                                    cx.listener(|this, _, cx| this.toggle_message_search(cx)),
+                                ),
+                        ),
+                )
+            })
             .child(div().flex_grow().px_2().map(|this| {
                 if self.active_chat.is_some() {
-                    this.child(list(self.message_list.clone()).size_full())
+                    if let Some(search) = self
+                        .message_search
+                        .as_ref()
+                        .filter(|search| search.is_pending || search.results.is_some())
+                    {
+                        this.child(self.render_search_results(search, cx))
+                    } else if let Some(root_id) = self.open_thread {
+                        this.child(self.render_thread(root_id, cx))
+                    } else {
+                        this.child(list(self.message_list.clone()).size_full())
+                    }
                 } else {
                     this.child(
                         div()
@@ -1189,6 +1690,7 @@ mod tests {
             mentions: vec![(ranges[0].clone(), 101), (ranges[1].clone(), 102)],
             reply_to_message_id: None,
             edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1243,6 +1745,7 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1290,6 +1793,7 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -4,6 +4,7 @@ use channel::ChannelStore;
 use client::{ChannelId, Client, Notification, User, UserStore};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
 use futures::StreamExt;
 use gpui::{
     actions, div, img, list, px, AnyElement, AppContext, AsyncWindowContext, CursorStyle,
@@ -13,11 +14,11 @@ use gpui::{
     WindowContext,
 };
 use notifications::{NotificationEntry, NotificationEvent, NotificationStore};
-use project::Fs;
+use project::{Fs, ProjectPath, WorktreeId};
 use rpc::proto;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
-use std::{sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{h_flex, prelude::*, v_flex, Avatar, Button, Icon, IconButton, IconName, Label, Tooltip};
 use util::{ResultExt, TryFutureExt};
@@ -405,9 +406,54 @@ impl NotificationPanel {
                     can_navigate: true,
                 })
             }
+            Notification::ProjectCommentReply {
+                sender_id,
+                thread_id,
+                project_id,
+                ref path,
+                ref body,
+                ..
+            } => {
+                let sender = user_store.get_cached_user(sender_id)?;
+                Some(NotificationPresenter {
+                    icon: "icons/conversations.svg",
+                    text: format!(
+                        "{} replied to your comment on {path}:\n{body}",
+                        sender.github_login
+                    ),
+                    needs_response: false,
+                    actor: Some(sender),
+                    can_navigate: self.has_comment_thread(project_id, thread_id, cx),
+                })
+            }
+            Notification::ProjectCommentThreadResolved {
+                resolver_id,
+                thread_id,
+                project_id,
+                ref path,
+                ..
+            } => {
+                let resolver = user_store.get_cached_user(resolver_id)?;
+                Some(NotificationPresenter {
+                    icon: "icons/check.svg",
+                    text: format!("{} resolved your comment on {path}", resolver.github_login),
+                    needs_response: false,
+                    actor: Some(resolver),
+                    can_navigate: self.has_comment_thread(project_id, thread_id, cx),
+                })
+            }
         }
     }
 
+    /// Returns whether the given comment thread can be shown. Notifications about threads
+    /// outlive them, since threads are deleted when their project is unshared.
+    fn has_comment_thread(&self, project_id: u64, thread_id: u64, cx: &AppContext) -> bool {
+        self.workspace.upgrade().map_or(false, |workspace| {
+            let project = workspace.read(cx).project().read(cx);
+            project.remote_id() == Some(project_id) && project.comment_thread(thread_id).is_some()
+        })
+    }
+
     fn did_render_notification(
         &mut self,
         notification_id: u64,
@@ -415,10 +461,12 @@ impl NotificationPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let should_mark_as_read = match notification {
-            Notification::ContactRequestAccepted { .. } => true,
+            Notification::ContactRequestAccepted { .. }
+            | Notification::ProjectCommentThreadResolved { .. } => true,
             Notification::ContactRequest { .. }
             | Notification::ChannelInvitation { .. }
-            | Notification::ChannelMessageMention { .. } => false,
+            | Notification::ChannelMessageMention { .. }
+            | Notification::ProjectCommentReply { .. } => false,
         };
 
         if should_mark_as_read {
@@ -461,6 +509,51 @@ impl NotificationPanel {
                 });
             }
         }
+
+        if let Notification::ProjectCommentReply {
+            thread_id,
+            project_id,
+            worktree_id,
+            path,
+            ..
+        }
+        | Notification::ProjectCommentThreadResolved {
+            thread_id,
+            project_id,
+            worktree_id,
+            path,
+            ..
+        } = notification.clone()
+        {
+            if let Some(workspace) = self.workspace.upgrade() {
+                cx.window_context().defer(move |cx| {
+                    workspace.update(cx, |workspace, cx| {
+                        // The thread can only be shown while its project is shared and open.
+                        let project = workspace.project().read(cx);
+                        if project.remote_id() != Some(project_id)
+                            || project.comment_thread(thread_id).is_none()
+                        {
+                            return;
+                        }
+                        let project_path = ProjectPath {
+                            worktree_id: WorktreeId::from_proto(worktree_id),
+                            path: Path::new(&path).into(),
+                        };
+                        let open_path = workspace.open_path(project_path, None, true, cx);
+                        cx.spawn(|_, mut cx| async move {
+                            let item = open_path.await?;
+                            if let Some(editor) = item.downcast::<Editor>() {
+                                editor.update(&mut cx, |editor, cx| {
+                                    editor.reveal_comment_thread(thread_id, cx)
+                                })?;
+                            }
+                            anyhow::Ok(())
+                        })
+                        .detach_and_log_err(cx);
+                    });
+                });
+            }
+        }
     }
 
     fn is_showing_notification(&self, notification: &Notification, cx: &ViewContext<Self>) -> bool {
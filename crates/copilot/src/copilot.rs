@@ -1254,7 +1254,7 @@ mod tests {
             self.abs_path.clone()
         }
 
-        fn load(&self, _: &AppContext) -> Task<Result<String>> {
+        fn load(&self, _: language::Encoding, _: &AppContext) -> Task<Result<String>> {
             unimplemented!()
         }
     }
@@ -132,6 +132,76 @@ pub struct ShowCompletions {
     pub(super) trigger: Option<char>,
 }
 
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+pub struct InsertNumberSequence {
+    #[serde(default = "default_one")]
+    pub start: i64,
+    #[serde(default = "default_one")]
+    pub step: i64,
+    /// The minimum number of digits, padded with leading zeros.
+    #[serde(default)]
+    pub width: usize,
+    #[serde(default)]
+    pub format: NumberSequenceFormat,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberSequenceFormat {
+    #[default]
+    Decimal,
+    LowerHex,
+    UpperHex,
+    Binary,
+}
+
+impl NumberSequenceFormat {
+    /// Formats the number with at least `width` digits. Negative numbers are formatted as
+    /// a minus sign followed by their magnitude, rather than their two's complement.
+    pub fn format(&self, number: i64, width: usize) -> String {
+        let magnitude = number.unsigned_abs();
+        let digits = match self {
+            NumberSequenceFormat::Decimal => format!("{magnitude:0width$}"),
+            NumberSequenceFormat::LowerHex => format!("{magnitude:0width$x}"),
+            NumberSequenceFormat::UpperHex => format!("{magnitude:0width$X}"),
+            NumberSequenceFormat::Binary => format!("{magnitude:0width$b}"),
+        };
+        if number < 0 {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+}
+
+fn default_one() -> i64 {
+    1
+}
+
+impl Default for InsertNumberSequence {
+    fn default() -> Self {
+        Self {
+            start: default_one(),
+            step: default_one(),
+            width: 0,
+            format: NumberSequenceFormat::default(),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct SplitSelectionsByRegex {
+    pub pattern: String,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct KeepSelectionsMatching {
+    pub pattern: String,
+    /// Keep the selections that don't match the pattern instead.
+    #[serde(default)]
+    pub invert: bool,
+}
+
 impl_actions!(
     editor,
     [
@@ -141,6 +211,8 @@ impl_actions!(
         ExpandExcerptsUp,
         ExpandExcerptsDown,
         FoldAt,
+        InsertNumberSequence,
+        KeepSelectionsMatching,
         MoveDownByLines,
         MovePageDown,
         MovePageUp,
@@ -154,6 +226,7 @@ impl_actions!(
         SelectToEndOfLine,
         SelectUpByLines,
         ShowCompletions,
+        SplitSelectionsByRegex,
         ToggleCodeActions,
         ToggleComments,
         UnfoldAt,
@@ -168,6 +241,7 @@ gpui::actions!(
         AcceptPartialInlineCompletion,
         AddSelectionAbove,
         AddSelectionBelow,
+        AlignSelections,
         Backspace,
         Cancel,
         CancelLanguageServerWork,
@@ -240,6 +314,7 @@ gpui::actions!(
         MoveToPreviousWordStart,
         MoveToStartOfParagraph,
         MoveUp,
+        NewCommentThread,
         Newline,
         NewlineAbove,
         NewlineBelow,
@@ -292,6 +367,7 @@ gpui::actions!(
         SplitSelectionIntoLines,
         Tab,
         TabPrev,
+        ToggleCommentThread,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleSelectionMenu,
@@ -312,4 +388,8 @@ gpui::actions!(
 
 action_as!(outline, ToggleOutline as Toggle);
 
+action_as!(undo_tree, ToggleUndoTree as Toggle);
+
+action_as!(local_history, ToggleLocalHistory as Toggle);
+
 action_as!(go_to_line, ToggleGoToLine as Toggle);
@@ -0,0 +1,667 @@
+//! Shows the comment threads that collaborators leave in a shared project: a marker in the
+//! gutter next to each commented range, and a block below it that expands to show the
+//! conversation, so that it can be replied to or resolved.
+
+use std::ops::Range;
+
+use collections::{HashMap, HashSet};
+use gpui::{AppContext, EventEmitter, FocusHandle, FocusableView, Model, Subscription, Task, View};
+use language::{Buffer, Point};
+use multi_buffer::{Anchor, MultiBufferRow, ToPoint as _};
+use project::{comments::CommentThread, Project};
+use time::OffsetDateTime;
+use ui::{prelude::*, Avatar, Tooltip};
+use util::ResultExt;
+
+use crate::{
+    scroll::Autoscroll, BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle,
+    Editor, EditorEvent, NewCommentThread, RenderBlock, ToggleCommentThread,
+};
+
+/// The most lines that the editor for a comment grows to before it scrolls.
+const MAX_COMMENT_EDITOR_LINES: usize = 6;
+
+struct OpenCommentThreads;
+struct ResolvedCommentThreads;
+
+#[derive(Default)]
+pub(crate) struct CommentThreadBlocks {
+    threads: HashMap<u64, CommentThreadBlock>,
+    draft: Option<CommentThreadBlock>,
+}
+
+struct CommentThreadBlock {
+    block_id: BlockId,
+    height: u8,
+    view: View<CommentThreadView>,
+    _subscription: Subscription,
+}
+
+impl Editor {
+    /// Starts a comment thread on the newest selection, or on the line containing the cursor.
+    pub fn new_comment_thread(&mut self, _: &NewCommentThread, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        if !project.read(cx).is_shared() && !project.read(cx).is_remote() {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selection = self.selections.newest::<usize>(cx);
+        let mut range = selection.range();
+        if range.is_empty() {
+            let row = snapshot.offset_to_point(range.start).row;
+            let line_len = snapshot.line_len(MultiBufferRow(row));
+            range = snapshot.point_to_offset(Point::new(row, 0))
+                ..snapshot.point_to_offset(Point::new(row, line_len));
+        }
+        let Some((buffer, buffer_range)) = self
+            .buffer
+            .read(cx)
+            .range_to_buffer_ranges(range.clone(), cx)
+            .into_iter()
+            .next()
+            .map(|(buffer, range, _)| (buffer, range))
+        else {
+            return;
+        };
+        let buffer_range = {
+            let buffer = buffer.read(cx);
+            buffer.anchor_before(buffer_range.start)..buffer.anchor_after(buffer_range.end)
+        };
+
+        if let Some(draft) = self.comment_thread_blocks.draft.take() {
+            self.remove_blocks(HashSet::from_iter([draft.block_id]), None, cx);
+        }
+        let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+        let view = cx.new_view(|cx| {
+            CommentThreadView::draft(project, buffer, buffer_range, range.clone(), cx)
+        });
+        let block = self.insert_comment_thread_block(range.end, view.clone(), cx);
+        self.comment_thread_blocks.draft = Some(block);
+        view.focus_handle(cx).focus(cx);
+    }
+
+    /// Expands or collapses the comment threads on the lines of the newest selection.
+    pub fn toggle_comment_thread(&mut self, _: &ToggleCommentThread, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selection = self.selections.newest::<usize>(cx);
+        let selected_rows = snapshot.offset_to_point(selection.start).row
+            ..=snapshot.offset_to_point(selection.end).row;
+        let views = self
+            .comment_thread_blocks
+            .threads
+            .values()
+            .map(|block| block.view.clone())
+            .collect::<Vec<_>>();
+        for view in views {
+            let range = view.read(cx).range.clone();
+            let thread_rows =
+                range.start.to_point(&snapshot).row..=range.end.to_point(&snapshot).row;
+            if thread_rows.start() <= selected_rows.end()
+                && selected_rows.start() <= thread_rows.end()
+            {
+                view.update(cx, |view, cx| view.toggle_expanded(cx));
+            }
+        }
+    }
+
+    /// Scrolls to the given thread and expands it.
+    pub fn reveal_comment_thread(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(view) = self
+            .comment_thread_blocks
+            .threads
+            .get(&thread_id)
+            .map(|block| block.view.clone())
+        else {
+            return;
+        };
+        let position = view.read(cx).range.start;
+        self.change_selections(Some(Autoscroll::center()), cx, |selections| {
+            selections.select_anchor_ranges([position..position]);
+        });
+        view.update(cx, |view, cx| view.set_expanded(true, cx));
+    }
+
+    /// Shows the project's threads on the buffers in this editor, after they changed or
+    /// excerpts were added or removed.
+    pub(crate) fn refresh_comment_threads(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut threads = Vec::new();
+        for buffer in multi_buffer.all_buffers() {
+            let excerpts = multi_buffer.excerpts_for_buffer(&buffer, cx);
+            let buffer_snapshot = buffer.read(cx).text_snapshot();
+            for (thread, range) in project.read(cx).comment_threads_for_buffer(&buffer, cx) {
+                // Show the thread in the first excerpt that contains its range.
+                let excerpt_id = excerpts.iter().find_map(|(excerpt_id, excerpt_range)| {
+                    let context = &excerpt_range.context;
+                    (context.start.cmp(&range.end, &buffer_snapshot).is_le()
+                        && range.start.cmp(&context.end, &buffer_snapshot).is_le())
+                    .then_some(*excerpt_id)
+                });
+                if let Some(range) = excerpt_id.and_then(|excerpt_id| {
+                    Some(
+                        snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                            ..snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                    )
+                }) {
+                    threads.push((thread.id, thread.is_resolved(), range));
+                }
+            }
+        }
+
+        let (resolved, open): (Vec<_>, Vec<_>) =
+            threads.iter().partition(|(_, is_resolved, _)| *is_resolved);
+        let open = open
+            .into_iter()
+            .map(|(_, _, range)| range.clone())
+            .collect::<Vec<_>>();
+        let resolved = resolved
+            .into_iter()
+            .map(|(_, _, range)| range.clone())
+            .collect::<Vec<_>>();
+        self.highlight_gutter::<OpenCommentThreads>(&open, |cx| cx.theme().status().info, cx);
+        self.highlight_gutter::<ResolvedCommentThreads>(
+            &resolved,
+            |cx| cx.theme().status().hint_border,
+            cx,
+        );
+
+        let thread_ids = threads.iter().map(|(id, _, _)| *id).collect::<HashSet<_>>();
+        let mut removed_blocks = HashSet::default();
+        self.comment_thread_blocks
+            .threads
+            .retain(|thread_id, block| {
+                let retain = thread_ids.contains(thread_id);
+                if !retain {
+                    removed_blocks.insert(block.block_id);
+                }
+                retain
+            });
+        if !removed_blocks.is_empty() {
+            self.remove_blocks(removed_blocks, None, cx);
+        }
+
+        for (thread_id, _, range) in threads {
+            if self.comment_thread_blocks.threads.contains_key(&thread_id) {
+                continue;
+            }
+            let view = cx.new_view(|cx| {
+                CommentThreadView::thread(project.clone(), thread_id, range.clone(), cx)
+            });
+            let block = self.insert_comment_thread_block(range.end, view, cx);
+            self.comment_thread_blocks.threads.insert(thread_id, block);
+        }
+        self.resize_comment_thread_blocks(cx);
+    }
+
+    fn insert_comment_thread_block(
+        &mut self,
+        position: Anchor,
+        view: View<CommentThreadView>,
+        cx: &mut ViewContext<Self>,
+    ) -> CommentThreadBlock {
+        let height = view.read(cx).height(cx);
+        let block_id = self
+            .insert_blocks(
+                [BlockProperties {
+                    position,
+                    height,
+                    style: BlockStyle::Flex,
+                    render: render_comment_thread_block(view.clone()),
+                    disposition: BlockDisposition::Below,
+                }],
+                None,
+                cx,
+            )
+            .pop()
+            .expect("inserted one block");
+        let subscription = cx.subscribe(&view, |editor, view, event, cx| match event {
+            CommentThreadViewEvent::Resized => editor.resize_comment_thread_blocks(cx),
+            CommentThreadViewEvent::Dismissed => {
+                let is_draft = editor
+                    .comment_thread_blocks
+                    .draft
+                    .as_ref()
+                    .map_or(false, |draft| draft.view == view);
+                if is_draft {
+                    if let Some(draft) = editor.comment_thread_blocks.draft.take() {
+                        editor.remove_blocks(HashSet::from_iter([draft.block_id]), None, cx);
+                    }
+                }
+                editor.focus(cx);
+            }
+        });
+        CommentThreadBlock {
+            block_id,
+            height,
+            view,
+            _subscription: subscription,
+        }
+    }
+
+    /// Updates the heights of the blocks whose threads were expanded, collapsed or replied to.
+    fn resize_comment_thread_blocks(&mut self, cx: &mut ViewContext<Self>) {
+        let mut resized_blocks = HashMap::default();
+        for block in self
+            .comment_thread_blocks
+            .threads
+            .values_mut()
+            .chain(self.comment_thread_blocks.draft.as_mut())
+        {
+            let height = block.view.read(cx).height(cx);
+            if height != block.height {
+                block.height = height;
+                resized_blocks.insert(
+                    block.block_id,
+                    (
+                        Some(height),
+                        render_comment_thread_block(block.view.clone()),
+                    ),
+                );
+            }
+        }
+        if !resized_blocks.is_empty() {
+            self.replace_blocks(resized_blocks, None, cx);
+        }
+    }
+}
+
+fn render_comment_thread_block(view: View<CommentThreadView>) -> RenderBlock {
+    Box::new(move |cx: &mut BlockContext| {
+        div()
+            .size_full()
+            .pl(cx.gutter_dimensions.full_width())
+            .child(view.clone())
+            .into_any_element()
+    })
+}
+
+enum CommentThreadTarget {
+    /// A thread that's being written and hasn't been posted yet.
+    Draft {
+        buffer: Model<Buffer>,
+        range: Range<text::Anchor>,
+    },
+    Thread(u64),
+}
+
+pub enum CommentThreadViewEvent {
+    Resized,
+    Dismissed,
+}
+
+/// A comment thread, or the first comment of a new one, in a block below the commented lines.
+pub struct CommentThreadView {
+    project: Model<Project>,
+    target: CommentThreadTarget,
+    range: Range<Anchor>,
+    expanded: bool,
+    comment_editor: View<Editor>,
+    comment_editor_lines: u8,
+    pending_request: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<CommentThreadViewEvent> for CommentThreadView {}
+
+impl FocusableView for CommentThreadView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.comment_editor.focus_handle(cx)
+    }
+}
+
+impl CommentThreadView {
+    fn draft(
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        buffer_range: Range<text::Anchor>,
+        range: Range<Anchor>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self::new(
+            project,
+            CommentThreadTarget::Draft {
+                buffer,
+                range: buffer_range,
+            },
+            range,
+            "Leave a comment…",
+            cx,
+        );
+        this.expanded = true;
+        this
+    }
+
+    fn thread(
+        project: Model<Project>,
+        thread_id: u64,
+        range: Range<Anchor>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        Self::new(
+            project,
+            CommentThreadTarget::Thread(thread_id),
+            range,
+            "Reply…",
+            cx,
+        )
+    }
+
+    fn new(
+        project: Model<Project>,
+        target: CommentThreadTarget,
+        range: Range<Anchor>,
+        placeholder: &'static str,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let comment_editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(MAX_COMMENT_EDITOR_LINES, cx);
+            editor.set_placeholder_text(placeholder, cx);
+            editor
+        });
+        let user_store = project.read(cx).user_store();
+        let subscriptions = vec![
+            cx.subscribe(&comment_editor, |this, editor, event, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    let lines = editor
+                        .read(cx)
+                        .buffer()
+                        .read(cx)
+                        .snapshot(cx)
+                        .max_point()
+                        .row
+                        + 1;
+                    let lines = lines.min(MAX_COMMENT_EDITOR_LINES as u32) as u8;
+                    if lines != this.comment_editor_lines {
+                        this.comment_editor_lines = lines;
+                        cx.emit(CommentThreadViewEvent::Resized);
+                    }
+                }
+            }),
+            cx.subscribe(&project, |_, _, event, cx| {
+                if let project::Event::CommentThreadsChanged = event {
+                    cx.emit(CommentThreadViewEvent::Resized);
+                    cx.notify();
+                }
+            }),
+            cx.observe(&user_store, |_, _, cx| cx.notify()),
+        ];
+        Self {
+            project,
+            target,
+            range,
+            expanded: false,
+            comment_editor,
+            comment_editor_lines: 1,
+            pending_request: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn comment_thread<'a>(&self, cx: &'a AppContext) -> Option<&'a CommentThread> {
+        match self.target {
+            CommentThreadTarget::Draft { .. } => None,
+            CommentThreadTarget::Thread(thread_id) => {
+                self.project.read(cx).comment_thread(thread_id)
+            }
+        }
+    }
+
+    /// The height of the view, in lines.
+    fn height(&self, cx: &AppContext) -> u8 {
+        if !self.expanded {
+            return 1;
+        }
+        let comment_lines = self.comment_thread(cx).map_or(0, |thread| {
+            thread
+                .comments
+                .iter()
+                .map(|comment| comment.body.lines().count().max(1) + 1)
+                .sum::<usize>()
+        });
+        // The comments, the editor for a new one and a row of buttons.
+        (comment_lines + self.comment_editor_lines as usize + 1).min(u8::MAX as usize) as u8
+    }
+
+    fn set_expanded(&mut self, expanded: bool, cx: &mut ViewContext<Self>) {
+        if self.expanded != expanded {
+            self.expanded = expanded;
+            cx.emit(CommentThreadViewEvent::Resized);
+            cx.notify();
+        }
+    }
+
+    fn toggle_expanded(&mut self, cx: &mut ViewContext<Self>) {
+        self.set_expanded(!self.expanded, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if self.pending_request.is_some() {
+            return;
+        }
+        let body = self.comment_editor.read(cx).text(cx).trim().to_string();
+        if body.is_empty() {
+            return;
+        }
+        let is_draft = matches!(self.target, CommentThreadTarget::Draft { .. });
+        let request = self.project.update(cx, |project, cx| match &self.target {
+            CommentThreadTarget::Draft { buffer, range } => {
+                let create = project.create_comment_thread(buffer, range.clone(), body, cx);
+                cx.background_executor()
+                    .spawn(async move { create.await.map(|_| ()) })
+            }
+            CommentThreadTarget::Thread(thread_id) => {
+                project.reply_to_comment_thread(*thread_id, body, cx)
+            }
+        });
+        self.pending_request = Some(cx.spawn(|this, mut cx| async move {
+            let result = request.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_request = None;
+                if result.log_err().is_some() {
+                    this.comment_editor
+                        .update(cx, |editor, cx| editor.clear(cx));
+                    if is_draft {
+                        cx.emit(CommentThreadViewEvent::Dismissed);
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        match self.target {
+            CommentThreadTarget::Draft { .. } => cx.emit(CommentThreadViewEvent::Dismissed),
+            CommentThreadTarget::Thread(_) => {
+                self.set_expanded(false, cx);
+                cx.emit(CommentThreadViewEvent::Dismissed);
+            }
+        }
+    }
+
+    fn toggle_resolved(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(thread) = self.comment_thread(cx) else {
+            return;
+        };
+        let (thread_id, resolved) = (thread.id, !thread.is_resolved());
+        self.project
+            .update(cx, |project, cx| {
+                project.resolve_comment_thread(thread_id, resolved, cx)
+            })
+            .detach_and_log_err(cx);
+        if resolved {
+            self.set_expanded(false, cx);
+        }
+    }
+
+    fn render_summary(&self, thread: &CommentThread, cx: &WindowContext) -> Div {
+        let user_store = self.project.read(cx).user_store().read(cx);
+        let first_comment = thread.comments.first();
+        let author = first_comment
+            .and_then(|comment| user_store.get_cached_user(comment.author_id))
+            .map(|user| user.github_login.clone());
+        let preview = first_comment
+            .and_then(|comment| comment.body.lines().next())
+            .unwrap_or_default()
+            .to_string();
+        let replies = thread.comments.len().saturating_sub(1);
+
+        h_flex()
+            .gap_1()
+            .child(
+                Icon::new(IconName::MessageBubbles)
+                    .size(IconSize::Small)
+                    .color(if thread.is_resolved() {
+                        Color::Muted
+                    } else {
+                        Color::Info
+                    }),
+            )
+            .children(author.map(|author| Label::new(author).size(LabelSize::Small)))
+            .child(
+                Label::new(preview)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .when(replies > 0, |this| {
+                this.child(
+                    Label::new(if replies == 1 {
+                        "· 1 reply".to_string()
+                    } else {
+                        format!("· {replies} replies")
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+            })
+            .when(thread.is_resolved(), |this| {
+                this.child(
+                    Label::new("· Resolved")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+    }
+
+    fn render_comments(&self, thread: &CommentThread, cx: &WindowContext) -> Div {
+        let user_store = self.project.read(cx).user_store().read(cx);
+        let local_timezone = cx.local_timezone();
+        let now = OffsetDateTime::now_utc();
+        v_flex().children(thread.comments.iter().map(|comment| {
+            let author = user_store.get_cached_user(comment.author_id);
+            let timestamp = time_format::format_localized_timestamp(
+                OffsetDateTime::from(comment.timestamp),
+                now,
+                local_timezone,
+                time_format::TimestampFormat::Relative,
+            );
+            v_flex()
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .children(
+                            author.as_ref().map(|author| {
+                                Avatar::new(author.avatar_uri.clone()).size(rems(1.))
+                            }),
+                        )
+                        .child(
+                            Label::new(
+                                author
+                                    .map(|author| author.github_login.clone())
+                                    .unwrap_or_else(|| "Unknown".into()),
+                            )
+                            .size(LabelSize::Small),
+                        )
+                        .child(
+                            Label::new(timestamp)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .children(
+                    comment
+                        .body
+                        .lines()
+                        .map(|line| Label::new(line.to_string()).size(LabelSize::Small)),
+                )
+        }))
+    }
+}
+
+impl Render for CommentThreadView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let thread = self.comment_thread(cx).cloned();
+        let is_draft = matches!(self.target, CommentThreadTarget::Draft { .. });
+        let is_resolved = thread.as_ref().map_or(false, |thread| thread.is_resolved());
+
+        let content = match &thread {
+            Some(thread) if !self.expanded => div()
+                .id("comment-thread-summary")
+                .cursor_pointer()
+                .child(self.render_summary(thread, cx))
+                .on_click(cx.listener(|this, _, cx| this.set_expanded(true, cx)))
+                .into_any_element(),
+            _ => v_flex()
+                .gap_1()
+                .children(
+                    thread
+                        .as_ref()
+                        .map(|thread| self.render_comments(thread, cx)),
+                )
+                .child(
+                    div()
+                        .px_1()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .rounded_md()
+                        .child(self.comment_editor.clone()),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("comment", if is_draft { "Comment" } else { "Reply" })
+                                .disabled(self.pending_request.is_some())
+                                .tooltip(|cx| Tooltip::for_action("Post", &menu::Confirm, cx))
+                                .on_click(
+                                    cx.listener(|this, _, cx| this.confirm(&menu::Confirm, cx)),
+                                ),
+                        )
+                        .when(!is_draft, |this| {
+                            this.child(
+                                Button::new(
+                                    "resolve",
+                                    if is_resolved { "Reopen" } else { "Resolve" },
+                                )
+                                .on_click(cx.listener(|this, _, cx| this.toggle_resolved(cx))),
+                            )
+                        })
+                        .child(
+                            Button::new("dismiss", if is_draft { "Cancel" } else { "Collapse" })
+                                .on_click(
+                                    cx.listener(|this, _, cx| this.cancel(&menu::Cancel, cx)),
+                                ),
+                        ),
+                )
+                .into_any_element(),
+        };
+
+        v_flex()
+            .key_context("CommentThread")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .max_w(rems(40.))
+            .child(content)
+    }
+}
@@ -15,6 +15,7 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod comment_threads;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
@@ -32,6 +33,7 @@ mod linked_editing_ranges;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod rainbow_brackets;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
@@ -50,6 +52,7 @@ use blink_manager::BlinkManager;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
+use comment_threads::CommentThreadBlocks;
 use convert_case::{Case, Casing};
 use debounced_delay::DebouncedDelay;
 use display_map::*;
@@ -111,6 +114,7 @@ use project::{
     ProjectTransaction, TaskSourceKind, WorktreeId,
 };
 use rand::prelude::*;
+use regex::Regex;
 use rpc::{proto::*, ErrorExt};
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
@@ -247,6 +251,7 @@ impl InlayId {
 }
 
 enum DiffRowHighlight {}
+enum DiffWordHighlight {}
 enum DocumentHighlightRead {}
 enum DocumentHighlightWrite {}
 enum InputComposition {}
@@ -267,6 +272,7 @@ pub fn init(cx: &mut AppContext) {
     workspace::register_project_item::<Editor>(cx);
     workspace::register_followable_item::<Editor>(cx);
     workspace::register_deserializable_item::<Editor>(cx);
+    project::buffer_history::set_buffer_history_store(Arc::new(persistence::BufferHistoryDb), cx);
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
             workspace.register_action(Editor::new_file);
@@ -527,6 +533,7 @@ pub struct Editor {
     show_inline_completions: bool,
     inlay_hint_cache: InlayHintCache,
     expanded_hunks: ExpandedHunks,
+    comment_thread_blocks: CommentThreadBlocks,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
@@ -1747,6 +1754,8 @@ impl Editor {
                                 }
                             }
                         }
+                    } else if let project::Event::CommentThreadsChanged = event {
+                        editor.refresh_comment_threads(cx);
                     }
                 }));
                 let task_inventory = project.read(cx).task_inventory().clone();
@@ -1847,6 +1856,7 @@ impl Editor {
             active_inline_completion: None,
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             expanded_hunks: ExpandedHunks::default(),
+            comment_thread_blocks: CommentThreadBlocks::default(),
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
@@ -1907,6 +1917,8 @@ impl Editor {
                 this.git_blame_inline_enabled = true;
                 this.start_git_blame_inline(false, cx);
             }
+
+            this.refresh_comment_threads(cx);
         }
 
         this.report_editor_event("open", None, cx);
@@ -7492,6 +7504,194 @@ impl Editor {
         });
     }
 
+    pub fn split_selections_by_regex(
+        &mut self,
+        action: &SplitSelectionsByRegex,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        let regex = Regex::new(&action.pattern)?;
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut new_selection_ranges = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let text = buffer.text_for_range(selection.range()).collect::<String>();
+            let ranges_len = new_selection_ranges.len();
+            let mut piece_start = 0;
+            for mat in regex.find_iter(&text) {
+                if mat.start() > piece_start {
+                    new_selection_ranges
+                        .push(selection.start + piece_start..selection.start + mat.start());
+                }
+                piece_start = mat.end();
+            }
+            if text.len() > piece_start {
+                new_selection_ranges.push(selection.start + piece_start..selection.end);
+            }
+
+            // Keep the selection as is if nothing is left once the matches are removed.
+            if new_selection_ranges.len() == ranges_len {
+                new_selection_ranges.push(selection.range());
+            }
+        }
+
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_ranges(new_selection_ranges);
+        });
+        Ok(())
+    }
+
+    pub fn keep_selections_matching(
+        &mut self,
+        action: &KeepSelectionsMatching,
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        let regex = Regex::new(&action.pattern)?;
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let selections = self
+            .selections
+            .all::<usize>(cx)
+            .into_iter()
+            .filter(|selection| {
+                let text = buffer.text_for_range(selection.range()).collect::<String>();
+                regex.is_match(&text) != action.invert
+            })
+            .collect::<Vec<_>>();
+
+        // There must always be at least one selection.
+        if !selections.is_empty() {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(selections);
+            });
+        }
+        Ok(())
+    }
+
+    /// Pads the selections with spaces so that the selections on different lines start at the
+    /// same column. When a line contains several selections, the first selections of every
+    /// line are aligned with each other, then the second ones and so on.
+    pub fn align_selections(&mut self, _: &AlignSelections, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<Point>(cx);
+
+        let mut selection_starts_by_row = BTreeMap::<u32, Vec<Point>>::default();
+        for selection in &selections {
+            selection_starts_by_row
+                .entry(selection.start.row)
+                .or_default()
+                .push(selection.start);
+        }
+        let max_selections_per_row = selection_starts_by_row
+            .values()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0);
+
+        let mut padding_by_row = HashMap::<u32, usize>::default();
+        let mut edits = Vec::new();
+        for ix in 0..max_selections_per_row {
+            let columns = selection_starts_by_row
+                .iter()
+                .filter_map(|(row, starts)| {
+                    let start = *starts.get(ix)?;
+                    let column = buffer
+                        .text_for_range(Point::new(start.row, 0)..start)
+                        .flat_map(str::chars)
+                        .count();
+                    let padding = padding_by_row.get(row).copied().unwrap_or(0);
+                    Some((start, column + padding))
+                })
+                .collect::<Vec<_>>();
+            let Some(target_column) = columns.iter().map(|(_, column)| *column).max() else {
+                continue;
+            };
+
+            for (start, column) in columns {
+                if column < target_column {
+                    edits.push((start..start, " ".repeat(target_column - column)));
+                    *padding_by_row.entry(start.row).or_default() += target_column - column;
+                }
+            }
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+
+        // Keep the padding in front of the selections.
+        let new_selections = selections
+            .iter()
+            .map(|selection| {
+                let end = if selection.is_empty() {
+                    buffer.anchor_after(selection.end)
+                } else {
+                    buffer.anchor_before(selection.end)
+                };
+                (
+                    buffer.anchor_after(selection.start),
+                    end,
+                    selection.reversed,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+
+            let buffer = this.buffer.read(cx).snapshot(cx);
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_selections.into_iter().map(|(start, end, reversed)| {
+                    let start = start.to_offset(&buffer);
+                    let end = end.to_offset(&buffer);
+                    if reversed {
+                        end..start
+                    } else {
+                        start..end
+                    }
+                }));
+            });
+        });
+    }
+
+    /// Replaces the selections with a sequence of numbers, in the order in which the
+    /// selections appear in the buffer.
+    pub fn insert_number_sequence(
+        &mut self,
+        action: &InsertNumberSequence,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut new_selections = Vec::new();
+        let mut edits = Vec::new();
+        let mut selection_adjustment = 0isize;
+        let mut number = action.start;
+
+        for selection in self.selections.all::<usize>(cx) {
+            let text = action.format.format(number, action.width);
+            number = number.saturating_add(action.step);
+
+            let start = (selection.start as isize + selection_adjustment) as usize;
+            new_selections.push(Selection {
+                start,
+                end: start + text.len(),
+                goal: SelectionGoal::None,
+                ..selection
+            });
+            selection_adjustment += text.len() as isize - selection.range().len() as isize;
+
+            edits.push((selection.range(), text));
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+        });
+    }
+
     pub fn add_selection_above(&mut self, _: &AddSelectionAbove, cx: &mut ViewContext<Self>) {
         self.add_selection(true, cx);
     }
@@ -11130,9 +11330,11 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_comment_threads(cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_comment_threads(cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsEdited { ids } => {
@@ -14,6 +14,7 @@ pub struct EditorSettings {
     pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
@@ -81,6 +82,13 @@ pub struct Scrollbar {
     pub cursors: bool,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Minimap {
+    pub show: bool,
+    pub width: f32,
+    pub max_columns: u32,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Gutter {
     pub line_numbers: bool,
@@ -173,6 +181,8 @@ pub struct EditorSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// Whether the editor will scroll beyond the last line.
@@ -266,6 +276,23 @@ pub struct ScrollbarContent {
     pub cursors: Option<bool>,
 }
 
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MinimapContent {
+    /// Whether to show the minimap next to the scrollbar.
+    ///
+    /// Default: false
+    pub show: Option<bool>,
+    /// The width of the minimap, in pixels.
+    ///
+    /// Default: 100
+    pub width: Option<f32>,
+    /// The maximum number of columns rendered for each line in the minimap.
+    ///
+    /// Default: 120
+    pub max_columns: Option<u32>,
+}
+
 /// Gutter related settings
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct GutterContent {
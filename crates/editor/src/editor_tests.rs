@@ -3563,6 +3563,180 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_column_selection_commands(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Test align_selections()
+    cx.set_state(indoc! {"
+        let x = ˇ1;
+        let foo = ˇ2;
+        let ab = «3ˇ»;
+    "});
+    cx.update_editor(|e, cx| e.align_selections(&AlignSelections, cx));
+    cx.assert_editor_state(indoc! {"
+        let x =   ˇ1;
+        let foo = ˇ2;
+        let ab =  «3ˇ»;
+    "});
+
+    // Selections sharing a line are aligned with the selections at the same index on other lines
+    cx.set_state(indoc! {"
+        a ˇ= 1 ˇ// one
+        bbb ˇ= 22 ˇ// two
+    "});
+    cx.update_editor(|e, cx| e.align_selections(&AlignSelections, cx));
+    cx.assert_editor_state(indoc! {"
+        a   ˇ= 1  ˇ// one
+        bbb ˇ= 22 ˇ// two
+    "});
+
+    // Test insert_number_sequence()
+    cx.set_state(indoc! {"
+        a[«0ˇ»]
+        b[ˇ]
+        c[«0ˇ»]
+    "});
+    cx.update_editor(|e, cx| {
+        e.insert_number_sequence(
+            &InsertNumberSequence {
+                start: 8,
+                step: 2,
+                width: 2,
+                format: NumberSequenceFormat::Decimal,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        a[«08ˇ»]
+        b[«10ˇ»]
+        c[«12ˇ»]
+    "});
+
+    cx.update_editor(|e, cx| {
+        e.insert_number_sequence(
+            &InsertNumberSequence {
+                start: 255,
+                step: 1,
+                width: 0,
+                format: NumberSequenceFormat::UpperHex,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        a[«FFˇ»]
+        b[«100ˇ»]
+        c[«101ˇ»]
+    "});
+
+    // Negative numbers are formatted with a sign rather than as their two's complement.
+    cx.update_editor(|e, cx| {
+        e.insert_number_sequence(
+            &InsertNumberSequence {
+                start: 1,
+                step: -8,
+                width: 0,
+                format: NumberSequenceFormat::LowerHex,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        a[«1ˇ»]
+        b[«-7ˇ»]
+        c[«-fˇ»]
+    "});
+
+    cx.update_editor(|e, cx| {
+        e.insert_number_sequence(
+            &InsertNumberSequence {
+                start: 0,
+                step: -1,
+                width: 2,
+                format: NumberSequenceFormat::Binary,
+            },
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        a[«00ˇ»]
+        b[«-01ˇ»]
+        c[«-10ˇ»]
+    "});
+
+    // A default-constructed action counts up by one, like one deserialized without fields.
+    assert_eq!(
+        InsertNumberSequence::default(),
+        serde_json::from_value::<InsertNumberSequence>(json!({})).unwrap()
+    );
+
+    // Test split_selections_by_regex()
+    cx.set_state(indoc! {"
+        «one, two,  threeˇ»
+    "});
+    cx.update_editor(|e, cx| {
+        e.split_selections_by_regex(
+            &SplitSelectionsByRegex {
+                pattern: r",\s*".into(),
+            },
+            cx,
+        )
+        .unwrap()
+    });
+    cx.assert_editor_state(indoc! {"
+        «oneˇ», «twoˇ»,  «threeˇ»
+    "});
+
+    // Test keep_selections_matching()
+    cx.update_editor(|e, cx| {
+        e.keep_selections_matching(
+            &KeepSelectionsMatching {
+                pattern: "^t".into(),
+                invert: false,
+            },
+            cx,
+        )
+        .unwrap()
+    });
+    cx.assert_editor_state(indoc! {"
+        one, «twoˇ»,  «threeˇ»
+    "});
+
+    cx.update_editor(|e, cx| {
+        e.keep_selections_matching(
+            &KeepSelectionsMatching {
+                pattern: "ee".into(),
+                invert: true,
+            },
+            cx,
+        )
+        .unwrap()
+    });
+    cx.assert_editor_state(indoc! {"
+        one, «twoˇ»,  three
+    "});
+
+    // Invalid patterns leave the selections unchanged
+    cx.update_editor(|e, cx| {
+        assert!(e
+            .keep_selections_matching(
+                &KeepSelectionsMatching {
+                    pattern: "(".into(),
+                    invert: false,
+                },
+                cx,
+            )
+            .is_err())
+    });
+    cx.assert_editor_state(indoc! {"
+        one, «twoˇ»,  three
+    "});
+}
+
 #[gpui::test]
 fn test_duplicate_line(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -10130,6 +10304,80 @@ async fn test_toggle_hunk_diff(executor: BackgroundExecutor, cx: &mut gpui::Test
     });
 }
 
+#[gpui::test]
+async fn test_word_diff_highlights_in_expanded_hunks(
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let diff_base = r#"
+        fn main() {
+            let value = compute(first, second);
+        }
+        "#
+    .unindent();
+
+    cx.set_state(
+        &r#"
+        fn main() {
+            let ˇresult = compute(first, third);
+        }
+        "#
+        .unindent(),
+    );
+    cx.set_diff_base(Some(&diff_base));
+    executor.run_until_parked();
+
+    cx.update_editor(|editor, cx| {
+        editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx);
+    });
+    executor.run_until_parked();
+    cx.assert_editor_text_highlights::<DiffWordHighlight>(
+        &r#"
+        fn main() {
+            let «result» = compute(first, «third»);
+        }
+        "#
+        .unindent(),
+    );
+
+    cx.update_editor(|editor, cx| {
+        editor.toggle_hunk_diff(&ToggleHunkDiff, cx);
+    });
+    executor.run_until_parked();
+    cx.assert_editor_text_highlights::<DiffWordHighlight>(
+        &r#"
+        fn main() {
+            let result = compute(first, third);
+        }
+        "#
+        .unindent(),
+    );
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.git.word_diff = Some(false);
+            });
+        });
+    });
+    cx.update_editor(|editor, cx| {
+        editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx);
+    });
+    executor.run_until_parked();
+    cx.assert_editor_text_highlights::<DiffWordHighlight>(
+        &r#"
+        fn main() {
+            let result = compute(first, third);
+        }
+        "#
+        .unindent(),
+    );
+}
+
 #[gpui::test]
 async fn test_toggled_diff_base_change(
     executor: BackgroundExecutor,
@@ -5,7 +5,8 @@ use crate::{
         BlockContext, BlockStyle, DisplaySnapshot, HighlightedChunk, ToDisplayPoint, TransformBlock,
     },
     editor_settings::{
-        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ShowScrollbar,
+        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, Scrollbar,
+        ShowScrollbar,
     },
     git::{
         blame::{CommitDetails, GitBlame},
@@ -17,19 +18,21 @@ use crate::{
     hunk_status,
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MouseContextMenu},
+    rainbow_brackets::{colorize_brackets, rainbow_bracket_pairs_in_range, RainbowBracketPair},
     scroll::scroll_amount::ScrollAmount,
-    CodeActionsMenu, CursorShape, DisplayPoint, DisplayRow, DocumentHighlightRead,
-    DocumentHighlightWrite, Editor, EditorMode, EditorSettings, EditorSnapshot, EditorStyle,
-    ExpandExcerpts, GutterDimensions, HalfPageDown, HalfPageUp, HoveredCursor, HunkToExpand,
-    LineDown, LineUp, OpenExcerpts, PageDown, PageUp, Point, RowExt, RowRangeExt, SelectPhase,
-    Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
+    BackgroundHighlight, CodeActionsMenu, CursorShape, DisplayPoint, DisplayRow,
+    DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode, EditorSettings,
+    EditorSnapshot, EditorStyle, ExpandExcerpts, GutterDimensions, HalfPageDown, HalfPageUp,
+    HoveredCursor, HunkToExpand, LineDown, LineUp, OpenExcerpts, PageDown, PageUp, Point, RowExt,
+    RowRangeExt, SelectPhase, Selection, SoftWrap, ToPoint, CURSORS_VISIBLE_FOR, MAX_LINE_LEN,
 };
 use client::ParticipantIndex;
 use collections::{BTreeMap, HashMap};
 use git::{blame::BlameEntry, diff::DiffHunkStatus, Oid};
 use gpui::{
     anchored, deferred, div, fill, outline, point, px, quad, relative, size, svg,
-    transparent_black, Action, AnchorCorner, AnyElement, AvailableSpace, Bounds, ClipboardItem,
+    transparent_black, AccessibilityAction, AccessibilityProperties, AccessibilityRole,
+    AccessibilityStates, Action, AnchorCorner, AnyElement, AvailableSpace, Bounds, ClipboardItem,
     ContentMask, Corners, CursorStyle, DispatchPhase, Edges, Element, ElementInputHandler, Entity,
     FontId, GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, Length,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
@@ -58,8 +61,8 @@ use std::{
     ops::{Deref, Range},
     sync::Arc,
 };
-use sum_tree::Bias;
-use theme::{ActiveTheme, PlayerColor};
+use sum_tree::{Bias, TreeMap};
+use theme::{ActiveTheme, PlayerColor, Theme};
 use ui::prelude::*;
 use ui::{h_flex, ButtonLike, ButtonStyle, ContextMenu, Tooltip};
 use util::ResultExt;
@@ -265,6 +268,14 @@ impl EditorElement {
         });
         register_action(view, cx, Editor::select_line);
         register_action(view, cx, Editor::split_selection_into_lines);
+        register_action(view, cx, |editor, action, cx| {
+            editor.split_selections_by_regex(action, cx).log_err();
+        });
+        register_action(view, cx, |editor, action, cx| {
+            editor.keep_selections_matching(action, cx).log_err();
+        });
+        register_action(view, cx, Editor::align_selections);
+        register_action(view, cx, Editor::insert_number_sequence);
         register_action(view, cx, Editor::add_selection_above);
         register_action(view, cx, Editor::add_selection_below);
         register_action(view, cx, |editor, action, cx| {
@@ -336,6 +347,8 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_git_blame);
         register_action(view, cx, Editor::toggle_git_blame_inline);
         register_action(view, cx, Editor::toggle_hunk_diff);
+        register_action(view, cx, Editor::new_comment_thread);
+        register_action(view, cx, Editor::toggle_comment_thread);
         register_action(view, cx, Editor::expand_all_hunk_diffs);
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.format(action, cx) {
@@ -1135,6 +1148,160 @@ impl EditorElement {
         })
     }
 
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        scroll_position: gpui::Point<f32>,
+        rows_per_page: f32,
+        max_scroll_top: f32,
+        cx: &mut WindowContext,
+    ) -> Option<MinimapLayout> {
+        let settings = EditorSettings::get_global(cx);
+        let minimap_settings = settings.minimap;
+        let scrollbar_settings = settings.scrollbar;
+        if !minimap_settings.show || snapshot.mode != EditorMode::Full {
+            return None;
+        }
+
+        // If a drag took place after we started dragging the minimap,
+        // cancel the minimap drag.
+        if cx.has_active_drag() {
+            self.editor.update(cx, |editor, cx| {
+                editor.scroll_manager.set_is_dragging_minimap(false, cx);
+            });
+        }
+
+        let minimap_right = self.scrollbar_left(&bounds);
+        let minimap_bounds = Bounds::from_corners(
+            point(minimap_right - px(minimap_settings.width), bounds.origin.y),
+            point(minimap_right, bounds.lower_left().y),
+        );
+        let hitbox = cx.insert_hitbox(minimap_bounds, false);
+
+        // When the buffer doesn't fit in the minimap, scroll the minimap proportionally to the
+        // editor, so that its top and bottom line up with the top and bottom of the buffer.
+        let total_rows = snapshot.max_point().row().next_row().as_f32();
+        let minimap_rows = (minimap_bounds.size.height / MinimapLayout::ROW_HEIGHT).floor();
+        let (first_row, viewport_row_height) = if total_rows > minimap_rows && max_scroll_top > 0. {
+            let scroll_fraction = (scroll_position.y / max_scroll_top).clamp(0., 1.);
+            let hidden_rows = total_rows - minimap_rows;
+            (
+                (scroll_fraction * hidden_rows).floor(),
+                (MinimapLayout::ROW_HEIGHT * (1. - hidden_rows / max_scroll_top))
+                    .max(MinimapLayout::ROW_HEIGHT / minimap_rows),
+            )
+        } else {
+            (0., MinimapLayout::ROW_HEIGHT)
+        };
+
+        // Only the rows that fit in the minimap are laid out, which keeps the cost of painting it
+        // independent of the size of the buffer.
+        let start_row = DisplayRow(first_row as u32);
+        let end_row = DisplayRow(
+            ((first_row + minimap_rows).ceil() as u32).min(snapshot.max_point().row().next_row().0),
+        );
+
+        let column_width = minimap_bounds.size.width / minimap_settings.max_columns.max(1) as f32;
+        let max_columns = minimap_settings.max_columns;
+        let mut line_quads = Vec::new();
+        let mut push_run = |row: u32, columns: Range<u32>, color: Hsla| {
+            let top = minimap_bounds.top() + (row as f32 - first_row) * MinimapLayout::ROW_HEIGHT;
+            line_quads.push(fill(
+                Bounds::from_corners(
+                    point(
+                        minimap_bounds.left() + columns.start as f32 * column_width,
+                        top,
+                    ),
+                    point(
+                        minimap_bounds.left() + columns.end as f32 * column_width,
+                        top + MinimapLayout::GLYPH_HEIGHT,
+                    ),
+                ),
+                color,
+            ));
+        };
+
+        let mut row = start_row.0;
+        let mut column = 0;
+        for chunk in snapshot.highlighted_chunks(start_row..end_row, true, &self.style) {
+            let mut color = chunk
+                .style
+                .and_then(|style| style.color)
+                .unwrap_or(self.style.text.color);
+            color.fade_out(0.3);
+
+            let mut run_start = None;
+            for ch in chunk.text.chars() {
+                if ch == '\n' || ch.is_whitespace() || column >= max_columns {
+                    if let Some(run_start) = run_start.take() {
+                        push_run(row, run_start..column, color);
+                    }
+                    if ch == '\n' {
+                        row += 1;
+                        column = 0;
+                    } else {
+                        column += 1;
+                    }
+                } else {
+                    run_start.get_or_insert(column);
+                    column += 1;
+                }
+            }
+            if let Some(run_start) = run_start {
+                push_run(row, run_start..column, color);
+            }
+        }
+
+        let mut marker_quads = Vec::new();
+        if self.editor.read(cx).is_singleton(cx) {
+            let start_buffer_row = DisplayPoint::new(start_row, 0)
+                .to_point(&snapshot.display_snapshot)
+                .row;
+            let end_buffer_row = DisplayPoint::new(end_row, 0)
+                .to_point(&snapshot.display_snapshot)
+                .row;
+            let [git_diff_ranges, mut highlight_ranges, diagnostic_ranges] = collect_marker_ranges(
+                snapshot,
+                &self.editor.read(cx).background_highlights,
+                MultiBufferRow(start_buffer_row)..MultiBufferRow(end_buffer_row + 1),
+                cx.theme(),
+                scrollbar_settings,
+            );
+            for range in &mut highlight_ranges {
+                range.color.fade_out(0.6);
+            }
+
+            marker_quads.extend(MinimapLayout::marker_quads_for_ranges(
+                &hitbox,
+                first_row,
+                highlight_ranges,
+                hitbox.left()..hitbox.right(),
+            ));
+            marker_quads.extend(MinimapLayout::marker_quads_for_ranges(
+                &hitbox,
+                first_row,
+                git_diff_ranges,
+                hitbox.left()..hitbox.left() + MinimapLayout::MARKER_WIDTH,
+            ));
+            marker_quads.extend(MinimapLayout::marker_quads_for_ranges(
+                &hitbox,
+                first_row,
+                diagnostic_ranges,
+                hitbox.right() - MinimapLayout::MARKER_WIDTH..hitbox.right(),
+            ));
+        }
+
+        Some(MinimapLayout {
+            hitbox,
+            first_row,
+            visible_row_range: scroll_position.y..scroll_position.y + rows_per_page,
+            viewport_row_height,
+            line_quads,
+            marker_quads,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn prepaint_gutter_fold_toggles(
         &self,
@@ -1488,6 +1655,54 @@ impl EditorElement {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_bracket_guides(
+        &self,
+        rainbow_brackets: &[RainbowBracketPair],
+        content_origin: gpui::Point<Pixels>,
+        text_origin: gpui::Point<Pixels>,
+        visible_rows: Range<DisplayRow>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        snapshot: &DisplaySnapshot,
+        cx: &mut WindowContext,
+    ) -> Vec<BracketGuideLayout> {
+        rainbow_brackets
+            .iter()
+            .filter(|pair| pair.show_guide)
+            .filter_map(|pair| {
+                let open_row = pair.open.start.row();
+                let close_row = pair.close.start.row();
+                let rows = cmp::max(open_row.next_row(), visible_rows.start)
+                    ..cmp::min(close_row, visible_rows.end);
+                if rows.is_empty() {
+                    return None;
+                }
+
+                // Align the guide with the start of the line containing the opening bracket,
+                // unless the closing bracket is further to the left.
+                let open_line_indent = snapshot
+                    .line(open_row)
+                    .chars()
+                    .take_while(|c| c.is_whitespace())
+                    .count();
+                let column = open_line_indent.min(pair.close.start.column() as usize);
+                let x = content_origin.x + self.column_pixels(column, cx) - scroll_pixel_position.x;
+                if x < text_origin.x {
+                    return None;
+                }
+
+                let y =
+                    content_origin.y + rows.start.as_f32() * line_height - scroll_pixel_position.y;
+                Some(BracketGuideLayout {
+                    origin: point(x, y),
+                    length: rows.len() as f32 * line_height,
+                    color: cx.theme().accents().color_for_index(pair.depth),
+                })
+            })
+            .collect()
+    }
+
     fn calculate_indent_guide_bounds(
         row_range: Range<MultiBufferRow>,
         line_height: Pixels,
@@ -1834,6 +2049,7 @@ impl EditorElement {
     fn layout_lines(
         rows: Range<DisplayRow>,
         line_number_layouts: &[Option<ShapedLine>],
+        rainbow_brackets: &[RainbowBracketPair],
         snapshot: &EditorSnapshot,
         style: &EditorStyle,
         cx: &mut WindowContext,
@@ -1879,8 +2095,16 @@ impl EditorElement {
                 .collect()
         } else {
             let chunks = snapshot.highlighted_chunks(rows.clone(), true, style);
+            let mut bracket_colors = rainbow_brackets
+                .iter()
+                .flat_map(|pair| {
+                    let color = cx.theme().accents().color_for_index(pair.depth);
+                    [(pair.open.clone(), color), (pair.close.clone(), color)]
+                })
+                .collect::<Vec<_>>();
+            bracket_colors.sort_by_key(|(range, _)| range.start);
             LineWithInvisibles::from_chunks(
-                chunks,
+                colorize_brackets(chunks, rows.start, &bracket_colors),
                 &style.text,
                 MAX_LINE_LEN,
                 rows.len(),
@@ -2743,8 +2967,13 @@ impl EditorElement {
                         .scrollbar_layout
                         .as_ref()
                         .map_or(false, |scrollbar| scrollbar.visible);
+                    let covered_by_minimap = layout
+                        .minimap_layout
+                        .as_ref()
+                        .map_or(false, |minimap| x > minimap.hitbox.left());
                     if x < layout.text_hitbox.origin.x
                         || (show_scrollbars && x > self.scrollbar_left(&layout.hitbox.bounds))
+                        || covered_by_minimap
                     {
                         continue;
                     }
@@ -2766,6 +2995,23 @@ impl EditorElement {
         })
     }
 
+    fn paint_bracket_guides(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        // TODO fixed for now, expose it through themes later
+        const BRACKET_GUIDE_ALPHA: f32 = 0.5;
+
+        for guide in &layout.bracket_guides {
+            let mut color = guide.color;
+            color.a = BRACKET_GUIDE_ALPHA;
+            cx.paint_quad(fill(
+                Bounds {
+                    origin: guide.origin,
+                    size: size(px(1.), guide.length),
+                },
+                color,
+            ));
+        }
+    }
+
     fn paint_indent_guides(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         let Some(indent_guides) = &layout.indent_guides else {
             return;
@@ -3351,6 +3597,131 @@ impl EditorElement {
         }
     }
 
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(minimap_layout) = layout.minimap_layout.as_ref() else {
+            return;
+        };
+
+        let viewport_bounds = minimap_layout.viewport_bounds();
+        cx.paint_layer(minimap_layout.hitbox.bounds, |cx| {
+            cx.paint_quad(quad(
+                minimap_layout.hitbox.bounds,
+                Corners::default(),
+                cx.theme().colors().editor_background,
+                Edges {
+                    top: Pixels::ZERO,
+                    right: Pixels::ZERO,
+                    bottom: Pixels::ZERO,
+                    left: MinimapLayout::BORDER_WIDTH,
+                },
+                cx.theme().colors().scrollbar_track_border,
+            ));
+
+            let (highlight_markers, edge_markers): (Vec<_>, Vec<_>) = minimap_layout
+                .marker_quads
+                .iter()
+                .partition(|marker| marker.bounds.size.width > MinimapLayout::MARKER_WIDTH);
+            for marker in highlight_markers {
+                cx.paint_quad(marker.clone());
+            }
+            for line in &minimap_layout.line_quads {
+                cx.paint_quad(line.clone());
+            }
+            for marker in edge_markers {
+                cx.paint_quad(marker.clone());
+            }
+
+            cx.paint_quad(fill(
+                viewport_bounds,
+                cx.theme().colors().scrollbar_thumb_background,
+            ));
+        });
+
+        cx.set_cursor_style(CursorStyle::Arrow, &minimap_layout.hitbox);
+
+        let viewport_row_height = minimap_layout.viewport_row_height;
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let hitbox = minimap_layout.hitbox.clone();
+            let mut mouse_position = cx.mouse_position();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    if event.pressed_button == Some(MouseButton::Left)
+                        && editor.scroll_manager.is_dragging_minimap()
+                    {
+                        let y = mouse_position.y;
+                        let new_y = event.position.y;
+                        if (hitbox.top()..hitbox.bottom()).contains(&y) {
+                            let mut position = editor.scroll_position(cx);
+                            position.y += (new_y - y) / viewport_row_height;
+                            if position.y < 0.0 {
+                                position.y = 0.0;
+                            }
+                            editor.set_scroll_position(position, cx);
+                        }
+
+                        cx.stop_propagation();
+                    } else {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                    }
+                    mouse_position = event.position;
+                })
+            }
+        });
+
+        if self.editor.read(cx).scroll_manager.is_dragging_minimap() {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                let hitbox = minimap_layout.hitbox.clone();
+                let first_row = minimap_layout.first_row;
+                let visible_row_range = minimap_layout.visible_row_range.clone();
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !hitbox.is_hovered(cx)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(true, cx);
+
+                        // Clicking outside of the viewport centers the editor on the clicked row.
+                        let y = event.position.y;
+                        if y < viewport_bounds.top() || viewport_bounds.bottom() < y {
+                            let clicked_row =
+                                first_row + (y - hitbox.top()) / MinimapLayout::ROW_HEIGHT;
+                            let page_rows = visible_row_range.end - visible_row_range.start;
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (clicked_row - page_rows / 2.).max(0.).floor();
+                            editor.set_scroll_position(position, cx);
+                        }
+
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -3401,119 +3772,26 @@ impl EditorElement {
                     let scrollbar_markers = cx
                         .background_executor()
                         .spawn(async move {
-                            let max_point = snapshot.display_snapshot.buffer_snapshot.max_point();
-                            let mut marker_quads = Vec::new();
-                            if scrollbar_settings.git_diff {
-                                let marker_row_ranges = snapshot
-                                    .buffer_snapshot
-                                    .git_diff_hunks_in_range(
-                                        MultiBufferRow::MIN..MultiBufferRow::MAX,
-                                    )
-                                    .map(|hunk| {
-                                        let start_display_row =
-                                            MultiBufferPoint::new(hunk.associated_range.start.0, 0)
-                                                .to_display_point(&snapshot.display_snapshot)
-                                                .row();
-                                        let mut end_display_row =
-                                            MultiBufferPoint::new(hunk.associated_range.end.0, 0)
-                                                .to_display_point(&snapshot.display_snapshot)
-                                                .row();
-                                        if end_display_row != start_display_row {
-                                            end_display_row.0 -= 1;
-                                        }
-                                        let color = match hunk_status(&hunk) {
-                                            DiffHunkStatus::Added => theme.status().created,
-                                            DiffHunkStatus::Modified => theme.status().modified,
-                                            DiffHunkStatus::Removed => theme.status().deleted,
-                                        };
-                                        ColoredRange {
-                                            start: start_display_row,
-                                            end: end_display_row,
-                                            color,
-                                        }
-                                    });
-
-                                marker_quads.extend(
-                                    scrollbar_layout
-                                        .marker_quads_for_ranges(marker_row_ranges, Some(0)),
+                            let [git_diff_ranges, highlight_ranges, diagnostic_ranges] =
+                                collect_marker_ranges(
+                                    &snapshot,
+                                    &background_highlights,
+                                    MultiBufferRow::MIN..MultiBufferRow::MAX,
+                                    &theme,
+                                    scrollbar_settings,
                                 );
-                            }
 
-                            for (background_highlight_id, (_, background_ranges)) in
-                                background_highlights.iter()
-                            {
-                                let is_search_highlights = *background_highlight_id
-                                    == TypeId::of::<BufferSearchHighlights>();
-                                let is_symbol_occurrences = *background_highlight_id
-                                    == TypeId::of::<DocumentHighlightRead>()
-                                    || *background_highlight_id
-                                        == TypeId::of::<DocumentHighlightWrite>();
-                                if (is_search_highlights && scrollbar_settings.search_results)
-                                    || (is_symbol_occurrences && scrollbar_settings.selected_symbol)
-                                {
-                                    let mut color = theme.status().info;
-                                    if is_symbol_occurrences {
-                                        color.fade_out(0.5);
-                                    }
-                                    let marker_row_ranges =
-                                        background_ranges.into_iter().map(|range| {
-                                            let display_start = range
-                                                .start
-                                                .to_display_point(&snapshot.display_snapshot);
-                                            let display_end = range
-                                                .end
-                                                .to_display_point(&snapshot.display_snapshot);
-                                            ColoredRange {
-                                                start: display_start.row(),
-                                                end: display_end.row(),
-                                                color,
-                                            }
-                                        });
-                                    marker_quads.extend(
-                                        scrollbar_layout
-                                            .marker_quads_for_ranges(marker_row_ranges, Some(1)),
-                                    );
-                                }
-                            }
-
-                            if scrollbar_settings.diagnostics {
-                                let diagnostics = snapshot
-                                    .buffer_snapshot
-                                    .diagnostics_in_range::<_, Point>(
-                                        Point::zero()..max_point,
-                                        false,
-                                    )
-                                    // We want to sort by severity, in order to paint the most severe diagnostics last.
-                                    .sorted_by_key(|diagnostic| {
-                                        std::cmp::Reverse(diagnostic.diagnostic.severity)
-                                    });
-
-                                let marker_row_ranges = diagnostics.into_iter().map(|diagnostic| {
-                                    let start_display = diagnostic
-                                        .range
-                                        .start
-                                        .to_display_point(&snapshot.display_snapshot);
-                                    let end_display = diagnostic
-                                        .range
-                                        .end
-                                        .to_display_point(&snapshot.display_snapshot);
-                                    let color = match diagnostic.diagnostic.severity {
-                                        DiagnosticSeverity::ERROR => theme.status().error,
-                                        DiagnosticSeverity::WARNING => theme.status().warning,
-                                        DiagnosticSeverity::INFORMATION => theme.status().info,
-                                        _ => theme.status().hint,
-                                    };
-                                    ColoredRange {
-                                        start: start_display.row(),
-                                        end: end_display.row(),
-                                        color,
-                                    }
-                                });
-                                marker_quads.extend(
-                                    scrollbar_layout
-                                        .marker_quads_for_ranges(marker_row_ranges, Some(2)),
-                                );
-                            }
+                            let mut marker_quads = Vec::new();
+                            marker_quads.extend(
+                                scrollbar_layout.marker_quads_for_ranges(git_diff_ranges, Some(0)),
+                            );
+                            marker_quads.extend(
+                                scrollbar_layout.marker_quads_for_ranges(highlight_ranges, Some(1)),
+                            );
+                            marker_quads.extend(
+                                scrollbar_layout
+                                    .marker_quads_for_ranges(diagnostic_ranges, Some(2)),
+                            );
 
                             Arc::from(marker_quads)
                         })
@@ -4479,6 +4757,50 @@ impl EditorElement {
             EditorMode::SingleLine { .. } | EditorMode::AutoHeight { .. } => None,
         }
     }
+
+    /// Describes the editor to assistive technologies. Full editors only expose the text of the
+    /// visible rows, and no text is exposed while any of it is redacted.
+    fn accessibility_properties(
+        &self,
+        layout: &EditorLayout,
+        cx: &WindowContext,
+    ) -> AccessibilityProperties {
+        let editor = self.editor.read(cx);
+        let snapshot = &layout.position_map.snapshot;
+        let role = match layout.mode {
+            EditorMode::SingleLine { .. } | EditorMode::AutoHeight { .. } => {
+                AccessibilityRole::TextInput
+            }
+            EditorMode::Full => AccessibilityRole::Document,
+        };
+        let mut properties = AccessibilityProperties::new(role)
+            .states(AccessibilityStates {
+                focusable: true,
+                editable: !editor.read_only(cx),
+                multiline: !matches!(layout.mode, EditorMode::SingleLine { .. }),
+                ..Default::default()
+            })
+            .action(AccessibilityAction::Focus);
+        if let Some(placeholder_text) = editor.placeholder_text(cx) {
+            properties = properties.name(SharedString::from(placeholder_text.to_string()));
+        }
+        if layout.redacted_ranges.is_empty() {
+            let max_point = snapshot.max_point();
+            let start = DisplayPoint::new(layout.visible_display_row_range.start, 0);
+            let end = if layout.visible_display_row_range.end > max_point.row() {
+                max_point
+            } else {
+                DisplayPoint::new(layout.visible_display_row_range.end, 0)
+            };
+            let range = start.to_point(snapshot)..end.to_point(snapshot);
+            let text = snapshot
+                .buffer_snapshot
+                .text_for_range(range)
+                .collect::<String>();
+            properties = properties.value(text);
+        }
+        properties
+    }
 }
 
 impl Element for EditorElement {
@@ -4513,6 +4835,7 @@ impl Element for EditorElement {
                                 let line = Self::layout_lines(
                                     DisplayRow(0)..DisplayRow(1),
                                     &[],
+                                    &[],
                                     &editor_snapshot,
                                     &style,
                                     cx,
@@ -4619,7 +4942,12 @@ impl Element for EditorElement {
                     let text_width = bounds.size.width - gutter_dimensions.width;
 
                     let right_margin = if snapshot.mode == EditorMode::Full {
-                        EditorElement::SCROLLBAR_WIDTH
+                        let minimap_settings = EditorSettings::get_global(cx).minimap;
+                        if minimap_settings.show {
+                            EditorElement::SCROLLBAR_WIDTH + px(minimap_settings.width)
+                        } else {
+                            EditorElement::SCROLLBAR_WIDTH
+                        }
                     } else {
                         px(0.)
                     };
@@ -4754,6 +5082,12 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let rainbow_brackets = rainbow_bracket_pairs_in_range(
+                        start_anchor..end_anchor,
+                        &snapshot.display_snapshot,
+                        cx,
+                    );
+
                     let (selections, active_rows, newest_selection_head) = self.layout_selections(
                         start_anchor,
                         end_anchor,
@@ -4798,6 +5132,7 @@ impl Element for EditorElement {
                     let mut line_layouts = Self::layout_lines(
                         start_row..end_row,
                         &line_numbers,
+                        &rainbow_brackets,
                         &snapshot,
                         &self.style,
                         cx,
@@ -4876,6 +5211,17 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let bracket_guides = self.layout_bracket_guides(
+                        &rainbow_brackets,
+                        content_origin,
+                        text_hitbox.origin,
+                        start_row..end_row,
+                        scroll_pixel_position,
+                        line_height,
+                        &snapshot,
+                        cx,
+                    );
+
                     let crease_trailers = cx.with_element_namespace("crease_trailers", |cx| {
                         self.prepaint_crease_trailers(
                             crease_trailers,
@@ -4995,6 +5341,15 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let minimap_layout = self.layout_minimap(
+                        &snapshot,
+                        bounds,
+                        scroll_position,
+                        height_in_lines,
+                        max_scroll_top,
+                        cx,
+                    );
+
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
                     let mut _context_menu_visible = false;
@@ -5134,12 +5489,14 @@ impl Element for EditorElement {
                         visible_display_row_range: start_row..end_row,
                         wrap_guides,
                         indent_guides,
+                        bracket_guides,
                         hitbox,
                         text_hitbox,
                         gutter_hitbox,
                         gutter_dimensions,
                         content_origin,
                         scrollbar_layout,
+                        minimap_layout,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -5219,34 +5576,43 @@ impl Element for EditorElement {
                 }
             });
         let rem_size = self.rem_size(cx);
-        cx.with_rem_size(rem_size, |cx| {
-            cx.with_text_style(Some(text_style), |cx| {
-                cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
-                    self.paint_mouse_listeners(layout, hovered_hunk, cx);
-                    self.paint_background(layout, cx);
-                    self.paint_indent_guides(layout, cx);
-
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_blamed_display_rows(layout, cx);
-                        self.paint_line_numbers(layout, cx);
-                    }
+        // Copying the visible text on every paint is only worth it when someone reads it.
+        let accessibility = cx
+            .accessibility_active()
+            .then(|| self.accessibility_properties(layout, cx))
+            .unwrap_or_default();
+        cx.with_accessibility_node(accessibility, bounds, Some(&focus_handle), None, |cx| {
+            cx.with_rem_size(rem_size, |cx| {
+                cx.with_text_style(Some(text_style), |cx| {
+                    cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                        self.paint_mouse_listeners(layout, hovered_hunk, cx);
+                        self.paint_background(layout, cx);
+                        self.paint_indent_guides(layout, cx);
+                        self.paint_bracket_guides(layout, cx);
+
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_blamed_display_rows(layout, cx);
+                            self.paint_line_numbers(layout, cx);
+                        }
 
-                    self.paint_text(layout, cx);
+                        self.paint_text(layout, cx);
 
-                    if !layout.blocks.is_empty() {
-                        cx.with_element_namespace("blocks", |cx| {
-                            self.paint_blocks(layout, cx);
-                        });
-                    }
+                        if !layout.blocks.is_empty() {
+                            cx.with_element_namespace("blocks", |cx| {
+                                self.paint_blocks(layout, cx);
+                            });
+                        }
 
-                    if layout.gutter_hitbox.size.width > Pixels::ZERO {
-                        self.paint_gutter_highlights(layout, cx);
-                        self.paint_gutter_indicators(layout, cx);
-                    }
+                        if layout.gutter_hitbox.size.width > Pixels::ZERO {
+                            self.paint_gutter_highlights(layout, cx);
+                            self.paint_gutter_indicators(layout, cx);
+                        }
 
-                    self.paint_scrollbar(layout, cx);
-                    self.paint_mouse_context_menu(layout, cx);
-                });
+                        self.paint_minimap(layout, cx);
+                        self.paint_scrollbar(layout, cx);
+                        self.paint_mouse_context_menu(layout, cx);
+                    });
+                })
             })
         })
     }
@@ -5268,9 +5634,11 @@ pub struct EditorLayout {
     gutter_dimensions: GutterDimensions,
     content_origin: gpui::Point<Pixels>,
     scrollbar_layout: Option<ScrollbarLayout>,
+    minimap_layout: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
+    bracket_guides: Vec<BracketGuideLayout>,
     visible_display_row_range: Range<DisplayRow>,
     active_rows: BTreeMap<DisplayRow, bool>,
     highlighted_rows: BTreeMap<DisplayRow, Hsla>,
@@ -5307,6 +5675,125 @@ struct ColoredRange<T> {
     color: Hsla,
 }
 
+/// Collects the git diff, background highlight and diagnostic markers that intersect the given
+/// buffer rows, in the order of the marker columns they're painted in.
+fn collect_marker_ranges(
+    snapshot: &EditorSnapshot,
+    background_highlights: &TreeMap<TypeId, BackgroundHighlight>,
+    rows: Range<MultiBufferRow>,
+    theme: &Theme,
+    scrollbar_settings: Scrollbar,
+) -> [Vec<ColoredRange<DisplayRow>>; 3] {
+    let buffer_snapshot = &snapshot.display_snapshot.buffer_snapshot;
+    let max_point = buffer_snapshot.max_point();
+    let start_point = Point::new(rows.start.0, 0).min(max_point);
+    let end_point = Point::new(rows.end.0, 0).min(max_point);
+
+    let mut git_diff_ranges = Vec::new();
+    if scrollbar_settings.git_diff {
+        git_diff_ranges.extend(
+            snapshot
+                .buffer_snapshot
+                .git_diff_hunks_in_range(rows.clone())
+                .map(|hunk| {
+                    let start_display_row = MultiBufferPoint::new(hunk.associated_range.start.0, 0)
+                        .to_display_point(&snapshot.display_snapshot)
+                        .row();
+                    let mut end_display_row = MultiBufferPoint::new(hunk.associated_range.end.0, 0)
+                        .to_display_point(&snapshot.display_snapshot)
+                        .row();
+                    if end_display_row != start_display_row {
+                        end_display_row.0 -= 1;
+                    }
+                    let color = match hunk_status(&hunk) {
+                        DiffHunkStatus::Added => theme.status().created,
+                        DiffHunkStatus::Modified => theme.status().modified,
+                        DiffHunkStatus::Removed => theme.status().deleted,
+                    };
+                    ColoredRange {
+                        start: start_display_row,
+                        end: end_display_row,
+                        color,
+                    }
+                }),
+        );
+    }
+
+    let mut highlight_ranges = Vec::new();
+    let start_anchor = buffer_snapshot.anchor_before(start_point);
+    let end_anchor = buffer_snapshot.anchor_after(end_point);
+    for (background_highlight_id, (_, background_ranges)) in background_highlights.iter() {
+        let is_search_highlights =
+            *background_highlight_id == TypeId::of::<BufferSearchHighlights>();
+        let is_symbol_occurrences = *background_highlight_id
+            == TypeId::of::<DocumentHighlightRead>()
+            || *background_highlight_id == TypeId::of::<DocumentHighlightWrite>();
+        if (is_search_highlights && scrollbar_settings.search_results)
+            || (is_symbol_occurrences && scrollbar_settings.selected_symbol)
+        {
+            let mut color = theme.status().info;
+            if is_symbol_occurrences {
+                color.fade_out(0.5);
+            }
+
+            let start_ix = match background_ranges.binary_search_by(|probe| {
+                if probe.end.cmp(&start_anchor, buffer_snapshot).is_ge() {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            }) {
+                Ok(i) | Err(i) => i,
+            };
+            for range in &background_ranges[start_ix..] {
+                if range.start.cmp(&end_anchor, buffer_snapshot).is_gt() {
+                    break;
+                }
+                let display_start = range.start.to_display_point(&snapshot.display_snapshot);
+                let display_end = range.end.to_display_point(&snapshot.display_snapshot);
+                highlight_ranges.push(ColoredRange {
+                    start: display_start.row(),
+                    end: display_end.row(),
+                    color,
+                });
+            }
+        }
+    }
+
+    let mut diagnostic_ranges = Vec::new();
+    if scrollbar_settings.diagnostics {
+        let diagnostics = snapshot
+            .buffer_snapshot
+            .diagnostics_in_range::<_, Point>(start_point..end_point, false)
+            // We want to sort by severity, in order to paint the most severe diagnostics last.
+            .sorted_by_key(|diagnostic| std::cmp::Reverse(diagnostic.diagnostic.severity));
+
+        diagnostic_ranges.extend(diagnostics.into_iter().map(|diagnostic| {
+            let start_display = diagnostic
+                .range
+                .start
+                .to_display_point(&snapshot.display_snapshot);
+            let end_display = diagnostic
+                .range
+                .end
+                .to_display_point(&snapshot.display_snapshot);
+            let color = match diagnostic.diagnostic.severity {
+                DiagnosticSeverity::ERROR => theme.status().error,
+                DiagnosticSeverity::WARNING => theme.status().warning,
+                DiagnosticSeverity::INFORMATION => theme.status().info,
+                _ => theme.status().hint,
+            };
+            ColoredRange {
+                start: start_display.row(),
+                end: end_display.row(),
+                color,
+            }
+        }));
+    }
+
+    [git_diff_ranges, highlight_ranges, diagnostic_ranges]
+}
+
 #[derive(Clone)]
 struct ScrollbarLayout {
     hitbox: Hitbox,
@@ -5413,6 +5900,59 @@ impl ScrollbarLayout {
     }
 }
 
+struct MinimapLayout {
+    hitbox: Hitbox,
+    /// The display row shown at the top of the minimap.
+    first_row: f32,
+    /// The display rows visible in the editor, highlighted as the minimap's viewport.
+    visible_row_range: Range<f32>,
+    /// How far the viewport moves in the minimap when the editor scrolls by one row.
+    viewport_row_height: Pixels,
+    line_quads: Vec<PaintQuad>,
+    marker_quads: Vec<PaintQuad>,
+}
+
+impl MinimapLayout {
+    const ROW_HEIGHT: Pixels = px(3.0);
+    const GLYPH_HEIGHT: Pixels = px(2.0);
+    const MARKER_WIDTH: Pixels = px(2.0);
+    const BORDER_WIDTH: Pixels = px(1.0);
+
+    fn y_for_row(&self, row: f32) -> Pixels {
+        self.hitbox.top() + (row - self.first_row) * Self::ROW_HEIGHT
+    }
+
+    fn viewport_bounds(&self) -> Bounds<Pixels> {
+        Bounds::from_corners(
+            point(
+                self.hitbox.left(),
+                self.y_for_row(self.visible_row_range.start),
+            ),
+            point(
+                self.hitbox.right(),
+                self.y_for_row(self.visible_row_range.end),
+            ),
+        )
+    }
+
+    fn marker_quads_for_ranges(
+        hitbox: &Hitbox,
+        first_row: f32,
+        row_ranges: impl IntoIterator<Item = ColoredRange<DisplayRow>>,
+        x_range: Range<Pixels>,
+    ) -> impl Iterator<Item = PaintQuad> {
+        let top = hitbox.top();
+        row_ranges.into_iter().map(move |range| {
+            let start_y = top + (range.start.as_f32() - first_row) * Self::ROW_HEIGHT;
+            let end_y = top + (range.end.next_row().as_f32() - first_row) * Self::ROW_HEIGHT;
+            fill(
+                Bounds::from_corners(point(x_range.start, start_y), point(x_range.end, end_y)),
+                range.color,
+            )
+        })
+    }
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -5516,6 +6056,12 @@ pub struct IndentGuideLayout {
     settings: IndentGuideSettings,
 }
 
+pub struct BracketGuideLayout {
+    origin: gpui::Point<Pixels>,
+    length: Pixels,
+    color: Hsla,
+}
+
 pub struct CursorLayout {
     origin: gpui::Point<Pixels>,
     block_width: Pixels,
@@ -5790,12 +6336,14 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockDisposition, BlockProperties},
+        editor_settings::MinimapContent,
         editor_tests::{init_test, update_test_language_settings},
         Editor, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
     use language::language_settings;
     use log::info;
+    use settings::SettingsStore;
     use std::num::NonZeroU32;
     use ui::Context;
     use util::test::sample_text;
@@ -6230,6 +6778,58 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    fn test_minimap_only_lays_out_visible_rows(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(true),
+                        width: Some(100.),
+                        max_columns: Some(100),
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&"abc def\n".repeat(10_000), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(300.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        let minimap = state.minimap_layout.as_ref().unwrap();
+        assert_eq!(minimap.first_row, 0.);
+        // Only the rows fitting in the minimap are laid out.
+        let visible_rows = (px(300.) / MinimapLayout::ROW_HEIGHT).floor() as usize;
+        let laid_out_rows = minimap
+            .line_quads
+            .iter()
+            .map(|quad| quad.bounds.top())
+            .dedup()
+            .count();
+        assert_eq!(laid_out_rows, visible_rows);
+
+        // Scrolling to the end of the buffer scrolls the minimap to its last rows.
+        window
+            .update(cx, |editor, cx| {
+                editor.set_scroll_position(point(0., 10_000.), cx);
+            })
+            .unwrap();
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(300.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        let minimap = state.minimap_layout.as_ref().unwrap();
+        assert_eq!(minimap.first_row, (10_001 - visible_rows) as f32);
+        assert!(minimap.viewport_bounds().top() < minimap.hitbox.bottom());
+    }
+
     fn collect_invisibles_from_new_editor(
         cx: &mut TestAppContext,
         editor_mode: EditorMode,
@@ -5,13 +5,14 @@ use std::{
 
 use collections::{hash_map, HashMap, HashSet};
 use git::diff::{DiffHunk, DiffHunkStatus};
-use gpui::{AppContext, Hsla, Model, Task, View};
+use gpui::{AppContext, HighlightStyle, Hsla, Model, Task, View};
 use language::Buffer;
 use multi_buffer::{
     Anchor, ExcerptRange, MultiBuffer, MultiBufferRow, MultiBufferSnapshot, ToPoint,
 };
-use settings::SettingsStore;
-use text::{BufferId, Point};
+use project::project_settings::ProjectSettings;
+use settings::{Settings, SettingsStore};
+use text::{BufferId, Point, ToOffset as _};
 use ui::{
     div, ActiveTheme, Context as _, IntoElement, ParentElement, Styled, ViewContext, VisualContext,
 };
@@ -21,8 +22,8 @@ use crate::{
     editor_settings::CurrentLineHighlight,
     git::{diff_hunk_to_display, DisplayDiffHunk},
     hunk_status, hunks_for_selections, BlockDisposition, BlockId, BlockProperties, BlockStyle,
-    DiffRowHighlight, Editor, EditorSnapshot, ExpandAllHunkDiffs, RangeToAnchorExt,
-    RevertSelectedHunks, ToDisplayPoint, ToggleHunkDiff,
+    DiffRowHighlight, DiffWordHighlight, Editor, EditorSnapshot, ExpandAllHunkDiffs,
+    RangeToAnchorExt, RevertSelectedHunks, ToDisplayPoint, ToggleHunkDiff,
 };
 
 #[derive(Debug, Clone)]
@@ -60,6 +61,7 @@ pub(super) struct ExpandedHunk {
     pub diff_base_byte_range: Range<usize>,
     pub status: DiffHunkStatus,
     pub folded: bool,
+    pub word_diff_ranges: Vec<Range<Anchor>>,
 }
 
 impl Editor {
@@ -202,6 +204,7 @@ impl Editor {
                     for hunk in hunks_to_expand {
                         editor.expand_diff_hunk(None, &hunk, cx);
                     }
+                    editor.refresh_diff_word_highlights(cx);
                     cx.notify();
                 })
                 .ok();
@@ -229,28 +232,64 @@ impl Editor {
 
         let buffer = self.buffer().clone();
         let snapshot = self.snapshot(cx);
-        let (diff_base_buffer, deleted_text_lines) = buffer.update(cx, |buffer, cx| {
-            let hunk = buffer_diff_hunk(&snapshot.buffer_snapshot, multi_buffer_row_range.clone())?;
-            let mut buffer_ranges = buffer.range_to_buffer_ranges(multi_buffer_row_range, cx);
-            if buffer_ranges.len() == 1 {
-                let (buffer, _, _) = buffer_ranges.pop()?;
-                let diff_base_buffer = diff_base_buffer
-                    .or_else(|| self.current_diff_base_buffer(&buffer, cx))
-                    .or_else(|| create_diff_base_buffer(&buffer, cx))?;
-                let buffer = buffer.read(cx);
-                let deleted_text_lines = buffer.diff_base().map(|diff_base| {
+        let compute_word_diff = hunk.status == DiffHunkStatus::Modified
+            && ProjectSettings::get_global(cx).git.word_diff_enabled();
+        let (diff_base_buffer, deleted_text_lines, word_diff) =
+            buffer.update(cx, |buffer, cx| {
+                let hunk =
+                    buffer_diff_hunk(&snapshot.buffer_snapshot, multi_buffer_row_range.clone())?;
+                let mut buffer_ranges = buffer.range_to_buffer_ranges(multi_buffer_row_range, cx);
+                if buffer_ranges.len() == 1 {
+                    let (buffer, _, excerpt_id) = buffer_ranges.pop()?;
+                    let diff_base_buffer = diff_base_buffer
+                        .or_else(|| self.current_diff_base_buffer(&buffer, cx))
+                        .or_else(|| create_diff_base_buffer(&buffer, cx))?;
+                    let buffer = buffer.read(cx);
+                    let diff_base = buffer.diff_base()?;
                     let diff_start_row = diff_base
                         .offset_to_point(hunk.diff_base_byte_range.start)
                         .row;
                     let diff_end_row = diff_base.offset_to_point(hunk.diff_base_byte_range.end).row;
-                    let line_count = diff_end_row - diff_start_row;
-                    line_count as u8
-                })?;
-                Some((diff_base_buffer, deleted_text_lines))
-            } else {
-                None
-            }
-        })?;
+                    let deleted_text_lines = (diff_end_row - diff_start_row) as u8;
+
+                    let word_diff = compute_word_diff
+                        .then(|| {
+                            let old_text = diff_base
+                                .slice(hunk.diff_base_byte_range.clone())
+                                .to_string();
+                            let new_text = buffer
+                                .text_for_range(hunk.buffer_range.clone())
+                                .collect::<String>();
+                            git::diff::word_diff(&old_text, &new_text)
+                        })
+                        .flatten()
+                        .map(|word_diff| {
+                            // Deleted words are highlighted in an editor containing only the
+                            // hunk's diff base text, so their offsets can be used as is.
+                            let new_text_start = hunk.buffer_range.start.to_offset(buffer);
+                            let inserted_word_ranges = word_diff
+                                .new_ranges
+                                .into_iter()
+                                .filter_map(|range| {
+                                    let start = buffer.anchor_after(new_text_start + range.start);
+                                    let end = buffer.anchor_before(new_text_start + range.end);
+                                    Some(
+                                        multi_buffer_snapshot
+                                            .anchor_in_excerpt(excerpt_id, start)?
+                                            ..multi_buffer_snapshot
+                                                .anchor_in_excerpt(excerpt_id, end)?,
+                                    )
+                                })
+                                .collect::<Vec<_>>();
+                            (word_diff.old_ranges, inserted_word_ranges)
+                        });
+
+                    Some((diff_base_buffer, deleted_text_lines, word_diff))
+                } else {
+                    None
+                }
+            })?;
+        let (deleted_word_ranges, inserted_word_ranges) = word_diff.unwrap_or_default();
 
         let block_insert_index = match self.expanded_hunks.hunks.binary_search_by(|probe| {
             probe
@@ -263,9 +302,13 @@ impl Editor {
         };
 
         let block = match hunk.status {
-            DiffHunkStatus::Removed => {
-                self.insert_deleted_text_block(diff_base_buffer, deleted_text_lines, &hunk, cx)
-            }
+            DiffHunkStatus::Removed => self.insert_deleted_text_block(
+                diff_base_buffer,
+                deleted_text_lines,
+                Vec::new(),
+                &hunk,
+                cx,
+            ),
             DiffHunkStatus::Added => {
                 self.highlight_rows::<DiffRowHighlight>(
                     to_inclusive_row_range(hunk_start..hunk_end, &snapshot),
@@ -282,7 +325,13 @@ impl Editor {
                     false,
                     cx,
                 );
-                self.insert_deleted_text_block(diff_base_buffer, deleted_text_lines, &hunk, cx)
+                self.insert_deleted_text_block(
+                    diff_base_buffer,
+                    deleted_text_lines,
+                    deleted_word_ranges,
+                    &hunk,
+                    cx,
+                )
             }
         };
         self.expanded_hunks.hunks.insert(
@@ -293,22 +342,50 @@ impl Editor {
                 status: hunk.status,
                 folded: false,
                 diff_base_byte_range: hunk.diff_base_byte_range.clone(),
+                word_diff_ranges: inserted_word_ranges,
             },
         );
+        self.refresh_diff_word_highlights(cx);
 
         Some(())
     }
 
+    fn refresh_diff_word_highlights(&mut self, cx: &mut ViewContext<Self>) {
+        let word_diff_ranges = self
+            .expanded_hunks
+            .hunks(false)
+            .flat_map(|hunk| hunk.word_diff_ranges.iter().cloned())
+            .collect::<Vec<_>>();
+        if word_diff_ranges.is_empty() {
+            self.clear_highlights::<DiffWordHighlight>(cx);
+        } else {
+            self.highlight_text::<DiffWordHighlight>(
+                word_diff_ranges,
+                HighlightStyle {
+                    background_color: Some(added_word_color(cx)),
+                    ..HighlightStyle::default()
+                },
+                cx,
+            );
+        }
+    }
+
     fn insert_deleted_text_block(
         &mut self,
         diff_base_buffer: Model<Buffer>,
         deleted_text_height: u8,
+        deleted_word_ranges: Vec<Range<usize>>,
         hunk: &HunkToExpand,
         cx: &mut ViewContext<'_, Self>,
     ) -> Option<BlockId> {
         let deleted_hunk_color = deleted_hunk_color(cx);
-        let (editor_height, editor_with_deleted_text) =
-            editor_with_deleted_text(diff_base_buffer, deleted_hunk_color, hunk, cx);
+        let (editor_height, editor_with_deleted_text) = editor_with_deleted_text(
+            diff_base_buffer,
+            deleted_hunk_color,
+            deleted_word_ranges,
+            hunk,
+            cx,
+        );
         let editor_model = cx.model().clone();
         let mut new_block_ids = self.insert_blocks(
             Some(BlockProperties {
@@ -348,6 +425,7 @@ impl Editor {
             .filter_map(|expanded_hunk| expanded_hunk.block)
             .collect();
         self.clear_row_highlights::<DiffRowHighlight>();
+        self.clear_highlights::<DiffWordHighlight>(cx);
         self.remove_blocks(to_remove, None, cx);
     }
 
@@ -491,6 +569,7 @@ impl Editor {
                             editor.expand_diff_hunk(Some(diff_base_buffer.clone()), &hunk, cx);
                         }
                     }
+                    editor.refresh_diff_word_highlights(cx);
                 })
                 .ok();
         });
@@ -552,9 +631,22 @@ fn deleted_hunk_color(cx: &AppContext) -> Hsla {
     deleted_color
 }
 
+fn added_word_color(cx: &AppContext) -> Hsla {
+    let mut created_color = cx.theme().status().git().created;
+    created_color.fade_out(0.4);
+    created_color
+}
+
+fn deleted_word_color(cx: &AppContext) -> Hsla {
+    let mut deleted_color = cx.theme().status().git().deleted;
+    deleted_color.fade_out(0.4);
+    deleted_color
+}
+
 fn editor_with_deleted_text(
     diff_base_buffer: Model<Buffer>,
     deleted_color: Hsla,
+    deleted_word_ranges: Vec<Range<usize>>,
     hunk: &HunkToExpand,
     cx: &mut ViewContext<'_, Editor>,
 ) -> (u8, View<Editor>) {
@@ -587,6 +679,23 @@ fn editor_with_deleted_text(
             .anchor_after(editor.buffer.read(cx).len(cx));
 
         editor.highlight_rows::<DiffRowHighlight>(start..=end, Some(deleted_color), false, cx);
+        if !deleted_word_ranges.is_empty() {
+            let deleted_word_ranges = deleted_word_ranges
+                .into_iter()
+                .map(|range| {
+                    editor_snapshot.buffer_snapshot.anchor_after(range.start)
+                        ..editor_snapshot.buffer_snapshot.anchor_before(range.end)
+                })
+                .collect();
+            editor.highlight_text::<DiffWordHighlight>(
+                deleted_word_ranges,
+                HighlightStyle {
+                    background_color: Some(deleted_word_color(cx)),
+                    ..HighlightStyle::default()
+                },
+                cx,
+            );
+        }
 
         let subscription_editor = parent_editor.clone();
         editor._subscriptions.extend([
@@ -30,7 +30,7 @@ use std::{
     path::Path,
     sync::Arc,
 };
-use text::{BufferId, Selection};
+use text::{BufferId, Selection, ToOffset as _};
 use theme::{Theme, ThemeSettings};
 use ui::{h_flex, prelude::*, Label};
 use util::{paths::PathExt, ResultExt, TryFutureExt};
@@ -896,6 +896,23 @@ impl Item for Editor {
         Some("Editor")
     }
 
+    fn serialize_to_workspace(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let item_id = cx.view().item_id().as_u64() as ItemId;
+        let Some(path) = self.buffer().read(cx).as_singleton().and_then(|buffer| {
+            let file = buffer.read(cx).file()?.as_local()?;
+            Some(file.abs_path(cx))
+        }) else {
+            return Task::ready(Ok(()));
+        };
+
+        cx.background_executor()
+            .spawn(async move { DB.save_path(item_id, workspace_id, path).await })
+    }
+
     fn to_item_events(event: &EditorEvent, mut f: impl FnMut(ItemEvent)) {
         match event {
             EditorEvent::Closed => f(ItemEvent::CloseItem),
@@ -1088,6 +1105,14 @@ impl SearchableItem for Editor {
     ) {
         let text = self.buffer.read(cx);
         let text = text.snapshot(cx);
+        if query.is_structural() {
+            if let Some(replacement) = structural_replacement_for(&text, identifier, query) {
+                self.transact(cx, |this, cx| {
+                    this.edit([(identifier.clone(), replacement)], cx);
+                });
+            }
+            return;
+        }
         let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
         let text: Cow<_> = if text.len() == 1 {
             text.first().cloned().unwrap().into()
@@ -1112,6 +1137,12 @@ impl SearchableItem for Editor {
         let text = text.snapshot(cx);
         let mut edits = vec![];
         for m in matches {
+            if query.is_structural() {
+                if let Some(replacement) = structural_replacement_for(&text, m, query) {
+                    edits.push((m.clone(), Arc::from(replacement)));
+                }
+                continue;
+            }
             let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
             let text: Cow<_> = if text.len() == 1 {
                 text.first().cloned().unwrap().into()
@@ -1317,6 +1348,18 @@ pub fn entry_git_aware_label_color(
     }
 }
 
+/// Expands the replacement of a structural query for one of its matches, using the syntax tree
+/// of the buffer that contains the match.
+fn structural_replacement_for(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<String> {
+    let buffer = snapshot.buffer_for_excerpt(range.start.excerpt_id)?;
+    let range = range.start.text_anchor.to_offset(buffer)..range.end.text_anchor.to_offset(buffer);
+    query.structural_replacement_for(buffer, range)
+}
+
 fn path_for_buffer<'a>(
     buffer: &Model<MultiBuffer>,
     height: usize,
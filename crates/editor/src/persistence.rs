@@ -1,7 +1,10 @@
+use anyhow::Result;
 use std::path::PathBuf;
 
 use db::sqlez_macros::sql;
 use db::{define_connection, query};
+use futures::{future::BoxFuture, FutureExt};
+use project::buffer_history::BufferHistoryStore;
 
 use workspace::{ItemId, WorkspaceDb, WorkspaceId};
 
@@ -15,6 +18,13 @@ define_connection!(
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     // )
+    //
+    // buffer_histories(
+    //   abs_path: PathBuf,
+    //   content_hash: String,
+    //   history: Vec<u8>,
+    //   saved_at: String,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -31,6 +41,15 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            CREATE TABLE buffer_histories(
+                abs_path BLOB NOT NULL,
+                content_hash TEXT NOT NULL,
+                history BLOB NOT NULL,
+                saved_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY(abs_path)
+            ) STRICT;
         )];
 );
 
@@ -80,4 +99,63 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    query! {
+        pub async fn get_buffer_history(abs_path: PathBuf, content_hash: String) -> Result<Option<Vec<u8>>> {
+            SELECT history FROM buffer_histories
+            WHERE abs_path = ? AND content_hash = ?
+        }
+    }
+
+    query! {
+        pub async fn save_buffer_history(abs_path: PathBuf, content_hash: String, history: Vec<u8>) -> Result<()> {
+            INSERT INTO buffer_histories
+                (abs_path, content_hash, history)
+            VALUES
+                (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                content_hash = ?2,
+                history = ?3,
+                saved_at = CURRENT_TIMESTAMP
+        }
+    }
+
+    // Only the histories of the most recently saved files are kept around
+    query! {
+        pub async fn prune_buffer_histories() -> Result<()> {
+            DELETE FROM buffer_histories
+            WHERE abs_path NOT IN (
+                SELECT abs_path FROM buffer_histories
+                ORDER BY saved_at DESC
+                LIMIT 500
+            )
+        }
+    }
+}
+
+/// Stores buffer histories in the editor database.
+pub(crate) struct BufferHistoryDb;
+
+impl BufferHistoryStore for BufferHistoryDb {
+    fn load(
+        &self,
+        abs_path: PathBuf,
+        content_hash: String,
+    ) -> BoxFuture<'static, Result<Option<Vec<u8>>>> {
+        DB.get_buffer_history(abs_path, content_hash).boxed()
+    }
+
+    fn save(
+        &self,
+        abs_path: PathBuf,
+        content_hash: String,
+        history: Vec<u8>,
+    ) -> BoxFuture<'static, Result<()>> {
+        async move {
+            DB.save_buffer_history(abs_path, content_hash, history)
+                .await?;
+            DB.prune_buffer_histories().await
+        }
+        .boxed()
+    }
 }
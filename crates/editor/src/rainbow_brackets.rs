@@ -0,0 +1,354 @@
+use std::ops::Range;
+
+use gpui::{AppContext, HighlightStyle, Hsla};
+use language::language_settings::language_settings;
+use multi_buffer::Anchor;
+
+use crate::{
+    display_map::{HighlightedChunk, ToDisplayPoint},
+    DisplayPoint, DisplayRow, DisplaySnapshot,
+};
+
+/// A pair of brackets, along with how deeply it is nested within other brackets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RainbowBracketPair {
+    pub open: Range<DisplayPoint>,
+    pub close: Range<DisplayPoint>,
+    pub depth: u32,
+    pub show_guide: bool,
+}
+
+/// Returns the bracket pairs overlapping the given range, for the buffers that have
+/// rainbow brackets enabled.
+pub fn rainbow_bracket_pairs_in_range(
+    range: Range<Anchor>,
+    snapshot: &DisplaySnapshot,
+    cx: &AppContext,
+) -> Vec<RainbowBracketPair> {
+    let multibuffer = &snapshot.buffer_snapshot;
+    let mut pairs = Vec::new();
+    for (excerpt_id, buffer, buffer_range) in multibuffer.excerpts_in_ranges([range]) {
+        let settings = language_settings(buffer.language(), buffer.file(), cx).rainbow_brackets;
+        if !settings.enabled {
+            continue;
+        }
+
+        let mut bracket_ranges = buffer
+            .bracket_ranges(buffer_range)
+            // Pairs like quotes use the same text on both sides and aren't worth coloring.
+            .filter(|(open, close)| {
+                buffer.text_for_range(open.clone()).collect::<String>()
+                    != buffer.text_for_range(close.clone()).collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        bracket_ranges.sort_by_key(|(open, close)| (open.start, std::cmp::Reverse(close.end)));
+        bracket_ranges.dedup();
+
+        let to_display_range = |range: Range<usize>| {
+            let start =
+                multibuffer.anchor_in_excerpt(excerpt_id, buffer.anchor_after(range.start))?;
+            let end = multibuffer.anchor_in_excerpt(excerpt_id, buffer.anchor_before(range.end))?;
+            let range = start.to_display_point(snapshot)..end.to_display_point(snapshot);
+            // Brackets hidden within a fold collapse to an empty range.
+            (range.start < range.end).then_some(range)
+        };
+
+        let mut enclosing_bracket_ends = Vec::new();
+        for (open, close) in bracket_ranges {
+            while enclosing_bracket_ends
+                .last()
+                .map_or(false, |end| *end <= open.start)
+            {
+                enclosing_bracket_ends.pop();
+            }
+            let depth = enclosing_bracket_ends.len() as u32;
+            enclosing_bracket_ends.push(close.end);
+
+            if let Some((open, close)) = to_display_range(open).zip(to_display_range(close)) {
+                pairs.push(RainbowBracketPair {
+                    open,
+                    close,
+                    depth,
+                    show_guide: settings.guides,
+                });
+            }
+        }
+    }
+    pairs
+}
+
+/// Overrides the color of the chunks covered by the given bracket ranges, which must be
+/// sorted and must not overlap. `start_row` is the display row of the first chunk.
+pub fn colorize_brackets<'a>(
+    chunks: impl Iterator<Item = HighlightedChunk<'a>>,
+    start_row: DisplayRow,
+    brackets: &'a [(Range<DisplayPoint>, Hsla)],
+) -> impl Iterator<Item = HighlightedChunk<'a>> {
+    RainbowBracketChunks {
+        chunks,
+        pending_chunk: None,
+        position: DisplayPoint::new(start_row, 0),
+        brackets,
+    }
+}
+
+struct RainbowBracketChunks<'a, I> {
+    chunks: I,
+    pending_chunk: Option<HighlightedChunk<'a>>,
+    position: DisplayPoint,
+    brackets: &'a [(Range<DisplayPoint>, Hsla)],
+}
+
+impl<'a, I: Iterator<Item = HighlightedChunk<'a>>> RainbowBracketChunks<'a, I> {
+    fn advance(&mut self, text: &str) {
+        let row = self.position.row().0 + text.matches('\n').count() as u32;
+        let column = match text.rfind('\n') {
+            Some(newline_ix) => (text.len() - newline_ix - 1) as u32,
+            None => self.position.column() + text.len() as u32,
+        };
+        self.position = DisplayPoint::new(DisplayRow(row), column);
+    }
+
+    /// Returns the offset in `text`, which starts at the current position, at which the
+    /// given point is located.
+    fn offset_of(&self, text: &str, point: DisplayPoint) -> Option<usize> {
+        let mut row = self.position.row();
+        let mut column = self.position.column();
+        let mut line_offset = 0;
+        for line in text.split('\n') {
+            if row == point.row() {
+                let offset = line_offset + point.column().checked_sub(column)? as usize;
+                return (offset < line_offset + line.len()).then_some(offset);
+            }
+            line_offset += line.len() + 1;
+            row.0 += 1;
+            column = 0;
+        }
+        None
+    }
+
+    fn split_chunk(
+        &mut self,
+        chunk: HighlightedChunk<'a>,
+        offset: usize,
+        color: Option<Hsla>,
+    ) -> HighlightedChunk<'a> {
+        if offset == 0 || offset >= chunk.text.len() || !chunk.text.is_char_boundary(offset) {
+            self.advance(chunk.text);
+            return chunk;
+        }
+
+        let (head, tail) = chunk.text.split_at(offset);
+        self.pending_chunk = Some(HighlightedChunk {
+            text: tail,
+            style: chunk.style,
+            is_tab: false,
+            renderer: None,
+        });
+        self.advance(head);
+        HighlightedChunk {
+            text: head,
+            style: match color {
+                Some(color) => Some(HighlightStyle {
+                    color: Some(color),
+                    ..chunk.style.unwrap_or_default()
+                }),
+                None => chunk.style,
+            },
+            is_tab: false,
+            renderer: None,
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = HighlightedChunk<'a>>> Iterator for RainbowBracketChunks<'a, I> {
+    type Item = HighlightedChunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.pending_chunk.take().or_else(|| self.chunks.next())?;
+        if chunk.is_tab || chunk.renderer.is_some() {
+            self.advance(chunk.text);
+            return Some(chunk);
+        }
+
+        while let Some((range, _)) = self.brackets.first() {
+            if range.end <= self.position {
+                self.brackets = &self.brackets[1..];
+            } else {
+                break;
+            }
+        }
+        let Some((range, color)) = self.brackets.first().cloned() else {
+            self.advance(chunk.text);
+            return Some(chunk);
+        };
+
+        if range.start <= self.position {
+            // Brackets never span multiple lines, so the rest of the bracket is on this row.
+            let len = (range.end.column() - self.position.column()) as usize;
+            if len >= chunk.text.len() {
+                self.advance(chunk.text);
+                return Some(HighlightedChunk {
+                    style: Some(HighlightStyle {
+                        color: Some(color),
+                        ..chunk.style.unwrap_or_default()
+                    }),
+                    ..chunk
+                });
+            }
+            Some(self.split_chunk(chunk, len, Some(color)))
+        } else {
+            match self.offset_of(chunk.text, range.start) {
+                Some(offset) => Some(self.split_chunk(chunk, offset, None)),
+                None => {
+                    self.advance(chunk.text);
+                    Some(chunk)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::build_editor};
+    use gpui::{hsla, Context as _, TestAppContext};
+    use language::{language_settings::RainbowBracketSettings, Buffer, Language, LanguageConfig};
+    use multi_buffer::MultiBuffer;
+    use std::sync::Arc;
+    use text::Point;
+
+    #[gpui::test]
+    async fn test_rainbow_bracket_pairs(cx: &mut TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.rainbow_brackets = Some(RainbowBracketSettings {
+                enabled: true,
+                guides: false,
+            });
+        });
+
+        // Pairs are nested by their position in the buffer, including parentheses that only
+        // group an expression.
+        assert_eq!(
+            bracket_pairs("fn f(a: [u8; 2]) { g((1)); }", None, cx).await,
+            [
+                ((0, 4), (0, 15), 0),
+                ((0, 8), (0, 14), 1),
+                ((0, 17), (0, 27), 0),
+                ((0, 20), (0, 24), 1),
+                ((0, 21), (0, 23), 2),
+            ]
+        );
+
+        // A bracket without a counterpart isn't colored and doesn't change the depth of
+        // the pairs around it.
+        assert_eq!(
+            bracket_pairs("fn f() { g(1)); }", None, cx).await,
+            [
+                ((0, 4), (0, 5), 0),
+                ((0, 7), (0, 16), 0),
+                ((0, 10), (0, 12), 1)
+            ]
+        );
+
+        // Pairs enclosing the visible range are found even though their brackets are
+        // outside of it, so that the visible pairs keep their depth.
+        let text = "fn f() {\n    g(\n        h(1),\n    );\n}\n";
+        assert_eq!(
+            bracket_pairs(text, Some(Point::new(2, 0)..Point::new(2, 13)), cx).await,
+            [
+                ((0, 7), (4, 0), 0),
+                ((1, 5), (3, 4), 1),
+                ((2, 9), (2, 11), 2),
+            ]
+        );
+    }
+
+    /// Returns the start of the brackets of the pairs overlapping the range, as rows and
+    /// columns, along with the pairs' depths.
+    async fn bracket_pairs(
+        text: &str,
+        range: Option<Range<Point>>,
+        cx: &mut TestAppContext,
+    ) -> Vec<((u32, u32), (u32, u32), u32)> {
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig::default(),
+                Some(tree_sitter_rust::language()),
+            )
+            .with_brackets_query(
+                r#"
+                ("(" @open ")" @close)
+                ("[" @open "]" @close)
+                ("{" @open "}" @close)
+                "#,
+            )
+            .unwrap(),
+        );
+        let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+        let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+        let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+        editor
+            .condition::<crate::EditorEvent>(cx, |editor, cx| {
+                !editor.buffer.read(cx).is_parsing(cx)
+            })
+            .await;
+
+        editor.update(cx, |editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            let buffer = &snapshot.display_snapshot.buffer_snapshot;
+            let range = match range {
+                Some(range) => buffer.anchor_before(range.start)..buffer.anchor_after(range.end),
+                None => buffer.anchor_before(0)..buffer.anchor_after(buffer.len()),
+            };
+            rainbow_bracket_pairs_in_range(range, &snapshot.display_snapshot, cx)
+                .into_iter()
+                .map(|pair| {
+                    (
+                        (pair.open.start.row().0, pair.open.start.column()),
+                        (pair.close.start.row().0, pair.close.start.column()),
+                        pair.depth,
+                    )
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_colorize_brackets() {
+        let red = hsla(0., 1., 0.5, 1.);
+        let blue = hsla(0.6, 1., 0.5, 1.);
+        let point = |row, column| DisplayPoint::new(DisplayRow(row), column);
+        let brackets = [
+            (point(1, 6)..point(1, 7), red),
+            (point(1, 10)..point(1, 11), blue),
+            (point(2, 0)..point(2, 1), red),
+        ];
+
+        let chunk = |text| HighlightedChunk {
+            text,
+            style: None,
+            is_tab: false,
+            renderer: None,
+        };
+        let chunks = [chunk("fn foo(a: [u"), chunk("8]) {\n}\n")];
+
+        let colorized = colorize_brackets(chunks.into_iter(), DisplayRow(1), &brackets)
+            .map(|chunk| (chunk.text, chunk.style.and_then(|style| style.color)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colorized,
+            [
+                ("fn foo", None),
+                ("(", Some(red)),
+                ("a: ", None),
+                ("[", Some(blue)),
+                ("u", None),
+                ("8]) {\n", None),
+                ("}", Some(red)),
+                ("\n", None),
+            ]
+        );
+    }
+}
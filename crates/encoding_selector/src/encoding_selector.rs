@@ -0,0 +1,362 @@
+mod active_buffer_encoding;
+
+pub use active_buffer_encoding::ActiveBufferEncoding;
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use language::{Buffer, Encoding};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, ModalView, Workspace};
+
+actions!(encoding_selector, [ReopenWithEncoding, SaveWithEncoding]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(EncodingSelector::register).detach();
+}
+
+/// What happens to the active buffer when an encoding is chosen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodingSelectorMode {
+    /// Reload the buffer's file, decoding it with the chosen encoding.
+    Reopen,
+    /// Save the buffer, converting its file to the chosen encoding.
+    Save,
+}
+
+pub struct EncodingSelector {
+    picker: View<Picker<EncodingSelectorDelegate>>,
+}
+
+impl EncodingSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(move |workspace, _: &ReopenWithEncoding, cx| {
+            Self::toggle(workspace, EncodingSelectorMode::Reopen, cx);
+        });
+        workspace.register_action(move |workspace, _: &SaveWithEncoding, cx| {
+            Self::toggle(workspace, EncodingSelectorMode::Save, cx);
+        });
+    }
+
+    pub fn toggle(
+        workspace: &mut Workspace,
+        mode: EncodingSelectorMode,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Option<()> {
+        let (_, buffer, _) = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .active_excerpt(cx)?;
+        buffer.read(cx).file()?;
+        let project = workspace.project().clone();
+
+        workspace.toggle_modal(cx, move |cx| {
+            EncodingSelector::new(buffer, project, mode, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        mode: EncodingSelectorMode,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate =
+            EncodingSelectorDelegate::new(cx.view().downgrade(), buffer, project, mode, cx);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for EncodingSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for EncodingSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EncodingSelector {}
+impl ModalView for EncodingSelector {}
+
+pub struct EncodingSelectorDelegate {
+    encoding_selector: WeakView<EncodingSelector>,
+    buffer: Model<Buffer>,
+    project: Model<Project>,
+    mode: EncodingSelectorMode,
+    encodings: Vec<Encoding>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EncodingSelectorDelegate {
+    fn new(
+        encoding_selector: WeakView<EncodingSelector>,
+        buffer: Model<Buffer>,
+        project: Model<Project>,
+        mode: EncodingSelectorMode,
+        cx: &AppContext,
+    ) -> Self {
+        let current_encoding = buffer.read(cx).encoding();
+        let encodings = Encoding::all().collect::<Vec<_>>();
+        let candidates = encodings
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, encoding)| {
+                StringMatchCandidate::new(candidate_id, encoding.display_name())
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            encoding_selector,
+            buffer,
+            project,
+            mode,
+            selected_index: encodings
+                .iter()
+                .position(|encoding| *encoding == current_encoding)
+                .unwrap_or(0),
+            encodings,
+            candidates,
+            matches: vec![],
+        }
+    }
+}
+
+impl PickerDelegate for EncodingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        match self.mode {
+            EncodingSelectorMode::Reopen => "Reopen with encoding...".into(),
+            EncodingSelectorMode::Save => "Save with encoding...".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let encoding = self.encodings[mat.candidate_id];
+            let buffer = self.buffer.clone();
+            let task = self.project.update(cx, |project, cx| match self.mode {
+                EncodingSelectorMode::Reopen => {
+                    project.reload_buffer_with_encoding(buffer, encoding, cx)
+                }
+                EncodingSelectorMode::Save => {
+                    project.save_buffer_with_encoding(buffer, encoding, cx)
+                }
+            });
+            task.detach_and_notify_err(cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.encoding_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let mut label = mat.string.clone();
+        if self.encodings[mat.candidate_id] == self.buffer.read(cx).encoding() {
+            label.push_str(" (current)");
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(label, mat.positions.clone())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Fs};
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_save_and_reopen_with_encoding(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({})).await;
+        fs.insert_file("/dir/a.txt", b"caf\xE9 cr\xE8me br\xFBl\xE9e".to_vec())
+            .await;
+
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        let buffer = editor.update(cx, |editor, cx| {
+            editor.buffer().read(cx).as_singleton().unwrap()
+        });
+        assert_eq!(
+            buffer.update(cx, |buffer, _| buffer.encoding()),
+            Encoding::from_name("windows-1252", false).unwrap()
+        );
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "café crème brûlée"
+        );
+
+        select_encoding(&workspace, SaveWithEncoding, "UTF-16LE with BOM", cx);
+        let utf16le_bom = Encoding::from_name("utf-16le", true).unwrap();
+        assert_eq!(
+            buffer.update(cx, |buffer, _| buffer.encoding()),
+            utf16le_bom
+        );
+        assert_eq!(
+            fs.load_bytes(Path::new("/dir/a.txt")).await.unwrap(),
+            utf16le_bom.encode("café crème brûlée").unwrap()
+        );
+
+        // Reopening the file with the wrong encoding garbles its text, but reopening
+        // it with the right one restores it.
+        select_encoding(&workspace, ReopenWithEncoding, "UTF-16BE with BOM", cx);
+        assert_ne!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "café crème brûlée"
+        );
+        select_encoding(&workspace, ReopenWithEncoding, "UTF-16LE with BOM", cx);
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "café crème brûlée"
+        );
+        assert_eq!(
+            buffer.update(cx, |buffer, _| buffer.encoding()),
+            utf16le_bom
+        );
+    }
+
+    fn select_encoding(
+        workspace: &View<Workspace>,
+        action: impl gpui::Action,
+        encoding_name: &str,
+        cx: &mut VisualTestContext,
+    ) {
+        cx.dispatch_action(action);
+        cx.run_until_parked();
+        let picker = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<EncodingSelector>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+        picker.update(cx, |picker, cx| {
+            let ix = picker
+                .delegate
+                .candidates
+                .iter()
+                .position(|candidate| candidate.string == encoding_name)
+                .unwrap();
+            picker.set_selected_index(ix, false, cx);
+        });
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
@@ -22,7 +22,7 @@ use std::{
     time::{Duration, SystemTime},
 };
 use tempfile::{NamedTempFile, TempDir};
-use text::LineEnding;
+use text::{Encoding, LineEnding};
 use util::ResultExt;
 
 #[cfg(any(test, feature = "test-support"))]
@@ -66,10 +66,21 @@ pub trait Fs: Send + Sync {
     async fn trash_file(&self, path: &Path, options: RemoveOptions) -> Result<()> {
         self.remove_file(path, options).await
     }
-    async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>>;
+    async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read + Send>>;
     async fn load(&self, path: &Path) -> Result<String>;
+    async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+        self.save_with_encoding(path, text, line_ending, Encoding::default())
+            .await
+    }
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -124,6 +135,8 @@ pub struct RemoveOptions {
 pub struct Metadata {
     pub inode: u64,
     pub mtime: SystemTime,
+    /// The size of the file in bytes.
+    pub len: u64,
     pub is_symlink: bool,
     pub is_dir: bool,
 }
@@ -309,7 +322,7 @@ impl Fs for RealFs {
         self.trash_file(path, options).await
     }
 
-    async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>> {
+    async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read + Send>> {
         Ok(Box::new(std::fs::File::open(path)?))
     }
 
@@ -319,6 +332,12 @@ impl Fs for RealFs {
         Ok(text)
     }
 
+    async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        let path = path.to_path_buf();
+        let bytes = smol::unblock(|| std::fs::read(path)).await?;
+        Ok(bytes)
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
             let mut tmp_file = if cfg!(target_os = "linux") {
@@ -338,15 +357,33 @@ impl Fs for RealFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        // Encode the text before touching the file, so that text the encoding can't
+        // represent doesn't leave it truncated.
+        let encoded = if encoding == Encoding::UTF8 {
+            None
+        } else {
+            Some(encoding.encode(&chunks(text, line_ending).collect::<String>())?)
+        };
+
         let buffer_size = text.summary().len.min(10 * 1024);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
         let file = smol::fs::File::create(path).await?;
         let mut writer = smol::io::BufWriter::with_capacity(buffer_size, file);
-        for chunk in chunks(text, line_ending) {
-            writer.write_all(chunk.as_bytes()).await?;
+        if let Some(encoded) = encoded {
+            writer.write_all(&encoded).await?;
+        } else {
+            for chunk in chunks(text, line_ending) {
+                writer.write_all(chunk.as_bytes()).await?;
+            }
         }
         writer.flush().await?;
         Ok(())
@@ -396,6 +433,7 @@ impl Fs for RealFs {
         Ok(Some(Metadata {
             inode,
             mtime: metadata.modified().unwrap(),
+            len: metadata.len(),
             is_symlink,
             is_dir: metadata.file_type().is_dir(),
         }))
@@ -648,6 +686,8 @@ struct FakeFsState {
     buffered_events: Vec<PathBuf>,
     metadata_call_count: usize,
     read_dir_call_count: usize,
+    /// Files that fail to be read after the given number of bytes.
+    read_errors: BTreeMap<PathBuf, usize>,
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -669,6 +709,20 @@ enum FakeFsEntry {
     },
 }
 
+/// A reader that fails with the given error, for simulating read errors in the middle of a file.
+#[cfg(any(test, feature = "test-support"))]
+struct FailingReader(Option<io::Error>);
+
+#[cfg(any(test, feature = "test-support"))]
+impl io::Read for FailingReader {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        match self.0.take() {
+            Some(error) => Err(error),
+            None => Ok(0),
+        }
+    }
+}
+
 #[cfg(any(test, feature = "test-support"))]
 impl FakeFsState {
     fn read_path(&self, target: &Path) -> Result<Arc<Mutex<FakeFsEntry>>> {
@@ -799,6 +853,7 @@ impl FakeFs {
                 events_paused: false,
                 read_dir_call_count: 0,
                 metadata_call_count: 0,
+                read_errors: Default::default(),
             }),
         })
     }
@@ -876,6 +931,13 @@ impl FakeFs {
         self.state.lock().events_paused = true;
     }
 
+    /// Makes reads of the given file that are started with [`Fs::open_sync`] fail after
+    /// `len` bytes have been read.
+    pub fn fail_reads_after(&self, path: impl AsRef<Path>, len: usize) {
+        let path = normalize_path(path.as_ref());
+        self.state.lock().read_errors.insert(path, len);
+    }
+
     pub fn buffered_event_count(&self) -> usize {
         self.state.lock().buffered_events.len()
     }
@@ -1423,8 +1485,22 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>> {
-        let bytes = self.load_internal(path).await?;
+    async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read + Send>> {
+        let mut bytes = self.load_internal(path).await?;
+        let read_error = self
+            .state
+            .lock()
+            .read_errors
+            .get(&normalize_path(path))
+            .copied();
+        if let Some(len) = read_error {
+            bytes.truncate(len);
+            let error = io::Error::new(io::ErrorKind::Other, "simulated read error");
+            return Ok(Box::new(io::Read::chain(
+                io::Cursor::new(bytes),
+                FailingReader(Some(error)),
+            )));
+        }
         Ok(Box::new(io::Cursor::new(bytes)))
     }
 
@@ -1433,6 +1509,10 @@ impl Fs for FakeFs {
         Ok(String::from_utf8(content.clone())?)
     }
 
+    async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        self.load_internal(path).await
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
@@ -1440,14 +1520,20 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
-        let content = chunks(text, line_ending).collect::<String>();
+        let content = encoding.encode(&chunks(text, line_ending).collect::<String>())?;
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
-        self.write_file_internal(path, content.into_bytes())?;
+        self.write_file_internal(path, content)?;
         Ok(())
     }
 
@@ -1496,15 +1582,21 @@ impl Fs for FakeFs {
 
             let entry = entry.lock();
             Ok(Some(match &*entry {
-                FakeFsEntry::File { inode, mtime, .. } => Metadata {
+                FakeFsEntry::File {
+                    inode,
+                    mtime,
+                    content,
+                } => Metadata {
                     inode: *inode,
                     mtime: *mtime,
+                    len: content.len() as u64,
                     is_dir: false,
                     is_symlink,
                 },
                 FakeFsEntry::Dir { inode, mtime, .. } => Metadata {
                     inode: *inode,
                     mtime: *mtime,
+                    len: 0,
                     is_dir: true,
                     is_symlink,
                 },
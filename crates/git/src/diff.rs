@@ -1,4 +1,5 @@
 use rope::Rope;
+use similar::{Algorithm, DiffTag};
 use std::{iter, ops::Range};
 use sum_tree::SumTree;
 use text::{Anchor, BufferId, BufferSnapshot, OffsetRangeExt, Point};
@@ -285,6 +286,115 @@ impl BufferDiff {
     }
 }
 
+/// Modified hunks whose old or new text is longer than this are not word-diffed,
+/// as the result is rarely useful and expensive to compute.
+const MAX_WORD_DIFF_LEN: usize = 8 * 1024;
+
+/// Word-level changes between the old and the new text of a modified hunk.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WordDiff {
+    /// Byte ranges in the old text that were deleted or replaced.
+    pub old_ranges: Vec<Range<usize>>,
+    /// Byte ranges in the new text that were inserted or replaced.
+    pub new_ranges: Vec<Range<usize>>,
+}
+
+/// Computes the words that changed between the old and the new text of a hunk.
+///
+/// Returns `None` when the texts are too large, or when they have no words in common,
+/// in which case highlighting the whole lines conveys the change better.
+pub fn word_diff(old_text: &str, new_text: &str) -> Option<WordDiff> {
+    if old_text.len() > MAX_WORD_DIFF_LEN || new_text.len() > MAX_WORD_DIFF_LEN {
+        return None;
+    }
+
+    let old_tokens = tokenize(old_text);
+    let new_tokens = tokenize(new_text);
+    let old_offsets = token_offsets(&old_tokens);
+    let new_offsets = token_offsets(&new_tokens);
+
+    let mut word_diff = WordDiff::default();
+    let mut has_common_words = false;
+    for op in similar::capture_diff_slices(Algorithm::Myers, &old_tokens, &new_tokens) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            has_common_words |= old_tokens[old_range]
+                .iter()
+                .any(|token| !token.trim().is_empty());
+            continue;
+        }
+
+        push_merged(
+            &mut word_diff.old_ranges,
+            old_offsets[old_range.start]..old_offsets[old_range.end],
+        );
+        push_merged(
+            &mut word_diff.new_ranges,
+            new_offsets[new_range.start]..new_offsets[new_range.end],
+        );
+    }
+
+    has_common_words.then_some(word_diff)
+}
+
+/// Splits text into words, runs of whitespace, and individual punctuation characters.
+fn tokenize(text: &str) -> Vec<&str> {
+    #[derive(PartialEq)]
+    enum CharKind {
+        Word,
+        Whitespace,
+        Punctuation,
+    }
+
+    fn char_kind(c: char) -> CharKind {
+        if c.is_alphanumeric() || c == '_' {
+            CharKind::Word
+        } else if c.is_whitespace() {
+            CharKind::Whitespace
+        } else {
+            CharKind::Punctuation
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut token_start = 0;
+    let mut prev_kind = None;
+    for (ix, c) in text.char_indices() {
+        let kind = char_kind(c);
+        if ix > token_start && (kind == CharKind::Punctuation || prev_kind.as_ref() != Some(&kind))
+        {
+            tokens.push(&text[token_start..ix]);
+            token_start = ix;
+        }
+        prev_kind = Some(kind);
+    }
+    if token_start < text.len() {
+        tokens.push(&text[token_start..]);
+    }
+    tokens
+}
+
+fn token_offsets(tokens: &[&str]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(tokens.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for token in tokens {
+        offset += token.len();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+fn push_merged(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    if range.is_empty() {
+        return;
+    }
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
 /// Range (crossing new lines), old, new
 #[cfg(any(test, feature = "test-support"))]
 #[track_caller]
@@ -367,6 +477,35 @@ mod tests {
         assert_hunks(diff.hunks(&buffer), &buffer, &diff_base, &[]);
     }
 
+    #[test]
+    fn test_word_diff() {
+        let old_text = "let value = compute(first, second);\n";
+        let new_text = "let result = compute(first, third);\n";
+        let diff = word_diff(old_text, new_text).unwrap();
+        assert_eq!(
+            diff.old_ranges
+                .iter()
+                .map(|range| &old_text[range.clone()])
+                .collect::<Vec<_>>(),
+            ["value", "second"]
+        );
+        assert_eq!(
+            diff.new_ranges
+                .iter()
+                .map(|range| &new_text[range.clone()])
+                .collect::<Vec<_>>(),
+            ["result", "third"]
+        );
+
+        // Insertions only produce ranges in the new text.
+        let diff = word_diff("foo(a)\n", "foo(a, b)\n").unwrap();
+        assert!(diff.old_ranges.is_empty());
+        assert_eq!(diff.new_ranges, [5..8]);
+
+        // Texts without any words in common aren't word-diffed.
+        assert_eq!(word_diff("one two\n", "three four\n"), None);
+    }
+
     #[test]
     fn test_buffer_diff_range() {
         let diff_base = "
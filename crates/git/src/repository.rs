@@ -38,6 +38,10 @@ pub trait GitRepository: Send + Sync {
 
     fn statuses(&self, path_prefix: &Path) -> Result<GitStatus>;
 
+    /// Returns the paths of the files in the working directory whose contents differ from
+    /// the given revision, including untracked files.
+    fn changed_paths(&self, revision: &str) -> Result<Vec<RepoPath>>;
+
     fn status(&self, path: &Path) -> Option<GitFileStatus> {
         Some(self.statuses(path).ok()?.entries.first()?.1)
     }
@@ -133,6 +137,22 @@ impl GitRepository for RealGitRepository {
         GitStatus::new(&self.git_binary_path, &working_directory, path_prefix)
     }
 
+    fn changed_paths(&self, revision: &str) -> Result<Vec<RepoPath>> {
+        let repo = self.repository.lock();
+        let tree = repo
+            .revparse_single(revision)
+            .with_context(|| format!("failed to resolve git revision {revision:?}"))?
+            .peel_to_tree()?;
+        let mut options = git2::DiffOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        let diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))?;
+        Ok(diff
+            .deltas()
+            .filter(|delta| delta.status() != git2::Delta::Deleted)
+            .filter_map(|delta| Some(RepoPath::new(delta.new_file().path()?.to_path_buf())))
+            .collect())
+    }
+
     fn branches(&self) -> Result<Vec<Branch>> {
         let repo = self.repository.lock();
         let local_branches = repo.branches(Some(BranchType::Local))?;
@@ -212,6 +232,8 @@ pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
+    /// The paths returned by [`GitRepository::changed_paths`] for each revision.
+    pub changed_paths: HashMap<String, Vec<RepoPath>>,
     pub branch_name: Option<String>,
 }
 
@@ -261,6 +283,15 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn changed_paths(&self, revision: &str) -> Result<Vec<RepoPath>> {
+        let state = self.state.lock();
+        state
+            .changed_paths
+            .get(revision)
+            .cloned()
+            .with_context(|| format!("failed to resolve git revision {revision:?}"))
+    }
+
     fn branches(&self) -> Result<Vec<Branch>> {
         Ok(vec![])
     }
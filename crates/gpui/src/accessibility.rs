@@ -0,0 +1,582 @@
+use crate::{Bounds, ClickEvent, FocusId, Pixels, Point, SharedString, WindowContext};
+use collections::{FxHashMap, FxHasher};
+use smallvec::SmallVec;
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
+
+/// What kind of user interface element an accessibility node represents.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AccessibilityRole {
+    /// The root of a window's tree.
+    Window,
+    /// A container that groups other nodes.
+    #[default]
+    Group,
+    /// A button that performs an action when clicked.
+    Button,
+    /// A control that can be checked and unchecked.
+    CheckBox,
+    /// A piece of text that can't be edited.
+    Label,
+    /// A heading for a section of content.
+    Heading,
+    /// A single line of editable text.
+    TextInput,
+    /// A multi-line document, which may be editable.
+    Document,
+    /// A link to some other content.
+    Link,
+    /// An image or icon.
+    Image,
+    /// A list of items.
+    List,
+    /// An item in a list.
+    ListItem,
+    /// A tree of items.
+    Tree,
+    /// An item in a tree.
+    TreeItem,
+    /// A tab that switches between views.
+    Tab,
+    /// A container of tabs.
+    TabList,
+    /// A menu of items.
+    Menu,
+    /// An item in a menu.
+    MenuItem,
+    /// A bar of tools.
+    Toolbar,
+    /// A dialog or modal.
+    Dialog,
+    /// A tooltip.
+    Tooltip,
+    /// A region that scrolls its content.
+    ScrollArea,
+    /// A visual divider between other nodes.
+    Separator,
+    /// A terminal emulator.
+    Terminal,
+}
+
+/// The state of an accessibility node that can change as the user interacts with it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AccessibilityStates {
+    /// Whether the node can receive keyboard focus.
+    pub focusable: bool,
+    /// Whether the node has keyboard focus.
+    pub focused: bool,
+    /// Whether the node is disabled, and so can't be interacted with.
+    pub disabled: bool,
+    /// Whether the node is selected.
+    pub selected: bool,
+    /// Whether the node is checked, or `None` if it can't be checked.
+    pub checked: Option<bool>,
+    /// Whether the node is expanded, or `None` if it can't be expanded.
+    pub expanded: Option<bool>,
+    /// Whether the node's text can be edited.
+    pub editable: bool,
+    /// Whether the node's text can span multiple lines.
+    pub multiline: bool,
+}
+
+/// An action that assistive technologies can perform on an accessibility node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityAction {
+    /// Click the node, as if with the mouse.
+    Click,
+    /// Move keyboard focus to the node.
+    Focus,
+}
+
+impl AccessibilityAction {
+    /// The name of the action, as presented to assistive technologies.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessibilityAction::Click => "click",
+            AccessibilityAction::Focus => "focus",
+        }
+    }
+}
+
+/// Describes an element to assistive technologies. Elements pass these to
+/// [`WindowContext::with_accessibility_node`](crate::WindowContext::with_accessibility_node)
+/// while painting.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessibilityProperties {
+    /// What kind of element this is.
+    pub role: AccessibilityRole,
+    /// The element's name, such as a button's label. If this isn't set for a node that is
+    /// usually named by its content, like a button, it's named by the text it contains.
+    pub name: Option<SharedString>,
+    /// The element's current value, such as the text in an input.
+    pub value: Option<SharedString>,
+    /// A longer description of the element, such as its tooltip.
+    pub description: Option<SharedString>,
+    /// The element's current state.
+    pub states: AccessibilityStates,
+    /// The actions that can be performed on the element.
+    pub actions: SmallVec<[AccessibilityAction; 2]>,
+}
+
+impl AccessibilityProperties {
+    /// Creates properties for an element with the given role.
+    pub fn new(role: AccessibilityRole) -> Self {
+        Self {
+            role,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the element's name.
+    pub fn name(mut self, name: impl Into<SharedString>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the element's value.
+    pub fn value(mut self, value: impl Into<SharedString>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Sets the element's description.
+    pub fn description(mut self, description: impl Into<SharedString>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the element's state.
+    pub fn states(mut self, states: AccessibilityStates) -> Self {
+        self.states = states;
+        self
+    }
+
+    /// Adds an action that can be performed on the element.
+    pub fn action(mut self, action: AccessibilityAction) -> Self {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+        self
+    }
+}
+
+/// Identifies a node in an [`AccessibilityTree`]. Ids are derived from the element ids of the
+/// node and its ancestors, so they're stable across frames for elements that have ids.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AccessibilityNodeId(pub u64);
+
+/// A node in an [`AccessibilityTree`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityNode {
+    /// The node's id.
+    pub id: AccessibilityNodeId,
+    /// What kind of element the node represents.
+    pub role: AccessibilityRole,
+    /// The node's name.
+    pub name: Option<SharedString>,
+    /// The node's current value.
+    pub value: Option<SharedString>,
+    /// A longer description of the node.
+    pub description: Option<SharedString>,
+    /// The node's current state.
+    pub states: AccessibilityStates,
+    /// The actions that can be performed on the node.
+    pub actions: SmallVec<[AccessibilityAction; 2]>,
+    /// The node's bounds, in window coordinates.
+    pub bounds: Bounds<Pixels>,
+    /// The node's parent, or `None` for the root.
+    pub parent: Option<AccessibilityNodeId>,
+    /// The node's children, in paint order.
+    pub children: Vec<AccessibilityNodeId>,
+    pub(crate) focus_id: Option<FocusId>,
+}
+
+/// The accessibility information for a window, built from the nodes its elements contributed
+/// while painting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityTree {
+    nodes: Vec<AccessibilityNode>,
+    indices: FxHashMap<AccessibilityNodeId, usize>,
+    focus: Option<AccessibilityNodeId>,
+}
+
+impl AccessibilityTree {
+    /// The root node, which represents the window.
+    pub fn root(&self) -> &AccessibilityNode {
+        &self.nodes[0]
+    }
+
+    /// Returns the node with the given id.
+    pub fn node(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        Some(&self.nodes[*self.indices.get(&id)?])
+    }
+
+    /// Returns the children of the given node.
+    pub fn children<'a>(
+        &'a self,
+        node: &'a AccessibilityNode,
+    ) -> impl Iterator<Item = &'a AccessibilityNode> + 'a {
+        node.children.iter().filter_map(|id| self.node(*id))
+    }
+
+    /// Returns the node with keyboard focus, if it's in the tree.
+    pub fn focused(&self) -> Option<&AccessibilityNode> {
+        self.node(self.focus?)
+    }
+
+    /// Iterates over every node in the tree, in depth-first order.
+    pub fn nodes(&self) -> impl Iterator<Item = &AccessibilityNode> {
+        self.nodes.iter()
+    }
+
+    /// Returns the first node with the given role and name, in depth-first order.
+    pub fn find(&self, role: AccessibilityRole, name: &str) -> Option<&AccessibilityNode> {
+        self.nodes
+            .iter()
+            .find(|node| node.role == role && node.name.as_deref() == Some(name))
+    }
+
+    /// Returns the deepest node containing the given point, in window coordinates.
+    pub fn node_at(&self, point: Point<Pixels>) -> Option<&AccessibilityNode> {
+        let mut node = self.root();
+        if !node.bounds.contains(&point) {
+            return None;
+        }
+        // Later children are painted on top of earlier ones.
+        while let Some(child) = node
+            .children
+            .iter()
+            .rev()
+            .filter_map(|id| self.node(*id))
+            .find(|child| child.bounds.contains(&point))
+        {
+            node = child;
+        }
+        Some(node)
+    }
+
+    pub(crate) fn build(
+        bounds: Bounds<Pixels>,
+        entries: &[AccessibilityEntry],
+        focus: Option<FocusId>,
+    ) -> Self {
+        let mut tree = Self {
+            nodes: Vec::new(),
+            indices: FxHashMap::default(),
+            focus: None,
+        };
+        let root_id = AccessibilityNodeId(0);
+        tree.push(AccessibilityNode {
+            id: root_id,
+            role: AccessibilityRole::Window,
+            name: None,
+            value: None,
+            description: None,
+            states: AccessibilityStates::default(),
+            actions: SmallVec::new(),
+            bounds,
+            parent: None,
+            children: Vec::new(),
+            focus_id: None,
+        });
+
+        // Nodes that haven't been closed yet, and how many of each of their children's keys
+        // have been seen so far, to tell apart siblings that share an element id.
+        let mut stack = vec![(0, FxHashMap::<u64, u64>::default())];
+        for entry in entries {
+            match entry {
+                AccessibilityEntry::Open {
+                    properties,
+                    bounds,
+                    key,
+                    focus_id,
+                    ..
+                } => {
+                    let (parent_ix, seen_keys) = stack.last_mut().unwrap();
+                    let parent_ix = *parent_ix;
+                    let occurrence = seen_keys.entry(*key).or_default();
+                    let mut hasher = FxHasher::default();
+                    tree.nodes[parent_ix].id.hash(&mut hasher);
+                    key.hash(&mut hasher);
+                    occurrence.hash(&mut hasher);
+                    *occurrence += 1;
+                    // Id 0 is reserved for the root.
+                    let id = AccessibilityNodeId(hasher.finish().max(1));
+
+                    let mut states = properties.states;
+                    if focus_id.is_some() && *focus_id == focus {
+                        states.focused = true;
+                    }
+                    let parent_id = tree.nodes[parent_ix].id;
+                    tree.nodes[parent_ix].children.push(id);
+                    let ix = tree.push(AccessibilityNode {
+                        id,
+                        role: properties.role,
+                        name: properties.name.clone(),
+                        value: properties.value.clone(),
+                        description: properties.description.clone(),
+                        states,
+                        actions: properties.actions.clone(),
+                        bounds: *bounds,
+                        parent: Some(parent_id),
+                        children: Vec::new(),
+                        focus_id: *focus_id,
+                    });
+                    if states.focused {
+                        tree.focus = Some(id);
+                    }
+                    stack.push((ix, FxHashMap::default()));
+                }
+                AccessibilityEntry::Close => {
+                    let (ix, _) = stack.pop().unwrap();
+                    tree.name_from_contents(ix);
+                }
+            }
+        }
+        debug_assert_eq!(stack.len(), 1, "unbalanced accessibility nodes");
+        tree
+    }
+
+    fn push(&mut self, node: AccessibilityNode) -> usize {
+        let ix = self.nodes.len();
+        self.indices.insert(node.id, ix);
+        self.nodes.push(node);
+        ix
+    }
+
+    /// Names nodes like buttons, which are usually named by their label, after the text they
+    /// contain.
+    fn name_from_contents(&mut self, ix: usize) {
+        let node = &self.nodes[ix];
+        let named_by_contents = matches!(
+            node.role,
+            AccessibilityRole::Button
+                | AccessibilityRole::CheckBox
+                | AccessibilityRole::Heading
+                | AccessibilityRole::Link
+                | AccessibilityRole::ListItem
+                | AccessibilityRole::TreeItem
+                | AccessibilityRole::Tab
+                | AccessibilityRole::MenuItem
+        );
+        if node.name.is_some() || !named_by_contents {
+            return;
+        }
+
+        let mut text = String::new();
+        let mut pending = node.children.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(id) = pending.pop() {
+            let child = &self.nodes[self.indices[&id]];
+            if child.role == AccessibilityRole::Label {
+                if let Some(name) = &child.name {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    text.push_str(name);
+                }
+            }
+            pending.extend(child.children.iter().rev());
+        }
+        if !text.is_empty() {
+            self.nodes[ix].name = Some(text.into());
+        }
+    }
+
+    fn fmt_node(
+        &self,
+        node: &AccessibilityNode,
+        depth: usize,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{:indent$}{:?}", "", node.role, indent = depth * 2)?;
+        if let Some(name) = &node.name {
+            write!(f, " {name:?}")?;
+        }
+        if let Some(value) = &node.value {
+            write!(f, " value={value:?}")?;
+        }
+        let states = &node.states;
+        let flags = [
+            (states.focusable, "focusable"),
+            (states.focused, "focused"),
+            (states.disabled, "disabled"),
+            (states.selected, "selected"),
+            (states.checked == Some(true), "checked"),
+            (states.checked == Some(false), "unchecked"),
+            (states.expanded == Some(true), "expanded"),
+            (states.expanded == Some(false), "collapsed"),
+            (states.editable, "editable"),
+            (states.multiline, "multiline"),
+        ];
+        let mut flags = flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, name)| *name)
+            .peekable();
+        if flags.peek().is_some() {
+            write!(f, " [{}]", flags.collect::<Vec<_>>().join(", "))?;
+        }
+        writeln!(f)?;
+        for child in self.children(node) {
+            self.fmt_node(child, depth + 1, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the tree as an indented outline of roles, names, values and states, for use in
+/// test assertions.
+impl fmt::Display for AccessibilityTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_node(self.root(), 0, f)
+    }
+}
+
+/// Called when assistive technology clicks an accessibility node, with a click at the center of
+/// the node.
+pub type AccessibilityClickHandler = Rc<dyn Fn(&ClickEvent, &mut WindowContext)>;
+
+/// A node recorded while painting. Nodes are recorded in paint order as balanced open and close
+/// entries, so that the entries painted by a cached view can be replayed in the next frame.
+#[derive(Clone)]
+pub(crate) enum AccessibilityEntry {
+    Open {
+        properties: AccessibilityProperties,
+        bounds: Bounds<Pixels>,
+        /// A hash of the element ids enclosing the node.
+        key: u64,
+        focus_id: Option<FocusId>,
+        on_click: Option<AccessibilityClickHandler>,
+    },
+    Close,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as gpui, div, point, px, size, FocusHandle, InteractiveElement, IntoElement,
+        ParentElement, Render, StatefulInteractiveElement, Styled, TestAppContext, ViewContext,
+    };
+
+    #[test]
+    fn test_build_tree() {
+        let bounds =
+            |x: f32, width: f32| Bounds::new(point(px(x), px(0.)), size(px(width), px(10.)));
+        let open = |properties: AccessibilityProperties, bounds: Bounds<Pixels>| {
+            AccessibilityEntry::Open {
+                properties,
+                bounds,
+                key: 1,
+                focus_id: None,
+                on_click: None,
+            }
+        };
+        let entries = [
+            open(
+                AccessibilityProperties::new(AccessibilityRole::Toolbar),
+                bounds(0., 30.),
+            ),
+            open(
+                AccessibilityProperties::new(AccessibilityRole::Button),
+                bounds(0., 10.),
+            ),
+            open(
+                AccessibilityProperties::new(AccessibilityRole::Label).name("Save"),
+                bounds(0., 10.),
+            ),
+            AccessibilityEntry::Close,
+            AccessibilityEntry::Close,
+            open(
+                AccessibilityProperties::new(AccessibilityRole::Button).name("Close"),
+                bounds(20., 10.),
+            ),
+            AccessibilityEntry::Close,
+            AccessibilityEntry::Close,
+        ];
+        let tree = AccessibilityTree::build(bounds(0., 100.), &entries, None);
+        assert_eq!(
+            tree.to_string(),
+            concat!(
+                "Window\n",
+                "  Toolbar\n",
+                "    Button \"Save\"\n",
+                "      Label \"Save\"\n",
+                "    Button \"Close\"\n",
+            )
+        );
+
+        // Siblings with the same element ids are still told apart.
+        let save = tree.find(AccessibilityRole::Button, "Save").unwrap();
+        let close = tree.find(AccessibilityRole::Button, "Close").unwrap();
+        assert_ne!(save.id, close.id);
+        assert_eq!(save.parent, close.parent);
+
+        // Ids are stable when the tree is rebuilt.
+        let rebuilt = AccessibilityTree::build(bounds(0., 100.), &entries, None);
+        assert_eq!(
+            rebuilt.find(AccessibilityRole::Button, "Save").unwrap().id,
+            save.id
+        );
+
+        assert_eq!(
+            tree.node_at(point(px(25.), px(5.))).map(|node| node.id),
+            Some(close.id)
+        );
+    }
+
+    struct TestView {
+        focus_handle: FocusHandle,
+        clicks: usize,
+    }
+
+    impl Render for TestView {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div().size_full().child(
+                div()
+                    .id("save")
+                    .role(AccessibilityRole::Button)
+                    .track_focus(&self.focus_handle)
+                    .w(px(50.))
+                    .h(px(20.))
+                    .on_click(cx.listener(|this, _, _| this.clicks += 1))
+                    .child("Save"),
+            )
+        }
+    }
+
+    #[gpui::test]
+    fn test_window_accessibility_tree(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|cx| TestView {
+            focus_handle: cx.focus_handle(),
+            clicks: 0,
+        });
+
+        let tree = cx.accessibility_tree();
+        assert_eq!(
+            tree.to_string(),
+            concat!(
+                "Window\n",
+                "  Button \"Save\" [focusable]\n",
+                "    Label \"Save\"\n",
+            )
+        );
+        let save = tree.find(AccessibilityRole::Button, "Save").unwrap();
+        assert_eq!(
+            save.actions.as_slice(),
+            &[AccessibilityAction::Click, AccessibilityAction::Focus]
+        );
+        let save = save.id;
+
+        cx.simulate_accessibility_action(save, AccessibilityAction::Click);
+        view.update(cx, |view, _| assert_eq!(view.clicks, 1));
+
+        cx.simulate_accessibility_action(save, AccessibilityAction::Focus);
+        let tree = cx.accessibility_tree();
+        assert_eq!(tree.focused().map(|node| node.id), Some(save));
+        assert!(tree.node(save).unwrap().states.focused);
+    }
+}
@@ -1,15 +1,19 @@
 use crate::{
-    Action, AnyView, AnyWindowHandle, AppCell, AppContext, AsyncAppContext, AvailableSpace,
-    BackgroundExecutor, BorrowAppContext, Bounds, ClipboardItem, Context, DrawPhase, Drawable,
-    Element, Empty, Entity, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Model,
-    ModelContext, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
-    MouseUpEvent, Pixels, Platform, Point, Render, Result, Size, Task, TestDispatcher,
+    AccessibilityAction, AccessibilityNodeId, AccessibilityTree, Action, AnyView, AnyWindowHandle,
+    AppCell, AppContext, AsyncAppContext, AvailableSpace, BackgroundExecutor, BorrowAppContext,
+    Bounds, ClipboardItem, Context, DevicePixels, DrawPhase, Drawable, Element, Empty, Entity,
+    EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Model, ModelContext,
+    Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    Pixels, Platform, Point, Render, Result, Size, SoftwareRenderer, Task, TestDispatcher,
     TestPlatform, TestWindow, TextSystem, View, ViewContext, VisualContext, WindowBounds,
     WindowContext, WindowHandle, WindowOptions,
 };
 use anyhow::{anyhow, bail};
 use futures::{channel::oneshot, Stream, StreamExt};
-use std::{cell::RefCell, future::Future, ops::Deref, rc::Rc, sync::Arc, time::Duration};
+use image::RgbaImage;
+use std::{
+    cell::RefCell, future::Future, ops::Deref, path::Path, rc::Rc, sync::Arc, time::Duration,
+};
 
 /// A TestAppContext is provided to tests created with `#[gpui::test]`, it provides
 /// an implementation of `Context` with additional methods that are useful in tests.
@@ -287,6 +291,102 @@ impl TestAppContext {
         self.test_window(window_handle).simulate_resize(size);
     }
 
+    /// Renders the window's current frame on the CPU, at the window's scale factor.
+    pub fn render_window(&mut self, window: AnyWindowHandle) -> RgbaImage {
+        self.update_window(window, |_, cx| {
+            if cx.window.dirty.get() {
+                cx.draw();
+            }
+            let scale_factor = cx.scale_factor();
+            let viewport_size = cx.window.viewport_size;
+            let size = Size {
+                width: DevicePixels((viewport_size.width.0 * scale_factor).ceil() as i32),
+                height: DevicePixels((viewport_size.height.0 * scale_factor).ceil() as i32),
+            };
+            let atlas = cx
+                .window
+                .platform_window
+                .as_test()
+                .unwrap()
+                .0
+                .lock()
+                .sprite_atlas
+                .clone();
+            SoftwareRenderer::new(size, &atlas).render(&cx.window.rendered_frame.scene)
+        })
+        .unwrap()
+    }
+
+    /// Renders the window and compares it against the reference PNG at the given path, failing
+    /// the test if they differ.
+    ///
+    /// Missing references are written from the current rendering, and running the test with
+    /// `UPDATE_SNAPSHOTS=1` overwrites them. When the rendering differs, it is written next to
+    /// the reference along with an image highlighting the differing pixels in red.
+    pub fn assert_window_snapshot(&mut self, window: AnyWindowHandle, path: impl AsRef<Path>) {
+        /// Absorbs rounding differences between platforms' math libraries.
+        const TOLERANCE: u8 = 2;
+
+        let path = path.as_ref();
+        let actual = self.render_window(window);
+        if !path.exists() || std::env::var("UPDATE_SNAPSHOTS").is_ok() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).unwrap();
+            }
+            actual
+                .save(path)
+                .unwrap_or_else(|error| panic!("failed to write snapshot {path:?}: {error}"));
+            return;
+        }
+
+        let expected = image::open(path)
+            .unwrap_or_else(|error| panic!("failed to read snapshot {path:?}: {error}"))
+            .into_rgba8();
+        if let Some((differing_pixels, diff)) = crate::diff_images(&expected, &actual, TOLERANCE) {
+            let actual_path = path.with_extension("actual.png");
+            let diff_path = path.with_extension("diff.png");
+            actual.save(&actual_path).unwrap();
+            diff.save(&diff_path).unwrap();
+            panic!(
+                "{differing_pixels} pixels differ from snapshot {path:?}: see {actual_path:?} and \
+                {diff_path:?}, or run with UPDATE_SNAPSHOTS=1 to accept the new rendering"
+            );
+        }
+    }
+
+    /// Simulates assistive technology attaching to the window, draws the window if needed and
+    /// returns its accessibility tree, as last given to the platform.
+    pub fn accessibility_tree(&mut self, window: AnyWindowHandle) -> Arc<AccessibilityTree> {
+        self.test_window(window).0.lock().accessibility_active = true;
+        let tree = self
+            .update_window(window, |_, cx| {
+                if cx.window.dirty.get() || !cx.window.accessibility_active {
+                    cx.draw();
+                }
+                cx.accessibility_tree()
+            })
+            .unwrap();
+        self.test_window(window)
+            .0
+            .lock()
+            .accessibility_tree
+            .clone()
+            .unwrap_or(tree)
+    }
+
+    /// Simulates assistive technology performing an action on a node of the window's
+    /// accessibility tree.
+    pub fn simulate_accessibility_action(
+        &mut self,
+        window: AnyWindowHandle,
+        node_id: AccessibilityNodeId,
+        action: AccessibilityAction,
+    ) {
+        self.test_window(window)
+            .simulate_accessibility_action(node_id, action);
+        self.background_executor.run_until_parked();
+    }
+
     /// Returns all windows open in the test.
     pub fn windows(&self) -> Vec<AnyWindowHandle> {
         self.app.borrow().windows().clone()
@@ -762,6 +862,33 @@ impl VisualTestContext {
         self.simulate_window_resize(self.window, size)
     }
 
+    /// Renders the window's current frame on the CPU, see [`TestAppContext::render_window`].
+    pub fn render(&mut self) -> RgbaImage {
+        self.cx.render_window(self.window)
+    }
+
+    /// Compares the window against a reference PNG, see
+    /// [`TestAppContext::assert_window_snapshot`].
+    pub fn assert_snapshot(&mut self, path: impl AsRef<Path>) {
+        self.cx.assert_window_snapshot(self.window, path)
+    }
+
+    /// Returns the window's accessibility tree, see [`TestAppContext::accessibility_tree`].
+    pub fn accessibility_tree(&mut self) -> Arc<AccessibilityTree> {
+        self.cx.accessibility_tree(self.window)
+    }
+
+    /// Simulates assistive technology performing an action on a node of the window's
+    /// accessibility tree.
+    pub fn simulate_accessibility_action(
+        &mut self,
+        node_id: AccessibilityNodeId,
+        action: AccessibilityAction,
+    ) {
+        self.cx
+            .simulate_accessibility_action(self.window, node_id, action)
+    }
+
     /// debug_bounds returns the bounds of the element with the given selector.
     pub fn debug_bounds(&mut self, selector: &'static str) -> Option<Bounds<Pixels>> {
         self.update(|cx| cx.window.rendered_frame.debug_bounds.get(selector).copied())
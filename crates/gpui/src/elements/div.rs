@@ -16,7 +16,8 @@
 //! constructed by combining these two systems into an all-in-one element.
 
 use crate::{
-    point, px, size, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, AppContext, Bounds,
+    point, px, size, AccessibilityAction, AccessibilityClickHandler, AccessibilityProperties,
+    AccessibilityRole, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, AppContext, Bounds,
     ClickEvent, DispatchPhase, Element, ElementId, FocusHandle, Global, GlobalElementId, Hitbox,
     HitboxId, IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
@@ -536,6 +537,33 @@ pub trait InteractiveElement: Sized {
         Stateful { element: self }
     }
 
+    /// Describe this element to assistive technologies, such as screen readers.
+    /// Clickable and focusable elements can be clicked and focused by them too.
+    fn accessibility(mut self, properties: AccessibilityProperties) -> Self {
+        self.interactivity().accessibility = Some(Box::new(properties));
+        self
+    }
+
+    /// Set the role this element has for assistive technologies.
+    /// See [`Self::accessibility`].
+    fn role(mut self, role: AccessibilityRole) -> Self {
+        self.interactivity()
+            .accessibility
+            .get_or_insert_with(Default::default)
+            .role = role;
+        self
+    }
+
+    /// Set the name assistive technologies use for this element, such as a button's label.
+    /// See [`Self::accessibility`].
+    fn accessible_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.interactivity()
+            .accessibility
+            .get_or_insert_with(Default::default)
+            .name = Some(name.into());
+        self
+    }
+
     /// Track the focus state of the given focus handle on this element.
     /// If the focus handle is focused by the application, this element will
     /// apply its focused styles.
@@ -1276,6 +1304,7 @@ pub struct Interactivity {
     pub(crate) hover_listener: Option<Box<dyn Fn(&bool, &mut WindowContext)>>,
     pub(crate) tooltip_builder: Option<TooltipBuilder>,
     pub(crate) occlude_mouse: bool,
+    pub(crate) accessibility: Option<Box<AccessibilityProperties>>,
 
     #[cfg(debug_assertions)]
     pub(crate) location: Option<core::panic::Location<'static>>,
@@ -1497,6 +1526,35 @@ impl Interactivity {
                     return ((), element_state);
                 }
 
+                let accessibility = self
+                    .accessibility
+                    .take()
+                    .filter(|_| cx.accessibility_active())
+                    .map(|properties| {
+                        let mut properties = *properties;
+                        let mut on_click = None;
+                        if !self.click_listeners.is_empty() {
+                            properties = properties.action(AccessibilityAction::Click);
+                            // Share the listeners between the mouse and assistive technology.
+                            let listeners = mem::take(&mut self.click_listeners);
+                            let handler: AccessibilityClickHandler = Rc::new(move |event, cx| {
+                                for listener in &listeners {
+                                    listener(event, cx);
+                                }
+                            });
+                            self.click_listeners.push(Box::new({
+                                let handler = handler.clone();
+                                move |event, cx| handler(event, cx)
+                            }));
+                            on_click = Some(handler);
+                        }
+                        if self.tracked_focus_handle.is_some() {
+                            properties.states.focusable = true;
+                            properties = properties.action(AccessibilityAction::Focus);
+                        }
+                        (properties, on_click)
+                    });
+
                 style.paint(bounds, cx, |cx: &mut WindowContext| {
                     cx.with_text_style(style.text_style().cloned(), |cx| {
                         cx.with_content_mask(style.overflow_mask(bounds, cx.rem_size()), |cx| {
@@ -1519,7 +1577,18 @@ impl Interactivity {
                             }
 
                             self.paint_keyboard_listeners(cx);
-                            f(&style, cx);
+                            if let Some((properties, on_click)) = accessibility {
+                                let focus_handle = self.tracked_focus_handle.as_ref();
+                                cx.with_accessibility_node(
+                                    properties,
+                                    bounds,
+                                    focus_handle,
+                                    on_click,
+                                    |cx| f(&style, cx),
+                                );
+                            } else {
+                                f(&style, cx);
+                            }
 
                             if hitbox.is_some() {
                                 if let Some(group) = self.group.as_ref() {
@@ -1,8 +1,8 @@
 use crate::{
-    ActiveTooltip, AnyTooltip, AnyView, Bounds, DispatchPhase, Element, ElementId, GlobalElementId,
-    HighlightStyle, Hitbox, IntoElement, LayoutId, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    Pixels, Point, SharedString, Size, TextRun, TextStyle, WhiteSpace, WindowContext, WrappedLine,
-    TOOLTIP_DELAY,
+    AccessibilityProperties, AccessibilityRole, ActiveTooltip, AnyTooltip, AnyView, Bounds,
+    DispatchPhase, Element, ElementId, GlobalElementId, HighlightStyle, Hitbox, IntoElement,
+    LayoutId, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point, SharedString, Size,
+    TextRun, TextStyle, WhiteSpace, WindowContext, WrappedLine, TOOLTIP_DELAY,
 };
 use anyhow::anyhow;
 use parking_lot::{Mutex, MutexGuard};
@@ -52,7 +52,7 @@ impl Element for &'static str {
         _: &mut (),
         cx: &mut WindowContext,
     ) {
-        text_layout.paint(self, cx)
+        text_layout.paint(SharedString::from(*self), cx)
     }
 }
 
@@ -110,7 +110,7 @@ impl Element for SharedString {
         _: &mut Self::PrepaintState,
         cx: &mut WindowContext,
     ) {
-        text_layout.paint(self.as_ref(), cx)
+        text_layout.paint(self.clone(), cx)
     }
 }
 
@@ -220,7 +220,7 @@ impl Element for StyledText {
         _: &mut Self::PrepaintState,
         cx: &mut WindowContext,
     ) {
-        self.layout.paint(&self.text, cx)
+        self.layout.paint(self.text.clone(), cx)
     }
 }
 
@@ -339,7 +339,7 @@ impl TextLayout {
         element_state.bounds = Some(bounds);
     }
 
-    fn paint(&mut self, text: &str, cx: &mut WindowContext) {
+    fn paint(&mut self, text: SharedString, cx: &mut WindowContext) {
         let element_state = self.lock();
         let element_state = element_state
             .as_ref()
@@ -356,6 +356,11 @@ impl TextLayout {
             line.paint(line_origin, line_height, cx).log_err();
             line_origin.y += line.size(line_height).height;
         }
+
+        if cx.accessibility_active() {
+            let properties = AccessibilityProperties::new(AccessibilityRole::Label).name(text);
+            cx.with_accessibility_node(properties, bounds, None, None, |_| {});
+        }
     }
 
     /// Get the byte index into the input of the pixel position.
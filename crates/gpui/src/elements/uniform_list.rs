@@ -5,10 +5,10 @@
 //! elements with uniform height.
 
 use crate::{
-    point, px, size, AnyElement, AvailableSpace, Bounds, ContentMask, Element, ElementId,
-    GlobalElementId, Hitbox, InteractiveElement, Interactivity, IntoElement, LayoutId,
-    ListSizingBehavior, Pixels, Render, ScrollHandle, Size, StyleRefinement, Styled, View,
-    ViewContext, WindowContext,
+    point, px, size, AccessibilityProperties, AccessibilityRole, AnyElement, AvailableSpace,
+    Bounds, ContentMask, Element, ElementId, GlobalElementId, Hitbox, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, ListSizingBehavior, Pixels, Render, ScrollHandle, Size,
+    StyleRefinement, Styled, View, ViewContext, WindowContext,
 };
 use smallvec::SmallVec;
 use std::{cell::RefCell, cmp, ops::Range, rc::Rc};
@@ -49,6 +49,9 @@ where
         interactivity: Interactivity {
             element_id: Some(id),
             base_style: Box::new(base_style),
+            accessibility: Some(Box::new(AccessibilityProperties::new(
+                AccessibilityRole::List,
+            ))),
 
             #[cfg(debug_assertions)]
             location: Some(*core::panic::Location::caller()),
@@ -23,11 +23,11 @@ mod test;
 mod windows;
 
 use crate::{
-    point, Action, AnyWindowHandle, AsyncWindowContext, BackgroundExecutor, Bounds, DevicePixels,
-    DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, Keymap,
-    LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImageParams,
-    RenderSvgParams, Scene, SharedString, Size, Task, TaskLabel, WindowContext,
-    DEFAULT_WINDOW_SIZE,
+    point, AccessibilityAction, AccessibilityNodeId, AccessibilityTree, Action, AnyWindowHandle,
+    AsyncWindowContext, BackgroundExecutor, Bounds, DevicePixels, DispatchEventResult, Font,
+    FontId, FontMetrics, FontRun, ForegroundExecutor, GlyphId, Keymap, LineLayout, Pixels,
+    PlatformInput, Point, RenderGlyphParams, RenderImageParams, RenderSvgParams, Scene,
+    SharedString, Size, Task, TaskLabel, WindowContext, DEFAULT_WINDOW_SIZE,
 };
 use anyhow::Result;
 use async_task::Runnable;
@@ -326,6 +326,17 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     fn completed_frame(&self) {}
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas>;
 
+    // Accessibility, where the platform supports it
+    fn accessibility_active(&self) -> bool {
+        false
+    }
+    fn update_accessibility_tree(&self, _tree: Arc<AccessibilityTree>) {}
+    fn on_accessibility_action(
+        &self,
+        _callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>,
+    ) {
+    }
+
     // macOS specific methods
     fn set_edited(&mut self, _edited: bool) {}
     fn show_character_palette(&self) {}
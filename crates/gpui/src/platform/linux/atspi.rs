@@ -0,0 +1,1069 @@
+//! Exposes the accessibility trees of windows over AT-SPI, the D-Bus protocol that screen readers
+//! and other assistive technologies use on Linux.
+//!
+//! The bridge connects to the accessibility bus lazily, when the first window is opened and
+//! assistive technologies are enabled, and registers an object for every node of every window's
+//! tree. Windows don't build their trees until the bridge is connected. D-Bus calls are answered on a
+//! background thread from the latest trees, and the actions they request are sent back to the
+//! main thread.
+
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::{BTreeMap, HashMap as StdHashMap},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering::SeqCst},
+        Arc,
+    },
+};
+
+use collections::{HashMap, HashSet};
+use futures::{channel::mpsc, StreamExt as _};
+use parking_lot::Mutex;
+use util::ResultExt as _;
+use zbus::{
+    fdo, interface,
+    names::InterfaceName,
+    zvariant::{ObjectPath, OwnedObjectPath, Structure, Value},
+    Connection,
+};
+
+use crate::{
+    AccessibilityAction, AccessibilityNode, AccessibilityNodeId, AccessibilityRole,
+    AccessibilityTree, AnyWindowHandle, BackgroundExecutor, ForegroundExecutor, Pixels, Point,
+    Task,
+};
+
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+const REGISTRY_NAME: &str = "org.a11y.atspi.Registry";
+const EVENT_INTERFACE: &str = "org.a11y.atspi.Event.Object";
+
+const ACCESSIBLE_INTERFACE: &str = "org.a11y.atspi.Accessible";
+const ACTION_INTERFACE: &str = "org.a11y.atspi.Action";
+const APPLICATION_INTERFACE: &str = "org.a11y.atspi.Application";
+const COMPONENT_INTERFACE: &str = "org.a11y.atspi.Component";
+const TEXT_INTERFACE: &str = "org.a11y.atspi.Text";
+
+const COORD_TYPE_SCREEN: u32 = 0;
+const COORD_TYPE_PARENT: u32 = 2;
+
+const LAYER_WIDGET: u32 = 3;
+const LAYER_WINDOW: u32 = 7;
+
+const STATE_CHECKED: u32 = 4;
+const STATE_COLLAPSED: u32 = 5;
+const STATE_EDITABLE: u32 = 7;
+const STATE_ENABLED: u32 = 8;
+const STATE_EXPANDABLE: u32 = 9;
+const STATE_EXPANDED: u32 = 10;
+const STATE_FOCUSABLE: u32 = 11;
+const STATE_FOCUSED: u32 = 12;
+const STATE_MULTI_LINE: u32 = 17;
+const STATE_SELECTABLE: u32 = 22;
+const STATE_SELECTED: u32 = 23;
+const STATE_SENSITIVE: u32 = 24;
+const STATE_SHOWING: u32 = 25;
+const STATE_SINGLE_LINE: u32 = 26;
+const STATE_VISIBLE: u32 = 30;
+const STATE_CHECKABLE: u32 = 41;
+
+const ROLE_APPLICATION: u32 = 75;
+
+/// A reference to an accessible object: the bus name of its application and its path.
+type ObjectRef = (String, OwnedObjectPath);
+
+type ActionRequest = (AccessibilityNodeId, AccessibilityAction);
+
+/// Connects the windows of the application to the accessibility bus.
+pub(crate) struct AtspiBridge {
+    background_executor: BackgroundExecutor,
+    foreground_executor: ForegroundExecutor,
+    state: Arc<Mutex<BridgeState>>,
+    /// Whether the bridge is connected to the accessibility bus.
+    active: Arc<AtomicBool>,
+    /// Tells the bridge's background task which windows to sync with the bus. Created when the
+    /// first window is opened.
+    changed_windows: OnceCell<mpsc::UnboundedSender<u64>>,
+}
+
+#[derive(Default)]
+struct BridgeState {
+    app_name: String,
+    app_id: i32,
+    bus_name: String,
+    desktop: Option<ObjectRef>,
+    windows: BTreeMap<u64, WindowState>,
+}
+
+struct WindowState {
+    title: String,
+    origin: Point<Pixels>,
+    scale_factor: f32,
+    tree: Arc<AccessibilityTree>,
+    actions: mpsc::UnboundedSender<ActionRequest>,
+}
+
+impl AtspiBridge {
+    pub fn new(
+        background_executor: BackgroundExecutor,
+        foreground_executor: ForegroundExecutor,
+    ) -> Self {
+        let app_name = std::env::current_exe()
+            .ok()
+            .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        Self {
+            background_executor,
+            foreground_executor,
+            state: Arc::new(Mutex::new(BridgeState {
+                app_name,
+                ..Default::default()
+            })),
+            active: Arc::default(),
+            changed_windows: OnceCell::new(),
+        }
+    }
+
+    /// Exposes a new window on the accessibility bus, until the returned handle is dropped.
+    pub fn window(&self, handle: AnyWindowHandle) -> AtspiWindow {
+        let window_id = handle.window_id().as_u64();
+        let changed_windows = self
+            .changed_windows
+            .get_or_init(|| {
+                let (sender, receiver) = mpsc::unbounded();
+                self.background_executor
+                    .spawn(run_bridge(
+                        self.state.clone(),
+                        self.active.clone(),
+                        receiver,
+                    ))
+                    .detach();
+                sender
+            })
+            .clone();
+
+        let (actions, mut action_requests) = mpsc::unbounded();
+        self.state.lock().windows.insert(
+            window_id,
+            WindowState {
+                title: String::new(),
+                origin: Point::default(),
+                scale_factor: 1.,
+                tree: Arc::new(AccessibilityTree::build(Default::default(), &[], None)),
+                actions,
+            },
+        );
+
+        let action_callback = Rc::new(RefCell::new(
+            None::<Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>>,
+        ));
+        let _actions_task = self.foreground_executor.spawn({
+            let action_callback = action_callback.clone();
+            async move {
+                while let Some((node_id, action)) = action_requests.next().await {
+                    if let Some(callback) = action_callback.borrow_mut().as_mut() {
+                        callback(node_id, action);
+                    }
+                }
+            }
+        });
+
+        changed_windows.unbounded_send(window_id).ok();
+        AtspiWindow {
+            window_id,
+            state: self.state.clone(),
+            active: self.active.clone(),
+            changed_windows,
+            action_callback,
+            _actions_task,
+        }
+    }
+}
+
+/// A window exposed on the accessibility bus.
+pub(crate) struct AtspiWindow {
+    window_id: u64,
+    state: Arc<Mutex<BridgeState>>,
+    active: Arc<AtomicBool>,
+    changed_windows: mpsc::UnboundedSender<u64>,
+    action_callback: Rc<RefCell<Option<Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>>>>,
+    _actions_task: Task<()>,
+}
+
+impl AtspiWindow {
+    /// Whether assistive technologies can see the window, and so whether it should build its
+    /// accessibility tree.
+    pub fn is_active(&self) -> bool {
+        self.active.load(SeqCst)
+    }
+
+    pub fn set_title(&self, title: &str) {
+        if let Some(window) = self.state.lock().windows.get_mut(&self.window_id) {
+            window.title = title.to_string();
+        }
+    }
+
+    /// Replaces the window's tree. The origin is the position of the window's content on the
+    /// screen, which assistive technologies use to find nodes under the pointer.
+    pub fn update_tree(
+        &self,
+        tree: Arc<AccessibilityTree>,
+        origin: Point<Pixels>,
+        scale_factor: f32,
+    ) {
+        if let Some(window) = self.state.lock().windows.get_mut(&self.window_id) {
+            window.tree = tree;
+            window.origin = origin;
+            window.scale_factor = scale_factor;
+        }
+        self.changed_windows.unbounded_send(self.window_id).ok();
+    }
+
+    pub fn on_action(&self, callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>) {
+        *self.action_callback.borrow_mut() = Some(callback);
+    }
+}
+
+impl Drop for AtspiWindow {
+    fn drop(&mut self) {
+        self.state.lock().windows.remove(&self.window_id);
+        self.changed_windows.unbounded_send(self.window_id).ok();
+    }
+}
+
+/// The objects registered on the bus for a window, and which of them had focus.
+#[derive(Default)]
+struct RegisteredWindow {
+    nodes: HashSet<AccessibilityNodeId>,
+    focus: Option<AccessibilityNodeId>,
+}
+
+async fn run_bridge(
+    state: Arc<Mutex<BridgeState>>,
+    active: Arc<AtomicBool>,
+    mut changed_windows: mpsc::UnboundedReceiver<u64>,
+) {
+    let connection = match connect(&state).await {
+        Ok(connection) => connection,
+        Err(error) => {
+            log::info!("accessibility bus is unavailable: {error}");
+            return;
+        }
+    };
+    active.store(true, SeqCst);
+
+    let mut registered = HashMap::<u64, RegisteredWindow>::default();
+    while let Some(window_id) = changed_windows.next().await {
+        sync_window(&connection, &state, &mut registered, window_id)
+            .await
+            .log_err();
+    }
+}
+
+async fn connect(state: &Arc<Mutex<BridgeState>>) -> zbus::Result<Connection> {
+    let session = Connection::session().await?;
+    wait_until_enabled(&session).await?;
+    let reply = session
+        .call_method(
+            Some("org.a11y.Bus"),
+            "/org/a11y/bus",
+            Some("org.a11y.Bus"),
+            "GetAddress",
+            &(),
+        )
+        .await?;
+    let address = reply.body().deserialize::<String>()?;
+    let connection = zbus::connection::Builder::address(address.as_str())?
+        .build()
+        .await?;
+
+    let bus_name = connection
+        .unique_name()
+        .map(|name| name.to_string())
+        .unwrap_or_default();
+    state.lock().bus_name = bus_name.clone();
+
+    let object_server = connection.object_server();
+    object_server
+        .at(ROOT_PATH, ApplicationRoot(state.clone()))
+        .await?;
+    object_server
+        .at(ROOT_PATH, Application(state.clone()))
+        .await?;
+
+    let reply = connection
+        .call_method(
+            Some(REGISTRY_NAME),
+            ROOT_PATH,
+            Some("org.a11y.atspi.Socket"),
+            "Embed",
+            &((bus_name, object_path(ROOT_PATH.to_string())),),
+        )
+        .await?;
+    state.lock().desktop = reply.body().deserialize::<ObjectRef>().log_err();
+
+    Ok(connection)
+}
+
+/// Waits until assistive technologies are enabled, which they announce by setting the
+/// accessibility bus's `IsEnabled` property, for example when a screen reader starts.
+async fn wait_until_enabled(session: &Connection) -> zbus::Result<()> {
+    let properties = fdo::PropertiesProxy::builder(session)
+        .destination("org.a11y.Bus")?
+        .path("/org/a11y/bus")?
+        .build()
+        .await?;
+    let mut changes = properties.receive_properties_changed().await?;
+    loop {
+        let enabled = properties
+            .get(
+                InterfaceName::from_static_str_unchecked("org.a11y.Status"),
+                "IsEnabled",
+            )
+            .await?;
+        if bool::try_from(enabled).unwrap_or(false) {
+            return Ok(());
+        }
+        if changes.next().await.is_none() {
+            return Err(zbus::Error::Failure(
+                "the accessibility bus went away".into(),
+            ));
+        }
+    }
+}
+
+/// Registers objects for the nodes that were added to a window's tree since it was last synced,
+/// removes those for nodes that are gone, and announces focus changes.
+async fn sync_window(
+    connection: &Connection,
+    state: &Arc<Mutex<BridgeState>>,
+    registered: &mut HashMap<u64, RegisteredWindow>,
+    window_id: u64,
+) -> zbus::Result<()> {
+    let tree = state
+        .lock()
+        .windows
+        .get(&window_id)
+        .map(|window| window.tree.clone());
+    let is_new = !registered.contains_key(&window_id);
+    let registered_window = registered.entry(window_id).or_default();
+    let node_ids = tree
+        .iter()
+        .flat_map(|tree| tree.nodes().map(|node| node.id))
+        .collect::<HashSet<_>>();
+
+    let object_server = connection.object_server();
+    for node_id in registered_window.nodes.difference(&node_ids) {
+        let path = node_path(window_id, *node_id);
+        object_server.remove::<AccessibleNode, _>(&path).await?;
+        object_server.remove::<ComponentNode, _>(&path).await?;
+        object_server.remove::<ActionNode, _>(&path).await?;
+        object_server.remove::<TextNode, _>(&path).await?;
+    }
+    for node_id in node_ids.difference(&registered_window.nodes) {
+        let path = node_path(window_id, *node_id);
+        let node = NodeRef {
+            state: state.clone(),
+            window_id,
+            node_id: *node_id,
+        };
+        object_server
+            .at(&path, AccessibleNode(node.clone()))
+            .await?;
+        object_server.at(&path, ComponentNode(node.clone())).await?;
+        object_server.at(&path, ActionNode(node.clone())).await?;
+        object_server.at(&path, TextNode(node)).await?;
+    }
+
+    let Some(tree) = tree else {
+        registered.remove(&window_id);
+        emit_event(
+            connection,
+            ROOT_PATH,
+            "ChildrenChanged",
+            "remove",
+            0,
+            object_ref_value(node_ref(state, window_id, AccessibilityNodeId(0))),
+        )
+        .await?;
+        return Ok(());
+    };
+    registered_window.nodes = node_ids;
+    if is_new {
+        emit_event(
+            connection,
+            ROOT_PATH,
+            "ChildrenChanged",
+            "add",
+            0,
+            object_ref_value(node_ref(state, window_id, tree.root().id)),
+        )
+        .await?;
+    }
+
+    let focus = tree.focused().map(|node| node.id);
+    if focus != registered_window.focus {
+        if let Some(previous_focus) = registered_window
+            .focus
+            .filter(|id| tree.node(*id).is_some())
+        {
+            emit_event(
+                connection,
+                node_path(window_id, previous_focus).as_str(),
+                "StateChanged",
+                "focused",
+                0,
+                Value::I32(0),
+            )
+            .await?;
+        }
+        if let Some(focus) = focus {
+            emit_event(
+                connection,
+                node_path(window_id, focus).as_str(),
+                "StateChanged",
+                "focused",
+                1,
+                Value::I32(0),
+            )
+            .await?;
+        }
+        registered_window.focus = focus;
+    }
+    Ok(())
+}
+
+async fn emit_event(
+    connection: &Connection,
+    path: &str,
+    event: &str,
+    detail: &str,
+    detail1: i32,
+    data: Value<'_>,
+) -> zbus::Result<()> {
+    connection
+        .emit_signal(
+            None::<zbus::names::BusName>,
+            path,
+            EVENT_INTERFACE,
+            event,
+            &(
+                detail,
+                detail1,
+                0i32,
+                data,
+                StdHashMap::<String, Value>::new(),
+            ),
+        )
+        .await
+}
+
+fn object_path(path: String) -> OwnedObjectPath {
+    ObjectPath::from_string_unchecked(path).into()
+}
+
+fn node_path(window_id: u64, node_id: AccessibilityNodeId) -> OwnedObjectPath {
+    object_path(format!(
+        "/org/a11y/atspi/accessible/w{window_id}/n{}",
+        node_id.0
+    ))
+}
+
+fn node_ref(
+    state: &Arc<Mutex<BridgeState>>,
+    window_id: u64,
+    node_id: AccessibilityNodeId,
+) -> ObjectRef {
+    (state.lock().bus_name.clone(), node_path(window_id, node_id))
+}
+
+fn object_ref_value((bus_name, path): ObjectRef) -> Value<'static> {
+    Structure::from((bus_name, ObjectPath::from(path))).into()
+}
+
+fn root_ref(state: &BridgeState) -> ObjectRef {
+    (state.bus_name.clone(), object_path(ROOT_PATH.to_string()))
+}
+
+fn null_ref(state: &BridgeState) -> ObjectRef {
+    (state.bus_name.clone(), object_path(NULL_PATH.to_string()))
+}
+
+/// The object at the root of the application, whose children are its windows.
+struct ApplicationRoot(Arc<Mutex<BridgeState>>);
+
+#[interface(name = "org.a11y.atspi.Accessible")]
+impl ApplicationRoot {
+    #[zbus(property)]
+    fn name(&self) -> String {
+        self.0.lock().app_name.clone()
+    }
+
+    #[zbus(property)]
+    fn description(&self) -> String {
+        String::new()
+    }
+
+    #[zbus(property)]
+    fn parent(&self) -> ObjectRef {
+        let state = self.0.lock();
+        state.desktop.clone().unwrap_or_else(|| null_ref(&state))
+    }
+
+    #[zbus(property)]
+    fn child_count(&self) -> i32 {
+        self.0.lock().windows.len() as i32
+    }
+
+    #[zbus(property)]
+    fn locale(&self) -> String {
+        String::new()
+    }
+
+    #[zbus(property)]
+    fn accessible_id(&self) -> String {
+        String::new()
+    }
+
+    fn get_child_at_index(&self, index: i32) -> fdo::Result<ObjectRef> {
+        self.get_children()
+            .into_iter()
+            .nth(index as usize)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("no child at index {index}")))
+    }
+
+    fn get_children(&self) -> Vec<ObjectRef> {
+        let state = self.0.lock();
+        state
+            .windows
+            .iter()
+            .map(|(window_id, window)| {
+                (
+                    state.bus_name.clone(),
+                    node_path(*window_id, window.tree.root().id),
+                )
+            })
+            .collect()
+    }
+
+    fn get_index_in_parent(&self) -> i32 {
+        -1
+    }
+
+    fn get_relation_set(&self) -> Vec<(u32, Vec<ObjectRef>)> {
+        Vec::new()
+    }
+
+    fn get_role(&self) -> u32 {
+        ROLE_APPLICATION
+    }
+
+    fn get_role_name(&self) -> String {
+        "application".into()
+    }
+
+    fn get_localized_role_name(&self) -> String {
+        self.get_role_name()
+    }
+
+    fn get_state(&self) -> Vec<u32> {
+        vec![0, 0]
+    }
+
+    fn get_attributes(&self) -> StdHashMap<String, String> {
+        StdHashMap::new()
+    }
+
+    fn get_application(&self) -> ObjectRef {
+        root_ref(&self.0.lock())
+    }
+
+    fn get_interfaces(&self) -> Vec<String> {
+        vec![ACCESSIBLE_INTERFACE.into(), APPLICATION_INTERFACE.into()]
+    }
+}
+
+struct Application(Arc<Mutex<BridgeState>>);
+
+#[interface(name = "org.a11y.atspi.Application")]
+impl Application {
+    #[zbus(property)]
+    fn toolkit_name(&self) -> String {
+        "gpui".into()
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").into()
+    }
+
+    #[zbus(property)]
+    fn atspi_version(&self) -> String {
+        "2.1".into()
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> i32 {
+        self.0.lock().app_id
+    }
+
+    #[zbus(property)]
+    fn set_id(&mut self, id: i32) {
+        self.0.lock().app_id = id;
+    }
+}
+
+/// Identifies a node of a window's tree, which is looked up in the window's latest tree
+/// whenever the node's object is queried.
+#[derive(Clone)]
+struct NodeRef {
+    state: Arc<Mutex<BridgeState>>,
+    window_id: u64,
+    node_id: AccessibilityNodeId,
+}
+
+impl NodeRef {
+    fn read<R>(
+        &self,
+        f: impl FnOnce(&BridgeState, &WindowState, &AccessibilityNode) -> R,
+    ) -> fdo::Result<R> {
+        let state = self.state.lock();
+        let node = state
+            .windows
+            .get(&self.window_id)
+            .and_then(|window| Some((window, window.tree.node(self.node_id)?)));
+        match node {
+            Some((window, node)) => Ok(f(&state, window, node)),
+            None => Err(fdo::Error::UnknownObject(format!(
+                "accessibility node {} no longer exists",
+                self.node_id.0
+            ))),
+        }
+    }
+
+    fn object_ref(&self, state: &BridgeState, node_id: AccessibilityNodeId) -> ObjectRef {
+        (state.bus_name.clone(), node_path(self.window_id, node_id))
+    }
+
+    fn request_action(&self, action: AccessibilityAction) -> fdo::Result<bool> {
+        self.read(|_, window, node| {
+            node.actions.contains(&action)
+                && window
+                    .actions
+                    .unbounded_send((self.node_id, action))
+                    .is_ok()
+        })
+    }
+}
+
+struct AccessibleNode(NodeRef);
+
+#[interface(name = "org.a11y.atspi.Accessible")]
+impl AccessibleNode {
+    #[zbus(property)]
+    fn name(&self) -> fdo::Result<String> {
+        self.0.read(|_, window, node| match node.parent {
+            None => window.title.clone(),
+            Some(_) => node.name.as_deref().unwrap_or_default().to_string(),
+        })
+    }
+
+    #[zbus(property)]
+    fn description(&self) -> fdo::Result<String> {
+        self.0
+            .read(|_, _, node| node.description.as_deref().unwrap_or_default().to_string())
+    }
+
+    #[zbus(property)]
+    fn parent(&self) -> fdo::Result<ObjectRef> {
+        self.0.read(|state, _, node| match node.parent {
+            Some(parent) => self.0.object_ref(state, parent),
+            None => root_ref(state),
+        })
+    }
+
+    #[zbus(property)]
+    fn child_count(&self) -> fdo::Result<i32> {
+        self.0.read(|_, _, node| node.children.len() as i32)
+    }
+
+    #[zbus(property)]
+    fn locale(&self) -> String {
+        String::new()
+    }
+
+    #[zbus(property)]
+    fn accessible_id(&self) -> String {
+        self.0.node_id.0.to_string()
+    }
+
+    fn get_child_at_index(&self, index: i32) -> fdo::Result<ObjectRef> {
+        self.0
+            .read(|state, _, node| {
+                let child = node.children.get(usize::try_from(index).ok()?)?;
+                Some(self.0.object_ref(state, *child))
+            })?
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("no child at index {index}")))
+    }
+
+    fn get_children(&self) -> fdo::Result<Vec<ObjectRef>> {
+        self.0.read(|state, _, node| {
+            node.children
+                .iter()
+                .map(|child| self.0.object_ref(state, *child))
+                .collect()
+        })
+    }
+
+    fn get_index_in_parent(&self) -> fdo::Result<i32> {
+        self.0.read(|state, window, node| {
+            let index = match node.parent {
+                Some(parent) => window
+                    .tree
+                    .node(parent)
+                    .and_then(|parent| parent.children.iter().position(|child| *child == node.id)),
+                None => state.windows.keys().position(|id| *id == self.0.window_id),
+            };
+            index.map_or(-1, |index| index as i32)
+        })
+    }
+
+    fn get_relation_set(&self) -> Vec<(u32, Vec<ObjectRef>)> {
+        Vec::new()
+    }
+
+    fn get_role(&self) -> fdo::Result<u32> {
+        self.0.read(|_, _, node| atspi_role(node.role))
+    }
+
+    fn get_role_name(&self) -> fdo::Result<String> {
+        self.0.read(|_, _, node| role_name(node.role).to_string())
+    }
+
+    fn get_localized_role_name(&self) -> fdo::Result<String> {
+        self.get_role_name()
+    }
+
+    fn get_state(&self) -> fdo::Result<Vec<u32>> {
+        self.0.read(|_, _, node| atspi_state(node))
+    }
+
+    fn get_attributes(&self) -> StdHashMap<String, String> {
+        StdHashMap::new()
+    }
+
+    fn get_application(&self) -> ObjectRef {
+        root_ref(&self.0.state.lock())
+    }
+
+    fn get_interfaces(&self) -> fdo::Result<Vec<String>> {
+        self.0.read(|_, _, node| {
+            let mut interfaces = vec![ACCESSIBLE_INTERFACE.into(), COMPONENT_INTERFACE.into()];
+            if !node.actions.is_empty() {
+                interfaces.push(ACTION_INTERFACE.into());
+            }
+            if node.value.is_some() {
+                interfaces.push(TEXT_INTERFACE.into());
+            }
+            interfaces
+        })
+    }
+}
+
+struct ComponentNode(NodeRef);
+
+#[interface(name = "org.a11y.atspi.Component")]
+impl ComponentNode {
+    fn contains(&self, x: i32, y: i32, coord_type: u32) -> fdo::Result<bool> {
+        self.0.read(|_, window, node| {
+            let point = window_point(window, node, x, y, coord_type);
+            node.bounds.contains(&point)
+        })
+    }
+
+    fn get_accessible_at_point(&self, x: i32, y: i32, coord_type: u32) -> fdo::Result<ObjectRef> {
+        self.0.read(|state, window, node| {
+            let point = window_point(window, node, x, y, coord_type);
+            let mut hit = window.tree.node_at(point);
+            // Only descendants of this node can be returned.
+            while let Some(candidate) = hit {
+                if candidate.parent == Some(node.id) {
+                    return self.0.object_ref(state, candidate.id);
+                }
+                hit = candidate.parent.and_then(|parent| window.tree.node(parent));
+            }
+            null_ref(state)
+        })
+    }
+
+    fn get_extents(&self, coord_type: u32) -> fdo::Result<(i32, i32, i32, i32)> {
+        self.0
+            .read(|_, window, node| atspi_extents(window, node, coord_type))
+    }
+
+    fn get_position(&self, coord_type: u32) -> fdo::Result<(i32, i32)> {
+        let (x, y, _, _) = self.get_extents(coord_type)?;
+        Ok((x, y))
+    }
+
+    fn get_size(&self) -> fdo::Result<(i32, i32)> {
+        let (_, _, width, height) = self.get_extents(COORD_TYPE_SCREEN)?;
+        Ok((width, height))
+    }
+
+    fn get_layer(&self) -> fdo::Result<u32> {
+        self.0.read(|_, _, node| {
+            if node.parent.is_none() {
+                LAYER_WINDOW
+            } else {
+                LAYER_WIDGET
+            }
+        })
+    }
+
+    fn grab_focus(&self) -> fdo::Result<bool> {
+        self.0.request_action(AccessibilityAction::Focus)
+    }
+}
+
+struct ActionNode(NodeRef);
+
+#[interface(name = "org.a11y.atspi.Action")]
+impl ActionNode {
+    #[zbus(property)]
+    fn n_actions(&self) -> fdo::Result<i32> {
+        self.0.read(|_, _, node| node.actions.len() as i32)
+    }
+
+    fn get_description(&self, _index: i32) -> String {
+        String::new()
+    }
+
+    fn get_name(&self, index: i32) -> fdo::Result<String> {
+        Ok(self.action(index)?.name().to_string())
+    }
+
+    fn get_localized_name(&self, index: i32) -> fdo::Result<String> {
+        self.get_name(index)
+    }
+
+    fn get_key_binding(&self, _index: i32) -> String {
+        String::new()
+    }
+
+    fn get_actions(&self) -> fdo::Result<Vec<(String, String, String)>> {
+        self.0.read(|_, _, node| {
+            node.actions
+                .iter()
+                .map(|action| (action.name().to_string(), String::new(), String::new()))
+                .collect()
+        })
+    }
+
+    fn do_action(&self, index: i32) -> fdo::Result<bool> {
+        let action = self.action(index)?;
+        self.0.request_action(action)
+    }
+}
+
+impl ActionNode {
+    fn action(&self, index: i32) -> fdo::Result<AccessibilityAction> {
+        self.0
+            .read(|_, _, node| node.actions.get(usize::try_from(index).ok()?).copied())?
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("no action at index {index}")))
+    }
+}
+
+/// Exposes the value of nodes, such as the contents of text inputs, as read-only text.
+struct TextNode(NodeRef);
+
+#[interface(name = "org.a11y.atspi.Text")]
+impl TextNode {
+    #[zbus(property)]
+    fn character_count(&self) -> fdo::Result<i32> {
+        self.0.read(|_, _, node| {
+            node.value
+                .as_deref()
+                .map_or(0, |value| value.chars().count() as i32)
+        })
+    }
+
+    #[zbus(property)]
+    fn caret_offset(&self) -> i32 {
+        0
+    }
+
+    fn get_text(&self, start_offset: i32, end_offset: i32) -> fdo::Result<String> {
+        self.0.read(|_, _, node| {
+            let value = node.value.as_deref().unwrap_or_default();
+            let start = start_offset.max(0) as usize;
+            // An end offset of -1 means the end of the text.
+            let len = if end_offset < 0 {
+                usize::MAX
+            } else {
+                (end_offset as usize).saturating_sub(start)
+            };
+            value.chars().skip(start).take(len).collect()
+        })
+    }
+}
+
+/// Converts a point in the given AT-SPI coordinates to window coordinates.
+fn window_point(
+    window: &WindowState,
+    node: &AccessibilityNode,
+    x: i32,
+    y: i32,
+    coord_type: u32,
+) -> Point<Pixels> {
+    let point = Point::new(
+        Pixels(x as f32 / window.scale_factor),
+        Pixels(y as f32 / window.scale_factor),
+    );
+    match coord_type {
+        COORD_TYPE_SCREEN => point - window.origin,
+        COORD_TYPE_PARENT => point + parent_origin(window, node),
+        _ => point,
+    }
+}
+
+/// Returns the bounds of a node in the given AT-SPI coordinates, in device pixels.
+fn atspi_extents(
+    window: &WindowState,
+    node: &AccessibilityNode,
+    coord_type: u32,
+) -> (i32, i32, i32, i32) {
+    let origin = match coord_type {
+        COORD_TYPE_SCREEN => window.origin + node.bounds.origin,
+        COORD_TYPE_PARENT => node.bounds.origin - parent_origin(window, node),
+        _ => node.bounds.origin,
+    };
+    let scale = |value: Pixels| (value.0 * window.scale_factor).round() as i32;
+    (
+        scale(origin.x),
+        scale(origin.y),
+        scale(node.bounds.size.width),
+        scale(node.bounds.size.height),
+    )
+}
+
+fn parent_origin(window: &WindowState, node: &AccessibilityNode) -> Point<Pixels> {
+    node.parent
+        .and_then(|parent| window.tree.node(parent))
+        .map_or(Point::default(), |parent| parent.bounds.origin)
+}
+
+fn atspi_role(role: AccessibilityRole) -> u32 {
+    match role {
+        AccessibilityRole::Window => 23,
+        AccessibilityRole::Group => 39,
+        AccessibilityRole::Button => 43,
+        AccessibilityRole::CheckBox => 7,
+        AccessibilityRole::Label => 29,
+        AccessibilityRole::Heading => 83,
+        AccessibilityRole::TextInput => 79,
+        AccessibilityRole::Document => 94,
+        AccessibilityRole::Link => 88,
+        AccessibilityRole::Image => 27,
+        AccessibilityRole::List => 31,
+        AccessibilityRole::ListItem => 32,
+        AccessibilityRole::Tree => 65,
+        AccessibilityRole::TreeItem => 91,
+        AccessibilityRole::Tab => 37,
+        AccessibilityRole::TabList => 38,
+        AccessibilityRole::Menu => 33,
+        AccessibilityRole::MenuItem => 35,
+        AccessibilityRole::Toolbar => 63,
+        AccessibilityRole::Dialog => 16,
+        AccessibilityRole::Tooltip => 64,
+        AccessibilityRole::ScrollArea => 49,
+        AccessibilityRole::Separator => 50,
+        AccessibilityRole::Terminal => 60,
+    }
+}
+
+fn role_name(role: AccessibilityRole) -> &'static str {
+    match role {
+        AccessibilityRole::Window => "frame",
+        AccessibilityRole::Group => "panel",
+        AccessibilityRole::Button => "push button",
+        AccessibilityRole::CheckBox => "check box",
+        AccessibilityRole::Label => "label",
+        AccessibilityRole::Heading => "heading",
+        AccessibilityRole::TextInput => "entry",
+        AccessibilityRole::Document => "document text",
+        AccessibilityRole::Link => "link",
+        AccessibilityRole::Image => "image",
+        AccessibilityRole::List => "list",
+        AccessibilityRole::ListItem => "list item",
+        AccessibilityRole::Tree => "tree",
+        AccessibilityRole::TreeItem => "tree item",
+        AccessibilityRole::Tab => "page tab",
+        AccessibilityRole::TabList => "page tab list",
+        AccessibilityRole::Menu => "menu",
+        AccessibilityRole::MenuItem => "menu item",
+        AccessibilityRole::Toolbar => "tool bar",
+        AccessibilityRole::Dialog => "dialog",
+        AccessibilityRole::Tooltip => "tool tip",
+        AccessibilityRole::ScrollArea => "scroll pane",
+        AccessibilityRole::Separator => "separator",
+        AccessibilityRole::Terminal => "terminal",
+    }
+}
+
+/// Returns the AT-SPI state set of a node, as two 32 bit words.
+fn atspi_state(node: &AccessibilityNode) -> Vec<u32> {
+    let states = node.states;
+    let mut bits = 0u64;
+    let mut set = |state: u32| bits |= 1 << state;
+    set(STATE_VISIBLE);
+    set(STATE_SHOWING);
+    if !states.disabled {
+        set(STATE_ENABLED);
+        set(STATE_SENSITIVE);
+    }
+    if states.focusable {
+        set(STATE_FOCUSABLE);
+    }
+    if states.focused {
+        set(STATE_FOCUSED);
+    }
+    if matches!(
+        node.role,
+        AccessibilityRole::ListItem | AccessibilityRole::TreeItem | AccessibilityRole::Tab
+    ) {
+        set(STATE_SELECTABLE);
+    }
+    if states.selected {
+        set(STATE_SELECTED);
+    }
+    if let Some(checked) = states.checked {
+        set(STATE_CHECKABLE);
+        if checked {
+            set(STATE_CHECKED);
+        }
+    }
+    if let Some(expanded) = states.expanded {
+        set(STATE_EXPANDABLE);
+        set(if expanded {
+            STATE_EXPANDED
+        } else {
+            STATE_COLLAPSED
+        });
+    }
+    if states.editable {
+        set(STATE_EDITABLE);
+    }
+    if matches!(
+        node.role,
+        AccessibilityRole::TextInput | AccessibilityRole::Document
+    ) {
+        set(if states.multiline {
+            STATE_MULTI_LINE
+        } else {
+            STATE_SINGLE_LINE
+        });
+    }
+    vec![bits as u32, (bits >> 32) as u32]
+}
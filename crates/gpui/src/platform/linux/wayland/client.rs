@@ -591,6 +591,7 @@ impl LinuxClient for WaylandClient {
             WaylandClientStatePtr(Rc::downgrade(&self.0)),
             params,
             state.common.appearance,
+            state.common.atspi.window(handle),
         )?;
         state.windows.insert(surface_id, window.0.clone());
 
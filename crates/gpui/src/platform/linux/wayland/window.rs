@@ -22,13 +22,14 @@ use wayland_protocols_plasma::blur::client::org_kde_kwin_blur;
 use crate::platform::blade::{BladeRenderer, BladeSurfaceConfig};
 use crate::platform::linux::wayland::display::WaylandDisplay;
 use crate::platform::linux::wayland::serial::SerialKind;
+use crate::platform::linux::AtspiWindow;
 use crate::platform::{PlatformAtlas, PlatformInputHandler, PlatformWindow};
 use crate::scene::Scene;
 use crate::{
-    px, size, AnyWindowHandle, Bounds, Decorations, Globals, Modifiers, Output, Pixels,
-    PlatformDisplay, PlatformInput, Point, PromptLevel, ResizeEdge, Size, Tiling,
-    WaylandClientStatePtr, WindowAppearance, WindowBackgroundAppearance, WindowBounds,
-    WindowControls, WindowDecorations, WindowParams,
+    px, size, AccessibilityAction, AccessibilityNodeId, AccessibilityTree, AnyWindowHandle, Bounds,
+    Decorations, Globals, Modifiers, Output, Pixels, PlatformDisplay, PlatformInput, Point,
+    PromptLevel, ResizeEdge, Size, Tiling, WaylandClientStatePtr, WindowAppearance,
+    WindowBackgroundAppearance, WindowBounds, WindowControls, WindowDecorations, WindowParams,
 };
 
 #[derive(Default)]
@@ -101,6 +102,7 @@ pub struct WaylandWindowState {
     window_controls: WindowControls,
     inset: Option<Pixels>,
     requested_inset: Option<Pixels>,
+    atspi: AtspiWindow,
 }
 
 #[derive(Clone)]
@@ -122,6 +124,7 @@ impl WaylandWindowState {
         client: WaylandClientStatePtr,
         globals: Globals,
         options: WindowParams,
+        atspi: AtspiWindow,
     ) -> anyhow::Result<Self> {
         let raw = RawWindow {
             window: surface.id().as_ptr().cast::<c_void>(),
@@ -190,6 +193,7 @@ impl WaylandWindowState {
             },
             inset: None,
             requested_inset: None,
+            atspi,
         })
     }
 
@@ -255,6 +259,7 @@ impl WaylandWindow {
         client: WaylandClientStatePtr,
         params: WindowParams,
         appearance: WindowAppearance,
+        atspi: AtspiWindow,
     ) -> anyhow::Result<(Self, ObjectId)> {
         let surface = globals.compositor.create_surface(&globals.qh, ());
         let xdg_surface = globals
@@ -292,6 +297,7 @@ impl WaylandWindow {
                 client,
                 globals,
                 params,
+                atspi,
             )?)),
             callbacks: Rc::new(RefCell::new(Callbacks::default())),
         });
@@ -816,7 +822,27 @@ impl PlatformWindow for WaylandWindow {
     }
 
     fn set_title(&mut self, title: &str) {
-        self.borrow().toplevel.set_title(title.to_string());
+        let state = self.borrow();
+        state.toplevel.set_title(title.to_string());
+        state.atspi.set_title(title);
+    }
+
+    fn accessibility_active(&self) -> bool {
+        self.borrow().atspi.is_active()
+    }
+
+    fn update_accessibility_tree(&self, tree: Arc<AccessibilityTree>) {
+        let state = self.borrow();
+        state
+            .atspi
+            .update_tree(tree, state.bounds.origin, state.scale);
+    }
+
+    fn on_accessibility_action(
+        &self,
+        callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>,
+    ) {
+        self.borrow().atspi.on_action(callback);
     }
 
     fn set_app_id(&mut self, app_id: &str) {
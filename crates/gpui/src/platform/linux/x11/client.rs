@@ -1027,6 +1027,7 @@ impl LinuxClient for X11Client {
             &state.atoms,
             state.scale_factor,
             state.common.appearance,
+            state.common.atspi.window(handle),
         )?;
 
         let window_ref = WindowRef {
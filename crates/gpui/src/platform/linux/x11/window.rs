@@ -2,11 +2,11 @@ use anyhow::Context;
 
 use crate::{
     platform::blade::{BladeRenderer, BladeSurfaceConfig},
-    px, size, AnyWindowHandle, Bounds, Decorations, DevicePixels, ForegroundExecutor, Modifiers,
-    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
-    Point, PromptLevel, ResizeEdge, Scene, Size, Tiling, WindowAppearance,
-    WindowBackgroundAppearance, WindowBounds, WindowDecorations, WindowKind, WindowParams,
-    X11ClientStatePtr,
+    px, size, AccessibilityAction, AccessibilityNodeId, AccessibilityTree, AnyWindowHandle, Bounds,
+    Decorations, DevicePixels, ForegroundExecutor, Modifiers, Pixels, PlatformAtlas,
+    PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptLevel,
+    ResizeEdge, Scene, Size, Tiling, WindowAppearance, WindowBackgroundAppearance, WindowBounds,
+    WindowDecorations, WindowKind, WindowParams, X11ClientStatePtr,
 };
 
 use blade_graphics as gpu;
@@ -29,7 +29,7 @@ use std::{
     sync::Arc, time::Duration,
 };
 
-use super::{X11Display, XINPUT_MASTER_DEVICE};
+use super::{AtspiWindow, X11Display, XINPUT_MASTER_DEVICE};
 x11rb::atom_manager! {
     pub XcbAtoms: AtomsCookie {
         UTF8_STRING,
@@ -200,6 +200,7 @@ pub struct X11WindowState {
     decorations: WindowDecorations,
     pub handle: AnyWindowHandle,
     last_insets: [u32; 4],
+    atspi: AtspiWindow,
 }
 
 impl X11WindowState {
@@ -257,6 +258,7 @@ impl X11WindowState {
         atoms: &XcbAtoms,
         scale_factor: f32,
         appearance: WindowAppearance,
+        atspi: AtspiWindow,
     ) -> anyhow::Result<Self> {
         let x_screen_index = params
             .display_id
@@ -498,6 +500,7 @@ impl X11WindowState {
             counter_id: sync_request_counter,
             last_sync_counter: None,
             refresh_rate,
+            atspi,
         })
     }
 
@@ -566,6 +569,7 @@ impl X11Window {
         atoms: &XcbAtoms,
         scale_factor: f32,
         appearance: WindowAppearance,
+        atspi: AtspiWindow,
     ) -> anyhow::Result<Self> {
         let ptr = X11WindowStatePtr {
             state: Rc::new(RefCell::new(X11WindowState::new(
@@ -579,6 +583,7 @@ impl X11Window {
                 atoms,
                 scale_factor,
                 appearance,
+                atspi,
             )?)),
             callbacks: Rc::new(RefCell::new(Callbacks::default())),
             xcb_connection: xcb_connection.clone(),
@@ -1022,6 +1027,26 @@ impl PlatformWindow for X11Window {
                 title.as_bytes(),
             )
             .unwrap();
+
+        self.0.state.borrow().atspi.set_title(title);
+    }
+
+    fn accessibility_active(&self) -> bool {
+        self.0.state.borrow().atspi.is_active()
+    }
+
+    fn update_accessibility_tree(&self, tree: Arc<AccessibilityTree>) {
+        let state = self.0.state.borrow();
+        state
+            .atspi
+            .update_tree(tree, state.bounds.origin, state.scale_factor);
+    }
+
+    fn on_accessibility_action(
+        &self,
+        callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>,
+    ) {
+        self.0.state.borrow().atspi.on_action(callback);
     }
 
     fn set_app_id(&mut self, app_id: &str) {
@@ -0,0 +1,772 @@
+use crate::{
+    point, AtlasTile, Bounds, Corners, DevicePixels, Hsla, MonochromeSprite, Path, Point,
+    PolychromeSprite, PrimitiveBatch, Quad, ScaledPixels, Scene, Shadow, Size, TestAtlas,
+    Underline,
+};
+use image::RgbaImage;
+use std::f32::consts::PI;
+
+const GRAYSCALE_FACTORS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// Rasterizes scenes on the CPU, so that tests can look at the pixels of a window without a GPU.
+///
+/// Each primitive is shaded like the blade shaders do: colors are blended in linear space with
+/// alpha blending, sampling at pixel centers, and the result is converted back to sRGB.
+pub(crate) struct SoftwareRenderer<'a> {
+    atlas: &'a TestAtlas,
+    size: Size<DevicePixels>,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl<'a> SoftwareRenderer<'a> {
+    pub fn new(size: Size<DevicePixels>, atlas: &'a TestAtlas) -> Self {
+        let pixel_count = size.width.0.max(0) as usize * size.height.0.max(0) as usize;
+        Self {
+            atlas,
+            size,
+            pixels: vec![[0.; 4]; pixel_count],
+        }
+    }
+
+    pub fn render(mut self, scene: &Scene) -> RgbaImage {
+        for batch in scene.batches() {
+            match batch {
+                PrimitiveBatch::Shadows(shadows) => shadows.iter().for_each(|s| self.shadow(s)),
+                PrimitiveBatch::Quads(quads) => quads.iter().for_each(|q| self.quad(q)),
+                PrimitiveBatch::Paths(paths) => paths.iter().for_each(|p| self.path(p)),
+                PrimitiveBatch::Underlines(underlines) => {
+                    underlines.iter().for_each(|u| self.underline(u))
+                }
+                PrimitiveBatch::MonochromeSprites { sprites, .. } => {
+                    sprites.iter().for_each(|s| self.monochrome_sprite(s))
+                }
+                PrimitiveBatch::PolychromeSprites { sprites, .. } => {
+                    sprites.iter().for_each(|s| self.polychrome_sprite(s))
+                }
+                // Surfaces only exist on macOS, where they are backed by video frames.
+                PrimitiveBatch::Surfaces(_) => {}
+            }
+        }
+        self.into_image()
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let mut image = RgbaImage::new(self.size.width.0 as u32, self.size.height.0 as u32);
+        for (pixel, [r, g, b, a]) in image.pixels_mut().zip(self.pixels) {
+            // Blending leaves the colors premultiplied by their alpha, but PNGs store them
+            // straight.
+            if a > 0. {
+                pixel.0 = [
+                    to_byte(linear_to_srgb(r / a)),
+                    to_byte(linear_to_srgb(g / a)),
+                    to_byte(linear_to_srgb(b / a)),
+                    to_byte(a),
+                ];
+            }
+        }
+        image
+    }
+
+    /// Calls `shade` with the center of every pixel in `bounds` that also lies in `clip`, blending
+    /// the returned color into that pixel.
+    fn fill(
+        &mut self,
+        bounds: Bounds<f32>,
+        clip: Bounds<f32>,
+        mut shade: impl FnMut(Point<f32>) -> Option<[f32; 4]>,
+    ) {
+        let bounds = intersect(bounds, clip);
+        let (x_range, y_range) = self.pixel_ranges(bounds);
+        for y in y_range {
+            for x in x_range.clone() {
+                let center = point(x as f32 + 0.5, y as f32 + 0.5);
+                if let Some(color) = shade(center) {
+                    self.blend(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn pixel_ranges(&self, bounds: Bounds<f32>) -> (std::ops::Range<i32>, std::ops::Range<i32>) {
+        let min_x = (bounds.origin.x - 0.5).ceil().max(0.) as i32;
+        let min_y = (bounds.origin.y - 0.5).ceil().max(0.) as i32;
+        let max_x =
+            ((bounds.origin.x + bounds.size.width - 0.5).ceil() as i32).min(self.size.width.0);
+        let max_y =
+            ((bounds.origin.y + bounds.size.height - 0.5).ceil() as i32).min(self.size.height.0);
+        (min_x..max_x, min_y..max_y)
+    }
+
+    fn blend(&mut self, x: i32, y: i32, [r, g, b, a]: [f32; 4]) {
+        if a <= 0. {
+            return;
+        }
+        let ix = y as usize * self.size.width.0 as usize + x as usize;
+        let below = &mut self.pixels[ix];
+        below[0] = r * a + below[0] * (1. - a);
+        below[1] = g * a + below[1] * (1. - a);
+        below[2] = b * a + below[2] * (1. - a);
+        below[3] = a + below[3] * (1. - a);
+    }
+
+    fn quad(&mut self, quad: &Quad) {
+        let bounds = to_f32_bounds(quad.bounds);
+        let background = to_linear(quad.background);
+        let border_color = to_linear(quad.border_color);
+        let radii = to_f32_corners(&quad.corner_radii);
+        let widths = &quad.border_widths;
+        let is_plain = radii == [0.; 4]
+            && widths.top.0 == 0.
+            && widths.right.0 == 0.
+            && widths.bottom.0 == 0.
+            && widths.left.0 == 0.;
+
+        self.fill(
+            bounds,
+            to_f32_bounds(quad.content_mask.bounds),
+            |position| {
+                if is_plain {
+                    return Some(background);
+                }
+
+                let half_size = point(bounds.size.width / 2., bounds.size.height / 2.);
+                let center = point(bounds.origin.x + half_size.x, bounds.origin.y + half_size.y);
+                let center_to_point = point(position.x - center.x, position.y - center.y);
+                let corner_radius = pick_corner_radius(center_to_point, radii);
+                let distance = rounded_rect_sdf(center_to_point, half_size, corner_radius);
+
+                let vertical_border = if center_to_point.x > 0. {
+                    widths.right.0
+                } else {
+                    widths.left.0
+                };
+                let horizontal_border = if center_to_point.y > 0. {
+                    widths.bottom.0
+                } else {
+                    widths.top.0
+                };
+                let inset_size = point(
+                    half_size.x - corner_radius - vertical_border,
+                    half_size.y - corner_radius - horizontal_border,
+                );
+                let point_to_inset_corner = point(
+                    center_to_point.x.abs() - inset_size.x,
+                    center_to_point.y.abs() - inset_size.y,
+                );
+                let border_width = if point_to_inset_corner.x < 0. && point_to_inset_corner.y < 0. {
+                    0.
+                } else if point_to_inset_corner.y > point_to_inset_corner.x {
+                    horizontal_border
+                } else {
+                    vertical_border
+                };
+
+                let mut color = background;
+                if border_width > 0. {
+                    let inset_distance = distance + border_width;
+                    let blended_border = over(background, border_color);
+                    color = mix(blended_border, background, saturate(0.5 - inset_distance));
+                }
+                Some(with_alpha_factor(color, saturate(0.5 - distance)))
+            },
+        );
+    }
+
+    fn shadow(&mut self, shadow: &Shadow) {
+        let blur_radius = shadow.blur_radius.0;
+        let bounds = to_f32_bounds(shadow.bounds);
+        let margin = 3. * blur_radius;
+        let expanded = Bounds {
+            origin: point(bounds.origin.x - margin, bounds.origin.y - margin),
+            size: Size {
+                width: bounds.size.width + 2. * margin,
+                height: bounds.size.height + 2. * margin,
+            },
+        };
+        let color = to_linear(shadow.color);
+        let radii = to_f32_corners(&shadow.corner_radii);
+
+        self.fill(
+            expanded,
+            to_f32_bounds(shadow.content_mask.bounds),
+            |position| {
+                let half_size = point(bounds.size.width / 2., bounds.size.height / 2.);
+                let center_to_point = point(
+                    position.x - (bounds.origin.x + half_size.x),
+                    position.y - (bounds.origin.y + half_size.y),
+                );
+                let corner_radius = pick_corner_radius(center_to_point, radii);
+                if blur_radius == 0. {
+                    let distance = rounded_rect_sdf(center_to_point, half_size, corner_radius);
+                    return Some(with_alpha_factor(color, saturate(0.5 - distance)));
+                }
+
+                // The signal is only non-zero in a limited range, so don't waste samples.
+                let low = center_to_point.y - half_size.y;
+                let high = center_to_point.y + half_size.y;
+                let start = (-3. * blur_radius).clamp(low, high);
+                let end = (3. * blur_radius).clamp(low, high);
+                let step = (end - start) / 4.;
+                let mut y = start + step * 0.5;
+                let mut alpha = 0.;
+                for _ in 0..4 {
+                    let blur = blur_along_x(
+                        center_to_point.x,
+                        center_to_point.y - y,
+                        blur_radius,
+                        corner_radius,
+                        half_size,
+                    );
+                    alpha += blur * gaussian(y, blur_radius) * step;
+                    y += step;
+                }
+                Some(with_alpha_factor(color, alpha))
+            },
+        );
+    }
+
+    /// Paths are made of triangles whose `st` coordinates describe quadratic curves. Coverage is
+    /// accumulated across all the triangles covering a pixel, which fills the path with the
+    /// even-odd rule.
+    fn path(&mut self, path: &Path<ScaledPixels>) {
+        let clip = intersect(
+            to_f32_bounds(path.bounds),
+            to_f32_bounds(path.content_mask.bounds),
+        );
+        let (x_range, y_range) = self.pixel_ranges(clip);
+        let width = x_range.len();
+        if width == 0 || y_range.is_empty() {
+            return;
+        }
+        let mut coverage = vec![0f32; width * y_range.len()];
+
+        for triangle in path.vertices.chunks_exact(3) {
+            let xy = [0, 1, 2].map(|i| {
+                let position = triangle[i].xy_position;
+                point(position.x.0, position.y.0)
+            });
+            let st = [0, 1, 2].map(|i| triangle[i].st_position);
+            let area = cross(xy[0], xy[1], xy[2]);
+            if area == 0. {
+                continue;
+            }
+
+            // The `st` coordinates are affine across the triangle, so their derivatives are
+            // constant.
+            let interpolate = |position: Point<f32>| {
+                let w0 = cross(xy[1], xy[2], position) / area;
+                let w1 = cross(xy[2], xy[0], position) / area;
+                let w2 = 1. - w0 - w1;
+                (
+                    [w0, w1, w2],
+                    point(
+                        w0 * st[0].x + w1 * st[1].x + w2 * st[2].x,
+                        w0 * st[0].y + w1 * st[1].y + w2 * st[2].y,
+                    ),
+                )
+            };
+            let (_, st_origin) = interpolate(point(0., 0.));
+            let (_, st_dx) = interpolate(point(1., 0.));
+            let (_, st_dy) = interpolate(point(0., 1.));
+            let dx = point(st_dx.x - st_origin.x, st_dx.y - st_origin.y);
+            let dy = point(st_dy.x - st_origin.x, st_dy.y - st_origin.y);
+
+            for (row, y) in y_range.clone().enumerate() {
+                for (column, x) in x_range.clone().enumerate() {
+                    let center = point(x as f32 + 0.5, y as f32 + 0.5);
+                    let (weights, st) = interpolate(center);
+                    if weights.iter().any(|weight| *weight < 0.) {
+                        continue;
+                    }
+                    let gradient = point(2. * st.x * dx.x - dx.y, 2. * st.x * dy.x - dy.y);
+                    let f = st.x * st.x - st.y;
+                    let length = (gradient.x * gradient.x + gradient.y * gradient.y).sqrt();
+                    let distance = if length > 0. { f / length } else { f };
+                    coverage[row * width + column] += saturate(0.5 - distance);
+                }
+            }
+        }
+
+        let color = to_linear(path.color);
+        for (row, y) in y_range.enumerate() {
+            for (column, x) in x_range.clone().enumerate() {
+                let sample = coverage[row * width + column];
+                let mask = 1. - (1. - sample % 2.).abs();
+                self.blend(x, y, with_alpha_factor(color, mask));
+            }
+        }
+    }
+
+    fn underline(&mut self, underline: &Underline) {
+        let bounds = to_f32_bounds(underline.bounds);
+        let color = to_linear(underline.color);
+        let thickness = underline.thickness.0;
+        let wavy = underline.wavy;
+
+        self.fill(
+            bounds,
+            to_f32_bounds(underline.content_mask.bounds),
+            |position| {
+                if !wavy {
+                    return Some(with_alpha_factor(color, color[3]));
+                }
+
+                let half_thickness = thickness * 0.5;
+                let st = point(
+                    (position.x - bounds.origin.x) / bounds.size.height,
+                    (position.y - bounds.origin.y) / bounds.size.height - 0.5,
+                );
+                let frequency = PI * 3. * thickness / 8.;
+                let amplitude = 1. / (2. * thickness);
+                let sine = (st.x * frequency).sin() * amplitude;
+                let d_sine = (st.x * frequency).cos() * amplitude * frequency;
+                let distance = (st.y - sine) / (1. + d_sine * d_sine).sqrt();
+                let distance_in_pixels = distance * bounds.size.height;
+                let distance_from_top_border = distance_in_pixels - half_thickness;
+                let distance_from_bottom_border = distance_in_pixels + half_thickness;
+                let alpha =
+                    saturate(0.5 - (-distance_from_bottom_border).max(distance_from_top_border));
+                Some(with_alpha_factor(color, alpha * color[3]))
+            },
+        );
+    }
+
+    fn monochrome_sprite(&mut self, sprite: &MonochromeSprite) {
+        let Some(texels) = self.atlas.tile_texels(&sprite.tile) else {
+            return;
+        };
+        let bounds = to_f32_bounds(sprite.bounds);
+        let clip = to_f32_bounds(sprite.content_mask.bounds);
+        let color = to_linear(sprite.color);
+
+        // Sprites can be rotated and scaled, so shade every pixel the transformed sprite covers
+        // and map it back onto the sprite to sample its tile.
+        let transform = sprite.transformation;
+        let [[a, b], [c, d]] = transform.rotation_scale;
+        let [tx, ty] = transform.translation;
+        let determinant = a * d - b * c;
+        if determinant == 0. {
+            return;
+        }
+        let apply = |p: Point<f32>| point(a * p.x + b * p.y + tx, c * p.x + d * p.y + ty);
+        let invert = |p: Point<f32>| {
+            let (x, y) = (p.x - tx, p.y - ty);
+            point((d * x - b * y) / determinant, (a * y - c * x) / determinant)
+        };
+        let corners = [
+            bounds.origin,
+            point(bounds.origin.x + bounds.size.width, bounds.origin.y),
+            point(bounds.origin.x, bounds.origin.y + bounds.size.height),
+            point(
+                bounds.origin.x + bounds.size.width,
+                bounds.origin.y + bounds.size.height,
+            ),
+        ]
+        .map(apply);
+        let min = corners.iter().fold(corners[0], |min, corner| {
+            point(min.x.min(corner.x), min.y.min(corner.y))
+        });
+        let max = corners.iter().fold(corners[0], |max, corner| {
+            point(max.x.max(corner.x), max.y.max(corner.y))
+        });
+        let transformed_bounds = Bounds {
+            origin: min,
+            size: Size {
+                width: max.x - min.x,
+                height: max.y - min.y,
+            },
+        };
+
+        let viewport = self.viewport();
+        self.fill(transformed_bounds, viewport, |position| {
+            let position = invert(position);
+            if !contains(bounds, position) || !contains(clip, position) {
+                return None;
+            }
+            let sample = sample(&texels, &sprite.tile, bounds, position, 1)[0];
+            Some(with_alpha_factor(color, sample))
+        });
+    }
+
+    fn polychrome_sprite(&mut self, sprite: &PolychromeSprite) {
+        let Some(texels) = self.atlas.tile_texels(&sprite.tile) else {
+            return;
+        };
+        let bounds = to_f32_bounds(sprite.bounds);
+        let radii = to_f32_corners(&sprite.corner_radii);
+
+        self.fill(
+            bounds,
+            to_f32_bounds(sprite.content_mask.bounds),
+            |position| {
+                // Polychrome tiles are stored as BGRA.
+                let [b, g, r, a] = sample(&texels, &sprite.tile, bounds, position, 4);
+                let mut color = [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a];
+                if sprite.grayscale {
+                    let grayscale = color[0] * GRAYSCALE_FACTORS[0]
+                        + color[1] * GRAYSCALE_FACTORS[1]
+                        + color[2] * GRAYSCALE_FACTORS[2];
+                    color = [grayscale, grayscale, grayscale, a];
+                }
+
+                let half_size = point(bounds.size.width / 2., bounds.size.height / 2.);
+                let center_to_point = point(
+                    position.x - (bounds.origin.x + half_size.x),
+                    position.y - (bounds.origin.y + half_size.y),
+                );
+                let corner_radius = pick_corner_radius(center_to_point, radii);
+                let distance = rounded_rect_sdf(center_to_point, half_size, corner_radius);
+                Some(with_alpha_factor(color, saturate(0.5 - distance)))
+            },
+        );
+    }
+
+    fn viewport(&self) -> Bounds<f32> {
+        Bounds {
+            origin: point(0., 0.),
+            size: Size {
+                width: self.size.width.0 as f32,
+                height: self.size.height.0 as f32,
+            },
+        }
+    }
+}
+
+/// Compares a rendering against its reference, returning how many pixels differ by more than
+/// `tolerance` in any channel along with an image highlighting them, or `None` if they match.
+pub(crate) fn diff_images(
+    expected: &RgbaImage,
+    actual: &RgbaImage,
+    tolerance: u8,
+) -> Option<(usize, RgbaImage)> {
+    let width = expected.width().max(actual.width());
+    let height = expected.height().max(actual.height());
+    let mut diff = RgbaImage::new(width, height);
+    let mut differing_pixels = 0;
+    for (x, y, pixel) in diff.enumerate_pixels_mut() {
+        let expected = expected.get_pixel_checked(x, y);
+        let actual = actual.get_pixel_checked(x, y);
+        let matches = match (expected, actual) {
+            (Some(expected), Some(actual)) => expected
+                .0
+                .iter()
+                .zip(actual.0)
+                .all(|(expected, actual)| expected.abs_diff(actual) <= tolerance),
+            _ => false,
+        };
+        if matches {
+            // Keep a faded copy of the reference, so that the differences can be located.
+            let [r, g, b, a] = expected.unwrap().0;
+            pixel.0 = [r / 4 + 191, g / 4 + 191, b / 4 + 191, a];
+        } else {
+            differing_pixels += 1;
+            pixel.0 = [255, 0, 0, 255];
+        }
+    }
+    (differing_pixels > 0).then_some((differing_pixels, diff))
+}
+
+/// Bilinearly samples the tile at the given position within the sprite's bounds, returning its
+/// channels normalized to `0.0..=1.0`.
+fn sample(
+    texels: &[u8],
+    tile: &AtlasTile,
+    bounds: Bounds<f32>,
+    position: Point<f32>,
+    channels: usize,
+) -> [f32; 4] {
+    let width = tile.bounds.size.width.0;
+    let height = tile.bounds.size.height.0;
+    let mut result = [0.; 4];
+    if width <= 0 || height <= 0 || texels.len() < (width * height) as usize * channels {
+        return result;
+    }
+
+    let x = (position.x - bounds.origin.x) / bounds.size.width * width as f32 - 0.5;
+    let y = (position.y - bounds.origin.y) / bounds.size.height * height as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let texel = |tx: i32, ty: i32, channel: usize| {
+        let tx = tx.clamp(0, width - 1) as usize;
+        let ty = ty.clamp(0, height - 1) as usize;
+        texels[(ty * width as usize + tx) * channels + channel] as f32 / 255.
+    };
+    for (channel, value) in result.iter_mut().enumerate().take(channels) {
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let top = texel(x0, y0, channel) * (1. - fx) + texel(x0 + 1, y0, channel) * fx;
+        let bottom = texel(x0, y0 + 1, channel) * (1. - fx) + texel(x0 + 1, y0 + 1, channel) * fx;
+        *value = top * (1. - fy) + bottom * fy;
+    }
+    result
+}
+
+fn to_f32_bounds(bounds: Bounds<ScaledPixels>) -> Bounds<f32> {
+    Bounds {
+        origin: point(bounds.origin.x.0, bounds.origin.y.0),
+        size: Size {
+            width: bounds.size.width.0,
+            height: bounds.size.height.0,
+        },
+    }
+}
+
+fn to_f32_corners(corners: &Corners<ScaledPixels>) -> [f32; 4] {
+    [
+        corners.top_left.0,
+        corners.top_right.0,
+        corners.bottom_right.0,
+        corners.bottom_left.0,
+    ]
+}
+
+fn intersect(a: Bounds<f32>, b: Bounds<f32>) -> Bounds<f32> {
+    let left = a.origin.x.max(b.origin.x);
+    let top = a.origin.y.max(b.origin.y);
+    let right = (a.origin.x + a.size.width).min(b.origin.x + b.size.width);
+    let bottom = (a.origin.y + a.size.height).min(b.origin.y + b.size.height);
+    Bounds {
+        origin: point(left, top),
+        size: Size {
+            width: (right - left).max(0.),
+            height: (bottom - top).max(0.),
+        },
+    }
+}
+
+fn contains(bounds: Bounds<f32>, position: Point<f32>) -> bool {
+    position.x >= bounds.origin.x
+        && position.y >= bounds.origin.y
+        && position.x <= bounds.origin.x + bounds.size.width
+        && position.y <= bounds.origin.y + bounds.size.height
+}
+
+fn cross(a: Point<f32>, b: Point<f32>, c: Point<f32>) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn pick_corner_radius(
+    center_to_point: Point<f32>,
+    [top_left, top_right, bottom_right, bottom_left]: [f32; 4],
+) -> f32 {
+    match (center_to_point.x < 0., center_to_point.y < 0.) {
+        (true, true) => top_left,
+        (true, false) => bottom_left,
+        (false, true) => top_right,
+        (false, false) => bottom_right,
+    }
+}
+
+fn rounded_rect_sdf(center_to_point: Point<f32>, half_size: Point<f32>, corner_radius: f32) -> f32 {
+    let edge_x = center_to_point.x.abs() - half_size.x + corner_radius;
+    let edge_y = center_to_point.y.abs() - half_size.y + corner_radius;
+    (edge_x.max(0.).powi(2) + edge_y.max(0.).powi(2)).sqrt() + edge_x.max(edge_y).min(0.)
+        - corner_radius
+}
+
+fn gaussian(x: f32, sigma: f32) -> f32 {
+    (-(x * x) / (2. * sigma * sigma)).exp() / ((2. * PI).sqrt() * sigma)
+}
+
+fn erf(v: f32) -> f32 {
+    let s = v.signum();
+    let a = v.abs();
+    let r1 = 1. + (0.278393 + (0.230389 + 0.078108 * (a * a)) * a) * a;
+    let r2 = r1 * r1;
+    s - s / (r2 * r2)
+}
+
+fn blur_along_x(x: f32, y: f32, sigma: f32, corner: f32, half_size: Point<f32>) -> f32 {
+    let delta = (half_size.y - corner - y.abs()).min(0.);
+    let curved = half_size.x - corner + (corner * corner - delta * delta).max(0.).sqrt();
+    let factor = 0.5f32.sqrt() / sigma;
+    let low = 0.5 + 0.5 * erf((x - curved) * factor);
+    let high = 0.5 + 0.5 * erf((x + curved) * factor);
+    high - low
+}
+
+fn to_linear(color: Hsla) -> [f32; 4] {
+    let rgba = color.to_rgb();
+    [
+        srgb_to_linear(rgba.r),
+        srgb_to_linear(rgba.g),
+        srgb_to_linear(rgba.b),
+        rgba.a,
+    ]
+}
+
+fn srgb_to_linear(component: f32) -> f32 {
+    if component < 0.04045 {
+        component / 12.92
+    } else {
+        ((component + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(component: f32) -> f32 {
+    if component < 0.0031308 {
+        component * 12.92
+    } else {
+        1.055 * component.powf(1. / 2.4) - 0.055
+    }
+}
+
+fn to_byte(component: f32) -> u8 {
+    (saturate(component) * 255.).round() as u8
+}
+
+fn saturate(value: f32) -> f32 {
+    value.clamp(0., 1.)
+}
+
+fn with_alpha_factor([r, g, b, a]: [f32; 4], factor: f32) -> [f32; 4] {
+    [r, g, b, a * factor]
+}
+
+fn over(below: [f32; 4], above: [f32; 4]) -> [f32; 4] {
+    let alpha = above[3] + below[3] * (1. - above[3]);
+    if alpha == 0. {
+        return [0.; 4];
+    }
+    let channel = |i: usize| (above[i] * above[3] + below[i] * below[3] * (1. - above[3])) / alpha;
+    [channel(0), channel(1), channel(2), alpha]
+}
+
+fn mix(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] * (1. - t) + b[i] * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bounds, hsla, px, size, ContentMask, Edges, PlatformAtlas as _, RenderSvgParams};
+
+    fn scaled_bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<ScaledPixels> {
+        bounds(
+            point(ScaledPixels(x), ScaledPixels(y)),
+            size(ScaledPixels(width), ScaledPixels(height)),
+        )
+    }
+
+    fn content_mask(width: f32, height: f32) -> ContentMask<ScaledPixels> {
+        ContentMask {
+            bounds: scaled_bounds(0., 0., width, height),
+        }
+    }
+
+    #[test]
+    fn test_quads() {
+        let mut scene = Scene::default();
+        scene.insert_primitive(Quad {
+            bounds: scaled_bounds(2., 2., 6., 4.),
+            content_mask: content_mask(10., 10.),
+            background: hsla(0., 1., 0.5, 1.),
+            border_widths: Edges {
+                top: ScaledPixels(1.),
+                ..Default::default()
+            },
+            border_color: hsla(2. / 3., 1., 0.5, 1.),
+            ..Default::default()
+        });
+        // Clipped to the bottom half of the window.
+        scene.insert_primitive(Quad {
+            bounds: scaled_bounds(0., 0., 10., 10.),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 8., 10., 2.),
+            },
+            background: hsla(1. / 3., 1., 0.5, 0.5),
+            ..Default::default()
+        });
+        scene.finish();
+
+        let atlas = TestAtlas::new();
+        let image =
+            SoftwareRenderer::new(size(DevicePixels(10), DevicePixels(10)), &atlas).render(&scene);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(4, 2).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(4, 4).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(7, 5).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(8, 5).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(4, 7).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(4, 9).0, [0, 255, 0, 128]);
+    }
+
+    #[test]
+    fn test_diff_images() {
+        let mut expected = RgbaImage::new(3, 2);
+        expected.put_pixel(0, 0, image::Rgba([10, 20, 30, 255]));
+        let mut actual = expected.clone();
+        actual.put_pixel(0, 0, image::Rgba([11, 20, 30, 255]));
+        assert!(diff_images(&expected, &actual, 1).is_none());
+
+        actual.put_pixel(2, 1, image::Rgba([255, 255, 255, 255]));
+        let (differing_pixels, diff) = diff_images(&expected, &actual, 1).unwrap();
+        assert_eq!(differing_pixels, 1);
+        assert_eq!(diff.get_pixel(2, 1).0, [255, 0, 0, 255]);
+
+        let (differing_pixels, _) = diff_images(&expected, &RgbaImage::new(3, 1), 1).unwrap();
+        assert_eq!(differing_pixels, 3);
+    }
+
+    #[test]
+    fn test_paths() {
+        let mut path = Path::new(point(px(0.), px(0.)));
+        path.line_to(point(px(8.), px(0.)));
+        path.line_to(point(px(8.), px(8.)));
+        path.line_to(point(px(0.), px(0.)));
+        path.content_mask = ContentMask {
+            bounds: bounds(point(px(0.), px(0.)), size(px(8.), px(8.))),
+        };
+        path.color = hsla(0., 0., 0., 1.);
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(path.scale(1.));
+        scene.finish();
+
+        let atlas = TestAtlas::new();
+        let image =
+            SoftwareRenderer::new(size(DevicePixels(8), DevicePixels(8)), &atlas).render(&scene);
+        // The triangle's upper right half is filled.
+        assert_eq!(image.get_pixel(6, 1).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(1, 6).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_monochrome_sprites() {
+        let atlas = TestAtlas::new();
+        let tile = atlas
+            .get_or_insert_with(
+                &RenderSvgParams {
+                    path: "icon.svg".into(),
+                    size: size(DevicePixels(2), DevicePixels(2)),
+                }
+                .into(),
+                &mut || {
+                    Ok(Some((
+                        size(DevicePixels(2), DevicePixels(2)),
+                        vec![255, 0, 0, 255].into(),
+                    )))
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(MonochromeSprite {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(0., 0., 2., 2.),
+            content_mask: content_mask(4., 4.),
+            color: hsla(0., 0., 1., 1.),
+            tile,
+            transformation: Default::default(),
+        });
+        scene.finish();
+
+        let image =
+            SoftwareRenderer::new(size(DevicePixels(4), DevicePixels(4)), &atlas).render(&scene);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(1, 1).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(3, 3).0, [0, 0, 0, 0]);
+    }
+}
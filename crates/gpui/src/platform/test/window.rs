@@ -1,8 +1,8 @@
 use crate::{
-    AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DispatchEventResult, Pixels,
-    PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point,
-    Size, TestPlatform, TileId, WindowAppearance, WindowBackgroundAppearance, WindowBounds,
-    WindowParams,
+    AccessibilityAction, AccessibilityNodeId, AccessibilityTree, AnyWindowHandle, AtlasKey,
+    AtlasTextureId, AtlasTile, Bounds, DispatchEventResult, Pixels, PlatformAtlas, PlatformDisplay,
+    PlatformInput, PlatformInputHandler, PlatformWindow, Point, Size, TestPlatform, TileId,
+    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowParams,
 };
 use collections::HashMap;
 use parking_lot::Mutex;
@@ -19,7 +19,7 @@ pub(crate) struct TestWindowState {
     pub(crate) title: Option<String>,
     pub(crate) edited: bool,
     platform: Weak<TestPlatform>,
-    sprite_atlas: Arc<dyn PlatformAtlas>,
+    pub(crate) sprite_atlas: Arc<TestAtlas>,
     pub(crate) should_close_handler: Option<Box<dyn FnMut() -> bool>>,
     input_callback: Option<Box<dyn FnMut(PlatformInput) -> DispatchEventResult>>,
     active_status_change_callback: Option<Box<dyn FnMut(bool)>>,
@@ -27,6 +27,9 @@ pub(crate) struct TestWindowState {
     moved_callback: Option<Box<dyn FnMut()>>,
     input_handler: Option<PlatformInputHandler>,
     is_fullscreen: bool,
+    pub(crate) accessibility_active: bool,
+    pub(crate) accessibility_tree: Option<Arc<AccessibilityTree>>,
+    accessibility_action_callback: Option<Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>>,
 }
 
 #[derive(Clone)]
@@ -70,6 +73,9 @@ impl TestWindow {
             moved_callback: None,
             input_handler: None,
             is_fullscreen: false,
+            accessibility_active: false,
+            accessibility_tree: None,
+            accessibility_action_callback: None,
         })))
     }
 
@@ -105,6 +111,20 @@ impl TestWindow {
         self.0.lock().input_callback = Some(callback);
         !result.propagate
     }
+
+    pub(crate) fn simulate_accessibility_action(
+        &mut self,
+        node_id: AccessibilityNodeId,
+        action: AccessibilityAction,
+    ) {
+        let mut lock = self.0.lock();
+        let Some(mut callback) = lock.accessibility_action_callback.take() else {
+            return;
+        };
+        drop(lock);
+        callback(node_id, action);
+        self.0.lock().accessibility_action_callback = Some(callback);
+    }
 }
 
 impl PlatformWindow for TestWindow {
@@ -247,6 +267,21 @@ impl PlatformWindow for TestWindow {
         self.0.lock().sprite_atlas.clone()
     }
 
+    fn accessibility_active(&self) -> bool {
+        self.0.lock().accessibility_active
+    }
+
+    fn update_accessibility_tree(&self, tree: Arc<AccessibilityTree>) {
+        self.0.lock().accessibility_tree = Some(tree);
+    }
+
+    fn on_accessibility_action(
+        &self,
+        callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>,
+    ) {
+        self.0.lock().accessibility_action_callback = Some(callback);
+    }
+
     fn as_test(&mut self) -> Option<&mut TestWindow> {
         Some(self)
     }
@@ -268,6 +303,7 @@ impl PlatformWindow for TestWindow {
 pub(crate) struct TestAtlasState {
     next_id: u32,
     tiles: HashMap<AtlasKey, AtlasTile>,
+    texels: HashMap<u32, Arc<[u8]>>,
 }
 
 pub(crate) struct TestAtlas(Mutex<TestAtlasState>);
@@ -277,8 +313,15 @@ impl TestAtlas {
         TestAtlas(Mutex::new(TestAtlasState {
             next_id: 0,
             tiles: HashMap::default(),
+            texels: HashMap::default(),
         }))
     }
+
+    /// Returns the bytes that were rendered into the given tile, so that the software renderer
+    /// can sample them.
+    pub(crate) fn tile_texels(&self, tile: &AtlasTile) -> Option<Arc<[u8]>> {
+        self.0.lock().texels.get(&tile.tile_id.0).cloned()
+    }
 }
 
 impl PlatformAtlas for TestAtlas {
@@ -295,7 +338,7 @@ impl PlatformAtlas for TestAtlas {
         }
         drop(state);
 
-        let Some((size, _)) = build()? else {
+        let Some((size, bytes)) = build()? else {
             return Ok(None);
         };
 
@@ -310,7 +353,7 @@ impl PlatformAtlas for TestAtlas {
             crate::AtlasTile {
                 texture_id: AtlasTextureId {
                     index: texture_id,
-                    kind: crate::AtlasTextureKind::Path,
+                    kind: key.texture_kind(),
                 },
                 tile_id: TileId(tile_id),
                 padding: 0,
@@ -321,6 +364,8 @@ impl PlatformAtlas for TestAtlas {
             },
         );
 
+        state.texels.insert(tile_id, bytes.into_owned().into());
+
         Ok(Some(state.tiles[key].clone()))
     }
 }
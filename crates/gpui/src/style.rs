@@ -7,15 +7,15 @@ use std::{
 use crate::{
     black, phi, point, quad, rems, AbsoluteLength, Bounds, ContentMask, Corners, CornersRefinement,
     CursorStyle, DefiniteLength, Edges, EdgesRefinement, Font, FontFeatures, FontStyle, FontWeight,
-    Hsla, Length, Pixels, Point, PointRefinement, Rgba, SharedString, Size, SizeRefinement, Styled,
-    TextRun, WindowContext,
+    Hsla, Length, Pixels, Point, PointRefinement, Rems, Rgba, SharedString, Size, SizeRefinement,
+    Styled, TextRun, WindowContext,
 };
 use collections::HashSet;
 use refineable::Refineable;
 use smallvec::SmallVec;
 pub use taffy::style::{
-    AlignContent, AlignItems, AlignSelf, Display, FlexDirection, FlexWrap, JustifyContent,
-    Overflow, Position,
+    AlignContent, AlignItems, AlignSelf, Display, FlexDirection, FlexWrap, GridAutoFlow,
+    JustifyContent, JustifyItems, JustifySelf, Overflow, Position,
 };
 
 /// Use this struct for interfacing with the 'debug_below' styling from your own elements.
@@ -84,7 +84,11 @@ pub struct Style {
     pub align_content: Option<AlignContent>,
     /// How should contained within this item be aligned in the main/inline axis
     pub justify_content: Option<JustifyContent>,
-    /// How large should the gaps between items in a flex container be?
+    /// How this node's children are aligned in the inline axis of their grid areas?
+    pub justify_items: Option<JustifyItems>,
+    /// How this node should be aligned in the inline axis of its grid area. Falls back to the parents [`JustifyItems`] if not set
+    pub justify_self: Option<JustifySelf>,
+    /// How large should the gaps between items in a flex or grid container be?
     #[refineable]
     pub gap: Size<DefiniteLength>,
 
@@ -100,6 +104,24 @@ pub struct Style {
     /// The relative rate at which this item shrinks when it is contracting to fit into space, 1.0 is the default value, and this value must be positive.
     pub flex_shrink: f32,
 
+    // Grid container properties
+    /// The sizes of the explicit rows of the grid
+    pub grid_template_rows: Vec<GridTemplateTrack>,
+    /// The sizes of the explicit columns of the grid
+    pub grid_template_columns: Vec<GridTemplateTrack>,
+    /// The sizes of rows created implicitly by items placed outside the explicit grid
+    pub grid_auto_rows: Vec<GridTrack>,
+    /// The sizes of columns created implicitly by items placed outside the explicit grid
+    pub grid_auto_columns: Vec<GridTrack>,
+    /// How items that aren't explicitly placed are flowed into the grid
+    pub grid_auto_flow: GridAutoFlow,
+
+    // Grid item properties
+    /// Which rows of its parent's grid this item occupies
+    pub grid_row: GridLocation,
+    /// Which columns of its parent's grid this item occupies
+    pub grid_column: GridLocation,
+
     /// The fill color of this element
     pub background: Option<Fill>,
 
@@ -157,6 +179,147 @@ pub struct BoxShadow {
     pub spread_radius: Pixels,
 }
 
+/// The size of a row or column of a grid, similar to a CSS grid track size
+/// like `1fr` or `minmax(100px, 1fr)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GridTrack {
+    /// The smallest the track may be.
+    pub min: GridTrackBreadth,
+    /// The largest the track may grow to.
+    pub max: GridTrackBreadth,
+}
+
+impl GridTrack {
+    /// A track with a fixed size.
+    pub fn fixed(length: impl Into<DefiniteLength>) -> Self {
+        let length = GridTrackBreadth::Length(length.into());
+        Self::minmax(length, length)
+    }
+
+    /// A track that takes the given share of the grid's remaining space, like `1fr`.
+    pub fn fr(fraction: f32) -> Self {
+        Self::minmax(GridTrackBreadth::Auto, GridTrackBreadth::Fraction(fraction))
+    }
+
+    /// A track sized to fit its items, like `auto`.
+    pub fn auto() -> Self {
+        Self::minmax(GridTrackBreadth::Auto, GridTrackBreadth::Auto)
+    }
+
+    /// A track as small as its items can be without overflowing, like `min-content`.
+    pub fn min_content() -> Self {
+        Self::minmax(GridTrackBreadth::MinContent, GridTrackBreadth::MinContent)
+    }
+
+    /// A track as large as its items' preferred size, like `max-content`.
+    pub fn max_content() -> Self {
+        Self::minmax(GridTrackBreadth::MaxContent, GridTrackBreadth::MaxContent)
+    }
+
+    /// A track sized to fit its items, but no larger than the given limit, like `fit-content()`.
+    pub fn fit_content(limit: impl Into<DefiniteLength>) -> Self {
+        Self::minmax(
+            GridTrackBreadth::Auto,
+            GridTrackBreadth::FitContent(limit.into()),
+        )
+    }
+
+    /// A track sized between the given bounds, like `minmax()`.
+    pub fn minmax(min: impl Into<GridTrackBreadth>, max: impl Into<GridTrackBreadth>) -> Self {
+        Self {
+            min: min.into(),
+            max: max.into(),
+        }
+    }
+}
+
+/// One of the bounds of a [`GridTrack`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GridTrackBreadth {
+    /// A fixed length, or a percentage of the grid container.
+    Length(DefiniteLength),
+    /// A share of the remaining space in the grid. Only meaningful as a maximum; used as a minimum
+    /// it behaves like `Auto`.
+    Fraction(f32),
+    /// The track's items' minimum size as a minimum, or their maximum size as a maximum.
+    Auto,
+    /// The largest min-content size of the track's items.
+    MinContent,
+    /// The largest max-content size of the track's items.
+    MaxContent,
+    /// The track's items' max-content size, clamped to the given limit. Only meaningful as a
+    /// maximum; used as a minimum it behaves like `Auto`.
+    FitContent(DefiniteLength),
+}
+
+impl From<DefiniteLength> for GridTrackBreadth {
+    fn from(length: DefiniteLength) -> Self {
+        Self::Length(length)
+    }
+}
+
+impl From<Pixels> for GridTrackBreadth {
+    fn from(pixels: Pixels) -> Self {
+        Self::Length(pixels.into())
+    }
+}
+
+impl From<Rems> for GridTrackBreadth {
+    fn from(rems: Rems) -> Self {
+        Self::Length(rems.into())
+    }
+}
+
+/// An entry in a grid template, similar to the values of the CSS properties
+/// `grid-template-rows` and `grid-template-columns`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTemplateTrack {
+    /// A single track.
+    Single(GridTrack),
+    /// The given tracks, repeated, like `repeat()`.
+    Repeat(GridRepetition, Vec<GridTrack>),
+}
+
+impl From<GridTrack> for GridTemplateTrack {
+    fn from(track: GridTrack) -> Self {
+        Self::Single(track)
+    }
+}
+
+/// How many times the tracks in a [`GridTemplateTrack::Repeat`] are repeated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GridRepetition {
+    /// Repeat the tracks the given number of times.
+    Count(u16),
+    /// Repeat the tracks as many times as fit in the container, like `auto-fill`.
+    AutoFill,
+    /// Like `AutoFill`, but collapse repetitions that don't contain any items, like `auto-fit`.
+    AutoFit,
+}
+
+/// Which rows or columns of a grid an item occupies, similar to the CSS properties `grid-row`
+/// and `grid-column`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GridLocation {
+    /// Where the item starts.
+    pub start: GridPlacement,
+    /// Where the item ends.
+    pub end: GridPlacement,
+}
+
+/// One edge of a [`GridLocation`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum GridPlacement {
+    /// Place the item automatically.
+    #[default]
+    Auto,
+    /// Place the edge at the given grid line. Lines are numbered from 1, and negative numbers
+    /// count back from the last line.
+    Line(i16),
+    /// Span the given number of tracks from the other edge.
+    Span(u16),
+}
+
 /// How to handle whitespace in text
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum WhiteSpace {
@@ -545,12 +708,22 @@ impl Default for Style {
             align_self: None,
             align_content: None,
             justify_content: None,
+            justify_items: None,
+            justify_self: None,
             // Flexbox
             flex_direction: FlexDirection::Row,
             flex_wrap: FlexWrap::NoWrap,
             flex_grow: 0.0,
             flex_shrink: 1.0,
             flex_basis: Length::Auto,
+            // Grid
+            grid_template_rows: Vec::new(),
+            grid_template_columns: Vec::new(),
+            grid_auto_rows: Vec::new(),
+            grid_auto_columns: Vec::new(),
+            grid_auto_flow: GridAutoFlow::Row,
+            grid_row: GridLocation::default(),
+            grid_column: GridLocation::default(),
             background: None,
             border_color: None,
             corner_radii: Corners::default(),
@@ -1,7 +1,9 @@
 use crate::{
     self as gpui, hsla, point, px, relative, rems, AbsoluteLength, AlignItems, CursorStyle,
-    DefiniteLength, Fill, FlexDirection, FlexWrap, Font, FontStyle, FontWeight, Hsla,
-    JustifyContent, Length, Position, SharedString, StyleRefinement, Visibility, WhiteSpace,
+    DefiniteLength, Fill, FlexDirection, FlexWrap, Font, FontStyle, FontWeight, GridAutoFlow,
+    GridPlacement, GridRepetition, GridTemplateTrack, GridTrack, GridTrackBreadth, Hsla,
+    JustifyContent, JustifyItems, JustifySelf, Length, Position, SharedString, StyleRefinement,
+    Visibility, WhiteSpace,
 };
 use crate::{BoxShadow, TextStyleRefinement};
 use smallvec::{smallvec, SmallVec};
@@ -43,6 +45,13 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Sets the display type of the element to `grid`.
+    /// [Docs](https://tailwindcss.com/docs/display)
+    fn grid(mut self) -> Self {
+        self.style().display = Some(Display::Grid);
+        self
+    }
+
     /// Sets the visibility of the element to `visible`.
     /// [Docs](https://tailwindcss.com/docs/visibility)
     fn visible(mut self) -> Self {
@@ -479,6 +488,218 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Sets the element to have the given number of equally sized grid columns.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-columns)
+    fn grid_cols(mut self, columns: u16) -> Self {
+        self.style().grid_template_columns = Some(equal_tracks(columns));
+        self
+    }
+
+    /// Sets the element to have the given number of equally sized grid rows.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-rows)
+    fn grid_rows(mut self, rows: u16) -> Self {
+        self.style().grid_template_rows = Some(equal_tracks(rows));
+        self
+    }
+
+    /// Sets the sizes of the element's grid columns.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-columns#arbitrary-values)
+    fn grid_template_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<GridTemplateTrack>>,
+    ) -> Self {
+        self.style().grid_template_columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the sizes of the element's grid rows.
+    /// [Docs](https://tailwindcss.com/docs/grid-template-rows#arbitrary-values)
+    fn grid_template_rows(
+        mut self,
+        rows: impl IntoIterator<Item = impl Into<GridTemplateTrack>>,
+    ) -> Self {
+        self.style().grid_template_rows = Some(rows.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the sizes of grid columns that are created implicitly.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-columns)
+    fn grid_auto_columns(mut self, columns: impl IntoIterator<Item = GridTrack>) -> Self {
+        self.style().grid_auto_columns = Some(columns.into_iter().collect());
+        self
+    }
+
+    /// Sets the sizes of grid rows that are created implicitly.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-rows)
+    fn grid_auto_rows(mut self, rows: impl IntoIterator<Item = GridTrack>) -> Self {
+        self.style().grid_auto_rows = Some(rows.into_iter().collect());
+        self
+    }
+
+    /// Sets the element to place grid items by filling each row in turn.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_row(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::Row);
+        self
+    }
+
+    /// Sets the element to place grid items by filling each column in turn.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_col(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::Column);
+        self
+    }
+
+    /// Sets the element to place grid items by filling each row in turn, filling in earlier
+    /// holes in the grid when later items fit them.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_row_dense(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::RowDense);
+        self
+    }
+
+    /// Sets the element to place grid items by filling each column in turn, filling in earlier
+    /// holes in the grid when later items fit them.
+    /// [Docs](https://tailwindcss.com/docs/grid-auto-flow)
+    fn grid_flow_col_dense(mut self) -> Self {
+        self.style().grid_auto_flow = Some(GridAutoFlow::ColumnDense);
+        self
+    }
+
+    /// Sets the element to span the given number of columns of its parent's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-column)
+    fn col_span(mut self, columns: u16) -> Self {
+        let column = self
+            .style()
+            .grid_column
+            .get_or_insert_with(Default::default);
+        column.end = GridPlacement::Span(columns);
+        self
+    }
+
+    /// Sets the element to span all columns of its parent's explicit grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-column)
+    fn col_span_full(mut self) -> Self {
+        let column = self
+            .style()
+            .grid_column
+            .get_or_insert_with(Default::default);
+        column.start = GridPlacement::Line(1);
+        column.end = GridPlacement::Line(-1);
+        self
+    }
+
+    /// Sets the element to start at the given column line of its parent's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-column)
+    fn col_start(mut self, line: i16) -> Self {
+        let column = self
+            .style()
+            .grid_column
+            .get_or_insert_with(Default::default);
+        column.start = GridPlacement::Line(line);
+        self
+    }
+
+    /// Sets the element to end at the given column line of its parent's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-column)
+    fn col_end(mut self, line: i16) -> Self {
+        let column = self
+            .style()
+            .grid_column
+            .get_or_insert_with(Default::default);
+        column.end = GridPlacement::Line(line);
+        self
+    }
+
+    /// Sets the element to span the given number of rows of its parent's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-row)
+    fn row_span(mut self, rows: u16) -> Self {
+        let row = self.style().grid_row.get_or_insert_with(Default::default);
+        row.end = GridPlacement::Span(rows);
+        self
+    }
+
+    /// Sets the element to span all rows of its parent's explicit grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-row)
+    fn row_span_full(mut self) -> Self {
+        let row = self.style().grid_row.get_or_insert_with(Default::default);
+        row.start = GridPlacement::Line(1);
+        row.end = GridPlacement::Line(-1);
+        self
+    }
+
+    /// Sets the element to start at the given row line of its parent's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-row)
+    fn row_start(mut self, line: i16) -> Self {
+        let row = self.style().grid_row.get_or_insert_with(Default::default);
+        row.start = GridPlacement::Line(line);
+        self
+    }
+
+    /// Sets the element to end at the given row line of its parent's grid.
+    /// [Docs](https://tailwindcss.com/docs/grid-row)
+    fn row_end(mut self, line: i16) -> Self {
+        let row = self.style().grid_row.get_or_insert_with(Default::default);
+        row.end = GridPlacement::Line(line);
+        self
+    }
+
+    /// Sets the element to align grid items against the start of their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#start)
+    fn justify_items_start(mut self) -> Self {
+        self.style().justify_items = Some(JustifyItems::Start);
+        self
+    }
+
+    /// Sets the element to align grid items against the end of their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#end)
+    fn justify_items_end(mut self) -> Self {
+        self.style().justify_items = Some(JustifyItems::End);
+        self
+    }
+
+    /// Sets the element to align grid items along the center of their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#center)
+    fn justify_items_center(mut self) -> Self {
+        self.style().justify_items = Some(JustifyItems::Center);
+        self
+    }
+
+    /// Sets the element to stretch grid items to fill their grid areas' inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-items#stretch)
+    fn justify_items_stretch(mut self) -> Self {
+        self.style().justify_items = Some(JustifyItems::Stretch);
+        self
+    }
+
+    /// Sets the element to align itself against the start of its grid area's inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-self#start)
+    fn justify_self_start(mut self) -> Self {
+        self.style().justify_self = Some(JustifySelf::Start);
+        self
+    }
+
+    /// Sets the element to align itself against the end of its grid area's inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-self#end)
+    fn justify_self_end(mut self) -> Self {
+        self.style().justify_self = Some(JustifySelf::End);
+        self
+    }
+
+    /// Sets the element to align itself along the center of its grid area's inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-self#center)
+    fn justify_self_center(mut self) -> Self {
+        self.style().justify_self = Some(JustifySelf::Center);
+        self
+    }
+
+    /// Sets the element to stretch itself to fill its grid area's inline axis.
+    /// [Docs](https://tailwindcss.com/docs/justify-self#stretch)
+    fn justify_self_stretch(mut self) -> Self {
+        self.style().justify_self = Some(JustifySelf::Stretch);
+        self
+    }
+
     /// Sets the background color of the element.
     fn bg<F>(mut self, fill: F) -> Self
     where
@@ -833,3 +1054,12 @@ pub trait Styled: Sized {
         self
     }
 }
+
+/// The tracks of a grid with the given number of equally sized rows or columns, like Tailwind's
+/// `repeat(n, minmax(0, 1fr))`.
+fn equal_tracks(count: u16) -> Vec<GridTemplateTrack> {
+    vec![GridTemplateTrack::Repeat(
+        GridRepetition::Count(count),
+        vec![GridTrack::minmax(px(0.), GridTrackBreadth::Fraction(1.))],
+    )]
+}
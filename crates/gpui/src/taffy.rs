@@ -1,5 +1,6 @@
 use crate::{
-    AbsoluteLength, Bounds, DefiniteLength, Edges, Length, Pixels, Point, Size, Style,
+    AbsoluteLength, Bounds, DefiniteLength, Edges, GridLocation, GridPlacement, GridRepetition,
+    GridTemplateTrack, GridTrack, GridTrackBreadth, Length, Pixels, Point, Size, Style,
     WindowContext,
 };
 use collections::{FxHashMap, FxHashSet};
@@ -265,13 +266,94 @@ impl ToTaffy<taffy::style::Style> for Style {
             align_self: self.align_self,
             align_content: self.align_content,
             justify_content: self.justify_content,
+            justify_items: self.justify_items,
+            justify_self: self.justify_self,
             gap: self.gap.to_taffy(rem_size),
             flex_direction: self.flex_direction,
             flex_wrap: self.flex_wrap,
             flex_basis: self.flex_basis.to_taffy(rem_size),
             flex_grow: self.flex_grow,
             flex_shrink: self.flex_shrink,
-            ..Default::default() // Ignore grid properties for now
+            grid_template_rows: self.grid_template_rows.to_taffy(rem_size),
+            grid_template_columns: self.grid_template_columns.to_taffy(rem_size),
+            grid_auto_rows: self.grid_auto_rows.to_taffy(rem_size),
+            grid_auto_columns: self.grid_auto_columns.to_taffy(rem_size),
+            grid_auto_flow: self.grid_auto_flow,
+            grid_row: self.grid_row.to_taffy(rem_size),
+            grid_column: self.grid_column.to_taffy(rem_size),
+        }
+    }
+}
+
+impl<T, U> ToTaffy<Vec<U>> for Vec<T>
+where
+    T: ToTaffy<U>,
+{
+    fn to_taffy(&self, rem_size: Pixels) -> Vec<U> {
+        self.iter().map(|item| item.to_taffy(rem_size)).collect()
+    }
+}
+
+impl ToTaffy<taffy::style::TrackSizingFunction> for GridTemplateTrack {
+    fn to_taffy(&self, rem_size: Pixels) -> taffy::style::TrackSizingFunction {
+        match self {
+            GridTemplateTrack::Single(track) => {
+                taffy::style::TrackSizingFunction::Single(track.to_taffy(rem_size))
+            }
+            GridTemplateTrack::Repeat(repetition, tracks) => {
+                let repetition = match repetition {
+                    GridRepetition::Count(count) => {
+                        taffy::style::GridTrackRepetition::Count(*count)
+                    }
+                    GridRepetition::AutoFill => taffy::style::GridTrackRepetition::AutoFill,
+                    GridRepetition::AutoFit => taffy::style::GridTrackRepetition::AutoFit,
+                };
+                taffy::style::TrackSizingFunction::Repeat(repetition, tracks.to_taffy(rem_size))
+            }
+        }
+    }
+}
+
+impl ToTaffy<taffy::style::NonRepeatedTrackSizingFunction> for GridTrack {
+    fn to_taffy(&self, rem_size: Pixels) -> taffy::style::NonRepeatedTrackSizingFunction {
+        use taffy::style::{MaxTrackSizingFunction as Max, MinTrackSizingFunction as Min};
+
+        let min = match self.min {
+            GridTrackBreadth::Length(length) => Min::Fixed(length.to_taffy(rem_size)),
+            GridTrackBreadth::MinContent => Min::MinContent,
+            GridTrackBreadth::MaxContent => Min::MaxContent,
+            // Flexible and fit-content sizes aren't valid minimums in CSS either.
+            GridTrackBreadth::Auto
+            | GridTrackBreadth::Fraction(_)
+            | GridTrackBreadth::FitContent(_) => Min::Auto,
+        };
+        let max = match self.max {
+            GridTrackBreadth::Length(length) => Max::Fixed(length.to_taffy(rem_size)),
+            GridTrackBreadth::Fraction(fraction) => Max::Fraction(fraction),
+            GridTrackBreadth::Auto => Max::Auto,
+            GridTrackBreadth::MinContent => Max::MinContent,
+            GridTrackBreadth::MaxContent => Max::MaxContent,
+            GridTrackBreadth::FitContent(limit) => Max::FitContent(limit.to_taffy(rem_size)),
+        };
+        taffy::geometry::MinMax { min, max }
+    }
+}
+
+impl ToTaffy<taffy::geometry::Line<taffy::style::GridPlacement>> for GridLocation {
+    fn to_taffy(&self, _: Pixels) -> taffy::geometry::Line<taffy::style::GridPlacement> {
+        taffy::geometry::Line {
+            start: self.start.into(),
+            end: self.end.into(),
+        }
+    }
+}
+
+impl From<GridPlacement> for taffy::style::GridPlacement {
+    fn from(placement: GridPlacement) -> Self {
+        match placement {
+            GridPlacement::Auto => taffy::style::GridPlacement::Auto,
+            GridPlacement::Line(index) => taffy::style_helpers::line(index),
+            GridPlacement::Span(count) => taffy::style::GridPlacement::Span(count),
         }
     }
 }
@@ -498,3 +580,75 @@ impl From<Size<Pixels>> for Size<AvailableSpace> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as gpui, canvas, div, point, px, size, Bounds, Canvas, GridTrack, ParentElement as _,
+        Pixels, Styled as _, TestAppContext,
+    };
+    use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+    #[gpui::test]
+    fn test_grid_layout(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+
+        let bounds = Rc::new(RefCell::new(BTreeMap::new()));
+        let cell = |name: &'static str| -> Canvas<()> {
+            let bounds = bounds.clone();
+            canvas(
+                move |cell_bounds, _| {
+                    bounds.borrow_mut().insert(name, cell_bounds);
+                },
+                |_, _, _| {},
+            )
+        };
+
+        cx.draw(point(px(0.), px(0.)), size(px(300.), px(300.)), |_| {
+            div()
+                .w(px(300.))
+                .grid()
+                .grid_cols(3)
+                .grid_auto_rows([GridTrack::fixed(px(50.))])
+                .gap_x(px(15.))
+                .gap_y(px(10.))
+                .child(cell("a"))
+                .child(cell("b").col_span(2))
+                .child(cell("c"))
+                .child(cell("d").col_span_full())
+        });
+        assert_eq!(
+            *bounds.borrow(),
+            BTreeMap::from_iter([
+                ("a", bounds_at(0., 0., 90., 50.)),
+                ("b", bounds_at(105., 0., 195., 50.)),
+                ("c", bounds_at(0., 60., 90., 50.)),
+                ("d", bounds_at(0., 120., 300., 50.)),
+            ])
+        );
+
+        bounds.borrow_mut().clear();
+        cx.draw(point(px(0.), px(0.)), size(px(300.), px(300.)), |_| {
+            div()
+                .w(px(300.))
+                .grid()
+                .grid_template_columns([GridTrack::fixed(px(100.)), GridTrack::fr(1.)])
+                .grid_template_rows([GridTrack::fixed(px(20.)), GridTrack::fixed(px(30.))])
+                .child(cell("placed").col_start(2).row_start(2))
+                .child(cell("auto"))
+                .child(cell("start").justify_self_start().w(px(40.)))
+        });
+        assert_eq!(
+            *bounds.borrow(),
+            BTreeMap::from_iter([
+                ("placed", bounds_at(100., 20., 200., 30.)),
+                ("auto", bounds_at(0., 0., 100., 20.)),
+                ("start", bounds_at(100., 0., 40., 20.)),
+            ])
+        );
+    }
+
+    fn bounds_at(x: f32, y: f32, width: f32, height: f32) -> Bounds<Pixels> {
+        Bounds::new(point(px(x), px(y)), size(px(width), px(height)))
+    }
+}
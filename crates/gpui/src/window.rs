@@ -1,20 +1,21 @@
 use crate::{
-    hash, point, prelude::*, px, size, transparent_black, Action, AnyDrag, AnyElement, AnyTooltip,
-    AnyView, AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace, Bounds, BoxShadow,
-    Context, Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener,
-    DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter,
-    FileDropEvent, Flatten, FontId, Global, GlobalElementId, GlyphId, Hsla, ImageData,
-    InputHandler, IsZero, KeyBinding, KeyContext, KeyDownEvent, KeyEvent, KeyMatch, KeymatchResult,
-    Keystroke, KeystrokeEvent, LayoutId, LineLayoutIndex, Model, ModelContext, Modifiers,
-    ModifiersChangedEvent, MonochromeSprite, MouseButton, MouseEvent, MouseMoveEvent, MouseUpEvent,
-    Path, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformWindow, Point, PolychromeSprite, PromptLevel, Quad, Render, RenderGlyphParams,
-    RenderImageParams, RenderSvgParams, ResizeEdge, ScaledPixels, Scene, Shadow, SharedString,
-    Size, StrikethroughStyle, Style, SubscriberSet, Subscription, TaffyLayoutEngine, Task,
-    TextStyle, TextStyleRefinement, TransformationMatrix, Underline, UnderlineStyle, View,
-    VisualContext, WeakView, WindowAppearance, WindowBackgroundAppearance, WindowBounds,
-    WindowControls, WindowDecorations, WindowOptions, WindowParams, WindowTextSystem,
-    SUBPIXEL_VARIANTS,
+    hash, point, prelude::*, px, size, transparent_black, AccessibilityAction,
+    AccessibilityClickHandler, AccessibilityEntry, AccessibilityNodeId, AccessibilityProperties,
+    AccessibilityTree, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, AppContext, Arena, Asset,
+    AsyncWindowContext, AvailableSpace, Bounds, BoxShadow, ClickEvent, Context, Corners,
+    CursorStyle, Decorations, DevicePixels, DispatchActionListener, DispatchNodeId, DispatchTree,
+    DisplayId, Edges, Effect, Entity, EntityId, EventEmitter, FileDropEvent, Flatten, FontId,
+    Global, GlobalElementId, GlyphId, Hsla, ImageData, InputHandler, IsZero, KeyBinding,
+    KeyContext, KeyDownEvent, KeyEvent, KeyMatch, KeymatchResult, Keystroke, KeystrokeEvent,
+    LayoutId, LineLayoutIndex, Model, ModelContext, Modifiers, ModifiersChangedEvent,
+    MonochromeSprite, MouseButton, MouseDownEvent, MouseEvent, MouseMoveEvent, MouseUpEvent, Path,
+    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
+    Point, PolychromeSprite, PromptLevel, Quad, Render, RenderGlyphParams, RenderImageParams,
+    RenderSvgParams, ResizeEdge, ScaledPixels, Scene, Shadow, SharedString, Size,
+    StrikethroughStyle, Style, SubscriberSet, Subscription, TaffyLayoutEngine, Task, TextStyle,
+    TextStyleRefinement, TransformationMatrix, Underline, UnderlineStyle, View, VisualContext,
+    WeakView, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowControls,
+    WindowDecorations, WindowOptions, WindowParams, WindowTextSystem, SUBPIXEL_VARIANTS,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::{FxHashMap, FxHashSet};
@@ -408,6 +409,7 @@ pub(crate) struct Frame {
     pub(crate) input_handlers: Vec<Option<PlatformInputHandler>>,
     pub(crate) tooltip_requests: Vec<Option<TooltipRequest>>,
     pub(crate) cursor_styles: Vec<CursorStyleRequest>,
+    pub(crate) accessibility_entries: Vec<AccessibilityEntry>,
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) debug_bounds: FxHashMap<String, Bounds<Pixels>>,
 }
@@ -428,6 +430,7 @@ pub(crate) struct PaintIndex {
     mouse_listeners_index: usize,
     input_handlers_index: usize,
     cursor_styles_index: usize,
+    accessibility_entries_index: usize,
     accessed_element_states_index: usize,
     line_layout_index: LineLayoutIndex,
 }
@@ -447,6 +450,7 @@ impl Frame {
             input_handlers: Vec::new(),
             tooltip_requests: Vec::new(),
             cursor_styles: Vec::new(),
+            accessibility_entries: Vec::new(),
 
             #[cfg(any(test, feature = "test-support"))]
             debug_bounds: FxHashMap::default(),
@@ -462,6 +466,7 @@ impl Frame {
         self.input_handlers.clear();
         self.tooltip_requests.clear();
         self.cursor_styles.clear();
+        self.accessibility_entries.clear();
         self.hitboxes.clear();
         self.deferred_draws.clear();
     }
@@ -553,6 +558,9 @@ pub struct Window {
     pending_modifier: ModifierState,
     pending_input_observers: SubscriberSet<(), AnyObserver>,
     prompt: Option<RenderablePromptHandle>,
+    accessibility_active: bool,
+    accessibility_tree: Arc<AccessibilityTree>,
+    accessibility_click_handlers: FxHashMap<AccessibilityNodeId, AccessibilityClickHandler>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -735,6 +743,13 @@ impl Window {
                 handle
                     .update(&mut cx, |_, cx| {
                         cx.complete_frame();
+                        // Assistive technology can attach at any time, so draw again when it
+                        // does, to hand it a tree.
+                        if cx.window.platform_window.accessibility_active()
+                            != cx.window.accessibility_active
+                        {
+                            cx.window.dirty.set(true);
+                        }
                     })
                     .log_err();
             }
@@ -788,6 +803,16 @@ impl Window {
                     .unwrap_or(DispatchEventResult::default())
             })
         });
+        platform_window.on_accessibility_action({
+            let mut cx = cx.to_async();
+            Box::new(move |node_id, action| {
+                handle
+                    .update(&mut cx, |_, cx| {
+                        cx.perform_accessibility_action(node_id, action)
+                    })
+                    .log_err();
+            })
+        });
 
         if let Some(app_id) = app_id {
             platform_window.set_app_id(&app_id);
@@ -841,6 +866,13 @@ impl Window {
             pending_modifier: ModifierState::default(),
             pending_input_observers: SubscriberSet::new(),
             prompt: None,
+            accessibility_active: false,
+            accessibility_tree: Arc::new(AccessibilityTree::build(
+                Bounds::new(Point::default(), content_size),
+                &[],
+                None,
+            )),
+            accessibility_click_handlers: FxHashMap::default(),
         })
     }
     fn new_focus_listener(
@@ -1395,6 +1427,14 @@ impl<'a> WindowContext<'a> {
         self.window.dirty.set(false);
         self.window.requested_autoscroll = None;
 
+        // Cached views didn't record accessibility nodes while no assistive technology was
+        // listening, so they're all painted again when one starts.
+        let accessibility_active = self.window.platform_window.accessibility_active();
+        if accessibility_active != self.window.accessibility_active {
+            self.window.accessibility_active = accessibility_active;
+            self.window.refreshing = true;
+        }
+
         // Restore the previously-used input handler.
         if let Some(input_handler) = self.window.platform_window.take_input_handler() {
             self.window
@@ -1472,12 +1512,42 @@ impl<'a> WindowContext<'a> {
                 .retain(&(), |listener| listener(&event, self));
         }
 
+        self.update_accessibility_tree();
         self.reset_cursor_style();
         self.window.refreshing = false;
         self.window.draw_phase = DrawPhase::None;
         self.window.needs_present.set(true);
     }
 
+    fn update_accessibility_tree(&mut self) {
+        if !self.window.accessibility_active {
+            return;
+        }
+
+        let entries = &self.window.rendered_frame.accessibility_entries;
+        let tree = AccessibilityTree::build(
+            Bounds::new(Point::default(), self.window.viewport_size),
+            entries,
+            self.window.focus,
+        );
+        // Every opened entry adds a node, in order, after the root.
+        self.window.accessibility_click_handlers = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                AccessibilityEntry::Open { on_click, .. } => Some(on_click),
+                AccessibilityEntry::Close => None,
+            })
+            .zip(tree.nodes().skip(1))
+            .filter_map(|(on_click, node)| Some((node.id, on_click.clone()?)))
+            .collect();
+        if tree != *self.window.accessibility_tree {
+            self.window.accessibility_tree = Arc::new(tree);
+            self.window
+                .platform_window
+                .update_accessibility_tree(self.window.accessibility_tree.clone());
+        }
+    }
+
     #[profiling::function]
     fn present(&self) {
         self.window
@@ -1707,6 +1777,7 @@ impl<'a> WindowContext<'a> {
             mouse_listeners_index: self.window.next_frame.mouse_listeners.len(),
             input_handlers_index: self.window.next_frame.input_handlers.len(),
             cursor_styles_index: self.window.next_frame.cursor_styles.len(),
+            accessibility_entries_index: self.window.next_frame.accessibility_entries.len(),
             accessed_element_states_index: self.window.next_frame.accessed_element_states.len(),
             line_layout_index: self.window.text_system.layout_index(),
         }
@@ -1733,6 +1804,12 @@ impl<'a> WindowContext<'a> {
                 .iter_mut()
                 .map(|listener| listener.take()),
         );
+        window.next_frame.accessibility_entries.extend(
+            window.rendered_frame.accessibility_entries
+                [range.start.accessibility_entries_index..range.end.accessibility_entries_index]
+                .iter()
+                .cloned(),
+        );
         window.next_frame.accessed_element_states.extend(
             window.rendered_frame.accessed_element_states[range.start.accessed_element_states_index
                 ..range.end.accessed_element_states_index]
@@ -1789,6 +1866,110 @@ impl<'a> WindowContext<'a> {
             });
     }
 
+    /// Whether an assistive technology is listening to this window. Nodes are only recorded while
+    /// it is, so elements can skip computing their accessibility properties otherwise.
+    pub fn accessibility_active(&self) -> bool {
+        self.window.accessibility_active
+    }
+
+    /// Describes the element being painted to assistive technologies, with the accessibility
+    /// nodes added by the given function as its children. The focus handle, if given, marks the
+    /// node as focused while it has focus, and the click handler, if given, is called when
+    /// assistive technology clicks the node. This method should only be called during paint.
+    pub fn with_accessibility_node<R>(
+        &mut self,
+        properties: AccessibilityProperties,
+        bounds: Bounds<Pixels>,
+        focus_handle: Option<&FocusHandle>,
+        on_click: Option<AccessibilityClickHandler>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        debug_assert_eq!(
+            self.window.draw_phase,
+            DrawPhase::Paint,
+            "this method can only be called during paint"
+        );
+        if !self.window.accessibility_active {
+            return f(self);
+        }
+
+        let mut hasher = collections::FxHasher::default();
+        self.window.element_id_stack.hash(&mut hasher);
+        self.window
+            .next_frame
+            .accessibility_entries
+            .push(AccessibilityEntry::Open {
+                properties,
+                bounds,
+                key: hasher.finish(),
+                focus_id: focus_handle.map(|handle| handle.id),
+                on_click,
+            });
+        let result = f(self);
+        self.window
+            .next_frame
+            .accessibility_entries
+            .push(AccessibilityEntry::Close);
+        result
+    }
+
+    /// Returns the window's accessibility tree as of the last frame.
+    pub fn accessibility_tree(&self) -> Arc<AccessibilityTree> {
+        self.window.accessibility_tree.clone()
+    }
+
+    /// Performs an action requested by assistive technology on a node of the window's
+    /// accessibility tree. Clicks are passed to the node's click handler, as if the node had
+    /// been clicked at its center, even when other elements cover it.
+    pub fn perform_accessibility_action(
+        &mut self,
+        node_id: AccessibilityNodeId,
+        action: AccessibilityAction,
+    ) {
+        let tree = self.window.accessibility_tree.clone();
+        let Some(node) = tree.node(node_id) else {
+            return;
+        };
+        match action {
+            AccessibilityAction::Click => {
+                let Some(on_click) = self
+                    .window
+                    .accessibility_click_handlers
+                    .get(&node_id)
+                    .cloned()
+                else {
+                    return;
+                };
+                let position = node.bounds.center();
+                let modifiers = self.window.modifiers;
+                let event = ClickEvent {
+                    down: MouseDownEvent {
+                        button: MouseButton::Left,
+                        position,
+                        modifiers,
+                        click_count: 1,
+                        first_mouse: false,
+                    },
+                    up: MouseUpEvent {
+                        button: MouseButton::Left,
+                        position,
+                        modifiers,
+                        click_count: 1,
+                    },
+                };
+                on_click(&event, self);
+            }
+            AccessibilityAction::Focus => {
+                if let Some(handle) = node
+                    .focus_id
+                    .and_then(|id| FocusHandle::for_id(id, &self.window.focus_handles))
+                {
+                    self.focus(&handle);
+                }
+            }
+        }
+    }
+
     /// Sets a tooltip to be rendered for the upcoming frame. This method should only be called
     /// during the paint phase of element drawing.
     pub fn set_tooltip(&mut self, tooltip: AnyTooltip) -> TooltipId {
@@ -346,19 +346,19 @@ fn box_prefixes() -> Vec<(&'static str, bool, Vec<TokenStream2>, &'static str)>
             "gap",
             false,
             vec![quote! { gap.width }, quote! { gap.height }],
-            "Sets the gap between rows and columns in flex layouts. [Docs](https://tailwindcss.com/docs/gap)"
+            "Sets the gap between rows and columns in flex and grid layouts. [Docs](https://tailwindcss.com/docs/gap)"
         ),
         (
             "gap_x",
             false,
             vec![quote! { gap.width }],
-            "Sets the gap between columns in flex layouts. [Docs](https://tailwindcss.com/docs/gap#changing-row-and-column-gaps-independently)"
+            "Sets the gap between columns in flex and grid layouts. [Docs](https://tailwindcss.com/docs/gap#changing-row-and-column-gaps-independently)"
         ),
         (
             "gap_y",
             false,
             vec![quote! { gap.height }],
-            "Sets the gap between rows in flex layouts. [Docs](https://tailwindcss.com/docs/gap#changing-row-and-column-gaps-independently)"
+            "Sets the gap between rows in flex and grid layouts. [Docs](https://tailwindcss.com/docs/gap#changing-row-and-column-gaps-independently)"
         ),
     ]
 }
@@ -0,0 +1,141 @@
+use editor::Editor;
+use gpui::{
+    div, prelude::*, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    SharedString, Styled, Subscription, View, ViewContext, VisualContext,
+};
+use ui::{h_flex, prelude::*, v_flex, Label};
+use workspace::ModalView;
+
+use crate::HexEditor;
+
+pub struct GoToOffset {
+    offset_editor: View<Editor>,
+    hex_editor: View<HexEditor>,
+    current_text: SharedString,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for GoToOffset {}
+
+impl FocusableView for GoToOffset {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.offset_editor.focus_handle(cx)
+    }
+}
+impl EventEmitter<DismissEvent> for GoToOffset {}
+
+impl GoToOffset {
+    pub fn new(hex_editor: View<HexEditor>, cx: &mut ViewContext<Self>) -> Self {
+        let cursor = hex_editor.read(cx).cursor();
+        let len = hex_editor.read(cx).item().read(cx).len();
+
+        let offset_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(format!("{cursor:#x}"), cx);
+            editor
+        });
+        let offset_editor_change = cx.subscribe(&offset_editor, Self::on_offset_editor_event);
+
+        Self {
+            offset_editor,
+            hex_editor,
+            current_text: format!("offset {cursor:#x} of {len:#x}").into(),
+            _subscriptions: vec![offset_editor_change],
+        }
+    }
+
+    fn on_offset_editor_event(
+        &mut self,
+        _: View<Editor>,
+        event: &editor::EditorEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            editor::EditorEvent::Blurred => cx.emit(DismissEvent),
+            editor::EditorEvent::BufferEdited { .. } => cx.notify(),
+            _ => {}
+        }
+    }
+
+    fn offset_from_query(&self, cx: &AppContext) -> Option<usize> {
+        parse_offset(&self.offset_editor.read(cx).text(cx))
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(offset) = self.offset_from_query(cx) {
+            self.hex_editor
+                .update(cx, |hex_editor, cx| hex_editor.go_to_offset(offset, cx));
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+/// Parses an offset written as a hexadecimal number with a `0x` prefix, or as a
+/// decimal number.
+pub(crate) fn parse_offset(text: &str) -> Option<usize> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+impl Render for GoToOffset {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let help_text = match self.offset_from_query(cx) {
+            Some(offset) => format!("Go to offset {offset:#x} ({offset})").into(),
+            None => self.current_text.clone(),
+        };
+
+        div()
+            .elevation_2(cx)
+            .key_context("GoToOffset")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.offset_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .px_2()
+                            .py_1()
+                            .child(Label::new(help_text).color(Color::Muted)),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("0x1F"), Some(31));
+        assert_eq!(parse_offset(" 0Xff "), Some(255));
+        assert_eq!(parse_offset("100"), Some(100));
+        assert_eq!(parse_offset("1F"), None);
+        assert_eq!(parse_offset("0x"), None);
+        assert_eq!(parse_offset(""), None);
+    }
+}
@@ -0,0 +1,1221 @@
+mod go_to_offset;
+
+use anyhow::{anyhow, Result};
+use gpui::{
+    actions, div, uniform_list, AnyElement, AppContext, ClipboardItem, Context, EntityId,
+    EventEmitter, FocusHandle, FocusableView, Hsla, InteractiveElement, IntoElement, KeyDownEvent,
+    Model, ModelContext, MouseButton, MouseDownEvent, ParentElement, Render, Styled, Subscription,
+    Task, UniformListScrollHandle, View, ViewContext, VisualContext, WeakModel, WindowContext,
+};
+use project::{search::SearchQuery, Fs, Project, ProjectEntryId, ProjectPath, RenameOptions};
+use settings::Settings;
+use std::{
+    ffi::OsString,
+    ops::Range,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+};
+use theme::ThemeSettings;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent, ProjectItem, TabContentParams},
+    searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
+    Workspace, WorkspaceId,
+};
+
+pub use go_to_offset::GoToOffset;
+
+const BYTES_PER_ROW: usize = 16;
+const ROWS_PER_PAGE: usize = 32;
+
+/// Files that are larger than this many bytes aren't opened in the hex editor, as they
+/// are read into memory in their entirety.
+pub const MAX_FILE_LEN: u64 = 256 * 1024 * 1024;
+
+actions!(
+    hex_editor,
+    [
+        MoveLeft,
+        MoveRight,
+        MoveUp,
+        MoveDown,
+        SelectLeft,
+        SelectRight,
+        SelectUp,
+        SelectDown,
+        MoveToBeginningOfRow,
+        MoveToEndOfRow,
+        MoveToBeginning,
+        MoveToEnd,
+        PageUp,
+        PageDown,
+        SelectAll,
+        ToggleColumn,
+        Copy,
+        Undo,
+        Redo,
+        ToggleGoToOffset,
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    // Only used for files that no other item can open, like binary files that aren't images.
+    workspace::register_fallback_project_item::<HexEditor>(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleGoToOffset, cx| {
+            let Some(hex_editor) = workspace.active_item_as::<HexEditor>(cx) else {
+                return;
+            };
+            workspace.toggle_modal(cx, move |cx| GoToOffset::new(hex_editor, cx));
+        });
+    })
+    .detach();
+}
+
+/// The contents of a file that is open in the hex editor.
+pub struct HexItem {
+    project: WeakModel<Project>,
+    project_path: ProjectPath,
+    abs_path: PathBuf,
+    fs: Arc<dyn Fs>,
+    bytes: Arc<Vec<u8>>,
+    undo_stack: Vec<Overwrite>,
+    redo_stack: Vec<Overwrite>,
+    /// The length of the undo stack when the file was last loaded or saved, or `None`
+    /// if that state can no longer be reached by undoing or redoing.
+    saved_undo_len: Option<usize>,
+}
+
+struct Overwrite {
+    offset: usize,
+    old_bytes: Vec<u8>,
+    new_bytes: Vec<u8>,
+}
+
+pub enum HexItemEvent {
+    Edited,
+    Saved,
+    Reloaded,
+}
+
+impl EventEmitter<HexItemEvent> for HexItem {}
+
+impl project::Item for HexItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<gpui::Result<Model<Self>>>> {
+        let project_handle = project.downgrade();
+        let project = project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        let abs_path = project.absolute_path(path, cx)?;
+        let fs = project.fs().clone();
+        let project_path = path.clone();
+
+        Some(cx.spawn(|mut cx| async move {
+            let bytes = load_bytes(fs.as_ref(), &abs_path).await?;
+            cx.new_model(|_| HexItem {
+                project: project_handle,
+                project_path,
+                abs_path,
+                fs,
+                bytes: Arc::new(bytes),
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+                saved_undo_len: Some(0),
+            })
+        }))
+    }
+
+    fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId> {
+        let project = self.project.upgrade()?;
+        let entry = project.read(cx).entry_for_path(&self.project_path, cx)?;
+        Some(entry.id)
+    }
+
+    fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+}
+
+impl HexItem {
+    pub fn bytes(&self) -> &Arc<Vec<u8>> {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saved_undo_len != Some(self.undo_stack.len())
+    }
+
+    /// Replaces the bytes starting at the given offset. The file's length never
+    /// changes, so bytes that would extend past its end are dropped.
+    pub fn overwrite(&mut self, offset: usize, new_bytes: &[u8], cx: &mut ModelContext<Self>) {
+        let end = (offset + new_bytes.len()).min(self.bytes.len());
+        if offset >= end {
+            return;
+        }
+        let new_bytes = &new_bytes[..end - offset];
+        let old_bytes = self.bytes[offset..end].to_vec();
+        if old_bytes == new_bytes {
+            return;
+        }
+
+        if self
+            .saved_undo_len
+            .is_some_and(|saved_undo_len| saved_undo_len > self.undo_stack.len())
+        {
+            self.saved_undo_len = None;
+        }
+        self.redo_stack.clear();
+        Arc::make_mut(&mut self.bytes)[offset..end].copy_from_slice(new_bytes);
+        self.undo_stack.push(Overwrite {
+            offset,
+            old_bytes,
+            new_bytes: new_bytes.to_vec(),
+        });
+        cx.emit(HexItemEvent::Edited);
+        cx.notify();
+    }
+
+    /// Reverts the last overwrite, returning the offset of the bytes it changed.
+    pub fn undo(&mut self, cx: &mut ModelContext<Self>) -> Option<usize> {
+        let overwrite = self.undo_stack.pop()?;
+        let range = overwrite.offset..overwrite.offset + overwrite.old_bytes.len();
+        Arc::make_mut(&mut self.bytes)[range].copy_from_slice(&overwrite.old_bytes);
+        let offset = overwrite.offset;
+        self.redo_stack.push(overwrite);
+        cx.emit(HexItemEvent::Edited);
+        cx.notify();
+        Some(offset)
+    }
+
+    /// Reapplies the last undone overwrite, returning the offset of the bytes it changed.
+    pub fn redo(&mut self, cx: &mut ModelContext<Self>) -> Option<usize> {
+        let overwrite = self.redo_stack.pop()?;
+        let range = overwrite.offset..overwrite.offset + overwrite.new_bytes.len();
+        Arc::make_mut(&mut self.bytes)[range].copy_from_slice(&overwrite.new_bytes);
+        let offset = overwrite.offset;
+        self.undo_stack.push(overwrite);
+        cx.emit(HexItemEvent::Edited);
+        cx.notify();
+        Some(offset)
+    }
+
+    pub fn save(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let bytes = self.bytes.clone();
+        let undo_len = self.undo_stack.len();
+        cx.spawn(|this, mut cx| async move {
+            write_bytes(fs.as_ref(), &abs_path, &bytes).await?;
+            this.update(&mut cx, |this, cx| {
+                this.saved_undo_len = Some(undo_len);
+                cx.emit(HexItemEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    pub fn save_as(
+        &mut self,
+        project_path: ProjectPath,
+        abs_path: PathBuf,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.project_path = project_path;
+        self.abs_path = abs_path;
+        self.save(cx)
+    }
+
+    /// Replaces the bytes with the file's contents on disk, discarding the undo history.
+    pub fn reload(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(|this, mut cx| async move {
+            let bytes = load_bytes(fs.as_ref(), &abs_path).await?;
+            this.update(&mut cx, |this, cx| {
+                this.bytes = Arc::new(bytes);
+                this.undo_stack.clear();
+                this.redo_stack.clear();
+                this.saved_undo_len = Some(0);
+                cx.emit(HexItemEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+}
+
+async fn load_bytes(fs: &dyn Fs, path: &Path) -> Result<Vec<u8>> {
+    let len = fs.metadata(path).await?.map_or(0, |metadata| metadata.len);
+    if len > MAX_FILE_LEN {
+        return Err(anyhow!(
+            "{path:?} is too large to open in the hex editor ({len} bytes)"
+        ));
+    }
+    fs.load_bytes(path).await
+}
+
+/// Writes the bytes to a temporary file next to the given path, and then moves it into
+/// place, so that the file is never left partially written.
+async fn write_bytes(fs: &dyn Fs, path: &Path, bytes: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid path {path:?}"))?;
+    let mut temp_file_name = OsString::from(".");
+    temp_file_name.push(file_name);
+    temp_file_name.push(".zed-tmp");
+    let temp_path = path.with_file_name(temp_file_name);
+
+    let mut content = futures::io::Cursor::new(bytes);
+    fs.create_file_with(&temp_path, Pin::new(&mut content))
+        .await?;
+    let result = fs
+        .rename(
+            &temp_path,
+            path,
+            RenameOptions {
+                overwrite: true,
+                ignore_if_exists: false,
+            },
+        )
+        .await;
+    if result.is_err() {
+        fs.remove_file(&temp_path, Default::default()).await.ok();
+    }
+    result
+}
+
+/// Which of the hex editor's columns the cursor is typing into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Hex,
+    Ascii,
+}
+
+pub enum Event {
+    Edited,
+    Saved,
+    Reloaded,
+}
+
+/// Shows the bytes of a file as rows of offsets, hex values and ASCII characters,
+/// and lets them be overwritten.
+pub struct HexEditor {
+    item: Model<HexItem>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    cursor: usize,
+    selection_tail: Option<usize>,
+    column: Column,
+    /// Whether the next hex digit that is typed replaces the low nibble of the byte
+    /// under the cursor.
+    pending_low_nibble: bool,
+    search_matches: Vec<Range<usize>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HexEditor {
+    pub fn new(item: Model<HexItem>, cx: &mut ViewContext<Self>) -> Self {
+        let subscriptions = vec![
+            cx.observe(&item, |_, _, cx| cx.notify()),
+            cx.subscribe(&item, |this, _, event, cx| match event {
+                HexItemEvent::Edited => {
+                    cx.emit(SearchEvent::MatchesInvalidated);
+                    cx.emit(Event::Edited);
+                }
+                HexItemEvent::Saved => cx.emit(Event::Saved),
+                HexItemEvent::Reloaded => {
+                    let len = this.item.read(cx).len();
+                    this.cursor = this.cursor.min(len.saturating_sub(1));
+                    this.selection_tail = None;
+                    this.pending_low_nibble = false;
+                    cx.emit(SearchEvent::MatchesInvalidated);
+                    cx.emit(Event::Reloaded);
+                }
+            }),
+        ];
+
+        Self {
+            item,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            selection_tail: None,
+            column: Column::Hex,
+            pending_low_nibble: false,
+            search_matches: Vec::new(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn item(&self) -> &Model<HexItem> {
+        &self.item
+    }
+
+    /// The offset of the byte under the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn column(&self) -> Column {
+        self.column
+    }
+
+    /// The range of selected bytes, which always includes the byte under the cursor.
+    pub fn selection(&self, cx: &AppContext) -> Range<usize> {
+        let len = self.item.read(cx).len();
+        let (start, end) = match self.selection_tail {
+            Some(tail) => (tail.min(self.cursor), tail.max(self.cursor) + 1),
+            None => (self.cursor, self.cursor + 1),
+        };
+        start.min(len)..end.min(len)
+    }
+
+    pub fn select_range(&mut self, range: Range<usize>, cx: &mut ViewContext<Self>) {
+        if range.is_empty() {
+            self.move_cursor(range.start, false, cx);
+        } else {
+            self.move_cursor(range.start, false, cx);
+            self.move_cursor(range.end - 1, true, cx);
+        }
+    }
+
+    /// Moves the cursor to the given offset, scrolling it into view.
+    pub fn go_to_offset(&mut self, offset: usize, cx: &mut ViewContext<Self>) {
+        self.move_cursor(offset, false, cx);
+    }
+
+    fn move_cursor(&mut self, offset: usize, select: bool, cx: &mut ViewContext<Self>) {
+        let len = self.item.read(cx).len();
+        if select {
+            self.selection_tail.get_or_insert(self.cursor);
+        } else {
+            self.selection_tail = None;
+        }
+        self.cursor = offset.min(len.saturating_sub(1));
+        self.pending_low_nibble = false;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW);
+        cx.emit(SearchEvent::ActiveMatchChanged);
+        cx.notify();
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor.saturating_sub(1), false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor + 1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor.saturating_sub(BYTES_PER_ROW), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor + BYTES_PER_ROW, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor.saturating_sub(1), true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor + 1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor.saturating_sub(BYTES_PER_ROW), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor + BYTES_PER_ROW, true, cx);
+    }
+
+    fn move_to_beginning_of_row(&mut self, _: &MoveToBeginningOfRow, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor - self.cursor % BYTES_PER_ROW, false, cx);
+    }
+
+    fn move_to_end_of_row(&mut self, _: &MoveToEndOfRow, cx: &mut ViewContext<Self>) {
+        let row_start = self.cursor - self.cursor % BYTES_PER_ROW;
+        self.move_cursor(row_start + BYTES_PER_ROW - 1, false, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, cx: &mut ViewContext<Self>) {
+        self.move_cursor(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, cx: &mut ViewContext<Self>) {
+        self.move_cursor(usize::MAX, false, cx);
+    }
+
+    fn page_up(&mut self, _: &PageUp, cx: &mut ViewContext<Self>) {
+        let offset = self.cursor.saturating_sub(ROWS_PER_PAGE * BYTES_PER_ROW);
+        self.move_cursor(offset, false, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, cx: &mut ViewContext<Self>) {
+        self.move_cursor(self.cursor + ROWS_PER_PAGE * BYTES_PER_ROW, false, cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, cx: &mut ViewContext<Self>) {
+        self.move_cursor(0, false, cx);
+        self.move_cursor(usize::MAX, true, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, cx: &mut ViewContext<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.pending_low_nibble = false;
+        cx.notify();
+    }
+
+    fn copy(&mut self, _: &Copy, cx: &mut ViewContext<Self>) {
+        let selection = self.selection(cx);
+        let bytes = &self.item.read(cx).bytes()[selection];
+        let text = match self.column {
+            Column::Hex => format_hex(bytes),
+            Column::Ascii => bytes.iter().map(|byte| ascii_char(*byte)).collect(),
+        };
+        cx.write_to_clipboard(ClipboardItem::new(text));
+    }
+
+    fn undo(&mut self, _: &Undo, cx: &mut ViewContext<Self>) {
+        if let Some(offset) = self.item.update(cx, |item, cx| item.undo(cx)) {
+            self.move_cursor(offset, false, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, cx: &mut ViewContext<Self>) {
+        if let Some(offset) = self.item.update(cx, |item, cx| item.redo(cx)) {
+            self.move_cursor(offset, false, cx);
+        }
+    }
+
+    /// Overwrites the byte under the cursor with a typed character, returning whether
+    /// the character could be written in the cursor's column.
+    pub fn input(&mut self, character: char, cx: &mut ViewContext<Self>) -> bool {
+        let offset = self.cursor;
+        let Some(&byte) = self.item.read(cx).bytes().get(offset) else {
+            return false;
+        };
+
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = character.to_digit(16) else {
+                    return false;
+                };
+                let digit = digit as u8;
+                let new_byte = if self.pending_low_nibble {
+                    (byte & 0xF0) | digit
+                } else {
+                    (digit << 4) | (byte & 0x0F)
+                };
+                self.item
+                    .update(cx, |item, cx| item.overwrite(offset, &[new_byte], cx));
+                if self.pending_low_nibble {
+                    self.move_cursor(offset + 1, false, cx);
+                } else {
+                    self.selection_tail = None;
+                    self.pending_low_nibble = true;
+                    cx.notify();
+                }
+            }
+            Column::Ascii => {
+                if !character.is_ascii() || character.is_ascii_control() {
+                    return false;
+                }
+                self.item.update(cx, |item, cx| {
+                    item.overwrite(offset, &[character as u8], cx)
+                });
+                self.move_cursor(offset + 1, false, cx);
+            }
+        }
+        true
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(input) = keystroke.ime_key.as_deref() else {
+            return;
+        };
+        let mut characters = input.chars();
+        if let (Some(character), None) = (characters.next(), characters.next()) {
+            if self.input(character, cx) {
+                cx.stop_propagation();
+            }
+        }
+    }
+
+    fn mouse_down_on_byte(
+        &mut self,
+        offset: usize,
+        column: Column,
+        event: &MouseDownEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.column = column;
+        self.move_cursor(offset, event.modifiers.shift, cx);
+        cx.focus(&self.focus_handle);
+    }
+
+    fn is_search_match(&self, offset: usize) -> bool {
+        self.search_matches
+            .binary_search_by(|range| {
+                if range.end <= offset {
+                    std::cmp::Ordering::Less
+                } else if range.start > offset {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    fn render_row(&self, row: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let start = row * BYTES_PER_ROW;
+        let bytes = self.item.read(cx).bytes().clone();
+        let selection = self.selection(cx);
+
+        h_flex()
+            .gap_4()
+            .px_2()
+            .child(
+                div()
+                    .text_color(cx.theme().colors().editor_line_number)
+                    .child(format!("{:08X}", start)),
+            )
+            .child(
+                h_flex().children((start..start + BYTES_PER_ROW).map(|offset| {
+                    self.render_byte(
+                        offset,
+                        bytes.get(offset).copied(),
+                        Column::Hex,
+                        &selection,
+                        cx,
+                    )
+                })),
+            )
+            .child(
+                h_flex().children((start..start + BYTES_PER_ROW).map(|offset| {
+                    self.render_byte(
+                        offset,
+                        bytes.get(offset).copied(),
+                        Column::Ascii,
+                        &selection,
+                        cx,
+                    )
+                })),
+            )
+    }
+
+    fn render_byte(
+        &self,
+        offset: usize,
+        byte: Option<u8>,
+        column: Column,
+        selection: &Range<usize>,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let colors = cx.theme().colors();
+        let player = cx.theme().players().local();
+        let Some(byte) = byte else {
+            let placeholder = match column {
+                Column::Hex => "   ",
+                Column::Ascii => " ",
+            };
+            return div().child(placeholder).into_any_element();
+        };
+
+        let (text, is_muted) = match column {
+            Column::Hex => (format!("{:02X} ", byte), byte == 0),
+            Column::Ascii => {
+                let character = ascii_char(byte);
+                (character.to_string(), character == '.' && byte != b'.')
+            }
+        };
+        let background = if offset == self.cursor && column == self.column {
+            Some(Hsla {
+                a: 0.5,
+                ..player.cursor
+            })
+        } else if selection.contains(&offset) {
+            Some(player.selection)
+        } else if self.is_search_match(offset) {
+            Some(colors.search_match_background)
+        } else {
+            None
+        };
+
+        div()
+            .when(
+                column == Column::Hex && offset % BYTES_PER_ROW == 7,
+                |this| this.mr_2(),
+            )
+            .when_some(background, |this, background| this.bg(background))
+            .when(is_muted, |this| this.text_color(colors.text_muted))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event, cx| {
+                    this.mouse_down_on_byte(offset, column, event, cx)
+                }),
+            )
+            .child(text)
+            .into_any_element()
+    }
+}
+
+/// Parses a search query into the bytes it should match. Queries made of pairs of
+/// hex digits, optionally separated by whitespace like `DE AD BE EF`, match those
+/// bytes. Other queries match their UTF-8 text, and wrapping a query in double
+/// quotes forces it to be matched as text.
+pub fn parse_byte_pattern(query: &str) -> Vec<u8> {
+    if let Some(text) = query
+        .strip_prefix('"')
+        .and_then(|query| query.strip_suffix('"'))
+    {
+        return text.as_bytes().to_vec();
+    }
+
+    let digits = query
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect::<Vec<_>>();
+    let is_hex = !digits.is_empty()
+        && digits.len() % 2 == 0
+        && digits.iter().all(|character| character.is_ascii_hexdigit());
+    if is_hex {
+        digits
+            .chunks(2)
+            .map(|pair| {
+                let high = pair[0].to_digit(16).unwrap_or(0) as u8;
+                let low = pair[1].to_digit(16).unwrap_or(0) as u8;
+                (high << 4) | low
+            })
+            .collect()
+    } else {
+        query.as_bytes().to_vec()
+    }
+}
+
+fn find_byte_pattern(bytes: &[u8], pattern: &[u8]) -> Vec<Range<usize>> {
+    let mut matches = Vec::new();
+    if pattern.is_empty() {
+        return matches;
+    }
+    let mut offset = 0;
+    while let Some(ix) = bytes[offset..]
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+    {
+        let start = offset + ix;
+        offset = start + pattern.len();
+        matches.push(start..offset);
+    }
+    matches
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn ascii_char(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+impl EventEmitter<Event> for HexEditor {}
+impl EventEmitter<SearchEvent> for HexEditor {}
+
+impl FocusableView for HexEditor {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HexEditor {
+    type Event = Event;
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let item = self.item.read(cx);
+        let title = item
+            .project_path
+            .path
+            .file_name()
+            .unwrap_or_else(|| item.abs_path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        Some(
+            self.item
+                .read(cx)
+                .abs_path
+                .to_string_lossy()
+                .to_string()
+                .into(),
+        )
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            Event::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            Event::Saved | Event::Reloaded => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(EntityId, &dyn project::Item),
+    ) {
+        f(self.item.entity_id(), self.item.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &AppContext) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| Self::new(self.item.clone(), cx)))
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.item.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _cx: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        _project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.item.update(cx, |item, cx| item.save(cx))
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(abs_path) = project.read(cx).absolute_path(&path, cx) else {
+            return Task::ready(Err(anyhow!("failed to find the absolute path")));
+        };
+        self.item
+            .update(cx, |item, cx| item.save_as(path, abs_path, cx))
+    }
+
+    fn reload(&mut self, _project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.item.update(cx, |item, cx| item.reload(cx))
+    }
+
+    fn as_searchable(&self, handle: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexItem;
+
+    fn for_project_item(
+        _project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, cx)
+    }
+}
+
+impl SearchableItem for HexEditor {
+    type Match = Range<usize>;
+
+    fn supported_options() -> SearchOptions {
+        SearchOptions {
+            case: false,
+            word: false,
+            regex: false,
+            replacement: false,
+            selection: false,
+        }
+    }
+
+    fn clear_matches(&mut self, cx: &mut ViewContext<Self>) {
+        self.search_matches.clear();
+        cx.notify();
+    }
+
+    fn update_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>) {
+        self.search_matches = matches.to_vec();
+        cx.notify();
+    }
+
+    fn query_suggestion(&mut self, cx: &mut ViewContext<Self>) -> String {
+        if self.selection_tail.is_none() {
+            return String::new();
+        }
+        let selection = self.selection(cx);
+        format_hex(&self.item.read(cx).bytes()[selection])
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(range) = matches.get(index) {
+            self.select_range(range.clone(), cx);
+        }
+    }
+
+    fn select_matches(&mut self, matches: &[Self::Match], cx: &mut ViewContext<Self>) {
+        // The hex editor only has a single selection, so select the first match.
+        if let Some(range) = matches.first() {
+            self.select_range(range.clone(), cx);
+        }
+    }
+
+    fn replace(&mut self, _: &Self::Match, _: &SearchQuery, _: &mut ViewContext<Self>) {}
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        let pattern = parse_byte_pattern(query.as_str());
+        let bytes = self.item.read(cx).bytes().clone();
+        cx.background_executor()
+            .spawn(async move { find_byte_pattern(&bytes, &pattern) })
+    }
+
+    fn active_match_index(
+        &mut self,
+        matches: &[Self::Match],
+        cx: &mut ViewContext<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        let start = self.selection(cx).start;
+        Some(
+            matches
+                .iter()
+                .position(|range| range.end > start)
+                .unwrap_or(0),
+        )
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let font = settings.buffer_font.clone();
+        let font_size = settings.buffer_font_size(cx);
+        let row_count = self.item.read(cx).len().div_ceil(BYTES_PER_ROW);
+
+        div()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::move_to_beginning_of_row))
+            .on_action(cx.listener(Self::move_to_end_of_row))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_key_down(cx.listener(Self::key_down))
+            .size_full()
+            .py_1()
+            .bg(cx.theme().colors().editor_background)
+            .text_color(cx.theme().colors().editor_foreground)
+            .font(font)
+            .text_size(font_size)
+            .map(|this| {
+                if row_count == 0 {
+                    this.child(
+                        div()
+                            .px_2()
+                            .text_color(cx.theme().colors().text_muted)
+                            .child("This file is empty."),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "hex-rows",
+                            row_count,
+                            |this, range, cx| range.map(|row| this.render_row(row, cx)).collect(),
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_opening_binary_files(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "a.txt": "some text" }))
+            .await;
+        fs.insert_file("/dir/b.bin", vec![0x7F, b'E', b'L', b'F', 0, 1, 2, 3])
+            .await;
+
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+
+        // Text files are still opened in an editor.
+        let item = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+            })
+            .await
+            .unwrap();
+        assert!(item.downcast::<editor::Editor>().is_some());
+
+        let hex_editor = open_hex_editor(&workspace, worktree_id, "b.bin", cx).await;
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(
+                hex_editor.item().read(cx).bytes().as_slice(),
+                &[0x7F, b'E', b'L', b'F', 0, 1, 2, 3]
+            );
+        });
+
+        // Opening the file again activates the existing hex editor.
+        let reopened = open_hex_editor(&workspace, worktree_id, "b.bin", cx).await;
+        assert_eq!(reopened.entity_id(), hex_editor.entity_id());
+    }
+
+    #[gpui::test]
+    async fn test_overwriting_and_saving(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({})).await;
+        fs.insert_file("/dir/b.bin", vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9])
+            .await;
+
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let hex_editor = open_hex_editor(&workspace, worktree_id, "b.bin", cx).await;
+
+        // Typing in the hex column replaces one nibble at a time.
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.go_to_offset(2, cx);
+            assert!(hex_editor.input('a', cx));
+            assert_eq!(hex_editor.cursor(), 2);
+            assert!(hex_editor.input('B', cx));
+            assert_eq!(hex_editor.cursor(), 3);
+            assert!(!hex_editor.input('g', cx));
+        });
+        // Typing in the ASCII column replaces whole bytes.
+        cx.dispatch_action(ToggleColumn);
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.column(), Column::Ascii);
+            assert!(hex_editor.input('Z', cx));
+            assert!(!hex_editor.input('é', cx));
+            assert_eq!(hex_editor.cursor(), 4);
+            assert!(hex_editor.item().read(cx).is_dirty());
+            assert_eq!(
+                hex_editor.item().read(cx).bytes().as_slice(),
+                &[0, 1, 0xAB, b'Z', 4, 5, 6, 7, 8, 9]
+            );
+        });
+
+        // Undoing every edit makes the file clean again.
+        cx.dispatch_action(Undo);
+        cx.dispatch_action(Undo);
+        cx.dispatch_action(Undo);
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(
+                hex_editor.item().read(cx).bytes().as_slice(),
+                &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+            );
+            assert!(!hex_editor.item().read(cx).is_dirty());
+        });
+        cx.dispatch_action(Redo);
+
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.save_active_item(workspace::SaveIntent::Save, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.load_bytes(Path::new("/dir/b.bin")).await.unwrap(),
+            vec![0, 1, 0xA2, 3, 4, 5, 6, 7, 8, 9]
+        );
+        // The file is written to a temporary file first, which is moved into place.
+        assert!(fs
+            .metadata(Path::new("/dir/.b.bin.zed-tmp"))
+            .await
+            .unwrap()
+            .is_none());
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert!(!hex_editor.item().read(cx).is_dirty())
+        });
+    }
+
+    #[gpui::test]
+    async fn test_byte_search(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({})).await;
+        fs.insert_file(
+            "/dir/b.bin",
+            b"\xDE\xAD\xBE\xEF\x00dead\x00\xDE\xAD\xBE\xEF".to_vec(),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees().next().unwrap().read(cx).id()
+        });
+        let hex_editor = open_hex_editor(&workspace, worktree_id, "b.bin", cx).await;
+
+        let find = |query: &str, cx: &mut VisualTestContext| {
+            let query = Arc::new(
+                SearchQuery::text(
+                    query,
+                    false,
+                    false,
+                    false,
+                    Default::default(),
+                    Default::default(),
+                )
+                .unwrap(),
+            );
+            hex_editor.update(cx, |hex_editor, cx| hex_editor.find_matches(query, cx))
+        };
+        assert_eq!(find("de ad be ef", cx).await, vec![0..4, 10..14]);
+        assert_eq!(find("DEADBEEF", cx).await, vec![0..4, 10..14]);
+        assert_eq!(find("dead", cx).await, vec![0..2, 10..12]);
+        assert_eq!(find("\"dead\"", cx).await, vec![5..9]);
+        assert_eq!(find("ead", cx).await, vec![6..9]);
+
+        let matches = find("DEADBEEF", cx).await;
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.go_to_offset(5, cx);
+            assert_eq!(hex_editor.active_match_index(&matches, cx), Some(1));
+            hex_editor.activate_match(1, &matches, cx);
+            assert_eq!(hex_editor.selection(cx), 10..14);
+            assert_eq!(hex_editor.query_suggestion(cx), "DE AD BE EF");
+        });
+    }
+
+    async fn open_hex_editor(
+        workspace: &View<Workspace>,
+        worktree_id: project::WorktreeId,
+        path: &str,
+        cx: &mut VisualTestContext,
+    ) -> View<HexEditor> {
+        let path = Arc::from(Path::new(path));
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, path), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<HexEditor>()
+            .unwrap()
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
@@ -114,6 +114,20 @@ impl Item for ImageView {
         Some(IMAGE_VIEWER_KIND)
     }
 
+    fn serialize_to_workspace(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let item_id = cx.entity_id().as_u64();
+        let image_path = self.path.clone();
+        cx.background_executor().spawn(async move {
+            IMAGE_VIEWER
+                .save_image_path(item_id, workspace_id, image_path)
+                .await
+        })
+    }
+
     fn deserialize(
         _project: Model<Project>,
         _workspace: WeakView<Workspace>,
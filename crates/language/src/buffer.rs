@@ -6,7 +6,7 @@ pub use crate::{
 };
 use crate::{
     diagnostic_set::{DiagnosticEntry, DiagnosticGroup},
-    language_settings::{language_settings, IndentGuideSettings, LanguageSettings},
+    language_settings::{language_settings, IndentGuideSettings, LanguageSettingsRef},
     markdown::parse_markdown,
     outline::OutlineItem,
     syntax_map::{
@@ -26,6 +26,7 @@ use gpui::{
 use lazy_static::lazy_static;
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
+use postage::{stream::Stream as _, watch};
 use similar::{ChangeTag, TextDiff};
 use smallvec::SmallVec;
 use smol::future::yield_now;
@@ -51,9 +52,9 @@ use text::operation_queue::OperationQueue;
 use text::*;
 pub use text::{
     Anchor, Bias, Buffer as TextBuffer, BufferId, BufferSnapshot as TextBufferSnapshot, Edit,
-    OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection, SelectionGoal,
-    Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint, ToPointUtf16,
-    Transaction, TransactionId, Unclipped,
+    Encoding, OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection,
+    SelectionGoal, Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint,
+    ToPointUtf16, Transaction, TransactionId, Unclipped,
 };
 use theme::SyntaxTheme;
 #[cfg(any(test, feature = "test-support"))]
@@ -82,6 +83,13 @@ pub enum Capability {
 
 pub type BufferRow = u32;
 
+/// Whether a [Buffer] is being parsed in the background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseStatus {
+    Idle,
+    Parsing,
+}
+
 /// An in-memory representation of a source code file, including its text,
 /// syntax trees, git status, and diagnostics.
 pub struct Buffer {
@@ -95,6 +103,11 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The encoding of the file when this buffer was last loaded from
+    /// or saved to disk, which is used the next time it is saved.
+    encoding: Encoding,
+    /// Whether the buffer's file is too large to be parsed or diffed.
+    large_file: bool,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
     reload_task: Option<Task<Result<()>>>,
@@ -104,6 +117,7 @@ pub struct Buffer {
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
     parsing_in_background: bool,
+    parse_status: (watch::Sender<ParseStatus>, watch::Receiver<ParseStatus>),
     non_text_state_update_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
@@ -375,8 +389,8 @@ pub trait LocalFile: File {
     /// Returns the absolute path of this file.
     fn abs_path(&self, cx: &AppContext) -> PathBuf;
 
-    /// Loads the file's contents from disk.
-    fn load(&self, cx: &AppContext) -> Task<Result<String>>;
+    /// Loads the file's contents from disk, decoding them with the given encoding.
+    fn load(&self, encoding: Encoding, cx: &AppContext) -> Task<Result<String>>;
 
     /// Returns true if the file should not be shared with collaborators.
     fn is_private(&self, _: &AppContext) -> bool {
@@ -600,6 +614,7 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        this.encoding = proto::deserialize_encoding(message.encoding).unwrap_or_default();
         Ok(this)
     }
 
@@ -613,6 +628,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: Some(proto::serialize_encoding(self.encoding)),
         }
     }
 
@@ -669,6 +685,47 @@ impl Buffer {
         self
     }
 
+    /// Assign the encoding of the buffer's file, returning the buffer.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Put the buffer in large-file mode, returning the buffer. Buffers in this mode
+    /// are never parsed or diffed against their Git index text.
+    pub fn with_large_file_mode(mut self) -> Self {
+        self.large_file = true;
+        self
+    }
+
+    /// Whether this buffer is in large-file mode, see [Buffer::with_large_file_mode].
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Appends text read from the buffer's file to the end of the buffer. Unlike an
+    /// edit, this can't be undone, and doesn't make a clean buffer dirty.
+    pub fn append_loaded_text(&mut self, text: String, cx: &mut ModelContext<Self>) {
+        if text.is_empty() {
+            return;
+        }
+
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+        let end = self.len();
+        self.text.start_transaction();
+        let operation = self.text.edit([(end..end, text)]);
+        if let Some((transaction_id, _)) = self.text.end_transaction() {
+            self.text.forget_transaction(transaction_id);
+        }
+        if !was_dirty {
+            self.saved_version = self.version();
+        }
+
+        self.send_operation(Operation::Buffer(operation), cx);
+        self.did_edit(&old_version, was_dirty, cx);
+    }
+
     /// Returns the [Capability] of this buffer.
     pub fn capability(&self) -> Capability {
         self.capability
@@ -691,6 +748,8 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
+            large_file: false,
             reload_task: None,
             transaction_depth: 0,
             was_dirty_before_starting_transaction: None,
@@ -708,6 +767,7 @@ impl Buffer {
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
             parsing_in_background: false,
+            parse_status: watch::channel_with(ParseStatus::Idle),
             non_text_state_update_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
             autoindent_requests: Default::default(),
@@ -769,6 +829,19 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The encoding used to read the buffer's file, and to write it when saving.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Changes the encoding that will be used the next time the buffer is saved.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut ModelContext<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut ModelContext<Self>) {
         self.non_text_state_update_count += 1;
@@ -816,13 +889,22 @@ impl Buffer {
     pub fn reload(
         &mut self,
         cx: &mut ModelContext<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
+        self.reload_with_encoding(self.encoding, cx)
+    }
+
+    /// Reloads the contents of the buffer from disk, decoding them with the given encoding.
+    pub fn reload_with_encoding(
+        &mut self,
+        encoding: Encoding,
+        cx: &mut ModelContext<Self>,
     ) -> oneshot::Receiver<Option<Transaction>> {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
             let Some((new_mtime, new_text)) = this.update(&mut cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
-                Some((file.mtime(), file.load(cx)))
+                Some((file.mtime(), file.load(encoding, cx)))
             })?
             else {
                 return Ok(());
@@ -838,7 +920,7 @@ impl Buffer {
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
-                    this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
+                    this.did_reload(this.version(), this.line_ending(), encoding, new_mtime, cx);
                 } else {
                     if !diff.edits.is_empty()
                         || this
@@ -849,7 +931,13 @@ impl Buffer {
                         this.has_conflict = true;
                     }
 
-                    this.did_reload(prev_version, this.line_ending(), this.saved_mtime, cx);
+                    this.did_reload(
+                        prev_version,
+                        this.line_ending(),
+                        this.encoding,
+                        this.saved_mtime,
+                        cx,
+                    );
                 }
 
                 this.reload_task.take();
@@ -863,6 +951,7 @@ impl Buffer {
         &mut self,
         version: clock::Global,
         line_ending: LineEnding,
+        encoding: Encoding,
         mtime: Option<SystemTime>,
         cx: &mut ModelContext<Self>,
     ) {
@@ -870,6 +959,7 @@ impl Buffer {
         self.has_unsaved_edits
             .set((self.saved_version.clone(), false));
         self.text.set_line_ending(line_ending);
+        self.encoding = encoding;
         self.saved_mtime = mtime;
         cx.emit(Event::Reloaded);
         cx.notify();
@@ -922,6 +1012,9 @@ impl Buffer {
     /// Sets the text that will be used to compute a Git diff
     /// against the buffer text.
     pub fn set_diff_base(&mut self, diff_base: Option<String>, cx: &mut ModelContext<Self>) {
+        if self.large_file {
+            return;
+        }
         self.diff_base = diff_base
             .map(|mut raw_diff_base| {
                 LineEnding::normalize(&mut raw_diff_base);
@@ -1032,7 +1125,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if self.parsing_in_background || self.large_file {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -1068,7 +1161,7 @@ impl Buffer {
                 return;
             }
             Err(parse_task) => {
-                self.parsing_in_background = true;
+                self.set_parsing_in_background(true);
                 cx.spawn(move |this, mut cx| async move {
                     let new_syntax_map = parse_task.await;
                     this.update(&mut cx, move |this, cx| {
@@ -1085,7 +1178,7 @@ impl Buffer {
                             || grammar_changed
                             || this.version.changed_since(&parsed_version);
                         this.did_finish_parsing(new_syntax_map, cx);
-                        this.parsing_in_background = false;
+                        this.set_parsing_in_background(false);
                         if parse_again {
                             this.reparse(cx);
                         }
@@ -1097,6 +1190,28 @@ impl Buffer {
         }
     }
 
+    fn set_parsing_in_background(&mut self, parsing_in_background: bool) {
+        self.parsing_in_background = parsing_in_background;
+        *self.parse_status.0.borrow_mut() = if parsing_in_background {
+            ParseStatus::Parsing
+        } else {
+            ParseStatus::Idle
+        };
+    }
+
+    /// Returns a future that resolves once the buffer is no longer being parsed in the
+    /// background, so that its syntax tree is up to date.
+    pub fn parsing_idle(&self) -> impl Future<Output = ()> {
+        let mut parse_status = self.parse_status.1.clone();
+        async move {
+            while let Some(status) = parse_status.recv().await {
+                if status == ParseStatus::Idle {
+                    break;
+                }
+            }
+        }
+    }
+
     fn did_finish_parsing(&mut self, syntax_snapshot: SyntaxSnapshot, cx: &mut ModelContext<Self>) {
         self.non_text_state_update_count += 1;
         self.syntax_map.lock().did_parse(syntax_snapshot);
@@ -2544,7 +2659,7 @@ impl BufferSnapshot {
         &self,
         position: D,
         cx: &'a AppContext,
-    ) -> &'a LanguageSettings {
+    ) -> LanguageSettingsRef<'a> {
         language_settings(self.language_at(position), self.file.as_ref(), cx)
     }
 
@@ -4,15 +4,23 @@ use crate::{File, Language, LanguageServerName};
 use anyhow::Result;
 use collections::{HashMap, HashSet};
 use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
-use gpui::AppContext;
+use gpui::{AppContext, Global};
 use itertools::{Either, Itertools};
 use schemars::{
     schema::{InstanceType, ObjectValidation, Schema, SchemaObject},
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
-use settings::{Settings, SettingsLocation, SettingsSources};
-use std::{num::NonZeroU32, path::Path, sync::Arc};
+use settings::{
+    EditorconfigIndentStyle, Settings, SettingsLocation, SettingsSources, SettingsStore,
+};
+use std::{
+    cell::{Cell, RefCell},
+    num::NonZeroU32,
+    ops::Deref,
+    path::Path,
+    sync::Arc,
+};
 use util::serde::default_true;
 
 impl<'a> Into<SettingsLocation<'a>> for &'a dyn File {
@@ -27,16 +35,145 @@ impl<'a> Into<SettingsLocation<'a>> for &'a dyn File {
 /// Initializes the language settings.
 pub fn init(cx: &mut AppContext) {
     AllLanguageSettings::register(cx);
+    cx.set_global(EditorconfigSettingsCache::default());
+}
+
+/// Language settings that have been merged with properties from `.editorconfig` files,
+/// keyed by the worktree, the path of the file and the name of its language.
+///
+/// Entries are `None` for files that aren't affected by any `.editorconfig` file.
+#[derive(Default)]
+struct EditorconfigSettingsCache {
+    revision: Cell<usize>,
+    settings: RefCell<HashMap<(usize, Arc<Path>, Option<Arc<str>>), Option<Arc<LanguageSettings>>>>,
+}
+
+impl Global for EditorconfigSettingsCache {}
+
+/// The settings returned by [`language_settings`].
+///
+/// Settings that aren't affected by `.editorconfig` files are borrowed from the settings
+/// store, and merged settings are shared with the cache, so neither is copied.
+#[derive(Debug, Clone)]
+pub enum LanguageSettingsRef<'a> {
+    /// Settings borrowed from [`AllLanguageSettings`].
+    Borrowed(&'a LanguageSettings),
+    /// Settings merged with the properties of `.editorconfig` files.
+    Merged(Arc<LanguageSettings>),
+}
+
+impl LanguageSettingsRef<'_> {
+    /// Returns an owned copy of the settings.
+    pub fn into_owned(self) -> LanguageSettings {
+        match self {
+            LanguageSettingsRef::Borrowed(settings) => settings.clone(),
+            LanguageSettingsRef::Merged(settings) => {
+                Arc::try_unwrap(settings).unwrap_or_else(|settings| (*settings).clone())
+            }
+        }
+    }
+}
+
+impl Deref for LanguageSettingsRef<'_> {
+    type Target = LanguageSettings;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            LanguageSettingsRef::Borrowed(settings) => settings,
+            LanguageSettingsRef::Merged(settings) => settings,
+        }
+    }
 }
 
 /// Returns the settings for the specified language from the provided file.
+///
+/// Properties from `.editorconfig` files override the user's settings, but are overridden
+/// by the project's `.zed/settings.json` files.
 pub fn language_settings<'a>(
     language: Option<&Arc<Language>>,
     file: Option<&Arc<dyn File>>,
     cx: &'a AppContext,
-) -> &'a LanguageSettings {
+) -> LanguageSettingsRef<'a> {
     let language_name = language.map(|l| l.name());
-    all_language_settings(file, cx).language(language_name.as_deref())
+    let settings = all_language_settings(file, cx).language(language_name.as_deref());
+    let Some(file) = file else {
+        return LanguageSettingsRef::Borrowed(settings);
+    };
+    let Some(cache) = cx.try_global::<EditorconfigSettingsCache>() else {
+        return match editorconfig_language_settings(language_name, file.as_ref(), cx) {
+            Some(settings) => LanguageSettingsRef::Merged(Arc::new(settings)),
+            None => LanguageSettingsRef::Borrowed(settings),
+        };
+    };
+
+    let revision = cx.global::<SettingsStore>().revision();
+    if cache.revision.replace(revision) != revision {
+        cache.settings.borrow_mut().clear();
+    }
+    let key = (
+        file.worktree_id(),
+        file.path().clone(),
+        language_name.clone(),
+    );
+    if let Some(cached) = cache.settings.borrow().get(&key) {
+        return match cached {
+            Some(settings) => LanguageSettingsRef::Merged(settings.clone()),
+            None => LanguageSettingsRef::Borrowed(settings),
+        };
+    }
+
+    let merged = editorconfig_language_settings(language_name, file.as_ref(), cx).map(Arc::new);
+    cache.settings.borrow_mut().insert(key, merged.clone());
+    match merged {
+        Some(settings) => LanguageSettingsRef::Merged(settings),
+        None => LanguageSettingsRef::Borrowed(settings),
+    }
+}
+
+/// Merges the user's settings for the given language with the properties of the
+/// `.editorconfig` files and the local settings that apply to the given file.
+///
+/// Returns `None` if no `.editorconfig` file applies to the file.
+fn editorconfig_language_settings(
+    language_name: Option<Arc<str>>,
+    file: &dyn File,
+    cx: &AppContext,
+) -> Option<LanguageSettings> {
+    let store = cx.global::<SettingsStore>();
+    let properties = store.editorconfig_properties(file.worktree_id(), file.path())?;
+
+    let mut settings = AllLanguageSettings::get_global(cx)
+        .language(language_name.as_deref())
+        .clone();
+    if let Some(tab_size) = properties.tab_size().and_then(NonZeroU32::new) {
+        settings.tab_size = tab_size;
+    }
+    if let Some(indent_style) = properties.indent_style {
+        settings.hard_tabs = indent_style == EditorconfigIndentStyle::Tab;
+    }
+    if let Some(max_line_length) = properties.max_line_length {
+        settings.preferred_line_length = max_line_length;
+    }
+    if let Some(trim_trailing_whitespace) = properties.trim_trailing_whitespace {
+        settings.remove_trailing_whitespace_on_save = trim_trailing_whitespace;
+    }
+    if let Some(insert_final_newline) = properties.insert_final_newline {
+        settings.ensure_final_newline_on_save = insert_final_newline;
+    }
+
+    // Local settings are applied in the same order as in `AllLanguageSettings::load`.
+    for local_settings in store
+        .local_settings_contents_for_path::<AllLanguageSettings>(file.worktree_id(), file.path())
+    {
+        merge_settings(&mut settings, &local_settings.defaults);
+        if let Some(language_settings) = language_name
+            .as_ref()
+            .and_then(|name| local_settings.languages.get(name))
+        {
+            merge_settings(&mut settings, language_settings);
+        }
+    }
+    Some(settings)
 }
 
 /// Returns the settings for all languages from the provided file.
@@ -80,6 +217,8 @@ pub struct LanguageSettings {
     pub wrap_guides: Vec<usize>,
     /// Indent guide related settings.
     pub indent_guides: IndentGuideSettings,
+    /// Rainbow bracket related settings.
+    pub rainbow_brackets: RainbowBracketSettings,
     /// Whether or not to perform a buffer format before saving.
     pub format_on_save: FormatOnSave,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
@@ -253,6 +392,9 @@ pub struct LanguageSettingsContent {
     /// Indent guide related settings.
     #[serde(default)]
     pub indent_guides: Option<IndentGuideSettings>,
+    /// Rainbow bracket related settings.
+    #[serde(default)]
+    pub rainbow_brackets: Option<RainbowBracketSettings>,
     /// Whether or not to perform a buffer format before saving.
     ///
     /// Default: on
@@ -473,6 +615,21 @@ pub struct IndentGuideSettings {
     pub background_coloring: IndentGuideBackgroundColoring,
 }
 
+/// The settings for rainbow brackets.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RainbowBracketSettings {
+    /// Whether to color brackets by their nesting depth.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+    /// Whether to draw a guide between the brackets of a pair spanning multiple lines.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub guides: bool,
+}
+
 fn line_width() -> u32 {
     1
 }
@@ -831,6 +988,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
     merge(&mut settings.show_wrap_guides, src.show_wrap_guides);
     merge(&mut settings.wrap_guides, src.wrap_guides.clone());
     merge(&mut settings.indent_guides, src.indent_guides);
+    merge(&mut settings.rainbow_brackets, src.rainbow_brackets);
     merge(
         &mut settings.code_actions_on_format,
         src.code_actions_on_format.clone(),
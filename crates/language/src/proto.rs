@@ -26,6 +26,21 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Deserializes a [`text::Encoding`] from the RPC representation. Returns `None` if the
+/// message doesn't contain an encoding, or one that isn't known, in which case the buffer's
+/// current encoding should be kept.
+pub fn deserialize_encoding(message: Option<proto::Encoding>) -> Option<text::Encoding> {
+    message.and_then(|message| text::Encoding::from_name(&message.name, message.bom))
+}
+
+/// Serializes a [`text::Encoding`] to be sent over RPC.
+pub fn serialize_encoding(encoding: text::Encoding) -> proto::Encoding {
+    proto::Encoding {
+        name: encoding.name().to_string(),
+        bom: encoding.has_bom(),
+    }
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
@@ -489,6 +504,67 @@ pub fn deserialize_transaction(transaction: proto::Transaction) -> Result<Transa
     })
 }
 
+/// Serializes a [`PersistedHistory`] so that it can be stored across sessions.
+pub fn serialize_history(history: &PersistedHistory) -> proto::BufferHistory {
+    let serialize_entries = |entries: &[PersistedHistoryEntry]| {
+        entries
+            .iter()
+            .map(|entry| proto::BufferHistoryEntry {
+                transaction: Some(serialize_transaction(&entry.transaction)),
+                parent_id: entry.parent_id.map(serialize_timestamp),
+            })
+            .collect()
+    };
+
+    proto::BufferHistory {
+        base_text: history.base_text.to_string(),
+        operations: history
+            .operations
+            .iter()
+            .map(|operation| serialize_operation(&crate::Operation::Buffer(operation.clone())))
+            .collect(),
+        undo_stack: serialize_entries(&history.undo_stack),
+        redo_stack: serialize_entries(&history.redo_stack),
+        abandoned: serialize_entries(&history.abandoned),
+    }
+}
+
+/// Deserializes a [`PersistedHistory`] from its stored representation.
+pub fn deserialize_history(message: proto::BufferHistory) -> Result<PersistedHistory> {
+    let deserialize_entries = |entries: Vec<proto::BufferHistoryEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| {
+                Ok(PersistedHistoryEntry {
+                    transaction: deserialize_transaction(
+                        entry
+                            .transaction
+                            .ok_or_else(|| anyhow!("missing transaction"))?,
+                    )?,
+                    parent_id: entry.parent_id.map(deserialize_timestamp),
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+
+    let operations = message
+        .operations
+        .into_iter()
+        .map(|operation| match deserialize_operation(operation)? {
+            crate::Operation::Buffer(operation) => Ok(operation),
+            _ => Err(anyhow!("unexpected operation in buffer history")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PersistedHistory {
+        base_text: message.base_text.as_str().into(),
+        operations,
+        undo_stack: deserialize_entries(message.undo_stack)?,
+        redo_stack: deserialize_entries(message.redo_stack)?,
+        abandoned: deserialize_entries(message.abandoned)?,
+    })
+}
+
 /// Serializes a [`clock::Lamport`] timestamp to be sent over RPC.
 pub fn serialize_timestamp(timestamp: clock::Lamport) -> proto::LamportTimestamp {
     proto::LamportTimestamp {
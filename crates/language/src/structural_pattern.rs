@@ -0,0 +1,416 @@
+use crate::{BufferSnapshot, Grammar, GrammarId};
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::{cmp::Reverse, fmt, ops::Range, sync::Arc};
+use tree_sitter::{Node, Tree};
+
+/// A code pattern that is matched against the syntax trees of buffers, rather than against
+/// their text.
+///
+/// The pattern is written in the language of the code it should match. Metavariables, written
+/// as `$name`, match any single syntax node, and every occurrence of the same metavariable must
+/// match the same code. For example, `$x.unwrap()` matches `foo.unwrap()` and
+/// `self.bar(1).unwrap()` in Rust, regardless of the whitespace and comments between tokens.
+pub struct StructuralPattern {
+    source: Arc<str>,
+    /// The pattern, with its metavariables replaced by placeholder identifiers so that it
+    /// can be parsed by any grammar.
+    text: String,
+    /// The trimmed range of `text` that the pattern's root node must span.
+    root_range: Range<usize>,
+    /// The ranges of the placeholders in `text`, along with their metavariable names.
+    metavariables: Vec<(Range<usize>, Arc<str>)>,
+    literal_words: Vec<String>,
+    trees: Mutex<HashMap<GrammarId, Option<Tree>>>,
+}
+
+/// A match of a [`StructuralPattern`] in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralMatch {
+    pub range: Range<usize>,
+    /// The range of code matched by each of the pattern's metavariables.
+    pub captures: Vec<(Arc<str>, Range<usize>)>,
+}
+
+const METAVARIABLE_PLACEHOLDER_PREFIX: &str = "zed_metavariable_";
+
+impl StructuralPattern {
+    pub fn new(source: &str) -> Result<Self> {
+        let mut text = String::with_capacity(source.len());
+        let mut metavariables = Vec::new();
+        let mut literal_words = Vec::new();
+        let mut last_end = 0;
+        for (range, name) in metavariable_ranges(source) {
+            literal_words.extend(words(&source[last_end..range.start]));
+            text.push_str(&source[last_end..range.start]);
+            let start = text.len();
+            text.push_str(METAVARIABLE_PLACEHOLDER_PREFIX);
+            text.push_str(name);
+            metavariables.push((start..text.len(), Arc::from(name)));
+            last_end = range.end;
+        }
+        literal_words.extend(words(&source[last_end..]));
+        text.push_str(&source[last_end..]);
+
+        let root_start = text.len() - text.trim_start().len();
+        let root_end = text.trim_end().len();
+        if root_start >= root_end {
+            return Err(anyhow!("structural search pattern is empty"));
+        }
+        if metavariables.len() == 1 && metavariables[0].0 == (root_start..root_end) {
+            return Err(anyhow!(
+                "structural search pattern must contain code besides a metavariable"
+            ));
+        }
+
+        Ok(Self {
+            source: source.into(),
+            text,
+            root_range: root_start..root_end,
+            metavariables,
+            literal_words,
+            trees: Default::default(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The words that occur in the pattern outside of metavariables. Every buffer containing a
+    /// match of the pattern contains all of these words.
+    pub fn literal_words(&self) -> &[String] {
+        &self.literal_words
+    }
+
+    /// Returns the non-overlapping matches of the pattern that lie within the given range of
+    /// the buffer, in every syntax layer whose grammar can parse the pattern.
+    ///
+    /// When matches are nested, only the outermost one is returned.
+    pub fn matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<StructuralMatch> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax.layers_for_range(range.clone(), buffer) {
+            let Some(grammar) = layer.language.grammar() else {
+                continue;
+            };
+            let Some(tree) = self.tree_for_grammar(grammar) else {
+                continue;
+            };
+            let Some(pattern_root) = self.pattern_root(&tree) else {
+                continue;
+            };
+
+            let mut cursor = layer.node().walk();
+            'walk: loop {
+                let node = cursor.node();
+                let node_range = node.byte_range();
+                let mut visit_children =
+                    node_range.start < range.end && node_range.end > range.start;
+                if visit_children
+                    && node.kind_id() == pattern_root.kind_id()
+                    && node_range.start >= range.start
+                    && node_range.end <= range.end
+                {
+                    let mut captures = Vec::new();
+                    if self.match_node(pattern_root, node, buffer, &mut captures) {
+                        matches.push(StructuralMatch {
+                            range: node_range,
+                            captures,
+                        });
+                        visit_children = false;
+                    }
+                }
+
+                if visit_children && cursor.goto_first_child() {
+                    continue;
+                }
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break 'walk;
+                    }
+                }
+            }
+        }
+
+        // Matches from different layers can overlap when languages are injected into each other.
+        matches.sort_by_key(|mat| (mat.range.start, Reverse(mat.range.end)));
+        let mut last_end = 0;
+        matches.retain(|mat| {
+            if mat.range.start < last_end {
+                false
+            } else {
+                last_end = mat.range.end;
+                true
+            }
+        });
+        matches
+    }
+
+    /// Expands a replacement template for the given match, substituting each `$name` in the
+    /// template with the code that the metavariable matched. Metavariables that don't occur in
+    /// the pattern are left as they are.
+    pub fn expand_replacement(
+        &self,
+        template: &str,
+        mat: &StructuralMatch,
+        buffer: &BufferSnapshot,
+    ) -> String {
+        let mut replacement = String::with_capacity(template.len());
+        let mut last_end = 0;
+        for (range, name) in metavariable_ranges(template) {
+            replacement.push_str(&template[last_end..range.start]);
+            if let Some((_, capture_range)) = mat
+                .captures
+                .iter()
+                .find(|(capture, _)| capture.as_ref() == name)
+            {
+                replacement.extend(buffer.text_for_range(capture_range.clone()));
+            } else {
+                replacement.push_str(&template[range.clone()]);
+            }
+            last_end = range.end;
+        }
+        replacement.push_str(&template[last_end..]);
+        replacement
+    }
+
+    fn tree_for_grammar(&self, grammar: &Grammar) -> Option<Tree> {
+        self.trees
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| {
+                crate::with_parser(|parser| {
+                    parser.set_language(&grammar.ts_language).ok()?;
+                    parser.parse(&self.text, None)
+                })
+            })
+            .clone()
+    }
+
+    /// Finds the deepest node of the parsed pattern that spans the whole pattern. Returns `None`
+    /// if the grammar couldn't parse the pattern, or if the pattern only consists of a
+    /// metavariable in this grammar.
+    fn pattern_root<'a>(&self, tree: &'a Tree) -> Option<Node<'a>> {
+        let mut node = tree.root_node();
+        'descend: loop {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.start_byte() <= self.root_range.start
+                    && child.end_byte() >= self.root_range.end
+                {
+                    node = child;
+                    continue 'descend;
+                }
+            }
+            break;
+        }
+
+        if node.has_error() || self.metavariable_for_range(node.byte_range()).is_some() {
+            None
+        } else {
+            Some(node)
+        }
+    }
+
+    fn metavariable_for_range(&self, range: Range<usize>) -> Option<&Arc<str>> {
+        self.metavariables
+            .iter()
+            .find(|(metavariable_range, _)| *metavariable_range == range)
+            .map(|(_, name)| name)
+    }
+
+    fn match_node(
+        &self,
+        pattern: Node,
+        node: Node,
+        buffer: &BufferSnapshot,
+        captures: &mut Vec<(Arc<str>, Range<usize>)>,
+    ) -> bool {
+        if let Some(name) = self.metavariable_for_range(pattern.byte_range()) {
+            if let Some((_, captured_range)) = captures.iter().find(|(capture, _)| capture == name)
+            {
+                return captured_range.len() == node.byte_range().len()
+                    && buffer
+                        .text_for_range(captured_range.clone())
+                        .collect::<String>()
+                        == buffer.text_for_range(node.byte_range()).collect::<String>();
+            }
+            captures.push((name.clone(), node.byte_range()));
+            return true;
+        }
+
+        if pattern.kind_id() != node.kind_id() {
+            return false;
+        }
+
+        let pattern_children = significant_children(pattern);
+        let node_children = significant_children(node);
+        if pattern_children.is_empty() {
+            let pattern_text = &self.text[pattern.byte_range()];
+            return node_children.is_empty()
+                && node.byte_range().len() == pattern_text.len()
+                && buffer.contains_str_at(node.start_byte(), pattern_text);
+        }
+
+        pattern_children.len() == node_children.len()
+            && pattern_children
+                .into_iter()
+                .zip(node_children)
+                .all(|(pattern_child, node_child)| {
+                    self.match_node(pattern_child, node_child, buffer, captures)
+                })
+    }
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StructuralPattern")
+            .field(&self.source)
+            .finish()
+    }
+}
+
+/// The children of a node that take part in matching. Comments and other extras are
+/// ignored, as are the empty nodes that tree-sitter inserts when recovering from errors.
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && child.start_byte() < child.end_byte())
+        .collect()
+}
+
+/// Returns the ranges of the `$name` metavariables in the given text, along with their names.
+fn metavariable_ranges(text: &str) -> Vec<(Range<usize>, &str)> {
+    let mut ranges = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        if ch != '$' {
+            continue;
+        }
+        if !chars
+            .peek()
+            .map_or(false, |(_, ch)| ch.is_ascii_alphabetic() || *ch == '_')
+        {
+            continue;
+        }
+        let mut end = start + 1;
+        while let Some((ix, ch)) = chars.peek() {
+            if ch.is_ascii_alphanumeric() || *ch == '_' {
+                end = ix + 1;
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        ranges.push((start..end, &text[start + 1..end]));
+    }
+    ranges
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Buffer, Language, LanguageConfig, LanguageMatcher};
+    use gpui::{Context as _, TestAppContext};
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_structural_matches(cx: &mut TestAppContext) {
+        let text = indoc! {r#"
+            fn main() {
+                let a = foo().unwrap();
+                let b = foo()
+                    // Comments are ignored.
+                    .unwrap();
+                let c = bar(a.unwrap()).unwrap();
+                let d = a.expect("a");
+                let e = f(a, a);
+                let f = f(a, b);
+            }
+        "#};
+        let buffer =
+            cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+        buffer
+            .read_with(cx, |buffer, _| buffer.parsing_idle())
+            .await;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let matched_text = |pattern: &StructuralPattern| {
+            pattern
+                .matches(&snapshot, 0..snapshot.len())
+                .into_iter()
+                .map(|mat| snapshot.text_for_range(mat.range).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        let pattern = StructuralPattern::new("$x.unwrap()").unwrap();
+        assert_eq!(pattern.literal_words(), ["unwrap"]);
+        assert_eq!(
+            matched_text(&pattern),
+            [
+                "foo().unwrap()",
+                "foo()\n        // Comments are ignored.\n        .unwrap()",
+                "bar(a.unwrap()).unwrap()",
+            ]
+        );
+
+        let matches = pattern.matches(&snapshot, 0..snapshot.len());
+        assert_eq!(
+            pattern.expand_replacement("$x?", &matches[0], &snapshot),
+            "foo()?"
+        );
+        assert_eq!(
+            pattern.expand_replacement("$x.context($y)?", &matches[2], &snapshot),
+            "bar(a.unwrap()).context($y)?"
+        );
+
+        // Repeated metavariables must match the same code.
+        let pattern = StructuralPattern::new("f($x, $x)").unwrap();
+        assert_eq!(matched_text(&pattern), ["f(a, a)"]);
+
+        // Patterns are only matched within the given range.
+        let pattern = StructuralPattern::new("$x.unwrap()").unwrap();
+        let range_start = text.find("bar").unwrap();
+        let range_end = text.find("let d").unwrap();
+        let matches = pattern.matches(&snapshot, range_start..range_end);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|mat| snapshot
+                    .text_for_range(mat.range.clone())
+                    .collect::<String>())
+                .collect::<Vec<_>>(),
+            ["bar(a.unwrap()).unwrap()"]
+        );
+        assert_eq!(
+            matches[0].captures,
+            [(
+                Arc::<str>::from("x"),
+                range_start..range_start + "bar(a.unwrap())".len()
+            )]
+        );
+
+        assert!(StructuralPattern::new("  ").is_err());
+        assert!(StructuralPattern::new("$x").is_err());
+    }
+
+    fn rust_lang() -> Language {
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+    }
+}
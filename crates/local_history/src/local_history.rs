@@ -0,0 +1,512 @@
+mod persistence;
+
+use editor::{
+    actions::{ExpandAllHunkDiffs, ToggleLocalHistory},
+    Editor, EditorMode, MultiBuffer,
+};
+use fs::Fs;
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, ParentElement,
+    Render, Styled, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::{Buffer, Capability};
+use persistence::LocalHistoryFiles;
+use picker::{Picker, PickerDelegate};
+use project::local_history::{
+    local_history_store, set_local_history_store, LocalHistorySnapshot, LocalHistoryStore,
+};
+use std::{path::PathBuf, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+pub use persistence::MAX_SNAPSHOTS_PER_FILE;
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    set_local_history_store(
+        Arc::new(LocalHistoryFiles::new(
+            fs,
+            paths::local_history_dir().clone(),
+        )),
+        cx,
+    );
+    cx.observe_new_views(LocalHistoryView::register).detach();
+}
+
+pub fn toggle(editor: View<Editor>, _: &ToggleLocalHistory, cx: &mut WindowContext) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) else {
+        return;
+    };
+    let abs_path = file.abs_path(cx);
+    let Some(store) = local_history_store(cx) else {
+        return;
+    };
+
+    if let Some(workspace) = editor.read(cx).workspace() {
+        workspace.update(cx, |workspace, cx| {
+            let weak_workspace = cx.view().downgrade();
+            workspace.toggle_modal(cx, |cx| {
+                LocalHistoryView::new(abs_path, store, buffer, editor, weak_workspace, cx)
+            });
+        })
+    }
+}
+
+/// Lists the snapshots in the local history of a file, which can be restored or compared
+/// with the file's current contents.
+pub struct LocalHistoryView {
+    picker: View<Picker<LocalHistoryViewDelegate>>,
+    _load_snapshots: Task<()>,
+}
+
+impl FocusableView for LocalHistoryView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LocalHistoryView {}
+impl ModalView for LocalHistoryView {}
+
+impl Render for LocalHistoryView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl LocalHistoryView {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        if editor.mode() == EditorMode::Full {
+            let handle = cx.view().downgrade();
+            editor
+                .register_action(move |action, cx| {
+                    if let Some(editor) = handle.upgrade() {
+                        toggle(editor, action, cx);
+                    }
+                })
+                .detach();
+        }
+    }
+
+    fn new(
+        abs_path: PathBuf,
+        store: Arc<dyn LocalHistoryStore>,
+        buffer: Model<Buffer>,
+        editor: View<Editor>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = LocalHistoryViewDelegate {
+            local_history_view: cx.view().downgrade(),
+            workspace,
+            active_editor: editor,
+            buffer,
+            store: store.clone(),
+            snapshots: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            now: OffsetDateTime::now_utc(),
+            timezone: cx.local_timezone(),
+        };
+        let picker =
+            cx.new_view(|cx| Picker::uniform_list(delegate, cx).max_height(Some(vh(0.75, cx))));
+
+        let snapshots = store.snapshots(abs_path);
+        let load_snapshots = cx.spawn(|this, mut cx| async move {
+            let Some(snapshots) = snapshots.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.snapshots = snapshots;
+                    picker.refresh(cx);
+                })
+            })
+            .ok();
+        });
+
+        LocalHistoryView {
+            picker,
+            _load_snapshots: load_snapshots,
+        }
+    }
+}
+
+struct LocalHistoryViewDelegate {
+    local_history_view: WeakView<LocalHistoryView>,
+    workspace: WeakView<Workspace>,
+    active_editor: View<Editor>,
+    buffer: Model<Buffer>,
+    store: Arc<dyn LocalHistoryStore>,
+    snapshots: Vec<LocalHistorySnapshot>,
+    matches: Vec<usize>,
+    selected_index: usize,
+    now: OffsetDateTime,
+    timezone: UtcOffset,
+}
+
+impl LocalHistoryViewDelegate {
+    fn timestamp(&self, snapshot: &LocalHistorySnapshot) -> String {
+        time_format::format_localized_timestamp(
+            OffsetDateTime::from(snapshot.recorded_at),
+            self.now,
+            self.timezone,
+            time_format::TimestampFormat::EnhancedAbsolute,
+        )
+    }
+
+    /// Replaces the contents of the buffer with those of the snapshot, as an edit that can be
+    /// undone.
+    fn restore(&self, snapshot: &LocalHistorySnapshot, cx: &mut ViewContext<Picker<Self>>) {
+        let load = self.store.load(snapshot);
+        let buffer = self.buffer.clone();
+        cx.spawn(|_, mut cx| async move {
+            let text = load.await?;
+            let diff = buffer
+                .update(&mut cx, |buffer, cx| buffer.diff(text, cx))?
+                .await;
+            buffer.update(&mut cx, |buffer, cx| buffer.apply_diff(diff, cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens a read-only editor with the current contents of the buffer, showing how they
+    /// differ from those of the snapshot.
+    fn open_diff(&self, snapshot: &LocalHistorySnapshot, cx: &mut ViewContext<Picker<Self>>) {
+        let load = self.store.load(snapshot);
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        let title = format!(
+            "{} ({}, {})",
+            snapshot
+                .abs_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy(),
+            snapshot.origin.label(),
+            self.timestamp(snapshot)
+        );
+        cx.spawn(|_, mut cx| async move {
+            let snapshot_text = load.await?;
+            let (editor, diff_buffer) = workspace.update(&mut cx, |workspace, cx| {
+                let project = workspace.project().clone();
+                let (text, language) = {
+                    let buffer = buffer.read(cx);
+                    (buffer.text(), buffer.language().cloned())
+                };
+                let diff_buffer = project.update(cx, |project, cx| {
+                    project.create_local_buffer(&text, language, cx)
+                });
+                diff_buffer.update(cx, |buffer, cx| {
+                    buffer.set_diff_base(Some(snapshot_text), cx);
+                    buffer.set_capability(Capability::ReadOnly, cx);
+                });
+                let multibuffer = cx.new_model(|cx| {
+                    MultiBuffer::singleton(diff_buffer.clone(), cx).with_title(title)
+                });
+                let editor = cx.new_view(|cx| {
+                    let mut editor = Editor::for_multibuffer(multibuffer, Some(project), true, cx);
+                    editor.set_read_only(true);
+                    editor
+                });
+                workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+                (editor, diff_buffer)
+            })?;
+
+            // Expand the hunks once they've been computed, so that the snapshot's contents
+            // are shown alongside the current ones.
+            let diff = diff_buffer.update(&mut cx, |buffer, cx| buffer.git_diff_recalc(cx))?;
+            if let Some(diff) = diff {
+                diff.await;
+            }
+            editor.update(&mut cx, |editor, cx| {
+                editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for LocalHistoryViewDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Filter local history...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No local history".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .snapshots
+            .iter()
+            .enumerate()
+            .filter(|(_, snapshot)| {
+                snapshot.origin.label().to_lowercase().contains(&query)
+                    || self.timestamp(snapshot).to_lowercase().contains(&query)
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(snapshot) = self
+            .matches
+            .get(self.selected_index)
+            .map(|ix| &self.snapshots[*ix])
+        else {
+            return;
+        };
+
+        if secondary {
+            self.open_diff(snapshot, cx);
+        } else {
+            self.restore(snapshot, cx);
+            self.active_editor.update(cx, |editor, cx| editor.focus(cx));
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.local_history_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let snapshot = &self.snapshots[*self.matches.get(ix)?];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .gap_2()
+                        .child(Label::new(snapshot.origin.label()))
+                        .child(Label::new(self.timestamp(snapshot)).color(Color::Muted)),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::actions::Undo;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{local_history::SnapshotOrigin, FakeFs, Project};
+    use serde_json::json;
+    use std::path::Path;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_recording_snapshots(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let store = LocalHistoryFiles::new(fs.clone(), "/local_history".into());
+        let abs_path = Path::new("/recording/a.txt").to_path_buf();
+
+        store
+            .record(abs_path.clone(), SnapshotOrigin::Save, "one".into())
+            .await
+            .unwrap();
+        // Contents that are the same as the latest snapshot's aren't recorded again.
+        store
+            .record(
+                abs_path.clone(),
+                SnapshotOrigin::ExternalChange,
+                "one".into(),
+            )
+            .await
+            .unwrap();
+        store
+            .record(abs_path.clone(), SnapshotOrigin::Format, "two".into())
+            .await
+            .unwrap();
+
+        let snapshots = store.snapshots(abs_path.clone()).await.unwrap();
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|snapshot| snapshot.origin)
+                .collect::<Vec<_>>(),
+            [SnapshotOrigin::Format, SnapshotOrigin::Save]
+        );
+        assert_eq!(store.load(&snapshots[0]).await.unwrap(), "two");
+        assert_eq!(store.load(&snapshots[1]).await.unwrap(), "one");
+
+        // Only the most recent snapshots are kept, and the contents of the others are
+        // deleted unless they're still referenced.
+        for ix in 0..MAX_SNAPSHOTS_PER_FILE {
+            let text = if ix % 2 == 0 { "one" } else { "three" };
+            store
+                .record(abs_path.clone(), SnapshotOrigin::Save, text.into())
+                .await
+                .unwrap();
+        }
+        let snapshots = store.snapshots(abs_path.clone()).await.unwrap();
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS_PER_FILE);
+        let content_paths = fs
+            .files()
+            .into_iter()
+            .filter(|path| path.starts_with("/local_history"))
+            .count();
+        assert_eq!(content_paths, 2);
+    }
+
+    #[gpui::test]
+    async fn test_local_history_view(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/view", json!({ "a.txt": "one\n" })).await;
+        cx.update(|cx| {
+            set_local_history_store(
+                Arc::new(LocalHistoryFiles::new(fs.clone(), "/local_history".into())),
+                cx,
+            )
+        });
+
+        let project = Project::test(fs, ["/view".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+
+        for text in ["one two\n", "one two three\n"] {
+            editor.update(cx, |editor, cx| editor.set_text(text, cx));
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.save_active_item(workspace::SaveIntent::Save, cx)
+                })
+                .await
+                .unwrap();
+            cx.run_until_parked();
+        }
+
+        let local_history_view = open_local_history_view(&workspace, cx);
+        assert_eq!(
+            entries(&local_history_view, cx),
+            [("Saved", "one two three\n"), ("Saved", "one two\n")]
+        );
+
+        cx.dispatch_action(menu::SelectNext);
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert_eq!(editor.update(cx, |editor, cx| editor.text(cx)), "one two\n");
+
+        // Restoring a snapshot is a change that can be undone.
+        editor.update(cx, |editor, cx| editor.undo(&Undo, cx));
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "one two three\n"
+        );
+
+        // Snapshots can be compared with the current contents of the file.
+        open_local_history_view(&workspace, cx);
+        cx.dispatch_action(menu::SelectNext);
+        cx.dispatch_action(menu::SecondaryConfirm);
+        cx.run_until_parked();
+        let diff_editor = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        assert_ne!(diff_editor.entity_id(), editor.entity_id());
+        diff_editor.update(cx, |diff_editor, cx| {
+            assert!(diff_editor.read_only(cx));
+            assert_eq!(diff_editor.text(cx), "one two three\n");
+            assert!(diff_editor
+                .buffer()
+                .read(cx)
+                .title(cx)
+                .starts_with("a.txt (Saved, "));
+        });
+    }
+
+    fn open_local_history_view(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<Picker<LocalHistoryViewDelegate>> {
+        cx.dispatch_action(ToggleLocalHistory);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<LocalHistoryView>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        })
+    }
+
+    fn entries(
+        local_history_view: &View<Picker<LocalHistoryViewDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<(&'static str, String)> {
+        let snapshots = local_history_view.update(cx, |local_history_view, _| {
+            let delegate = &local_history_view.delegate;
+            delegate
+                .matches
+                .iter()
+                .map(|ix| delegate.snapshots[*ix].clone())
+                .collect::<Vec<_>>()
+        });
+        let store = cx.update(|cx| local_history_store(cx).unwrap());
+        snapshots
+            .iter()
+            .map(|snapshot| {
+                let text = cx.background_executor.block(store.load(snapshot)).unwrap();
+                (snapshot.origin.label(), text)
+            })
+            .collect()
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            cx.observe_new_views(LocalHistoryView::register).detach();
+            state
+        })
+    }
+}
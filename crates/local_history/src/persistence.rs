@@ -0,0 +1,220 @@
+use anyhow::Result;
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+use fs::{Fs, RemoveOptions};
+use futures::{future::BoxFuture, lock::Mutex, FutureExt};
+use project::{
+    buffer_history::content_hash,
+    local_history::{LocalHistorySnapshot, LocalHistoryStore, SnapshotOrigin},
+};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The number of snapshots that are kept for each file.
+pub const MAX_SNAPSHOTS_PER_FILE: usize = 50;
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // local_history_snapshots(
+    //   abs_path: PathBuf,
+    //   content_hash: String,
+    //   origin: String,
+    //   recorded_at: i64, // milliseconds since the unix epoch
+    // )
+    pub static ref DB: LocalHistoryDb<()> =
+        &[sql!(
+            CREATE TABLE local_history_snapshots(
+                abs_path BLOB NOT NULL,
+                content_hash TEXT NOT NULL,
+                origin TEXT NOT NULL,
+                recorded_at INTEGER NOT NULL
+            ) STRICT;
+            CREATE INDEX local_history_snapshots_by_path
+                ON local_history_snapshots(abs_path, recorded_at);
+        )];
+);
+
+impl LocalHistoryDb {
+    query! {
+        pub async fn latest_content_hash(abs_path: PathBuf) -> Result<Option<String>> {
+            SELECT content_hash FROM local_history_snapshots
+            WHERE abs_path = ?
+            ORDER BY recorded_at DESC, rowid DESC
+            LIMIT 1
+        }
+    }
+
+    query! {
+        pub async fn get_snapshots(abs_path: PathBuf) -> Result<Vec<(String, String, i64)>> {
+            SELECT content_hash, origin, recorded_at FROM local_history_snapshots
+            WHERE abs_path = ?
+            ORDER BY recorded_at DESC, rowid DESC
+        }
+    }
+
+    /// Inserts a snapshot and forgets the oldest snapshots of its file in a single
+    /// transaction. Returns the hashes of the contents that are no longer referenced by
+    /// any snapshot.
+    pub async fn insert_snapshot(
+        &self,
+        abs_path: PathBuf,
+        content_hash: String,
+        origin: String,
+        recorded_at: i64,
+    ) -> Result<Vec<String>> {
+        self.write(move |conn| {
+            conn.with_savepoint("insert_snapshot", || {
+                conn.exec_bound(sql!(
+                    INSERT INTO local_history_snapshots
+                        (abs_path, content_hash, origin, recorded_at)
+                    VALUES
+                        (?1, ?2, ?3, ?4)
+                ))?((
+                    abs_path.as_path(),
+                    content_hash.as_str(),
+                    origin.as_str(),
+                    recorded_at,
+                ))?;
+
+                let mut expired_content_hashes =
+                    conn.select_bound::<_, String>(sql!(
+                        SELECT content_hash FROM local_history_snapshots
+                        WHERE abs_path = ?1
+                        ORDER BY recorded_at DESC, rowid DESC
+                        LIMIT -1 OFFSET ?2
+                    ))?((abs_path.as_path(), MAX_SNAPSHOTS_PER_FILE))?;
+                if expired_content_hashes.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                conn.exec_bound(sql!(
+                    DELETE FROM local_history_snapshots
+                    WHERE abs_path = ?1 AND rowid NOT IN (
+                        SELECT rowid FROM local_history_snapshots
+                        WHERE abs_path = ?1
+                        ORDER BY recorded_at DESC, rowid DESC
+                        LIMIT ?2
+                    )
+                ))?((abs_path.as_path(), MAX_SNAPSHOTS_PER_FILE))?;
+
+                expired_content_hashes.sort();
+                expired_content_hashes.dedup();
+                let mut unreferenced_content_hashes = Vec::new();
+                for content_hash in expired_content_hashes {
+                    let references = conn.select_row_bound::<_, usize>(sql!(
+                        SELECT COUNT(*) FROM local_history_snapshots
+                        WHERE content_hash = ?
+                    ))?(content_hash.as_str())?;
+                    if references.unwrap_or(0) == 0 {
+                        unreferenced_content_hashes.push(content_hash);
+                    }
+                }
+                Ok(unreferenced_content_hashes)
+            })
+        })
+        .await
+    }
+}
+
+/// Stores the contents of snapshots in files named after their hashes, so that identical
+/// contents are only stored once, and indexes the snapshots in the database.
+pub(crate) struct LocalHistoryFiles {
+    fs: Arc<dyn Fs>,
+    dir: PathBuf,
+    /// Held while recording, so that a content file is never deleted after another
+    /// snapshot has started referencing it.
+    lock: Arc<Mutex<()>>,
+}
+
+impl LocalHistoryFiles {
+    pub(crate) fn new(fs: Arc<dyn Fs>, dir: PathBuf) -> Self {
+        Self {
+            fs,
+            dir,
+            lock: Arc::default(),
+        }
+    }
+}
+
+impl LocalHistoryStore for LocalHistoryFiles {
+    fn record(
+        &self,
+        abs_path: PathBuf,
+        origin: SnapshotOrigin,
+        text: String,
+    ) -> BoxFuture<'static, Result<()>> {
+        let fs = self.fs.clone();
+        let dir = self.dir.clone();
+        let lock = self.lock.clone();
+        async move {
+            let _guard = lock.lock().await;
+            let content_hash = content_hash(&text);
+            let latest_content_hash = DB.latest_content_hash(abs_path.clone()).await?;
+            if latest_content_hash.as_ref() == Some(&content_hash) {
+                return Ok(());
+            }
+
+            // Write the content before indexing it, so that the database never references
+            // a missing file.
+            let content_path = dir.join(&content_hash);
+            if !fs.is_file(&content_path).await {
+                fs.create_dir(&dir).await?;
+                fs.atomic_write(content_path, text).await?;
+            }
+            let recorded_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as i64;
+            let unreferenced_content_hashes = DB
+                .insert_snapshot(
+                    abs_path,
+                    content_hash,
+                    origin.as_str().to_string(),
+                    recorded_at,
+                )
+                .await?;
+            for content_hash in unreferenced_content_hashes {
+                fs.remove_file(
+                    &dir.join(&content_hash),
+                    RemoveOptions {
+                        ignore_if_not_exists: true,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn snapshots(
+        &self,
+        abs_path: PathBuf,
+    ) -> BoxFuture<'static, Result<Vec<LocalHistorySnapshot>>> {
+        async move {
+            DB.get_snapshots(abs_path.clone())
+                .await?
+                .into_iter()
+                .map(|(content_hash, origin, recorded_at)| {
+                    Ok(LocalHistorySnapshot {
+                        abs_path: abs_path.clone(),
+                        content_hash,
+                        origin: origin.parse()?,
+                        recorded_at: UNIX_EPOCH + Duration::from_millis(recorded_at as u64),
+                    })
+                })
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn load(&self, snapshot: &LocalHistorySnapshot) -> BoxFuture<'static, Result<String>> {
+        let fs = self.fs.clone();
+        let content_path = self.dir.join(&snapshot.content_hash);
+        async move { fs.load(&content_path).await }.boxed()
+    }
+}
@@ -317,6 +317,12 @@ impl NotificationStore {
                     user_ids.push(sender_id);
                     message_ids.push(message_id);
                 }
+                Notification::ProjectCommentReply { sender_id, .. } => {
+                    user_ids.push(sender_id);
+                }
+                Notification::ProjectCommentThreadResolved { resolver_id, .. } => {
+                    user_ids.push(resolver_id);
+                }
             }
         }
 
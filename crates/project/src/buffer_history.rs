@@ -0,0 +1,149 @@
+//! Persists the undo history of local buffers when they're saved, so that it can be restored
+//! the next time the file is opened, as long as it hasn't changed on disk in the meantime.
+
+use anyhow::{anyhow, Result};
+use futures::future::BoxFuture;
+use gpui::{AppContext, Global};
+use language::proto::{deserialize_history, serialize_history};
+use rpc::proto::{self, Message as _};
+use sha2::{Digest, Sha256};
+use std::{path::PathBuf, sync::Arc};
+use text::{BufferId, LineEnding, PersistedHistory};
+use util::ResultExt;
+
+/// Histories that are larger than this many bytes once serialized aren't persisted.
+pub const MAX_PERSISTED_HISTORY_LEN: usize = 4 * 1024 * 1024;
+
+/// Storage for buffer histories, keyed by the absolute path of the file and a hash of its
+/// contents.
+pub trait BufferHistoryStore: 'static + Send + Sync {
+    fn load(
+        &self,
+        abs_path: PathBuf,
+        content_hash: String,
+    ) -> BoxFuture<'static, Result<Option<Vec<u8>>>>;
+
+    fn save(
+        &self,
+        abs_path: PathBuf,
+        content_hash: String,
+        history: Vec<u8>,
+    ) -> BoxFuture<'static, Result<()>>;
+}
+
+struct GlobalBufferHistoryStore(Arc<dyn BufferHistoryStore>);
+
+impl Global for GlobalBufferHistoryStore {}
+
+pub fn set_buffer_history_store(store: Arc<dyn BufferHistoryStore>, cx: &mut AppContext) {
+    cx.set_global(GlobalBufferHistoryStore(store));
+}
+
+pub(crate) fn buffer_history_store(cx: &AppContext) -> Option<Arc<dyn BufferHistoryStore>> {
+    cx.try_global::<GlobalBufferHistoryStore>()
+        .map(|store| store.0.clone())
+}
+
+/// Hashes the text of a buffer, with its line endings normalized.
+pub fn content_hash(normalized_text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(normalized_text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Builds the text buffer for a file that was loaded from disk, restoring its undo history if
+/// one was persisted for the same contents.
+pub(crate) async fn load_text_buffer(
+    store: Option<Arc<dyn BufferHistoryStore>>,
+    abs_path: Option<PathBuf>,
+    buffer_id: BufferId,
+    text: String,
+) -> text::Buffer {
+    if let Some((store, abs_path)) = store.zip(abs_path) {
+        let line_ending = LineEnding::detect(&text);
+        let mut normalized_text = text.clone();
+        LineEnding::normalize(&mut normalized_text);
+        let history = store
+            .load(abs_path, content_hash(&normalized_text))
+            .await
+            .log_err()
+            .flatten();
+        if let Some(history) = history {
+            if let Some(buffer) =
+                restore_text_buffer(&history, buffer_id, line_ending, &normalized_text).log_err()
+            {
+                return buffer;
+            }
+        }
+    }
+
+    text::Buffer::new(0, buffer_id, text)
+}
+
+fn restore_text_buffer(
+    history: &[u8],
+    buffer_id: BufferId,
+    line_ending: LineEnding,
+    normalized_text: &str,
+) -> Result<text::Buffer> {
+    let history = deserialize_history(proto::BufferHistory::decode(history)?)?;
+    let buffer = text::Buffer::from_persisted_history(0, buffer_id, line_ending, history)?;
+    if buffer.text() != normalized_text {
+        return Err(anyhow!(
+            "persisted history doesn't match the file's contents"
+        ));
+    }
+    Ok(buffer)
+}
+
+/// Stores the history of a buffer whose text was just saved to disk.
+pub(crate) async fn save_history(
+    store: Arc<dyn BufferHistoryStore>,
+    abs_path: PathBuf,
+    normalized_text: String,
+    history: PersistedHistory,
+) -> Result<()> {
+    let Some(history) = encode_history_within_limit(history, &normalized_text)? else {
+        log::warn!("not persisting history of {abs_path:?}, because it is too large");
+        return Ok(());
+    };
+    store
+        .save(abs_path, content_hash(&normalized_text), history)
+        .await
+}
+
+/// Encodes the given history, dropping its oldest transactions until it fits within
+/// [`MAX_PERSISTED_HISTORY_LEN`].
+fn encode_history_within_limit(
+    history: PersistedHistory,
+    normalized_text: &str,
+) -> Result<Option<Vec<u8>>> {
+    let encoded = serialize_history(&history).encode_to_vec();
+    if encoded.len() <= MAX_PERSISTED_HISTORY_LEN {
+        return Ok(Some(encoded));
+    }
+
+    let mut count = history.undo_stack.len();
+    let buffer =
+        text::Buffer::from_persisted_history(0, BufferId::new(1)?, LineEnding::Unix, history)?;
+    loop {
+        count /= 2;
+        let history = buffer.persisted_history_with_last_transactions(count);
+        let encoded = serialize_history(&history).encode_to_vec();
+        if encoded.len() <= MAX_PERSISTED_HISTORY_LEN {
+            let compacted = text::Buffer::from_persisted_history(
+                0,
+                BufferId::new(1)?,
+                LineEnding::Unix,
+                history,
+            )?;
+            if compacted.text() != normalized_text {
+                return Err(anyhow!("compacted history doesn't match the buffer's text"));
+            }
+            return Ok(Some(encoded));
+        }
+        if count == 0 {
+            return Ok(None);
+        }
+    }
+}
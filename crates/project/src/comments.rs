@@ -0,0 +1,247 @@
+//! Comment threads that collaborators leave on ranges of the buffers in a shared project.
+//!
+//! Threads are stored by the collab server for as long as the project is shared. Their ranges
+//! are anchored in the buffers' histories, so they keep pointing at the same text while it's
+//! edited by anyone in the project, but they're hidden if the host closes and reopens the buffer.
+//! Notifications about replies and resolved threads outlive the threads, and can only be
+//! followed while the thread still exists.
+
+use crate::{Event, File, Project, ProjectPath, WorktreeId};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope};
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    proto::{deserialize_anchor, serialize_anchor},
+    Buffer,
+};
+use std::{
+    future::Future,
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use text::Anchor;
+
+#[derive(Clone, Debug)]
+pub struct CommentThread {
+    pub id: u64,
+    pub project_path: ProjectPath,
+    /// The commented range. Its anchors only resolve in a buffer that has seen the edits they
+    /// were created after, see [`Project::comment_threads_for_buffer`].
+    pub range: Range<Anchor>,
+    /// The id of the user who resolved the thread, if it has been resolved.
+    pub resolved_by: Option<u64>,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Comment {
+    pub id: u64,
+    pub author_id: u64,
+    pub body: String,
+    pub timestamp: SystemTime,
+}
+
+impl CommentThread {
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_by.is_some()
+    }
+
+    fn from_proto(thread: proto::ProjectCommentThread) -> Option<Self> {
+        Some(Self {
+            id: thread.id,
+            project_path: ProjectPath {
+                worktree_id: WorktreeId::from_proto(thread.worktree_id),
+                path: Arc::from(PathBuf::from(thread.path)),
+            },
+            range: deserialize_anchor(thread.start?)?..deserialize_anchor(thread.end?)?,
+            resolved_by: thread.resolved_by_id,
+            comments: thread
+                .comments
+                .into_iter()
+                .map(|comment| Comment {
+                    id: comment.id,
+                    author_id: comment.author_id,
+                    body: comment.body,
+                    timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(comment.timestamp),
+                })
+                .collect(),
+        })
+    }
+}
+
+impl Project {
+    pub fn comment_threads(&self) -> impl Iterator<Item = &CommentThread> {
+        self.comment_threads.values()
+    }
+
+    pub fn comment_thread(&self, id: u64) -> Option<&CommentThread> {
+        self.comment_threads.get(&id)
+    }
+
+    /// Returns the threads on the given buffer, along with their ranges in it. Threads whose
+    /// anchors can't be resolved yet, because the buffer hasn't received the edits they refer to,
+    /// are omitted.
+    pub fn comment_threads_for_buffer<'a>(
+        &'a self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Vec<(&'a CommentThread, Range<Anchor>)> {
+        let buffer = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return Vec::new();
+        };
+        let worktree_id = file.worktree_id(cx);
+        let snapshot = buffer.text_snapshot();
+        let buffer_id = Some(snapshot.remote_id());
+        self.comment_threads
+            .values()
+            .filter(|thread| {
+                thread.project_path.worktree_id == worktree_id
+                    && thread.project_path.path == file.path
+            })
+            .filter_map(|thread| {
+                let range = Anchor {
+                    buffer_id,
+                    ..thread.range.start
+                }..Anchor {
+                    buffer_id,
+                    ..thread.range.end
+                };
+                (snapshot.can_resolve(&range.start) && snapshot.can_resolve(&range.end))
+                    .then_some((thread, range))
+            })
+            .collect()
+    }
+
+    /// Starts a thread on the given range of a buffer, with the given comment.
+    pub fn create_comment_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        range: Range<Anchor>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<u64>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("comments can only be left in shared projects")));
+        };
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return Task::ready(Err(anyhow!("can't comment on a buffer without a file")));
+        };
+        let request = self.client.request(proto::CreateProjectCommentThread {
+            project_id,
+            worktree_id: file.worktree_id(cx).to_proto(),
+            path: file.path.to_string_lossy().into(),
+            start: Some(serialize_anchor(&range.start)),
+            end: Some(serialize_anchor(&range.end)),
+            body,
+        });
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let thread = response.thread.context("missing comment thread")?;
+            let id = thread.id;
+            this.update(&mut cx, |this, cx| this.insert_comment_thread(thread, cx))?;
+            Ok(id)
+        })
+    }
+
+    pub fn reply_to_comment_thread(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("project is not shared")));
+        };
+        let request = self.client.request(proto::ReplyToProjectCommentThread {
+            project_id,
+            thread_id,
+            body,
+        });
+        self.apply_comment_thread_response(request, cx)
+    }
+
+    pub fn resolve_comment_thread(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("project is not shared")));
+        };
+        let request = self.client.request(proto::ResolveProjectCommentThread {
+            project_id,
+            thread_id,
+            resolved,
+        });
+        self.apply_comment_thread_response(request, cx)
+    }
+
+    /// Replaces the threads with the ones stored on the server, after joining or rejoining the
+    /// project.
+    pub(crate) fn refresh_comment_threads(
+        &mut self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Ok(()));
+        };
+        let request = self
+            .client
+            .request(proto::GetProjectCommentThreads { project_id });
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            this.update(&mut cx, |this, cx| {
+                this.comment_threads = response
+                    .threads
+                    .into_iter()
+                    .filter_map(CommentThread::from_proto)
+                    .map(|thread| (thread.id, thread))
+                    .collect();
+                cx.emit(Event::CommentThreadsChanged);
+            })
+        })
+    }
+
+    pub(crate) fn clear_comment_threads(&mut self, cx: &mut ModelContext<Self>) {
+        if !self.comment_threads.is_empty() {
+            self.comment_threads.clear();
+            cx.emit(Event::CommentThreadsChanged);
+        }
+    }
+
+    fn apply_comment_thread_response(
+        &mut self,
+        request: impl 'static + Future<Output = Result<proto::ProjectCommentThreadResponse>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            let thread = response.thread.context("missing comment thread")?;
+            this.update(&mut cx, |this, cx| this.insert_comment_thread(thread, cx))
+        })
+    }
+
+    fn insert_comment_thread(
+        &mut self,
+        thread: proto::ProjectCommentThread,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(thread) = CommentThread::from_proto(thread) {
+            self.comment_threads.insert(thread.id, thread);
+            cx.emit(Event::CommentThreadsChanged);
+        }
+    }
+
+    pub(crate) async fn handle_update_project_comment_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateProjectCommentThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = envelope.payload.thread.context("missing comment thread")?;
+        this.update(&mut cx, |this, cx| this.insert_comment_thread(thread, cx))
+    }
+}
@@ -21,15 +21,19 @@ pub struct Manager {
 }
 
 pub fn init(client: Arc<Client>, cx: &mut AppContext) {
-    let manager = cx.new_model(|_| Manager {
-        client,
-        maintain_connection: None,
-        projects: HashSet::default(),
-    });
+    let manager = cx.new_model(|_| Manager::new(client));
     cx.set_global(GlobalManager(manager));
 }
 
 impl Manager {
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            maintain_connection: None,
+            projects: HashSet::default(),
+        }
+    }
+
     pub fn global(cx: &AppContext) -> Model<Manager> {
         cx.global::<GlobalManager>().0.clone()
     }
@@ -94,6 +98,7 @@ impl Manager {
                                     }
                                 })
                                 .collect(),
+                            replica_id: Some(project.replica_id() as u32),
                         })
                     } else {
                         None
@@ -0,0 +1,119 @@
+//! Keeps a bounded history of the contents of local files as they're saved, formatted, changed
+//! on disk or reverted, so that their previous contents can be recovered even if they were never
+//! committed.
+
+use anyhow::{anyhow, Result};
+use futures::future::BoxFuture;
+use gpui::{AppContext, Global};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::SystemTime};
+use text::Rope;
+
+/// Files that are larger than this many bytes aren't recorded in the local history.
+pub const MAX_SNAPSHOT_LEN: usize = 4 * 1024 * 1024;
+
+/// The event that caused a snapshot to be recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotOrigin {
+    /// The contents that were written to disk when the file was saved.
+    Save,
+    /// The contents of the buffer right before a formatter changed them.
+    Format,
+    /// The contents that were loaded after the file changed on disk.
+    ExternalChange,
+    /// The unsaved contents of the buffer that were discarded when it was reverted.
+    Revert,
+}
+
+impl SnapshotOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotOrigin::Save => "save",
+            SnapshotOrigin::Format => "format",
+            SnapshotOrigin::ExternalChange => "external_change",
+            SnapshotOrigin::Revert => "revert",
+        }
+    }
+
+    /// A description of the snapshot to show to the user.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnapshotOrigin::Save => "Saved",
+            SnapshotOrigin::Format => "Before formatting",
+            SnapshotOrigin::ExternalChange => "Changed on disk",
+            SnapshotOrigin::Revert => "Before reverting",
+        }
+    }
+}
+
+impl FromStr for SnapshotOrigin {
+    type Err = anyhow::Error;
+
+    fn from_str(origin: &str) -> Result<Self> {
+        match origin {
+            "save" => Ok(SnapshotOrigin::Save),
+            "format" => Ok(SnapshotOrigin::Format),
+            "external_change" => Ok(SnapshotOrigin::ExternalChange),
+            "revert" => Ok(SnapshotOrigin::Revert),
+            _ => Err(anyhow!("invalid snapshot origin {origin:?}")),
+        }
+    }
+}
+
+/// A recorded version of a file's contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalHistorySnapshot {
+    pub abs_path: PathBuf,
+    /// The hash of the snapshot's contents, which the contents are stored under.
+    pub content_hash: String,
+    pub origin: SnapshotOrigin,
+    pub recorded_at: SystemTime,
+}
+
+/// Storage for the local history of files, keyed by their absolute paths.
+pub trait LocalHistoryStore: 'static + Send + Sync {
+    /// Records a snapshot of a file's contents, unless they're the same as the contents of the
+    /// file's latest snapshot.
+    fn record(
+        &self,
+        abs_path: PathBuf,
+        origin: SnapshotOrigin,
+        text: String,
+    ) -> BoxFuture<'static, Result<()>>;
+
+    /// Returns the snapshots of a file, newest first.
+    fn snapshots(&self, abs_path: PathBuf)
+        -> BoxFuture<'static, Result<Vec<LocalHistorySnapshot>>>;
+
+    /// Loads the contents of a snapshot.
+    fn load(&self, snapshot: &LocalHistorySnapshot) -> BoxFuture<'static, Result<String>>;
+}
+
+struct GlobalLocalHistoryStore(Arc<dyn LocalHistoryStore>);
+
+impl Global for GlobalLocalHistoryStore {}
+
+pub fn set_local_history_store(store: Arc<dyn LocalHistoryStore>, cx: &mut AppContext) {
+    cx.set_global(GlobalLocalHistoryStore(store));
+}
+
+pub fn local_history_store(cx: &AppContext) -> Option<Arc<dyn LocalHistoryStore>> {
+    cx.try_global::<GlobalLocalHistoryStore>()
+        .map(|store| store.0.clone())
+}
+
+/// Records a snapshot of a file in the background, if a local history store is set.
+pub(crate) fn record_snapshot(
+    abs_path: PathBuf,
+    origin: SnapshotOrigin,
+    text: &Rope,
+    cx: &AppContext,
+) {
+    if text.len() > MAX_SNAPSHOT_LEN {
+        return;
+    }
+    if let Some(store) = local_history_store(cx) {
+        cx.background_executor()
+            .spawn(store.record(abs_path, origin, text.to_string()))
+            .detach_and_log_err(cx);
+    }
+}
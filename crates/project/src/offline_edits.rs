@@ -0,0 +1,241 @@
+//! Edits that a guest makes while it can't reach the host of a project.
+//!
+//! A guest keeps editing its replicas of the buffers while its connection is down. Once it
+//! has rejoined the project, the operations that the host hasn't seen are sent as part of the
+//! buffer synchronization. Operations merge without loss, so conflicts are only reported where
+//! the offline edits interleave with edits that other collaborators made in the meantime.
+//!
+//! A guest that doesn't reconnect before the server gives up on it leaves the project, so its
+//! buffers are stashed instead. When it joins the project again, it reopens them and replays
+//! the edits that the host hasn't seen on top of the host's text, as a new replica.
+
+use crate::{Event, Item as _, Project, ProjectPath};
+use collections::HashMap;
+use gpui::{AppContext, Global, Model, ModelContext};
+use language::Buffer;
+use std::ops::Range;
+use text::{Anchor, Edit};
+
+/// The buffers of the projects that we left while we were offline, keyed by the projects'
+/// remote ids.
+#[derive(Default)]
+struct StashedOfflineEdits(HashMap<u64, Vec<StashedBuffer>>);
+
+impl Global for StashedOfflineEdits {}
+
+struct StashedBuffer {
+    path: ProjectPath,
+    snapshot: text::BufferSnapshot,
+}
+
+impl Project {
+    /// Emits [`Event::OfflineEditsConflicted`] if the edits we made to the buffer before
+    /// rejoining conflict with the host's, once the host's edits have been received.
+    pub(crate) fn report_offline_edit_conflicts(
+        &mut self,
+        buffer: Model<Buffer>,
+        offline_version: clock::Global,
+        host_version: clock::Global,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let host_edits = buffer.update(cx, |buffer, _| {
+            buffer.wait_for_version(host_version.clone())
+        });
+        cx.spawn(|this, mut cx| async move {
+            host_edits.await?;
+            this.update(&mut cx, |_, cx| {
+                let ranges =
+                    offline_edit_conflicts(buffer.read(cx), &offline_version, &host_version);
+                if !ranges.is_empty() {
+                    cx.emit(Event::OfflineEditsConflicted { buffer, ranges });
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Keeps the buffers we edited, so that edits the host may not have received can be
+    /// replayed if we join the project again. Only done when we leave the project because
+    /// we lost our connection.
+    pub(crate) fn stash_offline_edits(&self, cx: &mut AppContext) {
+        if self.client.status().borrow().is_connected() {
+            return;
+        }
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+
+        let buffers = self
+            .opened_buffers()
+            .into_iter()
+            .filter_map(|buffer| {
+                let buffer = buffer.read(cx);
+                let snapshot = buffer.text_snapshot();
+                if snapshot.version().get(snapshot.replica_id()) == 0 {
+                    return None;
+                }
+                Some(StashedBuffer {
+                    path: buffer.project_path(cx)?,
+                    snapshot,
+                })
+            })
+            .collect::<Vec<_>>();
+        if !buffers.is_empty() {
+            cx.default_global::<StashedOfflineEdits>()
+                .0
+                .insert(project_id, buffers);
+        }
+    }
+
+    /// Reopens the buffers stashed when we last left this project while offline, and
+    /// replays the edits that the host hasn't seen.
+    pub(crate) fn replay_stashed_offline_edits(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(buffers) = cx
+            .default_global::<StashedOfflineEdits>()
+            .0
+            .remove(&project_id)
+        else {
+            return;
+        };
+
+        for stashed in buffers {
+            let open_buffer = self.open_buffer(stashed.path.clone(), cx);
+            cx.spawn(|this, mut cx| async move {
+                let buffer = open_buffer.await?;
+                this.update(&mut cx, |_, cx| {
+                    let ranges = buffer.update(cx, |buffer, cx| {
+                        replay_offline_edits(buffer, &stashed.snapshot, cx)
+                    });
+                    if !ranges.is_empty() {
+                        cx.emit(Event::OfflineEditsConflicted { buffer, ranges });
+                    }
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+}
+
+/// Returns the ranges of our offline edits that touch edits made concurrently by other
+/// replicas, given our version and the host's version when we rejoined.
+fn offline_edit_conflicts(
+    buffer: &Buffer,
+    offline_version: &clock::Global,
+    host_version: &clock::Global,
+) -> Vec<Range<Anchor>> {
+    let replica_id = buffer.replica_id();
+    let mut base_version = offline_version.clone();
+    base_version.meet(host_version);
+
+    // Observing every other replica's edits leaves only ours, and everything that we had
+    // seen when we rejoined leaves only everyone else's.
+    let mut since_offline_edits = base_version;
+    for timestamp in buffer.version().iter() {
+        if timestamp.replica_id != replica_id {
+            since_offline_edits.observe(timestamp);
+        }
+    }
+    let concurrent_edits = buffer
+        .edits_since::<usize>(offline_version)
+        .collect::<Vec<_>>();
+    buffer
+        .anchored_edits_since::<usize>(&since_offline_edits)
+        .filter(|(edit, _)| {
+            concurrent_edits.iter().any(|concurrent_edit| {
+                concurrent_edit.new.start <= edit.new.end
+                    && edit.new.start <= concurrent_edit.new.end
+            })
+        })
+        .map(|(_, range)| range)
+        .collect()
+}
+
+/// Applies the edits of a stashed replica of the buffer that the buffer hasn't seen, and
+/// returns the ranges of those that touch edits made concurrently by other replicas.
+///
+/// Like operations merging, replaying an edit only deletes the text that was there when it
+/// was made, so text that other replicas inserted in the meantime is kept.
+fn replay_offline_edits(
+    buffer: &mut Buffer,
+    stashed: &text::BufferSnapshot,
+    cx: &mut ModelContext<Buffer>,
+) -> Vec<Range<Anchor>> {
+    let mut base_version = stashed.version().clone();
+    base_version.meet(&buffer.version());
+
+    // Both replicas' edits are expressed in the text that they shared before going apart.
+    let concurrent_edits = buffer
+        .edits_since::<usize>(&base_version)
+        .collect::<Vec<_>>();
+    let mut edits = Vec::new();
+    let mut conflicts = Vec::new();
+    for offline_edit in stashed.edits_since::<usize>(&base_version) {
+        let insertion = old_to_new(&concurrent_edits, offline_edit.old.start);
+        let text = stashed.text_for_range(offline_edit.new).collect::<String>();
+
+        // Only delete the parts of the range that other replicas didn't replace.
+        let mut deletions = Vec::new();
+        let mut deleted_start = offline_edit.old.start;
+        let mut conflicts_with_concurrent_edit = false;
+        for concurrent_edit in &concurrent_edits {
+            if concurrent_edit.old.start > offline_edit.old.end {
+                break;
+            } else if concurrent_edit.old.end < offline_edit.old.start {
+                continue;
+            }
+            conflicts_with_concurrent_edit = true;
+            if concurrent_edit.old.start > deleted_start {
+                deletions.push(deleted_start..concurrent_edit.old.start);
+            }
+            deleted_start = deleted_start.max(concurrent_edit.old.end);
+        }
+        if deleted_start < offline_edit.old.end {
+            deletions.push(deleted_start..offline_edit.old.end);
+        }
+        let mut deletions = deletions
+            .into_iter()
+            .map(|range| {
+                let start = old_to_new(&concurrent_edits, range.start);
+                start..start + range.len()
+            })
+            .collect::<Vec<_>>();
+
+        let end = deletions.last().map_or(insertion, |range| range.end);
+        if deletions
+            .first()
+            .map_or(false, |range| range.start == insertion)
+        {
+            edits.push((deletions.remove(0), text));
+        } else {
+            edits.push((insertion..insertion, text));
+        }
+        edits.extend(deletions.into_iter().map(|range| (range, String::new())));
+        if conflicts_with_concurrent_edit {
+            conflicts.push(buffer.anchor_before(insertion)..buffer.anchor_after(end));
+        }
+    }
+
+    if !edits.is_empty() {
+        buffer.edit(edits, None, cx);
+    }
+    conflicts
+}
+
+/// Maps an offset in the text before the given edits to the text after them. Offsets in
+/// replaced text are moved to the start of the replacement.
+fn old_to_new(edits: &[Edit<usize>], offset: usize) -> usize {
+    let mut new_offset = offset;
+    for edit in edits {
+        if edit.old.start > offset {
+            break;
+        } else if edit.old.end <= offset {
+            new_offset = edit.new.end + (offset - edit.old.end);
+        } else {
+            new_offset = edit.new.start;
+        }
+    }
+    new_offset
+}
@@ -1,7 +1,11 @@
+pub mod buffer_history;
+pub mod comments;
 pub mod connection_manager;
 pub mod debounced_delay;
+pub mod local_history;
 pub mod lsp_command;
 pub mod lsp_ext_command;
+mod offline_edits;
 mod prettier_support;
 pub mod project_settings;
 pub mod search;
@@ -20,6 +24,7 @@ use client::{
 };
 use clock::ReplicaId;
 use collections::{btree_map, hash_map, BTreeMap, HashMap, HashSet, VecDeque};
+use comments::CommentThread;
 use debounced_delay::DebouncedDelay;
 use futures::{
     channel::{
@@ -51,11 +56,12 @@ use language::{
         serialize_line_ending, serialize_version, split_operations,
     },
     range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel,
-    ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation,
+    ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation, Encoding,
     Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName, LocalFile,
     LspAdapterDelegate, Operation, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot,
     ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
+use local_history::SnapshotOrigin;
 use log::error;
 use lsp::{
     CompletionContext, DiagnosticSeverity, DiagnosticTag, DidChangeWatchedFilesRegistrationOptions,
@@ -75,10 +81,12 @@ use prettier_support::{DefaultPrettier, PrettierInstance};
 use project_settings::{LspSettings, ProjectSettings};
 use rand::prelude::*;
 use rpc::{ErrorCode, ErrorExt as _};
-use search::SearchQuery;
+use search::{SearchQuery, SearchScope, SearchScopeFilter};
 use search_history::SearchHistory;
 use serde::Serialize;
-use settings::{watch_config_file, Settings, SettingsLocation, SettingsStore};
+use settings::{
+    watch_config_file, Settings, SettingsLocation, SettingsStore, EDITORCONFIG_FILE_NAME,
+};
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use smol::channel::{Receiver, Sender};
@@ -107,14 +115,15 @@ use task::{
     static_source::{StaticSource, TrackedFile},
     RevealStrategy, TaskContext, TaskTemplate, TaskVariables, VariableName,
 };
+use terminal::Terminal;
 use terminals::Terminals;
 use text::{Anchor, BufferId, LineEnding};
 use unicase::UniCase;
 use util::{
-    debug_panic, defer, maybe, merge_json_value_into, parse_env_output, post_inc,
-    NumericPrefixWithSuffix, ResultExt, TryFutureExt as _,
+    debug_panic, defer, maybe, merge_json_value_into, parse_env_output, paths::PathMatcher,
+    post_inc, NumericPrefixWithSuffix, ResultExt, TryFutureExt as _,
 };
-use worktree::{CreatedEntry, RemoteWorktreeClient, Snapshot, Traversal};
+use worktree::{CreatedEntry, LargeFileReader, RemoteWorktreeClient, Snapshot, Traversal};
 
 pub use fs::*;
 pub use language::Location;
@@ -199,6 +208,7 @@ pub struct Project {
     client_subscriptions: Vec<client::Subscription>,
     _subscriptions: Vec<gpui::Subscription>,
     loading_buffers: HashMap<BufferId, Vec<oneshot::Sender<Result<Model<Buffer>, anyhow::Error>>>>,
+    loading_large_files: HashMap<BufferId, LargeFileLoadProgress>,
     incomplete_remote_buffers: HashMap<BufferId, Model<Buffer>>,
     shared_buffers: HashMap<proto::PeerId, HashSet<BufferId>>,
     #[allow(clippy::type_complexity)]
@@ -229,6 +239,7 @@ pub struct Project {
     hosted_project_id: Option<ProjectId>,
     dev_server_project_id: Option<client::DevServerProjectId>,
     search_history: SearchHistory,
+    comment_threads: BTreeMap<u64, CommentThread>,
 }
 
 pub enum LanguageServerToQuery {
@@ -270,6 +281,24 @@ enum OpenBuffer {
     Operations(Vec<Operation>),
 }
 
+/// The kinds of settings files that are read from worktrees.
+#[derive(Clone, Copy, Debug)]
+enum LocalSettingsKind {
+    /// A `.zed/settings.json` file.
+    Settings,
+    /// An `.editorconfig` file.
+    Editorconfig,
+}
+
+impl LocalSettingsKind {
+    fn to_proto(self) -> proto::LocalSettingsKind {
+        match self {
+            Self::Settings => proto::LocalSettingsKind::Settings,
+            Self::Editorconfig => proto::LocalSettingsKind::Editorconfig,
+        }
+    }
+}
+
 #[derive(Clone)]
 enum WorktreeHandle {
     Strong(Model<Worktree>),
@@ -356,6 +385,17 @@ pub enum Event {
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    CommentThreadsChanged,
+    /// A buffer was opened in the project, either by us or by a collaborator.
+    BufferOpened(Model<Buffer>),
+    /// The host of the project shared one of its terminals with us.
+    TerminalShared(Model<Terminal>),
+    /// Edits we made to the buffer while we couldn't reach the host touch edits that other
+    /// collaborators made in the meantime.
+    OfflineEditsConflicted {
+        buffer: Model<Buffer>,
+        ranges: Vec<Range<Anchor>>,
+    },
 }
 
 pub enum LanguageServerState {
@@ -388,6 +428,16 @@ pub struct LanguageServerProgress {
     pub last_update_at: Instant,
 }
 
+/// The progress of reading a file that was opened in large-file mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LargeFileLoadProgress {
+    pub path: ProjectPath,
+    /// How many bytes of the file have been read so far.
+    pub read_len: u64,
+    /// The size of the file in bytes.
+    pub len: u64,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct ProjectPath {
     pub worktree_id: WorktreeId,
@@ -649,7 +699,14 @@ impl Project {
     pub fn init(client: &Arc<Client>, cx: &mut AppContext) {
         connection_manager::init(client.clone(), cx);
         Self::init_settings(cx);
+        Self::register_client_handlers(client);
+    }
 
+    /// Registers the handlers that route project messages received by the given client.
+    ///
+    /// [`Project::init`] does this for the global client; clients that are connected to other
+    /// servers, such as the ones tunneled over SSH, need to call this once before joining.
+    pub fn register_client_handlers(client: &Arc<Client>) {
         client.add_model_message_handler(Self::handle_add_collaborator);
         client.add_model_message_handler(Self::handle_update_project_collaborator);
         client.add_model_message_handler(Self::handle_remove_collaborator);
@@ -704,6 +761,11 @@ impl Project {
         client.add_model_request_handler(Self::handle_task_context_for_location);
         client.add_model_request_handler(Self::handle_task_templates);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_model_message_handler(Self::handle_update_project_comment_thread);
+        client.add_model_message_handler(Self::handle_share_terminal);
+        client.add_model_message_handler(Self::handle_unshare_terminal);
+        client.add_model_message_handler(Self::handle_update_terminal);
+        client.add_model_request_handler(Self::handle_terminal_input);
     }
 
     pub fn local(
@@ -735,6 +797,7 @@ impl Project {
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 loading_buffers: HashMap::default(),
+                loading_large_files: HashMap::default(),
                 incomplete_remote_buffers: HashMap::default(),
                 client_subscriptions: Vec::new(),
                 _subscriptions: vec![
@@ -767,6 +830,8 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    shared: HashMap::default(),
+                    remote: HashMap::default(),
                 },
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: Some(node),
@@ -777,6 +842,7 @@ impl Project {
                 hosted_project_id: None,
                 dev_server_project_id: None,
                 search_history: Self::new_search_history(),
+                comment_threads: Default::default(),
             }
         })
     }
@@ -799,6 +865,40 @@ impl Project {
         Ok(project)
     }
 
+    /// Opens the project at the given path on the server at the other end of the client's
+    /// tunnel, rejoining it whenever the tunnel is reopened.
+    pub async fn ssh(
+        path: String,
+        client: Arc<Client>,
+        user_store: Model<UserStore>,
+        languages: Arc<LanguageRegistry>,
+        fs: Arc<dyn Fs>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Model<Self>> {
+        client.authenticate_and_connect(true, &cx).await?;
+        let response = client.request(proto::OpenSshProject { path }).await?;
+        let project = Self::in_room(
+            response.project_id,
+            client.clone(),
+            user_store,
+            languages,
+            fs,
+            cx.clone(),
+        )
+        .await?;
+
+        let manager = cx.new_model(|_| connection_manager::Manager::new(client))?;
+        manager.update(&mut cx, |manager, cx| {
+            manager.maintain_project_connection(&project, cx)
+        })?;
+        project.update(&mut cx, |_, cx| {
+            // The manager is only reachable through the project, so keep it alive until the
+            // project is released.
+            cx.on_release(move |_, _| drop(manager)).detach();
+        })?;
+        Ok(project)
+    }
+
     pub async fn in_room(
         remote_id: u64,
         client: Arc<Client>,
@@ -865,6 +965,7 @@ impl Project {
                 buffer_ordered_messages_tx: tx,
                 loading_buffers_by_path: Default::default(),
                 loading_buffers: Default::default(),
+                loading_large_files: Default::default(),
                 shared_buffers: Default::default(),
                 incomplete_remote_buffers: Default::default(),
                 loading_local_worktrees: Default::default(),
@@ -926,6 +1027,8 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    shared: HashMap::default(),
+                    remote: HashMap::default(),
                 },
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: None,
@@ -939,6 +1042,7 @@ impl Project {
                     .dev_server_project_id
                     .map(|dev_server_project_id| DevServerProjectId(dev_server_project_id)),
                 search_history: Self::new_search_history(),
+                comment_threads: Default::default(),
             };
             this.set_role(role, cx);
             for worktree in worktrees {
@@ -961,6 +1065,8 @@ impl Project {
         this.update(&mut cx, |this, cx| {
             this.set_collaborators_from_proto(response.payload.collaborators, cx)?;
             this.client_subscriptions.push(subscription);
+            this.refresh_comment_threads(cx).detach_and_log_err(cx);
+            this.replay_stashed_offline_edits(cx);
             anyhow::Ok(())
         })??;
 
@@ -1134,8 +1240,10 @@ impl Project {
                                 .push((file.worktree.clone(), Arc::clone(language)));
                         }
                     }
-                    language_formatters_to_check
-                        .push((buffer_file.map(|f| f.worktree_id(cx)), settings.clone()));
+                    language_formatters_to_check.push((
+                        buffer_file.map(|f| f.worktree_id(cx)),
+                        settings.into_owned(),
+                    ));
                 }
             }
         }
@@ -1564,6 +1672,20 @@ impl Project {
                         worktree_id,
                         path: path.to_string_lossy().into(),
                         content: Some(content),
+                        kind: proto::LocalSettingsKind::Settings.into(),
+                    })
+                    .log_err();
+            }
+            for (directory, content) in
+                store.local_editorconfigs(worktree.entity_id().as_u64() as usize)
+            {
+                self.client
+                    .send(proto::UpdateWorktreeSettings {
+                        project_id,
+                        worktree_id,
+                        path: directory.to_string_lossy().into(),
+                        content: Some(content),
+                        kind: proto::LocalSettingsKind::Editorconfig.into(),
                     })
                     .log_err();
             }
@@ -1702,6 +1824,7 @@ impl Project {
         self.shared_buffers.clear();
         self.set_collaborators_from_proto(message.collaborators, cx)?;
         self.metadata_changed(cx);
+        self.resend_shared_terminals(cx);
         Ok(())
     }
 
@@ -1739,12 +1862,15 @@ impl Project {
             .collect();
         self.enqueue_buffer_ordered_message(BufferOrderedMessage::Resync)
             .unwrap();
+        self.refresh_comment_threads(cx).detach_and_log_err(cx);
         cx.notify();
         Ok(())
     }
 
     pub fn unshare(&mut self, cx: &mut ModelContext<Self>) -> Result<()> {
         self.unshare_internal(cx)?;
+        self.clear_comment_threads(cx);
+        self.clear_shared_terminals();
         self.metadata_changed(cx);
         cx.notify();
         Ok(())
@@ -1805,7 +1931,10 @@ impl Project {
         if self.is_disconnected() {
             return;
         }
+        self.stash_offline_edits(cx);
         self.disconnected_from_host_internal(cx);
+        self.clear_comment_threads(cx);
+        self.close_remote_terminals(cx);
         cx.emit(Event::DisconnectedFromHost);
         cx.notify();
     }
@@ -2059,55 +2188,187 @@ impl Project {
         worktree: Model<Worktree>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Model<Buffer>>> {
+        let history_store = buffer_history::buffer_history_store(cx);
         let load_buffer = worktree.update(cx, |worktree, cx| {
             let load_file = worktree.load_file(path.as_ref(), cx);
+            let abs_path = worktree.absolutize(&path).ok();
             let reservation = cx.reserve_model();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
             cx.spawn(move |_, mut cx| async move {
                 let loaded = load_file.await?;
-                let text_buffer = cx
-                    .background_executor()
-                    .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
-                    .await;
-                cx.insert_model(reservation, |_| {
-                    Buffer::build(
-                        text_buffer,
-                        loaded.diff_base,
-                        Some(loaded.file),
-                        Capability::ReadWrite,
+                // Large files are read incrementally once the buffer is open, so their
+                // undo history isn't restored.
+                let (text_buffer, capability) = if loaded.large_file.is_some() {
+                    (
+                        text::Buffer::new(0, buffer_id, loaded.text),
+                        Capability::ReadOnly,
                     )
-                })
+                } else {
+                    let text_buffer = cx
+                        .background_executor()
+                        .spawn(buffer_history::load_text_buffer(
+                            history_store,
+                            abs_path,
+                            buffer_id,
+                            loaded.text,
+                        ))
+                        .await;
+                    // Files that had to be decoded lossily stay read-only, so that saving
+                    // them doesn't replace their invalid bytes.
+                    let capability = if loaded.lossy {
+                        Capability::ReadOnly
+                    } else {
+                        Capability::ReadWrite
+                    };
+                    (text_buffer, capability)
+                };
+                let is_large_file = loaded.large_file.is_some();
+                let buffer = cx.insert_model(reservation, |_| {
+                    let buffer =
+                        Buffer::build(text_buffer, loaded.diff_base, Some(loaded.file), capability)
+                            .with_encoding(loaded.encoding);
+                    if is_large_file {
+                        buffer.with_large_file_mode()
+                    } else {
+                        buffer
+                    }
+                })?;
+                anyhow::Ok((buffer, loaded.lossy, loaded.large_file))
             })
         });
 
         cx.spawn(move |this, mut cx| async move {
-            let buffer = match load_buffer.await {
-                Ok(buffer) => Ok(buffer),
-                Err(error) if is_not_found_error(&error) => cx.new_model(|cx| {
-                    let buffer_id = BufferId::from(cx.entity_id().as_non_zero_u64());
-                    let text_buffer = text::Buffer::new(0, buffer_id, "".into());
-                    Buffer::build(
-                        text_buffer,
-                        None,
-                        Some(Arc::new(File {
-                            worktree,
-                            path,
-                            mtime: None,
-                            entry_id: None,
-                            is_local: true,
-                            is_deleted: false,
-                            is_private: false,
-                        })),
-                        Capability::ReadWrite,
-                    )
-                }),
+            let (buffer, lossy, large_file) = match load_buffer.await {
+                Ok(loaded) => Ok(loaded),
+                Err(error) if is_not_found_error(&error) => cx
+                    .new_model(|cx| {
+                        let buffer_id = BufferId::from(cx.entity_id().as_non_zero_u64());
+                        let text_buffer = text::Buffer::new(0, buffer_id, "".into());
+                        Buffer::build(
+                            text_buffer,
+                            None,
+                            Some(Arc::new(File {
+                                worktree: worktree.clone(),
+                                path: path.clone(),
+                                mtime: None,
+                                entry_id: None,
+                                is_local: true,
+                                is_deleted: false,
+                                is_private: false,
+                            })),
+                            Capability::ReadWrite,
+                        )
+                    })
+                    .map(|buffer| (buffer, false, None)),
                 Err(e) => Err(e),
             }?;
             this.update(&mut cx, |this, cx| this.register_buffer(&buffer, cx))??;
+            if lossy {
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(Event::Notification(format!(
+                        "{:?} contains bytes that aren't valid {}. The file was opened \
+                        read-only; reopen it with a different encoding to edit it.",
+                        path,
+                        buffer.read(cx).encoding().name()
+                    )));
+                })?;
+            }
+            if let Some(large_file) = large_file {
+                this.update(&mut cx, |this, cx| {
+                    let project_path = ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path,
+                    };
+                    this.load_large_file(buffer.clone(), project_path, large_file, cx)
+                })?;
+            }
             Ok(buffer)
         })
     }
 
+    /// Reads the rest of a file that was opened in large-file mode into its buffer,
+    /// one chunk at a time, so that the buffer can be viewed while it loads.
+    fn load_large_file(
+        &mut self,
+        buffer: Model<Buffer>,
+        path: ProjectPath,
+        mut reader: LargeFileReader,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        self.loading_large_files.insert(
+            buffer_id,
+            LargeFileLoadProgress {
+                path: path.clone(),
+                read_len: reader.read_len(),
+                len: reader.len(),
+            },
+        );
+        cx.notify();
+
+        let buffer = buffer.downgrade();
+        cx.spawn(move |this, mut cx| async move {
+            let result = async {
+                loop {
+                    let (chunk, returned_reader) = cx
+                        .background_executor()
+                        .spawn(async move {
+                            let chunk = reader.next_chunk();
+                            (chunk, reader)
+                        })
+                        .await;
+                    reader = returned_reader;
+                    let Some(chunk) = chunk? else {
+                        break;
+                    };
+
+                    let Some(buffer) = buffer.upgrade() else {
+                        break;
+                    };
+                    buffer.update(&mut cx, |buffer, cx| buffer.append_loaded_text(chunk, cx))?;
+                    this.update(&mut cx, |this, cx| {
+                        if let Some(progress) = this.loading_large_files.get_mut(&buffer_id) {
+                            progress.read_len = reader.read_len();
+                            cx.notify();
+                        }
+                    })?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            // Buffers whose file couldn't be read completely stay read-only, so that
+            // saving them doesn't truncate the file.
+            if result.is_ok() {
+                if let Some(buffer) = buffer.upgrade() {
+                    buffer
+                        .update(&mut cx, |buffer, cx| {
+                            buffer.set_capability(Capability::ReadWrite, cx)
+                        })
+                        .ok();
+                }
+            }
+            let result = result.with_context(|| format!("failed to load {:?}", path.path));
+            this.update(&mut cx, |this, cx| {
+                this.loading_large_files.remove(&buffer_id);
+                if let Err(error) = &result {
+                    cx.emit(Event::Notification(format!(
+                        "{error:#}. The file was opened read-only."
+                    )));
+                }
+                cx.notify();
+            })
+            .ok();
+            result
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// The files that were opened in large-file mode and are still being read.
+    pub fn large_file_load_progress(&self) -> impl Iterator<Item = &LargeFileLoadProgress> {
+        self.loading_large_files.values()
+    }
+
     fn open_remote_buffer_internal(
         &mut self,
         path: &Arc<Path>,
@@ -2231,12 +2492,42 @@ impl Project {
         let worktree = file.worktree.clone();
         let path = file.path.clone();
         if self.is_local() {
+            if buffer.read(cx).read_only() {
+                return Task::ready(Err(anyhow!(
+                    "can't save {path:?} because it was opened read-only"
+                )));
+            }
             self.save_local_buffer(worktree, buffer, path, false, cx)
         } else {
             self.save_remote_buffer(buffer, None, cx)
         }
     }
 
+    /// Saves a buffer, converting its file to the given encoding. The buffer keeps its
+    /// previous encoding if the text can't be represented in the new one.
+    pub fn save_buffer_with_encoding(
+        &self,
+        buffer: Model<Buffer>,
+        encoding: Encoding,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let previous_encoding = buffer.update(cx, |buffer, cx| {
+            let previous_encoding = buffer.encoding();
+            buffer.set_encoding(encoding, cx);
+            previous_encoding
+        });
+        let save = self.save_buffer(buffer.clone(), cx);
+        cx.spawn(move |_, mut cx| async move {
+            let result = save.await;
+            if result.is_err() {
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.set_encoding(previous_encoding, cx)
+                })?;
+            }
+            result
+        })
+    }
+
     pub fn save_buffer_as(
         &mut self,
         buffer: Model<Buffer>,
@@ -2279,15 +2570,36 @@ impl Project {
     ) -> Task<Result<()>> {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if self.loading_large_files.contains_key(&buffer_id) {
+            return Task::ready(Err(anyhow!("can't save a file that is still loading")));
+        }
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         if buffer.file().is_some_and(|file| !file.is_created()) {
             has_changed_file = true;
         }
 
+        let save_history = buffer_history::buffer_history_store(cx)
+            .filter(|_| !buffer.is_large_file())
+            .zip(worktree.read(cx).absolutize(&path).ok())
+            .map(|(store, abs_path)| {
+                buffer_history::save_history(
+                    store,
+                    abs_path,
+                    text.to_string(),
+                    buffer.persisted_history(),
+                )
+            });
+
+        let local_history_snapshot = local_history::local_history_store(cx)
+            .filter(|_| !buffer.is_large_file())
+            .zip(worktree.read(cx).absolutize(&path).ok())
+            .map(|(_, abs_path)| (abs_path, text.clone()));
+
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         let client = self.client.clone();
@@ -2319,6 +2631,7 @@ impl Project {
                     buffer_id: buffer_id.into(),
                     version: serialize_version(&version),
                     mtime: mtime.map(|time| time.into()),
+                    encoding: Some(language::proto::serialize_encoding(encoding)),
                 })?;
             }
 
@@ -2326,6 +2639,18 @@ impl Project {
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
+            if let Some((abs_path, text)) = local_history_snapshot {
+                cx.update(|cx| {
+                    local_history::record_snapshot(abs_path, SnapshotOrigin::Save, &text, cx)
+                })?;
+            }
+
+            if let Some(save_history) = save_history {
+                cx.background_executor()
+                    .spawn(save_history)
+                    .detach_and_log_err(&cx);
+            }
+
             Ok(())
         })
     }
@@ -2339,6 +2664,7 @@ impl Project {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id().into();
         let version = buffer.version();
+        let encoding = buffer.encoding();
         let rpc = self.client.clone();
         let project_id = self.remote_id();
         cx.spawn(move |_, mut cx| async move {
@@ -2348,12 +2674,17 @@ impl Project {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    encoding: Some(language::proto::serialize_encoding(encoding)),
                 })
                 .await?;
             let version = deserialize_version(&response.version);
             let mtime = response.mtime.map(|mtime| mtime.into());
+            let encoding = language::proto::deserialize_encoding(response.encoding);
 
             buffer_handle.update(&mut cx, |buffer, cx| {
+                if let Some(encoding) = encoding {
+                    buffer.set_encoding(encoding, cx);
+                }
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
@@ -2462,6 +2793,7 @@ impl Project {
                 sender.send(Ok(buffer.clone())).ok();
             }
         }
+        cx.emit(Event::BufferOpened(buffer.clone()));
         Ok(())
     }
 
@@ -2472,6 +2804,9 @@ impl Project {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.is_large_file() {
+            return;
+        }
 
         if let Some(file) = File::from_dyn(buffer.file()) {
             if !file.is_local() {
@@ -2729,9 +3064,25 @@ impl Project {
                                 version: serialize_version(&buffer.version()),
                                 mtime: buffer.saved_mtime().map(|t| t.into()),
                                 line_ending: serialize_line_ending(buffer.line_ending()) as i32,
+                                encoding: Some(language::proto::serialize_encoding(
+                                    buffer.encoding(),
+                                )),
                             })
                             .log_err();
                     }
+
+                    let buffer = buffer.read(cx);
+                    if !buffer.is_dirty() && !buffer.is_large_file() {
+                        if let Some(file) = File::from_dyn(buffer.file()).and_then(|f| f.as_local())
+                        {
+                            local_history::record_snapshot(
+                                file.abs_path(cx),
+                                SnapshotOrigin::ExternalChange,
+                                buffer.as_rope(),
+                                cx,
+                            );
+                        }
+                    }
                 }
             }
 
@@ -3146,6 +3497,9 @@ impl Project {
     ) {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        if buffer.is_large_file() {
+            return;
+        }
         let Some(file) = buffer.file() else {
             return;
         };
@@ -3170,6 +3524,40 @@ impl Project {
         };
     }
 
+    /// Waits until the buffer's language has been loaded and its syntax tree is up to date, so
+    /// that it can be searched structurally.
+    fn parse_buffer_for_search(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let buffer = buffer_handle.read(cx);
+        let load_language = if buffer.is_large_file() || buffer.language().is_some() {
+            None
+        } else {
+            buffer.file().map(|file| {
+                self.languages
+                    .language_for_file(file, Some(buffer.as_rope()), cx)
+            })
+        };
+        let buffer_handle = buffer_handle.clone();
+        cx.spawn(move |this, mut cx| async move {
+            if let Some(language) = load_language {
+                if let Ok(language) = language.await {
+                    this.update(&mut cx, |this, cx| {
+                        this.set_language_for_buffer(&buffer_handle, language, cx)
+                    })
+                    .ok();
+                }
+            }
+            if let Ok(parsing_idle) =
+                buffer_handle.read_with(&cx, |buffer, _| buffer.parsing_idle())
+            {
+                parsing_idle.await;
+            }
+        })
+    }
+
     pub fn set_language_for_buffer(
         &mut self,
         buffer: &Model<Buffer>,
@@ -3185,7 +3573,8 @@ impl Project {
         });
 
         let buffer_file = buffer.read(cx).file().cloned();
-        let settings = language_settings(Some(&new_language), buffer_file.as_ref(), cx).clone();
+        let settings =
+            language_settings(Some(&new_language), buffer_file.as_ref(), cx).into_owned();
         let buffer_file = File::from_dyn(buffer_file.as_ref());
         let worktree = buffer_file.as_ref().map(|f| f.worktree_id(cx));
         if let Some(prettier_plugins) = prettier_support::prettier_plugins_for_language(&settings) {
@@ -4901,14 +5290,48 @@ impl Project {
         buffers: HashSet<Model<Buffer>>,
         push_to_history: bool,
         cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.reload_buffers_internal(buffers, None, push_to_history, cx)
+    }
+
+    /// Reloads a buffer from disk, decoding its file with the given encoding,
+    /// even if the buffer has no unsaved changes.
+    pub fn reload_buffer_with_encoding(
+        &self,
+        buffer: Model<Buffer>,
+        encoding: Encoding,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let reload =
+            self.reload_buffers_internal([buffer].into_iter().collect(), Some(encoding), true, cx);
+        cx.background_executor().spawn(async move {
+            reload.await?;
+            Ok(())
+        })
+    }
+
+    fn reload_buffers_internal(
+        &self,
+        buffers: HashSet<Model<Buffer>>,
+        encoding: Option<Encoding>,
+        push_to_history: bool,
+        cx: &mut ModelContext<Self>,
     ) -> Task<Result<ProjectTransaction>> {
         let mut local_buffers = Vec::new();
         let mut remote_buffers = None;
         for buffer_handle in buffers {
             let buffer = buffer_handle.read(cx);
-            if buffer.is_dirty() {
+            if buffer.is_dirty() || encoding.is_some() {
                 if let Some(file) = File::from_dyn(buffer.file()) {
-                    if file.is_local() {
+                    if let Some(file) = file.as_local() {
+                        if buffer.is_dirty() && !buffer.is_large_file() {
+                            local_history::record_snapshot(
+                                file.abs_path(cx),
+                                SnapshotOrigin::Revert,
+                                buffer.as_rope(),
+                                cx,
+                            );
+                        }
                         local_buffers.push(buffer_handle);
                     } else {
                         remote_buffers.get_or_insert(Vec::new()).push(buffer_handle);
@@ -4935,6 +5358,7 @@ impl Project {
                                     .ok()
                             })
                             .collect(),
+                        encoding: encoding.map(language::proto::serialize_encoding),
                     })
                     .await?
                     .transaction
@@ -4948,9 +5372,16 @@ impl Project {
 
             for buffer in local_buffers {
                 let transaction = buffer
-                    .update(&mut cx, |buffer, cx| buffer.reload(cx))?
+                    .update(&mut cx, |buffer, cx| {
+                        buffer.reload_with_encoding(encoding.unwrap_or(buffer.encoding()), cx)
+                    })?
                     .await?;
                 buffer.update(&mut cx, |buffer, cx| {
+                    // The file decoded without errors, so a buffer that was opened
+                    // read-only because it had to be decoded lossily can be edited again.
+                    if encoding.is_some() && !buffer.is_large_file() {
+                        buffer.set_capability(Capability::ReadWrite, cx);
+                    }
                     if let Some(transaction) = transaction {
                         if !push_to_history {
                             buffer.forget_transaction(transaction.id);
@@ -5065,6 +5496,10 @@ impl Project {
 
         let mut project_transaction = ProjectTransaction::default();
         for (buffer, buffer_abs_path) in &buffers_with_paths {
+            let text_before_formatting = buffer.read_with(&cx, |buffer, _| {
+                (!buffer.is_large_file()).then(|| buffer.as_rope().clone())
+            })?;
+
             let (primary_adapter_and_server, adapters_and_servers) =
                 project.update(&mut cx, |project, cx| {
                     let buffer = buffer.read(cx);
@@ -5082,7 +5517,7 @@ impl Project {
                 })?;
 
             let settings = buffer.update(&mut cx, |buffer, cx| {
-                language_settings(buffer.language(), buffer.file(), cx).clone()
+                language_settings(buffer.language(), buffer.file(), cx).into_owned()
             })?;
 
             let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
@@ -5281,6 +5716,15 @@ impl Project {
                     project_transaction.0.insert(buffer.clone(), transaction);
                 }
             })?;
+
+            if project_transaction.0.contains_key(buffer) {
+                if let Some((abs_path, text)) = buffer_abs_path.clone().zip(text_before_formatting)
+                {
+                    cx.update(|cx| {
+                        local_history::record_snapshot(abs_path, SnapshotOrigin::Format, &text, cx)
+                    })?;
+                }
+            }
         }
 
         Ok(project_transaction)
@@ -7091,6 +7535,160 @@ impl Project {
         &self,
         query: SearchQuery,
         cx: &mut ModelContext<Self>,
+    ) -> Receiver<SearchResult> {
+        if *query.scope() == SearchScope::All {
+            return self.search_local_in_scope(query, None, cx);
+        }
+
+        let scope_filter = self.search_scope_filter(query.scope(), cx);
+        let (result_tx, result_rx) = smol::channel::bounded(1024);
+        cx.spawn(|this, mut cx| async move {
+            let scope_filter = scope_filter.await?.map(Arc::new);
+            let results = this.update(&mut cx, |this, cx| {
+                this.search_local_in_scope(query, scope_filter, cx)
+            })?;
+            while let Ok(result) = results.recv().await {
+                if result_tx.send(result).await.is_err() {
+                    break;
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+        result_rx
+    }
+
+    /// Returns the names of the search scopes defined in the settings of the visible worktrees.
+    pub fn named_search_scopes(&self, cx: &AppContext) -> Vec<String> {
+        let mut names = Vec::new();
+        for worktree in self.visible_worktrees(cx) {
+            let settings = ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.read(cx).id().to_proto() as usize,
+                    path: Path::new(""),
+                }),
+                cx,
+            );
+            names.extend(settings.search_scopes.keys().cloned());
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Resolves a search scope to the files that it contains, or to `None` if it contains
+    /// every file in the project.
+    fn search_scope_filter(
+        &self,
+        scope: &SearchScope,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SearchScopeFilter>>> {
+        match scope {
+            SearchScope::All => Task::ready(Ok(None)),
+            SearchScope::OpenBuffers(buffer_ids) => {
+                let mut filter = SearchScopeFilter::empty();
+                for buffer_id in buffer_ids {
+                    let Some(buffer) = self.buffer_for_id(*buffer_id) else {
+                        continue;
+                    };
+                    match buffer.read(cx).project_path(cx) {
+                        Some(project_path) => filter.insert_path(project_path),
+                        None => filter.insert_unnamed_buffer(*buffer_id),
+                    }
+                }
+                Task::ready(Ok(Some(filter)))
+            }
+            SearchScope::GitChanges { base: None } => {
+                let mut filter = SearchScopeFilter::empty();
+                for worktree in self.visible_worktrees(cx) {
+                    let worktree = worktree.read(cx);
+                    for entry in worktree.files(false, 0) {
+                        if entry.git_status.is_some() {
+                            filter.insert_path(ProjectPath {
+                                worktree_id: worktree.id(),
+                                path: entry.path.clone(),
+                            });
+                        }
+                    }
+                }
+                Task::ready(Ok(Some(filter)))
+            }
+            SearchScope::GitChanges { base: Some(base) } => {
+                let repositories = self
+                    .visible_worktrees(cx)
+                    .filter_map(|worktree| {
+                        let worktree = worktree.read(cx).as_local()?;
+                        Some(
+                            worktree
+                                .repositories()
+                                .filter_map(|(work_directory, repository)| {
+                                    let repository = worktree.get_local_repo(repository)?;
+                                    Some((
+                                        worktree.id(),
+                                        work_directory.clone(),
+                                        repository.repo().clone(),
+                                    ))
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                    .flatten()
+                    .collect::<Vec<_>>();
+                let base = base.clone();
+                cx.background_executor().spawn(async move {
+                    let mut filter = SearchScopeFilter::empty();
+                    for (worktree_id, work_directory, repository) in repositories {
+                        for path in repository.changed_paths(&base)? {
+                            filter.insert_path(ProjectPath {
+                                worktree_id,
+                                path: work_directory.join(&*path).into(),
+                            });
+                        }
+                    }
+                    Ok(Some(filter))
+                })
+            }
+            SearchScope::Named(name) => {
+                let mut globs = HashMap::default();
+                for worktree in self.visible_worktrees(cx) {
+                    let worktree_id = worktree.read(cx).id();
+                    let settings = ProjectSettings::get(
+                        Some(SettingsLocation {
+                            worktree_id: worktree_id.to_proto() as usize,
+                            path: Path::new(""),
+                        }),
+                        cx,
+                    );
+                    if let Some(scope) = settings.search_scopes.get(name) {
+                        match (
+                            PathMatcher::new(&scope.include),
+                            PathMatcher::new(&scope.exclude),
+                        ) {
+                            (Ok(include), Ok(exclude)) => {
+                                globs.insert(worktree_id, (include, exclude));
+                            }
+                            (Err(error), _) | (_, Err(error)) => {
+                                return Task::ready(Err(anyhow!(
+                                    "invalid glob in search scope {name:?}: {error}"
+                                )));
+                            }
+                        }
+                    }
+                }
+                if globs.is_empty() {
+                    Task::ready(Err(anyhow!("no search scope named {name:?}")))
+                } else {
+                    Task::ready(Ok(Some(SearchScopeFilter::Globs(globs))))
+                }
+            }
+        }
+    }
+
+    fn search_local_in_scope(
+        &self,
+        query: SearchQuery,
+        scope_filter: Option<Arc<SearchScopeFilter>>,
+        cx: &mut ModelContext<Self>,
     ) -> Receiver<SearchResult> {
         // Local search is split into several phases.
         // TL;DR is that we do 2 passes; initial pass to pick files which contain at least one match
@@ -7152,15 +7750,20 @@ impl Project {
             .iter()
             .filter_map(|(_, b)| {
                 let buffer = b.upgrade()?;
-                let (is_ignored, snapshot) = buffer.update(cx, |buffer, cx| {
-                    let is_ignored = buffer
-                        .project_path(cx)
-                        .and_then(|path| self.entry_for_path(&path, cx))
+                let (project_path, is_ignored, snapshot) = buffer.update(cx, |buffer, cx| {
+                    let project_path = buffer.project_path(cx);
+                    let is_ignored = project_path
+                        .as_ref()
+                        .and_then(|path| self.entry_for_path(path, cx))
                         .map_or(false, |entry| entry.is_ignored);
-                    (is_ignored, buffer.snapshot())
+                    (project_path, is_ignored, buffer.snapshot())
                 });
                 if is_ignored && !query.include_ignored() {
                     return None;
+                } else if scope_filter.as_ref().map_or(false, |filter| {
+                    !filter.contains_buffer(project_path.as_ref(), snapshot.remote_id())
+                }) {
+                    return None;
                 } else if let Some(file) = snapshot.file() {
                     let matched_path = if include_root {
                         query.file_matches(Some(&file.full_path(cx)))
@@ -7187,6 +7790,7 @@ impl Project {
                 self.fs.clone(),
                 workers,
                 query.clone(),
+                scope_filter,
                 include_root,
                 path_count,
                 snapshots,
@@ -7236,8 +7840,15 @@ impl Project {
                         })?,
                     };
 
-                    chunk_results.push(cx.spawn(|cx| async move {
+                    let this = this.clone();
+                    chunk_results.push(cx.spawn(|mut cx| async move {
                         let buffer = buffer.await?;
+                        if query.is_structural() {
+                            this.update(&mut cx, |this, cx| {
+                                this.parse_buffer_for_search(&buffer, cx)
+                            })?
+                            .await;
+                        }
                         let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
                         let ranges = cx
                             .background_executor()
@@ -7292,6 +7903,7 @@ impl Project {
         fs: Arc<dyn Fs>,
         workers: usize,
         query: SearchQuery,
+        scope_filter: Option<Arc<SearchScopeFilter>>,
         include_root: bool,
         path_count: usize,
         snapshots: Vec<(Snapshot, WorktreeSettings)>,
@@ -7299,6 +7911,7 @@ impl Project {
     ) {
         let fs = &fs;
         let query = &query;
+        let scope_filter = scope_filter.as_deref();
         let matching_paths_tx = &matching_paths_tx;
         let snapshots = &snapshots;
         for buffer in unnamed_buffers {
@@ -7339,6 +7952,7 @@ impl Project {
                                 worker_start_ix,
                                 worker_end_ix,
                                 query,
+                                scope_filter,
                                 matching_paths_tx,
                                 &opened_buffers,
                                 include_root,
@@ -7361,6 +7975,7 @@ impl Project {
                                     ignored_entry,
                                     fs,
                                     query,
+                                    scope_filter,
                                     matching_paths_tx,
                                 )
                                 .await;
@@ -8201,6 +8816,7 @@ impl Project {
                 let fs = self.fs.clone();
                 settings_contents.push(async move {
                     (
+                        LocalSettingsKind::Settings,
                         settings_dir,
                         if removed {
                             None
@@ -8209,6 +8825,20 @@ impl Project {
                         },
                     )
                 });
+            } else if path.file_name() == Some(OsStr::new(EDITORCONFIG_FILE_NAME)) {
+                let directory = Arc::from(path.parent().unwrap_or(Path::new("")));
+                let fs = self.fs.clone();
+                settings_contents.push(async move {
+                    (
+                        LocalSettingsKind::Editorconfig,
+                        directory,
+                        if removed {
+                            None
+                        } else {
+                            Some(async move { fs.load(&abs_path).await }.await)
+                        },
+                    )
+                });
             } else if path.ends_with(local_tasks_file_relative_path()) {
                 self.task_inventory().update(cx, |task_inventory, cx| {
                     if removed {
@@ -8264,20 +8894,31 @@ impl Project {
 
         let client = self.client.clone();
         cx.spawn(move |_, cx| async move {
-            let settings_contents: Vec<(Arc<Path>, _)> =
+            let settings_contents: Vec<(LocalSettingsKind, Arc<Path>, _)> =
                 futures::future::join_all(settings_contents).await;
             cx.update(|cx| {
                 cx.update_global::<SettingsStore, _>(|store, cx| {
-                    for (directory, file_content) in settings_contents {
+                    for (kind, directory, file_content) in settings_contents {
                         let file_content = file_content.and_then(|content| content.log_err());
-                        store
-                            .set_local_settings(
-                                worktree_id.as_u64() as usize,
-                                directory.clone(),
-                                file_content.as_deref(),
-                                cx,
-                            )
-                            .log_err();
+                        match kind {
+                            LocalSettingsKind::Settings => {
+                                store
+                                    .set_local_settings(
+                                        worktree_id.as_u64() as usize,
+                                        directory.clone(),
+                                        file_content.as_deref(),
+                                        cx,
+                                    )
+                                    .log_err();
+                            }
+                            LocalSettingsKind::Editorconfig => {
+                                store.set_local_editorconfig(
+                                    worktree_id.as_u64() as usize,
+                                    directory.clone(),
+                                    file_content.as_deref(),
+                                );
+                            }
+                        }
                         if let Some(remote_id) = project_id {
                             client
                                 .send(proto::UpdateWorktreeSettings {
@@ -8285,6 +8926,7 @@ impl Project {
                                     worktree_id: remote_worktree_id.to_proto(),
                                     path: directory.to_string_lossy().into_owned(),
                                     content: file_content,
+                                    kind: kind.to_proto().into(),
                                 })
                                 .log_err();
                         }
@@ -8730,6 +9372,7 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            this.resend_shared_terminals(cx);
             cx.notify();
         })?;
 
@@ -8773,6 +9416,8 @@ impl Project {
                     .retain(|_, buffer| !matches!(buffer, OpenBuffer::Operations(_)));
                 this.enqueue_buffer_ordered_message(BufferOrderedMessage::Resync)
                     .unwrap();
+            } else {
+                this.resend_shared_terminals(cx);
             }
 
             cx.emit(Event::CollaboratorUpdated {
@@ -8851,15 +9496,25 @@ impl Project {
         this.update(&mut cx, |this, cx| {
             let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
             if let Some(worktree) = this.worktree_for_id(worktree_id, cx) {
-                cx.update_global::<SettingsStore, _>(|store, cx| {
-                    store
-                        .set_local_settings(
+                let path = PathBuf::from(&envelope.payload.path);
+                cx.update_global::<SettingsStore, _>(|store, cx| match envelope.payload.kind() {
+                    proto::LocalSettingsKind::Settings => {
+                        store
+                            .set_local_settings(
+                                worktree.entity_id().as_u64() as usize,
+                                path.into(),
+                                envelope.payload.content.as_deref(),
+                                cx,
+                            )
+                            .log_err();
+                    }
+                    proto::LocalSettingsKind::Editorconfig => {
+                        store.set_local_editorconfig(
                             worktree.entity_id().as_u64() as usize,
-                            PathBuf::from(&envelope.payload.path).into(),
+                            path.into(),
                             envelope.payload.content.as_deref(),
-                            cx,
-                        )
-                        .log_err();
+                        );
+                    }
                 });
             }
             Ok(())
@@ -9258,7 +9913,13 @@ impl Project {
                 buffer.wait_for_version(deserialize_version(&envelope.payload.version))
             })?
             .await?;
-        let buffer_id = buffer.update(&mut cx, |buffer, _| buffer.remote_id())?;
+        let encoding = language::proto::deserialize_encoding(envelope.payload.encoding);
+        let buffer_id = buffer.update(&mut cx, |buffer, cx| {
+            if let Some(encoding) = encoding {
+                buffer.set_encoding(encoding, cx);
+            }
+            buffer.remote_id()
+        })?;
 
         if let Some(new_path) = envelope.payload.new_path {
             let new_path = ProjectPath::from_proto(new_path);
@@ -9276,6 +9937,7 @@ impl Project {
             buffer_id: buffer_id.into(),
             version: serialize_version(buffer.saved_version()),
             mtime: buffer.saved_mtime().map(|time| time.into()),
+            encoding: Some(language::proto::serialize_encoding(buffer.encoding())),
         })
     }
 
@@ -9296,7 +9958,8 @@ impl Project {
                         .ok_or_else(|| anyhow!("unknown buffer id {}", buffer_id))?,
                 );
             }
-            Ok::<_, anyhow::Error>(this.reload_buffers(buffers, false, cx))
+            let encoding = language::proto::deserialize_encoding(envelope.payload.encoding.clone());
+            Ok::<_, anyhow::Error>(this.reload_buffers_internal(buffers, encoding, false, cx))
         })??;
 
         let project_transaction = reload.await?;
@@ -9369,6 +10032,7 @@ impl Project {
                             line_ending: language::proto::serialize_line_ending(
                                 buffer.line_ending(),
                             ) as i32,
+                            encoding: Some(language::proto::serialize_encoding(buffer.encoding())),
                         })
                         .log_err();
 
@@ -10300,9 +10964,11 @@ impl Project {
                         };
                         let remote_version = language::proto::deserialize_version(&buffer.version);
                         if let Some(buffer) = this.buffer_for_id(buffer_id) {
-                            let operations =
-                                buffer.read(cx).serialize_ops(Some(remote_version), cx);
-                            cx.background_executor().spawn(async move {
+                            let operations = buffer
+                                .read(cx)
+                                .serialize_ops(Some(remote_version.clone()), cx);
+                            let offline_version = buffer.read(cx).version();
+                            cx.spawn(move |this, mut cx| async move {
                                 let operations = operations.await;
                                 for chunk in split_operations(operations) {
                                     client
@@ -10313,6 +10979,18 @@ impl Project {
                                         })
                                         .await?;
                                 }
+                                // The host hasn't seen the edits we made while we couldn't
+                                // reach it.
+                                if !remote_version.observed_all(&offline_version) {
+                                    this.update(&mut cx, |this, cx| {
+                                        this.report_offline_edit_conflicts(
+                                            buffer,
+                                            offline_version,
+                                            remote_version,
+                                            cx,
+                                        )
+                                    })?;
+                                }
                                 anyhow::Ok(())
                             })
                         } else {
@@ -10510,6 +11188,7 @@ impl Project {
         let version = deserialize_version(&envelope.payload.version);
         let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
         let mtime = envelope.payload.mtime.map(|time| time.into());
+        let encoding = language::proto::deserialize_encoding(envelope.payload.encoding);
 
         this.update(&mut cx, |this, cx| {
             let buffer = this
@@ -10519,6 +11198,9 @@ impl Project {
                 .or_else(|| this.incomplete_remote_buffers.get(&buffer_id).cloned());
             if let Some(buffer) = buffer {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_save(version, mtime, cx);
                 });
             }
@@ -10537,6 +11219,7 @@ impl Project {
             proto::LineEnding::from_i32(payload.line_ending)
                 .ok_or_else(|| anyhow!("missing line ending"))?,
         );
+        let encoding = language::proto::deserialize_encoding(payload.encoding);
         let mtime = payload.mtime.map(|time| time.into());
         let buffer_id = BufferId::new(payload.buffer_id)?;
         this.update(&mut cx, |this, cx| {
@@ -10547,7 +11230,8 @@ impl Project {
                 .or_else(|| this.incomplete_remote_buffers.get(&buffer_id).cloned());
             if let Some(buffer) = buffer {
                 buffer.update(cx, |buffer, cx| {
-                    buffer.did_reload(version, line_ending, mtime, cx);
+                    let encoding = encoding.unwrap_or(buffer.encoding());
+                    buffer.did_reload(version, line_ending, encoding, mtime, cx);
                 });
             }
             Ok(())
@@ -11158,6 +11842,7 @@ async fn search_snapshots(
     worker_start_ix: usize,
     worker_end_ix: usize,
     query: &SearchQuery,
+    scope_filter: Option<&SearchScopeFilter>,
     results_tx: &Sender<SearchMatchCandidate>,
     opened_buffers: &HashMap<Arc<Path>, (Model<Buffer>, BufferSnapshot)>,
     include_root: bool,
@@ -11192,6 +11877,11 @@ async fn search_snapshots(
                 if opened_buffers.contains_key(&entry.path) {
                     continue;
                 }
+                if scope_filter.map_or(false, |filter| {
+                    !filter.contains_path(snapshot.id(), &entry.path)
+                }) {
+                    continue;
+                }
 
                 let matched_path = if include_root {
                     let mut full_path = PathBuf::from(snapshot.root_name());
@@ -11238,6 +11928,7 @@ async fn search_ignored_entry(
     ignored_entry: &Entry,
     fs: &Arc<dyn Fs>,
     query: &SearchQuery,
+    scope_filter: Option<&SearchScopeFilter>,
     counter_tx: &Sender<SearchMatchCandidate>,
 ) {
     let mut ignored_paths_to_process =
@@ -11272,6 +11963,14 @@ async fn search_ignored_entry(
                 {
                     continue;
                 }
+                if let Some(scope_filter) = scope_filter {
+                    let Ok(path) = ignored_abs_path.strip_prefix(snapshot.abs_path()) else {
+                        continue;
+                    };
+                    if !scope_filter.contains_path(snapshot.id(), path) {
+                        continue;
+                    }
+                }
                 let matches = if let Some(file) = fs
                     .open_sync(&ignored_abs_path)
                     .await
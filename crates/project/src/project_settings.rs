@@ -20,6 +20,27 @@ pub struct ProjectSettings {
     /// Configuration for Git-related features
     #[serde(default)]
     pub git: GitSettings,
+
+    /// Named sets of files that project search can be limited to.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub search_scopes: HashMap<String, SearchScopeSettings>,
+}
+
+/// The files in a named search scope, as globs relative to the worktree root.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct SearchScopeSettings {
+    /// The files to search. When empty, all files are searched.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// The files to skip, even if they're included.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -34,9 +55,18 @@ pub struct GitSettings {
     ///
     /// Default: on
     pub inline_blame: Option<InlineBlameSettings>,
+    /// Whether or not to highlight the changed words within
+    /// modified lines of expanded diff hunks.
+    ///
+    /// Default: true
+    pub word_diff: Option<bool>,
 }
 
 impl GitSettings {
+    pub fn word_diff_enabled(&self) -> bool {
+        self.word_diff.unwrap_or(true)
+    }
+
     pub fn inline_blame_enabled(&self) -> bool {
         #[allow(unknown_lints, clippy::manual_unwrap_or_default)]
         match self.inline_blame {
@@ -1,6 +1,7 @@
 use crate::{Event, *};
 use fs::FakeFs;
 use futures::{future, StreamExt};
+use git::repository::{GitFileStatus, RepoPath};
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
 use language::{
     language_settings::{AllLanguageSettings, LanguageSettingsContent},
@@ -294,6 +295,87 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_editorconfig_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".editorconfig": r#"
+                root = true
+
+                [*]
+                indent_style = tab
+                indent_size = 4
+                insert_final_newline = true
+
+                [*.md]
+                trim_trailing_whitespace = false
+                max_line_length = 100
+            "#.unindent(),
+            "a": {
+                "a.rs": "fn a() {}",
+                "README.md": "# A",
+            },
+            "b": {
+                ".zed": {
+                    "settings.json": r#"{ "tab_size": 2 }"#,
+                },
+                "b.rs": "fn b() {}"
+            }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree = project.update(cx, |project, _| project.worktrees().next().unwrap());
+    cx.executor().run_until_parked();
+
+    let settings_for = |path: &str, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let tree = worktree.read(cx);
+            let file =
+                File::for_entry(tree.entry_for_path(path).unwrap().clone(), worktree.clone());
+            language_settings(None, Some(&(file as _)), cx).into_owned()
+        })
+    };
+
+    let settings_a = settings_for("a/a.rs", cx);
+    assert_eq!(settings_a.tab_size.get(), 4);
+    assert!(settings_a.hard_tabs);
+    assert!(settings_a.ensure_final_newline_on_save);
+    assert!(settings_a.remove_trailing_whitespace_on_save);
+
+    let readme_settings = settings_for("a/README.md", cx);
+    assert!(!readme_settings.remove_trailing_whitespace_on_save);
+    assert_eq!(readme_settings.preferred_line_length, 100);
+
+    // Project settings take precedence over `.editorconfig` files.
+    let settings_b = settings_for("b/b.rs", cx);
+    assert_eq!(settings_b.tab_size.get(), 2);
+    assert!(settings_b.hard_tabs);
+
+    // Edits to `.editorconfig` files are applied right away.
+    fs.insert_file(
+        "/the-root/a/.editorconfig",
+        "[*.rs]\nindent_style = space\nindent_size = 3\n".into(),
+    )
+    .await;
+    cx.executor().run_until_parked();
+    let settings_a = settings_for("a/a.rs", cx);
+    assert_eq!(settings_a.tab_size.get(), 3);
+    assert!(!settings_a.hard_tabs);
+    assert!(settings_a.ensure_final_newline_on_save);
+
+    fs.remove_file("/the-root/a/.editorconfig".as_ref(), Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(settings_for("a/a.rs", cx).tab_size.get(), 4);
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3534,6 +3616,423 @@ async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_buffer_encodings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file("/dir/latin1.txt", b"na\xEFve caf\xE9\n".to_vec())
+        .await;
+    fs.insert_file("/dir/bom.txt", b"\xEF\xBB\xBFone\r\ntwo\r\n".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer1 = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/latin1.txt", cx))
+        .await
+        .unwrap();
+    let buffer2 = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/bom.txt", cx))
+        .await
+        .unwrap();
+
+    let windows_1252 = Encoding::from_name("windows-1252", false).unwrap();
+    let utf8_bom = Encoding::from_name("utf-8", true).unwrap();
+    buffer1.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "naïve café\n");
+        assert_eq!(buffer.encoding(), windows_1252);
+    });
+    buffer2.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "one\ntwo\n");
+        assert_eq!(buffer.encoding(), utf8_bom);
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+    });
+
+    // Saving preserves the encoding, the byte order mark and the line endings.
+    buffer1.update(cx, |buffer, cx| buffer.edit([(0..0, "très ")], None, cx));
+    buffer2.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer1.clone(), cx))
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer2.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes("/dir/latin1.txt".as_ref()).await.unwrap(),
+        b"tr\xE8s na\xEFve caf\xE9\n"
+    );
+    assert_eq!(
+        fs.load_bytes("/dir/bom.txt".as_ref()).await.unwrap(),
+        b"\xEF\xBB\xBFzero\r\none\r\ntwo\r\n"
+    );
+
+    // Saving text that the encoding can't represent fails, leaving the file untouched.
+    buffer1.update(cx, |buffer, cx| buffer.edit([(0..0, "🍐 ")], None, cx));
+    let save = project.update(cx, |project, cx| {
+        project.save_buffer_with_encoding(
+            buffer1.clone(),
+            Encoding::from_name("iso-8859-2", false).unwrap(),
+            cx,
+        )
+    });
+    assert!(save.await.is_err());
+    assert_eq!(
+        buffer1.update(cx, |buffer, _| buffer.encoding()),
+        windows_1252
+    );
+    assert!(buffer1.update(cx, |buffer, _| buffer.is_dirty()));
+    assert_eq!(
+        fs.load_bytes("/dir/latin1.txt".as_ref()).await.unwrap(),
+        b"tr\xE8s na\xEFve caf\xE9\n"
+    );
+
+    // Converting it to an encoding that can represent the text succeeds.
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer_with_encoding(buffer1.clone(), Encoding::UTF8, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        buffer1.update(cx, |buffer, _| buffer.encoding()),
+        Encoding::UTF8
+    );
+    assert_eq!(
+        fs.load("/dir/latin1.txt".as_ref()).await.unwrap(),
+        "🍐 très naïve café\n"
+    );
+
+    // Reopening a buffer with another encoding decodes its file again.
+    project
+        .update(cx, |project, cx| {
+            project.reload_buffer_with_encoding(buffer2.clone(), windows_1252, cx)
+        })
+        .await
+        .unwrap();
+    buffer2.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "ï»¿zero\none\ntwo\n");
+        assert_eq!(buffer.encoding(), windows_1252);
+        assert!(!buffer.is_dirty());
+    });
+}
+
+#[gpui::test]
+async fn test_buffer_with_invalid_bytes(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    // The file starts with a UTF-16LE byte order mark, but ends in the middle of a character.
+    let bytes = b"\xFF\xFE\xE5\x65\x2C\x67A".to_vec();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file("/dir/truncated.txt", bytes.clone()).await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let notifications = Arc::new(Mutex::new(Vec::new()));
+    project.update(cx, |_, cx| {
+        let notifications = notifications.clone();
+        cx.subscribe(&project, move |_, _, event, _| {
+            if let Event::Notification(message) = event {
+                notifications.lock().push(message.clone());
+            }
+        })
+        .detach();
+    });
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/truncated.txt", cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    // The invalid bytes are replaced, but the buffer stays read-only and can't be saved,
+    // so that they aren't lost.
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "日本\u{FFFD}");
+        assert!(buffer.read_only());
+    });
+    assert_eq!(notifications.lock().len(), 1);
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "x")], None, cx));
+    let save = project.update(cx, |project, cx| project.save_buffer(buffer.clone(), cx));
+    assert!(save.await.is_err());
+    assert_eq!(
+        fs.load_bytes("/dir/truncated.txt".as_ref()).await.unwrap(),
+        bytes
+    );
+
+    // Reopening the file with an encoding that decodes it makes the buffer editable.
+    project
+        .update(cx, |project, cx| {
+            project.reload_buffer_with_encoding(
+                buffer.clone(),
+                Encoding::from_name("windows-1252", false).unwrap(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "ÿþåe,gA");
+        assert!(!buffer.read_only());
+    });
+}
+
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold = Some(2048);
+            });
+        });
+    });
+
+    // The first line's Windows line ending is split across the chunks that the file
+    // is read in.
+    let text = format!("{}\r\n{}", "x".repeat(1023), "line\r\n".repeat(1000));
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "small.log": "one\ntwo\n" }))
+        .await;
+    fs.insert_file("/dir/large.log", text.clone().into_bytes())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    set_large_file_chunk_len(&project, 1024, cx);
+    let small_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/small.log", cx))
+        .await
+        .unwrap();
+    let large_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/large.log", cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    small_buffer.update(cx, |buffer, _| assert!(!buffer.is_large_file()));
+    large_buffer.update(cx, |buffer, _| {
+        assert!(buffer.is_large_file());
+        assert_eq!(buffer.text(), text.replace("\r\n", "\n"));
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert!(!buffer.is_dirty());
+        assert!(!buffer.read_only());
+        assert!(buffer.language().is_none());
+    });
+    project.update(cx, |project, _| {
+        assert_eq!(project.large_file_load_progress().count(), 0);
+    });
+
+    // Reading the file isn't part of the buffer's undo history.
+    large_buffer.update(cx, |buffer, cx| {
+        assert!(buffer.undo(cx).is_none());
+        buffer.edit([(0..0, "start\n")], None, cx);
+        assert!(buffer.is_dirty());
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), text.replace("\r\n", "\n"));
+        assert!(!buffer.is_dirty());
+    });
+}
+
+#[gpui::test]
+async fn test_large_file_read_error(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold = Some(2048);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "large.log": "line\n".repeat(1000) }))
+        .await;
+    fs.fail_reads_after("/dir/large.log", 2500);
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    set_large_file_chunk_len(&project, 1024, cx);
+    let notifications = Arc::new(Mutex::new(Vec::new()));
+    project.update(cx, |_, cx| {
+        let notifications = notifications.clone();
+        cx.subscribe(&project, move |_, _, event, _| {
+            if let Event::Notification(message) = event {
+                notifications.lock().push(message.clone());
+            }
+        })
+        .detach();
+    });
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/large.log", cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    // The part of the file that was read is shown, but the buffer stays read-only so
+    // that saving it doesn't truncate the file.
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.len(), 2048);
+        assert!(buffer.read_only());
+    });
+    project.update(cx, |project, _| {
+        assert_eq!(project.large_file_load_progress().count(), 0);
+    });
+    assert_eq!(notifications.lock().len(), 1);
+}
+
+fn set_large_file_chunk_len(
+    project: &Model<Project>,
+    chunk_len: usize,
+    cx: &mut gpui::TestAppContext,
+) {
+    project.update(cx, |project, cx| {
+        for worktree in project.worktrees() {
+            worktree.update(cx, |worktree, _| {
+                worktree
+                    .as_local_mut()
+                    .unwrap()
+                    .set_large_file_chunk_len(chunk_len)
+            });
+        }
+    });
+}
+
+#[gpui::test]
+async fn test_local_history_snapshots(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let store = Arc::new(FakeLocalHistoryStore::default());
+    cx.update(|cx| local_history::set_local_history_store(store.clone(), cx));
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.txt": "one\n" })).await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.txt", cx))
+        .await
+        .unwrap();
+
+    // Formatting records the contents of the buffer before they were formatted.
+    buffer.update(cx, |buffer, cx| buffer.set_text("two", cx));
+    project
+        .update(cx, |project, cx| {
+            project.format(
+                HashSet::from_iter([buffer.clone()]),
+                true,
+                FormatTrigger::Manual,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    // Reverting records the unsaved contents that are discarded.
+    buffer.update(cx, |buffer, cx| buffer.set_text("three\n", cx));
+    project
+        .update(cx, |project, cx| {
+            project.reload_buffers(HashSet::from_iter([buffer.clone()]), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    fs.save("/dir/a.txt".as_ref(), &"four\n".into(), LineEnding::Unix)
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(
+        store
+            .0
+            .lock()
+            .iter()
+            .map(|(abs_path, origin, text)| (abs_path.to_str().unwrap(), *origin, text.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("/dir/a.txt", SnapshotOrigin::Format, "two"),
+            ("/dir/a.txt", SnapshotOrigin::Save, "two\n"),
+            ("/dir/a.txt", SnapshotOrigin::Revert, "three\n"),
+            ("/dir/a.txt", SnapshotOrigin::ExternalChange, "two\n"),
+            ("/dir/a.txt", SnapshotOrigin::ExternalChange, "four\n"),
+        ]
+    );
+
+    // Snapshots are listed from the newest to the oldest.
+    use local_history::LocalHistoryStore as _;
+    let snapshots = store.snapshots("/dir/a.txt".into()).await.unwrap();
+    assert_eq!(snapshots.len(), 5);
+    assert_eq!(snapshots[0].origin, SnapshotOrigin::ExternalChange);
+    assert_eq!(store.load(&snapshots[0]).await.unwrap(), "four\n");
+    assert_eq!(store.load(&snapshots[4]).await.unwrap(), "two");
+}
+
+/// Records snapshots in memory, skipping ones that are the same as the latest snapshot of
+/// their file like real stores do.
+#[derive(Default)]
+struct FakeLocalHistoryStore(Mutex<Vec<(PathBuf, SnapshotOrigin, String)>>);
+
+impl local_history::LocalHistoryStore for FakeLocalHistoryStore {
+    fn record(
+        &self,
+        abs_path: PathBuf,
+        origin: SnapshotOrigin,
+        text: String,
+    ) -> future::BoxFuture<'static, Result<()>> {
+        let mut snapshots = self.0.lock();
+        let latest_snapshot = snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.0 == abs_path);
+        if latest_snapshot.map_or(true, |snapshot| snapshot.2 != text) {
+            snapshots.push((abs_path, origin, text));
+        }
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn snapshots(
+        &self,
+        abs_path: PathBuf,
+    ) -> future::BoxFuture<'static, Result<Vec<local_history::LocalHistorySnapshot>>> {
+        let snapshots = self
+            .0
+            .lock()
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, snapshot)| snapshot.0 == abs_path)
+            .map(
+                |(ix, (abs_path, origin, text))| local_history::LocalHistorySnapshot {
+                    abs_path: abs_path.clone(),
+                    content_hash: buffer_history::content_hash(text),
+                    origin: *origin,
+                    recorded_at: std::time::UNIX_EPOCH + Duration::from_secs(ix as u64),
+                },
+            )
+            .collect();
+        Box::pin(future::ready(Ok(snapshots)))
+    }
+
+    fn load(
+        &self,
+        snapshot: &local_history::LocalHistorySnapshot,
+    ) -> future::BoxFuture<'static, Result<String>> {
+        let text = self
+            .0
+            .lock()
+            .iter()
+            .find(|(_, _, text)| buffer_history::content_hash(text) == snapshot.content_hash)
+            .map(|(_, _, text)| text.clone())
+            .ok_or_else(|| anyhow!("no snapshot with hash {}", snapshot.content_hash));
+        Box::pin(future::ready(text))
+    }
+}
+
 #[gpui::test]
 async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3983,6 +4482,175 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_in_scopes(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            ".zed": {
+                "settings.json": r#"{
+                    "search_scopes": {
+                        "server": { "include": ["server/**"], "exclude": ["server/tests/**"] }
+                    }
+                }"#,
+            },
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = 2;",
+            "server": {
+                "three.rs": "const THREE: usize = 3;",
+                "tests": {
+                    "four.rs": "const FOUR: usize = 4;",
+                },
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    fs.set_status_for_repo_via_git_operation(
+        Path::new("/dir/.git"),
+        &[(Path::new("two.rs"), GitFileStatus::Modified)],
+    );
+    fs.with_git_state(Path::new("/dir/.git"), true, |state| {
+        state.changed_paths.insert(
+            "main".into(),
+            vec![
+                RepoPath::new("one.rs".into()),
+                RepoPath::new("server/three.rs".into()),
+            ],
+        );
+    });
+    cx.executor().run_until_parked();
+
+    let query = SearchQuery::text(
+        "usize",
+        false,
+        true,
+        false,
+        Default::default(),
+        Default::default(),
+    )
+    .unwrap();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    assert_eq!(
+        search(
+            &project,
+            query
+                .clone()
+                .with_scope(SearchScope::OpenBuffers(vec![buffer_id])),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), vec![11..16])])
+    );
+
+    assert_eq!(
+        search(
+            &project,
+            query
+                .clone()
+                .with_scope(SearchScope::GitChanges { base: None }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/two.rs".to_string(), vec![11..16])])
+    );
+
+    assert_eq!(
+        search(
+            &project,
+            query.clone().with_scope(SearchScope::GitChanges {
+                base: Some("main".into())
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![11..16]),
+            ("dir/server/three.rs".to_string(), vec![13..18])
+        ])
+    );
+
+    assert_eq!(
+        search(
+            &project,
+            query
+                .clone()
+                .with_scope(SearchScope::Named("server".into())),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/server/three.rs".to_string(), vec![13..18])])
+    );
+
+    assert!(search(
+        &project,
+        query.with_scope(SearchScope::Named("client".into())),
+        cx
+    )
+    .await
+    .unwrap()
+    .is_empty());
+}
+
+#[gpui::test]
+async fn test_search_structural(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "fn one() -> usize {\n    a().unwrap() + b.unwrap()\n}\n",
+            "two.rs": "fn two() -> usize {\n    c.expect(\"c\")\n}\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let query =
+        SearchQuery::structural("$x.unwrap()", false, Default::default(), Default::default())
+            .unwrap()
+            .with_replacement("$x?".to_string());
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([("dir/one.rs".to_string(), vec![24..36, 39..49])])
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(
+        query.structural_replacement_for(&snapshot, 24..36),
+        Some("a()?".to_string())
+    );
+    assert_eq!(
+        query.structural_replacement_for(&snapshot, 39..49),
+        Some("b?".to_string())
+    );
+    assert_eq!(query.structural_replacement_for(&snapshot, 24..49), None);
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1,7 +1,9 @@
+use crate::{ProjectPath, WorktreeId};
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::Result;
 use client::proto;
-use language::{char_kind, BufferSnapshot};
+use collections::{HashMap, HashSet};
+use language::{char_kind, BufferSnapshot, StructuralPattern};
 use regex::{Captures, Regex, RegexBuilder};
 use smol::future::yield_now;
 use std::{
@@ -11,15 +13,141 @@ use std::{
     path::Path,
     sync::{Arc, OnceLock},
 };
+use text::BufferId;
 use util::paths::PathMatcher;
 
 static TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX: OnceLock<Regex> = OnceLock::new();
 
+/// Limits the files that a [`SearchQuery`] searches, in addition to its include and exclude globs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SearchScope {
+    /// All files in the project.
+    #[default]
+    All,
+    /// The given buffers, usually the ones that are open in the workspace.
+    OpenBuffers(Vec<BufferId>),
+    /// The files with uncommitted changes relative to the given git revision, or to `HEAD`
+    /// if no revision is given.
+    GitChanges { base: Option<String> },
+    /// A scope defined in the `search_scopes` project setting.
+    Named(String),
+}
+
+impl SearchScope {
+    pub fn from_proto(scope: Option<proto::SearchScope>) -> Result<Self> {
+        let Some(variant) = scope.and_then(|scope| scope.variant) else {
+            return Ok(Self::All);
+        };
+        Ok(match variant {
+            proto::search_scope::Variant::OpenBuffers(open_buffers) => Self::OpenBuffers(
+                open_buffers
+                    .buffer_ids
+                    .into_iter()
+                    .map(BufferId::new)
+                    .collect::<Result<_>>()?,
+            ),
+            proto::search_scope::Variant::GitChanges(git_changes) => Self::GitChanges {
+                base: git_changes.base,
+            },
+            proto::search_scope::Variant::Named(name) => Self::Named(name),
+        })
+    }
+
+    pub fn to_proto(&self) -> Option<proto::SearchScope> {
+        let variant = match self {
+            Self::All => return None,
+            Self::OpenBuffers(buffer_ids) => {
+                proto::search_scope::Variant::OpenBuffers(proto::search_scope::OpenBuffers {
+                    buffer_ids: buffer_ids.iter().map(|id| (*id).into()).collect(),
+                })
+            }
+            Self::GitChanges { base } => {
+                proto::search_scope::Variant::GitChanges(proto::search_scope::GitChanges {
+                    base: base.clone(),
+                })
+            }
+            Self::Named(name) => proto::search_scope::Variant::Named(name.clone()),
+        };
+        Some(proto::SearchScope {
+            variant: Some(variant),
+        })
+    }
+}
+
+/// The files that a [`SearchScope`] contains in a local project.
+pub(crate) enum SearchScopeFilter {
+    Paths {
+        paths: HashMap<WorktreeId, HashSet<Arc<Path>>>,
+        unnamed_buffers: HashSet<BufferId>,
+    },
+    /// The include and exclude globs of a named scope in each worktree that defines it.
+    Globs(HashMap<WorktreeId, (PathMatcher, PathMatcher)>),
+}
+
+impl SearchScopeFilter {
+    pub(crate) fn empty() -> Self {
+        Self::Paths {
+            paths: HashMap::default(),
+            unnamed_buffers: HashSet::default(),
+        }
+    }
+
+    pub(crate) fn insert_path(&mut self, project_path: ProjectPath) {
+        if let Self::Paths { paths, .. } = self {
+            paths
+                .entry(project_path.worktree_id)
+                .or_default()
+                .insert(project_path.path);
+        }
+    }
+
+    pub(crate) fn insert_unnamed_buffer(&mut self, buffer_id: BufferId) {
+        if let Self::Paths {
+            unnamed_buffers, ..
+        } = self
+        {
+            unnamed_buffers.insert(buffer_id);
+        }
+    }
+
+    pub(crate) fn contains_path(&self, worktree_id: WorktreeId, path: &Path) -> bool {
+        match self {
+            Self::Paths { paths, .. } => paths
+                .get(&worktree_id)
+                .map_or(false, |paths| paths.contains(path)),
+            Self::Globs(globs) => globs.get(&worktree_id).map_or(false, |(include, exclude)| {
+                (include.sources().is_empty() || include.is_match(path)) && !exclude.is_match(path)
+            }),
+        }
+    }
+
+    /// Whether the scope contains the buffer with the given path, or the given untitled buffer.
+    pub(crate) fn contains_buffer(
+        &self,
+        project_path: Option<&ProjectPath>,
+        buffer_id: BufferId,
+    ) -> bool {
+        match (self, project_path) {
+            (_, Some(project_path)) => {
+                self.contains_path(project_path.worktree_id, &project_path.path)
+            }
+            (
+                Self::Paths {
+                    unnamed_buffers, ..
+                },
+                None,
+            ) => unnamed_buffers.contains(&buffer_id),
+            (Self::Globs(_), None) => false,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: PathMatcher,
     files_to_exclude: PathMatcher,
+    scope: SearchScope,
 }
 
 impl SearchInputs {
@@ -32,6 +160,9 @@ impl SearchInputs {
     pub fn files_to_exclude(&self) -> &PathMatcher {
         &self.files_to_exclude
     }
+    pub fn scope(&self) -> &SearchScope {
+        &self.scope
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -53,6 +184,13 @@ pub enum SearchQuery {
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -72,6 +210,7 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            scope: SearchScope::All,
         };
         Ok(Self::Text {
             search: Arc::new(search),
@@ -110,6 +249,7 @@ impl SearchQuery {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            scope: SearchScope::All,
         };
         Ok(Self::Regex {
             regex,
@@ -122,8 +262,38 @@ impl SearchQuery {
         })
     }
 
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            scope: SearchScope::All,
+        };
+        Ok(Self::Structural {
+            pattern: Arc::new(pattern),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        let scope = SearchScope::from_proto(message.scope)?;
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -141,7 +311,8 @@ impl SearchQuery {
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
             )
-        }
+        }?;
+        Ok(query.with_scope(scope))
     }
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
         match self {
@@ -152,22 +323,38 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
             }
         }
     }
+    pub fn with_scope(mut self, scope: SearchScope) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => {
+                inner.scope = scope;
+                self
+            }
+        }
+    }
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
         proto::SearchProject {
             project_id,
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
             files_to_include: self.files_to_include().sources().join(","),
             files_to_exclude: self.files_to_exclude().sources().join(","),
+            scope: self.scope().to_proto(),
         }
     }
 
@@ -206,19 +393,31 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let mut text = String::new();
+                BufReader::new(stream).read_to_string(&mut text)?;
+                Ok(pattern
+                    .literal_words()
+                    .iter()
+                    .all(|word| text.contains(word.as_str())))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    ///
+    /// Structural queries need the matched syntax nodes to expand their replacements, so they
+    /// must use [`Self::structural_replacement_for`] instead.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
+            SearchQuery::Structural { .. } => None,
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
             SearchQuery::Regex {
                 regex, replacement, ..
@@ -242,6 +441,28 @@ impl SearchQuery {
         }
     }
 
+    /// Returns the replacement for the match of a structural query at the given range of the
+    /// buffer, with the pattern's metavariables substituted by the code they matched.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        let SearchQuery::Structural {
+            pattern,
+            replacement: Some(replacement),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let mat = pattern
+            .matches(buffer, range.clone())
+            .into_iter()
+            .find(|mat| mat.range == range)?;
+        Some(pattern.expand_replacement(replacement, &mat, buffer))
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -254,6 +475,15 @@ impl SearchQuery {
         }
 
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
+        if let Self::Structural { pattern, .. } = self {
+            let range = subrange.unwrap_or(0..buffer.len());
+            return pattern
+                .matches(buffer, range)
+                .into_iter()
+                .map(|mat| mat.range.start - range_offset..mat.range.end - range_offset)
+                .collect();
+        }
+
         let rope = if let Some(range) = subrange {
             buffer.as_rope().slice(range)
         } else {
@@ -326,6 +556,7 @@ impl SearchQuery {
                     }
                 }
             }
+            Self::Structural { .. } => unreachable!(),
         }
 
         matches
@@ -343,6 +574,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -350,6 +582,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -361,6 +594,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -368,6 +604,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -376,6 +616,10 @@ impl SearchQuery {
         self.as_inner().files_to_exclude()
     }
 
+    pub fn scope(&self) -> &SearchScope {
+        self.as_inner().scope()
+    }
+
     pub fn file_matches(&self, file_path: Option<&Path>) -> bool {
         match file_path {
             Some(file_path) => {
@@ -397,7 +641,9 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 }
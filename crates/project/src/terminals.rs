@@ -1,8 +1,14 @@
-use crate::Project;
-use anyhow::Context as _;
+use crate::{Event, Project};
+use anyhow::{anyhow, bail, Context as _, Result};
+use client::{proto, TypedEnvelope};
 use collections::HashMap;
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver},
+    StreamExt,
+};
 use gpui::{
-    AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, SharedString, WeakModel,
+    AnyWindowHandle, AppContext, AsyncAppContext, Context, Entity, Model, ModelContext,
+    SharedString, Subscription, Task, WeakModel,
 };
 use itertools::Itertools;
 use settings::{Settings, SettingsLocation};
@@ -12,6 +18,7 @@ use std::{
     fs::File,
     io::Write,
     path::{Path, PathBuf},
+    time::Duration,
 };
 use task::{SpawnInTerminal, TerminalWorkDir};
 use terminal::{
@@ -23,8 +30,26 @@ use util::ResultExt;
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
+/// How often the screen of a shared terminal is sent to guests while its output changes.
+const SHARED_TERMINAL_UPDATE_INTERVAL: Duration = Duration::from_millis(30);
+
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    /// The local terminals shared with the guests of the project, keyed by their id.
+    pub(crate) shared: HashMap<u64, SharedTerminal>,
+    /// The terminals the host of the project shared with us, keyed by their id, until we close
+    /// them.
+    pub(crate) remote: HashMap<u64, Model<Terminal>>,
+}
+
+pub(crate) struct SharedTerminal {
+    terminal: WeakModel<Terminal>,
+    read_only: bool,
+    /// The screen and title that guests last received, so that only changes are sent.
+    last_screen: Option<proto::TerminalScreen>,
+    last_title: String,
+    pending_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
 }
 
 #[derive(Debug, Clone)]
@@ -303,6 +328,336 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Returns the terminals that the host of this project shared with us.
+    pub fn remote_terminals(&self) -> impl Iterator<Item = &Model<Terminal>> {
+        self.terminals.remote.values()
+    }
+
+    /// Stops receiving updates for a terminal the host shared with us, after we closed it. It's
+    /// shared with us again as a new terminal if the host shares it again.
+    pub fn forget_remote_terminal(&mut self, terminal: &Model<Terminal>) {
+        self.terminals.remote.retain(|_, remote| remote != terminal);
+    }
+
+    /// Returns whether the given local terminal is shared read-only with the guests of this
+    /// project, or `None` if it isn't shared.
+    pub fn shared_terminal_read_only(&self, terminal: &Model<Terminal>) -> Option<bool> {
+        self.terminals
+            .shared
+            .get(&terminal.entity_id().as_u64())
+            .map(|shared| shared.read_only)
+    }
+
+    /// Streams the screen of a local terminal to the guests of this project. Unless it's shared
+    /// read-only, guests with write access to the project can also type into it.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        read_only: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        if self.is_remote() || terminal.read(cx).is_remote() {
+            bail!("only the host can share its terminals");
+        }
+        if self.remote_id().is_none() {
+            bail!("project is not shared");
+        }
+
+        let terminal_id = terminal.entity_id().as_u64();
+        if let Some(shared) = self.terminals.shared.get_mut(&terminal_id) {
+            shared.read_only = read_only;
+        } else {
+            let subscriptions = [
+                cx.subscribe(terminal, move |this, terminal, event, cx| match event {
+                    terminal::Event::Wakeup | terminal::Event::TitleChanged => {
+                        this.schedule_shared_terminal_update(terminal_id, cx)
+                    }
+                    terminal::Event::CloseTerminal => {
+                        this.unshare_terminal(&terminal, cx).log_err();
+                    }
+                    _ => {}
+                }),
+                cx.observe_release(terminal, move |this, _, cx| {
+                    this.unshare_terminal_with_id(terminal_id, cx).log_err();
+                }),
+            ];
+            self.terminals.shared.insert(
+                terminal_id,
+                SharedTerminal {
+                    terminal: terminal.downgrade(),
+                    read_only,
+                    last_screen: None,
+                    last_title: String::new(),
+                    pending_update: None,
+                    _subscriptions: subscriptions,
+                },
+            );
+        }
+        cx.notify();
+        self.send_shared_terminal(terminal_id, cx)
+    }
+
+    pub fn unshare_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        self.unshare_terminal_with_id(terminal.entity_id().as_u64(), cx)
+    }
+
+    fn unshare_terminal_with_id(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        if self.terminals.shared.remove(&terminal_id).is_some() {
+            cx.notify();
+            if let Some(project_id) = self.remote_id() {
+                self.client.send(proto::UnshareTerminal {
+                    project_id,
+                    terminal_id,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends every shared terminal to the guests again, along with its whole screen, after a
+    /// collaborator joined or reconnected.
+    pub(crate) fn resend_shared_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        let terminal_ids = self.terminals.shared.keys().copied().collect::<Vec<_>>();
+        for terminal_id in terminal_ids {
+            self.send_shared_terminal(terminal_id, cx).log_err();
+        }
+    }
+
+    pub(crate) fn clear_shared_terminals(&mut self) {
+        self.terminals.shared.clear();
+    }
+
+    /// Closes the terminals that the host shared with us, after we stopped being a guest of
+    /// the project.
+    pub(crate) fn close_remote_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        for (_, terminal) in self.terminals.remote.drain() {
+            terminal.update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+        }
+    }
+
+    fn send_shared_terminal(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let project_id = self.remote_id().context("project is not shared")?;
+        let shared = self
+            .terminals
+            .shared
+            .get_mut(&terminal_id)
+            .context("no such terminal")?;
+        let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+        let terminal = terminal.read(cx);
+        let title = terminal.title(false);
+        let screen = terminal.screen();
+        self.client.send(proto::ShareTerminal {
+            project_id,
+            terminal_id,
+            title: title.clone(),
+            read_only: shared.read_only,
+        })?;
+        self.client.send(proto::UpdateTerminal {
+            project_id,
+            terminal_id,
+            screen: Some(screen.clone()),
+            title: None,
+        })?;
+        shared.last_screen = Some(screen);
+        shared.last_title = title;
+        shared.pending_update = None;
+        Ok(())
+    }
+
+    fn schedule_shared_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        if shared.pending_update.is_some() {
+            return;
+        }
+        shared.pending_update = Some(cx.spawn(move |this, mut cx| async move {
+            cx.background_executor()
+                .timer(SHARED_TERMINAL_UPDATE_INTERVAL)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.send_shared_terminal_update(terminal_id, cx)
+            })
+            .log_err();
+        }));
+    }
+
+    /// Sends the lines of the terminal's screen that changed since guests last received it.
+    fn send_shared_terminal_update(
+        &mut self,
+        terminal_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let project_id = self.remote_id().context("project is not shared")?;
+        let shared = self
+            .terminals
+            .shared
+            .get_mut(&terminal_id)
+            .context("no such terminal")?;
+        shared.pending_update = None;
+        let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+        let terminal = terminal.read(cx);
+        let title = terminal.title(false);
+        let mut screen = terminal.screen();
+        let last_screen = shared.last_screen.replace(screen.clone());
+
+        if let Some(last_screen) = last_screen.filter(|last_screen| {
+            last_screen.lines == screen.lines && last_screen.columns == screen.columns
+        }) {
+            screen
+                .changed_lines
+                .retain(|line| last_screen.changed_lines.get(line.index as usize) != Some(line));
+            if screen.changed_lines.is_empty()
+                && screen.cursor == last_screen.cursor
+                && screen.mode == last_screen.mode
+                && title == shared.last_title
+            {
+                return Ok(());
+            }
+        }
+
+        let title = if title == shared.last_title {
+            None
+        } else {
+            shared.last_title.clone_from(&title);
+            Some(title)
+        };
+        self.client.send(proto::UpdateTerminal {
+            project_id,
+            terminal_id,
+            screen: Some(screen),
+            title,
+        })?;
+        Ok(())
+    }
+
+    /// Sends the input typed into a remote terminal to the host, batching whatever was typed
+    /// while the previous input was in flight.
+    fn forward_remote_terminal_input(
+        &self,
+        project_id: u64,
+        terminal_id: u64,
+        mut input_rx: UnboundedReceiver<Vec<u8>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let client = self.client.clone();
+        cx.background_executor()
+            .spawn(async move {
+                while let Some(mut input) = input_rx.next().await {
+                    while let Ok(Some(more_input)) = input_rx.try_next() {
+                        input.extend(more_input);
+                    }
+                    client
+                        .request(proto::TerminalInput {
+                            project_id,
+                            terminal_id,
+                            input,
+                        })
+                        .await
+                        .log_err();
+                }
+            })
+            .detach();
+    }
+
+    pub(crate) async fn handle_share_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ShareTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let proto::ShareTerminal {
+                project_id,
+                terminal_id,
+                title,
+                read_only,
+            } = envelope.payload;
+            if let Some(terminal) = this.terminals.remote.get(&terminal_id) {
+                terminal.update(cx, |terminal, cx| {
+                    terminal.set_remote_state(title, read_only, cx)
+                });
+                return;
+            }
+
+            let (input_tx, input_rx) = mpsc::unbounded();
+            let terminal = cx.new_model(|cx| {
+                TerminalBuilder::new_remote(title, read_only, input_tx).subscribe(cx)
+            });
+            this.forward_remote_terminal_input(project_id, terminal_id, input_rx, cx);
+            this.terminals.remote.insert(terminal_id, terminal.clone());
+            cx.emit(Event::TerminalShared(terminal));
+        })
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if let Some(terminal) = this.terminals.remote.remove(&envelope.payload.terminal_id) {
+                terminal.update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+            }
+        })
+    }
+
+    pub(crate) async fn handle_update_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let proto::UpdateTerminal {
+            terminal_id,
+            screen,
+            title,
+            ..
+        } = envelope.payload;
+        let screen = screen.context("missing terminal screen")?;
+        this.update(&mut cx, |this, cx| {
+            // Updates keep arriving for the terminals we closed.
+            if let Some(terminal) = this.terminals.remote.get(&terminal_id) {
+                terminal.update(cx, |terminal, cx| {
+                    terminal.apply_remote_screen(&screen, title, cx)
+                });
+            }
+        })
+    }
+
+    pub(crate) async fn handle_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::TerminalInput>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let terminal_id = envelope.payload.terminal_id;
+        let terminal = this.update(&mut cx, |this, _| {
+            let shared = this
+                .terminals
+                .shared
+                .get(&terminal_id)
+                .ok_or_else(|| anyhow!("no such terminal {terminal_id}"))?;
+            if shared.read_only {
+                bail!("terminal is shared read-only");
+            }
+            shared.terminal.upgrade().context("terminal was closed")
+        })??;
+        terminal.update(&mut cx, |terminal, _| {
+            terminal.input_bytes(envelope.payload.input)
+        })?;
+        Ok(proto::Ack {})
+    }
 }
 
 fn prepare_ssh_shell(
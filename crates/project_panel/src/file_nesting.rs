@@ -0,0 +1,107 @@
+use crate::project_panel_settings::FileNestingSettings;
+use collections::HashMap;
+use project::{Entry, ProjectEntryId};
+use std::{collections::HashSet, ffi::OsStr, path::Path};
+
+const CAPTURE_PLACEHOLDER: &str = "${capture}";
+
+/// Determines which of the given entries are nested under another file in the same directory,
+/// returning the ids of the nested entries mapped to the ids of the files they're nested under.
+///
+/// Files are nested at most one level deep: a file that other files are nested under is never
+/// nested itself.
+pub(crate) fn nested_file_parents(
+    entries: &[Entry],
+    settings: &FileNestingSettings,
+) -> HashMap<ProjectEntryId, ProjectEntryId> {
+    let mut parents = HashMap::default();
+    if !settings.enabled || settings.patterns.is_empty() {
+        return parents;
+    }
+
+    let files_by_path = entries
+        .iter()
+        .filter(|entry| entry.is_file())
+        .map(|entry| (entry.path.as_ref(), entry.id))
+        .collect::<HashMap<&Path, _>>();
+    let mut nesting_parents = HashSet::default();
+    for entry in entries {
+        if !entry.is_file() || parents.contains_key(&entry.id) {
+            continue;
+        }
+        let (Some(dir), Some(file_name)) = (
+            entry.path.parent(),
+            entry.path.file_name().and_then(OsStr::to_str),
+        ) else {
+            continue;
+        };
+
+        for (pattern, nested_names) in &settings.patterns {
+            let Some(capture) = capture(pattern, file_name) else {
+                continue;
+            };
+            for nested_name in nested_names {
+                let nested_name = nested_name.replace(CAPTURE_PLACEHOLDER, capture);
+                if nested_name == file_name {
+                    continue;
+                }
+                let Some(&nested_id) = files_by_path.get(dir.join(&nested_name).as_path()) else {
+                    continue;
+                };
+                if !nesting_parents.contains(&nested_id) && !parents.contains_key(&nested_id) {
+                    parents.insert(nested_id, entry.id);
+                    nesting_parents.insert(entry.id);
+                }
+            }
+        }
+    }
+    parents
+}
+
+/// Moves nested entries right after the file they're nested under, omitting the ones whose
+/// parent file isn't expanded.
+pub(crate) fn nest_entries(
+    entries: Vec<Entry>,
+    parents: &HashMap<ProjectEntryId, ProjectEntryId>,
+    expanded_parent_ids: &HashSet<ProjectEntryId>,
+) -> Vec<Entry> {
+    let mut nested_entries = HashMap::<ProjectEntryId, Vec<Entry>>::default();
+    let mut top_level_entries = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if let Some(parent_id) = parents.get(&entry.id) {
+            if expanded_parent_ids.contains(parent_id) {
+                nested_entries.entry(*parent_id).or_default().push(entry);
+            }
+        } else {
+            top_level_entries.push(entry);
+        }
+    }
+
+    let mut result = Vec::with_capacity(top_level_entries.len());
+    for entry in top_level_entries {
+        let entry_id = entry.id;
+        result.push(entry);
+        if let Some(nested_entries) = nested_entries.remove(&entry_id) {
+            result.extend(nested_entries);
+        }
+    }
+    result
+}
+
+/// Matches a file name against a pattern containing at most one `*`, returning the text
+/// that the `*` matched.
+fn capture<'a>(pattern: &str, file_name: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            if file_name.len() >= prefix.len() + suffix.len()
+                && file_name.starts_with(prefix)
+                && file_name.ends_with(suffix)
+            {
+                Some(&file_name[prefix.len()..file_name.len() - suffix.len()])
+            } else {
+                None
+            }
+        }
+        None => (pattern == file_name).then_some(""),
+    }
+}
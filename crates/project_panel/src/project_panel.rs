@@ -1,3 +1,4 @@
+mod file_nesting;
 mod project_panel_settings;
 mod scrollbar;
 use client::{ErrorCode, ErrorExt};
@@ -11,6 +12,7 @@ use editor::{
     Editor,
 };
 use file_icons::FileIcons;
+use fuzzy::PathMatchCandidate;
 
 use anyhow::{anyhow, Result};
 use collections::{hash_map, BTreeSet, HashMap};
@@ -74,6 +76,15 @@ pub struct ProjectPanel {
     show_scrollbar: bool,
     scrollbar_drag_thumb_offset: Rc<Cell<Option<f32>>>,
     hide_scrollbar_task: Option<Task<()>>,
+    // Files that are nested under another file, mapped to that file
+    nested_file_parents: HashMap<ProjectEntryId, ProjectEntryId>,
+    nesting_parent_ids: HashSet<ProjectEntryId>,
+    expanded_nesting_parent_ids: HashSet<ProjectEntryId>,
+    filter_editor: View<Editor>,
+    show_filter: bool,
+    // Paths of the entries matching the filter and of their ancestors, or `None` when there's no filter
+    filtered_paths: Option<HashMap<WorktreeId, HashSet<Arc<Path>>>>,
+    filter_task: Task<()>,
 }
 
 #[derive(Clone, Debug)]
@@ -100,6 +111,7 @@ pub struct EntryDetails {
     kind: EntryKind,
     is_ignored: bool,
     is_expanded: bool,
+    has_nested_files: bool,
     is_selected: bool,
     is_marked: bool,
     is_editing: bool,
@@ -148,6 +160,7 @@ actions!(
         UnfoldDirectory,
         FoldDirectory,
         SelectParent,
+        ToggleFilter,
     ]
 );
 
@@ -224,6 +237,9 @@ impl ProjectPanel {
                 project::Event::WorktreeUpdatedEntries(_, _)
                 | project::Event::WorktreeAdded
                 | project::Event::WorktreeOrderChanged => {
+                    if this.filtered_paths.is_some() {
+                        this.update_filter(cx);
+                    }
                     this.update_visible_entries(None, cx);
                     cx.notify();
                 }
@@ -257,16 +273,31 @@ impl ProjectPanel {
             })
             .detach();
 
-            let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
-            cx.observe_global::<SettingsStore>(move |_, cx| {
-                let new_settings = *ProjectPanelSettings::get_global(cx);
+            let mut project_panel_settings = ProjectPanelSettings::get_global(cx).clone();
+            cx.observe_global::<SettingsStore>(move |this, cx| {
+                let new_settings = ProjectPanelSettings::get_global(cx).clone();
                 if project_panel_settings != new_settings {
+                    if project_panel_settings.file_nesting != new_settings.file_nesting {
+                        this.update_visible_entries(None, cx);
+                    }
                     project_panel_settings = new_settings;
                     cx.notify();
                 }
             })
             .detach();
 
+            let filter_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Filter files…", cx);
+                editor
+            });
+            cx.subscribe(&filter_editor, |this, _, event, cx| {
+                if let editor::EditorEvent::BufferEdited = event {
+                    this.update_filter(cx);
+                }
+            })
+            .detach();
+
             let mut this = Self {
                 project: project.clone(),
                 fs: workspace.app_state().fs.clone(),
@@ -290,6 +321,13 @@ impl ProjectPanel {
                 show_scrollbar: !Self::should_autohide_scrollbar(cx),
                 hide_scrollbar_task: None,
                 scrollbar_drag_thumb_offset: Default::default(),
+                nested_file_parents: Default::default(),
+                nesting_parent_ids: Default::default(),
+                expanded_nesting_parent_ids: Default::default(),
+                filter_editor,
+                show_filter: false,
+                filtered_paths: None,
+                filter_task: Task::ready(()),
             };
             this.update_visible_entries(None, cx);
 
@@ -613,11 +651,35 @@ impl ProjectPanel {
                         cx.notify();
                     }
                 }
+            } else if self.nesting_parent_ids.contains(&entry.id) {
+                if self.expanded_nesting_parent_ids.insert(entry.id) {
+                    self.update_visible_entries(None, cx);
+                    cx.notify();
+                } else {
+                    self.select_next(&SelectNext, cx);
+                }
             }
         }
     }
 
     fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(selection) = self.selection {
+            let nesting_parent_id = if self
+                .expanded_nesting_parent_ids
+                .contains(&selection.entry_id)
+            {
+                Some(selection.entry_id)
+            } else {
+                self.nested_file_parents.get(&selection.entry_id).copied()
+            };
+            if let Some(nesting_parent_id) = nesting_parent_id {
+                self.expanded_nesting_parent_ids.remove(&nesting_parent_id);
+                self.update_visible_entries(Some((selection.worktree_id, nesting_parent_id)), cx);
+                cx.notify();
+                return;
+            }
+        }
+
         if let Some((worktree, mut entry)) = self.selected_entry(cx) {
             let worktree_id = worktree.id();
             let expanded_dir_ids =
@@ -680,6 +742,75 @@ impl ProjectPanel {
         }
     }
 
+    fn toggle_nested_files(&mut self, entry_id: ProjectEntryId, cx: &mut ViewContext<Self>) {
+        if let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) {
+            if !self.expanded_nesting_parent_ids.remove(&entry_id) {
+                self.expanded_nesting_parent_ids.insert(entry_id);
+            }
+            self.update_visible_entries(Some((worktree_id, entry_id)), cx);
+            cx.focus(&self.focus_handle);
+            cx.notify();
+        }
+    }
+
+    fn toggle_filter(&mut self, _: &ToggleFilter, cx: &mut ViewContext<Self>) {
+        if self.show_filter {
+            self.clear_filter(cx);
+        } else {
+            self.show_filter = true;
+            cx.focus_view(&self.filter_editor);
+            cx.notify();
+        }
+    }
+
+    fn clear_filter(&mut self, cx: &mut ViewContext<Self>) {
+        self.show_filter = false;
+        self.filter_task = Task::ready(());
+        self.filter_editor
+            .update(cx, |editor, cx| editor.set_text("", cx));
+        self.filtered_paths = None;
+        self.update_visible_entries(None, cx);
+        cx.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn update_filter(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.filter_editor.read(cx).text(cx);
+        if query.is_empty() {
+            self.filter_task = Task::ready(());
+            if self.filtered_paths.take().is_some() {
+                self.update_visible_entries(None, cx);
+                cx.notify();
+            }
+            return;
+        }
+
+        let snapshots = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).snapshot())
+            .collect::<Vec<_>>();
+        self.filter_task = cx.spawn(|this, mut cx| async move {
+            let filtered_paths = cx
+                .background_executor()
+                .spawn(async move {
+                    snapshots
+                        .iter()
+                        .map(|snapshot| (snapshot.id(), paths_matching_filter(snapshot, &query)))
+                        .collect::<HashMap<_, _>>()
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.filtered_paths = Some(filtered_paths);
+                this.update_visible_entries(None, cx);
+                this.autoscroll(cx);
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
     fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
         if let Some(selection) = self.selection {
             let (mut worktree_ix, mut entry_ix, _) =
@@ -860,6 +991,10 @@ impl ProjectPanel {
     }
 
     fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        if self.edit_state.is_none() && self.show_filter {
+            self.clear_filter(cx);
+            return;
+        }
         self.edit_state = None;
         self.update_visible_entries(None, cx);
         self.marked_entries.clear();
@@ -1555,7 +1690,9 @@ impl ProjectPanel {
         new_selected_entry: Option<(WorktreeId, ProjectEntryId)>,
         cx: &mut ViewContext<Self>,
     ) {
-        let auto_collapse_dirs = ProjectPanelSettings::get_global(cx).auto_fold_dirs;
+        let settings = ProjectPanelSettings::get_global(cx);
+        let auto_collapse_dirs = settings.auto_fold_dirs;
+        let file_nesting = &settings.file_nesting;
         let project = self.project.read(cx);
         self.last_worktree_root_id = project
             .visible_worktrees(cx)
@@ -1565,9 +1702,15 @@ impl ProjectPanel {
             .map(|entry| entry.id);
 
         self.visible_entries.clear();
+        self.nested_file_parents.clear();
+        self.nesting_parent_ids.clear();
         for worktree in project.visible_worktrees(cx) {
             let snapshot = worktree.read(cx).snapshot();
             let worktree_id = snapshot.id();
+            let filtered_paths = self
+                .filtered_paths
+                .as_ref()
+                .map(|filtered_paths| filtered_paths.get(&worktree_id));
 
             let expanded_dir_ids = match self.expanded_dir_ids.entry(worktree_id) {
                 hash_map::Entry::Occupied(e) => e.into_mut(),
@@ -1598,7 +1741,15 @@ impl ProjectPanel {
             let mut visible_worktree_entries = Vec::new();
             let mut entry_iter = snapshot.entries(true, 0);
             while let Some(entry) = entry_iter.entry() {
-                if auto_collapse_dirs
+                if let Some(filtered_paths) = filtered_paths {
+                    // Only show the entries matching the filter, regardless of which directories are expanded.
+                    if filtered_paths.map_or(true, |paths| !paths.contains(&entry.path)) {
+                        if !entry_iter.advance_to_sibling() {
+                            entry_iter.advance();
+                        }
+                        continue;
+                    }
+                } else if auto_collapse_dirs
                     && entry.kind.is_dir()
                     && !self.unfolded_dir_ids.contains(&entry.id)
                 {
@@ -1632,7 +1783,8 @@ impl ProjectPanel {
                         is_symlink: entry.is_symlink,
                     });
                 }
-                if expanded_dir_ids.binary_search(&entry.id).is_err()
+                if filtered_paths.is_none()
+                    && expanded_dir_ids.binary_search(&entry.id).is_err()
                     && entry_iter.advance_to_sibling()
                 {
                     continue;
@@ -1642,6 +1794,20 @@ impl ProjectPanel {
 
             snapshot.propagate_git_statuses(&mut visible_worktree_entries);
             project::sort_worktree_entries(&mut visible_worktree_entries);
+            if filtered_paths.is_none() {
+                let nested_file_parents =
+                    file_nesting::nested_file_parents(&visible_worktree_entries, file_nesting);
+                if !nested_file_parents.is_empty() {
+                    visible_worktree_entries = file_nesting::nest_entries(
+                        visible_worktree_entries,
+                        &nested_file_parents,
+                        &self.expanded_nesting_parent_ids,
+                    );
+                    self.nesting_parent_ids
+                        .extend(nested_file_parents.values().copied());
+                    self.nested_file_parents.extend(nested_file_parents);
+                }
+            }
             self.visible_entries
                 .push((worktree_id, visible_worktree_entries, OnceCell::new()));
         }
@@ -1691,6 +1857,9 @@ impl ProjectPanel {
                 }
             }
         });
+        if let Some(nesting_parent_id) = self.nested_file_parents.get(&entry_id) {
+            self.expanded_nesting_parent_ids.insert(*nesting_parent_id);
+        }
     }
 
     fn drop_external_files(
@@ -1851,7 +2020,13 @@ impl ProjectPanel {
                 });
                 for entry in visible_worktree_entries[entry_range].iter() {
                     let status = git_status_setting.then(|| entry.git_status).flatten();
-                    let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
+                    let has_nested_files = self.nesting_parent_ids.contains(&entry.id);
+                    let is_expanded = if has_nested_files {
+                        self.expanded_nesting_parent_ids.contains(&entry.id)
+                    } else {
+                        (self.filtered_paths.is_some() && entry.is_dir())
+                            || expanded_entry_ids.binary_search(&entry.id).is_ok()
+                    };
                     let icon = match entry.kind {
                         EntryKind::File(_) => {
                             if show_file_icons {
@@ -1869,8 +2044,11 @@ impl ProjectPanel {
                         }
                     };
 
-                    let (depth, difference) =
+                    let (mut depth, difference) =
                         ProjectPanel::calculate_depth_and_difference(entry, &entries);
+                    if self.nested_file_parents.contains_key(&entry.id) {
+                        depth += 1;
+                    }
 
                     let filename = match difference {
                         diff if diff > 1 => entry
@@ -1899,6 +2077,7 @@ impl ProjectPanel {
                         kind: entry.kind,
                         is_ignored: entry.is_ignored,
                         is_expanded,
+                        has_nested_files,
                         is_selected: self.selection == Some(selection),
                         is_marked: self.marked_entries.contains(&selection),
                         is_editing: false,
@@ -2005,6 +2184,8 @@ impl ProjectPanel {
         let path = details.path.clone();
 
         let depth = details.depth;
+        let has_nested_files = details.has_nested_files;
+        let is_expanded = details.is_expanded;
         let worktree_id = details.worktree_id;
         let selections = Arc::new(self.marked_entries.clone());
 
@@ -2084,6 +2265,12 @@ impl ProjectPanel {
                     .indent_level(depth)
                     .indent_step_size(px(settings.indent_size))
                     .selected(is_marked || is_active)
+                    .when(has_nested_files, |this| {
+                        this.toggle(is_expanded)
+                            .on_toggle(cx.listener(move |this, _, cx| {
+                                this.toggle_nested_files(entry_id, cx);
+                            }))
+                    })
                     .when_some(canonical_path, |this, path| {
                         this.end_slot::<AnyElement>(
                             div()
@@ -2305,7 +2492,9 @@ impl ProjectPanel {
         dispatch_context.add("ProjectPanel");
         dispatch_context.add("menu");
 
-        let identifier = if self.filename_editor.focus_handle(cx).is_focused(cx) {
+        let identifier = if self.filename_editor.focus_handle(cx).is_focused(cx)
+            || self.filter_editor.focus_handle(cx).is_focused(cx)
+        {
             "editing"
         } else {
             "not_editing"
@@ -2377,7 +2566,7 @@ impl Render for ProjectPanel {
                 .map(|(_, worktree_entries, _)| worktree_entries.len())
                 .sum();
 
-            h_flex()
+            v_flex()
                 .id("project-panel")
                 .group("project-panel")
                 .size_full()
@@ -2409,6 +2598,7 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::new_search_in_directory))
                 .on_action(cx.listener(Self::unfold_directory))
                 .on_action(cx.listener(Self::fold_directory))
+                .on_action(cx.listener(Self::toggle_filter))
                 .when(!project.is_read_only(), |el| {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
@@ -2435,6 +2625,16 @@ impl Render for ProjectPanel {
                     }),
                 )
                 .track_focus(&self.focus_handle)
+                .when(self.show_filter, |this| {
+                    this.child(
+                        h_flex()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(self.filter_editor.clone()),
+                    )
+                })
                 .child(
                     uniform_list(cx.view().clone(), "entries", items_count, {
                         |this, range, cx| {
@@ -2619,6 +2819,39 @@ impl ClipboardEntry {
     }
 }
 
+/// Returns the paths of the entries whose paths fuzzy-match the query, along with the paths of
+/// their ancestors.
+fn paths_matching_filter(snapshot: &worktree::Snapshot, query: &str) -> HashSet<Arc<Path>> {
+    let candidates = snapshot
+        .entries(true, 0)
+        .map(|entry| PathMatchCandidate {
+            path: &entry.path,
+            char_bag: entry
+                .path
+                .to_string_lossy()
+                .chars()
+                .map(|c| c.to_ascii_lowercase())
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    let max_results = candidates.len() + 1;
+    let mut paths = HashSet::default();
+    for path_match in fuzzy::match_fixed_path_set(
+        candidates,
+        snapshot.id().to_usize(),
+        query,
+        false,
+        max_results,
+    ) {
+        for ancestor in path_match.path.ancestors() {
+            if !paths.insert(Arc::from(ancestor)) {
+                break;
+            }
+        }
+    }
+    paths
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2884,7 +3117,7 @@ mod tests {
         let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
         let cx = &mut VisualTestContext::from_window(*workspace, cx);
         cx.update(|cx| {
-            let settings = *ProjectPanelSettings::get_global(cx);
+            let settings = ProjectPanelSettings::get_global(cx).clone();
             ProjectPanelSettings::override_global(
                 ProjectPanelSettings {
                     auto_fold_dirs: true,
@@ -2962,6 +3195,165 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_file_nesting(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root1",
+            json!({
+                "src": {
+                    "bar.ts": "",
+                    "baz.css": "",
+                    "foo.module.css": "",
+                    "foo.test.ts": "",
+                    "foo.ts": "",
+                }
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root1".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        cx.update(|cx| {
+            let settings = ProjectPanelSettings::get_global(cx).clone();
+            ProjectPanelSettings::override_global(
+                ProjectPanelSettings {
+                    file_nesting: crate::project_panel_settings::FileNestingSettings {
+                        enabled: true,
+                        patterns: [(
+                            "*.ts".to_string(),
+                            vec![
+                                "${capture}.test.ts".to_string(),
+                                "${capture}.module.css".to_string(),
+                            ],
+                        )]
+                        .into_iter()
+                        .collect(),
+                    },
+                    ..settings
+                },
+                cx,
+            );
+        });
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        toggle_expand_dir(&panel, "root1/src", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root1",
+                "    v src  <== selected",
+                "          bar.ts",
+                "          baz.css",
+                "        > foo.ts",
+            ]
+        );
+
+        select_path(&panel, "root1/src/foo.ts", cx);
+        panel.update(cx, |panel, cx| {
+            panel.expand_selected_entry(&ExpandSelectedEntry, cx)
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root1",
+                "    v src",
+                "          bar.ts",
+                "          baz.css",
+                "        v foo.ts  <== selected",
+                "              foo.module.css",
+                "              foo.test.ts",
+            ]
+        );
+
+        select_path(&panel, "root1/src/foo.test.ts", cx);
+        panel.update(cx, |panel, cx| {
+            panel.collapse_selected_entry(&CollapseSelectedEntry, cx)
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root1",
+                "    v src",
+                "          bar.ts",
+                "          baz.css",
+                "        > foo.ts  <== selected",
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_filter_entries(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root1",
+            json!({
+                "docs": {
+                    "guide.md": "",
+                },
+                "src": {
+                    "lib.rs": "",
+                    "main.rs": "",
+                },
+                "README.md": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root1".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v root1", "    > docs", "    > src", "      README.md",]
+        );
+
+        panel.update(cx, |panel, cx| {
+            panel.toggle_filter(&ToggleFilter, cx);
+            panel
+                .filter_editor
+                .update(cx, |editor, cx| editor.set_text("lib", cx));
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v root1", "    v src", "          lib.rs",]
+        );
+
+        panel.update(cx, |panel, cx| {
+            panel
+                .filter_editor
+                .update(cx, |editor, cx| editor.set_text("md", cx));
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root1",
+                "    v docs",
+                "          guide.md",
+                "      README.md",
+            ]
+        );
+
+        panel.update(cx, |panel, cx| panel.cancel(&menu::Cancel, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &["v root1", "    > docs", "    > src", "      README.md",]
+        );
+    }
+
     #[gpui::test(iterations = 30)]
     async fn test_editing_files(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -5016,7 +5408,7 @@ mod tests {
                 }
 
                 let indent = "    ".repeat(details.depth);
-                let icon = if details.kind.is_dir() {
+                let icon = if details.kind.is_dir() || details.has_nested_files {
                     if details.is_expanded {
                         "v "
                     } else {
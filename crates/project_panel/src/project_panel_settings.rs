@@ -1,4 +1,5 @@
 use anyhow;
+use collections::BTreeMap;
 use gpui::Pixels;
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
@@ -11,7 +12,7 @@ pub enum ProjectPanelDockPosition {
     Right,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct ProjectPanelSettings {
     pub button: bool,
     pub default_width: Pixels,
@@ -23,6 +24,7 @@ pub struct ProjectPanelSettings {
     pub auto_reveal_entries: bool,
     pub auto_fold_dirs: bool,
     pub scrollbar: ScrollbarSettings,
+    pub file_nesting: FileNestingSettings,
 }
 
 /// When to show the scrollbar in the project panel.
@@ -54,6 +56,30 @@ pub struct ScrollbarSettingsContent {
     pub show: Option<ShowScrollbar>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FileNestingSettings {
+    /// Whether to show related files nested under another file in the same directory.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// Patterns for the names of the files that other files are nested under, mapped to
+    /// the names of the nested files. A `*` in a pattern matches any text, which replaces
+    /// `${capture}` in the names of the nested files.
+    pub patterns: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FileNestingSettingsContent {
+    /// Whether to show related files nested under another file in the same directory.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Patterns for the names of the files that other files are nested under, mapped to
+    /// the names of the nested files. A `*` in a pattern matches any text, which replaces
+    /// `${capture}` in the names of the nested files.
+    pub patterns: Option<BTreeMap<String, Vec<String>>>,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct ProjectPanelSettingsContent {
     /// Whether to show the project panel button in the status bar.
@@ -97,6 +123,8 @@ pub struct ProjectPanelSettingsContent {
     pub auto_fold_dirs: Option<bool>,
     /// Scrollbar-related settings
     pub scrollbar: Option<ScrollbarSettingsContent>,
+    /// Settings for showing related files nested under another file.
+    pub file_nesting: Option<FileNestingSettingsContent>,
 }
 
 impl Settings for ProjectPanelSettings {
@@ -140,6 +140,7 @@ messages!(
     (Ack, Foreground),
     (AckBufferOperation, Background),
     (AckChannelMessage, Background),
+    (AddChannelMessageReaction, Foreground),
     (AddNotification, Foreground),
     (AddProjectCollaborator, Foreground),
     (ApplyCodeAction, Background),
@@ -153,6 +154,7 @@ messages!(
     (CancelCall, Foreground),
     (ChannelMessageSent, Foreground),
     (ChannelMessageUpdate, Foreground),
+    (ChannelMessageReactionsUpdated, Foreground),
     (CompleteWithLanguageModel, Background),
     (ComputeEmbeddings, Background),
     (ComputeEmbeddingsResponse, Background),
@@ -185,6 +187,7 @@ messages!(
     (GetChannelMembersResponse, Foreground),
     (GetChannelMessages, Background),
     (GetChannelMessagesById, Background),
+    (GetChannelMessageThread, Background),
     (GetChannelMessagesResponse, Background),
     (GetCodeActions, Background),
     (GetCodeActionsResponse, Background),
@@ -255,6 +258,7 @@ messages!(
     (ReloadBuffersResponse, Foreground),
     (RemoveChannelMember, Foreground),
     (RemoveChannelMessage, Foreground),
+    (RemoveChannelMessageReaction, Foreground),
     (UpdateChannelMessage, Foreground),
     (RemoveContact, Foreground),
     (RemoveProjectCollaborator, Foreground),
@@ -274,6 +278,7 @@ messages!(
     (SetChannelVisibility, Foreground),
     (SearchProject, Background),
     (SearchProjectResponse, Background),
+    (SearchChannelMessages, Background),
     (SendChannelMessage, Background),
     (SendChannelMessageResponse, Background),
     (ShareProject, Foreground),
@@ -337,7 +342,20 @@ messages!(
     (OpenNewBuffer, Foreground),
     (RestartLanguageServers, Foreground),
     (LinkedEditingRange, Background),
-    (LinkedEditingRangeResponse, Background)
+    (LinkedEditingRangeResponse, Background),
+    (OpenSshProject, Foreground),
+    (OpenSshProjectResponse, Foreground),
+    (GetProjectCommentThreads, Foreground),
+    (GetProjectCommentThreadsResponse, Foreground),
+    (CreateProjectCommentThread, Foreground),
+    (ReplyToProjectCommentThread, Foreground),
+    (ResolveProjectCommentThread, Foreground),
+    (ProjectCommentThreadResponse, Foreground),
+    (UpdateProjectCommentThread, Foreground),
+    (ShareTerminal, Foreground),
+    (UnshareTerminal, Foreground),
+    (UpdateTerminal, Foreground),
+    (TerminalInput, Foreground)
 );
 
 request_messages!(
@@ -366,6 +384,7 @@ request_messages!(
     (GetChannelMembers, GetChannelMembersResponse),
     (GetChannelMessages, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
+    (GetChannelMessageThread, GetChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
     (GetCompletions, GetCompletionsResponse),
     (GetDefinition, GetDefinitionResponse),
@@ -408,6 +427,8 @@ request_messages!(
     (RemoveChannelMember, Ack),
     (RemoveChannelMessage, Ack),
     (UpdateChannelMessage, Ack),
+    (AddChannelMessageReaction, Ack),
+    (RemoveChannelMessageReaction, Ack),
     (RemoveContact, Ack),
     (RenameChannel, RenameChannelResponse),
     (RenameProjectEntry, ProjectEntryResponse),
@@ -421,6 +442,7 @@ request_messages!(
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
     (SearchProject, SearchProjectResponse),
+    (SearchChannelMessages, GetChannelMessagesResponse),
     (SendChannelMessage, SendChannelMessageResponse),
     (SetChannelMemberRole, Ack),
     (SetChannelVisibility, Ack),
@@ -449,7 +471,13 @@ request_messages!(
     (DeleteDevServerProject, Ack),
     (RegenerateDevServerToken, RegenerateDevServerTokenResponse),
     (RenameDevServer, Ack),
-    (RestartLanguageServers, Ack)
+    (RestartLanguageServers, Ack),
+    (OpenSshProject, OpenSshProjectResponse),
+    (GetProjectCommentThreads, GetProjectCommentThreadsResponse),
+    (CreateProjectCommentThread, ProjectCommentThreadResponse),
+    (ReplyToProjectCommentThread, ProjectCommentThreadResponse),
+    (ResolveProjectCommentThread, ProjectCommentThreadResponse),
+    (TerminalInput, Ack)
 );
 
 entity_messages!(
@@ -462,6 +490,7 @@ entity_messages!(
     BufferSaved,
     CopyProjectEntry,
     CreateBufferForPeer,
+    CreateProjectCommentThread,
     CreateProjectEntry,
     DeleteProjectEntry,
     ExpandProjectEntry,
@@ -472,6 +501,7 @@ entity_messages!(
     GetImplementation,
     GetDocumentHighlights,
     GetHover,
+    GetProjectCommentThreads,
     GetProjectSymbols,
     GetReferences,
     GetTypeDefinition,
@@ -492,15 +522,20 @@ entity_messages!(
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    ReplyToProjectCommentThread,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
+    ResolveProjectCommentThread,
     SaveBuffer,
     SearchProject,
+    ShareTerminal,
     StartLanguageServer,
     SynchronizeBuffers,
     TaskContextForLocation,
     TaskTemplates,
+    TerminalInput,
     UnshareProject,
+    UnshareTerminal,
     UpdateBuffer,
     UpdateBufferFile,
     UpdateDiagnosticSummary,
@@ -508,6 +543,8 @@ entity_messages!(
     UpdateLanguageServer,
     UpdateProject,
     UpdateProjectCollaborator,
+    UpdateProjectCommentThread,
+    UpdateTerminal,
     UpdateWorktree,
     UpdateWorktreeSettings,
     LspExtExpandMacro,
@@ -517,6 +554,7 @@ entity_messages!(
     {channel_id, Channel},
     ChannelMessageSent,
     ChannelMessageUpdate,
+    ChannelMessageReactionsUpdated,
     RemoveChannelMessage,
     UpdateChannelMessage,
     UpdateChannelBuffer,
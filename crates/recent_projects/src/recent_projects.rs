@@ -1,5 +1,7 @@
 mod dev_servers;
 pub mod disconnected_overlay;
+mod sessions;
+mod ssh_connections;
 
 use client::{DevServerProjectId, ProjectId};
 use dev_servers::reconnect_to_dev_server_project;
@@ -18,6 +20,10 @@ use picker::{
 };
 use rpc::proto::DevServerStatus;
 use serde::Deserialize;
+pub use sessions::OpenSession;
+use sessions::Sessions;
+use ssh_connections::SshConnections;
+pub use ssh_connections::{open_ssh_project, OpenOverSsh};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
@@ -48,6 +54,8 @@ pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(RecentProjects::register).detach();
     cx.observe_new_views(DevServerProjects::register).detach();
     cx.observe_new_views(DisconnectedOverlay::register).detach();
+    cx.observe_new_views(Sessions::register).detach();
+    cx.observe_new_views(SshConnections::register).detach();
 }
 
 pub struct RecentProjects {
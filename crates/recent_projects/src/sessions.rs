@@ -0,0 +1,229 @@
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Subscription,
+    Task, View, ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+gpui::actions!(projects, [OpenSession]);
+
+/// Lists the sessions saved for the workspace's worktree roots, restoring the selected one or
+/// saving the current layout under the typed name.
+pub struct Sessions {
+    picker: View<Picker<SessionsDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ModalView for Sessions {}
+
+impl Sessions {
+    fn new(
+        workspace: WeakView<Workspace>,
+        session_names: Vec<String>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = SessionsDelegate {
+            workspace,
+            session_names,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &OpenSession, cx| {
+            let weak_workspace = workspace.weak_handle();
+            let session_names = workspace.session_names(cx);
+            workspace.toggle_modal(cx, |cx| Self::new(weak_workspace, session_names, cx));
+        });
+    }
+}
+
+impl EventEmitter<DismissEvent> for Sessions {}
+
+impl FocusableView for Sessions {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for Sessions {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+enum SessionMatch {
+    Session(StringMatch),
+    SaveAs(String),
+}
+
+pub struct SessionsDelegate {
+    workspace: WeakView<Workspace>,
+    session_names: Vec<String>,
+    matches: Vec<SessionMatch>,
+    selected_index: usize,
+}
+
+impl SessionsDelegate {
+    fn delete_session(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let (Some(SessionMatch::Session(session_match)), Some(workspace)) =
+            (self.matches.get(ix), self.workspace.upgrade())
+        else {
+            return;
+        };
+        let name = self.session_names.remove(session_match.candidate_id);
+        let delete = workspace.read(cx).delete_session(name, cx);
+        cx.spawn(move |this, mut cx| async move {
+            delete.await.log_err();
+            this.update(&mut cx, |picker, cx| {
+                picker.update_matches(picker.query(cx), cx)
+            })
+        })
+        .detach();
+    }
+}
+
+impl PickerDelegate for SessionsDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, cx: &mut WindowContext) -> Arc<str> {
+        Arc::from(format!(
+            "Type a name to save the current layout, {} overwrites the selected session",
+            cx.keystroke_text_for(&menu::SecondaryConfirm)
+        ))
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.trim();
+        let candidates = self
+            .session_names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| StringMatchCandidate::new(id, name.clone()))
+            .collect::<Vec<_>>();
+
+        self.matches = if query.is_empty() {
+            candidates
+                .into_iter()
+                .map(|candidate| {
+                    SessionMatch::Session(StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                })
+                .collect()
+        } else {
+            let mut matches = smol::block_on(fuzzy::match_strings(
+                &candidates,
+                query,
+                false,
+                100,
+                &Default::default(),
+                cx.background_executor().clone(),
+            ))
+            .into_iter()
+            .map(SessionMatch::Session)
+            .collect::<Vec<_>>();
+            if !self.session_names.iter().any(|name| name == query) {
+                matches.push(SessionMatch::SaveAs(query.to_string()));
+            }
+            matches
+        };
+        self.selected_index = 0;
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some((session_match, workspace)) = self
+            .matches
+            .get(self.selected_index)
+            .zip(self.workspace.upgrade())
+        else {
+            return;
+        };
+
+        workspace.update(cx, |workspace, cx| match session_match {
+            SessionMatch::Session(session_match) if secondary => workspace
+                .save_session(session_match.string.clone(), cx)
+                .detach_and_log_err(cx),
+            SessionMatch::Session(session_match) => workspace
+                .open_session(session_match.string.clone(), cx)
+                .detach_and_log_err(cx),
+            SessionMatch::SaveAs(name) => workspace
+                .save_session(name.clone(), cx)
+                .detach_and_log_err(cx),
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut ViewContext<Picker<Self>>) {}
+
+    fn no_matches_text(&self, _: &mut WindowContext) -> SharedString {
+        "Type a name to save the current layout as a session".into()
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .selected(selected)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse);
+
+        Some(match self.matches.get(ix)? {
+            SessionMatch::Session(session_match) => item
+                .start_slot(Icon::new(IconName::Screen).color(Color::Muted))
+                .child(HighlightedLabel::new(
+                    session_match.string.clone(),
+                    session_match.positions.clone(),
+                ))
+                .map(|item| {
+                    let delete_button = IconButton::new("delete", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(move |this, _, cx| {
+                            cx.stop_propagation();
+                            this.delegate.delete_session(ix, cx);
+                        }))
+                        .tooltip(|cx| Tooltip::text("Delete Session", cx))
+                        .into_any_element();
+
+                    if selected {
+                        item.end_slot::<AnyElement>(delete_button)
+                    } else {
+                        item.end_hover_slot::<AnyElement>(delete_button)
+                    }
+                }),
+            SessionMatch::SaveAs(name) => item
+                .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                .child(Label::new(format!("Save current layout as \"{name}\""))),
+        })
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use client::{user::UserStore, Client};
+use gpui::{
+    AppContext, Context as _, DismissEvent, EventEmitter, FocusHandle, FocusableView, Subscription,
+    Task, View, ViewContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use release_channel::AppVersion;
+use remote::{SshConnectionOptions, SshSession};
+use std::sync::Arc;
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use workspace::{notifications::NotifyResultExt as _, AppState, ModalView, Workspace};
+
+gpui::actions!(projects, [OpenOverSsh]);
+
+/// Prompts for the SSH url of a project and opens it in a new window.
+pub struct SshConnections {
+    picker: View<Picker<SshConnectionsDelegate>>,
+    _subscription: Subscription,
+}
+
+impl ModalView for SshConnections {}
+
+impl SshConnections {
+    fn new(workspace: WeakView<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = SshConnectionsDelegate {
+            workspace,
+            target: None,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+
+    pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &OpenOverSsh, cx| {
+            let weak_workspace = workspace.weak_handle();
+            workspace.toggle_modal(cx, |cx| Self::new(weak_workspace, cx));
+        });
+    }
+}
+
+impl EventEmitter<DismissEvent> for SshConnections {}
+
+impl FocusableView for SshConnections {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for SshConnections {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct SshConnectionsDelegate {
+    workspace: WeakView<Workspace>,
+    target: Option<(SshConnectionOptions, String)>,
+}
+
+impl PickerDelegate for SshConnectionsDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        "ssh://user@host:port/path or user@host:path".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.target.iter().count()
+    }
+
+    fn selected_index(&self) -> usize {
+        0
+    }
+
+    fn set_selected_index(&mut self, _: usize, _: &mut ViewContext<Picker<Self>>) {}
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        self.target = SshConnectionOptions::parse_project_url(&query).ok();
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let (Some((options, path)), Some(workspace)) =
+            (self.target.clone(), self.workspace.upgrade())
+        else {
+            return;
+        };
+        let app_state = workspace.read(cx).app_state().clone();
+        let task = open_ssh_project(options, path, app_state, cx);
+        workspace.update(cx, |_, cx| {
+            cx.spawn(|workspace, mut cx| async move {
+                let result = task.await;
+                workspace.update(&mut cx, |workspace, cx| {
+                    result.notify_err(workspace, cx);
+                })
+            })
+            .detach();
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut ViewContext<Picker<Self>>) {}
+
+    fn no_matches_text(&self, _: &mut WindowContext) -> SharedString {
+        "Type the SSH url of a project".into()
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let (options, path) = self.target.as_ref()?;
+        let mut host = options.host.clone();
+        if let Some(username) = &options.username {
+            host = format!("{username}@{host}");
+        }
+        if let Some(port) = options.port {
+            host = format!("{host}:{port}");
+        }
+        Some(
+            ListItem::new(ix)
+                .selected(selected)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .start_slot(Icon::new(IconName::Server).color(Color::Muted))
+                .child(Label::new(format!("Open {path} on {host}"))),
+        )
+    }
+}
+
+/// Connects to the host, starting Zed's server on it if needed, and opens the project at the
+/// given path in a new window.
+pub fn open_ssh_project(
+    options: SshConnectionOptions,
+    path: String,
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let version = AppVersion::global(cx);
+    cx.spawn(|mut cx| async move {
+        let session = SshSession::connect(options, version, &cx).await?;
+        let client = cx.update(|cx| {
+            let client = Client::new(
+                Arc::new(clock::RealSystemClock),
+                app_state.client.http_client(),
+                cx,
+            );
+            client.set_tunnel(move |cx| Task::ready(session.connect_server(cx)));
+            Project::register_client_handlers(&client);
+            client
+        })?;
+        let user_store = cx.new_model(|cx| UserStore::new(client.clone(), cx))?;
+        let project = Project::ssh(
+            path,
+            client,
+            user_store,
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            cx.clone(),
+        )
+        .await?;
+
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await?;
+        let window = cx.update(|cx| {
+            let options = (app_state.build_window_options)(None, cx);
+            cx.open_window(options, |cx| {
+                cx.new_view(|cx| Workspace::new(Some(workspace_id), project, app_state.clone(), cx))
+            })
+        })??;
+        window.update(&mut cx, |_, cx| {
+            cx.activate(true);
+            cx.activate_window();
+        })?;
+        Ok(())
+    })
+}
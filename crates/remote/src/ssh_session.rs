@@ -0,0 +1,480 @@
+use anyhow::{anyhow, Context as _, Result};
+use futures::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, StreamExt as _};
+use gpui::{AsyncAppContext, SemanticVersion};
+use parking_lot::Mutex;
+use rpc::Connection;
+use smol::{
+    io::BufReader,
+    process::{Child, Command, Stdio},
+};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use util::ResultExt;
+
+/// The directory, relative to the remote user's home, that server binaries are uploaded to.
+const SERVER_DIR: &str = ".zed_server";
+const SERVER_BINARY_NAME: &str = "zed-remote-server";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SshConnectionOptions {
+    pub host: String,
+    pub username: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl SshConnectionOptions {
+    /// Parses either `ssh://[user@]host[:port]/path` or the scp-like `[user@]host:path`,
+    /// returning the connection options along with the project's path on the host.
+    pub fn parse_project_url(input: &str) -> Result<(Self, String)> {
+        let input = input.trim();
+        let url = input.strip_prefix("ssh://");
+        let rest = url.unwrap_or(input);
+        let (username, rest) = match rest.split_once('@') {
+            Some((username, rest)) if !username.is_empty() && !username.contains(['/', ':']) => {
+                (Some(username.to_string()), rest)
+            }
+            _ => (None, rest),
+        };
+
+        let (host, port, path) = if url.is_some() {
+            let (host_and_port, path) = rest
+                .split_once('/')
+                .ok_or_else(|| anyhow!("no project path in {input:?}"))?;
+            let (host, port) = match host_and_port.split_once(':') {
+                Some((host, port)) => (
+                    host,
+                    Some(
+                        port.parse()
+                            .with_context(|| format!("invalid port {port:?}"))?,
+                    ),
+                ),
+                None => (host_and_port, None),
+            };
+            let path = if path.starts_with('~') {
+                path.to_string()
+            } else {
+                format!("/{path}")
+            };
+            (host, port, path)
+        } else {
+            let (host, path) = rest
+                .split_once(':')
+                .ok_or_else(|| anyhow!("expected [user@]host:path, got {input:?}"))?;
+            (host, None, path.to_string())
+        };
+
+        if host.is_empty() {
+            return Err(anyhow!("no host in {input:?}"));
+        }
+        if path.is_empty() {
+            return Err(anyhow!("no project path in {input:?}"));
+        }
+
+        Ok((
+            Self {
+                host: host.to_string(),
+                username,
+                port,
+            },
+            path,
+        ))
+    }
+
+    fn destination(&self) -> String {
+        match &self.username {
+            Some(username) => format!("{username}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+}
+
+/// A connection to a host over SSH, multiplexed through a single master connection.
+///
+/// Opening the session makes sure that the server binary matching this version of Zed is
+/// installed on the host, uploading it if necessary. Every call to
+/// [`SshSession::connect_server`] then starts a proxy to that server over the master
+/// connection, so that reconnecting after a dropped tunnel doesn't need to authenticate again.
+///
+/// The master connection runs with `BatchMode=yes`, because there's no terminal to answer ssh's
+/// prompts: the host must accept a key from the agent or `~/.ssh/config`, and already be in
+/// `known_hosts`. Otherwise connecting fails with ssh's error instead of hanging on a prompt.
+pub struct SshSession {
+    options: SshConnectionOptions,
+    program: PathBuf,
+    control_path: PathBuf,
+    server_path: String,
+    proxy: Mutex<Option<Child>>,
+    master: Child,
+    _temp_dir: tempfile::TempDir,
+}
+
+impl SshSession {
+    pub async fn connect(
+        options: SshConnectionOptions,
+        version: SemanticVersion,
+        cx: &AsyncAppContext,
+    ) -> Result<Arc<Self>> {
+        Self::connect_internal("ssh".as_ref(), options, version, None, cx).await
+    }
+
+    async fn connect_internal(
+        program: &Path,
+        options: SshConnectionOptions,
+        version: SemanticVersion,
+        server_binary: Option<PathBuf>,
+        cx: &AsyncAppContext,
+    ) -> Result<Arc<Self>> {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("zed-ssh-session")
+            .tempdir()?;
+        let control_path = temp_dir.path().join("control");
+        let master = Command::new(program)
+            .args(["-N", "-o", "ControlMaster=yes", "-o", "BatchMode=yes"])
+            .arg("-o")
+            .arg(format!("ControlPath={}", control_path.display()))
+            .args(port_args(&options))
+            .arg(options.destination())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to start {program:?}"))?;
+
+        let mut this = Self {
+            options,
+            program: program.to_path_buf(),
+            control_path,
+            server_path: String::new(),
+            proxy: Mutex::new(None),
+            _temp_dir: temp_dir,
+            master,
+        };
+        this.wait_for_master(cx).await?;
+        this.server_path = this.ensure_server_binary(version, server_binary).await?;
+        Ok(Arc::new(this))
+    }
+
+    async fn wait_for_master(&mut self, cx: &AsyncAppContext) -> Result<()> {
+        let started_at = Instant::now();
+        loop {
+            if let Some(status) = self.master.try_status()? {
+                let mut stderr = String::new();
+                if let Some(mut output) = self.master.stderr.take() {
+                    output.read_to_string(&mut stderr).await.log_err();
+                }
+                return Err(anyhow!(
+                    "failed to connect to {}: {status}: {}. \
+                    Passwords, passphrases and unknown host keys can't be entered when \
+                    connecting from Zed, so make sure that `ssh {}` works without prompting.",
+                    self.options.host,
+                    stderr.trim(),
+                    self.options.destination(),
+                ));
+            }
+            if self
+                .command(&["-O", "check"], &[])
+                .output()
+                .await?
+                .status
+                .success()
+            {
+                return Ok(());
+            }
+            if started_at.elapsed() > CONNECT_TIMEOUT {
+                return Err(anyhow!("timed out connecting to {}", self.options.host));
+            }
+            cx.background_executor()
+                .timer(Duration::from_millis(100))
+                .await;
+        }
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        version: SemanticVersion,
+        server_binary: Option<PathBuf>,
+    ) -> Result<String> {
+        let server_path = format!("{SERVER_DIR}/{SERVER_BINARY_NAME}-{version}");
+        let server_binary = server_binary
+            .or_else(|| std::env::var_os("ZED_REMOTE_SERVER_BINARY").map(PathBuf::from));
+        if server_binary.is_none() && self.run(&["test", "-x", &server_path]).await.is_ok() {
+            return Ok(server_path);
+        }
+
+        let server_binary = match server_binary {
+            Some(path) => path,
+            None => {
+                let uname = self.run(&["uname", "-sm"]).await?;
+                let (os, arch) = parse_uname(&uname)?;
+                local_server_binary(os, arch)?
+            }
+        };
+        log::info!(
+            "uploading {server_binary:?} to {}:{server_path}",
+            self.options.host
+        );
+        let contents = smol::fs::read(&server_binary)
+            .await
+            .with_context(|| format!("failed to read server binary {server_binary:?}"))?;
+        let upload_path = format!("{server_path}.tmp");
+        let mut child = self
+            .command(
+                &[],
+                &[&format!(
+                    "mkdir -p {SERVER_DIR} && cat > {upload_path} && chmod +x {upload_path} && mv {upload_path} {server_path}"
+                )],
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&contents).await?;
+            stdin.close().await?;
+        }
+        let output = child.output().await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "failed to upload server binary: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(server_path)
+    }
+
+    /// Starts a proxy to the server on the host and returns a connection over its stdio.
+    ///
+    /// The proxy starts the server if it isn't running yet. The server outlives the proxy, so
+    /// projects opened on it survive a dropped tunnel.
+    pub fn connect_server(&self, cx: &AsyncAppContext) -> Result<Connection> {
+        let mut child = self
+            .command(&["-T"], &[&self.server_path, "proxy"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to start the server proxy")?;
+        let stdin = child.stdin.take().context("no proxy stdin")?;
+        let stdout = child.stdout.take().context("no proxy stdout")?;
+        if let Some(stderr) = child.stderr.take() {
+            let host = self.options.host.clone();
+            cx.background_executor()
+                .spawn(async move {
+                    let mut lines = BufReader::new(stderr).lines();
+                    while let Some(Ok(line)) = lines.next().await {
+                        log::info!("{host}: {line}");
+                    }
+                })
+                .detach();
+        }
+        // Dropping the previous proxy kills it, which closes the connection that used it.
+        self.proxy.lock().replace(child);
+        Ok(Connection::over_stream(stdout, stdin))
+    }
+
+    pub fn options(&self) -> &SshConnectionOptions {
+        &self.options
+    }
+
+    async fn run(&self, command: &[&str]) -> Result<String> {
+        let output = self.command(&[], command).output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(anyhow!(
+                "{:?} failed on {}: {}",
+                command.join(" "),
+                self.options.host,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    fn command(&self, ssh_args: &[&str], remote_command: &[&str]) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .arg("-o")
+            .arg(format!("ControlPath={}", self.control_path.display()))
+            .args(port_args(&self.options))
+            .args(ssh_args)
+            .arg(self.options.destination())
+            .args(remote_command.iter().map(OsStr::new))
+            .stdin(Stdio::null());
+        command
+    }
+}
+
+fn port_args(options: &SshConnectionOptions) -> Vec<String> {
+    match options.port {
+        Some(port) => vec!["-p".into(), port.to_string()],
+        None => Vec::new(),
+    }
+}
+
+fn parse_uname(uname: &str) -> Result<(&'static str, &'static str)> {
+    let mut parts = uname.split_whitespace();
+    let os = match parts.next() {
+        Some("Linux") => "linux",
+        Some("Darwin") => "macos",
+        _ => return Err(anyhow!("unsupported remote platform {uname:?}")),
+    };
+    let arch = match parts.next() {
+        Some("x86_64") | Some("amd64") => "x86_64",
+        Some("aarch64") | Some("arm64") => "aarch64",
+        _ => return Err(anyhow!("unsupported remote architecture {uname:?}")),
+    };
+    Ok((os, arch))
+}
+
+/// Finds the server binary to upload to a host with the given platform, which ships next to
+/// the Zed executable.
+fn local_server_binary(os: &str, arch: &str) -> Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let dir = exe
+        .parent()
+        .ok_or_else(|| anyhow!("no parent directory for {exe:?}"))?;
+    let mut candidates = vec![dir.join(format!("{SERVER_BINARY_NAME}-{os}-{arch}"))];
+    if os == std::env::consts::OS && arch == std::env::consts::ARCH {
+        candidates.push(dir.join(SERVER_BINARY_NAME));
+    }
+    candidates
+        .into_iter()
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow!("no {SERVER_BINARY_NAME} binary for {os} {arch} in {dir:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use rpc::{proto, Peer, TypedEnvelope};
+
+    #[test]
+    fn test_parse_project_url() {
+        let (options, path) =
+            SshConnectionOptions::parse_project_url("ssh://me@example.com:2222/home/me/src")
+                .unwrap();
+        assert_eq!(
+            options,
+            SshConnectionOptions {
+                host: "example.com".into(),
+                username: Some("me".into()),
+                port: Some(2222),
+            }
+        );
+        assert_eq!(path, "/home/me/src");
+
+        let (options, path) =
+            SshConnectionOptions::parse_project_url("ssh://example.com/~/src").unwrap();
+        assert_eq!(options.username, None);
+        assert_eq!(options.port, None);
+        assert_eq!(path, "~/src");
+
+        let (options, path) =
+            SshConnectionOptions::parse_project_url("me@example.com:src/zed").unwrap();
+        assert_eq!(options.host, "example.com");
+        assert_eq!(options.username.as_deref(), Some("me"));
+        assert_eq!(path, "src/zed");
+
+        assert!(SshConnectionOptions::parse_project_url("example.com").is_err());
+        assert!(SshConnectionOptions::parse_project_url("ssh://example.com").is_err());
+        assert!(SshConnectionOptions::parse_project_url("ssh://example.com:x/src").is_err());
+    }
+
+    #[test]
+    fn test_parse_uname() {
+        assert_eq!(parse_uname("Linux x86_64\n").unwrap(), ("linux", "x86_64"));
+        assert_eq!(parse_uname("Darwin arm64").unwrap(), ("macos", "aarch64"));
+        assert!(parse_uname("Windows_NT x86_64").is_err());
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_ssh_session_with_stand_in(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        std::fs::create_dir(&home).unwrap();
+
+        // Stands in for ssh by skipping its options and running the command on this machine,
+        // with the master connection sleeping until it is killed.
+        let ssh = write_script(
+            dir.path(),
+            "ssh",
+            &format!(
+                r#"
+                while [ $# -gt 0 ]; do
+                    case "$1" in
+                        -N) exec sleep 1000000 ;;
+                        -O) exit 0 ;;
+                        -o|-p) shift 2 ;;
+                        -*) shift ;;
+                        *) break ;;
+                    esac
+                done
+                shift
+                cd {home:?} && exec sh -c "$*"
+                "#
+            ),
+        );
+        // Echoes every frame back, which lets a peer answer its own requests.
+        let server = write_script(dir.path(), "server", "exec cat");
+
+        let options = SshConnectionOptions {
+            host: "example.com".into(),
+            username: None,
+            port: None,
+        };
+        let session = SshSession::connect_internal(
+            &ssh,
+            options,
+            SemanticVersion::new(1, 2, 3),
+            Some(server),
+            &cx.to_async(),
+        )
+        .await
+        .unwrap();
+        assert!(home.join(".zed_server/zed-remote-server-1.2.3").is_file());
+
+        for _ in 0..2 {
+            let connection = session.connect_server(&cx.to_async()).unwrap();
+            let peer = Peer::new(0);
+            let executor = cx.executor();
+            let (connection_id, io, mut incoming) =
+                peer.add_connection(connection, move |duration| executor.timer(duration));
+            cx.executor().spawn(io).detach();
+            cx.executor()
+                .spawn({
+                    let peer = peer.clone();
+                    async move {
+                        let envelope = incoming.next().await.unwrap();
+                        let envelope = envelope
+                            .into_any()
+                            .downcast::<TypedEnvelope<proto::Ping>>()
+                            .unwrap();
+                        peer.respond(envelope.receipt(), proto::Ack {}).unwrap();
+                    }
+                })
+                .detach();
+            peer.request(connection_id, proto::Ping {}).await.unwrap();
+        }
+    }
+
+    #[cfg(unix)]
+    fn write_script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+}
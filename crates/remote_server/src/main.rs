@@ -0,0 +1,179 @@
+#![cfg_attr(not(unix), allow(unused))]
+
+use anyhow::{Context as _, Result};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Serves projects to Zed over SSH.
+///
+/// Zed uploads this binary to the remote host and runs `proxy` over an SSH connection. The proxy
+/// connects to the long-running server started by `run`, starting it first if needed, so that
+/// projects survive dropped connections.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs the server, listening for proxies on a socket next to this binary.
+    Run,
+    /// Relays stdin and stdout to the server, starting it if it isn't running.
+    Proxy,
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("zed-remote-server is not supported on this platform");
+    std::process::exit(1);
+}
+
+#[cfg(unix)]
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let binary_path = std::env::current_exe().context("failed to find the server binary")?;
+    // The binary name includes its version, so each version gets its own server.
+    let socket_path = PathBuf::from(format!("{}.sock", binary_path.display()));
+    match cli.command {
+        Command::Run => run(socket_path),
+        Command::Proxy => proxy(socket_path, binary_path),
+    }
+}
+
+#[cfg(unix)]
+fn run(socket_path: PathBuf) -> Result<()> {
+    use client::{user::UserStore, Client};
+    use fs::RealFs;
+    use futures::StreamExt as _;
+    use git::GitHostingProviderRegistry;
+    use gpui::{App, Context as _};
+    use language::LanguageRegistry;
+    use node_runtime::RealNodeRuntime;
+    use project::Project;
+    use remote_server::{AppState, RemoteServer};
+    use rpc::Connection;
+    use smol::net::unix::UnixListener;
+    use std::sync::Arc;
+    use util::ResultExt as _;
+
+    env_logger::init();
+
+    if std::os::unix::net::UnixStream::connect(&socket_path).is_ok() {
+        log::info!("server is already running at {socket_path:?}");
+        return Ok(());
+    }
+    std::fs::remove_file(&socket_path).ok();
+    let listener = UnixListener::bind(&socket_path)
+        .with_context(|| format!("failed to listen at {socket_path:?}"))?;
+
+    App::new().run(move |cx| {
+        release_channel::init(
+            release_channel::AppVersion::init(env!("CARGO_PKG_VERSION")),
+            cx,
+        );
+        settings::init(cx);
+        client::init_settings(cx);
+        let client = Client::production(cx);
+        cx.update_http_client(client.http_client().clone());
+
+        let mut languages =
+            LanguageRegistry::new(gpui::Task::ready(()), cx.background_executor().clone());
+        languages.set_language_server_download_dir(paths::languages_dir().clone());
+        let languages = Arc::new(languages);
+        let node_runtime = RealNodeRuntime::new(client.http_client());
+        language::init(cx);
+        languages::init(languages.clone(), node_runtime.clone(), cx);
+        Project::init(&client, cx);
+
+        let app_state = AppState {
+            languages,
+            node_runtime,
+            user_store: cx.new_model(|cx| UserStore::new(client.clone(), cx)),
+            fs: Arc::new(RealFs::new(
+                Arc::new(GitHostingProviderRegistry::new()),
+                None,
+            )),
+        };
+        let server = cx.new_model(|cx| RemoteServer::new(client, app_state, cx));
+
+        cx.spawn(|mut cx| async move {
+            let mut incoming = listener.incoming();
+            while let Some(stream) = incoming.next().await {
+                let Some(stream) = stream.log_err() else {
+                    continue;
+                };
+                let connection = Connection::over_stream(stream.clone(), stream);
+                server.update(&mut cx, |server, cx| server.add_guest(connection, cx))?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    });
+    Ok(())
+}
+
+#[cfg(unix)]
+fn proxy(socket_path: PathBuf, binary_path: PathBuf) -> Result<()> {
+    use std::{
+        io::{Read as _, Write as _},
+        net::Shutdown,
+        os::unix::{net::UnixStream, process::CommandExt as _},
+        process::{Command, Stdio},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    const START_TIMEOUT: Duration = Duration::from_secs(5);
+
+    let stream = match UnixStream::connect(&socket_path) {
+        Ok(stream) => stream,
+        Err(_) => {
+            let log_file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(format!("{}.log", binary_path.display()))?;
+            // Detach the server from the SSH session, so that it outlives the connection, and
+            // keep gpui from looking for a display that was forwarded with it.
+            Command::new(&binary_path)
+                .arg("run")
+                .env_remove("DISPLAY")
+                .env_remove("WAYLAND_DISPLAY")
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(log_file)
+                .process_group(0)
+                .spawn()
+                .context("failed to start the server")?;
+
+            let started_at = Instant::now();
+            loop {
+                match UnixStream::connect(&socket_path) {
+                    Ok(stream) => break stream,
+                    Err(error) if started_at.elapsed() > START_TIMEOUT => {
+                        return Err(error).context("timed out waiting for the server to start")
+                    }
+                    Err(_) => thread::sleep(Duration::from_millis(50)),
+                }
+            }
+        }
+    };
+
+    let mut writer = stream.try_clone()?;
+    thread::spawn(move || {
+        std::io::copy(&mut std::io::stdin().lock(), &mut writer).ok();
+        writer.shutdown(Shutdown::Write).ok();
+    });
+
+    let mut reader = stream;
+    let mut stdout = std::io::stdout().lock();
+    let mut buffer = [0; 8192];
+    loop {
+        let len = reader.read(&mut buffer)?;
+        if len == 0 {
+            return Ok(());
+        }
+        stdout.write_all(&buffer[..len])?;
+        stdout.flush()?;
+    }
+}
@@ -0,0 +1,1034 @@
+use anyhow::{anyhow, Result};
+use client::{user::UserStore, Client};
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use futures::{FutureExt as _, StreamExt as _};
+use gpui::{Model, ModelContext, Task};
+use language::LanguageRegistry;
+use node_runtime::NodeRuntime;
+use project::Project;
+use rpc::{
+    proto::{self, AnyTypedEnvelope, EntityMessage, EnvelopedMessage, RequestMessage},
+    Connection, ConnectionId, ErrorExt as _, Peer, TypedEnvelope,
+};
+use settings::SettingsStore;
+use std::{any::TypeId, path::PathBuf, rc::Rc, sync::Arc};
+use util::ResultExt as _;
+
+#[cfg(test)]
+const MAX_CHUNK_SIZE: usize = 2;
+#[cfg(not(test))]
+const MAX_CHUNK_SIZE: usize = 256;
+
+pub struct AppState {
+    pub languages: Arc<LanguageRegistry>,
+    pub node_runtime: Arc<dyn NodeRuntime>,
+    pub user_store: Model<UserStore>,
+    pub fs: Arc<dyn Fs>,
+}
+
+type MessageHandler = Box<
+    dyn Fn(
+        &mut RemoteServer,
+        Box<dyn AnyTypedEnvelope>,
+        &mut ModelContext<RemoteServer>,
+    ) -> Task<Result<()>>,
+>;
+
+/// Serves the projects on this machine to Zed instances connected over SSH.
+///
+/// The server plays the part that collab plays for shared projects: local projects are shared
+/// with it through an in-process client, and it relays messages between those projects and the
+/// guests connected through [`RemoteServer::add_guest`].
+pub struct RemoteServer {
+    peer: Arc<Peer>,
+    host_client: Arc<Client>,
+    host_connection_id: Option<ConnectionId>,
+    app_state: AppState,
+    projects: HashMap<u64, SharedProject>,
+    next_project_id: u64,
+    handlers: Rc<HashMap<TypeId, MessageHandler>>,
+}
+
+struct SharedProject {
+    path: PathBuf,
+    project: Model<Project>,
+    guests: HashMap<ConnectionId, u16>,
+    next_replica_id: u16,
+    /// The ids of every entry and repository sent for each worktree, used to tell rejoining
+    /// guests which of them were removed while they were away.
+    sent_entry_ids: HashMap<u64, HashSet<u64>>,
+    sent_repository_ids: HashMap<u64, HashSet<u64>>,
+}
+
+impl SharedProject {
+    fn record_worktree_update(&mut self, update: &proto::UpdateWorktree) {
+        self.sent_entry_ids
+            .entry(update.worktree_id)
+            .or_default()
+            .extend(update.updated_entries.iter().map(|entry| entry.id));
+        self.sent_repository_ids
+            .entry(update.worktree_id)
+            .or_default()
+            .extend(
+                update
+                    .updated_repositories
+                    .iter()
+                    .map(|repository| repository.work_directory_id),
+            );
+    }
+}
+
+impl RemoteServer {
+    pub fn new(host_client: Arc<Client>, app_state: AppState, cx: &mut ModelContext<Self>) -> Self {
+        let this = cx.weak_model();
+        host_client.set_tunnel(move |cx| {
+            let (host_connection, server_connection) = Connection::in_process();
+            let result = this.update(&mut cx.clone(), |this, cx| {
+                this.add_connection(server_connection, true, cx);
+            });
+            Task::ready(result.map(|_| host_connection))
+        });
+
+        let mut handlers = HashMap::default();
+        add_request_handler(&mut handlers, Self::handle_open_ssh_project);
+        add_message_handler(&mut handlers, Self::handle_join_project);
+        add_message_handler(&mut handlers, Self::handle_rejoin_remote_projects);
+        add_message_handler(&mut handlers, Self::handle_leave_project);
+        add_message_handler(&mut handlers, Self::handle_unshare_project);
+        add_message_handler(&mut handlers, Self::handle_create_buffer_for_peer);
+        add_request_handler(&mut handlers, Self::handle_update_worktree);
+        add_request_handler(
+            &mut handlers,
+            Self::broadcast_project_request::<proto::UpdateProject>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::broadcast_project_request::<proto::UpdateBuffer>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::broadcast_project_request::<proto::RefreshInlayHints>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::UpdateBufferFile>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::BufferReloaded>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::BufferSaved>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::UpdateDiffBase>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::UpdateDiagnosticSummary>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::StartLanguageServer>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::UpdateLanguageServer>,
+        );
+        add_message_handler(
+            &mut handlers,
+            Self::broadcast_project_message::<proto::UpdateWorktreeSettings>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::CreateProjectEntry>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::RenameProjectEntry>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::CopyProjectEntry>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::DeleteProjectEntry>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::ExpandProjectEntry>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::ApplyCompletionAdditionalEdits>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::ResolveCompletionDocumentation>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::ApplyCodeAction>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::OnTypeFormatting>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::InlayHints>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::ResolveInlayHint>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::ReloadBuffers>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::SynchronizeBuffers>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::FormatBuffers>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetCodeActions>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetCompletions>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetHover>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetDefinition>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetTypeDefinition>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetImplementation>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetDocumentHighlights>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetReferences>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::PrepareRename>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::PerformRename>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::SearchProject>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::GetProjectSymbols>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::OpenBufferForSymbol>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::OpenBufferById>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::OpenBufferByPath>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::OpenNewBuffer>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::SaveBuffer>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::LspExtExpandMacro>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::BlameBuffer>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::MultiLspQuery>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::RestartLanguageServers>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::TaskContextForLocation>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::TaskTemplates>,
+        );
+        add_request_handler(
+            &mut handlers,
+            Self::forward_project_request::<proto::LinkedEditingRange>,
+        );
+
+        Self {
+            peer: Peer::new(0),
+            host_client,
+            host_connection_id: None,
+            app_state,
+            projects: HashMap::default(),
+            next_project_id: 0,
+            handlers: Rc::new(handlers),
+        }
+    }
+
+    /// Serves the guest at the other end of the given connection until it is closed.
+    pub fn add_guest(&mut self, connection: Connection, cx: &mut ModelContext<Self>) {
+        self.add_connection(connection, false, cx);
+    }
+
+    fn add_connection(
+        &mut self,
+        connection: Connection,
+        is_host: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> ConnectionId {
+        let executor = cx.background_executor().clone();
+        let (connection_id, io, mut incoming) = self
+            .peer
+            .add_connection(connection, move |duration| executor.timer(duration));
+        if is_host {
+            self.host_connection_id = Some(connection_id);
+        }
+        self.peer
+            .send(
+                connection_id,
+                proto::Hello {
+                    peer_id: Some(connection_id.into()),
+                },
+            )
+            .log_err();
+
+        let io = cx.background_executor().spawn(io);
+        cx.spawn(|this, mut cx| async move {
+            let mut io = io.fuse();
+            loop {
+                futures::select_biased! {
+                    result = io => {
+                        if let Err(error) = result {
+                            log::info!("connection {connection_id:?} closed: {error}");
+                        }
+                        break;
+                    }
+                    message = incoming.next().fuse() => {
+                        let Some(message) = message else { break };
+                        this.update(&mut cx, |this, cx| this.handle_message(message, cx))?;
+                    }
+                }
+            }
+            this.update(&mut cx, |this, _| this.connection_lost(connection_id))
+        })
+        .detach_and_log_err(cx);
+
+        connection_id
+    }
+
+    fn handle_message(&mut self, message: Box<dyn AnyTypedEnvelope>, cx: &mut ModelContext<Self>) {
+        let handlers = self.handlers.clone();
+        if let Some(handler) = handlers.get(&message.payload_type_id()) {
+            handler(self, message, cx).detach_and_log_err(cx);
+        } else {
+            log::warn!("unhandled message {}", message.payload_type_name());
+            self.peer.respond_with_unhandled_message(message).log_err();
+        }
+    }
+
+    fn connection_lost(&mut self, connection_id: ConnectionId) {
+        self.peer.disconnect(connection_id);
+        if self.host_connection_id == Some(connection_id) {
+            log::error!("lost the connection to the host client");
+            self.host_connection_id = None;
+            return;
+        }
+        let project_ids = self
+            .projects
+            .iter()
+            .filter(|(_, project)| project.guests.contains_key(&connection_id))
+            .map(|(project_id, _)| *project_id)
+            .collect::<Vec<_>>();
+        for project_id in project_ids {
+            self.remove_guest_from_project(project_id, connection_id);
+        }
+    }
+
+    fn handle_open_ssh_project(
+        &mut self,
+        envelope: TypedEnvelope<proto::OpenSshProject>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<proto::OpenSshProjectResponse>> {
+        let path = PathBuf::from(shellexpand::tilde(&envelope.payload.path).as_ref());
+        if let Some((project_id, _)) = self
+            .projects
+            .iter()
+            .find(|(_, project)| project.path == path)
+        {
+            return Task::ready(Ok(proto::OpenSshProjectResponse {
+                project_id: *project_id,
+            }));
+        }
+
+        let project = Project::local(
+            self.host_client.clone(),
+            self.app_state.node_runtime.clone(),
+            self.app_state.user_store.clone(),
+            self.app_state.languages.clone(),
+            self.app_state.fs.clone(),
+            cx,
+        );
+        let fs = self.app_state.fs.clone();
+        let client = self.host_client.clone();
+        cx.spawn(|this, mut cx| async move {
+            if fs.metadata(&path).await?.is_none() {
+                return Err(anyhow!("{path:?} does not exist"));
+            }
+            client.authenticate_and_connect(false, &cx).await?;
+
+            let (worktree, _) = project
+                .update(&mut cx, |project, cx| {
+                    project.find_or_create_local_worktree(&path, true, cx)
+                })?
+                .await?;
+            worktree.update(&mut cx, |worktree, cx| {
+                if let Some(worktree) = worktree.as_local_mut() {
+                    worktree.share_private_files(cx);
+                }
+            })?;
+
+            // Register the project before sharing it, so that its first updates are accepted.
+            let project_id = this.update(&mut cx, |this, _| {
+                this.next_project_id += 1;
+                let project_id = this.next_project_id;
+                this.projects.insert(
+                    project_id,
+                    SharedProject {
+                        path,
+                        project: project.clone(),
+                        guests: HashMap::default(),
+                        next_replica_id: 1,
+                        sent_entry_ids: HashMap::default(),
+                        sent_repository_ids: HashMap::default(),
+                    },
+                );
+                project_id
+            })?;
+            project.update(&mut cx, |project, cx| project.shared(project_id, cx))??;
+            Ok(proto::OpenSshProjectResponse { project_id })
+        })
+    }
+
+    fn handle_join_project(
+        &mut self,
+        envelope: TypedEnvelope<proto::JoinProject>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let guest = ConnectionId::from(envelope.sender_id);
+        let project_id = envelope.payload.project_id;
+        let replica_id = match self.add_guest_to_project(project_id, guest, None) {
+            Ok(replica_id) => replica_id,
+            Err(error) => {
+                return self
+                    .peer
+                    .respond_with_error(envelope.receipt(), error.to_proto())
+            }
+        };
+
+        let (worktrees, language_servers) = self.project_metadata(project_id, cx);
+        self.peer.respond(
+            envelope.receipt(),
+            proto::JoinProjectResponse {
+                project_id,
+                replica_id: replica_id as u32,
+                worktrees,
+                collaborators: Vec::new(),
+                language_servers,
+                role: proto::ChannelRole::Member.into(),
+                dev_server_project_id: None,
+            },
+        )?;
+        self.send_project_state(project_id, guest, cx);
+        Ok(())
+    }
+
+    /// Rejoins a guest whose tunnel was reopened to the projects it had open.
+    ///
+    /// Projects that can't be rejoined are left out of the response and unshared with the
+    /// guest, so that it stops waiting for them.
+    fn handle_rejoin_remote_projects(
+        &mut self,
+        envelope: TypedEnvelope<proto::RejoinRemoteProjects>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let guest = ConnectionId::from(envelope.sender_id);
+        let mut rejoined_projects = Vec::new();
+        let mut failed_project_ids = Vec::new();
+        for project in &envelope.payload.rejoined_projects {
+            let previous_replica_id = project.replica_id.map(|replica_id| replica_id as u16);
+            match self.add_guest_to_project(project.id, guest, previous_replica_id) {
+                Ok(_) => {
+                    let (worktrees, language_servers) = self.project_metadata(project.id, cx);
+                    rejoined_projects.push(proto::RejoinedProject {
+                        id: project.id,
+                        worktrees,
+                        collaborators: Vec::new(),
+                        language_servers,
+                    });
+                }
+                Err(error) => {
+                    // The server may have been restarted since the guest joined.
+                    log::error!("failed to rejoin project {}: {error:#}", project.id);
+                    failed_project_ids.push(project.id);
+                }
+            }
+        }
+
+        let rejoined_project_ids = rejoined_projects
+            .iter()
+            .map(|project| project.id)
+            .collect::<Vec<_>>();
+        self.peer.respond(
+            envelope.receipt(),
+            proto::RejoinRemoteProjectsResponse { rejoined_projects },
+        )?;
+        for project_id in rejoined_project_ids {
+            self.send_project_state(project_id, guest, cx);
+        }
+        for project_id in failed_project_ids {
+            self.peer
+                .send(guest, proto::UnshareProject { project_id })
+                .log_err();
+        }
+        Ok(())
+    }
+
+    fn handle_leave_project(
+        &mut self,
+        envelope: TypedEnvelope<proto::LeaveProject>,
+        _: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        self.remove_guest_from_project(
+            envelope.payload.project_id,
+            ConnectionId::from(envelope.sender_id),
+        );
+        Ok(())
+    }
+
+    fn handle_unshare_project(
+        &mut self,
+        envelope: TypedEnvelope<proto::UnshareProject>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let project_id = envelope.payload.project_id;
+        self.broadcast_project_message(envelope, cx)?;
+        self.projects.remove(&project_id);
+        Ok(())
+    }
+
+    fn handle_create_buffer_for_peer(
+        &mut self,
+        envelope: TypedEnvelope<proto::CreateBufferForPeer>,
+        _: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let peer_id = envelope
+            .payload
+            .peer_id
+            .ok_or_else(|| anyhow!("invalid peer id"))?;
+        self.peer.forward_send(
+            ConnectionId::from(envelope.sender_id),
+            ConnectionId::from(peer_id),
+            envelope.payload,
+        )
+    }
+
+    fn handle_update_worktree(
+        &mut self,
+        envelope: TypedEnvelope<proto::UpdateWorktree>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<proto::Ack>> {
+        if let Some(project) = self.projects.get_mut(&envelope.payload.project_id) {
+            project.record_worktree_update(&envelope.payload);
+        }
+        self.broadcast_project_request(envelope, cx)
+    }
+
+    fn forward_project_request<M: EntityMessage + RequestMessage>(
+        &mut self,
+        envelope: TypedEnvelope<M>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<M::Response>> {
+        let guest = ConnectionId::from(envelope.sender_id);
+        let project_id = envelope.payload.remote_entity_id();
+        let response = self
+            .projects
+            .get(&project_id)
+            .filter(|project| project.guests.contains_key(&guest))
+            .ok_or_else(|| anyhow!("not a guest of project {project_id}"))
+            .and_then(|_| {
+                self.host_connection_id
+                    .ok_or_else(|| anyhow!("host is not connected"))
+            })
+            .map(|host| self.peer.forward_request(guest, host, envelope.payload));
+        cx.spawn(|_, _| async move { response?.await })
+    }
+
+    fn broadcast_project_request<M: EntityMessage + RequestMessage<Response = proto::Ack>>(
+        &mut self,
+        envelope: TypedEnvelope<M>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<proto::Ack>> {
+        Task::ready(
+            self.broadcast_project_message(envelope, cx)
+                .map(|_| proto::Ack {}),
+        )
+    }
+
+    /// Forwards a message to everyone in the project but its sender.
+    fn broadcast_project_message<M: EntityMessage + EnvelopedMessage>(
+        &mut self,
+        envelope: TypedEnvelope<M>,
+        _: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let sender = ConnectionId::from(envelope.sender_id);
+        let project_id = envelope.payload.remote_entity_id();
+        let project = self
+            .projects
+            .get(&project_id)
+            .ok_or_else(|| anyhow!("no project with id {project_id}"))?;
+        let receivers = project
+            .guests
+            .keys()
+            .copied()
+            .chain(self.host_connection_id)
+            .filter(|receiver| *receiver != sender);
+        for receiver in receivers {
+            self.peer
+                .forward_send(sender, receiver, envelope.payload.clone())
+                .log_err();
+        }
+        Ok(())
+    }
+
+    fn add_guest_to_project(
+        &mut self,
+        project_id: u64,
+        guest: ConnectionId,
+        previous_replica_id: Option<u16>,
+    ) -> Result<u16> {
+        let host = self
+            .host_connection_id
+            .ok_or_else(|| anyhow!("host is not connected"))?;
+        let project = self
+            .projects
+            .get_mut(&project_id)
+            .ok_or_else(|| anyhow!("no project with id {project_id}"))?;
+
+        // Rejoining guests keep their replica id unless someone else holds it, so that the
+        // operations they made while away keep their authorship. Other replica ids are never
+        // reused.
+        let replica_id = previous_replica_id
+            .filter(|replica_id| {
+                *replica_id != 0
+                    && *replica_id < project.next_replica_id
+                    && !project
+                        .guests
+                        .values()
+                        .any(|guest_replica_id| guest_replica_id == replica_id)
+            })
+            .unwrap_or_else(|| {
+                let replica_id = project.next_replica_id;
+                project.next_replica_id += 1;
+                replica_id
+            });
+        project.guests.insert(guest, replica_id);
+        self.peer.send(
+            host,
+            proto::AddProjectCollaborator {
+                project_id,
+                collaborator: Some(proto::Collaborator {
+                    peer_id: Some(guest.into()),
+                    replica_id: replica_id as u32,
+                    user_id: 0,
+                }),
+            },
+        )?;
+        Ok(replica_id)
+    }
+
+    fn remove_guest_from_project(&mut self, project_id: u64, guest: ConnectionId) {
+        let Some(project) = self.projects.get_mut(&project_id) else {
+            return;
+        };
+        if project.guests.remove(&guest).is_none() {
+            return;
+        }
+        if let Some(host) = self.host_connection_id {
+            self.peer
+                .send(
+                    host,
+                    proto::RemoveProjectCollaborator {
+                        project_id,
+                        peer_id: Some(guest.into()),
+                    },
+                )
+                .log_err();
+        }
+    }
+
+    fn project_metadata(
+        &self,
+        project_id: u64,
+        cx: &ModelContext<Self>,
+    ) -> (Vec<proto::WorktreeMetadata>, Vec<proto::LanguageServer>) {
+        let Some(project) = self.projects.get(&project_id) else {
+            return Default::default();
+        };
+        let project = project.project.read(cx);
+        let language_servers = project
+            .language_server_statuses()
+            .map(|(server_id, status)| proto::LanguageServer {
+                id: server_id.0 as u64,
+                name: status.name.clone(),
+            })
+            .collect();
+        (project.worktree_metadata_protos(cx), language_servers)
+    }
+
+    /// Sends everything a guest needs to catch up with the project, after it joins or rejoins.
+    fn send_project_state(
+        &mut self,
+        project_id: u64,
+        guest: ConnectionId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let peer = self.peer.clone();
+        let Some(shared_project) = self.projects.get_mut(&project_id) else {
+            return;
+        };
+        let project = shared_project.project.clone();
+        let project = project.read(cx);
+        let settings_store = cx.global::<SettingsStore>();
+
+        for worktree_handle in project.worktrees() {
+            let worktree = worktree_handle.read(cx);
+            let Some(local_worktree) = worktree.as_local() else {
+                continue;
+            };
+            let worktree_id = worktree.id().to_proto();
+            let mut update = local_worktree
+                .snapshot()
+                .build_initial_update(project_id, worktree_id);
+
+            // A rejoining guest may still have entries that were removed while it was away.
+            let entry_ids = update
+                .updated_entries
+                .iter()
+                .map(|entry| entry.id)
+                .collect::<HashSet<_>>();
+            let sent_entry_ids = shared_project
+                .sent_entry_ids
+                .entry(worktree_id)
+                .or_default();
+            update.removed_entries = sent_entry_ids.difference(&entry_ids).copied().collect();
+            sent_entry_ids.extend(entry_ids);
+            let repository_ids = update
+                .updated_repositories
+                .iter()
+                .map(|repository| repository.work_directory_id)
+                .collect::<HashSet<_>>();
+            let sent_repository_ids = shared_project
+                .sent_repository_ids
+                .entry(worktree_id)
+                .or_default();
+            update.removed_repositories = sent_repository_ids
+                .difference(&repository_ids)
+                .copied()
+                .collect();
+            sent_repository_ids.extend(repository_ids);
+
+            for update in proto::split_worktree_update(update, MAX_CHUNK_SIZE) {
+                peer.send(guest, update).log_err();
+            }
+
+            let root_id = worktree_handle.entity_id().as_u64() as usize;
+            for (path, content) in settings_store.local_settings(root_id) {
+                peer.send(
+                    guest,
+                    proto::UpdateWorktreeSettings {
+                        project_id,
+                        worktree_id,
+                        path: path.to_string_lossy().into(),
+                        content: Some(content),
+                        kind: proto::LocalSettingsKind::Settings.into(),
+                    },
+                )
+                .log_err();
+            }
+            for (directory, content) in settings_store.local_editorconfigs(root_id) {
+                peer.send(
+                    guest,
+                    proto::UpdateWorktreeSettings {
+                        project_id,
+                        worktree_id,
+                        path: directory.to_string_lossy().into(),
+                        content: Some(content),
+                        kind: proto::LocalSettingsKind::Editorconfig.into(),
+                    },
+                )
+                .log_err();
+            }
+        }
+
+        for (project_path, server_id, summary) in project.diagnostic_summaries(false, cx) {
+            peer.send(
+                guest,
+                proto::UpdateDiagnosticSummary {
+                    project_id,
+                    worktree_id: project_path.worktree_id.to_proto(),
+                    summary: Some(summary.to_proto(server_id, &project_path.path)),
+                },
+            )
+            .log_err();
+        }
+    }
+}
+
+fn add_request_handler<M, F>(handlers: &mut HashMap<TypeId, MessageHandler>, handler: F)
+where
+    M: RequestMessage,
+    F: 'static
+        + Fn(
+            &mut RemoteServer,
+            TypedEnvelope<M>,
+            &mut ModelContext<RemoteServer>,
+        ) -> Task<Result<M::Response>>,
+{
+    handlers.insert(
+        TypeId::of::<M>(),
+        Box::new(move |this, envelope, cx| {
+            let envelope = *envelope.into_any().downcast::<TypedEnvelope<M>>().unwrap();
+            let receipt = envelope.receipt();
+            let response = handler(this, envelope, cx);
+            let peer = this.peer.clone();
+            cx.spawn(|_, _| async move {
+                match response.await {
+                    Ok(response) => peer.respond(receipt, response),
+                    Err(error) => peer.respond_with_error(receipt, error.to_proto()),
+                }
+            })
+        }),
+    );
+}
+
+fn add_message_handler<M, F>(handlers: &mut HashMap<TypeId, MessageHandler>, handler: F)
+where
+    M: EnvelopedMessage,
+    F: 'static
+        + Fn(&mut RemoteServer, TypedEnvelope<M>, &mut ModelContext<RemoteServer>) -> Result<()>,
+{
+    handlers.insert(
+        TypeId::of::<M>(),
+        Box::new(move |this, envelope, cx| {
+            let envelope = *envelope.into_any().downcast::<TypedEnvelope<M>>().unwrap();
+            Task::ready(handler(this, envelope, cx))
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::Status;
+    use clock::FakeSystemClock;
+    use fs::FakeFs;
+    use futures::channel::mpsc;
+    use gpui::{Context as _, SemanticVersion, TestAppContext};
+    use http::FakeHttpClient;
+    use node_runtime::FakeNodeRuntime;
+    use serde_json::json;
+    use std::{path::Path, time::Duration};
+
+    #[gpui::test]
+    async fn test_remote_project(server_cx: &mut TestAppContext, guest_cx: &mut TestAppContext) {
+        let fs = FakeFs::new(server_cx.executor());
+        fs.insert_tree(
+            "/code/project1",
+            json!({
+                "README.md": "# project 1",
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }",
+                    "main.rs": "fn main() {}",
+                },
+            }),
+        )
+        .await;
+
+        let server = server_cx.update(|cx| {
+            init_test(cx);
+            let client = Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            );
+            Project::init(&client, cx);
+            let app_state = AppState {
+                languages: Arc::new(LanguageRegistry::test(cx.background_executor().clone())),
+                node_runtime: FakeNodeRuntime::new(),
+                user_store: cx.new_model(|cx| UserStore::new(client.clone(), cx)),
+                fs: fs.clone(),
+            };
+            cx.new_model(|cx| RemoteServer::new(client, app_state, cx))
+        });
+
+        // The guest's tunnel hands the server's end of each connection to the server's app.
+        let (connections_tx, mut connections_rx) = mpsc::unbounded();
+        server_cx
+            .spawn({
+                let server = server.downgrade();
+                |mut cx| async move {
+                    while let Some(connection) = connections_rx.next().await {
+                        server.update(&mut cx, |server, cx| server.add_guest(connection, cx))?;
+                    }
+                    anyhow::Ok(())
+                }
+            })
+            .detach();
+
+        let guest_client = guest_cx.update(|cx| {
+            init_test(cx);
+            let client = Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            );
+            client.set_tunnel(move |_| {
+                let (guest_connection, server_connection) = Connection::in_process();
+                connections_tx.unbounded_send(server_connection).ok();
+                Task::ready(Ok(guest_connection))
+            });
+            Project::init(&client, cx);
+            client
+        });
+        let guest_user_store = guest_cx.new_model(|cx| UserStore::new(guest_client.clone(), cx));
+        let project = Project::ssh(
+            "/code/project1".into(),
+            guest_client.clone(),
+            guest_user_store,
+            Arc::new(LanguageRegistry::test(guest_cx.executor())),
+            FakeFs::new(guest_cx.executor()),
+            guest_cx.to_async(),
+        )
+        .await
+        .unwrap();
+        server_cx.run_until_parked();
+        guest_cx.run_until_parked();
+
+        let worktree =
+            project.read_with(guest_cx, |project, _| project.worktrees().next().unwrap());
+        worktree.read_with(guest_cx, |worktree, _| {
+            assert_eq!(
+                worktree.paths().map(AsRef::as_ref).collect::<Vec<_>>(),
+                vec![
+                    Path::new("README.md"),
+                    Path::new("src"),
+                    Path::new("src/lib.rs"),
+                    Path::new("src/main.rs"),
+                ]
+            );
+        });
+
+        // Buffers are opened, edited and saved on the server.
+        let worktree_id = worktree.read_with(guest_cx, |worktree, _| worktree.id());
+        let buffer = project
+            .update(guest_cx, |project, cx| {
+                project.open_buffer((worktree_id, "src/lib.rs"), cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(guest_cx, |buffer, cx| {
+            buffer.edit([(0..0, "pub ")], None, cx)
+        });
+        project
+            .update(guest_cx, |project, cx| {
+                project.save_buffer(buffer.clone(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.load("/code/project1/src/lib.rs".as_ref()).await.unwrap(),
+            "pub fn one() -> usize { 1 }"
+        );
+
+        // Files removed while the guest is disconnected are gone once its tunnel is reopened.
+        let peer = server.read_with(server_cx, |server, _| server.peer.clone());
+        peer.disconnect(ConnectionId::from(guest_client.peer_id().unwrap()));
+        fs.remove_file("/code/project1/README.md".as_ref(), Default::default())
+            .await
+            .unwrap();
+        server_cx.run_until_parked();
+        guest_cx.executor().advance_clock(Duration::from_secs(10));
+        server_cx.run_until_parked();
+        guest_cx.run_until_parked();
+
+        assert!(matches!(
+            *guest_client.status().borrow(),
+            Status::Connected { .. }
+        ));
+        assert!(!project.read_with(guest_cx, |project, _| project.is_disconnected()));
+        worktree.read_with(guest_cx, |worktree, _| {
+            assert_eq!(
+                worktree.paths().map(AsRef::as_ref).collect::<Vec<_>>(),
+                vec![
+                    Path::new("src"),
+                    Path::new("src/lib.rs"),
+                    Path::new("src/main.rs"),
+                ]
+            );
+        });
+    }
+
+    fn init_test(cx: &mut gpui::AppContext) {
+        if std::env::var("RUST_LOG").is_ok() {
+            env_logger::try_init().ok();
+        }
+
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        release_channel::init(SemanticVersion::default(), cx);
+        client::init_settings(cx);
+        language::init(cx);
+        Project::init_settings(cx);
+    }
+}
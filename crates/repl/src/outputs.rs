@@ -3,11 +3,14 @@ use std::sync::Arc;
 use crate::stdio::TerminalOutput;
 use anyhow::Result;
 use gpui::{img, AnyElement, FontWeight, ImageData, Render, View};
-use runtimelib::datatable::TableSchema;
+use runtimelib::datatable::FieldType;
 use runtimelib::media::datatable::TabularDataResource;
 use runtimelib::{ExecutionState, JupyterMessageContent, MimeBundle, MimeType};
 use serde_json::Value;
-use ui::{div, prelude::*, v_flex, IntoElement, Styled, ViewContext};
+use ui::{
+    div, prelude::*, ColumnAlignment, DataTable, IntoElement, Styled, TableColumn, TableDelegate,
+    ViewContext,
+};
 
 // Given these outputs are destined for the editor with the block decorations API, all of them must report
 // how many lines they will take up in the editor.
@@ -93,108 +96,139 @@ impl LineHeight for ImageView {
     }
 }
 
-/// TableView renders a static table inline in a buffer.
-/// It uses the https://specs.frictionlessdata.io/tabular-data-resource/ specification for data interchange.
-pub struct TableView {
-    pub table: TabularDataResource,
-}
+/// The most rows a table shows at once. Larger tables scroll within that height.
+const MAX_VISIBLE_TABLE_ROWS: usize = 20;
+/// The most rows looked at when estimating how wide each column needs to be.
+const MAX_MEASURED_TABLE_ROWS: usize = 1000;
 
-impl TableView {
-    pub fn render(&self, cx: &ViewContext<ExecutionView>) -> AnyElement {
-        let data = match &self.table.data {
-            Some(data) => data,
-            None => return div().into_any_element(),
-        };
+/// Rows of a tabular data resource, formatted for display in a [`DataTable`].
+pub struct TabularData {
+    columns: Vec<TableColumn>,
+    rows: Vec<Vec<SharedString>>,
+}
 
-        // todo!(): compute the width of each column by finding the widest cell in each column
+impl TabularData {
+    fn new(table: &TabularDataResource) -> Self {
+        let fields = &table.schema.fields;
+        let columns = fields
+            .iter()
+            .map(|field| {
+                let alignment = match field.field_type {
+                    FieldType::Number
+                    | FieldType::Integer
+                    | FieldType::Date
+                    | FieldType::Time
+                    | FieldType::Datetime
+                    | FieldType::Year
+                    | FieldType::Duration
+                    | FieldType::Yearmonth => ColumnAlignment::End,
+                    _ => ColumnAlignment::Start,
+                };
+                TableColumn::new(field.name.clone()).alignment(alignment)
+            })
+            .collect();
+        // We don't support Path based data sources
+        let rows = table
+            .data
+            .iter()
+            .flatten()
+            .map(|row| {
+                fields
+                    .iter()
+                    .map(|field| format_cell(row.get(&field.name)).into())
+                    .collect()
+            })
+            .collect();
+        Self { columns, rows }
+    }
+}
 
-        let mut headings = serde_json::Map::new();
-        for field in &self.table.schema.fields {
-            headings.insert(field.name.clone(), Value::String(field.name.clone()));
-        }
-        let header = self.render_row(&self.table.schema, true, &Value::Object(headings), cx);
+fn format_cell(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::Bool(b)) => b.to_string(),
+        Some(Value::Array(arr)) => format!("{:?}", arr),
+        Some(Value::Object(obj)) => format!("{:?}", obj),
+        Some(Value::Null) | None => String::new(),
+    }
+}
 
-        let body = data
-            .iter()
-            .map(|row| self.render_row(&self.table.schema, false, &row, cx));
+impl TableDelegate for TabularData {
+    fn columns(&self) -> Vec<TableColumn> {
+        self.columns.clone()
+    }
 
-        v_flex()
-            .w_full()
-            .child(header)
-            .children(body)
-            .into_any_element()
+    fn row_count(&self) -> usize {
+        self.rows.len()
     }
 
-    pub fn render_row(
-        &self,
-        schema: &TableSchema,
-        is_header: bool,
-        row: &Value,
-        cx: &ViewContext<ExecutionView>,
-    ) -> AnyElement {
-        let theme = cx.theme();
+    fn cell_text(&self, row: usize, column: usize) -> SharedString {
+        self.rows[row][column].clone()
+    }
+}
 
-        let row_cells = schema
-            .fields
-            .iter()
-            .map(|field| {
-                let container = match field.field_type {
-                    runtimelib::datatable::FieldType::String => div(),
-
-                    runtimelib::datatable::FieldType::Number
-                    | runtimelib::datatable::FieldType::Integer
-                    | runtimelib::datatable::FieldType::Date
-                    | runtimelib::datatable::FieldType::Time
-                    | runtimelib::datatable::FieldType::Datetime
-                    | runtimelib::datatable::FieldType::Year
-                    | runtimelib::datatable::FieldType::Duration
-                    | runtimelib::datatable::FieldType::Yearmonth => v_flex().items_end(),
-
-                    _ => div(),
-                };
+/// TableView renders a table inline in a buffer, scrolling it when it has many rows.
+/// It uses the https://specs.frictionlessdata.io/tabular-data-resource/ specification for data interchange.
+pub struct TableView {
+    pub table: View<DataTable<TabularData>>,
+}
 
-                let value = match row.get(&field.name) {
-                    Some(Value::String(s)) => s.clone(),
-                    Some(Value::Number(n)) => n.to_string(),
-                    Some(Value::Bool(b)) => b.to_string(),
-                    Some(Value::Array(arr)) => format!("{:?}", arr),
-                    Some(Value::Object(obj)) => format!("{:?}", obj),
-                    Some(Value::Null) | None => String::new(),
-                };
+impl TableView {
+    pub fn new(table: &TabularDataResource, cx: &mut WindowContext) -> Self {
+        let mut data = TabularData::new(table);
+
+        // Size each column to fit its widest cell, measured in the width of an 'm'.
+        let text_style = cx.text_style();
+        let font_size = text_style.font_size.to_pixels(cx.rem_size());
+        let font_id = cx.text_system().resolve_font(&text_style.font());
+        let em_width = cx
+            .text_system()
+            .advance(font_id, font_size, 'm')
+            .map(|size| size.width)
+            .unwrap_or(font_size);
+        for (ix, column) in data.columns.iter_mut().enumerate() {
+            let widest = data
+                .rows
+                .iter()
+                .take(MAX_MEASURED_TABLE_ROWS)
+                .map(|row| row[ix].chars().count())
+                .chain([column.title.chars().count() + 2])
+                .max()
+                .unwrap_or_default();
+            // Leave room for the cell padding.
+            column.width = em_width * (widest as f32 + 2.);
+        }
 
-                let mut cell = container
-                    .w_full()
-                    .child(value)
-                    .px_2()
-                    .py_1()
-                    .border_color(theme.colors().border);
+        Self {
+            table: cx.new_view(|cx| DataTable::new(data, cx)),
+        }
+    }
 
-                if is_header {
-                    cell = cell.border_2().bg(theme.colors().border_focused)
-                } else {
-                    cell = cell.border_1()
-                }
-                cell
-            })
-            .collect::<Vec<_>>();
+    fn height(&self, cx: &WindowContext) -> Pixels {
+        let visible_rows = self
+            .table
+            .read(cx)
+            .delegate()
+            .row_count()
+            .min(MAX_VISIBLE_TABLE_ROWS);
+        // Each row, and the header, has a line of text and a one pixel border above and below.
+        (cx.line_height() + px(2.)) * (visible_rows + 1) as f32
+    }
 
-        h_flex().children(row_cells).into_any_element()
+    pub fn render(&self, cx: &ViewContext<ExecutionView>) -> AnyElement {
+        div()
+            .w_full()
+            .h(self.height(cx))
+            .child(self.table.clone())
+            .into_any_element()
     }
 }
 
 impl LineHeight for TableView {
-    fn num_lines(&self, _cx: &mut WindowContext) -> u8 {
-        let num_rows = match &self.table.data {
-            Some(data) => data.len(),
-            // We don't support Path based data sources
-            None => 0,
-        };
-
-        // Given that each cell has both `py_1` and a border, we have to estimate
-        // a reasonable size to add on, then round up.
-        let row_heights = (num_rows as f32 * 1.2) + 1.0;
-
-        (row_heights as u8).saturating_add(2) // Header + spacing
+    fn num_lines(&self, cx: &mut WindowContext) -> u8 {
+        let lines = (self.height(cx) / cx.line_height()).ceil();
+        (lines as u8).saturating_add(1) // Spacing
     }
 }
 
@@ -283,8 +317,8 @@ impl LineHeight for OutputType {
     }
 }
 
-impl From<&MimeBundle> for OutputType {
-    fn from(data: &MimeBundle) -> Self {
+impl OutputType {
+    fn new(data: &MimeBundle, cx: &mut WindowContext) -> Self {
         match data.richest(rank_mime_type) {
             Some(MimeType::Plain(text)) => OutputType::Plain(TerminalOutput::from(text)),
             Some(MimeType::Markdown(text)) => OutputType::Plain(TerminalOutput::from(text)),
@@ -292,9 +326,7 @@ impl From<&MimeBundle> for OutputType {
                 Ok(view) => OutputType::Image(view),
                 Err(error) => OutputType::Message(format!("Failed to load image: {}", error)),
             },
-            Some(MimeType::DataTable(data)) => OutputType::Table(TableView {
-                table: data.clone(),
-            }),
+            Some(MimeType::DataTable(data)) => OutputType::Table(TableView::new(data, cx)),
             // Any other media types are not supported
             _ => OutputType::Message("Unsupported media type".to_string()),
         }
@@ -327,8 +359,8 @@ impl ExecutionView {
     /// Accept a Jupyter message belonging to this execution
     pub fn push_message(&mut self, message: &JupyterMessageContent, cx: &mut ViewContext<Self>) {
         let output: OutputType = match message {
-            JupyterMessageContent::ExecuteResult(result) => (&result.data).into(),
-            JupyterMessageContent::DisplayData(result) => (&result.data).into(),
+            JupyterMessageContent::ExecuteResult(result) => OutputType::new(&result.data, cx),
+            JupyterMessageContent::DisplayData(result) => OutputType::new(&result.data, cx),
             JupyterMessageContent::StreamContent(result) => {
                 // Previous stream data will combine together, handling colors, carriage returns, etc
                 if let Some(new_terminal) = self.apply_terminal_text(&result.text) {
@@ -354,7 +386,7 @@ impl ExecutionView {
                         // Pager data comes in via `?` at the end of a statement in Python, used for showing documentation.
                         // Some UI will show this as a popup. For ease of implementation, it's included as an output here.
                         runtimelib::Payload::Page { data, .. } => {
-                            let output: OutputType = (data).into();
+                            let output = OutputType::new(data, cx);
                             self.outputs.push(output);
                         }
 
@@ -173,5 +173,115 @@ fn rope_benchmarks(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, rope_benchmarks);
+fn generate_random_rows(mut rng: StdRng, rope: &Rope, count: usize) -> Vec<u32> {
+    let max_row = rope.max_point().row;
+    (0..count).map(|_| rng.gen_range(0..=max_row)).collect()
+}
+
+/// Benchmarks the operations that need to stay fast for files opened in large-file mode.
+fn large_rope_benchmarks(c: &mut Criterion) {
+    static SEED: u64 = 9999;
+    static MB: usize = 1024 * 1024;
+
+    let rng = StdRng::seed_from_u64(SEED);
+    let sizes = [8 * MB, 32 * MB];
+
+    let mut group = c.benchmark_group("large_push_chunks");
+    group.sample_size(10);
+    for size in sizes.iter() {
+        group.throughput(Throughput::Bytes(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let chunk = generate_random_text(rng.clone(), MB);
+
+            b.iter(|| {
+                let mut rope = Rope::new();
+                for _ in 0..*size / MB {
+                    rope.push(&chunk);
+                }
+                rope
+            });
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("large_point_to_offset");
+    for size in sizes.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let rope = generate_random_rope(rng.clone(), *size);
+
+            b.iter_batched(
+                || generate_random_rows(rng.clone(), &rope, 1000),
+                |rows| {
+                    for row in rows {
+                        black_box(rope.point_to_offset(Point::new(row, 0)));
+                    }
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("large_offset_to_point");
+    for size in sizes.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let rope = generate_random_rope(rng.clone(), *size);
+
+            b.iter_batched(
+                || {
+                    let mut rng = rng.clone();
+                    (0..1000)
+                        .map(|_| rng.gen_range(0..rope.len()))
+                        .collect::<Vec<_>>()
+                },
+                |offsets| {
+                    for offset in offsets {
+                        black_box(rope.offset_to_point(offset));
+                    }
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("large_scroll");
+    for size in sizes.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let rope = generate_random_rope(rng.clone(), *size);
+            let max_point = rope.max_point();
+
+            b.iter_batched(
+                || generate_random_rows(rng.clone(), &rope, 100),
+                |rows| {
+                    for row in rows {
+                        let start = rope.point_to_offset(Point::new(row, 0));
+                        let end = rope.point_to_offset(Point::new(row + 100, 0).min(max_point));
+                        black_box(rope.chunks_in_range(start..end).count());
+                    }
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("large_find");
+    group.sample_size(10);
+    for size in sizes.iter() {
+        group.throughput(Throughput::Bytes(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let rope = generate_random_rope(rng.clone(), *size);
+
+            b.iter(|| {
+                rope.chunks()
+                    .map(|chunk| chunk.matches("ab").count())
+                    .sum::<usize>()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, rope_benchmarks, large_rope_benchmarks);
 criterion_main!(benches);
@@ -1,5 +1,15 @@
 use async_tungstenite::tungstenite::Message as WebSocketMessage;
-use futures::{SinkExt as _, StreamExt as _};
+use futures::{
+    AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _, SinkExt as _, StreamExt as _,
+};
+
+const FRAME_BINARY: u8 = 0;
+const FRAME_PING: u8 = 1;
+const FRAME_PONG: u8 = 2;
+
+/// The largest payload that can be sent or received over a stream connection, which matches
+/// the default limit of the websocket connections to the server.
+pub const MAX_STREAM_MESSAGE_LEN: usize = 64 << 20;
 
 pub struct Connection {
     pub(crate) tx:
@@ -28,6 +38,97 @@ impl Connection {
         }
     }
 
+    /// Creates a connection that exchanges messages over a pair of byte streams, such as the
+    /// stdio of a process. Each message is written as a one-byte kind, a little-endian `u32`
+    /// length and the payload.
+    pub fn over_stream<R, W>(reader: R, writer: W) -> Self
+    where
+        R: 'static + Send + Unpin + AsyncRead,
+        W: 'static + Send + Unpin + AsyncWrite,
+    {
+        let tx =
+            futures::sink::unfold(writer, |mut writer, message: WebSocketMessage| async move {
+                let (kind, payload) = match message {
+                    WebSocketMessage::Binary(payload) => (FRAME_BINARY, payload),
+                    WebSocketMessage::Ping(payload) => (FRAME_PING, payload),
+                    WebSocketMessage::Pong(payload) => (FRAME_PONG, payload),
+                    WebSocketMessage::Close(_) => {
+                        writer.close().await?;
+                        return Ok(writer);
+                    }
+                    WebSocketMessage::Text(_) => {
+                        return Err(anyhow::anyhow!("text messages are not supported"))
+                    }
+                };
+                if payload.len() > MAX_STREAM_MESSAGE_LEN {
+                    return Err(anyhow::anyhow!(
+                        "message of {} bytes exceeds the maximum of {MAX_STREAM_MESSAGE_LEN}",
+                        payload.len()
+                    ));
+                }
+                let len = payload.len() as u32;
+                writer.write_all(&[kind]).await?;
+                writer.write_all(&len.to_le_bytes()).await?;
+                writer.write_all(&payload).await?;
+                writer.flush().await?;
+                Ok(writer)
+            });
+
+        let rx = futures::stream::unfold(reader, |mut reader| async move {
+            let mut header = [0; 5];
+            if let Err(error) = reader.read_exact(&mut header).await {
+                return if error.kind() == std::io::ErrorKind::UnexpectedEof {
+                    None
+                } else {
+                    Some((Err(error.into()), reader))
+                };
+            }
+            let len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+            if len > MAX_STREAM_MESSAGE_LEN {
+                // Don't allocate for a corrupt or hostile length; the connection can't be
+                // resynchronized, so the caller is expected to drop it.
+                return Some((
+                    Err(anyhow::anyhow!(
+                        "message of {len} bytes exceeds the maximum of {MAX_STREAM_MESSAGE_LEN}"
+                    )),
+                    reader,
+                ));
+            }
+            let mut payload = vec![0; len];
+            if let Err(error) = reader.read_exact(&mut payload).await {
+                return Some((Err(error.into()), reader));
+            }
+            let message = match header[0] {
+                FRAME_BINARY => Ok(WebSocketMessage::Binary(payload)),
+                FRAME_PING => Ok(WebSocketMessage::Ping(payload)),
+                FRAME_PONG => Ok(WebSocketMessage::Pong(payload)),
+                kind => Err(anyhow::anyhow!("invalid frame kind {kind}")),
+            };
+            Some((message, reader))
+        });
+
+        Self {
+            tx: Box::new(Box::pin(tx)),
+            rx: Box::new(Box::pin(rx)),
+        }
+    }
+
+    /// Creates a pair of connected connections that live in the same process.
+    pub fn in_process() -> (Self, Self) {
+        let (a_tx, a_rx) = futures::channel::mpsc::unbounded::<WebSocketMessage>();
+        let (b_tx, b_rx) = futures::channel::mpsc::unbounded::<WebSocketMessage>();
+        (
+            Self {
+                tx: Box::new(a_tx.sink_map_err(|error| anyhow::anyhow!(error))),
+                rx: Box::new(b_rx.map(Ok)),
+            },
+            Self {
+                tx: Box::new(b_tx.sink_map_err(|error| anyhow::anyhow!(error))),
+                rx: Box::new(a_rx.map(Ok)),
+            },
+        )
+    }
+
     pub async fn send(&mut self, message: WebSocketMessage) -> Result<(), anyhow::Error> {
         self.tx.send(message).await
     }
@@ -105,3 +206,90 @@ impl Connection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::{
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll},
+    };
+
+    #[test]
+    fn test_connection_over_stream() {
+        futures::executor::block_on(async {
+            let buffer = SharedBuffer::default();
+            let mut writer = Connection::over_stream(futures::io::empty(), buffer.clone());
+            writer
+                .send(WebSocketMessage::Binary(vec![1, 2, 3]))
+                .await
+                .unwrap();
+            writer
+                .send(WebSocketMessage::Ping(Vec::new()))
+                .await
+                .unwrap();
+            writer.send(WebSocketMessage::Pong(vec![4])).await.unwrap();
+
+            let bytes = buffer.0.lock().clone();
+            let mut reader =
+                Connection::over_stream(futures::io::Cursor::new(bytes), futures::io::sink());
+            assert_eq!(
+                reader.rx.next().await.unwrap().unwrap(),
+                WebSocketMessage::Binary(vec![1, 2, 3])
+            );
+            assert_eq!(
+                reader.rx.next().await.unwrap().unwrap(),
+                WebSocketMessage::Ping(Vec::new())
+            );
+            assert_eq!(
+                reader.rx.next().await.unwrap().unwrap(),
+                WebSocketMessage::Pong(vec![4])
+            );
+            assert!(reader.rx.next().await.is_none());
+        });
+    }
+
+    #[test]
+    fn test_oversized_messages_over_stream() {
+        futures::executor::block_on(async {
+            let mut writer = Connection::over_stream(futures::io::empty(), SharedBuffer::default());
+            assert!(writer
+                .send(WebSocketMessage::Binary(vec![
+                    0;
+                    MAX_STREAM_MESSAGE_LEN + 1
+                ]))
+                .await
+                .is_err());
+
+            let mut bytes = vec![FRAME_BINARY];
+            bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+            let mut reader =
+                Connection::over_stream(futures::io::Cursor::new(bytes), futures::io::sink());
+            assert!(reader.rx.next().await.unwrap().is_err());
+        });
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl AsyncWrite for SharedBuffer {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.0.lock().extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
@@ -38,6 +38,24 @@ pub enum Notification {
         sender_id: u64,
         channel_id: u64,
     },
+    ProjectCommentReply {
+        #[serde(rename = "entity_id")]
+        comment_id: u64,
+        thread_id: u64,
+        project_id: u64,
+        sender_id: u64,
+        worktree_id: u64,
+        path: String,
+        body: String,
+    },
+    ProjectCommentThreadResolved {
+        #[serde(rename = "entity_id")]
+        thread_id: u64,
+        project_id: u64,
+        resolver_id: u64,
+        worktree_id: u64,
+        path: String,
+    },
 }
 
 impl Notification {
@@ -96,6 +114,22 @@ mod tests {
                 channel_id: 30,
                 message_id: 1,
             },
+            Notification::ProjectCommentReply {
+                comment_id: 7,
+                thread_id: 3,
+                project_id: 12,
+                sender_id: 200,
+                worktree_id: 1,
+                path: "src/main.rs".into(),
+                body: "Should this be async?".into(),
+            },
+            Notification::ProjectCommentThreadResolved {
+                thread_id: 3,
+                project_id: 12,
+                resolver_id: 50,
+                worktree_id: 1,
+                path: "src/main.rs".into(),
+            },
         ] {
             let message = notification.to_proto();
             let deserialized = Notification::from_proto(&message).unwrap();
@@ -1,7 +1,7 @@
 use crate::{
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
     SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
-    ToggleReplace, ToggleWholeWord,
+    ToggleReplace, ToggleStructural, ToggleWholeWord,
 };
 use collections::{HashMap, HashSet};
 use editor::{
@@ -17,8 +17,13 @@ use gpui::{
     Subscription, Task, TextStyle, UpdateGlobal, View, ViewContext, VisualContext, WeakModel,
     WeakView, WhiteSpace, WindowContext,
 };
+use language::BufferId;
 use menu::Confirm;
-use project::{search::SearchQuery, search_history::SearchHistoryCursor, Project, ProjectPath};
+use project::{
+    search::{SearchQuery, SearchScope},
+    search_history::SearchHistoryCursor,
+    Project, ProjectPath,
+};
 use settings::Settings;
 use smol::stream::StreamExt;
 use std::{
@@ -29,8 +34,8 @@ use std::{
 };
 use theme::ThemeSettings;
 use ui::{
-    h_flex, prelude::*, v_flex, Icon, IconButton, IconName, Label, LabelCommon, LabelSize,
-    Selectable, Tooltip,
+    h_flex, prelude::*, v_flex, ButtonLike, ContextMenu, Icon, IconButton, IconName, Label,
+    LabelCommon, LabelSize, PopoverMenu, Selectable, Tooltip,
 };
 use util::paths::PathMatcher;
 use workspace::{
@@ -71,6 +76,9 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStructural, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+        });
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
@@ -148,9 +156,33 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    search_scope: SearchScopeKind,
+    git_base_editor: View<Editor>,
+    workspace: Option<WeakView<Workspace>>,
     _subscriptions: Vec<Subscription>,
 }
 
+/// The files that a project search is restricted to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+enum SearchScopeKind {
+    #[default]
+    All,
+    OpenFiles,
+    ChangedFiles,
+    Named(SharedString),
+}
+
+impl SearchScopeKind {
+    fn label(&self) -> SharedString {
+        match self {
+            SearchScopeKind::All => "All Files".into(),
+            SearchScopeKind::OpenFiles => "Open Files".into(),
+            SearchScopeKind::ChangedFiles => "Changed Files".into(),
+            SearchScopeKind::Named(name) => name.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ProjectSearchSettings {
     search_options: SearchOptions,
@@ -466,6 +498,7 @@ impl Item for ProjectSearchView {
     }
 
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.workspace = Some(workspace.weak_handle());
         self.results_editor
             .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
     }
@@ -542,6 +575,14 @@ impl ProjectSearchView {
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
         self.search_options.toggle(option);
+        // A query is either a regular expression or a structural pattern, but not both.
+        if self.search_options.contains(option) {
+            if option == SearchOptions::REGEX {
+                self.search_options.remove(SearchOptions::STRUCTURAL);
+            } else if option == SearchOptions::STRUCTURAL {
+                self.search_options.remove(SearchOptions::REGEX);
+            }
+        }
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.model.read(cx).project.downgrade(),
@@ -608,6 +649,8 @@ impl ProjectSearchView {
         let excerpts;
         let mut replacement_text = None;
         let mut query_text = String::new();
+        let mut search_scope = SearchScopeKind::All;
+        let mut git_base = None;
         let mut subscriptions = Vec::new();
 
         // Read in settings if available
@@ -625,6 +668,15 @@ impl ProjectSearchView {
                 query_text = active_query.as_str().to_string();
                 replacement_text = active_query.replacement().map(ToOwned::to_owned);
                 options = SearchOptions::from_query(active_query);
+                search_scope = match active_query.scope() {
+                    SearchScope::All => SearchScopeKind::All,
+                    SearchScope::OpenBuffers(_) => SearchScopeKind::OpenFiles,
+                    SearchScope::GitChanges { base } => {
+                        git_base.clone_from(base);
+                        SearchScopeKind::ChangedFiles
+                    }
+                    SearchScope::Named(name) => SearchScopeKind::Named(name.clone().into()),
+                };
             }
         }
         subscriptions.push(cx.observe(&model, |this, _, cx| this.model_changed(cx)));
@@ -692,6 +744,21 @@ impl ProjectSearchView {
             }),
         );
 
+        let git_base_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Compare with: HEAD", cx);
+            if let Some(git_base) = git_base {
+                editor.set_text(git_base, cx);
+            }
+            editor
+        });
+        // Subscribe to git_base_editor in order to reraise editor events for workspace item activation purposes
+        subscriptions.push(
+            cx.subscribe(&git_base_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(ViewEvent::EditorEvent(event.clone()))
+            }),
+        );
+
         let focus_handle = cx.focus_handle();
         subscriptions.push(cx.on_focus_in(&focus_handle, |this, cx| {
             if this.focus_handle.is_focused(cx) {
@@ -719,6 +786,9 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            search_scope,
+            git_base_editor,
+            workspace: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -895,57 +965,48 @@ impl ProjectSearchView {
                 }
             };
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
-            match SearchQuery::regex(
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+            )
+        } else if self.search_options.contains(SearchOptions::REGEX) {
+            SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
                 self.search_options.contains(SearchOptions::CASE_SENSITIVE),
                 self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
                 included_files,
                 excluded_files,
-            ) {
-                Ok(query) => {
-                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
-                    if should_unmark_error {
-                        cx.notify();
-                    }
-
-                    Some(query)
-                }
-                Err(_e) => {
-                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
-                    if should_mark_error {
-                        cx.notify();
-                    }
-
-                    None
-                }
-            }
+            )
         } else {
-            match SearchQuery::text(
+            SearchQuery::text(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
                 self.search_options.contains(SearchOptions::CASE_SENSITIVE),
                 self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
                 included_files,
                 excluded_files,
-            ) {
-                Ok(query) => {
-                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
-                    if should_unmark_error {
-                        cx.notify();
-                    }
-
-                    Some(query)
+            )
+        };
+        let query = match query {
+            Ok(query) => {
+                let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                if should_unmark_error {
+                    cx.notify();
                 }
-                Err(_e) => {
-                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
-                    if should_mark_error {
-                        cx.notify();
-                    }
 
-                    None
+                Some(query.with_scope(self.search_scope(cx)))
+            }
+            Err(_e) => {
+                let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                if should_mark_error {
+                    cx.notify();
                 }
+
+                None
             }
         };
         if !self.panels_with_errors.is_empty() {
@@ -957,6 +1018,56 @@ impl ProjectSearchView {
         query
     }
 
+    fn search_scope(&self, cx: &AppContext) -> SearchScope {
+        match &self.search_scope {
+            SearchScopeKind::All => SearchScope::All,
+            SearchScopeKind::OpenFiles => SearchScope::OpenBuffers(self.open_buffer_ids(cx)),
+            SearchScopeKind::ChangedFiles => {
+                let base = self.git_base_editor.read(cx).text(cx);
+                let base = base.trim();
+                SearchScope::GitChanges {
+                    base: (!base.is_empty()).then(|| base.to_string()),
+                }
+            }
+            SearchScopeKind::Named(name) => SearchScope::Named(name.to_string()),
+        }
+    }
+
+    /// Returns the buffers of the files that are open in the workspace's panes.
+    fn open_buffer_ids(&self, cx: &AppContext) -> Vec<BufferId> {
+        let Some(workspace) = self
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.upgrade())
+        else {
+            return Vec::new();
+        };
+        let mut buffer_ids = Vec::new();
+        for item in workspace.read(cx).items(cx) {
+            let buffer = item
+                .act_as::<Editor>(cx)
+                .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton());
+            if let Some(buffer) = buffer {
+                let buffer_id = buffer.read(cx).remote_id();
+                if !buffer_ids.contains(&buffer_id) {
+                    buffer_ids.push(buffer_id);
+                }
+            }
+        }
+        buffer_ids
+    }
+
+    fn set_search_scope(&mut self, search_scope: SearchScopeKind, cx: &mut ViewContext<Self>) {
+        if self.search_scope != search_scope {
+            self.search_scope = search_scope;
+            if self.search_scope == SearchScopeKind::ChangedFiles {
+                cx.focus_view(&self.git_base_editor);
+            }
+            self.search(cx);
+            cx.notify();
+        }
+    }
+
     fn parse_path_matches(text: &str) -> anyhow::Result<PathMatcher> {
         let queries = text
             .split(',')
@@ -1140,6 +1251,9 @@ impl ProjectSearchBar {
                     &project_view.included_files_editor,
                     &project_view.excluded_files_editor,
                 ]);
+                if project_view.search_scope == SearchScopeKind::ChangedFiles {
+                    views.push(&project_view.git_base_editor);
+                }
             }
             let current_index = match views
                 .iter()
@@ -1326,14 +1440,77 @@ impl ProjectSearchBar {
             },
         )
     }
+
+    fn render_search_scope_menu(
+        &self,
+        search_view: &View<ProjectSearchView>,
+        cx: &ViewContext<Self>,
+    ) -> impl IntoElement {
+        let search_scope = search_view.read(cx).search_scope.clone();
+        let search_view = search_view.clone();
+        PopoverMenu::new("project-search-scope")
+            .menu(move |cx| {
+                let search_scope = search_view.read(cx).search_scope.clone();
+                let named_scopes = search_view
+                    .read(cx)
+                    .model
+                    .read(cx)
+                    .project
+                    .read(cx)
+                    .named_search_scopes(cx);
+                let mut scopes = vec![
+                    SearchScopeKind::All,
+                    SearchScopeKind::OpenFiles,
+                    SearchScopeKind::ChangedFiles,
+                ];
+                scopes.extend(
+                    named_scopes
+                        .into_iter()
+                        .map(|name| SearchScopeKind::Named(name.into())),
+                );
+                ContextMenu::build(cx, |mut menu, cx| {
+                    for (ix, scope) in scopes.into_iter().enumerate() {
+                        if ix == 3 {
+                            menu = menu.separator();
+                        }
+                        menu = menu.toggleable_entry(
+                            scope.label(),
+                            scope == search_scope,
+                            None,
+                            cx.handler_for(&search_view, move |search_view, cx| {
+                                search_view.set_search_scope(scope.clone(), cx);
+                            }),
+                        );
+                    }
+                    menu
+                })
+                .into()
+            })
+            .trigger(
+                ButtonLike::new("project-search-scope-trigger")
+                    .child(
+                        h_flex()
+                            .gap_0p5()
+                            .child(Label::new(search_scope.label()).size(LabelSize::Small))
+                            .child(
+                                Icon::new(IconName::ChevronDown)
+                                    .color(Color::Muted)
+                                    .size(IconSize::XSmall),
+                            ),
+                    )
+                    .style(ButtonStyle::Subtle)
+                    .tooltip(|cx| Tooltip::text("Search Scope", cx)),
+            )
+            .attach(gpui::AnchorCorner::BottomLeft)
+    }
 }
 
 impl Render for ProjectSearchBar {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let Some(search) = self.active_project_search.clone() else {
+        let Some(search_view) = self.active_project_search.clone() else {
             return div();
         };
-        let search = search.read(cx);
+        let search = search_view.read(cx);
 
         let query_column = h_flex()
             .flex_1()
@@ -1369,6 +1546,12 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+                        }),
                     )),
             );
 
@@ -1552,6 +1735,23 @@ impl Render for ProjectSearchBar {
                         .rounded_lg()
                         .child(self.render_text_input(&search.excluded_files_editor, cx)),
                 )
+                .child(self.render_search_scope_menu(&search_view, cx))
+                .when(
+                    search.search_scope == SearchScopeKind::ChangedFiles,
+                    |this| {
+                        this.child(
+                            h_flex()
+                                .w(rems(10.))
+                                .h_8()
+                                .px_2()
+                                .py_1()
+                                .border_1()
+                                .border_color(cx.theme().colors().border)
+                                .rounded_lg()
+                                .child(self.render_text_input(&search.git_base_editor, cx)),
+                        )
+                    },
+                )
         });
 
         v_flex()
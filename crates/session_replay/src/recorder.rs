@@ -0,0 +1,474 @@
+use anyhow::Result;
+use call::{room, Room};
+use channel::{ChannelChat, ChannelChatEvent, ChannelMessageId, ChannelStore};
+use collections::{HashMap, HashSet};
+use futures::{channel::mpsc, AsyncWrite, AsyncWriteExt as _, StreamExt};
+use gpui::{EventEmitter, Model, ModelContext, Subscription, Task, WeakModel};
+use language::{Buffer, BufferId};
+use project::Project;
+use prost::Message as _;
+use rpc::proto::{self, session_recording_event::Event, PeerId};
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
+
+/// How long to wait before recording the changes to a buffer, so that a burst of edits
+/// is recorded as a single event.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Records the activity in a shared project of a call into a session file: the operations
+/// of the buffers that are open in the project, who follows whom, and the messages sent
+/// in the chat of the call's channel.
+///
+/// Only the buffers that are open in our own replica of the project are recorded, so the
+/// host should record a session for it to include everyone's buffers.
+pub struct SessionRecorder {
+    project: Model<Project>,
+    room: Model<Room>,
+    started_at: Instant,
+    started_at_utc: OffsetDateTime,
+    buffers: HashMap<BufferId, RecordedBuffer>,
+    dirty_buffers: HashSet<BufferId>,
+    follows: HashSet<(PeerId, PeerId)>,
+    recorded_message_ids: HashSet<u64>,
+    events_tx: Option<mpsc::UnboundedSender<PendingEvent>>,
+    flush_task: Option<Task<()>>,
+    _open_chat: Option<Task<Result<()>>>,
+    subscriptions: Vec<Subscription>,
+}
+
+pub enum SessionRecorderEvent {
+    Stopped,
+    /// The session could not be written, so the recording was stopped.
+    Failed(anyhow::Error),
+}
+
+impl EventEmitter<SessionRecorderEvent> for SessionRecorder {}
+
+struct RecordedBuffer {
+    buffer: WeakModel<Buffer>,
+    version: clock::Global,
+    _subscriptions: [Subscription; 3],
+}
+
+/// An event whose buffer operations are still being serialized in the background.
+enum PendingEvent {
+    Ready {
+        timestamp_ms: u64,
+        event: Event,
+    },
+    BufferOpened {
+        timestamp_ms: u64,
+        path: String,
+        state: proto::BufferState,
+        operations: Task<Vec<proto::Operation>>,
+    },
+    BufferOperations {
+        timestamp_ms: u64,
+        buffer_id: u64,
+        operations: Task<Vec<proto::Operation>>,
+    },
+}
+
+impl SessionRecorder {
+    pub fn new(
+        project: Model<Project>,
+        room: Model<Room>,
+        writer: impl AsyncWrite + Send + Unpin + 'static,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let (events_tx, events_rx) = mpsc::unbounded();
+        // The events that were recorded before stopping are still written after the
+        // recorder is released.
+        let write_task = cx
+            .background_executor()
+            .spawn(write_events(writer, events_rx));
+        cx.spawn(|this, mut cx| async move {
+            if let Err(error) = write_task.await {
+                this.update(&mut cx, |this, cx| {
+                    this.stop(cx);
+                    cx.emit(SessionRecorderEvent::Failed(error));
+                })
+                .ok();
+            }
+        })
+        .detach();
+
+        let subscriptions = vec![
+            cx.subscribe(&project, Self::handle_project_event),
+            cx.subscribe(&room, |this, _, event, cx| {
+                if let room::Event::RoomLeft { .. } = event {
+                    this.stop(cx);
+                }
+            }),
+            cx.observe(&room, |this, _, cx| this.record_follows(cx)),
+        ];
+
+        let open_chat = room.read(cx).channel_id().map(|channel_id| {
+            let open_chat = ChannelStore::global(cx)
+                .update(cx, |store, cx| store.open_channel_chat(channel_id, cx));
+            cx.spawn(|this, mut cx| async move {
+                let chat = open_chat.await?;
+                this.update(&mut cx, |this, cx| {
+                    if this.is_recording() {
+                        this.subscriptions
+                            .push(cx.subscribe(&chat, Self::handle_chat_event));
+                    }
+                })
+            })
+        });
+
+        let mut this = Self {
+            project: project.clone(),
+            room,
+            started_at: Instant::now(),
+            // Chat messages are timestamped with a precision of one second.
+            started_at_utc: OffsetDateTime::now_utc()
+                .replace_nanosecond(0)
+                .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+            buffers: HashMap::default(),
+            dirty_buffers: HashSet::default(),
+            follows: HashSet::default(),
+            recorded_message_ids: HashSet::default(),
+            events_tx: Some(events_tx),
+            flush_task: None,
+            _open_chat: open_chat,
+            subscriptions,
+        };
+        this.record_collaborators(cx);
+        for buffer in project.read(cx).opened_buffers() {
+            this.add_buffer(buffer, cx);
+        }
+        this.record_follows(cx);
+        this
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.events_tx.is_some()
+    }
+
+    /// Records the pending buffer changes and closes the session file.
+    pub fn stop(&mut self, cx: &mut ModelContext<Self>) {
+        if !self.is_recording() {
+            return;
+        }
+        self.flush(cx);
+        self.events_tx = None;
+        self.subscriptions.clear();
+        self.buffers.clear();
+        self._open_chat = None;
+        cx.emit(SessionRecorderEvent::Stopped);
+    }
+
+    fn timestamp_ms(&self) -> u64 {
+        self.started_at.elapsed().as_millis() as u64
+    }
+
+    fn record(&self, event: Event) {
+        if let Some(events_tx) = &self.events_tx {
+            events_tx
+                .unbounded_send(PendingEvent::Ready {
+                    timestamp_ms: self.timestamp_ms(),
+                    event,
+                })
+                .ok();
+        }
+    }
+
+    fn handle_project_event(
+        &mut self,
+        _: Model<Project>,
+        event: &project::Event,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match event {
+            project::Event::BufferOpened(buffer) => self.add_buffer(buffer.clone(), cx),
+            project::Event::CollaboratorJoined(_)
+            | project::Event::CollaboratorLeft(_)
+            | project::Event::CollaboratorUpdated { .. } => self.record_collaborators(cx),
+            project::Event::DisconnectedFromHost | project::Event::Closed => self.stop(cx),
+            project::Event::RemoteIdChanged(None) => self.stop(cx),
+            _ => {}
+        }
+    }
+
+    fn record_collaborators(&self, cx: &ModelContext<Self>) {
+        let project = self.project.read(cx);
+        let user_store = project.user_store().read(cx);
+        let mut collaborators = Vec::new();
+        if let Some(user) = user_store.current_user() {
+            collaborators.push(proto::SessionRecordingCollaborator {
+                replica_id: project.replica_id() as u32,
+                user_id: user.id,
+                github_login: user.github_login.clone(),
+            });
+        }
+        for collaborator in project.collaborators().values() {
+            collaborators.push(proto::SessionRecordingCollaborator {
+                replica_id: collaborator.replica_id as u32,
+                user_id: collaborator.user_id,
+                github_login: user_store
+                    .get_cached_user(collaborator.user_id)
+                    .map(|user| user.github_login.clone())
+                    .unwrap_or_default(),
+            });
+        }
+        self.record(Event::Collaborators(proto::SessionRecordingCollaborators {
+            collaborators,
+        }));
+    }
+
+    fn add_buffer(&mut self, buffer: Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let Some(events_tx) = self.events_tx.as_ref() else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+
+        let snapshot = buffer.read(cx);
+        let path = snapshot
+            .file()
+            .map(|file| file.full_path(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        events_tx
+            .unbounded_send(PendingEvent::BufferOpened {
+                timestamp_ms: self.timestamp_ms(),
+                path,
+                state: snapshot.to_proto(),
+                operations: snapshot.serialize_ops(None, cx),
+            })
+            .ok();
+        let version = snapshot.version();
+
+        self.buffers.insert(
+            buffer_id,
+            RecordedBuffer {
+                buffer: buffer.downgrade(),
+                version,
+                _subscriptions: [
+                    cx.subscribe(&buffer, move |this, _, event, cx| {
+                        if let language::Event::Operation(_) | language::Event::Edited = event {
+                            this.buffer_changed(buffer_id, cx);
+                        }
+                    }),
+                    // Remote selections are applied without emitting an event.
+                    cx.observe(&buffer, move |this, _, cx| {
+                        this.buffer_changed(buffer_id, cx)
+                    }),
+                    cx.observe_release(&buffer, move |this, _, _| {
+                        this.buffers.remove(&buffer_id);
+                    }),
+                ],
+            },
+        );
+    }
+
+    fn buffer_changed(&mut self, buffer_id: BufferId, cx: &mut ModelContext<Self>) {
+        self.dirty_buffers.insert(buffer_id);
+        if self.flush_task.is_none() {
+            self.flush_task = Some(cx.spawn(|this, mut cx| async move {
+                cx.background_executor().timer(FLUSH_INTERVAL).await;
+                this.update(&mut cx, |this, cx| this.flush(cx)).ok();
+            }));
+        }
+    }
+
+    fn flush(&mut self, cx: &mut ModelContext<Self>) {
+        self.flush_task = None;
+        let Some(events_tx) = self.events_tx.as_ref() else {
+            return;
+        };
+        let timestamp_ms = self.timestamp_ms();
+        for buffer_id in mem::take(&mut self.dirty_buffers) {
+            let Some(recorded) = self.buffers.get_mut(&buffer_id) else {
+                continue;
+            };
+            let Some(buffer) = recorded.buffer.upgrade() else {
+                continue;
+            };
+            let buffer = buffer.read(cx);
+            let operations = buffer.serialize_ops(Some(recorded.version.clone()), cx);
+            recorded.version = buffer.version();
+            events_tx
+                .unbounded_send(PendingEvent::BufferOperations {
+                    timestamp_ms,
+                    buffer_id: buffer_id.into(),
+                    operations,
+                })
+                .ok();
+        }
+    }
+
+    fn record_follows(&mut self, cx: &mut ModelContext<Self>) {
+        let project = self.project.read(cx);
+        let Some(project_id) = project.remote_id() else {
+            return;
+        };
+        let local_peer_id = project.client().peer_id();
+        let local_login = project
+            .user_store()
+            .read(cx)
+            .current_user()
+            .map(|user| user.github_login.clone())
+            .unwrap_or_default();
+        let room = self.room.read(cx);
+        let login = |peer_id: PeerId| {
+            if Some(peer_id) == local_peer_id {
+                local_login.clone()
+            } else {
+                room.remote_participant_for_peer_id(peer_id)
+                    .map(|participant| participant.user.github_login.clone())
+                    .unwrap_or_default()
+            }
+        };
+
+        let follows = room.follows_in_project(project_id).collect::<HashSet<_>>();
+        let started = follows
+            .difference(&self.follows)
+            .map(|follow| (follow, true));
+        let stopped = self
+            .follows
+            .difference(&follows)
+            .map(|follow| (follow, false));
+        let events = started
+            .chain(stopped)
+            .map(|((leader_id, follower_id), following)| {
+                Event::Follow(proto::SessionRecordingFollow {
+                    leader: login(*leader_id),
+                    follower: login(*follower_id),
+                    following,
+                })
+            })
+            .collect::<Vec<_>>();
+        self.follows = follows;
+        for event in events {
+            self.record(event);
+        }
+    }
+
+    fn handle_chat_event(
+        &mut self,
+        chat: Model<ChannelChat>,
+        event: &ChannelChatEvent,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let ChannelChatEvent::MessagesUpdated {
+            old_range,
+            new_count,
+        } = event
+        else {
+            return;
+        };
+
+        // Messages are only recorded once they have been saved, and older messages that
+        // are loaded while scrolling through the chat aren't part of the session.
+        let chat = chat.read(cx);
+        let mut events = Vec::new();
+        for message in chat.messages_in_range(old_range.start..old_range.start + new_count) {
+            if let ChannelMessageId::Saved(id) = message.id {
+                if message.timestamp >= self.started_at_utc && self.recorded_message_ids.insert(id)
+                {
+                    events.push(Event::ChatMessage(proto::SessionRecordingChatMessage {
+                        sender: message.sender.github_login.clone(),
+                        body: message.body.clone(),
+                    }));
+                }
+            }
+        }
+        for event in events {
+            self.record(event);
+        }
+    }
+}
+
+/// Writes the events to the session file as they are received, in the order in which
+/// they happened.
+async fn write_events(
+    mut writer: impl AsyncWrite + Unpin,
+    mut events: mpsc::UnboundedReceiver<PendingEvent>,
+) -> Result<()> {
+    let mut filter = OperationFilter::default();
+    while let Some(event) = events.next().await {
+        let (timestamp_ms, event) = match event {
+            PendingEvent::Ready {
+                timestamp_ms,
+                event,
+            } => (timestamp_ms, event),
+            PendingEvent::BufferOpened {
+                timestamp_ms,
+                path,
+                state,
+                operations,
+            } => {
+                let operations = filter.retain(state.id, operations.await);
+                let event = Event::BufferOpened(proto::SessionRecordingBufferOpened {
+                    path,
+                    state: Some(state),
+                    operations,
+                });
+                (timestamp_ms, event)
+            }
+            PendingEvent::BufferOperations {
+                timestamp_ms,
+                buffer_id,
+                operations,
+            } => {
+                let operations = filter.retain(buffer_id, operations.await);
+                if operations.is_empty() {
+                    continue;
+                }
+                let event = Event::BufferOperations(proto::SessionRecordingBufferOperations {
+                    buffer_id,
+                    operations,
+                });
+                (timestamp_ms, event)
+            }
+        };
+        let event = proto::SessionRecordingEvent {
+            timestamp_ms,
+            event: Some(event),
+        };
+        writer
+            .write_all(&event.encode_length_delimited_to_vec())
+            .await?;
+    }
+    writer.close().await?;
+    Ok(())
+}
+
+/// Keeps the operations that change a buffer's text or someone's selections in it,
+/// dropping the selections that were already recorded.
+#[derive(Default)]
+struct OperationFilter {
+    selection_timestamps: HashMap<(u64, u32), u32>,
+}
+
+impl OperationFilter {
+    fn retain(
+        &mut self,
+        buffer_id: u64,
+        operations: Vec<proto::Operation>,
+    ) -> Vec<proto::Operation> {
+        operations
+            .into_iter()
+            .filter(|operation| match &operation.variant {
+                Some(proto::operation::Variant::Edit(_))
+                | Some(proto::operation::Variant::Undo(_)) => true,
+                Some(proto::operation::Variant::UpdateSelections(selections)) => {
+                    let last_timestamp = self
+                        .selection_timestamps
+                        .entry((buffer_id, selections.replica_id))
+                        .or_default();
+                    let is_new = *last_timestamp < selections.lamport_timestamp;
+                    *last_timestamp = (*last_timestamp).max(selections.lamport_timestamp);
+                    is_new
+                }
+                _ => false,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, Context as _, Result};
+use gpui::{AppContext, Context as _, Model};
+use language::{proto::deserialize_operation, Buffer, Capability, ReplicaId};
+use prost::Message as _;
+use rpc::proto::{self, session_recording_event::Event};
+use util::ResultExt;
+
+/// The replica id of the buffers that are reconstructed from a recording. It doesn't
+/// belong to any collaborator, so that everyone's selections are shown.
+const REPLAY_REPLICA_ID: ReplicaId = ReplicaId::MAX;
+
+/// The events of a recorded collaboration session.
+pub struct SessionRecording {
+    pub events: Vec<proto::SessionRecordingEvent>,
+}
+
+impl SessionRecording {
+    pub fn parse(mut bytes: &[u8]) -> Result<Self> {
+        let mut events = Vec::new();
+        while !bytes.is_empty() {
+            events.push(
+                proto::SessionRecordingEvent::decode_length_delimited(&mut bytes)
+                    .context("invalid session recording")?,
+            );
+        }
+        Ok(Self { events })
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.events.last().map_or(0, |event| event.timestamp_ms)
+    }
+
+    /// Returns the number of events that happened at or before the given time.
+    pub fn position_at(&self, timestamp_ms: u64) -> usize {
+        self.events
+            .partition_point(|event| event.timestamp_ms <= timestamp_ms)
+    }
+
+    /// Returns the time at which the session had reached the given position.
+    pub fn timestamp_at(&self, position: usize) -> u64 {
+        position
+            .checked_sub(1)
+            .and_then(|ix| self.events.get(ix))
+            .map_or(0, |event| event.timestamp_ms)
+    }
+}
+
+/// The state of the project after replaying the first events of a recording.
+#[derive(Default)]
+pub struct ReplayState {
+    position: usize,
+    buffers: Vec<ReplayedBuffer>,
+    collaborators: Vec<proto::SessionRecordingCollaborator>,
+    log: Vec<LogEntry>,
+}
+
+pub struct ReplayedBuffer {
+    pub id: u64,
+    pub path: String,
+    pub buffer: Model<Buffer>,
+}
+
+pub struct LogEntry {
+    pub position: usize,
+    pub timestamp_ms: u64,
+    pub text: String,
+}
+
+impl ReplayState {
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn buffers(&self) -> &[ReplayedBuffer] {
+        &self.buffers
+    }
+
+    pub fn buffer(&self, id: u64) -> Option<&ReplayedBuffer> {
+        self.buffers.iter().find(|buffer| buffer.id == id)
+    }
+
+    pub fn collaborators(&self) -> &[proto::SessionRecordingCollaborator] {
+        &self.collaborators
+    }
+
+    /// The follows and chat messages that happened so far.
+    pub fn log(&self) -> &[LogEntry] {
+        &self.log
+    }
+
+    /// Replays the events up to the given position. Buffers can't be rewound, so seeking
+    /// backward replays the recording from its start.
+    pub fn seek(&mut self, recording: &SessionRecording, position: usize, cx: &mut AppContext) {
+        let position = position.min(recording.events.len());
+        if position < self.position {
+            *self = Self::default();
+        }
+        while self.position < position {
+            let event = &recording.events[self.position];
+            self.apply(event, cx).log_err();
+            self.position += 1;
+        }
+    }
+
+    fn apply(&mut self, event: &proto::SessionRecordingEvent, cx: &mut AppContext) -> Result<()> {
+        match event.event.as_ref().ok_or_else(|| anyhow!("empty event"))? {
+            Event::Collaborators(collaborators) => {
+                self.collaborators = collaborators.collaborators.clone();
+            }
+            Event::BufferOpened(opened) => {
+                let state = opened
+                    .state
+                    .clone()
+                    .ok_or_else(|| anyhow!("missing buffer state"))?;
+                let id = state.id;
+                let buffer =
+                    Buffer::from_proto(REPLAY_REPLICA_ID, Capability::ReadOnly, state, None)?;
+                let buffer = cx.new_model(|_| buffer);
+                apply_operations(&buffer, &opened.operations, cx)?;
+                self.buffers.retain(|buffer| buffer.id != id);
+                self.buffers.push(ReplayedBuffer {
+                    id,
+                    path: opened.path.clone(),
+                    buffer,
+                });
+            }
+            Event::BufferOperations(operations) => {
+                let buffer = self
+                    .buffer(operations.buffer_id)
+                    .ok_or_else(|| anyhow!("unknown buffer {}", operations.buffer_id))?
+                    .buffer
+                    .clone();
+                apply_operations(&buffer, &operations.operations, cx)?;
+            }
+            Event::Follow(follow) => {
+                let text = if follow.following {
+                    format!("{} started following {}", follow.follower, follow.leader)
+                } else {
+                    format!("{} stopped following {}", follow.follower, follow.leader)
+                };
+                self.push_log(event.timestamp_ms, text);
+            }
+            Event::ChatMessage(message) => {
+                let text = format!("{}: {}", message.sender, message.body);
+                self.push_log(event.timestamp_ms, text);
+            }
+        }
+        Ok(())
+    }
+
+    fn push_log(&mut self, timestamp_ms: u64, text: String) {
+        self.log.push(LogEntry {
+            position: self.position,
+            timestamp_ms,
+            text,
+        });
+    }
+}
+
+fn apply_operations(
+    buffer: &Model<Buffer>,
+    operations: &[proto::Operation],
+    cx: &mut AppContext,
+) -> Result<()> {
+    let operations = operations
+        .iter()
+        .cloned()
+        .map(deserialize_operation)
+        .collect::<Result<Vec<_>>>()?;
+    buffer.update(cx, |buffer, cx| buffer.apply_ops(operations, cx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::proto::serialize_operation;
+
+    #[gpui::test]
+    fn test_seek(cx: &mut TestAppContext) {
+        let host = cx.new_model(|cx| Buffer::local("abc", cx));
+        let initial_state = host.read_with(cx, |buffer, _| buffer.to_proto());
+        let mut operations = Vec::new();
+        for (offset, text) in [(3, "d"), (0, "x")] {
+            host.update(cx, |buffer, cx| {
+                let version = buffer.version();
+                buffer.edit([(offset..offset, text)], None, cx);
+                operations.extend(
+                    buffer
+                        .operations()
+                        .iter()
+                        .filter(|(_, operation)| !version.observed(operation.timestamp()))
+                        .map(|(_, operation)| {
+                            serialize_operation(&language::Operation::Buffer(operation.clone()))
+                        }),
+                );
+            });
+        }
+
+        let event = |timestamp_ms, event| proto::SessionRecordingEvent {
+            timestamp_ms,
+            event: Some(event),
+        };
+        let recording = SessionRecording {
+            events: vec![
+                event(
+                    0,
+                    Event::BufferOpened(proto::SessionRecordingBufferOpened {
+                        path: "a.txt".into(),
+                        state: Some(initial_state.clone()),
+                        operations: Vec::new(),
+                    }),
+                ),
+                event(
+                    10,
+                    Event::BufferOperations(proto::SessionRecordingBufferOperations {
+                        buffer_id: initial_state.id,
+                        operations: vec![operations[0].clone()],
+                    }),
+                ),
+                event(
+                    20,
+                    Event::ChatMessage(proto::SessionRecordingChatMessage {
+                        sender: "nathan".into(),
+                        body: "hi".into(),
+                    }),
+                ),
+                event(
+                    30,
+                    Event::BufferOperations(proto::SessionRecordingBufferOperations {
+                        buffer_id: initial_state.id,
+                        operations: vec![operations[1].clone()],
+                    }),
+                ),
+            ],
+        };
+
+        let mut bytes = Vec::new();
+        for event in &recording.events {
+            bytes.extend(event.encode_length_delimited_to_vec());
+        }
+        let recording = SessionRecording::parse(&bytes).unwrap();
+        assert_eq!(recording.duration_ms(), 30);
+        assert_eq!(recording.position_at(15), 2);
+        assert_eq!(recording.timestamp_at(2), 10);
+
+        let mut state = ReplayState::default();
+        let text = |state: &ReplayState, cx: &mut TestAppContext| {
+            let buffer = state.buffer(initial_state.id).unwrap().buffer.clone();
+            buffer.read_with(cx, |buffer, _| buffer.text())
+        };
+        cx.update(|cx| state.seek(&recording, 4, cx));
+        assert_eq!(text(&state, cx), "xabcd");
+        assert_eq!(state.log().len(), 1);
+        assert_eq!(state.log()[0].text, "nathan: hi");
+
+        cx.update(|cx| state.seek(&recording, 2, cx));
+        assert_eq!(text(&state, cx), "abcd");
+        assert!(state.log().is_empty());
+
+        cx.update(|cx| state.seek(&recording, 1, cx));
+        assert_eq!(text(&state, cx), "abc");
+    }
+}
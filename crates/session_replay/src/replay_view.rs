@@ -0,0 +1,438 @@
+use crate::replay::{ReplayState, SessionRecording};
+use client::{Collaborator, ParticipantIndex};
+use collections::HashMap;
+use editor::{CollaborationHub, Editor};
+use gpui::{
+    canvas, relative, AnyElement, AppContext, Bounds, Context as _, EventEmitter, FocusHandle,
+    FocusableView, Model, MouseButton, MouseDownEvent, MouseMoveEvent, Pixels, Point, Render,
+    SharedString, Task, View, ViewContext, VisualContext as _, WindowContext,
+};
+use language::Buffer;
+use project::Project;
+use rpc::proto::{self, PeerId};
+use std::{path::PathBuf, time::Duration};
+use ui::{prelude::*, IconButton, ListItem, ListItemSpacing, Tooltip};
+use workspace::item::{Item, TabContentParams};
+
+/// Replays a recorded session, showing the buffers of the project as they were at the
+/// point of the timeline that is selected.
+pub struct SessionReplayView {
+    path: PathBuf,
+    recording: SessionRecording,
+    state: ReplayState,
+    project: Model<Project>,
+    participants: Model<ReplayParticipants>,
+    selected_buffer_id: Option<u64>,
+    editor: Option<(Model<Buffer>, View<Editor>)>,
+    timeline_bounds: Bounds<Pixels>,
+    playback: Option<Task<()>>,
+    focus_handle: FocusHandle,
+}
+
+impl SessionReplayView {
+    pub fn new(
+        path: PathBuf,
+        recording: SessionRecording,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            path,
+            recording,
+            state: ReplayState::default(),
+            project,
+            participants: cx.new_model(|_| ReplayParticipants::default()),
+            selected_buffer_id: None,
+            editor: None,
+            timeline_bounds: Bounds::default(),
+            playback: None,
+            focus_handle: cx.focus_handle(),
+        };
+        this.seek(this.recording.events.len(), cx);
+        this
+    }
+
+    fn seek(&mut self, position: usize, cx: &mut ViewContext<Self>) {
+        self.state.seek(&self.recording, position, cx);
+        self.participants.update(cx, |participants, _| {
+            participants.set_collaborators(self.state.collaborators())
+        });
+        if self
+            .selected_buffer_id
+            .map_or(true, |id| self.state.buffer(id).is_none())
+        {
+            self.selected_buffer_id = self.state.buffers().last().map(|buffer| buffer.id);
+        }
+        self.update_editor(cx);
+        cx.notify();
+    }
+
+    fn seek_to_point(&mut self, point: Point<Pixels>, cx: &mut ViewContext<Self>) {
+        let width = self.timeline_bounds.size.width;
+        if width <= Pixels::ZERO {
+            return;
+        }
+        let fraction = ((point.x - self.timeline_bounds.left()) / width).clamp(0., 1.);
+        let timestamp_ms = (self.recording.duration_ms() as f64 * fraction as f64) as u64;
+        self.playback = None;
+        self.seek(self.recording.position_at(timestamp_ms), cx);
+    }
+
+    fn select_buffer(&mut self, id: u64, cx: &mut ViewContext<Self>) {
+        self.selected_buffer_id = Some(id);
+        self.update_editor(cx);
+        cx.notify();
+    }
+
+    /// Shows the selected buffer in the editor, creating a new one if the buffer was
+    /// reconstructed by seeking backward.
+    fn update_editor(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(replayed) = self.selected_buffer_id.and_then(|id| self.state.buffer(id)) else {
+            self.editor = None;
+            return;
+        };
+        if self
+            .editor
+            .as_ref()
+            .map_or(false, |(buffer, _)| *buffer == replayed.buffer)
+        {
+            return;
+        }
+
+        let buffer = replayed.buffer.clone();
+        let languages = self.project.read(cx).languages().clone();
+        let path = PathBuf::from(&replayed.path);
+        cx.spawn(|_, mut cx| {
+            let buffer = buffer.clone();
+            async move {
+                let language = languages.language_for_file_path(&path).await?;
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.set_language(Some(language), cx)
+                })
+            }
+        })
+        .detach_and_log_err(cx);
+
+        let participants = self.participants.clone();
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), None, cx);
+            editor.set_read_only(true);
+            editor.set_collaboration_hub(Box::new(participants));
+            editor
+        });
+        self.editor = Some((buffer, editor));
+    }
+
+    fn toggle_playback(&mut self, cx: &mut ViewContext<Self>) {
+        if self.playback.take().is_some() {
+            cx.notify();
+            return;
+        }
+        if self.state.position() >= self.recording.events.len() {
+            self.seek(0, cx);
+        }
+        self.playback = Some(cx.spawn(|this, mut cx| async move {
+            loop {
+                let Ok(Some(delay)) = this.update(&mut cx, |this, _| {
+                    let position = this.state.position();
+                    let next = this.recording.events.get(position)?;
+                    let now = this.recording.timestamp_at(position);
+                    Some(Duration::from_millis(next.timestamp_ms.saturating_sub(now)))
+                }) else {
+                    break;
+                };
+                cx.background_executor().timer(delay).await;
+                if this
+                    .update(&mut cx, |this, cx| this.seek(this.state.position() + 1, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                this.playback = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_sidebar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let buffers = self.state.buffers().iter().map(|buffer| {
+            let id = buffer.id;
+            ListItem::new(("buffer", id as usize))
+                .spacing(ListItemSpacing::Sparse)
+                .selected(self.selected_buffer_id == Some(id))
+                .child(Label::new(buffer.path.clone()).single_line())
+                .on_click(cx.listener(move |this, _, cx| this.select_buffer(id, cx)))
+        });
+        let log = self.state.log().iter().enumerate().map(|(ix, entry)| {
+            let position = entry.position + 1;
+            ListItem::new(("log", ix))
+                .spacing(ListItemSpacing::Sparse)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Label::new(format_timestamp(entry.timestamp_ms))
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        )
+                        .child(Label::new(entry.text.clone()).size(LabelSize::Small)),
+                )
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.playback = None;
+                    this.seek(position, cx);
+                }))
+        });
+
+        v_flex()
+            .id("session-replay-sidebar")
+            .w(rems(18.))
+            .h_full()
+            .p_1()
+            .gap_1()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new("Files")
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            )
+            .children(buffers)
+            .child(
+                Label::new("Activity")
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+            )
+            .children(log)
+    }
+
+    fn render_timeline(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let position = self.state.position();
+        let duration_ms = self.recording.duration_ms();
+        let timestamp_ms = self.recording.timestamp_at(position);
+        let progress = if duration_ms == 0 {
+            if position == 0 {
+                0.
+            } else {
+                1.
+            }
+        } else {
+            timestamp_ms as f32 / duration_ms as f32
+        };
+        let colors = cx.theme().colors();
+
+        h_flex()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(colors.border)
+            .child(
+                IconButton::new("previous-event", IconName::ChevronLeft)
+                    .disabled(position == 0)
+                    .tooltip(|cx| Tooltip::text("Previous Event", cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.playback = None;
+                        this.seek(this.state.position().saturating_sub(1), cx);
+                    })),
+            )
+            .child(
+                IconButton::new(
+                    "toggle-playback",
+                    if self.playback.is_some() {
+                        IconName::Stop
+                    } else {
+                        IconName::Play
+                    },
+                )
+                .tooltip(|cx| Tooltip::text("Toggle Playback", cx))
+                .on_click(cx.listener(|this, _, cx| this.toggle_playback(cx))),
+            )
+            .child(
+                IconButton::new("next-event", IconName::ChevronRight)
+                    .disabled(position >= self.recording.events.len())
+                    .tooltip(|cx| Tooltip::text("Next Event", cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.playback = None;
+                        this.seek(this.state.position() + 1, cx);
+                    })),
+            )
+            .child(
+                div()
+                    .id("session-replay-timeline")
+                    .relative()
+                    .flex_1()
+                    .h_2()
+                    .rounded_md()
+                    .bg(colors.element_background)
+                    .cursor_pointer()
+                    .child({
+                        let view = cx.view().clone();
+                        canvas(
+                            move |bounds, cx| {
+                                view.update(cx, |this, _| this.timeline_bounds = bounds)
+                            },
+                            |_, _, _| {},
+                        )
+                        .absolute()
+                        .size_full()
+                    })
+                    .child(
+                        div()
+                            .absolute()
+                            .left_0()
+                            .top_0()
+                            .h_full()
+                            .w(relative(progress))
+                            .rounded_md()
+                            .bg(colors.text_accent),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, event: &MouseDownEvent, cx| {
+                            this.seek_to_point(event.position, cx)
+                        }),
+                    )
+                    .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, cx| {
+                        if event.pressed_button == Some(MouseButton::Left)
+                            && this.timeline_bounds.contains(&event.position)
+                        {
+                            this.seek_to_point(event.position, cx)
+                        }
+                    })),
+            )
+            .child(
+                Label::new(format!(
+                    "{} / {}",
+                    format_timestamp(timestamp_ms),
+                    format_timestamp(duration_ms)
+                ))
+                .color(Color::Muted)
+                .size(LabelSize::Small),
+            )
+    }
+}
+
+fn format_timestamp(timestamp_ms: u64) -> String {
+    let seconds = timestamp_ms / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+impl Render for SessionReplayView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SessionReplayView")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(self.render_sidebar(cx))
+                    .child(
+                        div()
+                            .flex_1()
+                            .h_full()
+                            .children(self.editor.as_ref().map(|(_, editor)| editor.clone())),
+                    ),
+            )
+            .child(self.render_timeline(cx))
+    }
+}
+
+impl EventEmitter<()> for SessionReplayView {}
+
+impl FocusableView for SessionReplayView {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for SessionReplayView {
+    type Event = ();
+
+    fn tab_content(&self, params: TabContentParams, _cx: &WindowContext) -> AnyElement {
+        let title = self
+            .path
+            .file_name()
+            .unwrap_or_else(|| self.path.as_os_str())
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &AppContext) -> Option<SharedString> {
+        Some(self.path.to_string_lossy().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("session replay")
+    }
+}
+
+/// The collaborators of a recorded session, which the editor uses to show their
+/// selections.
+#[derive(Default)]
+struct ReplayParticipants {
+    collaborators: HashMap<PeerId, Collaborator>,
+    participant_indices: HashMap<u64, ParticipantIndex>,
+    names: HashMap<u64, SharedString>,
+}
+
+impl ReplayParticipants {
+    fn set_collaborators(&mut self, collaborators: &[proto::SessionRecordingCollaborator]) {
+        self.collaborators.clear();
+        for collaborator in collaborators {
+            let user_id = collaborator.user_id;
+            // Recorded collaborators have no peer, so their replica id stands in for it.
+            let peer_id = PeerId {
+                owner_id: 0,
+                id: collaborator.replica_id,
+            };
+            self.collaborators.insert(
+                peer_id,
+                Collaborator {
+                    peer_id,
+                    replica_id: collaborator.replica_id as u16,
+                    user_id,
+                },
+            );
+            // Keep the colors of the participants stable while seeking.
+            let next_index = self.participant_indices.len() as u32;
+            self.participant_indices
+                .entry(user_id)
+                .or_insert(ParticipantIndex(next_index));
+            self.names
+                .insert(user_id, collaborator.github_login.clone().into());
+        }
+    }
+}
+
+impl CollaborationHub for Model<ReplayParticipants> {
+    fn collaborators<'a>(&self, cx: &'a AppContext) -> &'a HashMap<PeerId, Collaborator> {
+        &self.read(cx).collaborators
+    }
+
+    fn user_participant_indices<'a>(
+        &self,
+        cx: &'a AppContext,
+    ) -> &'a HashMap<u64, ParticipantIndex> {
+        &self.read(cx).participant_indices
+    }
+
+    fn user_names(&self, cx: &AppContext) -> HashMap<u64, SharedString> {
+        self.read(cx).names.clone()
+    }
+}
@@ -0,0 +1,145 @@
+//! Records the collaboration sessions of a shared project, so that they can be replayed
+//! later with a timeline.
+
+mod recorder;
+mod replay;
+mod replay_view;
+
+use anyhow::anyhow;
+use call::ActiveCall;
+use collections::HashMap;
+use fs::Fs as _;
+use gpui::{
+    actions, AppContext, Context as _, EntityId, Global, Model, PathPromptOptions, ViewContext,
+    VisualContext as _,
+};
+use workspace::Workspace;
+
+pub use recorder::{SessionRecorder, SessionRecorderEvent};
+pub use replay::{ReplayState, SessionRecording};
+pub use replay_view::SessionReplayView;
+
+actions!(
+    session_replay,
+    [
+        StartRecordingSession,
+        StopRecordingSession,
+        OpenSessionRecording
+    ]
+);
+
+/// The sessions that are being recorded, by the entity id of their project.
+#[derive(Default)]
+struct ActiveRecorders(HashMap<EntityId, Model<SessionRecorder>>);
+
+impl Global for ActiveRecorders {}
+
+pub fn init(cx: &mut AppContext) {
+    cx.set_global(ActiveRecorders::default());
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(start_recording)
+            .register_action(stop_recording)
+            .register_action(open_recording);
+    })
+    .detach();
+}
+
+fn start_recording(
+    workspace: &mut Workspace,
+    _: &StartRecordingSession,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let Some(room) = ActiveCall::global(cx).read(cx).room().cloned() else {
+        workspace.show_error(&anyhow!("join a call to record a session"), cx);
+        return;
+    };
+    if project.read(cx).remote_id().is_none() {
+        workspace.show_error(&anyhow!("share the project to record a session"), cx);
+        return;
+    }
+    let is_recording = cx
+        .global::<ActiveRecorders>()
+        .0
+        .get(&project.entity_id())
+        .map_or(false, |recorder| recorder.read(cx).is_recording());
+    if is_recording {
+        return;
+    }
+
+    let start_path = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .next()
+        .and_then(|worktree| Some(worktree.read(cx).as_local()?.abs_path().to_path_buf()))
+        .unwrap_or_else(|| util::paths::home_dir().clone());
+    let path = cx.prompt_for_new_path(&start_path);
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(path) = path.await? else {
+            return Ok(());
+        };
+        let file = smol::fs::File::create(&path).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let file = match file {
+                Ok(file) => file,
+                Err(error) => {
+                    workspace.show_error(&error, cx);
+                    return;
+                }
+            };
+            let project_id = project.entity_id();
+            let recorder = cx.new_model(|cx| SessionRecorder::new(project, room, file, cx));
+            cx.subscribe(&recorder, move |workspace, recorder, event, cx| {
+                let recorders = &mut cx.global_mut::<ActiveRecorders>().0;
+                if recorders.get(&project_id) == Some(&recorder) {
+                    recorders.remove(&project_id);
+                }
+                if let SessionRecorderEvent::Failed(error) = event {
+                    workspace.show_error(error, cx);
+                }
+            })
+            .detach();
+            cx.global_mut::<ActiveRecorders>()
+                .0
+                .insert(project_id, recorder);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn stop_recording(
+    workspace: &mut Workspace,
+    _: &StopRecordingSession,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project_id = workspace.project().entity_id();
+    if let Some(recorder) = cx.global_mut::<ActiveRecorders>().0.remove(&project_id) {
+        recorder.update(cx, |recorder, cx| recorder.stop(cx));
+    }
+}
+
+fn open_recording(
+    workspace: &mut Workspace,
+    _: &OpenSessionRecording,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+    });
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(path) = paths.await?.and_then(|paths| paths.into_iter().next()) else {
+            return Ok(());
+        };
+        let recording = SessionRecording::parse(&fs.load_bytes(&path).await?)?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let view = cx.new_view(|cx| SessionReplayView::new(path, recording, project, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
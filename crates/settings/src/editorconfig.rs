@@ -0,0 +1,296 @@
+//! Support for [EditorConfig](https://editorconfig.org) files.
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::Path;
+use util::ResultExt as _;
+
+/// The file name of EditorConfig files.
+pub const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// A parsed `.editorconfig` file.
+#[derive(Debug)]
+pub struct Editorconfig {
+    root: bool,
+    sections: Vec<EditorconfigSection>,
+}
+
+#[derive(Debug)]
+struct EditorconfigSection {
+    matcher: GlobMatcher,
+    properties: Vec<(String, String)>,
+}
+
+/// The EditorConfig properties that apply to a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditorconfigProperties {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<IndentSize>,
+    pub tab_width: Option<u32>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub max_line_length: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentSize {
+    /// Indent using the width of a tab.
+    Tab,
+    Columns(u32),
+}
+
+impl Editorconfig {
+    /// Parses the contents of an `.editorconfig` file.
+    ///
+    /// Lines that can't be parsed and sections whose glob is invalid are ignored, as the
+    /// EditorConfig specification requires.
+    pub fn parse(content: &str) -> Self {
+        let mut root = false;
+        let mut sections = Vec::new();
+        let mut current_section: Option<EditorconfigSection> = None;
+        let mut in_preamble = true;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_preamble = false;
+                sections.extend(current_section.take());
+                current_section =
+                    section_matcher(name)
+                        .log_err()
+                        .map(|matcher| EditorconfigSection {
+                            matcher,
+                            properties: Vec::new(),
+                        });
+            } else if let Some((key, value)) = line.split_once('=') {
+                let key = key.trim().to_lowercase();
+                let value = value.trim().to_lowercase();
+                if in_preamble {
+                    if key == "root" {
+                        root = value == "true";
+                    }
+                } else if let Some(section) = current_section.as_mut() {
+                    section.properties.push((key, value));
+                }
+            }
+        }
+        sections.extend(current_section);
+
+        Self { root, sections }
+    }
+
+    /// Whether EditorConfig files in ancestor directories should be ignored.
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
+    /// Adds the raw properties of the sections that match the given path, which is relative
+    /// to the directory containing this file. Later sections override earlier ones.
+    fn collect_properties(&self, relative_path: &Path, properties: &mut HashMap<String, String>) {
+        for section in &self.sections {
+            if section.matcher.is_match(relative_path) {
+                for (key, value) in &section.properties {
+                    properties.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Computes the properties for a file from the EditorConfig files that apply to it, ordered
+/// from the outermost directory to the innermost one, along with the file's path relative to
+/// each of those directories.
+pub fn editorconfig_properties<'a>(
+    configs: impl IntoIterator<Item = (&'a Editorconfig, &'a Path)>,
+) -> Option<EditorconfigProperties> {
+    let mut raw_properties = HashMap::default();
+    for (config, relative_path) in configs {
+        config.collect_properties(relative_path, &mut raw_properties);
+    }
+
+    let properties = EditorconfigProperties {
+        indent_style: property(&raw_properties, "indent_style", |value| match value {
+            "tab" => Some(IndentStyle::Tab),
+            "space" => Some(IndentStyle::Space),
+            _ => None,
+        }),
+        indent_size: property(&raw_properties, "indent_size", |value| match value {
+            "tab" => Some(IndentSize::Tab),
+            _ => value
+                .parse()
+                .ok()
+                .filter(|size| *size > 0)
+                .map(IndentSize::Columns),
+        }),
+        tab_width: property(&raw_properties, "tab_width", |value| {
+            value.parse().ok().filter(|width| *width > 0)
+        }),
+        trim_trailing_whitespace: property(&raw_properties, "trim_trailing_whitespace", parse_bool),
+        insert_final_newline: property(&raw_properties, "insert_final_newline", parse_bool),
+        max_line_length: property(&raw_properties, "max_line_length", |value| {
+            value.parse().ok()
+        }),
+    };
+    (properties != EditorconfigProperties::default()).then_some(properties)
+}
+
+impl EditorconfigProperties {
+    /// The number of columns per indentation level, if specified.
+    pub fn tab_size(&self) -> Option<u32> {
+        match self.indent_size {
+            Some(IndentSize::Columns(columns)) => Some(columns),
+            Some(IndentSize::Tab) | None => self.tab_width,
+        }
+    }
+}
+
+fn property<T>(
+    properties: &HashMap<String, String>,
+    key: &str,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> Option<T> {
+    // Values that aren't recognized, including `unset`, leave the property unspecified.
+    parse(properties.get(key)?)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Builds the matcher for a section name. Names without a slash match files in any
+/// subdirectory, while names containing one are relative to the `.editorconfig` file.
+fn section_matcher(name: &str) -> Result<GlobMatcher> {
+    let glob = if let Some(relative) = name.strip_prefix('/') {
+        relative.to_string()
+    } else if name.contains('/') {
+        name.to_string()
+    } else {
+        format!("**/{name}")
+    };
+    // EditorConfig allows `**` within a path component, which globset doesn't.
+    let glob = glob
+        .split('/')
+        .map(|component| match component.find("**") {
+            Some(ix) if component != "**" => {
+                let (prefix, suffix) = component.split_at(ix);
+                let suffix = &suffix[2..];
+                let mut component = String::new();
+                if !prefix.is_empty() {
+                    component.push_str(prefix);
+                    component.push_str("*/");
+                }
+                component.push_str("**");
+                if !suffix.is_empty() {
+                    component.push_str("/*");
+                    component.push_str(suffix);
+                }
+                component
+            }
+            _ => component.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    let glob = GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .backslash_escape(true)
+        .build()
+        .map_err(|error| anyhow!("invalid .editorconfig section {name:?}: {error}"))?;
+    Ok(glob.compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_editorconfig_properties() {
+        let root = Editorconfig::parse(indoc! {"
+            # Top-most EditorConfig file
+            root = true
+
+            [*]
+            indent_style = space
+            indent_size = 4
+            insert_final_newline = true
+
+            [*.{js,ts}]
+            indent_size = 2
+
+            [Makefile]
+            indent_style = tab
+            indent_size = tab
+            tab_width = 8
+
+            [/docs/**.md]
+            trim_trailing_whitespace = false
+            max_line_length = off
+        "});
+        let nested = Editorconfig::parse(indoc! {"
+            [*.ts]
+            indent_size = unset
+            Max_Line_Length = 100
+        "});
+        assert!(root.is_root());
+        assert!(!nested.is_root());
+
+        let properties = |path: &str| {
+            let path = Path::new(path);
+            let mut configs = vec![(&root, path)];
+            if let Ok(relative_path) = path.strip_prefix("web") {
+                configs.push((&nested, relative_path));
+            }
+            editorconfig_properties(configs)
+        };
+
+        assert_eq!(
+            properties("src/main.rs"),
+            Some(EditorconfigProperties {
+                indent_style: Some(IndentStyle::Space),
+                indent_size: Some(IndentSize::Columns(4)),
+                insert_final_newline: Some(true),
+                ..Default::default()
+            })
+        );
+        assert_eq!(properties("src/lib.js").unwrap().tab_size(), Some(2));
+        assert_eq!(
+            properties("web/src/app.ts"),
+            Some(EditorconfigProperties {
+                indent_style: Some(IndentStyle::Space),
+                indent_size: None,
+                insert_final_newline: Some(true),
+                max_line_length: Some(100),
+                ..Default::default()
+            })
+        );
+
+        let makefile = properties("build/Makefile").unwrap();
+        assert_eq!(makefile.indent_style, Some(IndentStyle::Tab));
+        assert_eq!(makefile.tab_size(), Some(8));
+
+        let readme = properties("docs/guide/README.md").unwrap();
+        assert_eq!(readme.trim_trailing_whitespace, Some(false));
+        assert_eq!(readme.max_line_length, None);
+        assert_eq!(
+            properties("src/docs/README.md")
+                .unwrap()
+                .trim_trailing_whitespace,
+            None
+        );
+    }
+}
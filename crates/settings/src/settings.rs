@@ -1,3 +1,4 @@
+mod editorconfig;
 mod keymap_file;
 mod settings_file;
 mod settings_store;
@@ -7,6 +8,10 @@ use rust_embed::RustEmbed;
 use std::{borrow::Cow, str};
 use util::asset_str;
 
+pub use editorconfig::{
+    EditorconfigProperties, IndentSize as EditorconfigIndentSize,
+    IndentStyle as EditorconfigIndentStyle, EDITORCONFIG_FILE_NAME,
+};
 pub use keymap_file::KeymapFile;
 pub use settings_file::*;
 pub use settings_store::{
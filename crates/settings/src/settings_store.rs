@@ -15,6 +15,8 @@ use std::{
 };
 use util::{merge_non_null_json_value_into, RangeExt, ResultExt as _};
 
+use crate::editorconfig::{editorconfig_properties, Editorconfig, EditorconfigProperties};
+
 /// A value that can be defined as a user setting.
 ///
 /// Settings can be loaded from a combination of multiple JSON files.
@@ -157,6 +159,8 @@ pub struct SettingsStore {
     raw_user_settings: serde_json::Value,
     raw_extension_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(usize, Arc<Path>), serde_json::Value>,
+    raw_editorconfigs: BTreeMap<(usize, Arc<Path>), (String, Editorconfig)>,
+    revision: usize,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -173,6 +177,8 @@ impl Default for SettingsStore {
             raw_user_settings: serde_json::json!({}),
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            raw_editorconfigs: Default::default(),
+            revision: 0,
             tab_size_callback: Default::default(),
         }
     }
@@ -285,6 +291,7 @@ impl SettingsStore {
     ///
     /// The given value will be overwritten if the user settings file changes.
     pub fn override_global<T: Settings>(&mut self, value: T) {
+        self.revision += 1;
         self.setting_values
             .get_mut(&TypeId::of::<T>())
             .unwrap_or_else(|| panic!("unregistered setting type {}", type_name::<T>()))
@@ -464,6 +471,81 @@ impl SettingsStore {
         Ok(())
     }
 
+    /// Add or remove the `.editorconfig` file in the given directory.
+    pub fn set_local_editorconfig(
+        &mut self,
+        root_id: usize,
+        directory: Arc<Path>,
+        editorconfig_content: Option<&str>,
+    ) {
+        self.revision += 1;
+        if let Some(content) = editorconfig_content {
+            self.raw_editorconfigs.insert(
+                (root_id, directory),
+                (content.to_string(), Editorconfig::parse(content)),
+            );
+        } else {
+            self.raw_editorconfigs.remove(&(root_id, directory));
+        }
+    }
+
+    pub fn local_editorconfigs(
+        &self,
+        root_id: usize,
+    ) -> impl '_ + Iterator<Item = (Arc<Path>, String)> {
+        self.raw_editorconfigs
+            .range((root_id, Path::new("").into())..(root_id + 1, Path::new("").into()))
+            .map(|((_, directory), (content, _))| (directory.clone(), content.clone()))
+    }
+
+    /// Returns the properties of the `.editorconfig` files that apply to the given path.
+    pub fn editorconfig_properties(
+        &self,
+        root_id: usize,
+        path: &Path,
+    ) -> Option<EditorconfigProperties> {
+        // Directories are sorted before their subdirectories.
+        let configs = self
+            .raw_editorconfigs
+            .range((root_id, Path::new("").into())..(root_id + 1, Path::new("").into()))
+            .filter_map(|((_, directory), (_, config))| {
+                Some((config, path.strip_prefix(directory).ok()?))
+            })
+            .collect::<Vec<_>>();
+        let innermost_root = configs
+            .iter()
+            .rposition(|(config, _)| config.is_root())
+            .unwrap_or(0);
+        editorconfig_properties(configs.into_iter().skip(innermost_root))
+    }
+
+    /// Returns the contents of the local settings files that apply to the given path, from the
+    /// least specific to the most specific.
+    pub fn local_settings_contents_for_path<T: Settings>(
+        &self,
+        root_id: usize,
+        path: &Path,
+    ) -> Vec<T::FileContent> {
+        let setting_value = self
+            .setting_values
+            .get(&TypeId::of::<T>())
+            .unwrap_or_else(|| panic!("unregistered setting type {}", type_name::<T>()));
+        self.raw_local_settings
+            .range((root_id, Path::new("").into())..(root_id + 1, Path::new("").into()))
+            .filter(|((_, directory), _)| path.starts_with(directory))
+            .filter_map(|(_, settings)| {
+                let settings = setting_value.deserialize_setting(settings).log_err()?;
+                Some(*settings.0.downcast::<T::FileContent>().ok()?)
+            })
+            .collect()
+    }
+
+    /// Returns a number that changes whenever any of the settings change, so that values
+    /// derived from them can be cached.
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
     pub fn set_extension_settings<T: Serialize>(
         &mut self,
         content: T,
@@ -482,6 +564,7 @@ impl SettingsStore {
     /// Add or remove a set of local settings via a JSON string.
     pub fn clear_local_settings(&mut self, root_id: usize, cx: &mut AppContext) -> Result<()> {
         self.raw_local_settings.retain(|k, _| k.0 != root_id);
+        self.raw_editorconfigs.retain(|k, _| k.0 != root_id);
         self.recompute_values(Some((root_id, "".as_ref())), cx)?;
         Ok(())
     }
@@ -604,6 +687,8 @@ impl SettingsStore {
         changed_local_path: Option<(usize, &Path)>,
         cx: &mut AppContext,
     ) -> Result<()> {
+        self.revision += 1;
+
         // Reload the global and local values for every setting.
         let mut project_settings_stack = Vec::<DeserializedSetting>::new();
         let mut paths_stack = Vec::<Option<(usize, &Path)>>::new();
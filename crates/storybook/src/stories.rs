@@ -1,6 +1,7 @@
 mod auto_height_editor;
 mod cursor;
 mod focus;
+mod grid;
 mod kitchen_sink;
 mod overflow_scroll;
 mod picker;
@@ -12,6 +13,7 @@ mod with_rem_size;
 pub use auto_height_editor::*;
 pub use cursor::*;
 pub use focus::*;
+pub use grid::*;
 pub use kitchen_sink::*;
 pub use overflow_scroll::*;
 pub use picker::*;
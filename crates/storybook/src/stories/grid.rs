@@ -0,0 +1,69 @@
+use gpui::{GridTrack, Render};
+use story::Story;
+
+use ui::prelude::*;
+
+pub struct GridStory;
+
+impl Render for GridStory {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        Story::container()
+            .child(Story::title("Grid"))
+            .child(Story::label("`grid_cols(4)`"))
+            .child(
+                div()
+                    .grid()
+                    .grid_cols(4)
+                    .gap_2()
+                    .children((1..=10).map(|i| cell(i).debug_bg_cyan())),
+            )
+            .child(Story::label("`col_span`, `row_span` and `col_span_full`"))
+            .child(
+                div()
+                    .grid()
+                    .grid_cols(3)
+                    .grid_auto_rows([GridTrack::fixed(px(48.))])
+                    .gap_2()
+                    .child(cell(1).col_span(2).debug_bg_green())
+                    .child(cell(2).row_span(2).debug_bg_magenta())
+                    .child(cell(3).debug_bg_green())
+                    .child(cell(4).debug_bg_green())
+                    .child(cell(5).col_span_full().debug_bg_yellow()),
+            )
+            .child(Story::label("`grid_template_columns` with a fixed sidebar"))
+            .child(
+                div()
+                    .grid()
+                    .grid_template_columns([GridTrack::fixed(px(160.)), GridTrack::fr(1.)])
+                    .grid_template_rows([GridTrack::auto(), GridTrack::fixed(px(120.))])
+                    .gap_2()
+                    .child(cell(1).row_span_full().debug_bg_blue())
+                    .child(cell(2).debug_bg_red())
+                    .child(cell(3).debug_bg_red()),
+            )
+            .child(Story::label("`grid_flow_col`"))
+            .child(
+                div()
+                    .grid()
+                    .grid_rows(3)
+                    .grid_flow_col()
+                    .gap_2()
+                    .children((1..=8).map(|i| cell(i).debug_bg_cyan())),
+            )
+            .child(Story::label("`justify_items_center`"))
+            .child(
+                div()
+                    .grid()
+                    .grid_cols(3)
+                    .justify_items_center()
+                    .gap_2()
+                    .children((1..=6).map(|i| cell(i).debug_bg_green())),
+            )
+    }
+}
+
+fn cell(number: usize) -> Div {
+    div()
+        .p_4()
+        .child(SharedString::from(format!("Cell {number}")))
+}
@@ -21,6 +21,7 @@ pub enum ComponentStory {
     Cursor,
     Disclosure,
     Focus,
+    Grid,
     Icon,
     IconButton,
     Keybinding,
@@ -56,6 +57,7 @@ impl ComponentStory {
             Self::Cursor => cx.new_view(|_| crate::stories::CursorStory).into(),
             Self::Disclosure => cx.new_view(|_| ui::DisclosureStory).into(),
             Self::Focus => FocusStory::view(cx).into(),
+            Self::Grid => cx.new_view(|_| crate::stories::GridStory).into(),
             Self::Icon => cx.new_view(|_| ui::IconStory).into(),
             Self::IconButton => cx.new_view(|_| ui::IconButtonStory).into(),
             Self::Keybinding => cx.new_view(|_| ui::KeybindingStory).into(),
@@ -0,0 +1,259 @@
+//! Handles conversions of terminal screens to and from the [`rpc`] protocol, so that the
+//! terminals of a shared project can be displayed by its guests.
+
+use alacritty_terminal::{
+    grid::{Dimensions, Row},
+    index::{Column, Line},
+    term::{
+        cell::{Cell, Flags},
+        TermMode,
+    },
+    vte::ansi::{Color as AnsiColor, CursorShape, NamedColor, Rgb},
+    Term,
+};
+use rpc::proto;
+
+/// The named colors, indexed by their number in the protocol. Their discriminants aren't
+/// contiguous, so they can't be used instead.
+const NAMED_COLORS: [NamedColor; 29] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+    NamedColor::Foreground,
+    NamedColor::Background,
+    NamedColor::Cursor,
+    NamedColor::DimBlack,
+    NamedColor::DimRed,
+    NamedColor::DimGreen,
+    NamedColor::DimYellow,
+    NamedColor::DimBlue,
+    NamedColor::DimMagenta,
+    NamedColor::DimCyan,
+    NamedColor::DimWhite,
+    NamedColor::BrightForeground,
+    NamedColor::DimForeground,
+];
+
+/// Serializes the visible lines of the terminal, without its scrollback.
+pub fn serialize_screen<T>(term: &Term<T>) -> proto::TerminalScreen {
+    let grid = term.grid();
+    let cursor_shape = if term.mode().contains(TermMode::SHOW_CURSOR) {
+        term.cursor_style().shape
+    } else {
+        CursorShape::Hidden
+    };
+    proto::TerminalScreen {
+        lines: term.screen_lines() as u32,
+        columns: term.columns() as u32,
+        changed_lines: (0..term.screen_lines())
+            .map(|index| serialize_line(index as u32, &grid[Line(index as i32)]))
+            .collect(),
+        cursor: Some(proto::TerminalCursor {
+            line: grid.cursor.point.line.0.max(0) as u32,
+            column: grid.cursor.point.column.0 as u32,
+            shape: serialize_cursor_shape(cursor_shape) as i32,
+        }),
+        mode: term.mode().bits(),
+    }
+}
+
+/// Writes the lines of the screen into the terminal's grid, resizing it first if the
+/// dimensions of the screen have changed.
+pub fn apply_screen<T>(term: &mut Term<T>, screen: &proto::TerminalScreen) {
+    let size = ScreenSize {
+        lines: (screen.lines as usize).max(1),
+        columns: (screen.columns as usize).max(1),
+    };
+    if term.screen_lines() != size.lines || term.columns() != size.columns {
+        term.resize(size);
+    }
+
+    let grid = term.grid_mut();
+    for line in &screen.changed_lines {
+        if line.index as usize >= size.lines {
+            continue;
+        }
+        let row = &mut grid[Line(line.index as i32)];
+        let cells = line.runs.iter().flat_map(|run| {
+            let fg = deserialize_color(run.fg.as_ref());
+            let bg = deserialize_color(run.bg.as_ref());
+            let flags = Flags::from_bits_truncate(run.flags as u16);
+            run.text.chars().map(move |c| Cell {
+                c,
+                fg,
+                bg,
+                flags,
+                ..Cell::default()
+            })
+        });
+        let mut cells = cells.fuse();
+        for column in 0..size.columns {
+            row[Column(column)] = cells.next().unwrap_or_default();
+        }
+    }
+
+    if let Some(cursor) = screen.cursor.as_ref() {
+        grid.cursor.point.line = Line((cursor.line as usize).min(size.lines - 1) as i32);
+        grid.cursor.point.column = Column((cursor.column as usize).min(size.columns - 1));
+    }
+}
+
+pub fn deserialize_mode(mode: u32) -> TermMode {
+    TermMode::from_bits_truncate(mode)
+}
+
+pub fn deserialize_cursor_shape(shape: i32) -> CursorShape {
+    match proto::terminal_cursor::Shape::from_i32(shape) {
+        Some(proto::terminal_cursor::Shape::Block) | None => CursorShape::Block,
+        Some(proto::terminal_cursor::Shape::Underline) => CursorShape::Underline,
+        Some(proto::terminal_cursor::Shape::Beam) => CursorShape::Beam,
+        Some(proto::terminal_cursor::Shape::HollowBlock) => CursorShape::HollowBlock,
+        Some(proto::terminal_cursor::Shape::Hidden) => CursorShape::Hidden,
+    }
+}
+
+fn serialize_cursor_shape(shape: CursorShape) -> proto::terminal_cursor::Shape {
+    match shape {
+        CursorShape::Block => proto::terminal_cursor::Shape::Block,
+        CursorShape::Underline => proto::terminal_cursor::Shape::Underline,
+        CursorShape::Beam => proto::terminal_cursor::Shape::Beam,
+        CursorShape::HollowBlock => proto::terminal_cursor::Shape::HollowBlock,
+        CursorShape::Hidden => proto::terminal_cursor::Shape::Hidden,
+    }
+}
+
+/// Groups the cells of a row into runs of text sharing the same colors and flags.
+pub(crate) fn serialize_line(index: u32, row: &Row<Cell>) -> proto::TerminalLine {
+    let mut runs = Vec::<proto::TerminalCellRun>::new();
+    let mut last_style = None;
+    for cell in row {
+        let style = (cell.fg, cell.bg, cell.flags);
+        if last_style != Some(style) {
+            runs.push(proto::TerminalCellRun {
+                text: String::new(),
+                fg: Some(serialize_color(cell.fg)),
+                bg: Some(serialize_color(cell.bg)),
+                flags: cell.flags.bits() as u32,
+            });
+            last_style = Some(style);
+        }
+        if let Some(run) = runs.last_mut() {
+            run.text.push(cell.c);
+        }
+    }
+    proto::TerminalLine { index, runs }
+}
+
+fn serialize_color(color: AnsiColor) -> proto::TerminalColor {
+    let color = match color {
+        AnsiColor::Named(named) => proto::terminal_color::Color::Named(
+            NAMED_COLORS
+                .iter()
+                .position(|candidate| *candidate == named)
+                .unwrap_or_default() as u32,
+        ),
+        AnsiColor::Indexed(index) => proto::terminal_color::Color::Indexed(index as u32),
+        AnsiColor::Spec(Rgb { r, g, b }) => {
+            proto::terminal_color::Color::Rgb(u32::from_be_bytes([0, r, g, b]))
+        }
+    };
+    proto::TerminalColor { color: Some(color) }
+}
+
+fn deserialize_color(color: Option<&proto::TerminalColor>) -> AnsiColor {
+    match color.and_then(|color| color.color.as_ref()) {
+        Some(proto::terminal_color::Color::Named(index)) => AnsiColor::Named(
+            NAMED_COLORS
+                .get(*index as usize)
+                .copied()
+                .unwrap_or(NamedColor::Foreground),
+        ),
+        Some(proto::terminal_color::Color::Indexed(index)) => AnsiColor::Indexed(*index as u8),
+        Some(proto::terminal_color::Color::Rgb(rgb)) => {
+            let [_, r, g, b] = rgb.to_be_bytes();
+            AnsiColor::Spec(Rgb { r, g, b })
+        }
+        None => AnsiColor::Named(NamedColor::Foreground),
+    }
+}
+
+struct ScreenSize {
+    lines: usize,
+    columns: usize,
+}
+
+impl Dimensions for ScreenSize {
+    fn total_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::{event::VoidListener, term::Config, vte::ansi::Handler};
+
+    #[test]
+    fn test_screen_round_trip() {
+        let size = ScreenSize {
+            lines: 4,
+            columns: 10,
+        };
+        let mut host = Term::new(Config::default(), &size, VoidListener);
+        for c in "hello".chars() {
+            host.input(c);
+        }
+        host.grid_mut()[Line(0)][Column(0)].fg = AnsiColor::Spec(Rgb { r: 1, g: 2, b: 3 });
+        host.grid_mut()[Line(0)][Column(1)].bg = AnsiColor::Named(NamedColor::DimCyan);
+        host.grid_mut()[Line(0)][Column(2)].flags = Flags::BOLD;
+        host.newline();
+        for c in "world".chars() {
+            host.input(c);
+        }
+
+        let mut guest = Term::new(
+            Config::default(),
+            &ScreenSize {
+                lines: 2,
+                columns: 3,
+            },
+            VoidListener,
+        );
+        apply_screen(&mut guest, &serialize_screen(&host));
+
+        assert_eq!(guest.screen_lines(), 4);
+        assert_eq!(guest.columns(), 10);
+        assert_eq!(guest.grid().cursor.point, host.grid().cursor.point);
+        for line in 0..4 {
+            for column in 0..10 {
+                let expected = &host.grid()[Line(line)][Column(column)];
+                let actual = &guest.grid()[Line(line)][Column(column)];
+                assert_eq!(actual.c, expected.c);
+                assert_eq!(actual.fg, expected.fg);
+                assert_eq!(actual.bg, expected.bg);
+                assert_eq!(actual.flags, expected.flags);
+            }
+        }
+    }
+}
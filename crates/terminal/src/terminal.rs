@@ -2,6 +2,7 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+pub mod proto;
 mod pty_info;
 pub mod terminal_settings;
 
@@ -18,7 +19,7 @@ use alacritty_terminal::{
         Config, RenderableCursor, TermMode,
     },
     tty::{self, setup_env},
-    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Rgb},
+    vte::ansi::{ClearMode, CursorShape, Handler, NamedPrivateMode, PrivateMode, Rgb},
     Term,
 };
 use anyhow::{bail, Result};
@@ -91,6 +92,8 @@ const DEBUG_TERMINAL_WIDTH: Pixels = px(500.);
 const DEBUG_TERMINAL_HEIGHT: Pixels = px(30.);
 const DEBUG_CELL_WIDTH: Pixels = px(5.);
 const DEBUG_LINE_HEIGHT: Pixels = px(5.);
+const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#;
+const WORD_REGEX: &str = r#"[\$\+\w.\[\]:/\\@\-~]+"#;
 
 ///Upward flowing events, for changing the title and such
 #[derive(Clone, Debug)]
@@ -416,12 +419,15 @@ impl TerminalBuilder {
         let pty_tx = event_loop.channel();
         let _io_thread = event_loop.spawn(); // DANGER
 
-        let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
-        let word_regex = RegexSearch::new(r#"[\$\+\w.\[\]:/\\@\-~]+"#).unwrap();
+        let url_regex = RegexSearch::new(URL_REGEX).unwrap();
+        let word_regex = RegexSearch::new(WORD_REGEX).unwrap();
 
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            connection: TerminalConnection::Pty {
+                tx: Notifier(pty_tx),
+                info: pty_info,
+            },
             completion_tx,
             term,
             events: VecDeque::with_capacity(10), //Should never get this high.
@@ -429,7 +435,6 @@ impl TerminalBuilder {
             last_mouse: None,
             matches: Vec::new(),
             selection_head: None,
-            pty_info,
             breadcrumb_text: String::new(),
             scroll_px: px(0.),
             last_mouse_position: None,
@@ -447,6 +452,68 @@ impl TerminalBuilder {
         })
     }
 
+    /// Creates a terminal that mirrors one shared by the host of a remote project. Its screen
+    /// is replaced by [`Terminal::apply_remote_screen`], and its input is sent to `input_tx`.
+    pub fn new_remote(
+        title: String,
+        read_only: bool,
+        input_tx: UnboundedSender<Vec<u8>>,
+    ) -> TerminalBuilder {
+        Self::without_pty(TerminalConnection::Remote {
+            title,
+            read_only,
+            input_tx,
+            mode: TermMode::default(),
+            cursor_shape: CursorShape::Block,
+        })
+    }
+
+    /// Creates a terminal without a process, for tests to write output into with
+    /// [`Terminal::write_output`]. Its input is sent to `input_tx`.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn new_fake(input_tx: UnboundedSender<Vec<u8>>) -> TerminalBuilder {
+        Self::without_pty(TerminalConnection::Fake { input_tx })
+    }
+
+    fn without_pty(connection: TerminalConnection) -> TerminalBuilder {
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            Config::default(),
+            &TerminalSize::default(),
+            ZedListener(events_tx),
+        );
+        let (completion_tx, _) = smol::channel::unbounded();
+
+        let url_regex = RegexSearch::new(URL_REGEX).unwrap();
+        let word_regex = RegexSearch::new(WORD_REGEX).unwrap();
+
+        let terminal = Terminal {
+            task: None,
+            connection,
+            completion_tx,
+            term: Arc::new(FairMutex::new(term)),
+            events: VecDeque::with_capacity(10),
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            last_mouse_position: None,
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            secondary_pressed: false,
+            hovered_word: false,
+            url_regex,
+            word_regex,
+        };
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &mut ModelContext<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(|terminal, mut cx| async move {
@@ -571,8 +638,25 @@ pub enum SelectionPhase {
     Ended,
 }
 
+/// How the terminal receives its output and sends its input.
+enum TerminalConnection {
+    /// A process running in a local PTY.
+    Pty { tx: Notifier, info: PtyProcessInfo },
+    /// A terminal shared by the host of a remote project, whose screen is mirrored locally.
+    Remote {
+        title: String,
+        read_only: bool,
+        input_tx: UnboundedSender<Vec<u8>>,
+        mode: TermMode,
+        cursor_shape: CursorShape,
+    },
+    /// A terminal whose output is written by tests, and whose input is sent to a channel.
+    #[cfg(any(test, feature = "test-support"))]
+    Fake { input_tx: UnboundedSender<Vec<u8>> },
+}
+
 pub struct Terminal {
-    pty_tx: Notifier,
+    connection: TerminalConnection,
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     events: VecDeque<InternalEvent>,
@@ -584,7 +668,6 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
     scroll_px: Pixels,
     next_link_id: usize,
     selection_phase: SelectionPhase,
@@ -666,8 +749,10 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
-                    cx.emit(Event::TitleChanged);
+                if let TerminalConnection::Pty { info, .. } = &self.connection {
+                    if info.has_changed() {
+                        cx.emit(Event::TitleChanged);
+                    }
                 }
             }
             AlacTermEvent::ColorRequest(idx, fun_ptr) => {
@@ -685,7 +770,95 @@ impl Terminal {
     }
 
     pub fn get_cwd(&self) -> Option<PathBuf> {
-        self.pty_info.current.as_ref().map(|info| info.cwd.clone())
+        match &self.connection {
+            TerminalConnection::Pty { info, .. } => {
+                info.current.as_ref().map(|info| info.cwd.clone())
+            }
+            TerminalConnection::Remote { .. } => None,
+            #[cfg(any(test, feature = "test-support"))]
+            TerminalConnection::Fake { .. } => None,
+        }
+    }
+
+    /// Writes output into a fake terminal, as if its process had printed it.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn write_output(&mut self, output: &[u8], cx: &mut ModelContext<Self>) {
+        let mut processor: alacritty_terminal::vte::ansi::Processor = Default::default();
+        let mut term = self.term.lock_unfair();
+        for byte in output {
+            processor.advance(&mut *term, *byte);
+        }
+        drop(term);
+        cx.emit(Event::Wakeup);
+    }
+
+    /// Whether this terminal mirrors one shared by the host of a remote project.
+    pub fn is_remote(&self) -> bool {
+        matches!(self.connection, TerminalConnection::Remote { .. })
+    }
+
+    /// Whether the host of this remote terminal doesn't accept input from guests.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self.connection,
+            TerminalConnection::Remote {
+                read_only: true,
+                ..
+            }
+        )
+    }
+
+    /// Updates the title and permissions of a remote terminal, after the host shared it again.
+    pub fn set_remote_state(
+        &mut self,
+        new_title: String,
+        new_read_only: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let TerminalConnection::Remote {
+            title, read_only, ..
+        } = &mut self.connection
+        {
+            *title = new_title;
+            *read_only = new_read_only;
+            cx.emit(Event::TitleChanged);
+            cx.notify();
+        }
+    }
+
+    /// Serializes the visible lines of the terminal, to share them with guests.
+    pub fn screen(&self) -> ::rpc::proto::TerminalScreen {
+        proto::serialize_screen(&self.term.lock_unfair())
+    }
+
+    /// Replaces the lines of a remote terminal with the ones its host sent.
+    pub fn apply_remote_screen(
+        &mut self,
+        screen: &::rpc::proto::TerminalScreen,
+        new_title: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let TerminalConnection::Remote {
+            title,
+            mode,
+            cursor_shape,
+            ..
+        } = &mut self.connection
+        else {
+            return;
+        };
+
+        proto::apply_screen(&mut self.term.lock_unfair(), screen);
+        *mode = proto::deserialize_mode(screen.mode);
+        if let Some(cursor) = screen.cursor.as_ref() {
+            *cursor_shape = proto::deserialize_cursor_shape(cursor.shape);
+        }
+        if let Some(new_title) = new_title {
+            *title = new_title;
+            cx.emit(Event::TitleChanged);
+        }
+        cx.emit(Event::Wakeup);
+        cx.notify();
     }
 
     ///Takes events from Alacritty and translates them to behavior on this view
@@ -708,9 +881,11 @@ impl Terminal {
 
                 self.last_content.size = new_size;
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
-
-                term.resize(new_size);
+                // The grid of a remote terminal is sized by its host.
+                if let TerminalConnection::Pty { tx, .. } = &self.connection {
+                    tx.0.send(Msg::Resize(new_size.into())).ok();
+                    term.resize(new_size);
+                }
             }
             InternalEvent::Clear => {
                 // Clear back buffer
@@ -1055,11 +1230,26 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
+        self.write_bytes_to_pty(input.into_bytes());
     }
 
     fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        match &self.connection {
+            TerminalConnection::Pty { tx, .. } => tx.notify(input),
+            TerminalConnection::Remote {
+                read_only,
+                input_tx,
+                ..
+            } => {
+                if !read_only {
+                    input_tx.unbounded_send(input).ok();
+                }
+            }
+            #[cfg(any(test, feature = "test-support"))]
+            TerminalConnection::Fake { input_tx } => {
+                input_tx.unbounded_send(input).ok();
+            }
+        }
     }
 
     pub fn input(&mut self, input: String) {
@@ -1117,6 +1307,13 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        if let TerminalConnection::Remote {
+            mode, cursor_shape, ..
+        } = &self.connection
+        {
+            self.last_content.mode = *mode;
+            self.last_content.cursor.shape = *cursor_shape;
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1218,7 +1415,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.secondary_pressed {
@@ -1297,7 +1494,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             match e.button {
@@ -1356,7 +1553,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1394,7 +1591,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1403,7 +1600,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else {
                 if scroll_lines != 0 {
                     let scroll = AlacScroll::Delta(scroll_lines);
@@ -1459,10 +1656,7 @@ impl Terminal {
     }
 
     pub fn working_directory(&self) -> Option<PathBuf> {
-        self.pty_info
-            .current
-            .as_ref()
-            .map(|process| process.cwd.clone())
+        self.get_cwd()
     }
 
     pub fn title(&self, truncate: bool) -> String {
@@ -1475,38 +1669,48 @@ impl Terminal {
                     task_state.full_label.clone()
                 }
             }
-            None => self
-                .pty_info
-                .current
-                .as_ref()
-                .map(|fpi| {
-                    let process_file = fpi
-                        .cwd
-                        .file_name()
-                        .map(|name| name.to_string_lossy().to_string())
-                        .unwrap_or_default();
-
-                    let argv = fpi.argv.clone();
-                    let process_name = format!(
-                        "{}{}",
-                        fpi.name,
-                        if argv.len() >= 1 {
-                            format!(" {}", (argv[1..]).join(" "))
-                        } else {
-                            "".to_string()
-                        }
-                    );
-                    let (process_file, process_name) = if truncate {
-                        (
-                            truncate_and_trailoff(&process_file, MAX_CHARS),
-                            truncate_and_trailoff(&process_name, MAX_CHARS),
-                        )
+            None => match &self.connection {
+                TerminalConnection::Remote { title, .. } => {
+                    if truncate {
+                        truncate_and_trailoff(title, MAX_CHARS)
                     } else {
-                        (process_file, process_name)
-                    };
-                    format!("{process_file} — {process_name}")
-                })
-                .unwrap_or_else(|| "Terminal".to_string()),
+                        title.clone()
+                    }
+                }
+                TerminalConnection::Pty { info, .. } => info
+                    .current
+                    .as_ref()
+                    .map(|fpi| {
+                        let process_file = fpi
+                            .cwd
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default();
+
+                        let argv = fpi.argv.clone();
+                        let process_name = format!(
+                            "{}{}",
+                            fpi.name,
+                            if argv.len() >= 1 {
+                                format!(" {}", (argv[1..]).join(" "))
+                            } else {
+                                "".to_string()
+                            }
+                        );
+                        let (process_file, process_name) = if truncate {
+                            (
+                                truncate_and_trailoff(&process_file, MAX_CHARS),
+                                truncate_and_trailoff(&process_name, MAX_CHARS),
+                            )
+                        } else {
+                            (process_file, process_name)
+                        };
+                        format!("{process_file} — {process_name}")
+                    })
+                    .unwrap_or_else(|| "Terminal".to_string()),
+                #[cfg(any(test, feature = "test-support"))]
+                TerminalConnection::Fake { .. } => "Terminal".to_string(),
+            },
         }
     }
 
@@ -1624,7 +1828,9 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let TerminalConnection::Pty { tx, .. } = &self.connection {
+            tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
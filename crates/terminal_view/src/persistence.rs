@@ -38,14 +38,17 @@ define_connection! {
 }
 
 impl TerminalDb {
+    // Copies rather than moves the row, so that workspaces restored from a named session
+    // leave the session's own terminals in place.
     query! {
-       pub async fn update_workspace_id(
+       pub async fn copy_to_workspace(
             new_id: WorkspaceId,
             old_id: WorkspaceId,
             item_id: ItemId
         ) -> Result<()> {
-            UPDATE terminals
-            SET workspace_id = ?
+            INSERT OR REPLACE INTO terminals(workspace_id, item_id, working_directory)
+            SELECT ?, item_id, working_directory
+            FROM terminals
             WHERE workspace_id = ? AND item_id = ?
         }
     }
@@ -7,10 +7,11 @@ use futures::future::join_all;
 use gpui::{
     actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EventEmitter,
     ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
-    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    Styled, Subscription, Task, View, ViewContext, VisualContext, WeakModel, WeakView,
+    WindowContext,
 };
 use itertools::Itertools;
-use project::{Fs, ProjectEntryId};
+use project::{Fs, Project, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
@@ -47,7 +48,10 @@ pub fn init(cx: &mut AppContext) {
                 if workspace
                     .panel::<TerminalPanel>(cx)
                     .as_ref()
-                    .is_some_and(|panel| panel.read(cx).enabled)
+                    .is_some_and(|panel| {
+                        let panel = panel.read(cx);
+                        panel.enabled || !panel.has_no_terminals(cx)
+                    })
                 {
                     workspace.toggle_panel_focus::<TerminalPanel>(cx);
                 }
@@ -196,10 +200,16 @@ impl TerminalPanel {
         let subscriptions = vec![
             cx.observe(&pane, |_, _, cx| cx.notify()),
             cx.subscribe(&pane, Self::handle_pane_event),
+            cx.subscribe(workspace.project(), |this, project, event, cx| {
+                if let project::Event::TerminalShared(terminal) = event {
+                    this.add_remote_terminal(terminal.clone(), project.downgrade(), cx);
+                }
+            }),
         ];
         let project = workspace.project().read(cx);
         let enabled = project.is_local() || project.supports_remote_terminal(cx);
-        let this = Self {
+        let remote_terminals = project.remote_terminals().cloned().collect::<Vec<_>>();
+        let mut this = Self {
             pane,
             fs: workspace.app_state().fs.clone(),
             workspace: workspace.weak_handle(),
@@ -211,6 +221,9 @@ impl TerminalPanel {
             _subscriptions: subscriptions,
             enabled,
         };
+        for terminal in remote_terminals {
+            this.add_remote_terminal(terminal, workspace.project().downgrade(), cx);
+        }
         this
     }
 
@@ -539,6 +552,31 @@ impl TerminalPanel {
         })
     }
 
+    /// Adds a terminal that the host of the project shared with us, without focusing it. It
+    /// isn't associated with the workspace, as it can't be restored once the project is closed.
+    fn add_remote_terminal(
+        &mut self,
+        terminal: Model<Terminal>,
+        project: WeakModel<Project>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let workspace = self.workspace.clone();
+        let terminal_view =
+            cx.new_view(|cx| TerminalView::new(terminal.clone(), workspace, None, cx));
+        // Once we close the terminal, the project stops keeping it around, and the host sharing
+        // it again opens a new one.
+        cx.observe_release(&terminal_view, move |_, _, cx| {
+            if let Some(project) = project.upgrade() {
+                project.update(cx, |project, _| project.forget_remote_terminal(&terminal));
+            }
+        })
+        .detach();
+        let terminal_view = Box::new(terminal_view);
+        self.pane.update(cx, |pane, cx| {
+            pane.add_item(terminal_view, false, false, None, cx);
+        });
+    }
+
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
         let mut items_to_serialize = HashSet::default();
         let items = self
@@ -547,7 +585,8 @@ impl TerminalPanel {
             .items()
             .filter_map(|item| {
                 let terminal_view = item.act_as::<TerminalView>(cx)?;
-                if terminal_view.read(cx).terminal().read(cx).task().is_some() {
+                let terminal = terminal_view.read(cx).terminal().read(cx);
+                if terminal.task().is_some() || terminal.is_remote() {
                     None
                 } else {
                     let id = item.item_id().as_u64();
@@ -6,10 +6,10 @@ use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent, EventEmitter,
-    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
-    MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task, View,
-    VisualContext, WeakView,
+    actions, anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model,
+    MouseButton, MouseDownEvent, Pixels, PromptLevel, Render, ScrollWheelEvent, Styled,
+    Subscription, Task, View, VisualContext, WeakView,
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
@@ -69,6 +69,11 @@ pub struct SendKeystroke(String);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
+actions!(
+    terminal,
+    [ShareTerminal, ShareTerminalReadOnly, StopSharingTerminal]
+);
+
 pub fn init(cx: &mut AppContext) {
     terminal_panel::init(cx);
     terminal::init(cx);
@@ -213,9 +218,24 @@ impl TerminalView {
         position: gpui::Point<Pixels>,
         cx: &mut ViewContext<Self>,
     ) {
+        // Only the host of a shared project can share its terminals with guests.
+        let sharing = self.project(cx).and_then(|project| {
+            let project = project.read(cx);
+            (project.is_local() && project.is_shared())
+                .then(|| project.shared_terminal_read_only(&self.terminal))
+        });
         let context_menu = ContextMenu::build(cx, |menu, _| {
             menu.action("Clear", Box::new(Clear))
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
+                .when_some(sharing, |menu, read_only| {
+                    let menu = menu.separator();
+                    if read_only.is_some() {
+                        menu.action("Stop Sharing", Box::new(StopSharingTerminal))
+                    } else {
+                        menu.action("Share with Collaborators", Box::new(ShareTerminal))
+                            .action("Share Read-Only", Box::new(ShareTerminalReadOnly))
+                    }
+                })
         });
 
         cx.focus_view(&context_menu);
@@ -263,6 +283,57 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn project(&self, cx: &AppContext) -> Option<Model<Project>> {
+        Some(self.workspace.upgrade()?.read(cx).project().clone())
+    }
+
+    fn share_terminal(&mut self, _: &ShareTerminal, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project(cx) else {
+            return;
+        };
+        if project.read(cx).shared_terminal_read_only(&self.terminal) == Some(false) {
+            return;
+        }
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            "Share this terminal with write access?",
+            Some("Guests who can edit the project will be able to run commands on your machine."),
+            &["Share", "Cancel"],
+        );
+        cx.spawn(|this, mut cx| async move {
+            if answer.await == Ok(0) {
+                this.update(&mut cx, |this, cx| this.set_sharing(Some(false), cx))?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn share_terminal_read_only(&mut self, _: &ShareTerminalReadOnly, cx: &mut ViewContext<Self>) {
+        self.set_sharing(Some(true), cx);
+    }
+
+    fn stop_sharing_terminal(&mut self, _: &StopSharingTerminal, cx: &mut ViewContext<Self>) {
+        self.set_sharing(None, cx);
+    }
+
+    /// Shares the terminal with the guests of the project, or stops sharing it when
+    /// `read_only` is `None`.
+    fn set_sharing(&mut self, read_only: Option<bool>, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project(cx) else {
+            return;
+        };
+        let terminal = self.terminal.clone();
+        project
+            .update(cx, |project, cx| match read_only {
+                Some(read_only) => project.share_terminal(&terminal, read_only, cx),
+                None => project.unshare_terminal(&terminal, cx),
+            })
+            .log_err();
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+    }
+
     fn clear(&mut self, _: &Clear, cx: &mut ViewContext<Self>) {
         self.scroll_top = px(0.);
         self.terminal.update(cx, |term, _| term.clear());
@@ -902,6 +973,9 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::copy))
             .on_action(cx.listener(TerminalView::paste))
             .on_action(cx.listener(TerminalView::clear))
+            .on_action(cx.listener(TerminalView::share_terminal))
+            .on_action(cx.listener(TerminalView::share_terminal_read_only))
+            .on_action(cx.listener(TerminalView::stop_sharing_terminal))
             .on_action(cx.listener(TerminalView::scroll_line_up))
             .on_action(cx.listener(TerminalView::scroll_line_down))
             .on_action(cx.listener(TerminalView::scroll_page_up))
@@ -982,7 +1056,20 @@ impl Item for TerminalView {
                     }
                 }
             },
-            None => (IconName::Terminal, Color::Muted, None),
+            None => {
+                let is_shared = terminal.is_remote()
+                    || self.project(cx).map_or(false, |project| {
+                        project
+                            .read(cx)
+                            .shared_terminal_read_only(&self.terminal)
+                            .is_some()
+                    });
+                if is_shared {
+                    (IconName::Public, Color::Muted, None)
+                } else {
+                    (IconName::Terminal, Color::Muted, None)
+                }
+            }
         };
 
         h_flex()
@@ -1114,11 +1201,29 @@ impl Item for TerminalView {
         })
     }
 
+    fn serialize_to_workspace(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let terminal = self.terminal().read(cx);
+        let Some(cwd) = terminal.get_cwd().filter(|_| terminal.task().is_none()) else {
+            return Task::ready(Ok(()));
+        };
+
+        let item_id = cx.entity_id().as_u64();
+        cx.background_executor().spawn(async move {
+            TERMINAL_DB
+                .save_working_directory(item_id, workspace_id, cwd)
+                .await
+        })
+    }
+
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
         if self.terminal().read(cx).task().is_none() {
             if let Some((new_id, old_id)) = workspace.database_id().zip(self.workspace_id) {
                 cx.background_executor()
-                    .spawn(TERMINAL_DB.update_workspace_id(new_id, old_id, cx.entity_id().as_u64()))
+                    .spawn(TERMINAL_DB.copy_to_workspace(new_id, old_id, cx.entity_id().as_u64()))
                     .detach();
             }
             self.workspace_id = workspace.database_id();
@@ -1197,6 +1302,8 @@ impl SearchableItem for TerminalView {
                 .unwrap()),
             ),
             SearchQuery::Regex { .. } => regex_search_for_query(&query),
+            // Terminal output has no syntax trees to match structural patterns against.
+            SearchQuery::Structural { .. } => None,
         };
 
         if let Some(s) = searcher {
@@ -0,0 +1,363 @@
+use anyhow::{anyhow, Result};
+use std::fmt;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// How many bytes at the start of a file are inspected when guessing its encoding.
+const DETECTION_LEN: usize = 64 * 1024;
+
+/// The character encoding of a file, along with whether it starts with a byte order mark.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::UTF8
+    }
+}
+
+impl fmt::Debug for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.display_name())
+    }
+}
+
+impl Encoding {
+    pub const UTF8: Self = Self {
+        encoding: encoding_rs::UTF_8,
+        bom: false,
+    };
+
+    /// Returns the encoding with the given [WHATWG label](https://encoding.spec.whatwg.org/#names-and-labels).
+    pub fn from_name(name: &str, bom: bool) -> Option<Self> {
+        let encoding = encoding_rs::Encoding::for_label(name.as_bytes())?;
+        Some(Self {
+            encoding,
+            bom: bom && has_bom(encoding),
+        })
+    }
+
+    /// The encodings that can be chosen when reopening or saving a file.
+    pub fn all() -> impl Iterator<Item = Self> {
+        [
+            encoding_rs::UTF_8,
+            encoding_rs::UTF_16LE,
+            encoding_rs::UTF_16BE,
+            encoding_rs::WINDOWS_1252,
+            encoding_rs::ISO_8859_2,
+            encoding_rs::ISO_8859_15,
+            encoding_rs::WINDOWS_1250,
+            encoding_rs::WINDOWS_1251,
+            encoding_rs::KOI8_R,
+            encoding_rs::WINDOWS_1253,
+            encoding_rs::WINDOWS_1254,
+            encoding_rs::WINDOWS_1255,
+            encoding_rs::WINDOWS_1256,
+            encoding_rs::WINDOWS_874,
+            encoding_rs::WINDOWS_1258,
+            encoding_rs::SHIFT_JIS,
+            encoding_rs::EUC_JP,
+            encoding_rs::ISO_2022_JP,
+            encoding_rs::GBK,
+            encoding_rs::GB18030,
+            encoding_rs::BIG5,
+            encoding_rs::EUC_KR,
+        ]
+        .into_iter()
+        .flat_map(|encoding| {
+            let with_bom = has_bom(encoding).then_some(Self {
+                encoding,
+                bom: true,
+            });
+            [Self {
+                encoding,
+                bom: false,
+            }]
+            .into_iter()
+            .chain(with_bom)
+        })
+    }
+
+    /// The canonical name of the encoding.
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// A name for the encoding that can be shown to users.
+    pub fn display_name(&self) -> String {
+        if self.bom {
+            format!("{} with BOM", self.name())
+        } else {
+            self.name().to_string()
+        }
+    }
+
+    /// Guesses the encoding of a file's contents, using its byte order mark if it has one.
+    pub fn detect(bytes: &[u8]) -> Self {
+        Self::detect_from_sample(bytes, DETECTION_LEN)
+    }
+
+    /// Guesses the encoding of a file's contents and decodes them.
+    ///
+    /// The encoding is guessed from the start of the file. If the rest of the file isn't valid
+    /// in that encoding, it is guessed again from the whole file, and if the contents still
+    /// aren't valid, invalid sequences are replaced with U+FFFD and the result is marked as
+    /// lossy, since saving it would not preserve the original bytes. Fails for binary files.
+    pub fn detect_and_decode(bytes: Vec<u8>) -> Result<DecodedText> {
+        let encoding = Self::detect(&bytes);
+        let bytes = match encoding.try_decode(bytes) {
+            Ok(text) => return Ok(DecodedText::new(text, encoding)),
+            Err(bytes) => bytes,
+        };
+
+        let encoding = if bytes.len() > DETECTION_LEN {
+            Self::detect_from_sample(&bytes, bytes.len())
+        } else {
+            encoding
+        };
+        if encoding.is_binary(&bytes) {
+            return Err(encoding.invalid_content_error());
+        }
+        let bytes = match encoding.try_decode(bytes) {
+            Ok(text) => return Ok(DecodedText::new(text, encoding)),
+            Err(bytes) => bytes,
+        };
+
+        let (text, _) = encoding
+            .encoding
+            .decode_without_bom_handling(encoding.strip_bom(&bytes));
+        Ok(DecodedText {
+            text: text.into_owned(),
+            encoding,
+            lossy: true,
+        })
+    }
+
+    fn detect_from_sample(bytes: &[u8], sample_len: usize) -> Self {
+        if bytes.starts_with(UTF8_BOM) {
+            return Self {
+                encoding: encoding_rs::UTF_8,
+                bom: true,
+            };
+        } else if bytes.starts_with(UTF16LE_BOM) {
+            return Self {
+                encoding: encoding_rs::UTF_16LE,
+                bom: true,
+            };
+        } else if bytes.starts_with(UTF16BE_BOM) {
+            return Self {
+                encoding: encoding_rs::UTF_16BE,
+                bom: true,
+            };
+        }
+
+        let sample = &bytes[..bytes.len().min(sample_len)];
+        if let Some(encoding) = detect_utf16(sample) {
+            return Self {
+                encoding,
+                bom: false,
+            };
+        }
+
+        let is_utf8 = match std::str::from_utf8(sample) {
+            Ok(_) => true,
+            // The sample may end in the middle of a character.
+            Err(error) => error.error_len().is_none(),
+        };
+        if is_utf8 {
+            return Self::UTF8;
+        }
+
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(sample, sample.len() == bytes.len());
+        Self {
+            encoding: detector.guess(None, false),
+            bom: false,
+        }
+    }
+
+    /// Decodes a file's contents, failing if they aren't valid in this encoding.
+    pub fn decode(&self, bytes: Vec<u8>) -> Result<String> {
+        self.try_decode(bytes)
+            .map_err(|_| self.invalid_content_error())
+    }
+
+    /// Decodes a file's contents, giving them back if they aren't valid in this encoding.
+    fn try_decode(&self, bytes: Vec<u8>) -> Result<String, Vec<u8>> {
+        let content = self.strip_bom(&bytes);
+        if self.encoding == encoding_rs::UTF_8 {
+            return if content.len() == bytes.len() {
+                String::from_utf8(bytes).map_err(|error| error.into_bytes())
+            } else {
+                match std::str::from_utf8(content) {
+                    Ok(text) => Ok(text.to_string()),
+                    Err(_) => Err(bytes),
+                }
+            };
+        }
+
+        if self.is_binary(content) {
+            return Err(bytes);
+        }
+        match self
+            .encoding
+            .decode_without_bom_handling_and_without_replacement(content)
+        {
+            Some(text) => Ok(text.into_owned()),
+            None => Err(bytes),
+        }
+    }
+
+    fn strip_bom<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        match bom_for(self.encoding) {
+            Some(bom) if bytes.starts_with(bom) => &bytes[bom.len()..],
+            _ => bytes,
+        }
+    }
+
+    /// Files in encodings other than UTF-16 that contain null bytes are most likely binary.
+    fn is_binary(&self, bytes: &[u8]) -> bool {
+        let is_utf16 =
+            self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE;
+        !is_utf16 && bytes.contains(&0)
+    }
+
+    /// Encodes text in this encoding, failing if it contains characters that the encoding
+    /// can't represent.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if self.bom {
+            bytes.extend_from_slice(bom_for(self.encoding).unwrap_or_default());
+        }
+
+        if self.encoding == encoding_rs::UTF_8 {
+            bytes.extend_from_slice(text.as_bytes());
+        } else if self.encoding == encoding_rs::UTF_16LE {
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        } else {
+            let (encoded, _, had_unmappable_characters) = self.encoding.encode(text);
+            if had_unmappable_characters {
+                return Err(anyhow!(
+                    "the text contains characters that can't be represented in {}",
+                    self.name()
+                ));
+            }
+            bytes.extend_from_slice(&encoded);
+        }
+        Ok(bytes)
+    }
+
+    /// Returns a decoder for reading a file's contents in chunks.
+    pub fn new_decoder(&self) -> Decoder {
+        Decoder {
+            encoding: *self,
+            decoder: self.encoding.new_decoder_with_bom_removal(),
+        }
+    }
+
+    fn invalid_content_error(&self) -> anyhow::Error {
+        anyhow!("the file's contents aren't valid {}", self.name())
+    }
+}
+
+/// The contents of a file decoded by [`Encoding::detect_and_decode`].
+#[derive(Debug)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: Encoding,
+    /// Whether invalid sequences were replaced with U+FFFD, in which case the text can't be
+    /// encoded back into the file's original bytes.
+    pub lossy: bool,
+}
+
+impl DecodedText {
+    fn new(text: String, encoding: Encoding) -> Self {
+        Self {
+            text,
+            encoding,
+            lossy: false,
+        }
+    }
+}
+
+/// Decodes a file's contents incrementally, as they are read.
+pub struct Decoder {
+    encoding: Encoding,
+    decoder: encoding_rs::Decoder,
+}
+
+impl Decoder {
+    /// Decodes the next chunk of a file, which may end in the middle of a character.
+    /// Fails if the contents aren't valid in the decoder's encoding.
+    pub fn decode_chunk(&mut self, mut bytes: &[u8], is_last: bool) -> Result<String> {
+        let mut text = String::new();
+        loop {
+            let capacity = self
+                .decoder
+                .max_utf8_buffer_length_without_replacement(bytes.len())
+                .unwrap_or(bytes.len());
+            text.reserve(capacity);
+            let (result, read) = self
+                .decoder
+                .decode_to_string_without_replacement(bytes, &mut text, is_last);
+            bytes = &bytes[read..];
+            match result {
+                encoding_rs::DecoderResult::InputEmpty => return Ok(text),
+                encoding_rs::DecoderResult::OutputFull => continue,
+                encoding_rs::DecoderResult::Malformed(_, _) => {
+                    return Err(self.encoding.invalid_content_error())
+                }
+            }
+        }
+    }
+}
+
+fn bom_for(encoding: &'static encoding_rs::Encoding) -> Option<&'static [u8]> {
+    if encoding == encoding_rs::UTF_8 {
+        Some(UTF8_BOM)
+    } else if encoding == encoding_rs::UTF_16LE {
+        Some(UTF16LE_BOM)
+    } else if encoding == encoding_rs::UTF_16BE {
+        Some(UTF16BE_BOM)
+    } else {
+        None
+    }
+}
+
+fn has_bom(encoding: &'static encoding_rs::Encoding) -> bool {
+    bom_for(encoding).is_some()
+}
+
+/// Recognizes UTF-16 text without a byte order mark from the null bytes that mostly-ASCII
+/// text contains in every other byte.
+fn detect_utf16(sample: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    if sample.len() < 4 || sample.len() % 2 != 0 {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let (mut even_nulls, mut odd_nulls) = (0, 0);
+    for pair in sample.chunks_exact(2) {
+        even_nulls += (pair[0] == 0) as usize;
+        odd_nulls += (pair[1] == 0) as usize;
+    }
+
+    if odd_nulls * 10 >= pairs * 9 && even_nulls == 0 {
+        Some(encoding_rs::UTF_16LE)
+    } else if even_nulls * 10 >= pairs * 9 && odd_nulls == 0 {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
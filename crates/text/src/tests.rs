@@ -189,6 +189,69 @@ fn test_line_endings() {
     buffer.check_invariants();
 }
 
+#[test]
+fn test_encodings() {
+    let utf8 = Encoding::from_name("utf-8", false).unwrap();
+    let utf8_bom = Encoding::from_name("utf-8", true).unwrap();
+    let utf16le_bom = Encoding::from_name("utf-16le", true).unwrap();
+    let windows_1252 = Encoding::from_name("windows-1252", false).unwrap();
+    assert_eq!(utf8, Encoding::default());
+    assert_eq!(Encoding::from_name("latin1", true), Some(windows_1252));
+    assert_eq!(utf16le_bom.display_name(), "UTF-16LE with BOM");
+
+    assert_eq!(Encoding::detect("héllo".as_bytes()), utf8);
+    assert_eq!(Encoding::detect(b"\xEF\xBB\xBFhello"), utf8_bom);
+    assert_eq!(Encoding::detect(b"\xFF\xFEh\0i\0"), utf16le_bom);
+    assert_eq!(
+        Encoding::detect(b"h\0e\0l\0l\0o\0"),
+        Encoding::from_name("utf-16le", false).unwrap()
+    );
+    assert_eq!(
+        Encoding::detect(b"Caf\xE9 cr\xE8me br\xFBl\xE9e, d\xE9j\xE0 vu"),
+        windows_1252
+    );
+
+    // Decoding strips the byte order mark, and encoding restores it.
+    for (encoding, text) in [
+        (utf8, "héllo"),
+        (utf8_bom, "héllo"),
+        (utf16le_bom, "héllo 🍐"),
+        (Encoding::from_name("utf-16be", false).unwrap(), "héllo 🍐"),
+        (windows_1252, "héllo"),
+        (
+            Encoding::from_name("shift_jis", false).unwrap(),
+            "こんにちは",
+        ),
+    ] {
+        let bytes = encoding.encode(text).unwrap();
+        assert_eq!(Encoding::detect(&bytes).has_bom(), encoding.has_bom());
+        assert_eq!(encoding.decode(bytes).unwrap(), text, "{encoding:?}");
+    }
+    assert_eq!(utf8_bom.encode("hi").unwrap(), b"\xEF\xBB\xBFhi".to_vec());
+
+    // Text that isn't valid in an encoding is rejected rather than silently replaced.
+    assert!(utf8.decode(b"h\xE9llo".to_vec()).is_err());
+    assert!(windows_1252.decode(b"binary\0data".to_vec()).is_err());
+    assert!(windows_1252.encode("🍐").is_err());
+
+    // When the end of a file isn't valid in the encoding detected from its start, the
+    // encoding is detected again from the whole file.
+    let mut bytes = "a".repeat(100_000).into_bytes();
+    bytes.extend_from_slice(b"Caf\xE9 cr\xE8me");
+    let decoded = Encoding::detect_and_decode(bytes).unwrap();
+    assert_eq!(decoded.encoding, windows_1252);
+    assert!(decoded.text.ends_with("Café crème"));
+    assert!(!decoded.lossy);
+
+    // Invalid sequences are replaced as a last resort, and the text is marked as lossy so
+    // that it isn't saved over the file. Binary files are rejected.
+    let decoded = Encoding::detect_and_decode(b"\xFF\xFEh\0\0\xD8".to_vec()).unwrap();
+    assert_eq!(decoded.encoding, utf16le_bom);
+    assert_eq!(decoded.text, "h\u{FFFD}");
+    assert!(decoded.lossy);
+    assert!(Encoding::detect_and_decode(b"\xE9binary\0data".to_vec()).is_err());
+}
+
 #[test]
 fn test_line_len() {
     let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
@@ -604,6 +667,136 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_undo_tree() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+
+    buffer.edit([(3..3, "d")]);
+    buffer.edit([(4..4, "e")]);
+    buffer.undo();
+    // Editing after an undo abandons the undone transaction, starting a new branch.
+    buffer.edit([(4..4, "f")]);
+    assert_eq!(buffer.text(), "abcdf");
+    buffer.redo();
+    assert_eq!(buffer.text(), "abcdf");
+
+    let tree = buffer.undo_tree();
+    assert_eq!(
+        tree.iter()
+            .map(|node| (node.parent_id, node.state))
+            .collect::<Vec<_>>(),
+        [
+            (None, UndoTreeNodeState::Applied),
+            (Some(tree[0].transaction_id), UndoTreeNodeState::Abandoned),
+            (Some(tree[0].transaction_id), UndoTreeNodeState::Applied),
+        ]
+    );
+    assert_eq!(
+        buffer.rope_for_version(&tree[0].version).to_string(),
+        "abcd"
+    );
+    assert_eq!(
+        buffer.rope_for_version(&tree[1].version).to_string(),
+        "abcde"
+    );
+    assert_eq!(
+        buffer.rope_for_version(&tree[2].version).to_string(),
+        "abcdf"
+    );
+
+    buffer.undo();
+    let tree = buffer.undo_tree();
+    assert_eq!(tree[2].state, UndoTreeNodeState::Undone);
+}
+
+#[test]
+fn test_abandoned_transactions_are_capped() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+
+    buffer.edit([(0..0, "a")]);
+    for _ in 0..MAX_ABANDONED_TRANSACTIONS + 10 {
+        buffer.edit([(1..1, "b")]);
+        buffer.undo();
+    }
+    let abandoned_count = buffer
+        .undo_tree()
+        .iter()
+        .filter(|node| node.state == UndoTreeNodeState::Abandoned)
+        .count();
+    assert_eq!(abandoned_count, MAX_ABANDONED_TRANSACTIONS);
+}
+
+#[test]
+fn test_persisted_history() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc\r\ndef".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+    buffer.edit([(0..0, "1")]);
+    buffer.edit([(1..1, "2")]);
+    buffer.undo();
+    assert_eq!(buffer.text(), "1abc\ndef");
+
+    let mut restored = Buffer::from_persisted_history(
+        0,
+        BufferId::new(2).unwrap(),
+        buffer.line_ending(),
+        buffer.persisted_history(),
+    )
+    .unwrap();
+    assert_eq!(restored.text(), "1abc\ndef");
+    assert_eq!(restored.line_ending(), LineEnding::Windows);
+    assert_eq!(restored.version(), buffer.version());
+
+    restored.redo();
+    assert_eq!(restored.text(), "12abc\ndef");
+    restored.undo();
+    restored.undo();
+    assert_eq!(restored.text(), "abc\ndef");
+
+    // New edits are timestamped after the restored operations.
+    restored.edit([(0..0, "3")]);
+    assert_eq!(restored.text(), "3abc\ndef");
+    restored.undo();
+    assert_eq!(restored.text(), "abc\ndef");
+}
+
+#[test]
+fn test_persisted_history_with_last_transactions() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc".into());
+    buffer.set_group_interval(Duration::from_secs(0));
+    buffer.edit([(0..0, "1")]);
+    buffer.edit([(4..4, "2")]);
+    buffer.edit([(1..2, "AB")]);
+    buffer.edit([(0..0, "3")]);
+    buffer.undo();
+    assert_eq!(buffer.text(), "1ABbc2");
+
+    let mut restored = Buffer::from_persisted_history(
+        0,
+        BufferId::new(2).unwrap(),
+        buffer.line_ending(),
+        buffer.persisted_history_with_last_transactions(2),
+    )
+    .unwrap();
+    assert_eq!(restored.text(), "1ABbc2");
+    restored.undo();
+    assert_eq!(restored.text(), "1abc2");
+    restored.undo();
+    assert_eq!(restored.text(), "1abc");
+    restored.undo();
+    assert_eq!(restored.text(), "1abc");
+
+    let restored = Buffer::from_persisted_history(
+        0,
+        BufferId::new(2).unwrap(),
+        buffer.line_ending(),
+        buffer.persisted_history_with_last_transactions(0),
+    )
+    .unwrap();
+    assert_eq!(restored.text(), "1ABbc2");
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -1,4 +1,5 @@
 mod anchor;
+mod encoding;
 pub mod locator;
 #[cfg(any(test, feature = "test-support"))]
 pub mod network;
@@ -14,6 +15,7 @@ pub use anchor::*;
 use anyhow::{anyhow, Context as _, Result};
 pub use clock::ReplicaId;
 use collections::{HashMap, HashSet};
+pub use encoding::{DecodedText, Decoder, Encoding};
 use locator::Locator;
 use operation_queue::OperationQueue;
 pub use patch::Patch;
@@ -48,6 +50,10 @@ lazy_static! {
     static ref LINE_SEPARATORS_REGEX: Regex = Regex::new("\r\n|\r|\u{2028}|\u{2029}").unwrap();
 }
 
+/// The number of abandoned transactions kept in a buffer's history for its undo tree. Older
+/// ones are forgotten, so that the history doesn't grow without bound.
+pub const MAX_ABANDONED_TRANSACTIONS: usize = 1000;
+
 pub type TransactionId = clock::Lamport;
 
 pub struct Buffer {
@@ -118,6 +124,7 @@ pub struct BufferSnapshot {
 #[derive(Clone, Debug)]
 pub struct HistoryEntry {
     transaction: Transaction,
+    parent_id: Option<TransactionId>,
     first_edit_at: Instant,
     last_edit_at: Instant,
     suppress_grouping: bool,
@@ -136,12 +143,52 @@ impl HistoryEntry {
     }
 }
 
+/// A transaction in the undo tree of a buffer.
+#[derive(Clone, Debug)]
+pub struct UndoTreeNode {
+    pub transaction_id: TransactionId,
+    /// The transaction that was last applied when this transaction was started.
+    pub parent_id: Option<TransactionId>,
+    pub state: UndoTreeNodeState,
+    /// The version of the buffer right after the transaction.
+    pub version: clock::Global,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UndoTreeNodeState {
+    /// The transaction is applied and can be undone.
+    Applied,
+    /// The transaction was undone and can be redone.
+    Undone,
+    /// The transaction was undone before other edits were made, so it can no longer be redone.
+    Abandoned,
+}
+
+/// The history of a buffer, in a form that allows rebuilding the buffer along with its
+/// undo stack in a later session.
+#[derive(Clone, Debug)]
+pub struct PersistedHistory {
+    pub base_text: Rope,
+    pub operations: Vec<Operation>,
+    pub undo_stack: Vec<PersistedHistoryEntry>,
+    pub redo_stack: Vec<PersistedHistoryEntry>,
+    pub abandoned: Vec<PersistedHistoryEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PersistedHistoryEntry {
+    pub transaction: Transaction,
+    pub parent_id: Option<TransactionId>,
+}
+
 struct History {
     base_text: Rope,
     operations: TreeMap<clock::Lamport, Operation>,
     insertion_slices: HashMap<clock::Lamport, Vec<InsertionSlice>>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    /// Transactions that were on the redo stack when a new transaction was started.
+    abandoned: Vec<HistoryEntry>,
     transaction_depth: usize,
     group_interval: Duration,
 }
@@ -160,6 +207,7 @@ impl History {
             insertion_slices: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            abandoned: Vec::new(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -182,12 +230,14 @@ impl History {
         self.transaction_depth += 1;
         if self.transaction_depth == 1 {
             let id = clock.tick();
+            let parent_id = self.undo_stack.last().map(|entry| entry.transaction.id);
             self.undo_stack.push(HistoryEntry {
                 transaction: Transaction {
                     id,
                     start,
                     edit_ids: Default::default(),
                 },
+                parent_id,
                 first_edit_at: now,
                 last_edit_at: now,
                 suppress_grouping: false,
@@ -213,7 +263,7 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                self.abandon_redo_stack();
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -283,13 +333,24 @@ impl History {
 
     fn push_transaction(&mut self, transaction: Transaction, now: Instant) {
         assert_eq!(self.transaction_depth, 0);
+        let parent_id = self.undo_stack.last().map(|entry| entry.transaction.id);
         self.undo_stack.push(HistoryEntry {
             transaction,
+            parent_id,
             first_edit_at: now,
             last_edit_at: now,
             suppress_grouping: false,
         });
-        self.redo_stack.clear();
+        self.abandon_redo_stack();
+    }
+
+    fn abandon_redo_stack(&mut self) {
+        self.abandoned.extend(self.redo_stack.drain(..));
+        let excess = self
+            .abandoned
+            .len()
+            .saturating_sub(MAX_ABANDONED_TRANSACTIONS);
+        self.abandoned.drain(..excess);
     }
 
     fn push_undo(&mut self, op_id: clock::Lamport) {
@@ -1312,6 +1373,142 @@ impl Buffer {
         &self.history.operations
     }
 
+    /// Returns every transaction in the buffer's history, including the ones that can no
+    /// longer be redone, ordered by when they were started.
+    pub fn undo_tree(&self) -> Vec<UndoTreeNode> {
+        let node = |entry: &HistoryEntry, state| {
+            let mut version = entry.transaction.start.clone();
+            for edit_id in &entry.transaction.edit_ids {
+                version.observe(*edit_id);
+            }
+            UndoTreeNode {
+                transaction_id: entry.transaction.id,
+                parent_id: entry.parent_id,
+                state,
+                version,
+            }
+        };
+
+        let mut nodes = self
+            .history
+            .undo_stack
+            .iter()
+            .map(|entry| node(entry, UndoTreeNodeState::Applied))
+            .chain(
+                self.history
+                    .redo_stack
+                    .iter()
+                    .map(|entry| node(entry, UndoTreeNodeState::Undone)),
+            )
+            .chain(
+                self.history
+                    .abandoned
+                    .iter()
+                    .map(|entry| node(entry, UndoTreeNodeState::Abandoned)),
+            )
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.transaction_id);
+        nodes
+    }
+
+    pub fn persisted_history(&self) -> PersistedHistory {
+        let entries = |entries: &[HistoryEntry]| {
+            entries
+                .iter()
+                .map(|entry| PersistedHistoryEntry {
+                    transaction: entry.transaction.clone(),
+                    parent_id: entry.parent_id,
+                })
+                .collect()
+        };
+
+        PersistedHistory {
+            base_text: self.history.base_text.clone(),
+            operations: self.history.operations.values().cloned().collect(),
+            undo_stack: entries(&self.history.undo_stack),
+            redo_stack: entries(&self.history.redo_stack),
+            abandoned: entries(&self.history.abandoned),
+        }
+    }
+
+    /// Returns a persisted history that only contains the last `count` transactions of the
+    /// undo stack, with the text from before them as its base text. Each kept transaction is
+    /// replayed as a single edit, and transactions that were undone or abandoned are dropped.
+    pub fn persisted_history_with_last_transactions(&self, count: usize) -> PersistedHistory {
+        let undo_stack = &self.history.undo_stack;
+        let kept_entries = &undo_stack[undo_stack.len().saturating_sub(count)..];
+        let base_text = match kept_entries.first() {
+            Some(entry) => self.rope_for_version(&entry.transaction.start),
+            None => self.as_rope().clone(),
+        };
+
+        let mut buffer = Self::new_normalized(0, self.remote_id(), self.line_ending(), base_text);
+        for entry in kept_entries {
+            let mut version = entry.transaction.start.clone();
+            for edit_id in &entry.transaction.edit_ids {
+                version.observe(*edit_id);
+            }
+            let old_text = buffer.text();
+            let new_text = self.rope_for_version(&version).to_string();
+            let prefix_len = old_text
+                .chars()
+                .zip(new_text.chars())
+                .take_while(|(old, new)| old == new)
+                .map(|(old, _)| old.len_utf8())
+                .sum::<usize>();
+            let suffix_len = old_text[prefix_len..]
+                .chars()
+                .rev()
+                .zip(new_text[prefix_len..].chars().rev())
+                .take_while(|(old, new)| old == new)
+                .map(|(old, _)| old.len_utf8())
+                .sum::<usize>();
+
+            buffer.start_transaction();
+            buffer.edit([(
+                prefix_len..old_text.len() - suffix_len,
+                &new_text[prefix_len..new_text.len() - suffix_len],
+            )]);
+            buffer.end_transaction();
+            buffer.finalize_last_transaction();
+        }
+        buffer.persisted_history()
+    }
+
+    /// Rebuilds a buffer by replaying the operations of a history that was persisted
+    /// with [`Buffer::persisted_history`].
+    pub fn from_persisted_history(
+        replica_id: ReplicaId,
+        remote_id: BufferId,
+        line_ending: LineEnding,
+        history: PersistedHistory,
+    ) -> Result<Self> {
+        let mut buffer =
+            Self::new_normalized(replica_id, remote_id, line_ending, history.base_text);
+        buffer.apply_ops(history.operations)?;
+        if buffer.deferred_ops_len() > 0 {
+            return Err(anyhow!("persisted history is missing operations"));
+        }
+
+        let now = Instant::now();
+        let entries = |entries: Vec<PersistedHistoryEntry>| {
+            entries
+                .into_iter()
+                .map(|entry| HistoryEntry {
+                    transaction: entry.transaction,
+                    parent_id: entry.parent_id,
+                    first_edit_at: now,
+                    last_edit_at: now,
+                    suppress_grouping: true,
+                })
+                .collect()
+        };
+        buffer.history.undo_stack = entries(history.undo_stack);
+        buffer.history.redo_stack = entries(history.redo_stack);
+        buffer.history.abandoned = entries(history.abandoned);
+        Ok(buffer)
+    }
+
     pub fn undo(&mut self) -> Option<(TransactionId, Operation)> {
         if let Some(entry) = self.history.pop_undo() {
             let transaction = entry.transaction.clone();
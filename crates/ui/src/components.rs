@@ -2,6 +2,7 @@ mod avatar;
 mod button;
 mod checkbox;
 mod context_menu;
+mod data_table;
 mod disclosure;
 mod divider;
 mod dropdown_menu;
@@ -30,6 +31,7 @@ pub use avatar::*;
 pub use button::*;
 pub use checkbox::*;
 pub use context_menu::*;
+pub use data_table::*;
 pub use disclosure::*;
 pub use divider::*;
 use dropdown_menu::*;
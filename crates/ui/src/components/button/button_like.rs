@@ -1,4 +1,7 @@
-use gpui::{relative, CursorStyle, DefiniteLength, MouseButton};
+use gpui::{
+    relative, AccessibilityProperties, AccessibilityRole, AccessibilityStates, CursorStyle,
+    DefiniteLength, MouseButton,
+};
 use gpui::{transparent_black, AnyElement, AnyView, ClickEvent, Hsla, Rems};
 use smallvec::SmallVec;
 
@@ -475,6 +478,15 @@ impl RenderOnce for ButtonLike {
         self.base
             .h_flex()
             .id(self.id.clone())
+            .accessibility(
+                AccessibilityProperties::new(AccessibilityRole::Button).states(
+                    AccessibilityStates {
+                        disabled: self.disabled,
+                        selected: self.selected,
+                        ..Default::default()
+                    },
+                ),
+            )
             .group("")
             .flex_none()
             .h(self.height.unwrap_or(self.size.rems().into()))
@@ -1,4 +1,7 @@
-use gpui::{div, prelude::*, ElementId, IntoElement, Styled, WindowContext};
+use gpui::{
+    div, prelude::*, AccessibilityProperties, AccessibilityRole, AccessibilityStates, ElementId,
+    IntoElement, Styled, WindowContext,
+};
 
 use crate::prelude::*;
 use crate::{Color, Icon, IconName, Selection};
@@ -81,6 +84,15 @@ impl RenderOnce for Checkbox {
 
         h_flex()
             .id(self.id)
+            .accessibility(
+                AccessibilityProperties::new(AccessibilityRole::CheckBox).states(
+                    AccessibilityStates {
+                        disabled: self.disabled,
+                        checked: Some(selected),
+                        ..Default::default()
+                    },
+                ),
+            )
             .justify_center()
             .items_center()
             .size(crate::styles::custom_spacing(cx, 20.))
@@ -0,0 +1,888 @@
+use std::{cmp::Ordering, ops::Range};
+
+use gpui::{
+    transparent_black, uniform_list, AnyElement, AppContext, ClipboardItem, DragMoveEvent,
+    EntityId, FocusHandle, FocusableView, MouseButton, MouseDownEvent, ScrollHandle, Task,
+    UniformListScrollHandle,
+};
+
+use crate::prelude::*;
+
+use data_table_actions::{
+    MoveDown, MoveLeft, MoveRight, MoveToFirstRow, MoveToLastRow, MoveUp, PageDown, PageUp,
+    SelectAll, SelectDown, SelectLeft, SelectRight, SelectUp,
+};
+
+/// The actions of a [`DataTable`], kept in their own module so that `Copy` doesn't shadow the
+/// trait of the same name for crates that glob import `ui`.
+pub mod data_table_actions {
+    use gpui::actions;
+
+    actions!(
+        data_table,
+        [
+            MoveLeft,
+            MoveRight,
+            MoveUp,
+            MoveDown,
+            SelectLeft,
+            SelectRight,
+            SelectUp,
+            SelectDown,
+            MoveToFirstRow,
+            MoveToLastRow,
+            PageUp,
+            PageDown,
+            SelectAll,
+            Copy,
+        ]
+    );
+}
+
+const MIN_COLUMN_WIDTH: Pixels = px(32.);
+const ROWS_PER_PAGE: usize = 20;
+
+/// How the cells of a column are aligned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    #[default]
+    Start,
+    /// Used for numbers, so that their digits line up.
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A column of a [`DataTable`].
+#[derive(Clone, Debug)]
+pub struct TableColumn {
+    pub title: SharedString,
+    pub width: Pixels,
+    pub alignment: ColumnAlignment,
+    pub sortable: bool,
+}
+
+impl TableColumn {
+    pub fn new(title: impl Into<SharedString>) -> Self {
+        Self {
+            title: title.into(),
+            width: px(120.),
+            alignment: ColumnAlignment::Start,
+            sortable: true,
+        }
+    }
+
+    pub fn width(mut self, width: Pixels) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: ColumnAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+}
+
+/// A cell of a [`DataTable`], by the row it's displayed in, after sorting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TableCell {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// Provides the columns and rows of a [`DataTable`]. Rows are identified by their index in the
+/// delegate, regardless of how the table is sorted.
+pub trait TableDelegate: 'static {
+    fn columns(&self) -> Vec<TableColumn>;
+
+    fn row_count(&self) -> usize;
+
+    /// The text of a cell, used for sorting and copying, and for rendering it by default.
+    fn cell_text(&self, row: usize, column: usize) -> SharedString;
+
+    fn render_cell(&self, row: usize, column: usize, _cx: &mut WindowContext) -> AnyElement {
+        Label::new(self.cell_text(row, column))
+            .single_line()
+            .into_any_element()
+    }
+
+    /// The value a cell is sorted by. It's computed once for every row when the table is
+    /// sorted, and the rows are then sorted in the background.
+    fn sort_key(&self, row: usize, column: usize) -> SortKey {
+        SortKey::new(&self.cell_text(row, column))
+    }
+}
+
+/// The value a cell is sorted by. Numbers sort before text.
+#[derive(Clone, Debug)]
+pub enum SortKey {
+    Number(f64),
+    /// Text is compared case-insensitively first.
+    Text {
+        lowercase: String,
+        text: String,
+    },
+}
+
+impl SortKey {
+    /// Sorts the text numerically if it's a number, and as case-insensitive text otherwise.
+    pub fn new(text: &str) -> Self {
+        match text.trim().parse::<f64>() {
+            Ok(number) => Self::Number(number),
+            Err(_) => Self::Text {
+                lowercase: text.to_lowercase(),
+                text: text.to_string(),
+            },
+        }
+    }
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Number(_), Self::Text { .. }) => Ordering::Less,
+            (Self::Text { .. }, Self::Number(_)) => Ordering::Greater,
+            (
+                Self::Text { lowercase, text },
+                Self::Text {
+                    lowercase: other_lowercase,
+                    text: other_text,
+                },
+            ) => lowercase
+                .cmp(other_lowercase)
+                .then_with(|| text.cmp(other_text)),
+        }
+    }
+}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortKey {}
+
+/// Compares cells numerically when both are numbers, and as case-insensitive text otherwise.
+pub fn compare_cells(a: &str, b: &str) -> Ordering {
+    SortKey::new(a).cmp(&SortKey::new(b))
+}
+
+/// Formats rows of cells as tab separated values, the format spreadsheets paste.
+fn format_tsv(rows: impl IntoIterator<Item = Vec<SharedString>>) -> String {
+    let mut text = String::new();
+    for (ix, row) in rows.into_iter().enumerate() {
+        if ix > 0 {
+            text.push('\n');
+        }
+        for (ix, cell) in row.iter().enumerate() {
+            if ix > 0 {
+                text.push('\t');
+            }
+            text.extend(cell.chars().map(|c| match c {
+                '\t' | '\n' | '\r' => ' ',
+                c => c,
+            }));
+        }
+    }
+    text
+}
+
+/// The drag state of a column's resize handle.
+#[derive(Clone, Copy, Render)]
+struct DraggedColumn {
+    table: EntityId,
+    column: usize,
+}
+
+/// A table whose header stays in place as its rows scroll, and which only renders the rows that
+/// are visible, so that it can show hundreds of thousands of them.
+///
+/// Columns can be resized by dragging the edges of their headers and sorted by clicking them.
+/// Cells can be selected with the mouse or keyboard and copied as tab separated values.
+pub struct DataTable<D: TableDelegate> {
+    delegate: D,
+    columns: Vec<TableColumn>,
+    sort: Option<(usize, SortDirection)>,
+    /// The delegate rows in the order they are displayed, or `None` if the table isn't sorted.
+    /// It's stale while the rows are being sorted.
+    row_order: Option<Vec<usize>>,
+    sort_task: Task<()>,
+    cursor: TableCell,
+    selection_tail: Option<TableCell>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    horizontal_scroll_handle: ScrollHandle,
+}
+
+impl<D: TableDelegate> FocusableView for DataTable<D> {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<D: TableDelegate> DataTable<D> {
+    pub fn new(delegate: D, cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            columns: delegate.columns(),
+            delegate,
+            sort: None,
+            row_order: None,
+            sort_task: Task::ready(()),
+            cursor: TableCell::default(),
+            selection_tail: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            horizontal_scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    pub fn delegate(&self) -> &D {
+        &self.delegate
+    }
+
+    /// Updates the delegate. Call [`Self::refresh`] afterwards if its rows or columns changed.
+    pub fn delegate_mut(&mut self) -> &mut D {
+        &mut self.delegate
+    }
+
+    /// Reloads the rows and columns from the delegate. Column widths are kept if the number of
+    /// columns didn't change.
+    pub fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let columns = self.delegate.columns();
+        if columns.len() == self.columns.len() {
+            for (column, old_column) in self.columns.iter_mut().zip(columns) {
+                *column = TableColumn {
+                    width: column.width,
+                    ..old_column
+                };
+            }
+        } else {
+            self.columns = columns;
+            self.sort = None;
+        }
+        self.sort_rows(cx);
+        self.move_cursor(self.cursor, false, cx);
+    }
+
+    pub fn columns(&self) -> &[TableColumn] {
+        &self.columns
+    }
+
+    pub fn sort(&self) -> Option<(usize, SortDirection)> {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: Option<(usize, SortDirection)>, cx: &mut ViewContext<Self>) {
+        self.sort = sort.filter(|(column, _)| *column < self.columns.len());
+        self.sort_rows(cx);
+        cx.notify();
+    }
+
+    /// The index in the delegate of the row displayed at the given position. Rows are displayed
+    /// in the delegate's order until they're sorted, if the delegate's rows changed since.
+    pub fn delegate_row(&self, row: usize) -> usize {
+        self.row_order
+            .as_ref()
+            .filter(|order| order.len() == self.delegate.row_count())
+            .and_then(|order| order.get(row).copied())
+            .unwrap_or(row)
+    }
+
+    pub fn cursor(&self) -> TableCell {
+        self.cursor
+    }
+
+    /// The displayed rows and the columns of the selected cells, which always include the
+    /// cell under the cursor.
+    pub fn selection(&self) -> (Range<usize>, Range<usize>) {
+        let tail = self.selection_tail.unwrap_or(self.cursor);
+        (
+            tail.row.min(self.cursor.row)..tail.row.max(self.cursor.row) + 1,
+            tail.column.min(self.cursor.column)..tail.column.max(self.cursor.column) + 1,
+        )
+    }
+
+    /// Moves the cursor to the given cell, scrolling it into view, and extends the selection to
+    /// it if `select` is true.
+    pub fn move_cursor(&mut self, cell: TableCell, select: bool, cx: &mut ViewContext<Self>) {
+        if select {
+            self.selection_tail.get_or_insert(self.cursor);
+        } else {
+            self.selection_tail = None;
+        }
+        self.cursor = TableCell {
+            row: cell.row.min(self.delegate.row_count().saturating_sub(1)),
+            column: cell.column.min(self.columns.len().saturating_sub(1)),
+        };
+        self.scroll_handle.scroll_to_item(self.cursor.row);
+        self.scroll_to_column(self.cursor.column);
+        cx.notify();
+    }
+
+    /// Sorts the rows on the background executor. They keep their current order until then.
+    fn sort_rows(&mut self, cx: &mut ViewContext<Self>) {
+        let Some((column, direction)) = self.sort else {
+            self.row_order = None;
+            self.sort_task = Task::ready(());
+            return;
+        };
+
+        let keys = (0..self.delegate.row_count())
+            .map(|row| self.delegate.sort_key(row, column))
+            .collect::<Vec<_>>();
+        let order = cx.background_executor().spawn(async move {
+            let mut order = (0..keys.len()).collect::<Vec<_>>();
+            order.sort_by(|a, b| {
+                let ordering = keys[*a].cmp(&keys[*b]);
+                match direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+            order
+        });
+        self.sort_task = cx.spawn(|this, mut cx| async move {
+            let order = order.await;
+            this.update(&mut cx, |this, cx| {
+                this.row_order = Some(order);
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Cycles the given column between ascending, descending and unsorted.
+    fn toggle_sort(&mut self, column: usize, cx: &mut ViewContext<Self>) {
+        let sort = match self.sort {
+            Some((sorted_column, SortDirection::Ascending)) if sorted_column == column => {
+                Some((column, SortDirection::Descending))
+            }
+            Some((sorted_column, SortDirection::Descending)) if sorted_column == column => None,
+            _ => Some((column, SortDirection::Ascending)),
+        };
+        self.set_sort(sort, cx);
+    }
+
+    fn column_left(&self, column: usize) -> Pixels {
+        self.columns[..column]
+            .iter()
+            .fold(Pixels::ZERO, |left, column| left + column.width)
+    }
+
+    fn scroll_to_column(&self, column: usize) {
+        let Some(width) = self.columns.get(column).map(|column| column.width) else {
+            return;
+        };
+        let viewport_width = self.horizontal_scroll_handle.bounds().size.width;
+        let mut offset = self.horizontal_scroll_handle.offset();
+        let left = self.column_left(column);
+        if left < -offset.x {
+            offset.x = -left;
+        } else if left + width > viewport_width - offset.x {
+            offset.x = viewport_width - left - width;
+        }
+        self.horizontal_scroll_handle.set_offset(offset);
+    }
+
+    fn resize_column(&mut self, event: &DragMoveEvent<DraggedColumn>, cx: &mut ViewContext<Self>) {
+        let DraggedColumn { table, column } = *event.drag(cx);
+        if table != cx.entity_id() {
+            return;
+        }
+        let left = event.bounds.left()
+            + self.horizontal_scroll_handle.offset().x
+            + self.column_left(column);
+        self.columns[column].width = (event.event.position.x - left).max(MIN_COLUMN_WIDTH);
+        cx.notify();
+    }
+
+    fn move_by(&mut self, rows: isize, columns: isize, select: bool, cx: &mut ViewContext<Self>) {
+        let cell = TableCell {
+            row: self.cursor.row.saturating_add_signed(rows),
+            column: self.cursor.column.saturating_add_signed(columns),
+        };
+        self.move_cursor(cell, select, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, cx: &mut ViewContext<Self>) {
+        self.move_by(0, -1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, cx: &mut ViewContext<Self>) {
+        self.move_by(0, 1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, cx: &mut ViewContext<Self>) {
+        self.move_by(-1, 0, false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, cx: &mut ViewContext<Self>) {
+        self.move_by(1, 0, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, cx: &mut ViewContext<Self>) {
+        self.move_by(0, -1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, cx: &mut ViewContext<Self>) {
+        self.move_by(0, 1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, cx: &mut ViewContext<Self>) {
+        self.move_by(-1, 0, true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, cx: &mut ViewContext<Self>) {
+        self.move_by(1, 0, true, cx);
+    }
+
+    fn move_to_first_row(&mut self, _: &MoveToFirstRow, cx: &mut ViewContext<Self>) {
+        let column = self.cursor.column;
+        self.move_cursor(TableCell { row: 0, column }, false, cx);
+    }
+
+    fn move_to_last_row(&mut self, _: &MoveToLastRow, cx: &mut ViewContext<Self>) {
+        let column = self.cursor.column;
+        self.move_cursor(
+            TableCell {
+                row: usize::MAX,
+                column,
+            },
+            false,
+            cx,
+        );
+    }
+
+    fn page_up(&mut self, _: &PageUp, cx: &mut ViewContext<Self>) {
+        self.move_by(-(ROWS_PER_PAGE as isize), 0, false, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, cx: &mut ViewContext<Self>) {
+        self.move_by(ROWS_PER_PAGE as isize, 0, false, cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, cx: &mut ViewContext<Self>) {
+        self.move_cursor(TableCell::default(), false, cx);
+        self.move_cursor(
+            TableCell {
+                row: usize::MAX,
+                column: usize::MAX,
+            },
+            true,
+            cx,
+        );
+    }
+
+    fn copy(&mut self, _: &data_table_actions::Copy, cx: &mut ViewContext<Self>) {
+        if self.delegate.row_count() == 0 || self.columns.is_empty() {
+            return;
+        }
+        let (rows, columns) = self.selection();
+        let text = format_tsv(rows.map(|row| {
+            let row = self.delegate_row(row);
+            columns
+                .clone()
+                .map(|column| self.delegate.cell_text(row, column))
+                .collect()
+        }));
+        cx.write_to_clipboard(ClipboardItem::new(text));
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let table = cx.entity_id();
+        h_flex()
+            .flex_none()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().title_bar_background)
+            .children(self.columns.iter().enumerate().map(|(ix, column)| {
+                let sort_icon = match self.sort {
+                    Some((sorted_column, SortDirection::Ascending)) if sorted_column == ix => {
+                        Some(IconName::ArrowUp)
+                    }
+                    Some((sorted_column, SortDirection::Descending)) if sorted_column == ix => {
+                        Some(IconName::ArrowDown)
+                    }
+                    _ => None,
+                };
+                h_flex()
+                    .id(("data-table-column", ix))
+                    .relative()
+                    .flex_none()
+                    .w(column.width)
+                    .px_2()
+                    .gap_1()
+                    .overflow_hidden()
+                    .when(column.alignment == ColumnAlignment::End, |this| {
+                        this.justify_end()
+                    })
+                    .child(Label::new(column.title.clone()).single_line())
+                    .children(
+                        sort_icon
+                            .map(|icon| Icon::new(icon).size(IconSize::XSmall).color(Color::Muted)),
+                    )
+                    .when(column.sortable, |this| {
+                        this.cursor_pointer()
+                            .on_click(cx.listener(move |this, _, cx| this.toggle_sort(ix, cx)))
+                    })
+                    .child(
+                        div()
+                            .id(("data-table-resize-handle", ix))
+                            .absolute()
+                            .top_0()
+                            .bottom_0()
+                            .right_0()
+                            .w(px(4.))
+                            .cursor_col_resize()
+                            .on_drag(DraggedColumn { table, column: ix }, |dragged, cx| {
+                                cx.stop_propagation();
+                                cx.new_view(|_| *dragged)
+                            })
+                            .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation()),
+                    )
+            }))
+    }
+
+    fn render_row(&self, row: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let delegate_row = self.delegate_row(row);
+        let (selected_rows, selected_columns) = self.selection();
+        let is_focused = self.focus_handle.is_focused(cx);
+        let colors = cx.theme().colors();
+        let selected_background = colors.element_selected;
+        let cursor_border = colors.border_focused;
+
+        h_flex().children(self.columns.iter().enumerate().map(|(column_ix, column)| {
+            let cell = TableCell {
+                row,
+                column: column_ix,
+            };
+            let is_selected = selected_rows.contains(&row) && selected_columns.contains(&column_ix);
+            let is_cursor = is_focused && cell == self.cursor;
+            h_flex()
+                .flex_none()
+                .w(column.width)
+                .px_2()
+                .overflow_hidden()
+                .border_1()
+                .border_color(if is_cursor {
+                    cursor_border
+                } else {
+                    transparent_black()
+                })
+                .when(column.alignment == ColumnAlignment::End, |this| {
+                    this.justify_end()
+                })
+                .when(is_selected, |this| this.bg(selected_background))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, event: &MouseDownEvent, cx| {
+                        cx.focus(&this.focus_handle);
+                        this.move_cursor(cell, event.modifiers.shift, cx);
+                    }),
+                )
+                .child(self.delegate.render_cell(delegate_row, column_ix, cx))
+        }))
+    }
+}
+
+impl<D: TableDelegate> Render for DataTable<D> {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let row_count = self.delegate.row_count();
+        let table_width = self.column_left(self.columns.len());
+
+        div()
+            .key_context("DataTable")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::move_to_first_row))
+            .on_action(cx.listener(Self::move_to_last_row))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::copy))
+            .on_drag_move(cx.listener(Self::resize_column))
+            .size_full()
+            .child(
+                div()
+                    .id("data-table")
+                    .size_full()
+                    .overflow_x_scroll()
+                    .track_scroll(&self.horizontal_scroll_handle)
+                    .child(
+                        v_flex()
+                            .flex_none()
+                            .w(table_width)
+                            .h_full()
+                            .child(self.render_header(cx))
+                            .child(
+                                uniform_list(
+                                    cx.view().clone(),
+                                    "data-table-rows",
+                                    row_count,
+                                    |this, range, cx| {
+                                        range.map(|row| this.render_row(row, cx)).collect()
+                                    },
+                                )
+                                .flex_grow()
+                                .track_scroll(self.scroll_handle.clone()),
+                            ),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, View, VisualTestContext};
+    use settings::SettingsStore;
+    use std::{cell::Cell, rc::Rc};
+
+    struct TestDelegate {
+        rows: Vec<Vec<SharedString>>,
+        rendered_cells: Rc<Cell<usize>>,
+    }
+
+    impl TestDelegate {
+        fn new(rows: &[&[&'static str]]) -> Self {
+            Self {
+                rows: rows
+                    .iter()
+                    .map(|row| row.iter().map(|cell| SharedString::from(*cell)).collect())
+                    .collect(),
+                rendered_cells: Rc::default(),
+            }
+        }
+    }
+
+    impl TableDelegate for TestDelegate {
+        fn columns(&self) -> Vec<TableColumn> {
+            vec![TableColumn::new("Name"), TableColumn::new("Count")]
+        }
+
+        fn row_count(&self) -> usize {
+            self.rows.len()
+        }
+
+        fn cell_text(&self, row: usize, column: usize) -> SharedString {
+            self.rows[row][column].clone()
+        }
+
+        fn render_cell(&self, row: usize, column: usize, _cx: &mut WindowContext) -> AnyElement {
+            self.rendered_cells.set(self.rendered_cells.get() + 1);
+            Label::new(self.cell_text(row, column)).into_any_element()
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    fn build_table(
+        delegate: TestDelegate,
+        cx: &mut TestAppContext,
+    ) -> (View<DataTable<TestDelegate>>, &mut VisualTestContext) {
+        init_test(cx);
+        let (table, cx) = cx.add_window_view(|cx| DataTable::new(delegate, cx));
+        table.update(cx, |table, cx| cx.focus(&table.focus_handle));
+        cx.run_until_parked();
+        (table, cx)
+    }
+
+    fn displayed_rows(
+        table: &View<DataTable<TestDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<usize> {
+        table.update(cx, |table, _| {
+            (0..table.delegate.row_count())
+                .map(|row| table.delegate_row(row))
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_sorting(cx: &mut TestAppContext) {
+        let delegate = TestDelegate::new(&[&["b", "2"], &["a", "10"], &["C", "1"]]);
+        let (table, cx) = build_table(delegate, cx);
+
+        table.update(cx, |table, cx| table.toggle_sort(1, cx));
+        // Rows keep their order until they're sorted in the background.
+        assert_eq!(displayed_rows(&table, cx), [0, 1, 2]);
+        cx.run_until_parked();
+        assert_eq!(displayed_rows(&table, cx), [2, 0, 1]);
+
+        table.update(cx, |table, cx| table.toggle_sort(1, cx));
+        cx.run_until_parked();
+        assert_eq!(displayed_rows(&table, cx), [1, 0, 2]);
+
+        table.update(cx, |table, cx| table.toggle_sort(1, cx));
+        assert_eq!(displayed_rows(&table, cx), [0, 1, 2]);
+
+        table.update(cx, |table, cx| table.toggle_sort(0, cx));
+        cx.run_until_parked();
+        assert_eq!(displayed_rows(&table, cx), [1, 0, 2]);
+        table.update(cx, |table, _| {
+            assert_eq!(table.sort(), Some((0, SortDirection::Ascending)))
+        });
+
+        // Removing rows while they're sorted displays them unsorted until they're sorted again,
+        // rather than indexing past the delegate's rows.
+        table.update(cx, |table, cx| {
+            table.delegate_mut().rows.remove(1);
+            table.refresh(cx);
+        });
+        assert_eq!(displayed_rows(&table, cx), [0, 1]);
+        cx.run_until_parked();
+        assert_eq!(displayed_rows(&table, cx), [0, 1]);
+        table.update(cx, |table, cx| {
+            table.delegate_mut().rows.push(vec!["a".into(), "3".into()]);
+            table.refresh(cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(displayed_rows(&table, cx), [2, 0, 1]);
+    }
+
+    #[gpui::test]
+    async fn test_keyboard_navigation(cx: &mut TestAppContext) {
+        let rows = (0..50).map(|_| ["a", "1"].as_slice()).collect::<Vec<_>>();
+        let (table, cx) = build_table(TestDelegate::new(&rows), cx);
+
+        cx.dispatch_action(MoveDown);
+        cx.dispatch_action(MoveRight);
+        cx.dispatch_action(MoveRight);
+        table.update(cx, |table, _| {
+            assert_eq!(table.cursor(), TableCell { row: 1, column: 1 });
+            assert_eq!(table.selection(), (1..2, 1..2));
+        });
+
+        cx.dispatch_action(SelectLeft);
+        cx.dispatch_action(SelectDown);
+        table.update(cx, |table, _| {
+            assert_eq!(table.cursor(), TableCell { row: 2, column: 0 });
+            assert_eq!(table.selection(), (1..3, 0..2));
+        });
+
+        cx.dispatch_action(PageDown);
+        table.update(cx, |table, _| {
+            assert_eq!(table.cursor(), TableCell { row: 22, column: 0 });
+            assert_eq!(table.selection(), (22..23, 0..1));
+        });
+
+        cx.dispatch_action(MoveToLastRow);
+        cx.dispatch_action(MoveDown);
+        table.update(cx, |table, _| {
+            assert_eq!(table.cursor(), TableCell { row: 49, column: 0 })
+        });
+
+        cx.dispatch_action(PageUp);
+        cx.dispatch_action(MoveUp);
+        cx.dispatch_action(MoveLeft);
+        table.update(cx, |table, _| {
+            assert_eq!(table.cursor(), TableCell { row: 28, column: 0 })
+        });
+
+        cx.dispatch_action(MoveToFirstRow);
+        cx.dispatch_action(MoveUp);
+        table.update(cx, |table, _| {
+            assert_eq!(table.cursor(), TableCell::default())
+        });
+    }
+
+    #[gpui::test]
+    async fn test_copy_selection(cx: &mut TestAppContext) {
+        let delegate = TestDelegate::new(&[&["b", "2"], &["a", "10"], &["c\td", "1"]]);
+        let (table, cx) = build_table(delegate, cx);
+
+        cx.dispatch_action(SelectDown);
+        cx.dispatch_action(data_table_actions::Copy);
+        assert_eq!(
+            cx.read_from_clipboard().map(|item| item.text().clone()),
+            Some("b\na".into())
+        );
+
+        // Copying follows the displayed order of the rows.
+        table.update(cx, |table, cx| {
+            table.set_sort(Some((1, SortDirection::Ascending)), cx)
+        });
+        cx.run_until_parked();
+        cx.dispatch_action(SelectAll);
+        cx.dispatch_action(data_table_actions::Copy);
+        assert_eq!(
+            cx.read_from_clipboard().map(|item| item.text().clone()),
+            Some("c d\t1\nb\t2\na\t10".into())
+        );
+    }
+
+    #[gpui::test]
+    async fn test_only_visible_rows_are_rendered(cx: &mut TestAppContext) {
+        let rows = (0..100_000)
+            .map(|_| ["a", "1"].as_slice())
+            .collect::<Vec<_>>();
+        let delegate = TestDelegate::new(&rows);
+        let rendered_cells = delegate.rendered_cells.clone();
+        let (table, cx) = build_table(delegate, cx);
+
+        assert!(rendered_cells.get() > 0);
+        assert!(
+            rendered_cells.get() < 1000,
+            "rendered {} cells",
+            rendered_cells.get()
+        );
+
+        rendered_cells.set(0);
+        cx.dispatch_action(MoveToLastRow);
+        table.update(cx, |table, _| {
+            assert_eq!(table.cursor().row, 99_999);
+        });
+        assert!(rendered_cells.get() > 0);
+        assert!(
+            rendered_cells.get() < 1000,
+            "rendered {} cells",
+            rendered_cells.get()
+        );
+    }
+
+    #[test]
+    fn test_compare_cells() {
+        let mut cells = vec!["banana", "10", "Apple", "9.5", "apple", "-1"];
+        cells.sort_by(|a, b| compare_cells(a, b));
+        assert_eq!(cells, ["-1", "9.5", "10", "Apple", "apple", "banana"]);
+    }
+
+    #[test]
+    fn test_format_tsv() {
+        let rows = vec![
+            vec!["a".into(), "b\tc".into()],
+            vec!["line\nbreak".into(), "".into()],
+        ];
+        assert_eq!(format_tsv(rows), "a\tb c\nline break\t");
+    }
+}
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use gpui::{px, AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels};
+use gpui::{
+    px, AccessibilityProperties, AccessibilityRole, AccessibilityStates, AnyElement, AnyView,
+    ClickEvent, MouseButton, MouseDownEvent, Pixels,
+};
 use smallvec::SmallVec;
 
 use crate::{prelude::*, Disclosure};
@@ -157,6 +160,16 @@ impl RenderOnce for ListItem {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
         h_flex()
             .id(self.id)
+            .accessibility(
+                AccessibilityProperties::new(AccessibilityRole::ListItem).states(
+                    AccessibilityStates {
+                        disabled: self.disabled,
+                        selected: self.selected,
+                        expanded: self.toggle,
+                        ..Default::default()
+                    },
+                ),
+            )
             .w_full()
             .relative()
             // When an item is inset draw the indent spacing outside of the item
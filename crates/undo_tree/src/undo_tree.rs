@@ -0,0 +1,375 @@
+use collections::HashMap;
+use editor::{actions::ToggleUndoTree, Editor, EditorMode};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, ParentElement,
+    Render, Styled, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use text::{TransactionId, UndoTreeNode, UndoTreeNodeState};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::ModalView;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(UndoTreeView::register).detach();
+}
+
+pub fn toggle(editor: View<Editor>, _: &ToggleUndoTree, cx: &mut WindowContext) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+
+    if let Some(workspace) = editor.read(cx).workspace() {
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| UndoTreeView::new(buffer, editor, cx));
+        })
+    }
+}
+
+pub struct UndoTreeView {
+    picker: View<Picker<UndoTreeViewDelegate>>,
+}
+
+impl FocusableView for UndoTreeView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for UndoTreeView {}
+impl ModalView for UndoTreeView {}
+
+impl Render for UndoTreeView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl UndoTreeView {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        if editor.mode() == EditorMode::Full {
+            let handle = cx.view().downgrade();
+            editor
+                .register_action(move |action, cx| {
+                    if let Some(editor) = handle.upgrade() {
+                        toggle(editor, action, cx);
+                    }
+                })
+                .detach();
+        }
+    }
+
+    fn new(buffer: Model<Buffer>, editor: View<Editor>, cx: &mut ViewContext<Self>) -> Self {
+        let entries = undo_tree_entries(buffer.read(cx).undo_tree());
+        let delegate = UndoTreeViewDelegate {
+            undo_tree_view: cx.view().downgrade(),
+            active_editor: editor,
+            buffer,
+            selected_index: entries
+                .iter()
+                .position(|entry| entry.is_current)
+                .unwrap_or(0),
+            matches: (0..entries.len()).collect(),
+            entries,
+        };
+        let picker =
+            cx.new_view(|cx| Picker::uniform_list(delegate, cx).max_height(Some(vh(0.75, cx))));
+        UndoTreeView { picker }
+    }
+}
+
+#[derive(Debug)]
+struct UndoTreeEntry {
+    /// The transaction after which the buffer is restored, or `None` for the buffer's
+    /// original text.
+    node: Option<UndoTreeNode>,
+    label: SharedString,
+    depth: usize,
+    is_current: bool,
+}
+
+/// Lays out the nodes of an undo tree, so that each branch is listed after the branch
+/// it diverged from, one level deeper.
+fn undo_tree_entries(nodes: Vec<UndoTreeNode>) -> Vec<UndoTreeEntry> {
+    let current_id = nodes
+        .iter()
+        .filter(|node| node.state == UndoTreeNodeState::Applied)
+        .map(|node| node.transaction_id)
+        .max();
+    let labels = nodes
+        .iter()
+        .enumerate()
+        .map(|(ix, node)| (node.transaction_id, format!("Change {}", ix + 1)))
+        .collect::<HashMap<_, _>>();
+
+    let mut children = HashMap::<Option<TransactionId>, Vec<UndoTreeNode>>::default();
+    for node in nodes {
+        let parent_id = node.parent_id.filter(|id| labels.contains_key(id));
+        children.entry(parent_id).or_default().push(node);
+    }
+
+    let mut entries = vec![UndoTreeEntry {
+        node: None,
+        label: "Original text".into(),
+        depth: 0,
+        is_current: current_id.is_none(),
+    }];
+    let mut stack = vec![(None, 0)];
+    while let Some((parent_id, depth)) = stack.pop() {
+        let Some(mut siblings) = children.remove(&parent_id) else {
+            continue;
+        };
+        // The nodes are sorted by id, so the first sibling is the oldest branch.
+        let node = siblings.remove(0);
+        if !siblings.is_empty() {
+            children.insert(parent_id, siblings);
+            stack.push((parent_id, depth + 1));
+        }
+
+        let label = match node.state {
+            UndoTreeNodeState::Applied => labels[&node.transaction_id].clone(),
+            UndoTreeNodeState::Undone => format!("{} (undone)", labels[&node.transaction_id]),
+            UndoTreeNodeState::Abandoned => {
+                format!("{} (abandoned)", labels[&node.transaction_id])
+            }
+        };
+        stack.push((Some(node.transaction_id), depth));
+        entries.push(UndoTreeEntry {
+            is_current: current_id == Some(node.transaction_id),
+            node: Some(node),
+            label: label.into(),
+            depth,
+        });
+    }
+    entries
+}
+
+struct UndoTreeViewDelegate {
+    undo_tree_view: WeakView<UndoTreeView>,
+    active_editor: View<Editor>,
+    buffer: Model<Buffer>,
+    entries: Vec<UndoTreeEntry>,
+    matches: Vec<usize>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for UndoTreeViewDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Filter undo history...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.label.to_lowercase().contains(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .matches
+            .iter()
+            .position(|ix| self.entries[*ix].is_current)
+            .unwrap_or(0);
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .map(|ix| &self.entries[*ix])
+        else {
+            return;
+        };
+
+        let version = entry
+            .node
+            .as_ref()
+            .map(|node| node.version.clone())
+            .unwrap_or_default();
+        let buffer = self.buffer.clone();
+        let diff = buffer.update(cx, |buffer, cx| {
+            let text = buffer.rope_for_version(&version).to_string();
+            buffer.diff(text, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let diff = diff.await;
+            buffer.update(&mut cx, |buffer, cx| buffer.apply_diff(diff, cx))
+        })
+        .detach_and_log_err(cx);
+
+        self.active_editor.update(cx, |editor, cx| editor.focus(cx));
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.undo_tree_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.entries[*self.matches.get(ix)?];
+        let color = match entry.node.as_ref().map(|node| node.state) {
+            Some(UndoTreeNodeState::Undone) | Some(UndoTreeNodeState::Abandoned) => Color::Muted,
+            Some(UndoTreeNodeState::Applied) | None => Color::Default,
+        };
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .pl(rems(entry.depth as f32))
+                        .gap_2()
+                        .child(Label::new(entry.label.clone()).color(color))
+                        .when(entry.is_current, |this| {
+                            this.child(Label::new("current").color(Color::Accent))
+                        }),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::actions::Undo;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::{AppState, Workspace};
+
+    #[gpui::test]
+    async fn test_undo_tree_view(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "a.txt": "one" })).await;
+
+        let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path((worktree_id, "a.txt"), None, true, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+
+        // Make two changes, undo the second one and make another change instead,
+        // which leaves the undone change on its own branch.
+        editor.update(cx, |editor, cx| {
+            editor.set_text("one two", cx);
+            editor.set_text("one two three", cx);
+            editor.undo(&Undo, cx);
+            editor.set_text("one two four", cx);
+        });
+
+        let undo_tree_view = open_undo_tree_view(&workspace, cx);
+        assert_eq!(
+            entries(&undo_tree_view, cx),
+            [
+                ("Original text".to_string(), 0, false),
+                ("Change 1".to_string(), 0, false),
+                ("Change 2 (abandoned)".to_string(), 0, false),
+                ("Change 3".to_string(), 1, true),
+            ]
+        );
+
+        cx.dispatch_action(menu::SelectPrev);
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "one two three"
+        );
+
+        open_undo_tree_view(&workspace, cx);
+        cx.dispatch_action(menu::SelectFirst);
+        cx.dispatch_action(menu::Confirm);
+        cx.run_until_parked();
+        assert_eq!(editor.update(cx, |editor, cx| editor.text(cx)), "one");
+
+        // Restoring a change is itself a change that can be undone.
+        editor.update(cx, |editor, cx| editor.undo(&Undo, cx));
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "one two three"
+        );
+    }
+
+    fn open_undo_tree_view(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<Picker<UndoTreeViewDelegate>> {
+        cx.dispatch_action(ToggleUndoTree);
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<UndoTreeView>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        })
+    }
+
+    fn entries(
+        undo_tree_view: &View<Picker<UndoTreeViewDelegate>>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<(String, usize, bool)> {
+        undo_tree_view.update(cx, |undo_tree_view, _| {
+            let delegate = &undo_tree_view.delegate;
+            delegate
+                .matches
+                .iter()
+                .map(|ix| {
+                    let entry = &delegate.entries[*ix];
+                    (entry.label.to_string(), entry.depth, entry.is_current)
+                })
+                .collect()
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use collections::HashMap;
 use editor::{
+    actions::{AlignSelections, InsertNumberSequence},
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement,
     scroll::Autoscroll,
@@ -32,6 +33,8 @@ actions!(
         VisualDelete,
         VisualDeleteLine,
         VisualYank,
+        VisualAlign,
+        VisualNumberSequence,
         OtherEnd,
         SelectNext,
         SelectPrevious,
@@ -72,6 +75,23 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
         });
     });
 
+    workspace.register_action(|_, _: &VisualAlign, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.record_current_action(cx);
+            vim.update_active_editor(cx, |_, editor, cx| {
+                editor.align_selections(&AlignSelections, cx);
+            });
+            vim.switch_mode(Mode::Normal, false, cx);
+        });
+    });
+    workspace.register_action(|_, _: &VisualNumberSequence, cx| {
+        Vim::update(cx, |vim, cx| {
+            vim.record_current_action(cx);
+            let start = vim.take_count(cx).unwrap_or(1) as i64;
+            number_sequence(vim, start, cx);
+        });
+    });
+
     workspace.register_action(select_next);
     workspace.register_action(select_previous);
     workspace.register_action(|workspace, _: &SelectNextMatch, cx| {
@@ -524,6 +544,26 @@ pub(crate) fn visual_replace(text: Arc<str>, cx: &mut WindowContext) {
     });
 }
 
+/// Replaces each selection (or each line of a visual block) with a number, counting up from
+/// `start`, and leaves the cursor at the start of the first number.
+fn number_sequence(vim: &mut Vim, start: i64, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |_, editor, cx| {
+        editor.insert_number_sequence(
+            &InsertNumberSequence {
+                start,
+                step: 1,
+                ..Default::default()
+            },
+            cx,
+        );
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            let start = s.first_anchor().start;
+            s.select_anchor_ranges([start..start]);
+        });
+    });
+    vim.switch_mode(Mode::Normal, true, cx);
+}
+
 pub fn select_next(_: &mut Workspace, _: &SelectNext, cx: &mut ViewContext<Workspace>) {
     Vim::update(cx, |vim, cx| {
         let count =
@@ -1225,6 +1265,27 @@ mod test {
         cx.assert_state("«aaˇ» aa\n«aaˇ»", Mode::Visual);
     }
 
+    #[gpui::test]
+    async fn test_visual_align(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("a «=ˇ» 1\nbbb «=ˇ» 2", Mode::Visual);
+        cx.simulate_keystrokes("g |");
+        cx.assert_state("a   ˇ= 1\nbbb ˇ= 2", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_visual_number_sequence(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa0\nb0\nc0", Mode::Normal);
+        cx.simulate_keystrokes("l ctrl-v j j g +");
+        cx.assert_state("aˇ1\nb2\nc3", Mode::Normal);
+
+        cx.simulate_keystrokes("ctrl-v j j 5 g +");
+        cx.assert_state("aˇ5\nb6\nc7", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_dgn_repeat(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -244,6 +244,16 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
         None
     }
 
+    /// Writes the state that [`Item::deserialize`] reads under the given workspace id, so that
+    /// the item can be restored from a copy of the workspace, such as a named session.
+    fn serialize_to_workspace(
+        &mut self,
+        _workspace_id: WorkspaceId,
+        _cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+
     fn deserialize(
         _project: Model<Project>,
         _workspace: WeakView<Workspace>,
@@ -328,6 +338,11 @@ pub trait ItemHandle: 'static + Send {
     fn breadcrumb_location(&self, cx: &AppContext) -> ToolbarItemLocation;
     fn breadcrumbs(&self, theme: &Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>>;
     fn serialized_item_kind(&self) -> Option<&'static str>;
+    fn serialize_to_workspace(
+        &self,
+        workspace_id: WorkspaceId,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>>;
     fn show_toolbar(&self, cx: &AppContext) -> bool;
     fn pixel_position_of_cursor(&self, cx: &AppContext) -> Option<Point<Pixels>>;
     fn downgrade_item(&self) -> Box<dyn WeakItemHandle>;
@@ -711,6 +726,14 @@ impl<T: Item> ItemHandle for View<T> {
         T::serialized_item_kind()
     }
 
+    fn serialize_to_workspace(
+        &self,
+        workspace_id: WorkspaceId,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        self.update(cx, |item, cx| item.serialize_to_workspace(workspace_id, cx))
+    }
+
     fn show_toolbar(&self, cx: &AppContext) -> bool {
         self.read(cx).show_toolbar()
     }
@@ -202,6 +202,13 @@ define_connection! {
     //     active: bool, // Indicates if this item is the active one in the pane
     //     preview: bool // Indicates if this item is a preview item
     // )
+    //
+    // sessions(
+    //     name: String, // The name the session was saved under
+    //     local_paths: Bincode<Vec<PathBuf>>, // The worktree roots the session belongs to
+    //     workspace_id: usize, // References the workspaces row holding the session's layout
+    //     timestamp: String, // UTC YYYY-MM-DD HH:MM:SS
+    // )
     pub static ref DB: WorkspaceDb<()> =
     &[sql!(
         CREATE TABLE workspaces(
@@ -344,6 +351,17 @@ define_connection! {
     sql!(
         ALTER TABLE workspaces ADD COLUMN local_paths_order BLOB;
     ),
+    sql!(
+        CREATE TABLE sessions(
+            name TEXT NOT NULL,
+            local_paths BLOB NOT NULL,
+            workspace_id INTEGER NOT NULL,
+            timestamp TEXT DEFAULT CURRENT_TIMESTAMP NOT NULL,
+            PRIMARY KEY(local_paths, name),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    ),
     ];
 }
 
@@ -660,6 +678,122 @@ impl WorkspaceDb {
         .await;
     }
 
+    /// Saves a workspace layout as a named session of the given worktree roots, replacing any
+    /// session previously saved under the same name.
+    ///
+    /// The workspace's id must be a fresh one from [`Self::next_id`]: session rows don't have any
+    /// paths of their own, so they never show up as recent workspaces.
+    pub(crate) async fn save_session(
+        &self,
+        name: String,
+        local_paths: LocalPaths,
+        workspace: SerializedWorkspace,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_session", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM workspaces WHERE workspace_id IN (
+                        SELECT workspace_id FROM sessions WHERE local_paths = ? AND name = ?
+                    )
+                ))?((&local_paths, name.as_str()))
+                .context("Clearing previous session")?;
+
+                conn.exec_bound(sql!(
+                    UPDATE workspaces
+                    SET
+                        left_dock_visible = ?2,
+                        left_dock_active_panel = ?3,
+                        left_dock_zoom = ?4,
+                        right_dock_visible = ?5,
+                        right_dock_active_panel = ?6,
+                        right_dock_zoom = ?7,
+                        bottom_dock_visible = ?8,
+                        bottom_dock_active_panel = ?9,
+                        bottom_dock_zoom = ?10,
+                        centered_layout = ?11,
+                        timestamp = CURRENT_TIMESTAMP
+                    WHERE workspace_id = ?1
+                ))?((workspace.id, workspace.docks, workspace.centered_layout))
+                .context("Updating session workspace")?;
+
+                Self::save_pane_group(conn, workspace.id, &workspace.center_group, None)
+                    .context("save pane group in save session")?;
+
+                conn.exec_bound(sql!(
+                    INSERT INTO sessions(name, local_paths, workspace_id)
+                    VALUES (?, ?, ?)
+                ))?((name.as_str(), &local_paths, workspace.id))
+                .context("Inserting session")?;
+
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    /// Returns the session saved under the given name for the worktree roots, if any.
+    pub(crate) fn session(
+        &self,
+        local_paths: &LocalPaths,
+        name: &str,
+    ) -> Option<SerializedWorkspace> {
+        let (workspace_id, centered_layout, docks): (WorkspaceId, Option<bool>, DockStructure) =
+            self.select_row_bound(sql! {
+                SELECT
+                    workspaces.workspace_id,
+                    centered_layout,
+                    left_dock_visible,
+                    left_dock_active_panel,
+                    left_dock_zoom,
+                    right_dock_visible,
+                    right_dock_active_panel,
+                    right_dock_zoom,
+                    bottom_dock_visible,
+                    bottom_dock_active_panel,
+                    bottom_dock_zoom
+                FROM sessions
+                JOIN workspaces ON sessions.workspace_id = workspaces.workspace_id
+                WHERE sessions.local_paths = ? AND sessions.name = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)((local_paths, name)))
+            .context("No session found")
+            .warn_on_err()
+            .flatten()?;
+
+        Some(SerializedWorkspace {
+            id: workspace_id,
+            location: SerializedWorkspaceLocation::Local(
+                local_paths.clone(),
+                LocalPathsOrder::default_for_paths(local_paths),
+            ),
+            center_group: self
+                .get_center_pane_group(workspace_id)
+                .context("Getting center group")
+                .log_err()?,
+            window_bounds: None,
+            centered_layout: centered_layout.unwrap_or(false),
+            display: None,
+            docks,
+        })
+    }
+
+    query! {
+        pub fn session_names(local_paths: &LocalPaths) -> Result<Vec<String>> {
+            SELECT name
+            FROM sessions
+            WHERE local_paths = ?
+            ORDER BY timestamp DESC
+        }
+    }
+
+    query! {
+        pub async fn delete_session(local_paths: LocalPaths, name: String) -> Result<()> {
+            DELETE FROM workspaces WHERE workspace_id IN (
+                SELECT workspace_id FROM sessions WHERE local_paths = ? AND name = ?
+            )
+        }
+    }
+
     query! {
         pub async fn next_id() -> Result<WorkspaceId> {
             INSERT INTO workspaces DEFAULT VALUES RETURNING workspace_id
@@ -1279,7 +1413,9 @@ mod tests {
     }
 
     use crate::persistence::model::SerializedWorkspace;
-    use crate::persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup};
+    use crate::persistence::model::{
+        DockData, SerializedItem, SerializedPane, SerializedPaneGroup,
+    };
 
     fn default_workspace<P: AsRef<Path>>(
         workspace_id: &[P],
@@ -1418,4 +1554,97 @@ mod tests {
 
         assert_eq!(workspace.center_group, new_workspace.center_group);
     }
+
+    #[gpui::test]
+    async fn test_named_sessions() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_named_sessions").await);
+        let local_paths = LocalPaths::new(["/tmp", "/tmp2"]);
+
+        let workspace = default_workspace(&["/tmp", "/tmp2"], &Default::default());
+        db.save_workspace(workspace.clone()).await;
+
+        let session = SerializedWorkspace {
+            id: db.next_id().await.unwrap(),
+            location: SerializedWorkspaceLocation::Local(
+                local_paths.clone(),
+                LocalPathsOrder::default_for_paths(&local_paths),
+            ),
+            center_group: group(
+                Axis::Horizontal,
+                vec![
+                    SerializedPaneGroup::Pane(SerializedPane::new(
+                        vec![
+                            SerializedItem::new("Editor", 1, true, false),
+                            SerializedItem::new("Editor", 2, false, false),
+                        ],
+                        true,
+                    )),
+                    SerializedPaneGroup::Pane(SerializedPane::new(
+                        vec![SerializedItem::new("Terminal", 3, true, false)],
+                        false,
+                    )),
+                ],
+            ),
+            window_bounds: None,
+            display: None,
+            docks: DockStructure {
+                bottom: DockData {
+                    visible: true,
+                    active_panel: Some("TerminalPanel".into()),
+                    zoom: false,
+                },
+                ..Default::default()
+            },
+            centered_layout: true,
+        };
+        db.save_session("review".into(), local_paths.clone(), session.clone())
+            .await
+            .unwrap();
+        assert_eq!(db.session(&local_paths, "review"), Some(session.clone()));
+        assert_eq!(db.session(&LocalPaths::new(["/tmp"]), "review"), None);
+
+        // Sessions don't show up as recent workspaces, and saving the regular workspace for the
+        // same roots leaves them alone.
+        assert_eq!(db.recent_workspaces().unwrap().len(), 1);
+        db.save_workspace(workspace).await;
+        assert_eq!(db.session(&local_paths, "review"), Some(session.clone()));
+
+        // Saving under an existing name replaces the previous session.
+        let replacement = SerializedWorkspace {
+            id: db.next_id().await.unwrap(),
+            center_group: Default::default(),
+            ..session.clone()
+        };
+        db.save_session("review".into(), local_paths.clone(), replacement.clone())
+            .await
+            .unwrap();
+        assert_eq!(db.session(&local_paths, "review"), Some(replacement));
+        assert_eq!(
+            db.select_row_bound::<WorkspaceId, WorkspaceId>(sql!(
+                SELECT workspace_id FROM workspaces WHERE workspace_id = ?
+            ))
+            .unwrap()(session.id)
+            .unwrap(),
+            None
+        );
+
+        let debugging = SerializedWorkspace {
+            id: db.next_id().await.unwrap(),
+            ..session
+        };
+        db.save_session("debugging".into(), local_paths.clone(), debugging)
+            .await
+            .unwrap();
+        let mut names = db.session_names(&local_paths).unwrap();
+        names.sort();
+        assert_eq!(names, ["debugging", "review"]);
+
+        db.delete_session(local_paths.clone(), "review".into())
+            .await
+            .unwrap();
+        assert_eq!(db.session_names(&local_paths).unwrap(), ["debugging"]);
+        assert_eq!(db.session(&local_paths, "review"), None);
+    }
 }
@@ -40,7 +40,7 @@ use item::{
     ProjectItem,
 };
 use itertools::Itertools;
-use language::{LanguageRegistry, Rope};
+use language::{LanguageRegistry, Rope, ToPoint as _};
 use lazy_static::lazy_static;
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
@@ -335,25 +335,43 @@ type WorkspaceItemBuilder = Box<dyn FnOnce(&mut ViewContext<Pane>) -> Box<dyn It
 
 impl Global for ProjectItemOpeners {}
 
+#[derive(Clone, Default, Deref, DerefMut)]
+struct FallbackProjectItemOpeners(Vec<ProjectItemOpener>);
+
+impl Global for FallbackProjectItemOpeners {}
+
 /// Registers a [ProjectItem] for the app. When opening a file, all the registered
 /// items will get a chance to open the file, starting from the project item that
 /// was added last.
 pub fn register_project_item<I: ProjectItem>(cx: &mut AppContext) {
     let builders = cx.default_global::<ProjectItemOpeners>();
-    builders.push(|project, project_path, cx| {
-        let project_item = <I::Item as project::Item>::try_open(&project, project_path, cx)?;
-        let project = project.clone();
-        Some(cx.spawn(|cx| async move {
-            let project_item = project_item.await?;
-            let project_entry_id: Option<ProjectEntryId> =
-                project_item.read_with(&cx, |item, cx| project::Item::entry_id(item, cx))?;
-            let build_workspace_item = Box::new(|cx: &mut ViewContext<Pane>| {
-                Box::new(cx.new_view(|cx| I::for_project_item(project, project_item, cx)))
-                    as Box<dyn ItemHandle>
-            }) as Box<_>;
-            Ok((project_entry_id, build_workspace_item))
-        }))
-    });
+    builders.push(project_item_opener::<I>);
+}
+
+/// Registers a [ProjectItem] that opens the files that none of the items registered with
+/// [register_project_item] can open, or that they fail to open, like binary files.
+pub fn register_fallback_project_item<I: ProjectItem>(cx: &mut AppContext) {
+    let builders = cx.default_global::<FallbackProjectItemOpeners>();
+    builders.push(project_item_opener::<I>);
+}
+
+fn project_item_opener<I: ProjectItem>(
+    project: &Model<Project>,
+    project_path: &ProjectPath,
+    cx: &mut WindowContext,
+) -> Option<Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>>> {
+    let project_item = <I::Item as project::Item>::try_open(project, project_path, cx)?;
+    let project = project.clone();
+    Some(cx.spawn(|cx| async move {
+        let project_item = project_item.await?;
+        let project_entry_id: Option<ProjectEntryId> =
+            project_item.read_with(&cx, |item, cx| project::Item::entry_id(item, cx))?;
+        let build_workspace_item = Box::new(|cx: &mut ViewContext<Pane>| {
+            Box::new(cx.new_view(|cx| I::for_project_item(project, project_item, cx)))
+                as Box<dyn ItemHandle>
+        }) as Box<_>;
+        Ok((project_entry_id, build_workspace_item))
+    }))
 }
 
 type FollowableItemBuilder = fn(
@@ -688,6 +706,32 @@ impl Workspace {
                     )
                 }
 
+                project::Event::OfflineEditsConflicted { buffer, ranges } => {
+                    struct OfflineEditsConflicted;
+
+                    let id = buffer.entity_id().as_u64() as usize;
+                    let buffer = buffer.read(cx);
+                    let path = buffer.file().map_or_else(
+                        || "an untitled buffer".to_string(),
+                        |file| file.path().to_string_lossy().to_string(),
+                    );
+                    let lines = ranges
+                        .iter()
+                        .map(|range| range.start.to_point(buffer).row + 1)
+                        .sorted()
+                        .dedup()
+                        .join(", ");
+                    let message = format!(
+                        "Edits you made to {path} while offline overlap with your collaborators' \
+                         edits on line(s) {lines}."
+                    );
+                    this.show_notification(
+                        NotificationId::identified::<OfflineEditsConflicted>(id),
+                        cx,
+                        |cx| cx.new_view(|_| MessageNotification::new(message)),
+                    )
+                }
+
                 project::Event::LanguageServerPrompt(request) => {
                     struct LanguageServerPrompt;
 
@@ -2293,14 +2337,34 @@ impl Workspace {
     ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
         let project = self.project().clone();
         let project_item_builders = cx.default_global::<ProjectItemOpeners>().clone();
-        let Some(open_project_item) = project_item_builders
+        let fallback_builders = cx.default_global::<FallbackProjectItemOpeners>().clone();
+        let open_project_item = project_item_builders
             .iter()
             .rev()
-            .find_map(|open_project_item| open_project_item(&project, &path, cx))
-        else {
-            return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
-        };
-        open_project_item
+            .find_map(|open_project_item| open_project_item(&project, &path, cx));
+        cx.spawn(|mut cx| async move {
+            let error = match open_project_item {
+                Some(open_project_item) => match open_project_item.await {
+                    Ok(item) => return Ok(item),
+                    Err(error) => error,
+                },
+                None => anyhow!("cannot open file {:?}", path.path),
+            };
+
+            // If no item can open the file, e.g. because an editor can't open a binary
+            // file, give the fallback items a chance.
+            for open_project_item in fallback_builders.iter().rev() {
+                let Some(open_project_item) =
+                    cx.update(|cx| open_project_item(&project, &path, cx))?
+                else {
+                    continue;
+                };
+                if let Ok(item) = open_project_item.await {
+                    return Ok(item);
+                }
+            }
+            Err(error)
+        })
     }
 
     pub fn open_project_item<T>(
@@ -3601,6 +3665,17 @@ impl Workspace {
             return Task::ready(());
         };
 
+        if let Some(serialized_workspace) = self.serialized_workspace(database_id, cx) {
+            return cx.spawn(|_| persistence::DB.save_workspace(serialized_workspace));
+        }
+        Task::ready(())
+    }
+
+    fn serialized_workspace(
+        &self,
+        database_id: WorkspaceId,
+        cx: &mut WindowContext,
+    ) -> Option<SerializedWorkspace> {
         fn serialize_pane_handle(pane_handle: &View<Pane>, cx: &WindowContext) -> SerializedPane {
             let (items, active) = {
                 let pane = pane_handle.read(cx);
@@ -3731,22 +3806,120 @@ impl Workspace {
         };
 
         // don't save workspace state for the empty workspace.
-        if let Some(location) = location {
-            let center_group = build_serialized_pane_group(&self.center.root, cx);
-            let docks = build_serialized_docks(self, cx);
-            let window_bounds = Some(SerializedWindowBounds(cx.window_bounds()));
-            let serialized_workspace = SerializedWorkspace {
-                id: database_id,
-                location,
-                center_group,
-                window_bounds,
-                display: Default::default(),
-                docks,
-                centered_layout: self.centered_layout,
+        let location = location?;
+        let center_group = build_serialized_pane_group(&self.center.root, cx);
+        let docks = build_serialized_docks(self, cx);
+        let window_bounds = Some(SerializedWindowBounds(cx.window_bounds()));
+        Some(SerializedWorkspace {
+            id: database_id,
+            location,
+            center_group,
+            window_bounds,
+            display: Default::default(),
+            docks,
+            centered_layout: self.centered_layout,
+        })
+    }
+
+    /// Returns the names of the sessions saved for this workspace's worktree roots, most
+    /// recently saved first.
+    pub fn session_names(&self, cx: &AppContext) -> Vec<String> {
+        self.session_paths(cx)
+            .and_then(|local_paths| persistence::DB.session_names(&local_paths).log_err())
+            .unwrap_or_default()
+    }
+
+    /// Saves the current pane layout, open items and dock states as a named session of this
+    /// workspace's worktree roots, replacing any session with the same name.
+    pub fn save_session(&mut self, name: String, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(local_paths) = self.session_paths(cx) else {
+            return Task::ready(Err(anyhow!(
+                "Sessions can only be saved for local projects"
+            )));
+        };
+
+        cx.spawn(|this, mut cx| async move {
+            let session_id = persistence::DB.next_id().await?;
+            let (serialized_session, item_tasks) = this.update(&mut cx, |this, cx| {
+                let items = this
+                    .panes
+                    .iter()
+                    .flat_map(|pane| {
+                        pane.read(cx)
+                            .items()
+                            .map(|item| item.boxed_clone())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let item_tasks = items
+                    .iter()
+                    .map(|item| item.serialize_to_workspace(session_id, cx))
+                    .collect::<Vec<_>>();
+                (this.serialized_workspace(session_id, cx), item_tasks)
+            })?;
+            let Some(serialized_session) = serialized_session else {
+                persistence::DB.delete_workspace_by_id(session_id).await?;
+                return Err(anyhow!("Sessions can only be saved for local projects"));
             };
-            return cx.spawn(|_| persistence::DB.save_workspace(serialized_workspace));
+
+            for task in item_tasks {
+                task.await.log_err();
+            }
+            persistence::DB
+                .save_session(name, local_paths, serialized_session)
+                .await
+        })
+    }
+
+    /// Replaces the current pane layout, open items and dock states with the ones saved in the
+    /// named session, after closing the open items.
+    pub fn open_session(&mut self, name: String, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(serialized_session) = self
+            .session_paths(cx)
+            .and_then(|local_paths| persistence::DB.session(&local_paths, &name))
+        else {
+            return Task::ready(Err(anyhow!("No session named {name:?} was found")));
+        };
+
+        cx.spawn(|this, mut cx| async move {
+            if let Some(close) = this.update(&mut cx, |this, cx| {
+                this.close_all_internal(false, SaveIntent::Close, cx)
+            })? {
+                close.await?;
+            }
+
+            let load = this.update(&mut cx, |this, cx| {
+                // Leave the workspace alone if any of the items couldn't be closed.
+                if this.panes.iter().any(|pane| pane.read(cx).items_len() > 0) {
+                    return None;
+                }
+
+                this.centered_layout = serialized_session.centered_layout;
+                Some(Self::load_workspace(serialized_session, Vec::new(), cx))
+            })?;
+            if let Some(load) = load {
+                load.await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Deletes the named session of this workspace's worktree roots.
+    pub fn delete_session(&self, name: String, cx: &AppContext) -> Task<Result<()>> {
+        let Some(local_paths) = self.session_paths(cx) else {
+            return Task::ready(Ok(()));
+        };
+        cx.background_executor()
+            .spawn(async move { persistence::DB.delete_session(local_paths, name).await })
+    }
+
+    fn session_paths(&self, cx: &AppContext) -> Option<LocalPaths> {
+        let local_paths = self.local_paths(cx)?;
+        if local_paths.is_empty() {
+            None
+        } else {
+            Some(LocalPaths::new(local_paths))
         }
-        Task::ready(())
     }
 
     pub(crate) fn load_workspace(
@@ -6363,6 +6536,69 @@ mod tests {
             }
         }
 
+        // Model
+        struct TestBrokenPngItem {}
+
+        impl project::Item for TestBrokenPngItem {
+            fn try_open(
+                _project: &Model<Project>,
+                path: &ProjectPath,
+                _cx: &mut AppContext,
+            ) -> Option<Task<gpui::Result<Model<Self>>>> {
+                if path.path.extension().unwrap() == "png" {
+                    Some(Task::ready(Err(anyhow!("failed to open png"))))
+                } else {
+                    None
+                }
+            }
+
+            fn entry_id(&self, _: &AppContext) -> Option<ProjectEntryId> {
+                None
+            }
+
+            fn project_path(&self, _: &AppContext) -> Option<ProjectPath> {
+                None
+            }
+        }
+
+        // View
+        struct TestBrokenPngItemView {
+            focus_handle: FocusHandle,
+        }
+
+        impl Item for TestBrokenPngItemView {
+            type Event = ();
+        }
+        impl EventEmitter<()> for TestBrokenPngItemView {}
+        impl FocusableView for TestBrokenPngItemView {
+            fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+
+        impl Render for TestBrokenPngItemView {
+            fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        impl ProjectItem for TestBrokenPngItemView {
+            type Item = TestBrokenPngItem;
+
+            fn for_project_item(
+                _project: Model<Project>,
+                _item: Model<Self::Item>,
+                cx: &mut ViewContext<Self>,
+            ) -> Self
+            where
+                Self: Sized,
+            {
+                Self {
+                    focus_handle: cx.focus_handle(),
+                }
+            }
+        }
+
         #[gpui::test]
         async fn test_register_project_item(cx: &mut TestAppContext) {
             init_test(cx);
@@ -6469,6 +6705,62 @@ mod tests {
                 .await;
             assert!(handle.is_err());
         }
+
+        #[gpui::test]
+        async fn test_register_project_item_falls_back_on_error(cx: &mut TestAppContext) {
+            init_test(cx);
+
+            cx.update(|cx| {
+                register_project_item::<TestPngItemView>(cx);
+                register_project_item::<TestBrokenPngItemView>(cx);
+            });
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root1",
+                json!({
+                    "one.png": "BINARYDATAHERE",
+                    "three.txt": "editing text, sure why not?"
+                }),
+            )
+            .await;
+
+            let project = Project::test(fs, ["root1".as_ref()], cx).await;
+            let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            });
+
+            // Items that were registered before the one that fails to open the file
+            // don't get a chance to open it.
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "one.png");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await;
+            assert!(handle.is_err());
+
+            // Fallback items do.
+            cx.update(|cx| register_fallback_project_item::<TestPngItemView>(cx));
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "one.png");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await
+                .unwrap();
+            assert_eq!(handle.serialized_item_kind().unwrap(), TEST_PNG_KIND);
+
+            let handle = workspace
+                .update(cx, |workspace, cx| {
+                    let project_path = (worktree_id, "three.txt");
+                    workspace.open_path(project_path, None, true, cx)
+                })
+                .await;
+            assert!(handle.is_err());
+        }
     }
 
     pub fn init_test(cx: &mut TestAppContext) {
@@ -47,6 +47,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io::{self, Read as _},
     mem,
     ops::{AddAssign, Deref, DerefMut, Sub},
     path::{Path, PathBuf},
@@ -58,7 +59,7 @@ use std::{
     time::{Duration, Instant, SystemTime},
 };
 use sum_tree::{Bias, Edit, SeekTarget, SumTree, TreeMap, TreeSet};
-use text::{LineEnding, Rope};
+use text::{Decoder, Encoding, LineEnding, Rope};
 use util::{paths::home_dir, ResultExt};
 pub use worktree_settings::WorktreeSettings;
 
@@ -104,7 +105,94 @@ pub enum CreatedEntry {
 pub struct LoadedFile {
     pub file: Arc<File>,
     pub text: String,
+    pub encoding: Encoding,
+    /// Whether invalid sequences in the file were replaced while decoding it, so that
+    /// saving the text would not preserve the file's original bytes.
+    pub lossy: bool,
     pub diff_base: Option<String>,
+    /// For files opened in large-file mode, the rest of the file after the
+    /// initial chunk in `text`.
+    pub large_file: Option<LargeFileReader>,
+}
+
+/// The number of bytes that are read at a time from files opened in large-file mode.
+pub const LARGE_FILE_CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+/// Reads a file that is opened in large-file mode in chunks, so that it can be shown
+/// before it has been read completely.
+pub struct LargeFileReader {
+    reader: Box<dyn io::Read + Send>,
+    chunk_len: usize,
+    decoder: Decoder,
+    len: u64,
+    read_len: u64,
+    pending_carriage_return: bool,
+    done: bool,
+}
+
+impl LargeFileReader {
+    /// Reads the first chunk of the file, detecting its encoding.
+    fn open(
+        mut reader: Box<dyn io::Read + Send>,
+        len: u64,
+        chunk_len: usize,
+    ) -> Result<(String, Encoding, Self)> {
+        let chunk = read_large_file_chunk(&mut reader, chunk_len)?;
+        let encoding = Encoding::detect(&chunk);
+        let mut this = Self {
+            reader,
+            chunk_len,
+            decoder: encoding.new_decoder(),
+            len,
+            read_len: 0,
+            pending_carriage_return: false,
+            done: false,
+        };
+        let text = this.decode(chunk)?;
+        Ok((text, encoding, this))
+    }
+
+    /// The size of the file in bytes when it was opened.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// The number of bytes that have been read so far.
+    pub fn read_len(&self) -> u64 {
+        self.read_len
+    }
+
+    /// Reads and decodes the next chunk of the file, returning `None` once the whole
+    /// file has been read. This blocks on IO, so it should be called in the background.
+    pub fn next_chunk(&mut self) -> Result<Option<String>> {
+        if self.done {
+            return Ok(None);
+        }
+        let chunk = read_large_file_chunk(&mut self.reader, self.chunk_len)?;
+        self.decode(chunk).map(Some)
+    }
+
+    fn decode(&mut self, chunk: Vec<u8>) -> Result<String> {
+        self.read_len += chunk.len() as u64;
+        self.done = chunk.len() < self.chunk_len;
+        let mut text = self.decoder.decode_chunk(&chunk, self.done)?;
+        if self.pending_carriage_return {
+            text.insert(0, '\r');
+        }
+        // Hold back a trailing carriage return until the next chunk, so that a
+        // Windows line ending split across chunks isn't read as two line breaks.
+        self.pending_carriage_return = !self.done && text.ends_with('\r');
+        if self.pending_carriage_return {
+            text.pop();
+        }
+        Ok(text)
+    }
+}
+
+fn read_large_file_chunk(reader: &mut dyn io::Read, chunk_len: usize) -> io::Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(chunk_len);
+    reader.take(chunk_len as u64).read_to_end(&mut chunk)?;
+    Ok(chunk)
 }
 
 pub struct LocalWorktree {
@@ -120,6 +208,7 @@ pub struct LocalWorktree {
     next_entry_id: Arc<AtomicUsize>,
     settings: WorktreeSettings,
     share_private_files: bool,
+    large_file_chunk_len: usize,
 }
 
 struct ScanRequest {
@@ -451,6 +540,7 @@ impl Worktree {
                 fs_case_sensitive,
                 visible,
                 settings,
+                large_file_chunk_len: LARGE_FILE_CHUNK_LEN,
             };
             worktree.start_background_scanner(scan_requests_rx, path_prefixes_to_scan_rx, cx);
             Worktree::Local(worktree)
@@ -684,10 +774,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1236,6 +1327,11 @@ impl LocalWorktree {
         self.settings.clone()
     }
 
+    /// Sets how many bytes are read at a time from files opened in large-file mode.
+    pub fn set_large_file_chunk_len(&mut self, chunk_len: usize) {
+        self.large_file_chunk_len = chunk_len.max(1);
+    }
+
     pub fn local_git_repo(&self, path: &Path) -> Option<Arc<dyn GitRepository>> {
         self.repo_for_path(path)
             .map(|(_, entry)| entry.repo_ptr.clone())
@@ -1251,13 +1347,37 @@ impl LocalWorktree {
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let large_file_threshold = self.settings.large_file_threshold;
+        let large_file_chunk_len = self.large_file_chunk_len;
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let len = fs
+                .metadata(&abs_path)
+                .await?
+                .map_or(0, |metadata| metadata.len);
+            let (text, encoding, lossy, large_file) = if len >= large_file_threshold {
+                let reader = fs.open_sync(&abs_path).await?;
+                let (text, encoding, large_file) = cx
+                    .background_executor()
+                    .spawn(async move { LargeFileReader::open(reader, len, large_file_chunk_len) })
+                    .await
+                    .with_context(|| format!("loading file {abs_path:?}"))?;
+                (text, encoding, false, Some(large_file))
+            } else {
+                let bytes = fs.load_bytes(&abs_path).await?;
+                let decoded = Encoding::detect_and_decode(bytes)
+                    .with_context(|| format!("loading file {abs_path:?}"))?;
+                (decoded.text, decoded.encoding, decoded.lossy, None)
+            };
+
             let mut index_task = None;
             let snapshot = this.update(&mut cx, |this, _| this.as_local().unwrap().snapshot())?;
-            if let Some(repo) = snapshot.repository_for_path(&path) {
+            // Large files aren't diffed against the git index.
+            let repo = snapshot
+                .repository_for_path(&path)
+                .filter(|_| large_file.is_none());
+            if let Some(repo) = repo {
                 if let Some(repo_path) = repo.relativize(&snapshot, &path).log_err() {
                     if let Some(git_repo) = snapshot.git_repositories.get(&*repo.work_directory) {
                         let git_repo = git_repo.repo_ptr.clone();
@@ -1320,7 +1440,10 @@ impl LocalWorktree {
             Ok(LoadedFile {
                 file,
                 text,
+                encoding,
+                lossy,
                 diff_base,
+                large_file,
             })
         })
     }
@@ -1407,6 +1530,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1419,7 +1543,10 @@ impl LocalWorktree {
         let write = cx.background_executor().spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move {
+                fs.save_with_encoding(&abs_path, &text, line_ending, encoding)
+                    .await
+            }
         });
 
         cx.spawn(move |this, mut cx| async move {
@@ -2384,7 +2511,7 @@ impl LocalSnapshot {
         }
     }
 
-    fn build_initial_update(&self, project_id: u64, worktree_id: u64) -> proto::UpdateWorktree {
+    pub fn build_initial_update(&self, project_id: u64, worktree_id: u64) -> proto::UpdateWorktree {
         let mut updated_entries = self
             .entries_by_path
             .iter()
@@ -3036,12 +3163,17 @@ impl language::LocalFile for File {
         }
     }
 
-    fn load(&self, cx: &AppContext) -> Task<Result<String>> {
+    fn load(&self, encoding: Encoding, cx: &AppContext) -> Task<Result<String>> {
         let worktree = self.worktree.read(cx).as_local().unwrap();
         let abs_path = worktree.absolutize(&self.path);
         let fs = worktree.fs.clone();
-        cx.background_executor()
-            .spawn(async move { fs.load(&abs_path?).await })
+        cx.background_executor().spawn(async move {
+            let abs_path = abs_path?;
+            let bytes = fs.load_bytes(&abs_path).await?;
+            encoding
+                .decode(bytes)
+                .with_context(|| format!("loading file {abs_path:?}"))
+        })
     }
 }
 
@@ -11,6 +11,8 @@ use util::paths::PathMatcher;
 pub struct WorktreeSettings {
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    /// Files of at least this many bytes are opened in large-file mode.
+    pub large_file_threshold: u64,
 }
 
 impl WorktreeSettings {
@@ -45,6 +47,12 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// The size in bytes from which files are opened in large-file mode, which loads
+    /// them incrementally and disables syntax highlighting and language servers.
+    ///
+    /// Default: 33554432
+    pub large_file_threshold: Option<u64>,
 }
 
 impl Settings for WorktreeSettings {
@@ -64,6 +72,7 @@ impl Settings for WorktreeSettings {
         Ok(Self {
             file_scan_exclusions: path_matchers(&file_scan_exclusions, "file_scan_exclusions")?,
             private_files: path_matchers(&private_files, "private_files")?,
+            large_file_threshold: result.large_file_threshold.unwrap_or(32 * 1024 * 1024),
         })
     }
 }
@@ -174,6 +174,7 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     theme::init(theme::LoadThemes::All(Box::new(Assets)), cx);
     app_state.languages.set_theme(cx.theme().clone());
     command_palette::init(cx);
+    hex_editor::init(cx);
     editor::init(cx);
     image_viewer::init(cx);
     diagnostics::init(cx);
@@ -186,6 +187,8 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     file_finder::init(cx);
     tab_switcher::init(cx);
     outline::init(cx);
+    undo_tree::init(cx);
+    local_history::init(app_state.fs.clone(), cx);
     project_symbols::init(cx);
     project_panel::init(Assets, cx);
     outline_panel::init(Assets, cx);
@@ -194,9 +197,11 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     search::init(cx);
     vim::init(cx);
     terminal_view::init(cx);
+    session_replay::init(cx);
 
     journal::init(app_state.clone(), cx);
     language_selector::init(cx);
+    encoding_selector::init(cx);
     theme_selector::init(cx);
     language_tools::init(cx);
     call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -143,6 +143,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             activity_indicator::ActivityIndicator::new(workspace, app_state.languages.clone(), cx);
         let active_buffer_language =
             cx.new_view(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_encoding =
+            cx.new_view(|_| encoding_selector::ActiveBufferEncoding::new(workspace));
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
         let cursor_position =
             cx.new_view(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
@@ -150,6 +152,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
             status_bar.add_right_item(inline_completion_button, cx);
+            status_bar.add_right_item(active_buffer_encoding, cx);
             status_bar.add_right_item(active_buffer_language, cx);
             status_bar.add_right_item(vim_mode_indicator, cx);
             status_bar.add_right_item(cursor_position, cx);
@@ -960,6 +963,7 @@ mod tests {
     use settings::{handle_settings_file_changes, watch_config_file, SettingsStore};
     use std::path::{Path, PathBuf};
     use theme::{ThemeRegistry, ThemeSettings};
+    use util::paths::PathLikeWithPosition;
     use workspace::{
         item::{Item, ItemHandle},
         open_new, open_paths, pane, NewFile, OpenVisible, SaveIntent, SplitDirection,
@@ -1540,6 +1544,99 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_open_session_with_paths(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/session",
+                json!({
+                    "a.txt": "",
+                    "b.txt": "",
+                    "c.txt": "",
+                }),
+            )
+            .await;
+
+        let (window, _) = cx
+            .update(|cx| {
+                open_paths(
+                    &[PathBuf::from("/session")],
+                    app_state.clone(),
+                    workspace::OpenOptions::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        window
+            .update(cx, |workspace, cx| {
+                workspace.open_paths(vec!["/session/a.txt".into()], OpenVisible::All, None, cx)
+            })
+            .unwrap()
+            .await;
+        window
+            .update(cx, |workspace, cx| {
+                workspace.save_session("review".into(), cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        // Open another file, which restoring the session closes.
+        window
+            .update(cx, |workspace, cx| {
+                workspace.open_paths(vec!["/session/b.txt".into()], OpenVisible::All, None, cx)
+            })
+            .unwrap()
+            .await;
+
+        let (restored_window, items, restored) = cx
+            .spawn({
+                let app_state = app_state.clone();
+                |mut cx| async move {
+                    open_session_with_positions(
+                        "review",
+                        &vec![
+                            PathLikeWithPosition::parse_str("/session", |_, path| {
+                                Ok::<_, std::convert::Infallible>(PathBuf::from(path))
+                            })
+                            .unwrap(),
+                            PathLikeWithPosition::parse_str("/session/c.txt", |_, path| {
+                                Ok::<_, std::convert::Infallible>(PathBuf::from(path))
+                            })
+                            .unwrap(),
+                        ],
+                        app_state,
+                        workspace::OpenOptions::default(),
+                        &mut cx,
+                    )
+                    .await
+                }
+            })
+            .await
+            .unwrap();
+        restored.unwrap();
+        assert!(restored_window == window);
+        assert_eq!(cx.update(|cx| cx.windows().len()), 1);
+
+        // The session replaces the open items, and the requested file is opened on top of it
+        // rather than being closed by the restore.
+        let requested_item = items[1].as_ref().unwrap().as_ref().unwrap().item_id();
+        window
+            .update(cx, |workspace, cx| {
+                let titles = workspace
+                    .items(cx)
+                    .map(|item| item.act_as::<Editor>(cx).unwrap().read(cx).title(cx))
+                    .collect::<Vec<_>>();
+                assert_eq!(titles, ["a.txt", "c.txt"]);
+                assert_eq!(workspace.active_item(cx).unwrap().item_id(), requested_item);
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_open_paths(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
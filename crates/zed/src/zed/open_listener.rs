@@ -20,7 +20,7 @@ use util::paths::PathLikeWithPosition;
 use util::ResultExt;
 use welcome::{show_welcome_view, FIRST_OPEN};
 use workspace::item::ItemHandle;
-use workspace::{AppState, Workspace};
+use workspace::{AppState, OpenVisible, Workspace};
 
 use crate::{init_headless, init_ui};
 
@@ -169,8 +169,68 @@ pub async fn open_paths_with_positions(
     WindowHandle<Workspace>,
     Vec<Option<Result<Box<dyn ItemHandle>>>>,
 )> {
-    let mut caret_positions = HashMap::default();
+    let (paths, caret_positions) = paths_with_caret_positions(path_likes);
+    let (workspace, items) = cx
+        .update(|cx| workspace::open_paths(&paths, app_state, open_options, cx))?
+        .await?;
+    move_carets(workspace, &items, &paths, caret_positions, cx);
+    Ok((workspace, items))
+}
+
+/// Restores the named session in the workspace for the given paths before opening them, so
+/// that restoring the session doesn't close the opened items.
+///
+/// Only the directories are opened before restoring the session, unless there are none, as
+/// the session is looked up by the workspace's worktree roots.
+pub async fn open_session_with_positions(
+    session: &str,
+    path_likes: &Vec<PathLikeWithPosition<PathBuf>>,
+    app_state: Arc<AppState>,
+    open_options: workspace::OpenOptions,
+    cx: &mut AsyncAppContext,
+) -> Result<(
+    WindowHandle<Workspace>,
+    Vec<Option<Result<Box<dyn ItemHandle>>>>,
+    Result<()>,
+)> {
+    let (paths, caret_positions) = paths_with_caret_positions(path_likes);
+    let mut roots = Vec::new();
+    for path in &paths {
+        if let Ok(Some(metadata)) = app_state.fs.metadata(path).await {
+            if metadata.is_dir {
+                roots.push(path.clone());
+            }
+        }
+    }
+    if roots.is_empty() {
+        roots = paths.clone();
+    }
+
+    let (workspace, _) = cx
+        .update(|cx| workspace::open_paths(&roots, app_state, open_options, cx))?
+        .await?;
+    let restored = async {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_session(session.to_string(), cx)
+            })?
+            .await
+    }
+    .await;
 
+    let items = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_paths(paths.clone(), OpenVisible::All, None, cx)
+        })?
+        .await;
+    move_carets(workspace, &items, &paths, caret_positions, cx);
+    Ok((workspace, items, restored))
+}
+
+fn paths_with_caret_positions(
+    path_likes: &[PathLikeWithPosition<PathBuf>],
+) -> (Vec<PathBuf>, HashMap<PathBuf, Point>) {
+    let mut caret_positions = HashMap::default();
     let paths = path_likes
         .iter()
         .map(|path_with_position| {
@@ -185,12 +245,17 @@ pub async fn open_paths_with_positions(
             path
         })
         .collect::<Vec<_>>();
+    (paths, caret_positions)
+}
 
-    let (workspace, items) = cx
-        .update(|cx| workspace::open_paths(&paths, app_state, open_options, cx))?
-        .await?;
-
-    for (item, path) in items.iter().zip(&paths) {
+fn move_carets(
+    workspace: WindowHandle<Workspace>,
+    items: &[Option<Result<Box<dyn ItemHandle>>>],
+    paths: &[PathBuf],
+    mut caret_positions: HashMap<PathBuf, Point>,
+    cx: &mut AsyncAppContext,
+) {
+    for (item, path) in items.iter().zip(paths) {
         let Some(Ok(item)) = item else {
             continue;
         };
@@ -211,8 +276,6 @@ pub async fn open_paths_with_positions(
                 .log_err();
         }
     }
-
-    Ok((workspace, items))
 }
 
 pub async fn handle_cli_connection(
@@ -227,6 +290,7 @@ pub async fn handle_cli_connection(
                 wait,
                 open_new_workspace,
                 dev_server_token,
+                session,
             } => {
                 if let Some(dev_server_token) = dev_server_token {
                     match cx
@@ -309,17 +373,36 @@ pub async fn handle_cli_connection(
                 let mut errored = false;
 
                 if !paths.is_empty() {
-                    match open_paths_with_positions(
-                        &paths,
-                        app_state,
-                        workspace::OpenOptions {
-                            open_new_workspace,
-                            ..Default::default()
-                        },
-                        &mut cx,
-                    )
-                    .await
-                    {
+                    let open_options = workspace::OpenOptions {
+                        open_new_workspace,
+                        ..Default::default()
+                    };
+                    let opened = if let Some(session) = session.as_deref() {
+                        open_session_with_positions(
+                            session,
+                            &paths,
+                            app_state,
+                            open_options,
+                            &mut cx,
+                        )
+                        .await
+                        .map(|(workspace, items, restored)| {
+                            if let Err(error) = restored {
+                                responses
+                                    .send(CliResponse::Stderr {
+                                        message: format!(
+                                            "error restoring session {session:?}: {error}"
+                                        ),
+                                    })
+                                    .log_err();
+                                errored = true;
+                            }
+                            (workspace, items)
+                        })
+                    } else {
+                        open_paths_with_positions(&paths, app_state, open_options, &mut cx).await
+                    };
+                    match opened {
                         Ok((workspace, items)) => {
                             let mut item_release_futures = Vec::new();
 